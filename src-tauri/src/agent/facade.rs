@@ -0,0 +1,285 @@
+//! Agent Facade
+//!
+//! Single entry point over the two agent stacks. Insights are selected by the
+//! intelligence pipeline (`HybridBandit` over `agent_linear_bandit`); the
+//! legacy arms in `agent_bandit_arms` are deprecated (see migration 020) and
+//! only survive as message templates and as a fallback when the v2 pipeline
+//! fails.
+//!
+//! v2 insights use the key `action:<action_name>` and carry the
+//! recommendation ID as `insight_id`, so feedback on them is routed to
+//! `IntelligenceAgent` rather than the legacy bandit.
+//!
+//! In shadow mode nothing is surfaced (see `preferences::is_shadow_mode`).
+//! While ML components are still loading at startup, or when reduced-data
+//! mode has turned them off, insights come from the rule-based legacy
+//! pipeline (see `ml::readiness`).
+
+use std::collections::HashMap;
+
+use sqlx::{Pool, Sqlite};
+
+use super::insights::{
+    self, append_pattern_insights, finish_insights, generate_insight_for_arm, log_insight_shown,
+    Insight, InsightFilter, DEFAULT_DAILY_CAP, MAX_ARM_INSIGHTS,
+};
+use super::intelligence::IntelligenceAgent;
+use super::preferences::is_shadow_mode;
+use crate::error::ApiError;
+use crate::ml::bandit_v2::HybridBandit;
+use crate::ml::models::RewardEngine;
+use crate::ml::readiness;
+use crate::ml::{ContextualBandit, FeatureStore};
+
+/// Insight key prefix for v2 actions
+const ACTION_KEY_PREFIX: &str = "action:";
+
+/// Legacy insight arm → v2 action (kept in sync with `agent_legacy_arm_map`)
+pub const LEGACY_ARM_ACTIONS: &[(&str, &str)] = &[
+    ("remind_checkin", "do_checkin"),
+    ("suggest_pomodoro", "start_pomodoro"),
+    ("warn_overdue", "tackle_assignment"),
+    ("recommend_break", "take_break"),
+    ("suggest_workout", "do_workout"),
+    ("practice_reminder", "practice_skill"),
+    ("weekly_reflection", "weekly_review"),
+    ("celebrate_streak", "celebrate_streak"),
+    ("productivity_tip", "productivity_tip"),
+    ("pre_exam_protocol", "pre_exam_protocol"),
+];
+
+/// Legacy arm whose message template fits a v2 action
+pub fn legacy_arm_for_action(action_name: &str) -> Option<&'static str> {
+    LEGACY_ARM_ACTIONS
+        .iter()
+        .find(|(_, action)| *action == action_name)
+        .map(|(arm, _)| *arm)
+}
+
+/// Entry point for insight generation and feedback
+pub struct AgentFacade;
+
+impl AgentFacade {
+    /// Get insights, falling back to the legacy pipeline if v2 fails or ML
+    /// is still loading
+    ///
+    /// In shadow mode recommendations are still selected and logged, but
+    /// nothing is returned.
+    pub async fn insights(pool: &Pool<Sqlite>) -> Result<Vec<Insight>, ApiError> {
+        let shadow = is_shadow_mode(pool).await.map_err(ApiError::internal)?;
+
+        if readiness::is_disabled() {
+            // Nothing to shadow without the recommendation pipeline
+            return if shadow { Ok(Vec::new()) } else { insights::get_insights_for_pool(pool).await };
+        }
+        if readiness::is_loading() && !shadow {
+            return insights::get_insights_for_pool(pool).await;
+        }
+
+        match Self::v2_insights(pool, shadow).await {
+            Ok(insights) => Ok(insights),
+            Err(e) if shadow => {
+                log::warn!("Shadow recommendation failed: {}", e.message);
+                Ok(Vec::new())
+            }
+            Err(e) => {
+                log::warn!("Intelligence insights failed, using legacy pipeline: {}", e.message);
+                insights::get_insights_for_pool(pool).await
+            }
+        }
+    }
+
+    /// Record explicit feedback on an insight
+    pub async fn record_feedback(
+        pool: &Pool<Sqlite>,
+        insight_key: Option<&str>,
+        insight_id: i64,
+        acted_on: bool,
+        feedback_score: Option<i32>,
+    ) -> Result<(), String> {
+        if insight_key.is_some_and(|k| k.starts_with(ACTION_KEY_PREFIX)) {
+            let outcome =
+                RewardEngine::compute_immediate_reward(acted_on, feedback_score, false, None);
+            IntelligenceAgent::record_feedback(
+                pool,
+                insight_id,
+                acted_on,
+                None,
+                feedback_score,
+                Some(outcome),
+            )
+            .await
+        } else {
+            ContextualBandit::record_feedback(pool, insight_id, acted_on, feedback_score).await
+        }
+    }
+
+    async fn v2_insights(pool: &Pool<Sqlite>, shadow: bool) -> Result<Vec<Insight>, ApiError> {
+        let filter = InsightFilter::load(pool).await?;
+        let caps = get_action_daily_caps(pool).await.map_err(ApiError::internal)?;
+
+        // Skip capped and dismissed actions before selection, so every
+        // recorded recommendation is one the user actually sees
+        let exclude: Vec<String> = HybridBandit::get_actions(pool)
+            .await
+            .map_err(ApiError::internal)?
+            .into_iter()
+            .filter(|action| {
+                let cap = caps.get(&action.name).copied().unwrap_or(DEFAULT_DAILY_CAP);
+                !filter.allows(&action_key(&action.name), cap)
+            })
+            .map(|action| action.name)
+            .collect();
+
+        let recommendations =
+            IntelligenceAgent::get_recommendations_excluding(pool, MAX_ARM_INSIGHTS, &exclude)
+                .await
+                .map_err(ApiError::internal)?;
+
+        if shadow {
+            // Caps still apply, so the shadow log matches what would have been shown
+            for rec in &recommendations {
+                let _ = log_insight_shown(pool, &action_key(&rec.action.name), &rec.action.category)
+                    .await;
+            }
+            return Ok(Vec::new());
+        }
+
+        // Legacy context is only needed for message templates and patterns
+        let context = FeatureStore::capture_context(pool)
+            .await
+            .map_err(ApiError::internal)?;
+
+        let mut insights = Vec::new();
+        let mut shown_keys = Vec::new();
+
+        for rec in recommendations {
+            let key = action_key(&rec.action.name);
+            let (icon, message) = match legacy_arm_for_action(&rec.action.name) {
+                Some(arm) => match generate_insight_for_arm(pool, arm, &context).await {
+                    Some(t) => (t.icon, t.message),
+                    None => {
+                        // The template's own checks say it doesn't apply right
+                        // now (e.g. already checked in); don't score it as ignored
+                        if let Some(id) = rec.recommendation_id {
+                            let _ = sqlx::query(
+                                "UPDATE agent_recommendations SET implicit_checked_at = datetime('now') WHERE id = ?",
+                            )
+                            .bind(id)
+                            .execute(pool)
+                            .await;
+                        }
+                        continue;
+                    }
+                },
+                None => (
+                    category_icon(&rec.action.category).to_string(),
+                    format!("{}. {}", rec.action.description, rec.explanation),
+                ),
+            };
+
+            shown_keys.push((key.clone(), rec.action.category.clone()));
+            insights.push(Insight {
+                icon,
+                message,
+                category: rec.action.category.clone(),
+                confidence: Some(rec.expected_reward as f64),
+                insight_id: rec.recommendation_id,
+                arm_name: Some(rec.action.name.clone()),
+                insight_key: Some(key),
+            });
+        }
+
+        append_pattern_insights(pool, &context, &filter, &mut insights, &mut shown_keys).await?;
+        finish_insights(pool, &filter, insights, shown_keys).await
+    }
+}
+
+/// Per-action daily caps (actions without one use `DEFAULT_DAILY_CAP`)
+async fn get_action_daily_caps(pool: &Pool<Sqlite>) -> Result<HashMap<String, i64>, String> {
+    let rows: Vec<(String, i64)> = sqlx::query_as(
+        "SELECT action_name, daily_cap FROM agent_linear_bandit WHERE daily_cap IS NOT NULL",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(rows.into_iter().collect())
+}
+
+fn action_key(action_name: &str) -> String {
+    format!("{}{}", ACTION_KEY_PREFIX, action_name)
+}
+
+fn category_icon(category: &str) -> &'static str {
+    match category {
+        "productivity" | "academic" => "🎯",
+        "physical" => "💪",
+        "wellness" => "🧘",
+        "skills" => "🎸",
+        "reflection" => "📓",
+        "motivation" => "🔥",
+        _ => "💡",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+    use std::str::FromStr;
+
+    async fn setup_pool_with_migrations() -> Pool<Sqlite> {
+        let options = SqliteConnectOptions::from_str("sqlite::memory:")
+            .unwrap()
+            .foreign_keys(false);
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect_with(options)
+            .await
+            .expect("Failed to connect to in-memory DB");
+
+        crate::db::migrations::run_migrations(&pool)
+            .await
+            .expect("Failed to run migrations");
+
+        pool
+    }
+
+    #[tokio::test]
+    async fn every_legacy_arm_has_a_v2_action() {
+        let pool = setup_pool_with_migrations().await;
+
+        let arms: Vec<String> = sqlx::query_scalar("SELECT arm_name FROM agent_bandit_arms")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+        assert!(!arms.is_empty());
+
+        for arm in arms {
+            let (_, action) = LEGACY_ARM_ACTIONS
+                .iter()
+                .find(|(a, _)| *a == arm)
+                .unwrap_or_else(|| panic!("{} is not mapped", arm));
+            assert_eq!(legacy_arm_for_action(action), Some(arm.as_str()));
+
+            let mapped: String = sqlx::query_scalar(
+                "SELECT action_name FROM agent_legacy_arm_map WHERE arm_name = ?",
+            )
+            .bind(&arm)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+            assert_eq!(mapped, *action);
+
+            let exists: i64 = sqlx::query_scalar(
+                "SELECT COUNT(*) FROM agent_linear_bandit WHERE action_name = ?",
+            )
+            .bind(action)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+            assert_eq!(exists, 1, "{} has no v2 action", action);
+        }
+    }
+}
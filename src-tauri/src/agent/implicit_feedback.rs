@@ -0,0 +1,257 @@
+//! Implicit Feedback
+//!
+//! Infers whether a shown recommendation was followed from what the user did
+//! afterwards (study sessions, workouts, check-ins, ...), so the bandit keeps
+//! learning when no explicit feedback is ever given. A followed pomodoro
+//! suggestion earns less when the pomodoros were abandoned or interrupted.
+//!
+//! Shadow-mode recommendations were never shown, so their inferred outcome is
+//! only recorded (`feedback_type = 'shadow'`) and the bandit is left alone.
+
+use serde::Serialize;
+use sqlx::{Pool, Sqlite};
+
+use crate::ml::bandit_v2::HybridBandit;
+use crate::ml::rich_features::RichContext;
+use crate::services::pomodoro;
+
+/// Hours after a recommendation in which a matching action counts as following it
+pub const IMPLICIT_WINDOW_HOURS: f64 = 3.0;
+
+/// Recommendations older than this are no longer evaluated
+const LOOKBACK_DAYS: i64 = 7;
+
+/// Reward when a matching action happened within the window
+const FOLLOWED_REWARD: f64 = 0.8;
+
+/// Reward when nothing matching happened (mild: not acting isn't a rejection)
+const IGNORED_REWARD: f64 = 0.3;
+
+/// Outcome of one implicit feedback pass
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct ImplicitFeedbackSummary {
+    pub followed: usize,
+    pub ignored: usize,
+    /// Actions with no observable evidence (or no stored context)
+    pub skipped: usize,
+    /// Shadow-mode recommendations whose outcome was recorded
+    pub shadow: usize,
+}
+
+/// Query counting evidence that an action was taken
+///
+/// Binds: `?1` recommendation timestamp, `?2` window in hours.
+fn evidence_query(action_name: &str) -> Option<&'static str> {
+    match action_name {
+        "start_pomodoro" | "start_study_session" => Some(
+            "SELECT COUNT(*) FROM sessions
+             WHERE julianday(started_at) BETWEEN julianday(?1) AND julianday(?1) + ?2 / 24.0",
+        ),
+        "deep_work_block" => Some(
+            "SELECT COUNT(*) FROM sessions
+             WHERE julianday(started_at) BETWEEN julianday(?1) AND julianday(?1) + ?2 / 24.0
+               AND COALESCE(duration_minutes, 0) >= 90",
+        ),
+        "tackle_assignment" => Some(
+            "SELECT COUNT(*) FROM assignments
+             WHERE julianday(completed_at) BETWEEN julianday(?1) AND julianday(?1) + ?2 / 24.0",
+        ),
+        "do_workout" | "take_walk" | "stretch_break" => Some(
+            "SELECT COUNT(*) FROM workouts
+             WHERE julianday(logged_at) BETWEEN julianday(?1) AND julianday(?1) + ?2 / 24.0",
+        ),
+        "do_checkin" => Some(
+            "SELECT COUNT(*) FROM check_ins
+             WHERE julianday(checked_in_at) BETWEEN julianday(?1) AND julianday(?1) + ?2 / 24.0",
+        ),
+        "practice_skill" => Some(
+            "SELECT COUNT(*) FROM practice_logs
+             WHERE julianday(logged_at) BETWEEN julianday(?1) AND julianday(?1) + ?2 / 24.0",
+        ),
+        "meditation" => Some(
+            "SELECT COUNT(*) FROM tracker_logs
+             WHERE tracker_id = (SELECT id FROM trackers WHERE slug = 'meditation')
+               AND julianday(logged_at) BETWEEN julianday(?1) AND julianday(?1) + ?2 / 24.0",
+        ),
+        "weekly_review" => Some(
+            "SELECT COUNT(*) FROM weekly_reviews
+             WHERE julianday(created_at) BETWEEN julianday(?1) AND julianday(?1) + ?2 / 24.0",
+        ),
+        _ => None,
+    }
+}
+
+/// Evaluate recommendations that received no explicit feedback
+///
+/// Only recommendations whose window has closed are considered, and each one
+/// is evaluated once. Followed/ignored outcomes update the bandit using the
+/// context captured when the recommendation was made.
+pub async fn process_implicit_feedback(
+    pool: &Pool<Sqlite>,
+) -> Result<ImplicitFeedbackSummary, String> {
+    let pending: Vec<(i64, String, String, Option<Vec<u8>>, bool)> = sqlx::query_as(
+        r#"
+        SELECT r.id, r.action_recommended, r.timestamp, c.context_features, r.is_shadow
+        FROM agent_recommendations r
+        LEFT JOIN agent_rich_context c ON r.context_id = c.id
+        WHERE r.feedback_type IS NULL
+          AND r.implicit_checked_at IS NULL
+          AND julianday(r.timestamp) <= julianday('now') - ? / 24.0
+          AND r.timestamp >= datetime('now', '-' || ? || ' days')
+        ORDER BY r.timestamp
+        "#,
+    )
+    .bind(IMPLICIT_WINDOW_HOURS)
+    .bind(LOOKBACK_DAYS)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let mut summary = ImplicitFeedbackSummary::default();
+
+    for (id, action_name, timestamp, context_bytes, is_shadow) in pending {
+        let context = context_bytes.as_deref().and_then(RichContext::from_bytes);
+        let (Some(query), Some(context)) = (evidence_query(&action_name), context) else {
+            sqlx::query(
+                "UPDATE agent_recommendations SET implicit_checked_at = datetime('now') WHERE id = ?",
+            )
+            .bind(id)
+            .execute(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+            summary.skipped += 1;
+            continue;
+        };
+
+        let matches: i64 = sqlx::query_scalar(query)
+            .bind(&timestamp)
+            .bind(IMPLICIT_WINDOW_HOURS)
+            .fetch_one(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+        let followed = matches > 0;
+        let reward = match (followed, action_name.as_str()) {
+            // A pomodoro started but abandoned or interrupted is only partly followed
+            (true, "start_pomodoro") => match pomodoro::window_quality(pool, &timestamp, IMPLICIT_WINDOW_HOURS)
+                .await
+                .map_err(|e| e.message)?
+            {
+                Some(quality) => IGNORED_REWARD + (FOLLOWED_REWARD - IGNORED_REWARD) * quality,
+                None => FOLLOWED_REWARD,
+            },
+            (true, _) => FOLLOWED_REWARD,
+            (false, _) => IGNORED_REWARD,
+        };
+
+        sqlx::query(
+            r#"
+            UPDATE agent_recommendations
+            SET was_accepted = ?, outcome_score = COALESCE(?, outcome_score), feedback_type = ?,
+                implicit_checked_at = datetime('now')
+            WHERE id = ?
+            "#,
+        )
+        .bind(followed)
+        .bind(is_shadow.then_some(reward))
+        .bind(if is_shadow { "shadow" } else { "implicit" })
+        .bind(id)
+        .execute(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+        if is_shadow {
+            summary.shadow += 1;
+            continue;
+        }
+
+        HybridBandit::update(pool, &action_name, &context, reward).await?;
+        HybridBandit::log_reward(pool, &action_name, &context, reward as f32, "implicit").await?;
+
+        if followed {
+            summary.followed += 1;
+        } else {
+            summary.ignored += 1;
+        }
+    }
+
+    Ok(summary)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ml::rich_features::RichFeatureStore;
+    use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+    use std::str::FromStr;
+
+    async fn setup_pool_with_migrations() -> Pool<Sqlite> {
+        let options = SqliteConnectOptions::from_str("sqlite::memory:")
+            .unwrap()
+            .foreign_keys(false);
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect_with(options)
+            .await
+            .expect("Failed to connect to in-memory DB");
+
+        crate::db::migrations::run_migrations(&pool)
+            .await
+            .expect("Failed to run migrations");
+
+        pool
+    }
+
+    async fn insert_recommendation(pool: &Pool<Sqlite>, action: &str, context_id: i64) {
+        sqlx::query(
+            "INSERT INTO agent_recommendations (timestamp, action_recommended, context_id)
+             VALUES (datetime('now', '-5 hours'), ?, ?)",
+        )
+        .bind(action)
+        .bind(context_id)
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn infers_followed_and_ignored_recommendations() {
+        let pool = setup_pool_with_migrations().await;
+        let context_id = RichFeatureStore::save_snapshot(&pool, &RichContext::default())
+            .await
+            .unwrap();
+
+        insert_recommendation(&pool, "do_workout", context_id).await;
+        insert_recommendation(&pool, "do_checkin", context_id).await;
+        insert_recommendation(&pool, "take_break", context_id).await;
+        sqlx::query("INSERT INTO workouts (logged_at) VALUES (datetime('now', '-4 hours'))")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let summary = process_implicit_feedback(&pool).await.unwrap();
+        assert_eq!(summary.followed, 1);
+        assert_eq!(summary.ignored, 1);
+        assert_eq!(summary.skipped, 1);
+
+        let pulls: i64 = sqlx::query_scalar(
+            "SELECT total_pulls FROM agent_linear_bandit WHERE action_name = 'do_workout'",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(pulls, 1);
+
+        // Each recommendation is evaluated only once
+        let again = process_implicit_feedback(&pool).await.unwrap();
+        assert_eq!(again.followed + again.ignored + again.skipped, 0);
+    }
+
+    #[test]
+    fn test_evidence_covers_observable_actions() {
+        for action in ["start_pomodoro", "do_workout", "do_checkin", "practice_skill", "meditation"] {
+            assert!(evidence_query(action).is_some(), "{} has no evidence", action);
+        }
+        // Nothing in the database records a break
+        assert!(evidence_query("take_break").is_none());
+    }
+}
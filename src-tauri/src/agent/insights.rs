@@ -6,9 +6,30 @@
 use tauri::State;
 use serde_json;
 
+use std::collections::{HashMap, HashSet};
+
 use crate::{DbState, error::ApiError};
+use crate::commands::procrastination::procrastination_report;
+use crate::commands::techniques::best_techniques;
 use crate::ml::{FeatureStore, ContextualBandit, PatternMiner, UserProfile};
-use crate::ml::models::AdaptiveInsight;
+use crate::ml::models::{AdaptiveInsight, Pattern, PatternData};
+use super::facade::AgentFacade;
+use super::preferences::{self, get_muted_categories};
+
+/// Maximum number of bandit-selected insights per call
+pub(super) const MAX_ARM_INSIGHTS: usize = 3;
+
+/// Default number of times the same insight may be shown per rolling day
+pub(super) const DEFAULT_DAILY_CAP: i64 = 1;
+
+/// How long a dismissed insight stays hidden
+const DISMISS_DAYS: i64 = 7;
+
+/// Assignments a course needs before its start lead is worth mentioning
+const MIN_PROCRASTINATION_SAMPLES: i64 = 3;
+
+/// Starting closer to the deadline than this, on average, is mentioned
+const PROCRASTINATION_LEAD_DAYS: f64 = 3.0;
 
 #[derive(Debug, serde::Serialize)]
 pub struct Insight {
@@ -21,15 +42,50 @@ pub struct Insight {
     pub insight_id: Option<i64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub arm_name: Option<String>,
+    /// Stable key used for dedupe and dismissal
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub insight_key: Option<String>,
 }
 
-/// Get adaptive insights using ML-powered selection
+/// Get adaptive insights
+///
+/// Routed through `AgentFacade`, which uses the intelligence pipeline and
+/// falls back to the legacy bandit if that fails.
 #[tauri::command]
 pub async fn get_insights(state: State<'_, DbState>) -> Result<Vec<Insight>, ApiError> {
-    get_insights_for_pool(&state.0).await
+    AgentFacade::insights(&state.0).await
 }
 
-async fn get_insights_for_pool(pool: &sqlx::Pool<sqlx::Sqlite>) -> Result<Vec<Insight>, ApiError> {
+/// Shared dedupe state: frequency caps, dismissals and mutes
+pub(super) struct InsightFilter {
+    recently_shown: HashMap<String, i64>,
+    dismissed: HashSet<String>,
+    muted: Vec<String>,
+}
+
+impl InsightFilter {
+    pub(super) async fn load(pool: &sqlx::Pool<sqlx::Sqlite>) -> Result<Self, ApiError> {
+        Ok(Self {
+            recently_shown: get_recently_shown(pool).await.map_err(ApiError::internal)?,
+            dismissed: get_dismissed_keys(pool).await.map_err(ApiError::internal)?,
+            muted: get_muted_categories(pool).await.map_err(ApiError::internal)?,
+        })
+    }
+
+    /// Whether an insight key is under its cap and not dismissed
+    pub(super) fn allows(&self, key: &str, cap: i64) -> bool {
+        self.recently_shown.get(key).copied().unwrap_or(0) < cap && !self.dismissed.contains(key)
+    }
+
+    pub(super) fn is_muted(&self, category: &str) -> bool {
+        self.muted.iter().any(|m| m == category)
+    }
+}
+
+/// Legacy pipeline: Thompson sampling over `agent_bandit_arms`
+pub(super) async fn get_insights_for_pool(
+    pool: &sqlx::Pool<sqlx::Sqlite>,
+) -> Result<Vec<Insight>, ApiError> {
     let mut insights = Vec::new();
 
     // Capture current context
@@ -40,19 +96,29 @@ async fn get_insights_for_pool(pool: &sqlx::Pool<sqlx::Sqlite>) -> Result<Vec<In
     // Save context snapshot for pattern mining (don't fail if this fails)
     let _ = FeatureStore::save_snapshot(pool, &context).await;
 
-    // Get top bandit arms to show
-    let selected_arms = ContextualBandit::select_top_arms(pool, &context, 3)
-        .await
-        .map_err(ApiError::internal)?;
-
-    // Get active patterns for context-aware insights
-    let patterns = PatternMiner::get_active_patterns(pool)
+    // Rank all arms; frequency caps decide which of them can be shown
+    let selected_arms = ContextualBandit::select_top_arms(pool, &context, usize::MAX)
         .await
         .map_err(ApiError::internal)?;
+    let arm_caps = get_arm_daily_caps(pool).await.map_err(ApiError::internal)?;
+    let filter = InsightFilter::load(pool).await?;
+    let mut shown_keys: Vec<(String, String)> = Vec::new();
 
     // Generate insights from selected arms
     for arm in &selected_arms {
+        if insights.len() >= MAX_ARM_INSIGHTS {
+            break;
+        }
+        let key = format!("arm:{}", arm.arm_name);
+        let cap = arm_caps.get(&arm.arm_name).copied().unwrap_or(DEFAULT_DAILY_CAP);
+        if !filter.allows(&key, cap) || arm.category.as_ref().is_some_and(|c| filter.is_muted(c)) {
+            continue;
+        }
+
         if let Some(insight) = generate_insight_for_arm(pool, &arm.arm_name, &context).await {
+            if filter.is_muted(&insight.category) {
+                continue;
+            }
             // Record that this insight was shown
             let context_json = serde_json::to_string(&context).unwrap_or_default();
             let insight_id = ContextualBandit::record_insight_shown(
@@ -64,6 +130,7 @@ async fn get_insights_for_pool(pool: &sqlx::Pool<sqlx::Sqlite>) -> Result<Vec<In
             .await
             .ok();
 
+            shown_keys.push((key.clone(), insight.category.clone()));
             insights.push(Insight {
                 icon: insight.icon,
                 message: insight.message,
@@ -71,15 +138,53 @@ async fn get_insights_for_pool(pool: &sqlx::Pool<sqlx::Sqlite>) -> Result<Vec<In
                 confidence: Some(arm.expected_value()),
                 insight_id,
                 arm_name: Some(arm.arm_name.clone()),
+                insight_key: Some(key),
             });
         }
     }
 
-    // Add pattern-based insights
-    for pattern in patterns.iter().take(2) {
-        if let Some(msg) = PatternMiner::pattern_to_insight(pattern, &context) {
+    append_pattern_insights(pool, &context, &filter, &mut insights, &mut shown_keys).await?;
+    finish_insights(pool, &filter, insights, shown_keys).await
+}
+
+/// Add up to two pattern-based insights
+///
+/// Mining can store the same pattern more than once, so patterns are deduped
+/// by name as well as by message.
+pub(super) async fn append_pattern_insights(
+    pool: &sqlx::Pool<sqlx::Sqlite>,
+    context: &crate::ml::models::Context,
+    filter: &InsightFilter,
+    insights: &mut Vec<Insight>,
+    shown_keys: &mut Vec<(String, String)>,
+) -> Result<(), ApiError> {
+    let patterns = PatternMiner::get_active_patterns(pool)
+        .await
+        .map_err(ApiError::internal)?;
+
+    let mut seen_patterns = HashSet::new();
+    let mut pattern_count = 0;
+    for pattern in &patterns {
+        if pattern_count >= 2 {
+            break;
+        }
+        let key = format!(
+            "pattern:{}",
+            pattern.pattern_name.clone().unwrap_or_else(|| pattern.id.to_string())
+        );
+        if !seen_patterns.insert(key.clone())
+            || !filter.allows(&key, DEFAULT_DAILY_CAP)
+            || filter.is_muted(&pattern.pattern_type)
+            || pattern_topic(pattern).is_some_and(|t| filter.is_muted(t))
+        {
+            continue;
+        }
+
+        if let Some(msg) = PatternMiner::pattern_to_insight(pattern, context) {
             // Avoid duplicate insights
             if !insights.iter().any(|i| i.message.contains(&msg[..20.min(msg.len())])) {
+                pattern_count += 1;
+                shown_keys.push((key.clone(), pattern.pattern_type.clone()));
                 insights.push(Insight {
                     icon: "🔍".to_string(),
                     message: msg,
@@ -87,14 +192,45 @@ async fn get_insights_for_pool(pool: &sqlx::Pool<sqlx::Sqlite>) -> Result<Vec<In
                     confidence: Some(pattern.confidence),
                     insight_id: None,
                     arm_name: None,
+                    insight_key: Some(key),
                 });
             }
         }
     }
 
+    Ok(())
+}
+
+/// Apply rule-based fallbacks, log what is shown and fill in a default message
+pub(super) async fn finish_insights(
+    pool: &sqlx::Pool<sqlx::Sqlite>,
+    filter: &InsightFilter,
+    mut insights: Vec<Insight>,
+    mut shown_keys: Vec<(String, String)>,
+) -> Result<Vec<Insight>, ApiError> {
     // Fallback to rule-based if no ML insights
     if insights.is_empty() {
-        insights = get_fallback_insights(pool).await?;
+        for (key, insight) in get_fallback_insights(pool).await? {
+            if filter.allows(&key, DEFAULT_DAILY_CAP) && !filter.is_muted(&insight.category) {
+                shown_keys.push((key, insight.category.clone()));
+                insights.push(insight);
+            }
+        }
+    }
+
+    if let Some((key, insight)) = get_technique_insight(pool, filter).await {
+        shown_keys.push((key, insight.category.clone()));
+        insights.push(insight);
+    }
+
+    if let Some((key, insight)) = get_procrastination_insight(pool, filter).await {
+        shown_keys.push((key, insight.category.clone()));
+        insights.push(insight);
+    }
+
+    for (key, category) in &shown_keys {
+        // Logging is best-effort; a failure only weakens future dedupe
+        let _ = log_insight_shown(pool, key, category).await;
     }
 
     // If still no insights, default message
@@ -106,6 +242,7 @@ async fn get_insights_for_pool(pool: &sqlx::Pool<sqlx::Sqlite>) -> Result<Vec<In
             confidence: None,
             insight_id: None,
             arm_name: None,
+            insight_key: None,
         });
     }
 
@@ -113,7 +250,7 @@ async fn get_insights_for_pool(pool: &sqlx::Pool<sqlx::Sqlite>) -> Result<Vec<In
 }
 
 /// Generate an insight for a specific bandit arm
-async fn generate_insight_for_arm(
+pub(super) async fn generate_insight_for_arm(
     pool: &sqlx::Pool<sqlx::Sqlite>,
     arm_name: &str,
     context: &crate::ml::models::Context,
@@ -143,7 +280,17 @@ async fn generate_insight_for_arm(
         },
         
         "suggest_pomodoro" => {
-            if !context.session_in_progress {
+            if !context.session_in_progress && context.in_pre_exam_window() {
+                Some(AdaptiveInsight {
+                    icon: "🍅".to_string(),
+                    message: "Exam coming up: keep study blocks short (25 min) and frequent rather than cramming.".to_string(),
+                    category: "productivity".to_string(),
+                    arm_name: arm_name.to_string(),
+                    confidence: 0.85,
+                    context_hash: String::new(),
+                    insight_id: None,
+                })
+            } else if !context.session_in_progress {
                 let optimal = (9..=11).contains(&context.hour_of_day) || (14..=17).contains(&context.hour_of_day);
                 if optimal {
                     Some(AdaptiveInsight {
@@ -221,7 +368,17 @@ async fn generate_insight_for_arm(
         },
         
         "suggest_workout" => {
-            if context.recent_workout_count == 0 {
+            if context.recent_workout_count == 0 && context.in_pre_exam_window() {
+                Some(AdaptiveInsight {
+                    icon: "💪".to_string(),
+                    message: "Exam week: a light session keeps you fresh. Skip heavy training until after the exam.".to_string(),
+                    category: "physical".to_string(),
+                    arm_name: arm_name.to_string(),
+                    confidence: 0.6,
+                    context_hash: String::new(),
+                    insight_id: None,
+                })
+            } else if context.recent_workout_count == 0 {
                 let morning = context.hour_of_day < 12;
                 Some(AdaptiveInsight {
                     icon: "💪".to_string(),
@@ -318,12 +475,111 @@ async fn generate_insight_for_arm(
             }
         },
         
+        "pre_exam_protocol" => {
+            match context.days_to_next_exam {
+                Some(days) if context.in_pre_exam_window() => {
+                    let when = match days {
+                        0 => "today".to_string(),
+                        1 => "tomorrow".to_string(),
+                        d => format!("in {} days", d),
+                    };
+                    Some(AdaptiveInsight {
+                        icon: "😴".to_string(),
+                        message: format!(
+                            "Exam {}. Protect your sleep: keep a consistent bedtime and wind down without screens.",
+                            when
+                        ),
+                        category: "wellness".to_string(),
+                        arm_name: arm_name.to_string(),
+                        confidence: 0.85,
+                        context_hash: String::new(),
+                        insight_id: None,
+                    })
+                }
+                _ => None,
+            }
+        },
+        
+        _ => None,
+    }
+}
+
+/// Per-arm daily caps (arms without one use `DEFAULT_DAILY_CAP`)
+async fn get_arm_daily_caps(
+    pool: &sqlx::Pool<sqlx::Sqlite>,
+) -> Result<HashMap<String, i64>, String> {
+    let rows: Vec<(String, i64)> = sqlx::query_as(
+        "SELECT arm_name, daily_cap FROM agent_bandit_arms WHERE daily_cap IS NOT NULL",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(rows.into_iter().collect())
+}
+
+/// How often each insight key was shown in the last 24 hours
+async fn get_recently_shown(
+    pool: &sqlx::Pool<sqlx::Sqlite>,
+) -> Result<HashMap<String, i64>, String> {
+    let rows: Vec<(String, i64)> = sqlx::query_as(
+        r#"
+        SELECT insight_key, COUNT(*) FROM agent_shown_insights
+        WHERE shown_at >= datetime('now', '-1 day')
+        GROUP BY insight_key
+        "#,
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(rows.into_iter().collect())
+}
+
+/// Keys of insights dismissed within `DISMISS_DAYS`
+async fn get_dismissed_keys(pool: &sqlx::Pool<sqlx::Sqlite>) -> Result<HashSet<String>, String> {
+    let keys: Vec<String> = sqlx::query_scalar(
+        "SELECT DISTINCT insight_key FROM agent_insight_dismissals WHERE dismissed_at >= datetime('now', '-' || ? || ' days')",
+    )
+    .bind(DISMISS_DAYS)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(keys.into_iter().collect())
+}
+
+/// Category a pattern nudges about, beyond its pattern type
+fn pattern_topic(pattern: &Pattern) -> Option<&'static str> {
+    match serde_json::from_str::<PatternData>(&pattern.pattern_json).ok()? {
+        PatternData::Correlation { factor_a, .. } if factor_a.contains("workout") => Some("physical"),
+        PatternData::Temporal { metric, .. } if metric == "study_duration" => Some("productivity"),
+        PatternData::Temporal { metric, .. } if metric == "mood" || metric == "energy" => Some("wellness"),
         _ => None,
     }
 }
 
+/// Record that an insight was shown, for cross-session dedupe
+pub(super) async fn log_insight_shown(
+    pool: &sqlx::Pool<sqlx::Sqlite>,
+    insight_key: &str,
+    category: &str,
+) -> Result<(), String> {
+    sqlx::query("INSERT INTO agent_shown_insights (insight_key, category) VALUES (?, ?)")
+        .bind(insight_key)
+        .bind(category)
+        .execute(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+    Ok(())
+}
+
 /// Fallback to simple rule-based insights when ML hasn't learned enough
-async fn get_fallback_insights(pool: &sqlx::Pool<sqlx::Sqlite>) -> Result<Vec<Insight>, ApiError> {
+///
+/// Each insight is paired with its dedupe key.
+async fn get_fallback_insights(
+    pool: &sqlx::Pool<sqlx::Sqlite>,
+) -> Result<Vec<(String, Insight)>, ApiError> {
     let mut insights = Vec::new();
 
     // Check for missing check-in today
@@ -335,14 +591,15 @@ async fn get_fallback_insights(pool: &sqlx::Pool<sqlx::Sqlite>) -> Result<Vec<In
     .unwrap_or(0);
 
     if has_checkin_today == 0 {
-        insights.push(Insight {
+        insights.push(("rule:checkin".to_string(), Insight {
             icon: "📝".to_string(),
             message: "Start your day with a quick check-in to track mood and energy.".to_string(),
             category: "wellness".to_string(),
             confidence: None,
             insight_id: None,
             arm_name: None,
-        });
+            insight_key: Some("rule:checkin".to_string()),
+        }));
     }
 
     // Check for overdue assignments
@@ -354,7 +611,7 @@ async fn get_fallback_insights(pool: &sqlx::Pool<sqlx::Sqlite>) -> Result<Vec<In
     .unwrap_or(0);
 
     if overdue_count > 0 {
-        insights.push(Insight {
+        insights.push(("rule:overdue".to_string(), Insight {
             icon: "⚠️".to_string(),
             message: format!("You have {} overdue assignment{}. Consider prioritizing these today.", 
                 overdue_count, if overdue_count == 1 { "" } else { "s" }),
@@ -362,12 +619,90 @@ async fn get_fallback_insights(pool: &sqlx::Pool<sqlx::Sqlite>) -> Result<Vec<In
             confidence: None,
             insight_id: None,
             arm_name: None,
-        });
+            insight_key: Some("rule:overdue".to_string()),
+        }));
     }
 
     Ok(insights)
 }
 
+/// The study technique that clearly works best in some course, if any
+///
+/// Best-effort: analytics errors just mean no insight.
+async fn get_technique_insight(
+    pool: &sqlx::Pool<sqlx::Sqlite>,
+    filter: &InsightFilter,
+) -> Option<(String, Insight)> {
+    if filter.is_muted("academic") {
+        return None;
+    }
+    let winners = best_techniques(pool).await.ok()?;
+    let winner = winners.into_iter().find(|w| {
+        filter.allows(&format!("technique:{}:{}", w.course_id, w.best.technique_id), DEFAULT_DAILY_CAP)
+    })?;
+    let key = format!("technique:{}:{}", winner.course_id, winner.best.technique_id);
+
+    Some((key.clone(), Insight {
+        icon: "🧠".to_string(),
+        message: format!(
+            "{} works best for you in {}: those sessions score {:.0}% vs {:.0}% with {}.",
+            winner.best.technique_name,
+            winner.course_name,
+            winner.best.avg_outcome * 100.0,
+            winner.runner_up.avg_outcome * 100.0,
+            winner.runner_up.technique_name.to_lowercase(),
+        ),
+        category: "academic".to_string(),
+        confidence: None,
+        insight_id: None,
+        arm_name: None,
+        insight_key: Some(key),
+    }))
+}
+
+/// How close to the deadline assignments in the latest-starting course get
+/// started, once there are enough of them to say
+///
+/// Best-effort: analytics errors just mean no insight.
+async fn get_procrastination_insight(
+    pool: &sqlx::Pool<sqlx::Sqlite>,
+    filter: &InsightFilter,
+) -> Option<(String, Insight)> {
+    if filter.is_muted("academic") {
+        return None;
+    }
+    let report = procrastination_report(pool).await.ok()?;
+    let course = report.by_course.into_iter().find(|c| {
+        c.stats.assignments >= MIN_PROCRASTINATION_SAMPLES
+            && filter.allows(&format!("procrastination:{}", c.course_id), DEFAULT_DAILY_CAP)
+    })?;
+    let lead = course.stats.avg_start_lead_days?;
+    if lead >= PROCRASTINATION_LEAD_DAYS {
+        return None;
+    }
+    let key = format!("procrastination:{}", course.course_id);
+
+    let mut message = format!(
+        "You start {} assignments on average {:.1} days before they're due",
+        course.course_name, lead
+    );
+    match course.stats.avg_completion_margin_hours {
+        Some(margin) if margin < 0.0 => message.push_str(&format!(" and finish {:.0}h late.", -margin)),
+        _ => message.push('.'),
+    }
+    message.push_str(" Try a first session right after they're set.");
+
+    Some((key.clone(), Insight {
+        icon: "⏳".to_string(),
+        message,
+        category: "academic".to_string(),
+        confidence: None,
+        insight_id: None,
+        arm_name: None,
+        insight_key: Some(key),
+    }))
+}
+
 /// Record feedback on an insight (called from frontend)
 #[tauri::command]
 pub async fn record_insight_feedback(
@@ -375,9 +710,66 @@ pub async fn record_insight_feedback(
     insight_id: i64,
     acted_on: bool,
     feedback_score: Option<i32>,
+    insight_key: Option<String>,
 ) -> Result<(), ApiError> {
     let pool = &state.0;
-    ContextualBandit::record_feedback(pool, insight_id, acted_on, feedback_score)
+    AgentFacade::record_feedback(pool, insight_key.as_deref(), insight_id, acted_on, feedback_score)
+        .await
+        .map_err(ApiError::internal)
+}
+
+/// Dismiss an insight so it isn't shown again for a while
+///
+/// Dismissing a bandit insight also counts as "not acted on" for learning.
+#[tauri::command]
+pub async fn dismiss_insight(
+    state: State<'_, DbState>,
+    insight_key: String,
+    insight_id: Option<i64>,
+) -> Result<(), ApiError> {
+    let pool = &state.0;
+
+    sqlx::query("INSERT INTO agent_insight_dismissals (insight_key) VALUES (?)")
+        .bind(&insight_key)
+        .execute(pool)
+        .await
+        .map_err(ApiError::from)?;
+
+    if let Some(id) = insight_id {
+        if !insight_key.starts_with("action:") {
+            sqlx::query("UPDATE agent_insights SET dismissed_at = CURRENT_TIMESTAMP WHERE id = ?")
+                .bind(id)
+                .execute(pool)
+                .await
+                .map_err(ApiError::from)?;
+        }
+        AgentFacade::record_feedback(pool, Some(&insight_key), id, false, None)
+            .await
+            .map_err(ApiError::internal)?;
+    }
+
+    Ok(())
+}
+
+/// Get muted insight/recommendation categories
+#[tauri::command]
+pub async fn get_muted_insight_categories(state: State<'_, DbState>) -> Result<Vec<String>, ApiError> {
+    let pool = &state.0;
+    get_muted_categories(pool).await.map_err(ApiError::internal)
+}
+
+/// Mute or unmute a whole category of insights and recommendations
+#[tauri::command]
+pub async fn set_insight_category_muted(
+    state: State<'_, DbState>,
+    category: String,
+    muted: bool,
+) -> Result<Vec<String>, ApiError> {
+    let pool = &state.0;
+    if !preferences::MUTABLE_CATEGORIES.contains(&category.as_str()) {
+        return Err(ApiError::validation(format!("Unknown category: {}", category)));
+    }
+    preferences::set_category_muted(pool, &category, muted)
         .await
         .map_err(ApiError::internal)
 }
@@ -490,4 +882,39 @@ mod tests {
             err.message
         );
     }
+
+    #[tokio::test]
+    async fn get_insights_does_not_repeat_within_a_day() {
+        let pool = setup_pool_with_migrations().await;
+
+        let first = get_insights_for_pool(&pool).await.unwrap();
+        let second = get_insights_for_pool(&pool).await.unwrap();
+
+        let first_messages: Vec<&str> = first.iter().map(|i| i.message.as_str()).collect();
+        for insight in second.iter().filter(|i| i.category != "general") {
+            assert!(
+                !first_messages.contains(&insight.message.as_str()),
+                "repeated insight: {}",
+                insight.message
+            );
+        }
+    }
+
+    #[tokio::test]
+    async fn get_insights_respects_muted_categories_and_dismissals() {
+        let pool = setup_pool_with_migrations().await;
+        preferences::set_category_muted(&pool, "wellness", true).await.unwrap();
+        sqlx::query("INSERT INTO agent_insight_dismissals (insight_key) VALUES ('arm:warn_overdue')")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let insights = get_insights_for_pool(&pool).await.unwrap();
+        assert!(insights.iter().all(|i| i.category != "wellness"));
+        assert!(insights
+            .iter()
+            .all(|i| i.insight_key.as_deref() != Some("arm:warn_overdue")));
+
+        assert!(preferences::set_category_muted(&pool, "unknown", true).await.is_err());
+    }
 }
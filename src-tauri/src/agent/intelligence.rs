@@ -13,10 +13,14 @@
 use serde::{Deserialize, Serialize};
 use sqlx::{Pool, Sqlite};
 
+use super::preferences::is_shadow_mode;
+use super::scheduler::{self, MaintenanceRun};
 use crate::ml::bandit_v2::{ActionSelection, BanditAction, HybridBandit};
 use crate::ml::models::RewardEngine;
+use crate::ml::readiness;
 use crate::ml::rich_features::{RichContext, RichFeatureStore};
 use crate::ml::semantic_memory::SemanticMemory;
+use crate::services::{clock, energy};
 
 /// Recommendation from the intelligence agent
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -41,6 +45,9 @@ pub struct AgentRecommendation {
     pub confidence_level: String,
     /// Recommendation ID (for feedback tracking)
     pub recommendation_id: Option<i64>,
+    /// Logged in shadow mode; must not be shown
+    #[serde(default)]
+    pub is_shadow: bool,
 }
 
 /// Feature contribution for explainability
@@ -99,14 +106,35 @@ impl IntelligenceAgent {
         pool: &Pool<Sqlite>,
         n: usize,
     ) -> Result<Vec<AgentRecommendation>, String> {
+        let recommendations = Self::get_recommendations_excluding(pool, n, &[]).await?;
+        if recommendations.is_empty() {
+            return Err("No actions available".to_string());
+        }
+        Ok(recommendations)
+    }
+
+    /// Get top N recommendations, skipping the named actions
+    ///
+    /// Returns an empty list (rather than an error) when nothing is eligible.
+    pub async fn get_recommendations_excluding(
+        pool: &Pool<Sqlite>,
+        n: usize,
+        exclude: &[String],
+    ) -> Result<Vec<AgentRecommendation>, String> {
+        let is_shadow = is_shadow_mode(pool).await?;
+
         // Capture current rich context
         let context = RichFeatureStore::capture_context(pool).await?;
 
         // Save context snapshot
         let _ = RichFeatureStore::save_snapshot(pool, &context).await;
 
-        // Get similar past experiences from semantic memory
-        let similar_experiences = Self::get_similar_experiences(&context).await.unwrap_or_default();
+        // Get similar past experiences from semantic memory (skipped while it loads)
+        let similar_experiences = if readiness::semantic_memory_usable() {
+            Self::get_similar_experiences(&context).await.unwrap_or_default()
+        } else {
+            Vec::new()
+        };
 
         // Update context with memory-based features
         let mut enriched_context = context.clone();
@@ -119,13 +147,22 @@ impl IntelligenceAgent {
             enriched_context.similar_context_outcome = avg_outcome;
         }
 
-        // Get action selections from bandit
-        let selections = HybridBandit::select_top_actions(pool, &enriched_context, n + 2, None).await?;
-
-        if selections.is_empty() {
-            return Err("No actions available".to_string());
+        // Hold back demanding actions once today's energy is nearly spent
+        // (best-effort: without a budget nothing extra is excluded)
+        let mut exclude = exclude.to_vec();
+        let low_energy = energy::energy_budget(pool, clock::now().date_naive())
+            .await
+            .is_ok_and(|budget| budget.is_low());
+        if low_energy {
+            exclude.extend(energy::DEMANDING_ACTIONS.iter().map(|a| a.to_string()));
         }
 
+        // Get action selections from bandit (already diversified across categories,
+        // so request exactly n rather than trimming a larger set)
+        let selections =
+            HybridBandit::select_top_actions_excluding(pool, &enriched_context, n, None, &exclude)
+                .await?;
+
         // Build recommendations
         let mut recommendations = Vec::new();
 
@@ -165,6 +202,7 @@ impl IntelligenceAgent {
                 selection.ucb_score,
                 context_id,
                 &explanation,
+                is_shadow,
             )
             .await
             .ok();
@@ -184,6 +222,7 @@ impl IntelligenceAgent {
                 alternatives: vec![], // Filled below
                 confidence_level,
                 recommendation_id: rec_id,
+                is_shadow,
             });
         }
 
@@ -281,6 +320,27 @@ impl IntelligenceAgent {
                         "Let's build some momentum"
                     }
                 }
+                "days_to_next_exam" => {
+                    if context.days_to_next_exam < 0.25 {
+                        "You have an exam coming up soon"
+                    } else {
+                        "No exams are imminent"
+                    }
+                }
+                "deadline_pressure" => {
+                    if context.deadline_pressure > 0.5 {
+                        "Several deadlines are stacking up"
+                    } else {
+                        "Deadline pressure is low"
+                    }
+                }
+                "cycle_phase" => {
+                    if context.cycle_phase < 0.4 {
+                        "Your cycle phase usually means lower energy"
+                    } else {
+                        "Your cycle phase usually means good energy"
+                    }
+                }
                 _ => "Based on your current context",
             };
             parts.push(feature_reason.to_string());
@@ -354,12 +414,13 @@ impl IntelligenceAgent {
         ucb_score: f32,
         context_id: Option<i64>,
         explanation: &str,
+        is_shadow: bool,
     ) -> Result<i64, String> {
         let result = sqlx::query(
             r#"
             INSERT INTO agent_recommendations 
-            (action_recommended, confidence, uncertainty, ucb_score, context_id, explanation_json)
-            VALUES (?, ?, ?, ?, ?, ?)
+            (action_recommended, confidence, uncertainty, ucb_score, context_id, explanation_json, is_shadow)
+            VALUES (?, ?, ?, ?, ?, ?, ?)
             "#,
         )
         .bind(action)
@@ -368,6 +429,7 @@ impl IntelligenceAgent {
         .bind(ucb_score)
         .bind(context_id)
         .bind(serde_json::json!({"text": explanation}).to_string())
+        .bind(is_shadow)
         .execute(pool)
         .await
         .map_err(|e| e.to_string())?;
@@ -389,9 +451,7 @@ impl IntelligenceAgent {
             r#"
             SELECT r.action_recommended, c.context_features 
             FROM agent_recommendations r
-            LEFT JOIN (
-                SELECT id, NULL as context_features FROM agent_rich_context
-            ) c ON r.context_id = c.id
+            LEFT JOIN agent_rich_context c ON r.context_id = c.id
             WHERE r.id = ?
             "#,
         )
@@ -404,7 +464,8 @@ impl IntelligenceAgent {
         sqlx::query(
             r#"
             UPDATE agent_recommendations 
-            SET was_accepted = ?, alternative_chosen = ?, feedback_score = ?, outcome_score = ?
+            SET was_accepted = ?, alternative_chosen = ?, feedback_score = ?, outcome_score = ?,
+                feedback_type = ?
             WHERE id = ?
             "#,
         )
@@ -412,15 +473,19 @@ impl IntelligenceAgent {
         .bind(alternative_chosen)
         .bind(feedback_score)
         .bind(outcome_score)
+        .bind(if accepted { "accepted" } else { "rejected" })
         .bind(recommendation_id)
         .execute(pool)
         .await
         .map_err(|e| e.to_string())?;
 
         // If we have outcome, update the bandit
-        if let (Some((action_name, _context_bytes)), Some(outcome)) = (rec, outcome_score) {
-            // Get current context (or reconstruct from bytes)
-            let context = RichFeatureStore::capture_context(pool).await.unwrap_or_default();
+        if let (Some((action_name, context_bytes)), Some(outcome)) = (rec, outcome_score) {
+            // Use the context the recommendation was made in, falling back to the current one
+            let context = match context_bytes.as_deref().and_then(RichContext::from_bytes) {
+                Some(ctx) => ctx,
+                None => RichFeatureStore::capture_context(pool).await.unwrap_or_default(),
+            };
 
             // Update bandit with observed reward
             HybridBandit::update(pool, &action_name, &context, outcome as f64).await?;
@@ -439,6 +504,34 @@ impl IntelligenceAgent {
         Ok(())
     }
 
+    /// Snooze a recommendation ("not now")
+    ///
+    /// The action is hidden from recommendations for `hours` without being
+    /// treated as a rejection, so no reward is recorded. Returns the time the
+    /// snooze expires.
+    pub async fn snooze_recommendation(
+        pool: &Pool<Sqlite>,
+        recommendation_id: i64,
+        hours: f32,
+    ) -> Result<String, String> {
+        let action_name: String = sqlx::query_scalar(
+            "SELECT action_recommended FROM agent_recommendations WHERE id = ?",
+        )
+        .bind(recommendation_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(|e| e.to_string())?
+        .ok_or_else(|| format!("Recommendation {} not found", recommendation_id))?;
+
+        sqlx::query("UPDATE agent_recommendations SET feedback_type = 'snoozed' WHERE id = ?")
+            .bind(recommendation_id)
+            .execute(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+
+        HybridBandit::snooze_action(pool, &action_name, hours, Some(recommendation_id)).await
+    }
+
     /// Record that user completed an action (for memory and learning)
     pub async fn record_action_completed(
         pool: &Pool<Sqlite>,
@@ -447,8 +540,12 @@ impl IntelligenceAgent {
         outcome_score: f32,
         metadata: Option<serde_json::Value>,
     ) -> Result<i64, String> {
-        // Add to semantic memory
-        let memory = SemanticMemory::global().await?;
+        // Add to semantic memory once it has loaded; the SQL row is kept either way
+        let memory = if readiness::semantic_memory_usable() {
+            SemanticMemory::global().await.ok()
+        } else {
+            None
+        };
         let context = RichFeatureStore::capture_context(pool).await?;
 
         let content = format!("{}: {}", action_type, description);
@@ -471,16 +568,18 @@ impl IntelligenceAgent {
         .last_insert_rowid();
 
         // Add to vector store
-        let _ = memory
-            .add_event(
-                event_id,
-                &chrono::Local::now().to_rfc3339(),
-                action_type,
-                &content,
-                metadata_json.as_deref(),
-                Some(outcome_score),
-            )
-            .await;
+        if let Some(memory) = memory {
+            let _ = memory
+                .add_event(
+                    event_id,
+                    &chrono::Local::now().to_rfc3339(),
+                    action_type,
+                    &content,
+                    metadata_json.as_deref(),
+                    Some(outcome_score),
+                )
+                .await;
+        }
 
         // Update bandit with the action and outcome
         if let Some(action_name) = Self::map_event_to_action(action_type) {
@@ -497,6 +596,7 @@ impl IntelligenceAgent {
             "workout" => Some("do_workout"),
             "checkin" => Some("do_checkin"),
             "skill_practice" => Some("practice_skill"),
+            "meditation" => Some("meditation"),
             "assignment_completed" => Some("tackle_assignment"),
             "break" => Some("take_break"),
             "weekly_review" => Some("weekly_review"),
@@ -510,9 +610,13 @@ impl IntelligenceAgent {
         let total_samples = HybridBandit::total_samples(pool).await?;
         let ready = HybridBandit::ready_for_neural(pool).await?;
 
-        let memory_events = match SemanticMemory::global().await {
-            Ok(mem) => mem.count_events().await.unwrap_or(0),
-            Err(_) => 0,
+        let memory_events = if readiness::semantic_memory_usable() {
+            match SemanticMemory::global().await {
+                Ok(mem) => mem.count_events().await.unwrap_or(0),
+                Err(_) => 0,
+            }
+        } else {
+            0
         };
 
         // Compute average accuracy from recent recommendations
@@ -554,26 +658,11 @@ impl IntelligenceAgent {
         })
     }
 
-    /// Perform daily maintenance (update rewards, train if needed)
-    pub async fn daily_maintenance(pool: &Pool<Sqlite>) -> Result<(), String> {
-        // Update daily rewards from yesterday
-        RewardEngine::update_daily_rewards(pool).await?;
-
-        // Finalize any complete reward records
-        RewardEngine::finalize_rewards(pool).await?;
-
-        // Check if we should switch to neural mode
-        if HybridBandit::ready_for_neural(pool).await? {
-            let current_mode = HybridBandit::get_mode(pool).await?;
-            if current_mode == "linear" {
-                // Log that we're ready for neural upgrade
-                // (actual training would be done offline)
-                log::info!("Agent ready for neural upgrade with {} samples", 
-                    HybridBandit::total_samples(pool).await?);
-            }
-        }
-
-        Ok(())
+    /// Perform daily maintenance (rewards, patterns, profile, memory, readiness)
+    ///
+    /// See `scheduler::run_maintenance` for the individual steps.
+    pub async fn daily_maintenance(pool: &Pool<Sqlite>) -> Result<MaintenanceRun, String> {
+        scheduler::run_maintenance(pool, "manual").await
     }
 
     /// Get Big 3 goals for today
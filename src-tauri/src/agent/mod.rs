@@ -5,11 +5,25 @@
 //!
 //! ## Components
 //!
-//! - **insights**: Legacy insight generation (rule-based + simple bandit)
+//! - **facade**: Single entry point routing insights through the v2 pipeline
+//! - **insights**: Insight commands and the deprecated legacy pipeline (fallback only)
 //! - **intelligence**: New Maximum Intelligence Agent with full ML pipeline
+//! - **implicit_feedback**: Infers recommendation outcomes from later behavior
+//! - **outcomes**: Links finished sessions and workouts to the agent
+//! - **scheduler**: Background daily maintenance with a run log
+//! - **preferences**: User-muted suggestion categories and shadow mode
+//! - **shadow**: Report on recommendations logged in shadow mode
+//! - **simulation**: Synthetic history for development and demos
 
+pub mod facade;
+pub mod implicit_feedback;
 pub mod insights;
 pub mod intelligence;
+pub mod outcomes;
+pub mod preferences;
+pub mod scheduler;
+pub mod shadow;
+pub mod simulation;
 
 // Re-export the main intelligence agent
 pub use intelligence::{
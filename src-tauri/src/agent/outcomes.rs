@@ -0,0 +1,169 @@
+//! Outcome Linking
+//!
+//! Computes outcome scores for finished study sessions, workouts and
+//! meditation sits and feeds them to the agent, so reward attribution doesn't
+//! depend on the frontend remembering to call `record_action_completed`.
+
+use sqlx::{Pool, Sqlite};
+
+use super::intelligence::IntelligenceAgent;
+use crate::models::session::{Session, SessionType};
+use crate::models::workout::Workout;
+use crate::services::pomodoro;
+use crate::services::trackers::TrackerLog;
+
+/// Expected session length when no plan was given (one Pomodoro)
+const DEFAULT_SESSION_MINUTES: f32 = 25.0;
+
+/// Workout length considered a full outcome
+const TARGET_WORKOUT_MINUTES: f32 = 30.0;
+
+/// Weight of the focus rating when one was given
+const FOCUS_WEIGHT: f32 = 0.4;
+
+/// Outcome score (0-1) for a session
+///
+/// Completion is duration vs. planned minutes (capped at 1); a 1-5 focus
+/// rating, when given, is blended in.
+pub fn session_outcome(
+    duration_minutes: Option<i64>,
+    planned_minutes: Option<i64>,
+    focus_rating: Option<i64>,
+) -> f32 {
+    let planned = planned_minutes
+        .filter(|p| *p > 0)
+        .map(|p| p as f32)
+        .unwrap_or(DEFAULT_SESSION_MINUTES);
+    let completion = (duration_minutes.unwrap_or(0).max(0) as f32 / planned).min(1.0);
+
+    match focus_rating {
+        Some(rating) => {
+            let focus = ((rating.clamp(1, 5) - 1) as f32) / 4.0;
+            (1.0 - FOCUS_WEIGHT) * completion + FOCUS_WEIGHT * focus
+        }
+        None => completion,
+    }
+}
+
+/// Outcome score (0-1) for a logged workout
+pub fn workout_outcome(duration_minutes: Option<i64>) -> f32 {
+    match duration_minutes {
+        Some(minutes) if minutes > 0 => (minutes as f32 / TARGET_WORKOUT_MINUTES).min(1.0),
+        // Logged without a duration: it happened, but we can't tell how well
+        _ => 0.5,
+    }
+}
+
+/// Record a finished session with the agent in the background; each
+/// interruption logged during it lowers the outcome
+pub fn link_session_outcome(pool: &Pool<Sqlite>, session: &Session, interruptions: i64) {
+    let outcome = session_outcome(
+        session.duration_minutes,
+        session.planned_minutes,
+        session.focus_rating,
+    ) * pomodoro::interruption_factor(interruptions) as f32;
+    let event_type = match session.session_type {
+        SessionType::Study => "study_session",
+        SessionType::Practice => "skill_practice",
+        SessionType::Custom(_) => "custom_session",
+    };
+    let description = format!("{} minute session", session.duration_minutes.unwrap_or(0));
+    let metadata = serde_json::json!({
+        "session_id": session.id,
+        "session_type": session.session_type.as_str(),
+        "duration_minutes": session.duration_minutes,
+        "planned_minutes": session.planned_minutes,
+        "focus_rating": session.focus_rating,
+        "interruptions": interruptions,
+        "reference_type": session.reference_type,
+        "reference_id": session.reference_id,
+    });
+
+    spawn_record(pool.clone(), event_type, description, outcome, metadata);
+}
+
+/// Record a logged workout with the agent in the background
+pub fn link_workout_outcome(pool: &Pool<Sqlite>, workout: &Workout) {
+    let outcome = workout_outcome(workout.duration_minutes);
+    let description = match &workout.name {
+        Some(name) => format!("{} ({} min)", name, workout.duration_minutes.unwrap_or(0)),
+        None => format!("{} minute workout", workout.duration_minutes.unwrap_or(0)),
+    };
+    let metadata = serde_json::json!({
+        "workout_id": workout.id,
+        "duration_minutes": workout.duration_minutes,
+    });
+
+    spawn_record(pool.clone(), "workout", description, outcome, metadata);
+}
+
+/// Record a meditation sit with the agent in the background; the calm rating
+/// plays the part of the focus rating
+pub fn link_meditation_outcome(pool: &Pool<Sqlite>, log: &TrackerLog, planned_minutes: Option<i64>) {
+    let outcome = session_outcome(log.minutes, planned_minutes, log.rating);
+    let description = format!(
+        "{} minute {} meditation",
+        log.minutes.unwrap_or(0),
+        log.kind.as_deref().unwrap_or("other").replace('_', " ")
+    );
+    let metadata = serde_json::json!({
+        "tracker_log_id": log.id,
+        "style": log.kind,
+        "duration_minutes": log.minutes,
+        "planned_minutes": planned_minutes,
+        "calm_rating": log.rating,
+    });
+
+    spawn_record(pool.clone(), "meditation", description, outcome, metadata);
+}
+
+/// Outcome recording touches semantic memory (embeddings), so it runs off the
+/// command path and failures are only logged
+fn spawn_record(
+    pool: Pool<Sqlite>,
+    event_type: &'static str,
+    description: String,
+    outcome: f32,
+    metadata: serde_json::Value,
+) {
+    tauri::async_runtime::spawn(async move {
+        if let Err(e) = IntelligenceAgent::record_action_completed(
+            &pool,
+            event_type,
+            &description,
+            outcome,
+            Some(metadata),
+        )
+        .await
+        {
+            log::warn!("Failed to record {} outcome: {}", event_type, e);
+        }
+    });
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_session_outcome() {
+        // Full planned session, no rating
+        assert_eq!(session_outcome(Some(50), Some(50), None), 1.0);
+        // Half of the plan
+        assert!((session_outcome(Some(25), Some(50), None) - 0.5).abs() < 1e-6);
+        // Overrunning doesn't exceed 1
+        assert_eq!(session_outcome(Some(90), Some(50), None), 1.0);
+        // Falls back to a Pomodoro when no plan
+        assert_eq!(session_outcome(Some(25), None, None), 1.0);
+        // Poor focus drags a complete session down
+        assert!((session_outcome(Some(50), Some(50), Some(1)) - 0.6).abs() < 1e-6);
+        assert_eq!(session_outcome(Some(50), Some(50), Some(5)), 1.0);
+    }
+
+    #[test]
+    fn test_workout_outcome() {
+        assert_eq!(workout_outcome(Some(45)), 1.0);
+        assert!((workout_outcome(Some(15)) - 0.5).abs() < 1e-6);
+        assert_eq!(workout_outcome(None), 0.5);
+    }
+}
@@ -0,0 +1,73 @@
+//! Agent Preferences
+//!
+//! User-controlled limits on what the agent may suggest. Muted categories are
+//! stored in the `muted_categories` setting (JSON array) and respected by
+//! both the legacy insights and the intelligence recommendations.
+//!
+//! Shadow mode (the `agent_shadow_mode` setting) keeps the agent selecting
+//! and logging recommendations while nothing is surfaced.
+
+use sqlx::{Pool, Sqlite};
+
+use crate::services::settings;
+
+/// Categories used by insight arms and bandit actions
+pub const MUTABLE_CATEGORIES: &[&str] = &[
+    "academic",
+    "productivity",
+    "physical",
+    "wellness",
+    "skills",
+    "reflection",
+    "motivation",
+    "general",
+    "temporal",
+    "correlation",
+];
+
+/// Get the list of muted categories
+pub async fn get_muted_categories(pool: &Pool<Sqlite>) -> Result<Vec<String>, String> {
+    settings::get_string_list(pool, "muted_categories")
+        .await
+        .map_err(|e| e.message)
+}
+
+/// Mute or unmute a category; returns the updated list
+pub async fn set_category_muted(
+    pool: &Pool<Sqlite>,
+    category: &str,
+    muted: bool,
+) -> Result<Vec<String>, String> {
+    if !MUTABLE_CATEGORIES.contains(&category) {
+        return Err(format!("Unknown category: {}", category));
+    }
+
+    let mut categories = get_muted_categories(pool).await?;
+    categories.retain(|c| c != category);
+    if muted {
+        categories.push(category.to_string());
+        categories.sort();
+    }
+
+    settings::set(pool, "muted_categories", serde_json::json!(categories))
+        .await
+        .map_err(|e| e.message)?;
+
+    Ok(categories)
+}
+
+/// Whether the agent runs in shadow mode
+pub async fn is_shadow_mode(pool: &Pool<Sqlite>) -> Result<bool, String> {
+    settings::get_bool(pool, "agent_shadow_mode")
+        .await
+        .map_err(|e| e.message)
+}
+
+/// Turn shadow mode on or off
+pub async fn set_shadow_mode(pool: &Pool<Sqlite>, enabled: bool) -> Result<(), String> {
+    settings::set(pool, "agent_shadow_mode", serde_json::Value::Bool(enabled))
+        .await
+        .map_err(|e| e.message)?;
+
+    Ok(())
+}
@@ -0,0 +1,464 @@
+//! Maintenance Scheduler
+//!
+//! Runs the agent's daily maintenance in the background at a configurable
+//! quiet hour and records every run in `agent_maintenance_log`.
+//!
+//! Each step runs independently: a failing step is recorded and the remaining
+//! steps still run.
+
+use std::time::Duration;
+
+use chrono::{DateTime, Local, Timelike};
+use serde::{Deserialize, Serialize};
+use sqlx::{Pool, Sqlite};
+
+use super::implicit_feedback::process_implicit_feedback;
+use crate::ml::bandit_v2::HybridBandit;
+use crate::ml::models::RewardEngine;
+use crate::ml::pattern_miner::PatternMiner;
+use crate::ml::readiness;
+use crate::ml::user_profile::UserProfile;
+use crate::services::{accountability, achievements, calendar_feed, clock, missed_blocks, retention};
+
+/// Default local hour for scheduled maintenance
+pub const DEFAULT_MAINTENANCE_HOUR: u32 = 3;
+
+/// How often the background loop checks whether maintenance is due
+const CHECK_INTERVAL: Duration = Duration::from_secs(15 * 60);
+
+/// Result of one maintenance step
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MaintenanceStepResult {
+    pub step: String,
+    pub ok: bool,
+    pub detail: String,
+}
+
+/// A recorded maintenance run
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MaintenanceRun {
+    pub id: i64,
+    /// 'scheduled' or 'manual'
+    pub trigger: String,
+    pub started_at: String,
+    pub finished_at: Option<String>,
+    /// 'ok', 'partial' or 'failed'
+    pub status: String,
+    pub steps: Vec<MaintenanceStepResult>,
+}
+
+/// Get the configured maintenance hour (local time)
+pub async fn get_maintenance_hour(pool: &Pool<Sqlite>) -> Result<u32, String> {
+    let value: Option<String> = sqlx::query_scalar(
+        "SELECT value_json FROM agent_state WHERE key = 'maintenance_hour'",
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(value
+        .and_then(|v| v.parse::<u32>().ok())
+        .filter(|h| *h < 24)
+        .unwrap_or(DEFAULT_MAINTENANCE_HOUR))
+}
+
+/// Set the maintenance hour (local time, 0-23)
+pub async fn set_maintenance_hour(pool: &Pool<Sqlite>, hour: u32) -> Result<(), String> {
+    if hour > 23 {
+        return Err("Maintenance hour must be between 0 and 23".into());
+    }
+
+    sqlx::query(
+        r#"
+        INSERT INTO agent_state (key, value_json, updated_at)
+        VALUES ('maintenance_hour', ?, datetime('now'))
+        ON CONFLICT(key) DO UPDATE SET value_json = excluded.value_json, updated_at = excluded.updated_at
+        "#,
+    )
+    .bind(hour.to_string())
+    .execute(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(())
+}
+
+/// Run every maintenance step and record the run
+pub async fn run_maintenance(pool: &Pool<Sqlite>, trigger: &str) -> Result<MaintenanceRun, String> {
+    let started_at = clock::now().with_timezone(&chrono::Utc).format("%Y-%m-%d %H:%M:%S").to_string();
+    let id = sqlx::query(
+        "INSERT INTO agent_maintenance_log (trigger, status, started_at) VALUES (?, 'running', ?)",
+    )
+    .bind(trigger)
+    .bind(&started_at)
+    .execute(pool)
+    .await
+    .map_err(|e| e.to_string())?
+    .last_insert_rowid();
+
+    // Reduced-data mode skips the ML steps; rule-based insights still need
+    // patterns, the profile and achievements
+    let ml = !readiness::is_disabled();
+    let steps = vec![
+        step("reward_finalization", ml_step(ml, finalize_rewards(pool)).await),
+        step(
+            "feature_space_upgrade",
+            ml_step(ml, async {
+                HybridBandit::upgrade_feature_space(pool)
+                    .await
+                    .map(|n| format!("{} actions upgraded", n))
+            })
+            .await,
+        ),
+        step(
+            "implicit_feedback",
+            ml_step(ml, async {
+                process_implicit_feedback(pool).await.map(|s| {
+                    format!(
+                        "{} followed, {} ignored, {} skipped, {} shadow",
+                        s.followed, s.ignored, s.skipped, s.shadow
+                    )
+                })
+            })
+            .await,
+        ),
+        step(
+            "pattern_mining",
+            PatternMiner::discover_and_save_patterns(pool)
+                .await
+                .map(|n| format!("{} patterns", n)),
+        ),
+        step(
+            "profile_learning",
+            UserProfile::learn_all(pool).await.map(|_| "profile updated".to_string()),
+        ),
+        step("memory_consolidation", ml_step(ml, consolidate_memory(pool)).await),
+        step("neural_readiness", ml_step(ml, check_neural_readiness(pool)).await),
+        step(
+            "missed_blocks",
+            missed_blocks::run(pool)
+                .await
+                .map(|r| format!("{} missed, {} rescheduled", r.missed.len(), r.rescheduled.len()))
+                .map_err(|e| e.message),
+        ),
+        step(
+            "achievement_rescan",
+            achievements::evaluate_all(pool)
+                .await
+                .map(|unlocked| format!("{} achievements unlocked", unlocked.len()))
+                .map_err(|e| e.message),
+        ),
+        step(
+            "data_retention",
+            retention::run(pool)
+                .await
+                .map(|r| {
+                    format!(
+                        "{} sessions compressed into {} daily rows, {} context snapshots purged, {} KB reclaimed",
+                        r.sessions_compressed,
+                        r.daily_rows,
+                        r.contexts_purged,
+                        r.bytes_reclaimed / 1024
+                    )
+                })
+                .map_err(|e| e.message),
+        ),
+        step(
+            "calendar_feed",
+            calendar_feed::publish(pool)
+                .await
+                .map(|path| match path {
+                    Some(path) => format!("written to {}", path.display()),
+                    None => "no feed file configured".to_string(),
+                })
+                .map_err(|e| e.message),
+        ),
+        step(
+            "accountability_report",
+            accountability::run(pool)
+                .await
+                .map_err(|e| e.message)
+                .and_then(|delivery| match delivery {
+                    Some(d) if d.status == "failed" => Err(format!(
+                        "{} delivery failed: {}",
+                        d.method,
+                        d.error.unwrap_or_default()
+                    )),
+                    Some(d) => Ok(format!("{} via {}", d.status, d.method)),
+                    None => Ok("not due".to_string()),
+                }),
+        ),
+    ];
+
+    let failed = steps.iter().filter(|s| !s.ok).count();
+    let status = match failed {
+        0 => "ok",
+        n if n == steps.len() => "failed",
+        _ => "partial",
+    };
+    let steps_json = serde_json::to_string(&steps).map_err(|e| e.to_string())?;
+
+    let (started_at, finished_at): (String, Option<String>) = sqlx::query_as(
+        r#"
+        UPDATE agent_maintenance_log
+        SET status = ?, steps_json = ?, finished_at = datetime('now')
+        WHERE id = ?
+        RETURNING started_at, finished_at
+        "#,
+    )
+    .bind(status)
+    .bind(&steps_json)
+    .bind(id)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    log::info!("Agent maintenance ({}) finished: {}", trigger, status);
+
+    Ok(MaintenanceRun {
+        id,
+        trigger: trigger.to_string(),
+        started_at,
+        finished_at,
+        status: status.to_string(),
+        steps,
+    })
+}
+
+/// Get recent maintenance runs, newest first
+pub async fn get_maintenance_log(
+    pool: &Pool<Sqlite>,
+    limit: i64,
+) -> Result<Vec<MaintenanceRun>, String> {
+    let rows: Vec<(i64, String, String, Option<String>, String, Option<String>)> = sqlx::query_as(
+        r#"
+        SELECT id, trigger, started_at, finished_at, status, steps_json
+        FROM agent_maintenance_log
+        ORDER BY started_at DESC, id DESC
+        LIMIT ?
+        "#,
+    )
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(rows
+        .into_iter()
+        .map(|(id, trigger, started_at, finished_at, status, steps_json)| MaintenanceRun {
+            id,
+            trigger,
+            started_at,
+            finished_at,
+            status,
+            steps: steps_json
+                .and_then(|j| serde_json::from_str(&j).ok())
+                .unwrap_or_default(),
+        })
+        .collect())
+}
+
+/// Start the background scheduler
+///
+/// Maintenance runs once per local day, at the first check after the
+/// configured hour (so a day missed while the app was closed is caught up
+/// the next time it is open past that hour).
+pub fn start(pool: Pool<Sqlite>) {
+    tauri::async_runtime::spawn(async move {
+        loop {
+            match maintenance_due(&pool).await {
+                Ok(true) => {
+                    if let Err(e) = run_maintenance(&pool, "scheduled").await {
+                        log::warn!("Scheduled agent maintenance failed: {}", e);
+                    }
+                }
+                Ok(false) => {}
+                Err(e) => log::warn!("Failed to check maintenance schedule: {}", e),
+            }
+            tokio::time::sleep(CHECK_INTERVAL).await;
+        }
+    });
+}
+
+/// Whether a scheduled run is due now
+async fn maintenance_due(pool: &Pool<Sqlite>) -> Result<bool, String> {
+    maintenance_due_at(pool, clock::now()).await
+}
+
+/// Whether a scheduled run is due at local time `now`
+async fn maintenance_due_at(pool: &Pool<Sqlite>, now: DateTime<Local>) -> Result<bool, String> {
+    let hour = get_maintenance_hour(pool).await?;
+    if now.hour() < hour {
+        return Ok(false);
+    }
+
+    let ran_today: i64 = sqlx::query_scalar(
+        r#"
+        SELECT COUNT(*) FROM agent_maintenance_log
+        WHERE trigger = 'scheduled'
+          AND date(started_at, 'localtime') = ?
+        "#,
+    )
+    .bind(now.format("%Y-%m-%d").to_string())
+    .fetch_one(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    Ok(ran_today == 0)
+}
+
+/// Run an ML step unless reduced-data mode is on
+async fn ml_step(
+    enabled: bool,
+    run: impl std::future::Future<Output = Result<String, String>>,
+) -> Result<String, String> {
+    if enabled {
+        run.await
+    } else {
+        Ok("skipped (reduced-data mode)".to_string())
+    }
+}
+
+fn step(name: &str, result: Result<String, String>) -> MaintenanceStepResult {
+    match result {
+        Ok(detail) => MaintenanceStepResult {
+            step: name.to_string(),
+            ok: true,
+            detail,
+        },
+        Err(e) => {
+            log::warn!("Maintenance step {} failed: {}", name, e);
+            MaintenanceStepResult {
+                step: name.to_string(),
+                ok: false,
+                detail: e,
+            }
+        }
+    }
+}
+
+async fn finalize_rewards(pool: &Pool<Sqlite>) -> Result<String, String> {
+    let daily = RewardEngine::update_daily_rewards(pool).await?;
+    let finalized = RewardEngine::finalize_rewards(pool).await?;
+    Ok(format!("{} daily rewards, {} finalized", daily, finalized))
+}
+
+/// Back-fill daily outcomes on memory events and prune stale bookkeeping
+async fn consolidate_memory(pool: &Pool<Sqlite>) -> Result<String, String> {
+    let outcomes = sqlx::query(
+        r#"
+        UPDATE agent_memory_events
+        SET outcome_daily = (
+            SELECT AVG(r.reward_daily) FROM agent_reward_log r
+            WHERE date(r.timestamp) = date(agent_memory_events.timestamp)
+              AND r.reward_daily IS NOT NULL
+        )
+        WHERE outcome_daily IS NULL
+          AND date(timestamp) < date('now')
+        "#,
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| e.to_string())?
+    .rows_affected();
+
+    let snoozes = sqlx::query(
+        "DELETE FROM agent_action_snoozes WHERE snoozed_until < datetime('now', '-1 day')",
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| e.to_string())?
+    .rows_affected();
+
+    let shown = sqlx::query(
+        "DELETE FROM agent_shown_insights WHERE shown_at < datetime('now', '-30 days')",
+    )
+    .execute(pool)
+    .await
+    .map_err(|e| e.to_string())?
+    .rows_affected();
+
+    Ok(format!(
+        "{} events back-filled, {} snoozes and {} insight log entries cleared",
+        outcomes, snoozes, shown
+    ))
+}
+
+async fn check_neural_readiness(pool: &Pool<Sqlite>) -> Result<String, String> {
+    let samples = HybridBandit::total_samples(pool).await?;
+    if HybridBandit::ready_for_neural(pool).await?
+        && HybridBandit::get_mode(pool).await? == "linear"
+    {
+        // Actual training is done offline
+        log::info!("Agent ready for neural upgrade with {} samples", samples);
+        return Ok(format!("ready ({} samples)", samples));
+    }
+    Ok(format!("not ready ({} samples)", samples))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+    use std::str::FromStr;
+
+    async fn setup_pool_with_migrations() -> Pool<Sqlite> {
+        let options = SqliteConnectOptions::from_str("sqlite::memory:")
+            .unwrap()
+            .foreign_keys(false);
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect_with(options)
+            .await
+            .expect("Failed to connect to in-memory DB");
+
+        crate::db::migrations::run_migrations(&pool)
+            .await
+            .expect("Failed to run migrations");
+
+        pool
+    }
+
+    #[tokio::test]
+    async fn maintenance_run_is_logged() {
+        let pool = setup_pool_with_migrations().await;
+
+        let run = run_maintenance(&pool, "manual").await.unwrap();
+        assert_eq!(run.steps.len(), 12);
+        assert_eq!(run.status, "ok", "steps: {:?}", run.steps);
+
+        let log = get_maintenance_log(&pool, 10).await.unwrap();
+        assert_eq!(log.len(), 1);
+        assert_eq!(log[0].trigger, "manual");
+        assert_eq!(log[0].steps.len(), 12);
+    }
+
+    #[tokio::test]
+    async fn maintenance_hour_is_validated() {
+        let pool = setup_pool_with_migrations().await;
+
+        assert_eq!(get_maintenance_hour(&pool).await.unwrap(), DEFAULT_MAINTENANCE_HOUR);
+        set_maintenance_hour(&pool, 22).await.unwrap();
+        assert_eq!(get_maintenance_hour(&pool).await.unwrap(), 22);
+        assert!(set_maintenance_hour(&pool, 24).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn scheduled_run_is_due_once_per_day_after_the_hour() {
+        use chrono::TimeZone;
+
+        let pool = setup_pool_with_migrations().await;
+        let day = |d: u32, h: u32, m: u32| Local.with_ymd_and_hms(2026, 3, d, h, m, 0).unwrap();
+
+        assert!(!maintenance_due_at(&pool, day(2, 2, 59)).await.unwrap());
+        assert!(maintenance_due_at(&pool, day(2, 3, 5)).await.unwrap());
+
+        {
+            let _clock = clock::freeze(day(2, 3, 5));
+            assert!(maintenance_due(&pool).await.unwrap());
+            run_maintenance(&pool, "scheduled").await.unwrap();
+        }
+        assert!(!maintenance_due_at(&pool, day(2, 23, 55)).await.unwrap());
+        assert!(maintenance_due_at(&pool, day(3, 3, 0)).await.unwrap());
+    }
+}
@@ -0,0 +1,140 @@
+//! Shadow Mode Report
+//!
+//! Summarizes what the agent would have recommended while in shadow mode and
+//! how often the user did that anyway, to judge whether its suggestions are
+//! worth turning on.
+
+use serde::Serialize;
+use sqlx::{Pool, Sqlite};
+
+/// Shadow statistics for one action
+#[derive(Debug, Clone, Serialize)]
+pub struct ShadowActionStats {
+    pub action_name: String,
+    pub recommended: i64,
+    /// Recommendations whose outcome has been inferred
+    pub evaluated: i64,
+    pub followed: i64,
+}
+
+/// Shadow-mode summary over a period
+#[derive(Debug, Clone, Serialize)]
+pub struct ShadowReport {
+    pub days: i64,
+    pub recommended: i64,
+    pub evaluated: i64,
+    pub followed: i64,
+    /// Share of evaluated recommendations the user followed anyway (0-1)
+    pub follow_rate: Option<f64>,
+    pub by_action: Vec<ShadowActionStats>,
+}
+
+/// Build the shadow report for the last `days` days
+pub async fn get_report(pool: &Pool<Sqlite>, days: i64) -> Result<ShadowReport, String> {
+    let rows: Vec<(String, i64, i64, i64)> = sqlx::query_as(
+        r#"
+        SELECT action_recommended,
+               COUNT(*),
+               SUM(CASE WHEN feedback_type = 'shadow' THEN 1 ELSE 0 END),
+               SUM(CASE WHEN feedback_type = 'shadow' AND was_accepted = 1 THEN 1 ELSE 0 END)
+        FROM agent_recommendations
+        WHERE is_shadow = 1
+          AND timestamp >= datetime('now', '-' || ? || ' days')
+        GROUP BY action_recommended
+        ORDER BY COUNT(*) DESC, action_recommended
+        "#,
+    )
+    .bind(days)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| e.to_string())?;
+
+    let by_action: Vec<ShadowActionStats> = rows
+        .into_iter()
+        .map(|(action_name, recommended, evaluated, followed)| ShadowActionStats {
+            action_name,
+            recommended,
+            evaluated,
+            followed,
+        })
+        .collect();
+
+    let recommended = by_action.iter().map(|a| a.recommended).sum();
+    let evaluated: i64 = by_action.iter().map(|a| a.evaluated).sum();
+    let followed = by_action.iter().map(|a| a.followed).sum();
+
+    Ok(ShadowReport {
+        days,
+        recommended,
+        evaluated,
+        followed,
+        follow_rate: (evaluated > 0).then(|| followed as f64 / evaluated as f64),
+        by_action,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::agent::implicit_feedback::process_implicit_feedback;
+    use crate::ml::rich_features::{RichContext, RichFeatureStore};
+    use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+    use std::str::FromStr;
+
+    async fn setup_pool_with_migrations() -> Pool<Sqlite> {
+        let options = SqliteConnectOptions::from_str("sqlite::memory:")
+            .unwrap()
+            .foreign_keys(false);
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect_with(options)
+            .await
+            .expect("Failed to connect to in-memory DB");
+
+        crate::db::migrations::run_migrations(&pool)
+            .await
+            .expect("Failed to run migrations");
+
+        pool
+    }
+
+    #[tokio::test]
+    async fn shadow_outcomes_are_logged_without_training() {
+        let pool = setup_pool_with_migrations().await;
+        let context_id = RichFeatureStore::save_snapshot(&pool, &RichContext::default())
+            .await
+            .unwrap();
+
+        for action in ["do_workout", "do_checkin"] {
+            sqlx::query(
+                "INSERT INTO agent_recommendations (timestamp, action_recommended, context_id, is_shadow)
+                 VALUES (datetime('now', '-5 hours'), ?, ?, 1)",
+            )
+            .bind(action)
+            .bind(context_id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+        sqlx::query("INSERT INTO workouts (logged_at) VALUES (datetime('now', '-4 hours'))")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let summary = process_implicit_feedback(&pool).await.unwrap();
+        assert_eq!(summary.shadow, 2);
+        assert_eq!(summary.followed + summary.ignored, 0);
+
+        let pulls: i64 = sqlx::query_scalar("SELECT SUM(total_pulls) FROM agent_linear_bandit")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(pulls, 0);
+
+        let report = get_report(&pool, 7).await.unwrap();
+        assert_eq!(report.recommended, 2);
+        assert_eq!(report.evaluated, 2);
+        assert_eq!(report.followed, 1);
+        assert_eq!(report.follow_rate, Some(0.5));
+    }
+}
@@ -0,0 +1,59 @@
+//! Accountability report commands
+
+use chrono::NaiveDate;
+use tauri::State;
+
+use crate::services::accountability::{self, AccountabilityDelivery, AccountabilityReport};
+use crate::services::clock;
+use crate::{error::ApiError, DbState};
+
+const HISTORY_LIMIT: i64 = 20;
+
+fn week_end(date: Option<String>) -> Result<NaiveDate, ApiError> {
+    match date {
+        Some(d) => NaiveDate::parse_from_str(&d, "%Y-%m-%d")
+            .map_err(|_| ApiError::validation("week_end must be a YYYY-MM-DD date")),
+        None => Ok(clock::now().date_naive()),
+    }
+}
+
+/// The report for the seven days ending on `week_end` (default today), in
+/// the configured format
+#[tauri::command]
+pub async fn preview_accountability_report(
+    state: State<'_, DbState>,
+    week_end: Option<String>,
+) -> Result<AccountabilityReport, ApiError> {
+    accountability::report(&state.0, week_end(week_end)?).await
+}
+
+/// Deliver the report now, e.g. to retry a failed delivery; replaces the
+/// record for that week
+#[tauri::command]
+pub async fn send_accountability_report(
+    state: State<'_, DbState>,
+    week_end: Option<String>,
+) -> Result<AccountabilityDelivery, ApiError> {
+    accountability::deliver(&state.0, week_end(week_end)?).await
+}
+
+/// A mailto draft waiting to be opened, if any
+#[tauri::command]
+pub async fn get_due_accountability_draft(
+    state: State<'_, DbState>,
+) -> Result<Option<AccountabilityDelivery>, ApiError> {
+    accountability::due_draft(&state.0).await
+}
+
+/// Record that the draft for `week_end` was opened
+#[tauri::command]
+pub async fn ack_accountability_draft(state: State<'_, DbState>, week_end: String) -> Result<(), ApiError> {
+    accountability::ack_draft(&state.0, &week_end).await
+}
+
+#[tauri::command]
+pub async fn get_accountability_deliveries(
+    state: State<'_, DbState>,
+) -> Result<Vec<AccountabilityDelivery>, ApiError> {
+    accountability::history(&state.0, HISTORY_LIMIT).await
+}
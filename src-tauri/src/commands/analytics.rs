@@ -1,6 +1,12 @@
+use std::collections::HashMap;
+
 use tauri::State;
 
-use crate::{DbState, error::ApiError};
+use crate::services::focus::{self, AttentionReport};
+use crate::services::life_balance::{self, LifeBalance};
+use crate::services::pomodoro::{self, PomodoroStats};
+use crate::services::session_types::{self, SessionTypeStats};
+use crate::{DbState, error::ApiError, services::{achievements, clock, settings}};
 
 #[derive(Debug, serde::Serialize)]
 pub struct StatsSummary {
@@ -10,7 +16,7 @@ pub struct StatsSummary {
     pub active_streaks: i64,
 }
 
-#[derive(Debug, serde::Serialize)]
+#[derive(Debug, Clone, serde::Serialize)]
 pub struct Streaks {
     pub study_streak: i64,
     pub workout_streak: i64,
@@ -71,6 +77,9 @@ pub struct DetailedStats {
     // Active skills stats
     pub active_skills_count: i64,
     pub skills_target: i64,
+
+    // Time across domains against the ideal proportions, last 7 days
+    pub balance: LifeBalance,
 }
 
 #[derive(Debug, serde::Serialize)]
@@ -109,17 +118,20 @@ pub struct PersonalRecord {
 
 #[tauri::command]
 pub async fn get_stats(state: State<'_, DbState>) -> Result<StatsSummary, ApiError> {
-    let pool = &state.0;
+    compute_stats(&state.0).await
+}
 
+/// Last-7-days summary shared by `get_stats` and the local HTTP API
+pub(crate) async fn compute_stats(pool: &sqlx::Pool<sqlx::Sqlite>) -> Result<StatsSummary, ApiError> {
     let study_minutes: i64 = sqlx::query_scalar(
-        "SELECT COALESCE(SUM(duration_minutes), 0) FROM sessions WHERE session_type = 'study' AND started_at >= date('now', '-6 days')"
+        "SELECT COALESCE(SUM(duration_minutes), 0) FROM sessions WHERE session_type IN (SELECT name FROM session_types WHERE counts_toward = 'study') AND started_at >= date('now', '-6 days')"
     )
     .fetch_one(pool)
     .await
     .unwrap_or(0);
 
     let practice_minutes: i64 = sqlx::query_scalar(
-        "SELECT COALESCE(SUM(duration_minutes), 0) FROM sessions WHERE session_type = 'practice' AND started_at >= date('now', '-6 days')"
+        "SELECT COALESCE(SUM(duration_minutes), 0) FROM sessions WHERE session_type IN (SELECT name FROM session_types WHERE counts_toward = 'practice') AND started_at >= date('now', '-6 days')"
     )
     .fetch_one(pool)
     .await
@@ -149,7 +161,11 @@ pub async fn get_stats(state: State<'_, DbState>) -> Result<StatsSummary, ApiErr
 
 #[tauri::command]
 pub async fn get_streaks(state: State<'_, DbState>) -> Result<Streaks, ApiError> {
-    let pool = &state.0;
+    compute_streaks(&state.0).await
+}
+
+/// Uncached streaks; prefer `services::aggregates` on hot paths
+pub(crate) async fn compute_streaks(pool: &sqlx::Pool<sqlx::Sqlite>) -> Result<Streaks, ApiError> {
 
     // Study streak: consecutive days with study sessions
     let study_streak: i64 = sqlx::query_scalar(
@@ -263,24 +279,11 @@ pub async fn get_streaks(state: State<'_, DbState>) -> Result<Streaks, ApiError>
 #[tauri::command]
 pub async fn get_user_settings(state: State<'_, DbState>) -> Result<UserSettings, ApiError> {
     let pool = &state.0;
-    
-    let row = sqlx::query_as::<_, (i64, i64)>(
-        "SELECT weekly_workout_target, weekly_active_skills_target FROM user_settings WHERE user_id = 1"
-    )
-    .fetch_optional(pool)
-    .await
-    .map_err(ApiError::from)?;
-    
-    match row {
-        Some((workout_target, skills_target)) => Ok(UserSettings {
-            weekly_workout_target: workout_target,
-            weekly_active_skills_target: skills_target,
-        }),
-        None => Ok(UserSettings {
-            weekly_workout_target: 3,
-            weekly_active_skills_target: 5,
-        }),
-    }
+
+    Ok(UserSettings {
+        weekly_workout_target: settings::get_i64(pool, "weekly_workout_target").await?,
+        weekly_active_skills_target: settings::get_i64(pool, "weekly_active_skills_target").await?,
+    })
 }
 
 #[tauri::command]
@@ -290,23 +293,13 @@ pub async fn update_user_settings(
     weekly_active_skills_target: i64,
 ) -> Result<UserSettings, ApiError> {
     let pool = &state.0;
-    
-    sqlx::query(
-        r#"
-        INSERT INTO user_settings (id, user_id, weekly_workout_target, weekly_active_skills_target, updated_at)
-        VALUES (1, 1, ?, ?, CURRENT_TIMESTAMP)
-        ON CONFLICT(id) DO UPDATE SET
-            weekly_workout_target = excluded.weekly_workout_target,
-            weekly_active_skills_target = excluded.weekly_active_skills_target,
-            updated_at = CURRENT_TIMESTAMP
-        "#
-    )
-    .bind(weekly_workout_target)
-    .bind(weekly_active_skills_target)
-    .execute(pool)
-    .await
-    .map_err(ApiError::from)?;
-    
+
+    // Validate both before writing either
+    let workout = settings::definition("weekly_workout_target")?.validate(weekly_workout_target.into())?;
+    let skills = settings::definition("weekly_active_skills_target")?.validate(weekly_active_skills_target.into())?;
+    settings::set(pool, "weekly_workout_target", workout).await?;
+    settings::set(pool, "weekly_active_skills_target", skills).await?;
+
     Ok(UserSettings {
         weekly_workout_target,
         weekly_active_skills_target,
@@ -322,13 +315,10 @@ pub async fn get_detailed_stats(state: State<'_, DbState>) -> Result<DetailedSta
     let pool = &state.0;
     
     // Get user settings for targets
-    let settings = sqlx::query_as::<_, (i64, i64)>(
-        "SELECT COALESCE(weekly_workout_target, 3), COALESCE(weekly_active_skills_target, 5) FROM user_settings WHERE user_id = 1"
-    )
-    .fetch_optional(pool)
-    .await
-    .map_err(ApiError::from)?
-    .unwrap_or((3, 5));
+    let targets = (
+        settings::get_i64(pool, "weekly_workout_target").await?,
+        settings::get_i64(pool, "weekly_active_skills_target").await?,
+    );
     
     // Get study hours breakdown by course
     let course_rows = sqlx::query_as::<_, (i64, String, Option<String>, String, f64, Option<f64>, Option<f64>, f64)>(
@@ -470,11 +460,13 @@ pub async fn get_detailed_stats(state: State<'_, DbState>) -> Result<DetailedSta
     .await
     .unwrap_or(0);
     
-    let workout_percent = if settings.0 > 0 {
-        (workouts_week as f64 / settings.0 as f64 * 100.0).min(100.0)
+    let workout_percent = if targets.0 > 0 {
+        (workouts_week as f64 / targets.0 as f64 * 100.0).min(100.0)
     } else {
         0.0
     };
+
+    let balance = life_balance::life_balance(pool, clock::now().date_naive()).await?;
     
     Ok(DetailedStats {
         study_hours_week,
@@ -486,22 +478,20 @@ pub async fn get_detailed_stats(state: State<'_, DbState>) -> Result<DetailedSta
         practice_percent,
         practice_breakdown,
         workouts_week,
-        workout_target_week: settings.0,
+        workout_target_week: targets.0,
         workout_percent,
         active_skills_count,
-        skills_target: settings.1,
+        skills_target: targets.1,
+        balance,
     })
 }
 
 #[cfg(test)]
 async fn get_detailed_stats_inner(pool: &sqlx::Pool<sqlx::Sqlite>) -> Result<DetailedStats, String> {
-    let settings = sqlx::query_as::<_, (i64, i64)>(
-        "SELECT COALESCE(weekly_workout_target, 3), COALESCE(weekly_active_skills_target, 5) FROM user_settings WHERE user_id = 1",
-    )
-    .fetch_optional(pool)
-    .await
-    .map_err(|e| e.to_string())?
-    .unwrap_or((3, 5));
+    let targets = (
+        settings::get_i64(pool, "weekly_workout_target").await.map_err(|e| e.message)?,
+        settings::get_i64(pool, "weekly_active_skills_target").await.map_err(|e| e.message)?,
+    );
 
     let course_rows = sqlx::query_as::<_, (i64, String, Option<String>, String, f64, Option<f64>, Option<f64>, f64)>(
         r#"
@@ -639,12 +629,16 @@ async fn get_detailed_stats_inner(pool: &sqlx::Pool<sqlx::Sqlite>) -> Result<Det
     .await
     .unwrap_or(0);
 
-    let workout_percent = if settings.0 > 0 {
-        (workouts_week as f64 / settings.0 as f64 * 100.0).min(100.0)
+    let workout_percent = if targets.0 > 0 {
+        (workouts_week as f64 / targets.0 as f64 * 100.0).min(100.0)
     } else {
         0.0
     };
 
+    let balance = life_balance::life_balance(pool, clock::now().date_naive())
+        .await
+        .map_err(|e| e.message)?;
+
     Ok(DetailedStats {
         study_hours_week,
         study_target_week,
@@ -655,10 +649,11 @@ async fn get_detailed_stats_inner(pool: &sqlx::Pool<sqlx::Sqlite>) -> Result<Det
         practice_percent,
         practice_breakdown,
         workouts_week,
-        workout_target_week: settings.0,
+        workout_target_week: targets.0,
         workout_percent,
         active_skills_count,
-        skills_target: settings.1,
+        skills_target: targets.1,
+        balance,
     })
 }
 
@@ -682,10 +677,8 @@ async fn run_get_detailed_stats_benchmark() -> std::time::Duration {
         .await
         .expect("failed to run migrations");
 
-    sqlx::query("UPDATE user_settings SET weekly_workout_target = 3, weekly_active_skills_target = 5 WHERE id = 1")
-        .execute(&pool)
-        .await
-        .unwrap();
+    settings::set(&pool, "weekly_workout_target", 3.into()).await.unwrap();
+    settings::set(&pool, "weekly_active_skills_target", 5.into()).await.unwrap();
 
     for i in 0..10 {
         let course_row = sqlx::query("INSERT INTO courses (name, target_weekly_hours, is_active) VALUES (?, ?, 1) RETURNING id")
@@ -777,6 +770,63 @@ pub async fn get_workout_heatmap(
     Ok(heatmap)
 }
 
+// ============================================================================
+// ATTENTION REPORT
+// ============================================================================
+
+/// Weekly deep / shallow / distracted hours from focus-mode samples.
+/// `week_start` (YYYY-MM-DD) defaults to the current week.
+#[tauri::command]
+pub async fn get_weekly_attention_report(
+    state: State<'_, DbState>,
+    week_start: Option<String>,
+) -> Result<AttentionReport, ApiError> {
+    let pool = &state.0;
+
+    let week_start = match week_start {
+        Some(s) => {
+            let date = chrono::NaiveDate::parse_from_str(&s, "%Y-%m-%d")
+                .map_err(|_| ApiError::validation("week_start must be a YYYY-MM-DD date"))?;
+            Some(focus::week_start_for(pool, date).await?)
+        }
+        None => None,
+    };
+
+    focus::weekly_attention(pool, week_start).await
+}
+
+// ============================================================================
+// POMODORO STATS
+// ============================================================================
+
+/// Completed and abandoned pomodoros, interruptions by reason and the best
+/// uninterrupted streak over the last `days` days (default 30)
+#[tauri::command]
+pub async fn get_pomodoro_stats(state: State<'_, DbState>, days: Option<i64>) -> Result<PomodoroStats, ApiError> {
+    let pool = &state.0;
+    let days = days.unwrap_or(30);
+    if !(1..=365).contains(&days) {
+        return Err(ApiError::validation("days must be between 1 and 365"));
+    }
+    pomodoro::stats(pool, days, &clock::now().date_naive().to_string()).await
+}
+
+// ============================================================================
+// SESSION TYPES
+// ============================================================================
+
+/// Sessions, hours and focus per session type, custom ones included, over
+/// the last `days` days (default 30)
+#[tauri::command]
+pub async fn get_session_type_stats(state: State<'_, DbState>, days: Option<i64>) -> Result<Vec<SessionTypeStats>, ApiError> {
+    let pool = &state.0;
+    let days = days.unwrap_or(30);
+    if !(1..=365).contains(&days) {
+        return Err(ApiError::validation("days must be between 1 and 365"));
+    }
+    session_types::stats(pool, days, &clock::now().date_naive().to_string()).await
+}
+
 // ============================================================================
 // PERSONAL RECORDS
 // ============================================================================
@@ -817,168 +867,123 @@ pub async fn check_and_update_prs(
     workout_id: i64,
 ) -> Result<Vec<PersonalRecord>, ApiError> {
     let pool = &state.0;
-    
-    // Get all exercises from this workout
-    let exercises = sqlx::query_as::<_, (String, Option<i64>, Option<i64>, Option<f64>)>(
+    update_prs(pool, workout_id).await
+}
+
+/// (exercise name, sets, reps, weight)
+type WorkoutExerciseRow = (String, Option<i64>, Option<i64>, Option<f64>);
+
+/// Record new weight, volume and reps PRs set in a workout
+///
+/// Loads the current bests for all of the workout's exercises in one query
+/// and inserts the new records in one transaction.
+pub(crate) async fn update_prs(pool: &sqlx::Pool<sqlx::Sqlite>, workout_id: i64) -> Result<Vec<PersonalRecord>, ApiError> {
+    let exercises: Vec<WorkoutExerciseRow> = sqlx::query_as(
         r#"
         SELECT exercise_name, sets, reps, weight
         FROM workout_exercises
         WHERE workout_id = ?
+        ORDER BY id
         "#
     )
     .bind(workout_id)
     .fetch_all(pool)
     .await
     .map_err(ApiError::from)?;
-    
-    let mut new_prs: Vec<PersonalRecord> = Vec::new();
-    
+    if exercises.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let bests: Vec<(String, String, f64)> = sqlx::query_as(
+        r#"
+        SELECT exercise_name, pr_type, MAX(value)
+        FROM exercise_prs
+        WHERE user_id = 1
+          AND exercise_name IN (SELECT exercise_name FROM workout_exercises WHERE workout_id = ?)
+        GROUP BY exercise_name, pr_type
+        "#
+    )
+    .bind(workout_id)
+    .fetch_all(pool)
+    .await
+    .map_err(ApiError::from)?;
+    let mut bests: HashMap<(String, String), f64> = bests
+        .into_iter()
+        .map(|(exercise_name, pr_type, value)| ((exercise_name, pr_type), value))
+        .collect();
+
+    let records = find_new_prs(&exercises, &mut bests);
+    if records.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    let mut tx = pool.begin().await.map_err(ApiError::from)?;
+    let mut new_prs = Vec::with_capacity(records.len());
+    for (exercise_name, pr_type, value) in records {
+        let (id, achieved_at): (i64, String) = sqlx::query_as(
+            r#"
+            INSERT INTO exercise_prs (user_id, exercise_name, pr_type, value, workout_id, achieved_at)
+            VALUES (1, ?, ?, ?, ?, CURRENT_TIMESTAMP)
+            RETURNING id, achieved_at
+            "#
+        )
+        .bind(&exercise_name)
+        .bind(pr_type)
+        .bind(value)
+        .bind(workout_id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(ApiError::from)?;
+
+        new_prs.push(PersonalRecord {
+            id,
+            exercise_name,
+            pr_type: pr_type.to_string(),
+            value,
+            achieved_at,
+            workout_id: Some(workout_id),
+        });
+    }
+    tx.commit().await.map_err(ApiError::from)?;
+
+    Ok(new_prs)
+}
+
+/// New (exercise name, PR type, value) records, in workout order
+///
+/// `bests` is updated as records are found, so an exercise logged twice in
+/// one workout only counts when the later entry beats the earlier one.
+fn find_new_prs(
+    exercises: &[WorkoutExerciseRow],
+    bests: &mut HashMap<(String, String), f64>,
+) -> Vec<(String, &'static str, f64)> {
+    let mut records = Vec::new();
+
     for (exercise_name, sets, reps, weight) in exercises {
         let weight = weight.unwrap_or(0.0);
         let sets = sets.unwrap_or(0);
         let reps = reps.unwrap_or(0);
-        
-        // Check weight PR
-        if weight > 0.0 {
-            let current_weight_pr: Option<f64> = sqlx::query_scalar(
-                r#"
-                SELECT value FROM exercise_prs
-                WHERE user_id = 1 AND exercise_name = ? AND pr_type = 'weight'
-                ORDER BY value DESC
-                LIMIT 1
-                "#
-            )
-            .bind(&exercise_name)
-            .fetch_optional(pool)
-            .await
-            .map_err(ApiError::from)?
-            .flatten();
-            
-            if current_weight_pr.is_none() || weight > current_weight_pr.unwrap() {
-                sqlx::query(
-                    r#"
-                    INSERT INTO exercise_prs (user_id, exercise_name, pr_type, value, workout_id, achieved_at)
-                    VALUES (1, ?, 'weight', ?, ?, CURRENT_TIMESTAMP)
-                    "#
-                )
-                .bind(&exercise_name)
-                .bind(weight)
-                .bind(workout_id)
-                .execute(pool)
-                .await
-                .map_err(ApiError::from)?;
-                
-                let id: i64 = sqlx::query_scalar("SELECT last_insert_rowid()")
-                    .fetch_one(pool)
-                    .await
-                    .map_err(ApiError::from)?;
-                
-                new_prs.push(PersonalRecord {
-                    id,
-                    exercise_name: exercise_name.clone(),
-                    pr_type: "weight".to_string(),
-                    value: weight,
-                    achieved_at: chrono::Utc::now().to_rfc3339(),
-                    workout_id: Some(workout_id),
-                });
-            }
-        }
-        
-        // Check volume PR (sets * reps * weight)
-        let volume = sets as f64 * reps as f64 * weight;
-        if volume > 0.0 {
-            let current_volume_pr: Option<f64> = sqlx::query_scalar(
-                r#"
-                SELECT value FROM exercise_prs
-                WHERE user_id = 1 AND exercise_name = ? AND pr_type = 'volume'
-                ORDER BY value DESC
-                LIMIT 1
-                "#
-            )
-            .bind(&exercise_name)
-            .fetch_optional(pool)
-            .await
-            .map_err(ApiError::from)?
-            .flatten();
-            
-            if current_volume_pr.is_none() || volume > current_volume_pr.unwrap() {
-                sqlx::query(
-                    r#"
-                    INSERT INTO exercise_prs (user_id, exercise_name, pr_type, value, workout_id, achieved_at)
-                    VALUES (1, ?, 'volume', ?, ?, CURRENT_TIMESTAMP)
-                    "#
-                )
-                .bind(&exercise_name)
-                .bind(volume)
-                .bind(workout_id)
-                .execute(pool)
-                .await
-                .map_err(ApiError::from)?;
-                
-                let id: i64 = sqlx::query_scalar("SELECT last_insert_rowid()")
-                    .fetch_one(pool)
-                    .await
-                    .map_err(ApiError::from)?;
-                
-                new_prs.push(PersonalRecord {
-                    id,
-                    exercise_name: exercise_name.clone(),
-                    pr_type: "volume".to_string(),
-                    value: volume,
-                    achieved_at: chrono::Utc::now().to_rfc3339(),
-                    workout_id: Some(workout_id),
-                });
+
+        // Weight, volume (sets * reps * weight) and reps in a single set
+        let candidates = [
+            ("weight", weight),
+            ("volume", sets as f64 * reps as f64 * weight),
+            ("reps", reps as f64),
+        ];
+        for (pr_type, value) in candidates {
+            if value <= 0.0 {
+                continue;
             }
-        }
-        
-        // Check reps PR (single set)
-        if reps > 0 {
-            let current_reps_pr: Option<f64> = sqlx::query_scalar(
-                r#"
-                SELECT value FROM exercise_prs
-                WHERE user_id = 1 AND exercise_name = ? AND pr_type = 'reps'
-                ORDER BY value DESC
-                LIMIT 1
-                "#
-            )
-            .bind(&exercise_name)
-            .fetch_optional(pool)
-            .await
-            .map_err(ApiError::from)?
-            .flatten();
-            
-            if current_reps_pr.is_none() || (reps as f64) > current_reps_pr.unwrap() {
-                sqlx::query(
-                    r#"
-                    INSERT INTO exercise_prs (user_id, exercise_name, pr_type, value, workout_id, achieved_at)
-                    VALUES (1, ?, 'reps', ?, ?, CURRENT_TIMESTAMP)
-                    "#
-                )
-                .bind(&exercise_name)
-                .bind(reps)
-                .bind(workout_id)
-                .execute(pool)
-                .await
-                .map_err(ApiError::from)?;
-                
-                let id: i64 = sqlx::query_scalar("SELECT last_insert_rowid()")
-                    .fetch_one(pool)
-                    .await
-                    .map_err(ApiError::from)?;
-                
-                new_prs.push(PersonalRecord {
-                    id,
-                    exercise_name: exercise_name.clone(),
-                    pr_type: "reps".to_string(),
-                    value: reps as f64,
-                    achieved_at: chrono::Utc::now().to_rfc3339(),
-                    workout_id: Some(workout_id),
-                });
+            let key = (exercise_name.clone(), pr_type.to_string());
+            if bests.get(&key).is_some_and(|best| value <= *best) {
+                continue;
             }
+            bests.insert(key, value);
+            records.push((exercise_name.clone(), pr_type, value));
         }
     }
-    
-    Ok(new_prs)
+
+    records
 }
 
 // ============================================================================
@@ -1016,275 +1021,13 @@ pub async fn get_achievements(state: State<'_, DbState>) -> Result<Vec<Achieveme
     Ok(achievements)
 }
 
+/// Rescan every achievement metric
+///
+/// Achievements are normally awarded as activity is logged (see
+/// `services::achievements`); this catches up on anything missed.
 #[tauri::command]
 pub async fn check_achievements(state: State<'_, DbState>) -> Result<Vec<Achievement>, ApiError> {
-    let pool = &state.0;
-    let mut new_achievements: Vec<Achievement> = Vec::new();
-    
-    // Check workout milestones: 10, 25, 50, 100, 250, 500
-    let total_workouts: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM workouts WHERE user_id = 1")
-        .fetch_one(pool)
-        .await
-        .unwrap_or(0);
-    
-    let workout_milestones = vec![10, 25, 50, 100, 250, 500];
-    for milestone in workout_milestones {
-        if total_workouts >= milestone {
-            // Check if achievement already exists
-            let exists: i64 = sqlx::query_scalar(
-                r#"
-                SELECT COUNT(*) FROM achievements
-                WHERE user_id = 1 AND achievement_type = 'workout_milestone' AND metadata LIKE ?
-                "#
-            )
-            .bind(format!("%\"count\":{}%", milestone))
-            .fetch_one(pool)
-            .await
-            .unwrap_or(0);
-            
-            if exists == 0 {
-                let title = format!("{} Workouts!", milestone);
-                let description = format!("Completed {} total workouts", milestone);
-                let metadata = format!(r#"{{"count":{}}}"#, milestone);
-                
-                sqlx::query(
-                    r#"
-                    INSERT INTO achievements (user_id, achievement_type, title, description, category, metadata)
-                    VALUES (1, 'workout_milestone', ?, ?, 'physical', ?)
-                    "#
-                )
-                .bind(&title)
-                .bind(&description)
-                .bind(&metadata)
-                .execute(pool)
-                .await
-                .map_err(ApiError::from)?;
-                
-                let id: i64 = sqlx::query_scalar("SELECT last_insert_rowid()")
-                    .fetch_one(pool)
-                    .await
-                    .map_err(ApiError::from)?;
-                
-                new_achievements.push(Achievement {
-                    id,
-                    achievement_type: "workout_milestone".to_string(),
-                    title,
-                    description: Some(description),
-                    category: Some("physical".to_string()),
-                    achieved_at: chrono::Utc::now().to_rfc3339(),
-                    metadata: Some(metadata),
-                });
-            }
-        }
-    }
-    
-    // Check study hour milestones: 10, 25, 50, 100, 250, 500
-    let total_study_hours: f64 = sqlx::query_scalar(
-        "SELECT COALESCE(SUM(duration_minutes), 0) / 60.0 FROM sessions WHERE session_type = 'study'"
-    )
-    .fetch_one(pool)
-    .await
-    .unwrap_or(0.0);
-    
-    let study_milestones = vec![10, 25, 50, 100, 250, 500];
-    for milestone in study_milestones {
-        if total_study_hours >= milestone as f64 {
-            let exists: i64 = sqlx::query_scalar(
-                r#"
-                SELECT COUNT(*) FROM achievements
-                WHERE user_id = 1 AND achievement_type = 'study_milestone' AND metadata LIKE ?
-                "#
-            )
-            .bind(format!("%\"hours\":{}%", milestone))
-            .fetch_one(pool)
-            .await
-            .unwrap_or(0);
-            
-            if exists == 0 {
-                let title = format!("{} Study Hours!", milestone);
-                let description = format!("Studied for {} total hours", milestone);
-                let metadata = format!(r#"{{"hours":{}}}"#, milestone);
-                
-                sqlx::query(
-                    r#"
-                    INSERT INTO achievements (user_id, achievement_type, title, description, category, metadata)
-                    VALUES (1, 'study_milestone', ?, ?, 'academic', ?)
-                    "#
-                )
-                .bind(&title)
-                .bind(&description)
-                .bind(&metadata)
-                .execute(pool)
-                .await
-                .map_err(ApiError::from)?;
-                
-                let id: i64 = sqlx::query_scalar("SELECT last_insert_rowid()")
-                    .fetch_one(pool)
-                    .await
-                    .map_err(ApiError::from)?;
-                
-                new_achievements.push(Achievement {
-                    id,
-                    achievement_type: "study_milestone".to_string(),
-                    title,
-                    description: Some(description),
-                    category: Some("academic".to_string()),
-                    achieved_at: chrono::Utc::now().to_rfc3339(),
-                    metadata: Some(metadata),
-                });
-            }
-        }
-    }
-    
-    // Check skill level milestones (any skill reaching level 5, 10, 15, 20)
-    let skill_levels = sqlx::query_as::<_, (String, i64)>(
-        "SELECT name, current_level FROM skills WHERE user_id = 1"
-    )
-    .fetch_all(pool)
-    .await
-    .map_err(ApiError::from)?;
-    
-    let level_milestones = vec![5, 10, 15, 20];
-    for (skill_name, level) in skill_levels {
-        for milestone in &level_milestones {
-            if level >= *milestone {
-                let exists: i64 = sqlx::query_scalar(
-                    r#"
-                    SELECT COUNT(*) FROM achievements
-                    WHERE user_id = 1 AND achievement_type = 'skill_level' 
-                    AND metadata LIKE ? AND metadata LIKE ?
-                    "#
-                )
-                .bind(format!("%\"skill\":\"{}\"%", skill_name))
-                .bind(format!("%\"level\":{}%", milestone))
-                .fetch_one(pool)
-                .await
-                .unwrap_or(0);
-                
-                if exists == 0 {
-                    let title = format!("{} Level {}!", skill_name, milestone);
-                    let description = format!("Reached level {} in {}", milestone, skill_name);
-                    let metadata = format!(r#"{{"skill":"{}","level":{}}}"#, skill_name, milestone);
-                    
-                    sqlx::query(
-                        r#"
-                        INSERT INTO achievements (user_id, achievement_type, title, description, category, metadata)
-                        VALUES (1, 'skill_level', ?, ?, 'skills', ?)
-                        "#
-                    )
-                    .bind(&title)
-                    .bind(&description)
-                    .bind(&metadata)
-                    .execute(pool)
-                    .await
-                    .map_err(ApiError::from)?;
-                    
-                    let id: i64 = sqlx::query_scalar("SELECT last_insert_rowid()")
-                        .fetch_one(pool)
-                        .await
-                        .map_err(ApiError::from)?;
-                    
-                    new_achievements.push(Achievement {
-                        id,
-                        achievement_type: "skill_level".to_string(),
-                        title,
-                        description: Some(description),
-                        category: Some("skills".to_string()),
-                        achieved_at: chrono::Utc::now().to_rfc3339(),
-                        metadata: Some(metadata),
-                    });
-                }
-            }
-        }
-    }
-    
-    // Check consistency achievements (7-day, 14-day, 30-day streaks)
-    let streak_milestones = vec![7, 14, 30, 60, 90];
-    
-    // Check-in streak
-    let checkin_streak = get_consecutive_days(pool, "check_ins", "checked_in_at").await;
-    for milestone in &streak_milestones {
-        if checkin_streak >= *milestone {
-            let exists: i64 = sqlx::query_scalar(
-                r#"
-                SELECT COUNT(*) FROM achievements
-                WHERE user_id = 1 AND achievement_type = 'checkin_streak' AND metadata LIKE ?
-                "#
-            )
-            .bind(format!("%\"days\":{}%", milestone))
-            .fetch_one(pool)
-            .await
-            .unwrap_or(0);
-            
-            if exists == 0 {
-                let title = format!("{}-Day Check-in Streak!", milestone);
-                let description = format!("Checked in for {} consecutive days", milestone);
-                let metadata = format!(r#"{{"days":{}}}"#, milestone);
-                
-                sqlx::query(
-                    r#"
-                    INSERT INTO achievements (user_id, achievement_type, title, description, category, metadata)
-                    VALUES (1, 'checkin_streak', ?, ?, 'wellness', ?)
-                    "#
-                )
-                .bind(&title)
-                .bind(&description)
-                .bind(&metadata)
-                .execute(pool)
-                .await
-                .map_err(ApiError::from)?;
-                
-                let id: i64 = sqlx::query_scalar("SELECT last_insert_rowid()")
-                    .fetch_one(pool)
-                    .await
-                    .map_err(ApiError::from)?;
-                
-                new_achievements.push(Achievement {
-                    id,
-                    achievement_type: "checkin_streak".to_string(),
-                    title,
-                    description: Some(description),
-                    category: Some("wellness".to_string()),
-                    achieved_at: chrono::Utc::now().to_rfc3339(),
-                    metadata: Some(metadata),
-                });
-            }
-        }
-    }
-    
-    Ok(new_achievements)
-}
-
-// Helper function to get consecutive days
-async fn get_consecutive_days(pool: &sqlx::Pool<sqlx::Sqlite>, table: &str, date_column: &str) -> i64 {
-    let query = format!(
-        r#"
-        WITH dated AS (
-            SELECT DISTINCT date({}) as activity_date
-            FROM {}
-            ORDER BY activity_date DESC
-        ),
-        numbered AS (
-            SELECT activity_date,
-                   ROW_NUMBER() OVER (ORDER BY activity_date DESC) as rn,
-                   julianday(activity_date) as jd
-            FROM dated
-        ),
-        streak AS (
-            SELECT activity_date, jd - rn as grp
-            FROM numbered
-            WHERE julianday('now') - jd <= 1
-        )
-        SELECT COUNT(*) FROM streak
-        WHERE grp = (SELECT grp FROM streak LIMIT 1)
-        "#,
-        date_column, table
-    );
-    
-    sqlx::query_scalar(&query)
-        .fetch_one(pool)
-        .await
-        .unwrap_or(0)
+    achievements::evaluate_all(&state.0).await
 }
 
 // ============================================================================
@@ -1570,6 +1313,7 @@ mod tests {
             workout_percent: 66.67,
             active_skills_count: 3,
             skills_target: 5,
+            balance: LifeBalance { score: None, domains: vec![] },
         };
         
         let json = serde_json::to_string(&stats).unwrap();
@@ -1579,6 +1323,80 @@ mod tests {
     }
 }
 
+#[cfg(test)]
+mod pr_tests {
+    use super::*;
+    use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+    use std::str::FromStr;
+
+    #[test]
+    fn repeated_exercise_only_counts_when_it_beats_the_earlier_entry() {
+        let exercises = vec![
+            ("Squat".to_string(), Some(3), Some(5), Some(100.0)),
+            ("Squat".to_string(), Some(1), Some(3), Some(110.0)),
+            ("Squat".to_string(), Some(1), Some(1), Some(105.0)),
+        ];
+        let mut bests = HashMap::from([(("Squat".to_string(), "reps".to_string()), 8.0)]);
+
+        let records = find_new_prs(&exercises, &mut bests);
+        assert_eq!(
+            records,
+            vec![
+                ("Squat".to_string(), "weight", 100.0),
+                ("Squat".to_string(), "volume", 1500.0),
+                ("Squat".to_string(), "weight", 110.0),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn update_prs_records_new_bests_once() {
+        let options = SqliteConnectOptions::from_str("sqlite::memory:")
+            .unwrap()
+            .foreign_keys(false);
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect_with(options)
+            .await
+            .unwrap();
+        crate::db::migrations::run_migrations(&pool).await.unwrap();
+
+        sqlx::query(
+            r#"INSERT INTO exercise_prs (exercise_name, pr_type, value) VALUES ('Bench', 'weight', 80)"#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            r#"INSERT INTO workout_exercises (workout_id, exercise_name, sets, reps, weight)
+               VALUES (1, 'Bench', 3, 5, 75), (1, 'Row', 3, 10, 50)"#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let prs = update_prs(&pool, 1).await.unwrap();
+        let found: Vec<(&str, &str)> = prs
+            .iter()
+            .map(|p| (p.exercise_name.as_str(), p.pr_type.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            vec![
+                ("Bench", "volume"),
+                ("Bench", "reps"),
+                ("Row", "weight"),
+                ("Row", "volume"),
+                ("Row", "reps"),
+            ]
+        );
+        assert!(prs.iter().all(|p| p.workout_id == Some(1)));
+
+        // Nothing new the second time
+        assert!(update_prs(&pool, 1).await.unwrap().is_empty());
+    }
+}
+
 #[cfg(test)]
 mod benchmarks {
     use super::*;
@@ -0,0 +1,199 @@
+//! Assignment Checklists
+//!
+//! Each assignment has a submission checklist, separate from its plan
+//! blocks: the steps that make it actually done (draft, review, submit).
+//! New assignments start from `assignment_checklist_template`, and
+//! `toggle_assignment` refuses to mark one done while required items remain
+//! unless forced.
+
+use sqlx::{Pool, Sqlite};
+use tauri::State;
+
+use crate::{
+    error::ApiError,
+    models::assignment::AssignmentChecklistItem,
+    services::settings,
+    DbState,
+};
+
+const MAX_LABEL_LENGTH: usize = 200;
+
+const ITEM_COLUMNS: &str = "id, assignment_id, label, is_required, is_done, done_at, position";
+
+#[tauri::command]
+pub async fn get_assignment_checklist(
+    state: State<'_, DbState>,
+    assignment_id: i64,
+) -> Result<Vec<AssignmentChecklistItem>, ApiError> {
+    checklist(&state.0, assignment_id).await
+}
+
+#[tauri::command]
+pub async fn add_checklist_item(
+    state: State<'_, DbState>,
+    assignment_id: i64,
+    label: String,
+    is_required: Option<bool>,
+) -> Result<AssignmentChecklistItem, ApiError> {
+    insert_item(&state.0, assignment_id, &label, is_required.unwrap_or(true)).await
+}
+
+#[tauri::command]
+pub async fn toggle_checklist_item(state: State<'_, DbState>, id: i64) -> Result<AssignmentChecklistItem, ApiError> {
+    toggle_item(&state.0, id).await
+}
+
+#[tauri::command]
+pub async fn delete_checklist_item(state: State<'_, DbState>, id: i64) -> Result<bool, ApiError> {
+    let result = sqlx::query("DELETE FROM assignment_checklist_items WHERE id = ?")
+        .bind(id)
+        .execute(&state.0)
+        .await
+        .map_err(ApiError::from)?;
+    if result.rows_affected() == 0 {
+        return Err(ApiError::not_found("Checklist item not found"));
+    }
+    Ok(true)
+}
+
+pub(crate) async fn checklist(
+    pool: &Pool<Sqlite>,
+    assignment_id: i64,
+) -> Result<Vec<AssignmentChecklistItem>, ApiError> {
+    sqlx::query_as::<_, AssignmentChecklistItem>(&format!(
+        "SELECT {} FROM assignment_checklist_items WHERE assignment_id = ? ORDER BY position, id",
+        ITEM_COLUMNS
+    ))
+    .bind(assignment_id)
+    .fetch_all(pool)
+    .await
+    .map_err(ApiError::from)
+}
+
+pub(crate) async fn insert_item(
+    pool: &Pool<Sqlite>,
+    assignment_id: i64,
+    label: &str,
+    is_required: bool,
+) -> Result<AssignmentChecklistItem, ApiError> {
+    let label = label.trim();
+    if label.is_empty() {
+        return Err(ApiError::validation("Checklist item label is required"));
+    }
+    if label.len() > MAX_LABEL_LENGTH {
+        return Err(ApiError::validation(format!(
+            "Checklist item label must be at most {} characters",
+            MAX_LABEL_LENGTH
+        )));
+    }
+    sqlx::query_as::<_, AssignmentChecklistItem>(&format!(
+        r#"INSERT INTO assignment_checklist_items (assignment_id, label, is_required, position)
+           VALUES (?1, ?2, ?3, (SELECT COALESCE(MAX(position), -1) + 1 FROM assignment_checklist_items WHERE assignment_id = ?1))
+           RETURNING {}"#,
+        ITEM_COLUMNS
+    ))
+    .bind(assignment_id)
+    .bind(label)
+    .bind(is_required)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| ApiError::from_sqlx(e, "Failed to add checklist item"))
+}
+
+async fn toggle_item(pool: &Pool<Sqlite>, id: i64) -> Result<AssignmentChecklistItem, ApiError> {
+    sqlx::query_as::<_, AssignmentChecklistItem>(&format!(
+        r#"UPDATE assignment_checklist_items
+           SET is_done = 1 - is_done, done_at = CASE WHEN is_done = 1 THEN NULL ELSE CURRENT_TIMESTAMP END
+           WHERE id = ?
+           RETURNING {}"#,
+        ITEM_COLUMNS
+    ))
+    .bind(id)
+    .fetch_optional(pool)
+    .await
+    .map_err(ApiError::from)?
+    .ok_or_else(|| ApiError::not_found("Checklist item not found"))
+}
+
+/// Give a new assignment the checklist from `assignment_checklist_template`
+pub(crate) async fn add_template_items(pool: &Pool<Sqlite>, assignment_id: i64) -> Result<(), ApiError> {
+    let labels = settings::get_string_list(pool, "assignment_checklist_template").await?;
+    for label in labels.iter().filter(|l| !l.trim().is_empty()) {
+        insert_item(pool, assignment_id, label, true).await?;
+    }
+    Ok(())
+}
+
+/// Labels of required items not yet done, in checklist order
+pub(crate) async fn remaining_required(pool: &Pool<Sqlite>, assignment_id: i64) -> Result<Vec<String>, ApiError> {
+    sqlx::query_scalar(
+        r#"SELECT label FROM assignment_checklist_items
+           WHERE assignment_id = ? AND is_required = 1 AND is_done = 0
+           ORDER BY position, id"#,
+    )
+    .bind(assignment_id)
+    .fetch_all(pool)
+    .await
+    .map_err(ApiError::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::assignments::{insert_assignment, toggle_completed, AssignmentInput};
+    use crate::error::ErrorCode;
+    use serde_json::json;
+
+    async fn assignment(pool: &Pool<Sqlite>) -> i64 {
+        let course = crate::test_support::course("Writing").create(pool).await;
+        let input = AssignmentInput {
+            course_id: course.id,
+            title: "Essay".to_string(),
+            description: None,
+            due_date: Some("2030-04-01".to_string()),
+            priority: None,
+            score: None,
+            estimated_minutes: None,
+            actual_minutes: None,
+        };
+        insert_assignment(pool, &input).await.unwrap().id
+    }
+
+    #[tokio::test]
+    async fn required_items_gate_completion_unless_forced() {
+        let pool = crate::test_support::pool().await;
+        let id = assignment(&pool).await;
+
+        let items = checklist(&pool, id).await.unwrap();
+        let labels: Vec<&str> = items.iter().map(|i| i.label.as_str()).collect();
+        assert_eq!(labels, vec!["Draft", "Review", "Submit"]);
+        insert_item(&pool, id, "Cite sources", false).await.unwrap();
+
+        let err = toggle_completed(&pool, id, false).await.unwrap_err();
+        assert_eq!(err.code, ErrorCode::Conflict);
+        assert_eq!(err.details, Some(json!({ "remaining_checklist": ["Draft", "Review", "Submit"] })));
+
+        for item in &items {
+            toggle_item(&pool, item.id).await.unwrap();
+        }
+        // Optional items don't block
+        let done = toggle_completed(&pool, id, false).await.unwrap();
+        assert_eq!(done.is_completed, Some(1));
+
+        // Reopening is never gated, and forcing skips the checklist
+        toggle_completed(&pool, id, false).await.unwrap();
+        toggle_item(&pool, items[2].id).await.unwrap();
+        assert!(toggle_completed(&pool, id, false).await.is_err());
+        assert_eq!(toggle_completed(&pool, id, true).await.unwrap().is_completed, Some(1));
+    }
+
+    #[tokio::test]
+    async fn an_empty_template_adds_no_items() {
+        let pool = crate::test_support::pool().await;
+        settings::set(&pool, "assignment_checklist_template", json!([])).await.unwrap();
+        let id = assignment(&pool).await;
+        assert!(checklist(&pool, id).await.unwrap().is_empty());
+        assert!(toggle_completed(&pool, id, false).await.is_ok());
+        assert!(insert_item(&pool, id, "   ", true).await.is_err());
+    }
+}
@@ -1,6 +1,16 @@
 use tauri::State;
 
-use crate::{DbState, error::ApiError, models::assignment::Assignment};
+use crate::{
+    DbState,
+    db::filter::FilteredQuery,
+    error::ApiError,
+    commands::{
+        assignment_checklist,
+        week_plan_blocks::{release_remaining_blocks, Deliverable},
+    },
+    models::assignment::Assignment,
+    services::{dedupe, estimates::{self, CourseCalibration}},
+};
 
 #[derive(Debug, serde::Deserialize)]
 pub struct AssignmentInput {
@@ -9,54 +19,97 @@ pub struct AssignmentInput {
     pub description: Option<String>,
     pub due_date: Option<String>,
     pub priority: Option<String>,
+    /// Percent score once graded
+    #[serde(default)]
+    pub score: Option<f64>,
+    #[serde(default)]
+    pub estimated_minutes: Option<i64>,
+    /// Time actually spent, once done
+    #[serde(default)]
+    pub actual_minutes: Option<i64>,
 }
 
+fn validate_score(score: Option<f64>) -> Result<(), ApiError> {
+    match score {
+        Some(s) if !(0.0..=100.0).contains(&s) => Err(ApiError::validation("Score must be between 0 and 100")),
+        _ => Ok(()),
+    }
+}
+
+fn validate_minutes(data: &AssignmentInput) -> Result<(), ApiError> {
+    if data.estimated_minutes.is_some_and(|m| m <= 0) {
+        return Err(ApiError::validation("Estimated minutes must be positive"));
+    }
+    if data.actual_minutes.is_some_and(|m| m < 0) {
+        return Err(ApiError::validation("Actual minutes cannot be negative"));
+    }
+    Ok(())
+}
+
+/// Create an assignment. One with the same title and due date in the course
+/// fails with a conflict, unless `force` is set.
 #[tauri::command]
-pub async fn create_assignment(state: State<'_, DbState>, data: AssignmentInput) -> Result<Assignment, ApiError> {
-    let pool = &state.0;
+pub async fn create_assignment(state: State<'_, DbState>, data: AssignmentInput, force: Option<bool>) -> Result<Assignment, ApiError> {
+    if !force.unwrap_or(false) {
+        dedupe::check_assignment(&state.0, &data).await?;
+    }
+    insert_assignment(&state.0, &data).await
+}
+
+pub(crate) async fn insert_assignment(
+    pool: &sqlx::Pool<sqlx::Sqlite>,
+    data: &AssignmentInput,
+) -> Result<Assignment, ApiError> {
+    validate_score(data.score)?;
+    validate_minutes(data)?;
     let rec = sqlx::query_as::<_, Assignment>(
-        "INSERT INTO assignments (course_id, title, description, due_date, priority) VALUES (?, ?, ?, ?, ?) RETURNING id, course_id, title, description, due_date, priority, is_completed, completed_at, created_at"
+        "INSERT INTO assignments (course_id, title, description, due_date, priority, score, estimated_minutes, actual_minutes) VALUES (?, ?, ?, ?, ?, ?, ?, ?) RETURNING id, course_id, title, description, due_date, priority, is_completed, completed_at, created_at, score, estimated_minutes, actual_minutes"
     )
     .bind(data.course_id)
     .bind(&data.title)
     .bind(&data.description)
     .bind(&data.due_date)
     .bind(&data.priority)
+    .bind(data.score)
+    .bind(data.estimated_minutes)
+    .bind(data.actual_minutes)
     .fetch_one(pool)
     .await
     .map_err(ApiError::from)?;
+    assignment_checklist::add_template_items(pool, rec.id).await?;
     Ok(rec)
 }
 
 #[tauri::command]
 pub async fn get_assignments(state: State<'_, DbState>, course_id: Option<i64>) -> Result<Vec<Assignment>, ApiError> {
     let pool = &state.0;
-    let rows = if let Some(course_id) = course_id {
-        sqlx::query_as::<_, Assignment>("SELECT * FROM assignments WHERE course_id = ? ORDER BY due_date IS NULL, due_date")
-            .bind(course_id)
-            .fetch_all(pool)
-            .await
-            .map_err(ApiError::from)?
-    } else {
-        sqlx::query_as::<_, Assignment>("SELECT * FROM assignments ORDER BY due_date IS NULL, due_date")
-            .fetch_all(pool)
-            .await
-            .map_err(ApiError::from)?
-    };
+    let mut query = FilteredQuery::new("SELECT * FROM assignments")
+        .eq("course_id", course_id)
+        .order_by("due_date IS NULL, due_date");
+    let rows = query
+        .build_query_as::<Assignment>()
+        .fetch_all(pool)
+        .await
+        .map_err(ApiError::from)?;
     Ok(rows)
 }
 
 #[tauri::command]
 pub async fn update_assignment(state: State<'_, DbState>, id: i64, data: AssignmentInput) -> Result<Assignment, ApiError> {
     let pool = &state.0;
+    validate_score(data.score)?;
+    validate_minutes(&data)?;
     let rec = sqlx::query_as::<_, Assignment>(
-        "UPDATE assignments SET course_id = COALESCE(?, course_id), title = COALESCE(?, title), description = COALESCE(?, description), due_date = COALESCE(?, due_date), priority = COALESCE(?, priority) WHERE id = ? RETURNING id, course_id, title, description, due_date, priority, is_completed, completed_at, created_at"
+        "UPDATE assignments SET course_id = COALESCE(?, course_id), title = COALESCE(?, title), description = COALESCE(?, description), due_date = COALESCE(?, due_date), priority = COALESCE(?, priority), score = COALESCE(?, score), estimated_minutes = COALESCE(?, estimated_minutes), actual_minutes = COALESCE(?, actual_minutes) WHERE id = ? RETURNING id, course_id, title, description, due_date, priority, is_completed, completed_at, created_at, score, estimated_minutes, actual_minutes"
     )
     .bind(Some(data.course_id))
     .bind(Some(&data.title))
     .bind(&data.description)
     .bind(&data.due_date)
     .bind(&data.priority)
+    .bind(data.score)
+    .bind(data.estimated_minutes)
+    .bind(data.actual_minutes)
     .bind(id)
     .fetch_one(pool)
     .await
@@ -80,15 +133,51 @@ pub async fn delete_assignment(state: State<'_, DbState>, id: i64) -> Result<boo
     Ok(true)
 }
 
+/// Mark an assignment done or not done. Marking it done is refused while
+/// required checklist items remain, unless `force` is set.
 #[tauri::command]
-pub async fn toggle_assignment(state: State<'_, DbState>, id: i64) -> Result<Assignment, ApiError> {
-    let pool = &state.0;
+pub async fn toggle_assignment(state: State<'_, DbState>, id: i64, force: Option<bool>) -> Result<Assignment, ApiError> {
+    toggle_completed(&state.0, id, force.unwrap_or(false)).await
+}
+
+pub(crate) async fn toggle_completed(
+    pool: &sqlx::Pool<sqlx::Sqlite>,
+    id: i64,
+    force: bool,
+) -> Result<Assignment, ApiError> {
+    let is_completed: Option<i64> = sqlx::query_scalar("SELECT is_completed FROM assignments WHERE id = ?")
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+        .map_err(ApiError::from)?
+        .ok_or_else(|| ApiError::not_found("Assignment not found"))?;
+    if is_completed != Some(1) && !force {
+        let remaining = assignment_checklist::remaining_required(pool, id).await?;
+        if !remaining.is_empty() {
+            return Err(ApiError::conflict(format!(
+                "Checklist not finished: {}",
+                remaining.join(", ")
+            ))
+            .with_details(serde_json::json!({ "remaining_checklist": remaining })));
+        }
+    }
+
     let rec = sqlx::query_as::<_, Assignment>(
-        "UPDATE assignments SET is_completed = CASE WHEN is_completed = 1 THEN 0 ELSE 1 END, completed_at = CASE WHEN is_completed = 1 THEN NULL ELSE CURRENT_TIMESTAMP END WHERE id = ? RETURNING id, course_id, title, description, due_date, priority, is_completed, completed_at, created_at"
+        "UPDATE assignments SET is_completed = CASE WHEN is_completed = 1 THEN 0 ELSE 1 END, completed_at = CASE WHEN is_completed = 1 THEN NULL ELSE CURRENT_TIMESTAMP END WHERE id = ? RETURNING id, course_id, title, description, due_date, priority, is_completed, completed_at, created_at, score, estimated_minutes, actual_minutes"
     )
     .bind(id)
     .fetch_one(pool)
     .await
     .map_err(ApiError::from)?;
+    if rec.is_completed == Some(1) {
+        release_remaining_blocks(pool, Deliverable::Assignment(id)).await?;
+    }
     Ok(rec)
 }
+
+/// Per-course ratio of actual to estimated minutes from completed assignments
+#[tauri::command]
+pub async fn get_estimate_calibration(state: State<'_, DbState>) -> Result<Vec<CourseCalibration>, ApiError> {
+    let pool = &state.0;
+    estimates::calibrations(pool).await
+}
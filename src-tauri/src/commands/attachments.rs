@@ -0,0 +1,57 @@
+//! Attachment commands
+//!
+//! Thin wrappers over `services::attachments` that resolve the attachments
+//! directory under the app data dir.
+
+use std::path::{Path, PathBuf};
+
+use tauri::{Manager, State};
+
+use crate::services::attachments::{self, GcReport, StoredAttachment, ATTACHMENTS_DIR};
+use crate::{error::ApiError, DbState};
+
+pub(crate) fn attachments_dir(app: &tauri::AppHandle) -> Result<PathBuf, ApiError> {
+    let mut path = app
+        .path()
+        .app_data_dir()
+        .map_err(|e| ApiError::internal(e.to_string()))?;
+    path.push(ATTACHMENTS_DIR);
+    Ok(path)
+}
+
+/// Copy a file (e.g. from a file picker) and attach it to a practice log or workout
+#[tauri::command]
+pub async fn attach_file(
+    app: tauri::AppHandle,
+    state: State<'_, DbState>,
+    owner_type: String,
+    owner_id: i64,
+    source_path: String,
+) -> Result<StoredAttachment, ApiError> {
+    let root = attachments_dir(&app)?;
+    attachments::attach(&state.0, &root, &owner_type, owner_id, Path::new(&source_path)).await
+}
+
+#[tauri::command]
+pub async fn get_attachments(
+    app: tauri::AppHandle,
+    state: State<'_, DbState>,
+    owner_type: String,
+    owner_id: i64,
+) -> Result<Vec<StoredAttachment>, ApiError> {
+    let root = attachments_dir(&app)?;
+    attachments::list(&state.0, &root, &owner_type, owner_id).await
+}
+
+#[tauri::command]
+pub async fn delete_attachment(app: tauri::AppHandle, state: State<'_, DbState>, id: i64) -> Result<(), ApiError> {
+    let root = attachments_dir(&app)?;
+    attachments::delete(&state.0, &root, id).await
+}
+
+/// Remove attachments of deleted entries and files nothing refers to
+#[tauri::command]
+pub async fn gc_attachments(app: tauri::AppHandle, state: State<'_, DbState>) -> Result<GcReport, ApiError> {
+    let root = attachments_dir(&app)?;
+    attachments::gc(&state.0, &root).await
+}
@@ -0,0 +1,389 @@
+//! Morning Briefing
+//!
+//! Today's agenda, what's due, the Big Three (or suggestions for them when
+//! none are set), a recovery read and one insight, in one payload. `text` is
+//! the same content as a short plain-text digest for a notification.
+//!
+//! There is no weather provider or LLM service in the app yet, so the
+//! briefing has no weather section and `text` comes from a fixed template.
+
+use chrono::Local;
+use serde::Serialize;
+use sqlx::{Pool, Sqlite};
+use tauri::State;
+
+use crate::agent::facade::AgentFacade;
+use crate::agent::insights::Insight;
+use crate::agent::{BigThreeGoal, IntelligenceAgent};
+use crate::commands::calendar::{get_calendar_items_for_pool, CalendarItem, CalendarQuery};
+use crate::commands::next_task::{next_best_tasks, NextTaskKind};
+use crate::utils::parse_datetime_utc;
+use crate::{error::ApiError, DbState};
+
+/// Assignments due within this many days (and overdue ones) are listed
+const ASSIGNMENT_DUE_DAYS: i64 = 2;
+const EXAM_DUE_DAYS: i64 = 7;
+/// Days of training compared against the usual load
+const RECOVERY_WINDOW_DAYS: i64 = 3;
+/// History the usual training load is taken from
+const BASELINE_DAYS: i64 = 28;
+/// Study minutes yesterday that count as a heavy day
+const HEAVY_STUDY_MINUTES: i64 = 360;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DueItem {
+    /// "assignment" or "exam"
+    pub kind: String,
+    pub id: i64,
+    pub title: String,
+    pub course_name: String,
+    pub due_at: String,
+    /// Negative when overdue
+    pub days_left: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SuggestedGoal {
+    pub title: String,
+    pub category: String,
+    pub reason: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RecoveryLevel {
+    Fresh,
+    Normal,
+    Strained,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RecoveryStatus {
+    pub level: RecoveryLevel,
+    /// Workout minutes over the last three days
+    pub workout_minutes: i64,
+    /// Usual workout minutes for three days, from the last four weeks
+    pub typical_workout_minutes: f64,
+    pub study_minutes_yesterday: i64,
+    /// Latest check-in energy (1-10) from the last 24 hours
+    pub energy: Option<i64>,
+    pub note: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MorningBriefing {
+    pub date: String,
+    /// Today's classes, events, exams and accepted plan blocks, in order
+    pub agenda: Vec<CalendarItem>,
+    pub due: Vec<DueItem>,
+    pub big_three: Vec<BigThreeGoal>,
+    /// Candidates for the Big Three; empty once today's are set
+    pub suggested_big_three: Vec<SuggestedGoal>,
+    pub recovery: RecoveryStatus,
+    pub insight: Option<Insight>,
+    /// Plain-text digest for a notification
+    pub text: String,
+}
+
+/// Compose this morning's briefing
+#[tauri::command]
+pub async fn get_morning_briefing(state: State<'_, DbState>) -> Result<MorningBriefing, ApiError> {
+    let pool = &state.0;
+
+    // The insight is a nice-to-have; the briefing stands without it
+    let insight = match AgentFacade::insights(pool).await {
+        Ok(insights) => insights.into_iter().next(),
+        Err(e) => {
+            log::warn!("No insight for the morning briefing: {}", e.message);
+            None
+        }
+    };
+
+    morning_briefing(pool, insight).await
+}
+
+pub(crate) async fn morning_briefing(pool: &Pool<Sqlite>, insight: Option<Insight>) -> Result<MorningBriefing, ApiError> {
+    let date = Local::now().format("%Y-%m-%d").to_string();
+
+    let mut agenda: Vec<CalendarItem> = get_calendar_items_for_pool(
+        pool,
+        CalendarQuery {
+            start_date: date.clone(),
+            end_date: date.clone(),
+            include_assignments: Some(false),
+            include_exams: Some(true),
+        },
+    )
+    .await?
+    .into_iter()
+    .filter(|item| item.source != "plan_block" || item.status.as_deref() != Some("suggested"))
+    .collect();
+    agenda.sort_by(|a, b| (!a.all_day, &a.start_at).cmp(&(!b.all_day, &b.start_at)));
+
+    let due = due_items(pool).await?;
+    let big_three = IntelligenceAgent::get_big_three(pool).await.map_err(ApiError::internal)?;
+    let suggested_big_three = if big_three.is_empty() {
+        suggest_big_three(pool).await?
+    } else {
+        Vec::new()
+    };
+    let recovery = recovery_status(pool).await?;
+
+    let mut briefing = MorningBriefing {
+        date,
+        agenda,
+        due,
+        big_three,
+        suggested_big_three,
+        recovery,
+        insight,
+        text: String::new(),
+    };
+    briefing.text = render_text(&briefing);
+    Ok(briefing)
+}
+
+async fn due_items(pool: &Pool<Sqlite>) -> Result<Vec<DueItem>, ApiError> {
+    let rows: Vec<(String, i64, String, String, String, i64)> = sqlx::query_as(
+        r#"
+        SELECT 'assignment', a.id, a.title, c.name, a.due_date,
+               CAST(julianday(date(a.due_date)) - julianday(date('now', 'localtime')) AS INTEGER) AS days_left
+        FROM assignments a
+        JOIN courses c ON c.id = a.course_id
+        WHERE a.is_completed = 0 AND c.is_active = 1 AND a.due_date IS NOT NULL
+          AND date(a.due_date) <= date('now', 'localtime', ?)
+        UNION ALL
+        SELECT 'exam', e.id, e.title, c.name, e.exam_date,
+               CAST(julianday(date(e.exam_date)) - julianday(date('now', 'localtime')) AS INTEGER)
+        FROM exams e
+        JOIN courses c ON c.id = e.course_id
+        WHERE c.is_active = 1 AND e.exam_date IS NOT NULL
+          AND date(e.exam_date) BETWEEN date('now', 'localtime') AND date('now', 'localtime', ?)
+        ORDER BY days_left, 1 DESC
+        "#,
+    )
+    .bind(format!("+{} days", ASSIGNMENT_DUE_DAYS))
+    .bind(format!("+{} days", EXAM_DUE_DAYS))
+    .fetch_all(pool)
+    .await
+    .map_err(ApiError::from)?;
+
+    Ok(rows
+        .into_iter()
+        .map(|(kind, id, title, course_name, due_at, days_left)| DueItem {
+            kind,
+            id,
+            title,
+            course_name,
+            due_at,
+            days_left,
+        })
+        .collect())
+}
+
+/// Top non-Big-Three entries of the next-task ranking, for the whole day
+async fn suggest_big_three(pool: &Pool<Sqlite>) -> Result<Vec<SuggestedGoal>, ApiError> {
+    let tasks = next_best_tasks(pool, 24 * 60, None).await?;
+    Ok(tasks
+        .into_iter()
+        .filter(|t| matches!(t.kind, NextTaskKind::Assignment | NextTaskKind::PlanBlock))
+        .take(3)
+        .map(|t| SuggestedGoal {
+            title: t.title,
+            category: "academic".to_string(),
+            reason: t.reasons.join("; "),
+        })
+        .collect())
+}
+
+async fn recovery_status(pool: &Pool<Sqlite>) -> Result<RecoveryStatus, ApiError> {
+    let (workout_minutes, baseline_minutes): (i64, i64) = sqlx::query_as(
+        r#"
+        SELECT COALESCE(SUM(CASE WHEN logged_at >= datetime('now', ?) THEN duration_minutes END), 0),
+               COALESCE(SUM(CASE WHEN logged_at < datetime('now', ?) THEN duration_minutes END), 0)
+        FROM workouts
+        WHERE logged_at >= datetime('now', ?)
+        "#,
+    )
+    .bind(format!("-{} days", RECOVERY_WINDOW_DAYS))
+    .bind(format!("-{} days", RECOVERY_WINDOW_DAYS))
+    .bind(format!("-{} days", BASELINE_DAYS + RECOVERY_WINDOW_DAYS))
+    .fetch_one(pool)
+    .await
+    .map_err(ApiError::from)?;
+    let typical_workout_minutes = baseline_minutes as f64 * RECOVERY_WINDOW_DAYS as f64 / BASELINE_DAYS as f64;
+
+    let study_minutes_yesterday: i64 = sqlx::query_scalar(
+        r#"
+        SELECT COALESCE(SUM(duration_minutes), 0) FROM sessions
+        WHERE session_type = 'study' AND date(started_at, 'localtime') = date('now', 'localtime', '-1 day')
+        "#,
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(ApiError::from)?;
+
+    let energy: Option<i64> = sqlx::query_scalar(
+        "SELECT energy FROM check_ins WHERE energy IS NOT NULL AND checked_in_at >= datetime('now', '-1 day') ORDER BY checked_in_at DESC LIMIT 1",
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(ApiError::from)?
+    .flatten();
+
+    // Training well above the usual load only counts once there is a usual load
+    let overtrained = typical_workout_minutes >= 30.0 && workout_minutes as f64 > 1.5 * typical_workout_minutes;
+    let (level, note) = if energy.is_some_and(|e| e <= 3) {
+        (RecoveryLevel::Strained, "Energy is low; keep today light".to_string())
+    } else if overtrained {
+        (
+            RecoveryLevel::Strained,
+            format!("{} workout minutes in three days is well above usual; consider a rest day", workout_minutes),
+        )
+    } else if study_minutes_yesterday >= HEAVY_STUDY_MINUTES {
+        (
+            RecoveryLevel::Strained,
+            format!("{:.1} hours of study yesterday; plan breaks", study_minutes_yesterday as f64 / 60.0),
+        )
+    } else if energy.is_some_and(|e| e >= 7) || (energy.is_none() && workout_minutes == 0) {
+        (RecoveryLevel::Fresh, "Well rested; a good day for hard work".to_string())
+    } else {
+        (RecoveryLevel::Normal, "Recovered as usual".to_string())
+    };
+
+    Ok(RecoveryStatus {
+        level,
+        workout_minutes,
+        typical_workout_minutes,
+        study_minutes_yesterday,
+        energy,
+        note,
+    })
+}
+
+fn local_time(value: &str) -> Option<String> {
+    parse_datetime_utc(value).map(|t| t.with_timezone(&Local).format("%H:%M").to_string())
+}
+
+fn render_text(briefing: &MorningBriefing) -> String {
+    let mut lines = Vec::new();
+
+    let timed: Vec<&CalendarItem> = briefing.agenda.iter().filter(|i| !i.all_day).collect();
+    match timed.first() {
+        Some(first) => lines.push(format!(
+            "{} on the agenda, starting {} with {}",
+            timed.len(),
+            local_time(&first.start_at).unwrap_or_default(),
+            first.title
+        )),
+        None => lines.push("Nothing scheduled today".to_string()),
+    }
+
+    if !briefing.due.is_empty() {
+        let due: Vec<String> = briefing
+            .due
+            .iter()
+            .map(|d| match d.days_left {
+                n if n < 0 => format!("{} (overdue)", d.title),
+                0 => format!("{} (today)", d.title),
+                1 => format!("{} (tomorrow)", d.title),
+                n => format!("{} (in {} days)", d.title, n),
+            })
+            .collect();
+        lines.push(format!("Due: {}", due.join(", ")));
+    }
+
+    let goals: Vec<&str> = if briefing.big_three.is_empty() {
+        briefing.suggested_big_three.iter().map(|g| g.title.as_str()).collect()
+    } else {
+        briefing.big_three.iter().map(|g| g.title.as_str()).collect()
+    };
+    if !goals.is_empty() {
+        let label = if briefing.big_three.is_empty() { "Suggested Big Three" } else { "Big Three" };
+        lines.push(format!("{}: {}", label, goals.join(", ")));
+    }
+
+    lines.push(briefing.recovery.note.clone());
+    if let Some(insight) = &briefing.insight {
+        lines.push(insight.message.clone());
+    }
+
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+    use std::str::FromStr;
+
+    async fn setup_pool_with_migrations() -> Pool<Sqlite> {
+        let options = SqliteConnectOptions::from_str("sqlite::memory:")
+            .unwrap()
+            .foreign_keys(false);
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect_with(options)
+            .await
+            .expect("Failed to connect to in-memory DB");
+
+        crate::db::migrations::run_migrations(&pool)
+            .await
+            .expect("Failed to run migrations");
+
+        pool
+    }
+
+    #[tokio::test]
+    async fn briefing_lists_due_items_and_suggests_big_three() {
+        let pool = setup_pool_with_migrations().await;
+        let course: i64 = sqlx::query_scalar("INSERT INTO courses (name) VALUES ('Biology') RETURNING id")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        sqlx::query(
+            "INSERT INTO assignments (course_id, title, due_date, priority) VALUES
+             (?, 'Lab write-up', date('now', 'localtime', '+1 day'), 'high'),
+             (?, 'Late quiz', date('now', 'localtime', '-1 day'), 'medium'),
+             (?, 'Term paper', date('now', 'localtime', '+5 days'), 'high')",
+        )
+        .bind(course)
+        .bind(course)
+        .bind(course)
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query("INSERT INTO exams (course_id, title, exam_date) VALUES (?, 'Midterm', date('now', 'localtime', '+4 days'))")
+            .bind(course)
+            .execute(&pool)
+            .await
+            .unwrap();
+        let tomorrow = (Local::now().date_naive() + Duration::days(1)).format("%Y-%m-%d").to_string();
+        sqlx::query("INSERT INTO check_ins (energy, checked_in_at) VALUES (2, datetime('now', '-2 hours'))")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let briefing = morning_briefing(&pool, None).await.unwrap();
+        let due: Vec<(&str, i64)> = briefing.due.iter().map(|d| (d.title.as_str(), d.days_left)).collect();
+        assert_eq!(due, vec![("Late quiz", -1), ("Lab write-up", 1), ("Midterm", 4)]);
+        assert!(briefing.due[1].due_at.starts_with(&tomorrow));
+
+        assert!(briefing.big_three.is_empty());
+        assert_eq!(briefing.suggested_big_three.len(), 3);
+        assert_eq!(briefing.suggested_big_three[0].title, "Late quiz");
+
+        assert_eq!(briefing.recovery.level, RecoveryLevel::Strained);
+        assert!(briefing.text.contains("Due: Late quiz (overdue), Lab write-up (tomorrow), Midterm (in 4 days)"));
+        assert!(briefing.text.contains("Suggested Big Three: Late quiz"));
+
+        // Once the Big Three are set there is nothing to suggest
+        IntelligenceAgent::set_big_three(&pool, vec![("Lab write-up".to_string(), None, None)])
+            .await
+            .unwrap();
+        let briefing = morning_briefing(&pool, None).await.unwrap();
+        assert_eq!(briefing.big_three.len(), 1);
+        assert!(briefing.suggested_big_three.is_empty());
+    }
+}
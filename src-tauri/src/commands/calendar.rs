@@ -1,8 +1,21 @@
+use std::collections::HashMap;
+
+use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, Offset, Utc};
 use tauri::State;
-use crate::{DbState, error::ApiError};
+use crate::{DbState, error::ApiError, models::category::Category};
 use serde::Serialize;
+use crate::commands::categories::category_map;
+use crate::commands::export::validate_path;
+use crate::services::{calendar_feed, clock};
 use crate::utils::parse_datetime_to_rfc3339;
 
+/// TZID of the exported VTIMEZONE, which is built from the system
+/// timezone's offsets; a name of our own keeps clients from substituting
+/// their own rules for it
+const ICS_TZID: &str = "Life-OS-Local";
+/// iCalendar weekday codes, from Sunday
+const ICS_WEEKDAYS: [&str; 7] = ["SU", "MO", "TU", "WE", "TH", "FR", "SA"];
+
 /// A unified calendar item for frontend rendering
 #[derive(Debug, Serialize, Clone)]
 pub struct CalendarItem {
@@ -16,6 +29,7 @@ pub struct CalendarItem {
     pub course_id: Option<i64>,
     pub course_name: Option<String>,
     pub category: Option<String>,
+    pub busy: bool,              // From the category; unknown categories count as busy
     pub status: Option<String>,  // For plan blocks: suggested/accepted/locked
     pub locked: bool,
     pub editable: bool,
@@ -32,25 +46,266 @@ pub struct CalendarQuery {
     pub include_exams: Option<bool>,
 }
 
+#[derive(Debug, Serialize)]
+pub struct IcsExportResult {
+    pub path: String,
+    /// VEVENTs written; a weekly series counts once
+    pub events: usize,
+}
+
 #[tauri::command]
 pub async fn get_calendar_items(
     state: State<'_, DbState>,
     query: CalendarQuery,
 ) -> Result<Vec<CalendarItem>, ApiError> {
-    get_calendar_items_for_test(&state.0, query).await
+    get_calendar_items_for_pool(&state.0, query).await
+}
+
+/// Write everything `get_calendar_items` returns for the query to an .ics
+/// file. Weekly course meetings and recurring events become one RRULE series
+/// each; timed events are in a VTIMEZONE matching the system timezone.
+#[tauri::command]
+pub async fn export_calendar_ics(
+    state: State<'_, DbState>,
+    query: CalendarQuery,
+    path: String,
+) -> Result<IcsExportResult, ApiError> {
+    let path = validate_path(&path)?;
+    let (ics, events) = render_ics(&state.0, query).await?;
+    tokio::fs::write(&path, ics)
+        .await
+        .map_err(|e| ApiError::internal(format!("Failed to write calendar: {}", e)))?;
+    Ok(IcsExportResult { path: path.to_string_lossy().into_owned(), events })
 }
 
-async fn get_calendar_items_for_test(
+/// The calendar items as an iCalendar document, with the number of VEVENTs
+pub(crate) async fn render_ics(
+    pool: &sqlx::Pool<sqlx::Sqlite>,
+    query: CalendarQuery,
+) -> Result<(String, usize), ApiError> {
+    let items = get_calendar_items_for_pool(pool, query).await?;
+
+    // Occurrences of a weekly series share their id up to the date suffix
+    let mut index: HashMap<&str, usize> = HashMap::new();
+    let mut series: Vec<(&str, Vec<&CalendarItem>)> = Vec::new();
+    for item in &items {
+        let key = match item.source.as_str() {
+            "course_meeting" | "calendar_event" if item.id.matches('_').count() == 2 => {
+                item.id.rsplit_once('_').map_or(item.id.as_str(), |(key, _)| key)
+            }
+            _ => item.id.as_str(),
+        };
+        match index.get(key) {
+            Some(&i) => series[i].1.push(item),
+            None => {
+                index.insert(key, series.len());
+                series.push((key, vec![item]));
+            }
+        }
+    }
+
+    let stamp = calendar_feed::utc_stamp(&clock::now().with_timezone(&Utc));
+    let mut events = Vec::new();
+    let (mut first, mut last): (Option<DateTime<Utc>>, Option<DateTime<Utc>>) = (None, None);
+    for (key, occurrences) in &series {
+        let Some(lines) = ics_event(key, occurrences, &stamp) else {
+            continue;
+        };
+        for occurrence in occurrences.iter().filter(|o| !o.all_day) {
+            for time in [&occurrence.start_at, &occurrence.end_at] {
+                if let Ok(time) = DateTime::parse_from_rfc3339(time) {
+                    let time = time.with_timezone(&Utc);
+                    first = Some(first.map_or(time, |f| f.min(time)));
+                    last = Some(last.map_or(time, |l| l.max(time)));
+                }
+            }
+        }
+        events.push(lines);
+    }
+
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//Life OS//Calendar Export//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+        "METHOD:PUBLISH".to_string(),
+        "X-WR-CALNAME:Life OS".to_string(),
+    ];
+    if let (Some(first), Some(last)) = (first, last) {
+        lines.extend(vtimezone(first, last));
+    }
+    let count = events.len();
+    lines.extend(events.into_iter().flatten());
+    lines.push("END:VCALENDAR".to_string());
+
+    let ics = lines.iter().map(|l| calendar_feed::fold(l)).collect::<Vec<_>>().join("\r\n") + "\r\n";
+    Ok((ics, count))
+}
+
+/// One VEVENT for an item, or a weekly series when it has several occurrences
+fn ics_event(key: &str, occurrences: &[&CalendarItem], stamp: &str) -> Option<Vec<String>> {
+    let item = occurrences.first()?;
+    let mut lines = vec![
+        "BEGIN:VEVENT".to_string(),
+        format!("UID:{}@life-os", key),
+        format!("DTSTAMP:{}", stamp),
+    ];
+
+    if item.all_day {
+        // All-day items end on the (exclusive) next day
+        let date = |value: &str| value.get(..10).and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok());
+        let (start, end) = (date(&item.start_at)?, date(&item.end_at)?);
+        lines.push(format!("DTSTART;VALUE=DATE:{}", start.format("%Y%m%d")));
+        lines.push(format!("DTEND;VALUE=DATE:{}", (end.max(start) + Duration::days(1)).format("%Y%m%d")));
+    } else {
+        let local = |value: &str| DateTime::parse_from_rfc3339(value).ok().map(|t| t.with_timezone(&Local));
+        let (start, end) = (local(&item.start_at)?, local(&item.end_at)?);
+        lines.push(format!("DTSTART;TZID={}:{}", ICS_TZID, start.format("%Y%m%dT%H%M%S")));
+        lines.push(format!("DTEND;TZID={}:{}", ICS_TZID, end.format("%Y%m%dT%H%M%S")));
+
+        if occurrences.len() > 1 {
+            let starts: Vec<DateTime<Local>> = occurrences.iter().filter_map(|o| local(&o.start_at)).collect();
+            // Monday-first, as calendar apps list them
+            let mut days: Vec<usize> = starts.iter().map(|s| s.weekday().num_days_from_sunday() as usize).collect();
+            days.sort_by_key(|d| (d + 6) % 7);
+            days.dedup();
+            let by_day = days.iter().map(|d| ICS_WEEKDAYS[*d]).collect::<Vec<_>>().join(",");
+            let until = starts.iter().max()?.with_timezone(&Utc);
+            lines.push(format!(
+                "RRULE:FREQ=WEEKLY;BYDAY={};UNTIL={}",
+                by_day,
+                calendar_feed::utc_stamp(&until)
+            ));
+        }
+    }
+
+    lines.push(format!("SUMMARY:{}", calendar_feed::escape(&item.title)));
+    let location = item
+        .metadata_json
+        .as_deref()
+        .and_then(|m| serde_json::from_str::<serde_json::Value>(m).ok())
+        .and_then(|m| m.get("location").and_then(|l| l.as_str()).map(str::to_string));
+    if let Some(location) = location {
+        lines.push(format!("LOCATION:{}", calendar_feed::escape(&location)));
+    }
+    if let Some(category) = &item.category {
+        lines.push(format!("CATEGORIES:{}", calendar_feed::escape(category)));
+    }
+    let tentative = item.source == "plan_block" && item.status.as_deref() == Some("suggested");
+    lines.push(format!("STATUS:{}", if tentative { "TENTATIVE" } else { "CONFIRMED" }));
+    lines.push(format!("TRANSP:{}", if item.busy { "OPAQUE" } else { "TRANSPARENT" }));
+    lines.push("END:VEVENT".to_string());
+    Some(lines)
+}
+
+/// VTIMEZONE for the system timezone, with an observance for each offset
+/// change between `from` and `to`
+fn vtimezone(from: DateTime<Utc>, to: DateTime<Utc>) -> Vec<String> {
+    let offset_at = |time: DateTime<Utc>| time.with_timezone(&Local).offset().fix().local_minus_utc();
+    let format_offset = |seconds: i32| {
+        let sign = if seconds < 0 { '-' } else { '+' };
+        format!("{}{:02}{:02}", sign, seconds.abs() / 3600, seconds.abs() % 3600 / 60)
+    };
+
+    // (offset before, offset after, UTC instant of the change)
+    let initial = offset_at(from);
+    let mut changes: Vec<(i32, i32, DateTime<Utc>)> = Vec::new();
+    let mut current = initial;
+    let mut hour = DateTime::from_timestamp(from.timestamp() / 3600 * 3600, 0).unwrap_or(from);
+    while hour <= to {
+        let next = hour + Duration::hours(1);
+        let offset = offset_at(next);
+        if offset != current {
+            // Narrow the change down to the minute
+            let (mut lo, mut hi) = (0, 60);
+            while hi - lo > 1 {
+                let mid = (lo + hi) / 2;
+                if offset_at(hour + Duration::minutes(mid)) == current {
+                    lo = mid;
+                } else {
+                    hi = mid;
+                }
+            }
+            changes.push((current, offset, hour + Duration::minutes(hi)));
+            current = offset;
+        }
+        hour = next;
+    }
+
+    let standard = changes.iter().map(|(_, after, _)| *after).chain([initial]).min().unwrap_or(initial);
+    let observance = |before: i32, after: i32, start: String| {
+        let kind = if after > standard { "DAYLIGHT" } else { "STANDARD" };
+        vec![
+            format!("BEGIN:{}", kind),
+            format!("DTSTART:{}", start),
+            format!("TZOFFSETFROM:{}", format_offset(before)),
+            format!("TZOFFSETTO:{}", format_offset(after)),
+            format!("END:{}", kind),
+        ]
+    };
+
+    let mut lines = vec!["BEGIN:VTIMEZONE".to_string(), format!("TZID:{}", ICS_TZID)];
+    lines.extend(observance(initial, initial, "19700101T000000".to_string()));
+    for (before, after, at) in changes {
+        // Observance starts are wall-clock times in the offset before the change
+        let wall = (at + Duration::seconds(before as i64)).naive_utc();
+        lines.extend(observance(before, after, wall.format("%Y%m%dT%H%M%S").to_string()));
+    }
+    lines.push("END:VTIMEZONE".to_string());
+    lines
+}
+
+/// Course color first, then the category's; busy unless the category says free
+fn category_style(
+    categories: &HashMap<String, Category>,
+    category: &str,
+    course_color: Option<String>,
+) -> (Option<String>, bool) {
+    let found = categories.get(category);
+    (
+        course_color.or_else(|| found.map(|c| c.color.clone())),
+        found.map_or(true, |c| c.is_busy),
+    )
+}
+
+/// Dates in `start_date..=end_date` a weekly rule ("WEEKLY:0,2,4", days
+/// from Sunday) falls on; other rule kinds expand to nothing
+pub(crate) fn expand_weekly_rule(rule: &str, start_date: NaiveDate, end_date: NaiveDate) -> Vec<NaiveDate> {
+    let Some(days_str) = rule.strip_prefix("WEEKLY:") else {
+        return Vec::new();
+    };
+    let days: Vec<i64> = days_str.split(',').filter_map(|d| d.trim().parse().ok()).collect();
+    weekday_occurrences(&days, start_date, end_date)
+}
+
+/// Dates in `start_date..=end_date` whose weekday (days from Sunday) is in `days`
+fn weekday_occurrences(days: &[i64], start_date: NaiveDate, end_date: NaiveDate) -> Vec<NaiveDate> {
+    start_date
+        .iter_days()
+        .take_while(|d| *d <= end_date)
+        .filter(|d| days.contains(&(d.weekday().num_days_from_sunday() as i64)))
+        .collect()
+}
+
+/// Local start and end of an occurrence on `date`; an end at or before the
+/// start ("22:00"-"01:00") finishes the next day
+pub(crate) fn occurrence_bounds(date: NaiveDate, start_time: &str, end_time: &str) -> (String, String) {
+    let end_date = if end_time <= start_time { date + chrono::Duration::days(1) } else { date };
+    (format!("{}T{}:00", date, start_time), format!("{}T{}:00", end_date, end_time))
+}
+
+pub(crate) async fn get_calendar_items_for_pool(
     pool: &sqlx::Pool<sqlx::Sqlite>,
     query: CalendarQuery,
 ) -> Result<Vec<CalendarItem>, ApiError> {
     let mut items: Vec<CalendarItem> = Vec::new();
 
     // Parse dates for day-of-week calculations
-    let start_date = chrono::NaiveDate::parse_from_str(&query.start_date, "%Y-%m-%d")
+    let start_date = NaiveDate::parse_from_str(&query.start_date, "%Y-%m-%d")
         .map_err(|_| ApiError::validation("Invalid start_date format"))?;
-    let end_date = chrono::NaiveDate::parse_from_str(&query.end_date, "%Y-%m-%d")
+    let end_date = NaiveDate::parse_from_str(&query.end_date, "%Y-%m-%d")
         .map_err(|_| ApiError::validation("Invalid end_date format"))?;
+    let categories = category_map(pool).await?;
 
     // 1. Course meetings (expand weekly recurrence)
     let meetings = sqlx::query_as::<_, (i64, i64, i64, String, String, Option<String>, Option<String>, Option<String>, Option<String>)>(
@@ -58,7 +313,7 @@ async fn get_calendar_items_for_test(
                   cm.location, cm.meeting_type, c.name as course_name, c.color
            FROM course_meetings cm
            JOIN courses c ON c.id = cm.course_id
-           WHERE c.is_active = 1"#
+           WHERE c.is_active = 1 AND cm.archived_at IS NULL"#
     )
     .fetch_all(pool)
     .await
@@ -66,39 +321,35 @@ async fn get_calendar_items_for_test(
 
     for (id, course_id, day_of_week, start_time, end_time, location, meeting_type, course_name, color) in meetings {
         // Expand to each occurrence in the date range
-        let mut current = start_date;
-        while current <= end_date {
-            use chrono::Datelike;
-            if current.weekday().num_days_from_sunday() as i64 == day_of_week {
-                let start_at = format!("{}T{}:00", current, start_time);
-                let end_at = format!("{}T{}:00", current, end_time);
-
-                let title = format!("{} - {}",
-                    course_name.as_deref().unwrap_or("Course"),
-                    meeting_type.as_deref().unwrap_or("Class")
-                );
+        for current in weekday_occurrences(&[day_of_week], start_date, end_date) {
+            let (start_at, end_at) = occurrence_bounds(current, &start_time, &end_time);
 
-                let normalized_start = parse_datetime_to_rfc3339(&start_at).unwrap_or(start_at);
-                let normalized_end = parse_datetime_to_rfc3339(&end_at).unwrap_or(end_at);
+            let title = format!("{} - {}",
+                course_name.as_deref().unwrap_or("Course"),
+                meeting_type.as_deref().unwrap_or("Class")
+            );
 
-                items.push(CalendarItem {
-                    id: format!("cm_{}_{}", id, current),
-                    source: "course_meeting".to_string(),
-                    title,
-                    start_at: normalized_start,
-                    end_at: normalized_end,
-                    all_day: false,
-                    color: color.clone(),
-                    course_id: Some(course_id),
-                    course_name: course_name.clone(),
-                    category: Some("class".to_string()),
-                    status: None,
-                    locked: true,
-                    editable: false,
-                    metadata_json: location.as_ref().map(|l| format!(r#"{{"location":"{}"}}"#, l)),
-                });
-            }
-            current += chrono::Duration::days(1);
+            let normalized_start = parse_datetime_to_rfc3339(&start_at).unwrap_or(start_at);
+            let normalized_end = parse_datetime_to_rfc3339(&end_at).unwrap_or(end_at);
+            let (color, busy) = category_style(&categories, "class", color.clone());
+
+            items.push(CalendarItem {
+                id: format!("cm_{}_{}", id, current),
+                source: "course_meeting".to_string(),
+                title,
+                start_at: normalized_start,
+                end_at: normalized_end,
+                all_day: false,
+                color,
+                course_id: Some(course_id),
+                course_name: course_name.clone(),
+                category: Some("class".to_string()),
+                busy,
+                status: None,
+                locked: true,
+                editable: false,
+                metadata_json: location.as_ref().map(|l| format!(r#"{{"location":"{}"}}"#, l)),
+            });
         }
     }
 
@@ -112,46 +363,34 @@ async fn get_calendar_items_for_test(
     .map_err(ApiError::from)?;
 
     for (id, title, start_at, end_at, rrule, start_time, end_time, category, locked) in events {
+        let (color, busy) = category_style(&categories, &category, None);
         if let Some(ref rule) = rrule {
             // Recurring event: parse rrule like "WEEKLY:0,2,4"
-            if rule.starts_with("WEEKLY:") {
-                let days_str = rule.strip_prefix("WEEKLY:").unwrap();
-                let days: Vec<i64> = days_str.split(',')
-                    .filter_map(|d| d.parse().ok())
-                    .collect();
-
-                let st = start_time.as_deref().unwrap_or("09:00");
-                let et = end_time.as_deref().unwrap_or("10:00");
-
-                let mut current = start_date;
-                while current <= end_date {
-                    use chrono::Datelike;
-                    let dow = current.weekday().num_days_from_sunday() as i64;
-                    if days.contains(&dow) {
-                        let start_at = format!("{}T{}:00", current, st);
-                        let end_at = format!("{}T{}:00", current, et);
-                        let normalized_start = parse_datetime_to_rfc3339(&start_at).unwrap_or(start_at);
-                        let normalized_end = parse_datetime_to_rfc3339(&end_at).unwrap_or(end_at);
-
-                        items.push(CalendarItem {
-                            id: format!("ce_{}_{}", id, current),
-                            source: "calendar_event".to_string(),
-                            title: title.clone(),
-                            start_at: normalized_start,
-                            end_at: normalized_end,
-                            all_day: false,
-                            color: None,
-                            course_id: None,
-                            course_name: None,
-                            category: Some(category.clone()),
-                            status: None,
-                            locked: locked.unwrap_or(0) == 1,
-                            editable: locked.unwrap_or(0) != 1,
-                            metadata_json: None,
-                        });
-                    }
-                    current += chrono::Duration::days(1);
-                }
+            let st = start_time.as_deref().unwrap_or("09:00");
+            let et = end_time.as_deref().unwrap_or("10:00");
+
+            for current in expand_weekly_rule(rule, start_date, end_date) {
+                let (start_at, end_at) = occurrence_bounds(current, st, et);
+                let normalized_start = parse_datetime_to_rfc3339(&start_at).unwrap_or(start_at);
+                let normalized_end = parse_datetime_to_rfc3339(&end_at).unwrap_or(end_at);
+
+                items.push(CalendarItem {
+                    id: format!("ce_{}_{}", id, current),
+                    source: "calendar_event".to_string(),
+                    title: title.clone(),
+                    start_at: normalized_start,
+                    end_at: normalized_end,
+                    all_day: false,
+                    color: color.clone(),
+                    course_id: None,
+                    course_name: None,
+                    category: Some(category.clone()),
+                    busy,
+                    status: None,
+                    locked: locked.unwrap_or(0) == 1,
+                    editable: locked.unwrap_or(0) != 1,
+                    metadata_json: None,
+                });
             }
         } else if let (Some(sa), Some(ea)) = (start_at, end_at) {
             // One-off event: check if in range
@@ -168,10 +407,11 @@ async fn get_calendar_items_for_test(
                     start_at: normalized_start,
                     end_at: normalized_end,
                     all_day: is_all_day,
-                    color: None,
+                    color,
                     course_id: None,
                     course_name: None,
                     category: Some(category.clone()),
+                    busy,
                     status: None,
                     locked: locked.unwrap_or(0) == 1,
                     editable: locked.unwrap_or(0) != 1,
@@ -201,6 +441,7 @@ async fn get_calendar_items_for_test(
 
         let normalized_start = parse_datetime_to_rfc3339(&start_at).unwrap_or(start_at);
         let normalized_end = parse_datetime_to_rfc3339(&end_at).unwrap_or(end_at);
+        let (color, busy) = category_style(&categories, &block_type, color);
 
         items.push(CalendarItem {
             id: format!("wpb_{}", id),
@@ -213,6 +454,7 @@ async fn get_calendar_items_for_test(
             course_id,
             course_name: None,
             category: Some(block_type),
+            busy,
             status,
             locked: is_locked,
             editable: !is_locked,
@@ -238,6 +480,7 @@ async fn get_calendar_items_for_test(
         for (id, title, due_date, course_id, color) in assignments {
             let normalized_start = parse_datetime_to_rfc3339(&due_date).unwrap_or(due_date.clone());
             let normalized_end = parse_datetime_to_rfc3339(&due_date).unwrap_or(due_date);
+            let (color, busy) = category_style(&categories, "deadline", color);
 
             items.push(CalendarItem {
                 id: format!("asgn_{}", id),
@@ -250,6 +493,7 @@ async fn get_calendar_items_for_test(
                 course_id: Some(course_id),
                 course_name: None,
                 category: Some("deadline".to_string()),
+                busy,
                 status: None,
                 locked: true,
                 editable: false,
@@ -296,6 +540,7 @@ async fn get_calendar_items_for_test(
 
                 let normalized_start = parse_datetime_to_rfc3339(&ed).unwrap_or(ed);
                 let normalized_end = parse_datetime_to_rfc3339(&end_at).unwrap_or(end_at);
+                let (color, busy) = category_style(&categories, "exam", color);
 
                 items.push(CalendarItem {
                     id: format!("exam_{}", id),
@@ -308,6 +553,7 @@ async fn get_calendar_items_for_test(
                     course_id: Some(course_id),
                     course_name: None,
                     category: Some("exam".to_string()),
+                    busy,
                     status: None,
                     locked: true,
                     editable: false,
@@ -326,6 +572,8 @@ async fn get_calendar_items_for_test(
 #[cfg(test)]
 mod tests {
     use super::*;
+    use chrono::TimeZone;
+    use proptest::prelude::*;
     use sqlx::sqlite::SqlitePoolOptions;
 
     async fn setup_db() -> sqlx::Pool<sqlx::Sqlite> {
@@ -349,6 +597,56 @@ mod tests {
         pool
     }
 
+    fn date_in_range() -> impl Strategy<Value = (NaiveDate, NaiveDate)> {
+        (0i64..130 * 366, 0i64..62).prop_map(|(offset, span)| {
+            let start = NaiveDate::from_ymd_opt(1970, 1, 1).unwrap() + chrono::Duration::days(offset);
+            (start, start + chrono::Duration::days(span))
+        })
+    }
+
+    fn time_of_day() -> impl Strategy<Value = String> {
+        (0u32..24, 0u32..60).prop_map(|(h, m)| format!("{:02}:{:02}", h, m))
+    }
+
+    proptest! {
+        #[test]
+        fn weekly_rules_hit_exactly_the_listed_weekdays(
+            days in prop::collection::btree_set(0i64..7, 0..=7),
+            (start, end) in date_in_range(),
+        ) {
+            let rule = format!("WEEKLY:{}", days.iter().map(|d| d.to_string()).collect::<Vec<_>>().join(","));
+            let dates = expand_weekly_rule(&rule, start, end);
+
+            let expected = start
+                .iter_days()
+                .take_while(|d| *d <= end)
+                .filter(|d| days.contains(&(d.weekday().num_days_from_sunday() as i64)))
+                .count();
+            prop_assert_eq!(dates.len(), expected);
+            prop_assert!(dates.windows(2).all(|w| w[0] < w[1]));
+            prop_assert!(dates.iter().all(|d| *d >= start && *d <= end));
+        }
+
+        #[test]
+        fn occurrences_end_after_they_start(
+            (date, _) in date_in_range(),
+            start_time in time_of_day(),
+            end_time in time_of_day(),
+        ) {
+            let (start_at, end_at) = occurrence_bounds(date, &start_time, &end_time);
+            let start = chrono::NaiveDateTime::parse_from_str(&start_at, "%Y-%m-%dT%H:%M:%S").unwrap();
+            let end = chrono::NaiveDateTime::parse_from_str(&end_at, "%Y-%m-%dT%H:%M:%S").unwrap();
+            prop_assert_eq!(start.date(), date);
+            prop_assert!(end > start && end - start <= chrono::Duration::days(1), "{} - {}", start_at, end_at);
+        }
+    }
+
+    #[test]
+    fn unknown_rules_expand_to_nothing() {
+        let day = NaiveDate::from_ymd_opt(2026, 2, 7).unwrap();
+        assert!(expand_weekly_rule("DAILY", day, day + chrono::Duration::days(7)).is_empty());
+    }
+
     fn is_rfc3339(value: &str) -> bool {
         value.contains('Z') || value.contains('+')
     }
@@ -365,7 +663,7 @@ mod tests {
         .await
         .unwrap();
 
-        let items = get_calendar_items_for_test(
+        let items = get_calendar_items_for_pool(
             &pool,
             CalendarQuery {
                 start_date: "2026-02-07".to_string(),
@@ -385,4 +683,99 @@ mod tests {
         assert!(is_rfc3339(&event.start_at));
         assert!(is_rfc3339(&event.end_at));
     }
+
+    #[tokio::test]
+    async fn calendar_items_take_category_colors_and_busy_flag() {
+        let pool = setup_db().await;
+        sqlx::query("UPDATE categories SET color = '#123456' WHERE name = 'personal'")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query(
+            r#"INSERT INTO calendar_events (title, start_at, end_at, category)
+               VALUES ('Dinner', '2026-02-07T18:00:00', '2026-02-07T19:00:00', 'personal'),
+                      ('Mystery', '2026-02-07T20:00:00', '2026-02-07T21:00:00', 'unlisted')"#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query("INSERT INTO courses (id, user_id, name, color) VALUES (1, 1, 'Physics', '#ff0000')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query(
+            r#"INSERT INTO week_plan_blocks (week_start_date, start_at, end_at, block_type, course_id, status)
+               VALUES ('2026-02-02', '2026-02-07T10:00:00', '2026-02-07T11:00:00', 'study', 1, 'accepted'),
+                      ('2026-02-02', '2026-02-07T11:00:00', '2026-02-07T11:15:00', 'break', NULL, 'accepted')"#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let items = get_calendar_items_for_pool(
+            &pool,
+            CalendarQuery {
+                start_date: "2026-02-07".to_string(),
+                end_date: "2026-02-07".to_string(),
+                include_assignments: Some(false),
+                include_exams: Some(false),
+            },
+        )
+        .await
+        .unwrap();
+        let find = |title: &str| items.iter().find(|i| i.title == title).unwrap();
+
+        assert_eq!(find("Dinner").color.as_deref(), Some("#123456"));
+        assert!(find("Dinner").busy);
+        assert_eq!(find("Mystery").color, None);
+        assert!(find("Mystery").busy);
+        // Course color wins over the study category color
+        assert_eq!(find("study").color.as_deref(), Some("#ff0000"));
+        assert!(!find("break").busy);
+        assert_eq!(find("break").color.as_deref(), Some("#94a3b8"));
+    }
+
+    #[tokio::test]
+    async fn ics_export_turns_weekly_meetings_into_one_series() {
+        let pool = setup_db().await;
+        sqlx::query("INSERT INTO courses (id, user_id, name) VALUES (1, 1, 'Physics')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        // Mondays and Wednesdays; 2026-02-02 is a Monday
+        sqlx::query(
+            r#"INSERT INTO course_meetings (course_id, day_of_week, start_time, end_time, location, meeting_type)
+               VALUES (1, 1, '09:00', '10:30', 'Room 1, east wing', 'Lecture'),
+                      (1, 3, '14:00', '15:00', NULL, 'Lab')"#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query("INSERT INTO exams (course_id, title, exam_date) VALUES (1, 'Midterm', '2026-02-12')")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let (ics, events) = render_ics(
+            &pool,
+            CalendarQuery {
+                start_date: "2026-02-02".to_string(),
+                end_date: "2026-02-15".to_string(),
+                include_assignments: Some(true),
+                include_exams: Some(true),
+            },
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(events, 3);
+        assert_eq!(ics.matches("BEGIN:VEVENT").count(), 3);
+        assert_eq!(ics.matches("BEGIN:VTIMEZONE").count(), 1);
+        assert!(ics.contains(&format!("DTSTART;TZID={}:20260202T090000", ICS_TZID)));
+        let until = Local.with_ymd_and_hms(2026, 2, 9, 9, 0, 0).unwrap().with_timezone(&Utc);
+        assert!(ics.contains(&format!("RRULE:FREQ=WEEKLY;BYDAY=MO;UNTIL={}", calendar_feed::utc_stamp(&until))));
+        assert!(ics.contains("LOCATION:Room 1\\, east wing"));
+        assert!(ics.contains("DTSTART;VALUE=DATE:20260212"));
+        assert!(ics.split("\r\n").all(|l| l.len() <= 75));
+    }
 }
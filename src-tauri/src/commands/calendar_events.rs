@@ -1,11 +1,26 @@
+use std::collections::HashMap;
+
+use chrono::{Duration, NaiveDate, NaiveDateTime};
 use tauri::State;
 use crate::{
     DbState,
+    db::filter::FilteredQuery,
     error::ApiError,
     models::calendar_event::CalendarEvent,
+    services::{
+        change_log::{self, Source},
+        clock,
+        ics_import::{self, IcsOccurrence},
+    },
     utils::{is_valid_time, parse_datetime_to_rfc3339},
 };
 
+/// Largest .ics file accepted
+const MAX_ICS_BYTES: u64 = 10 * 1024 * 1024;
+/// Recurring events are imported this many days either side of today
+const ICS_WINDOW_DAYS: i64 = 365;
+const MAX_SOURCE_LENGTH: usize = 60;
+
 #[cfg(test)]
 use sqlx::sqlite::SqlitePoolOptions;
 
@@ -41,10 +56,10 @@ pub async fn create_calendar_event(
     state: State<'_, DbState>,
     data: CalendarEventInput,
 ) -> Result<CalendarEvent, ApiError> {
-    create_calendar_event_for_test(&state.0, data).await
+    create_calendar_event_inner(&state.0, data).await
 }
 
-async fn create_calendar_event_for_test(
+pub(crate) async fn create_calendar_event_inner(
     pool: &sqlx::Pool<sqlx::Sqlite>,
     data: CalendarEventInput,
 ) -> Result<CalendarEvent, ApiError> {
@@ -130,25 +145,19 @@ pub async fn get_calendar_events(
 ) -> Result<Vec<CalendarEvent>, ApiError> {
     let pool = &state.0;
 
-    let events = if let Some(cat) = category {
-        sqlx::query_as::<_, CalendarEvent>(
-            "SELECT * FROM calendar_events WHERE category = ? ORDER BY start_at, start_time"
-        )
-        .bind(cat)
-        .fetch_all(pool)
-        .await
-    } else {
-        sqlx::query_as::<_, CalendarEvent>(
-            "SELECT * FROM calendar_events ORDER BY start_at, start_time"
-        )
+    let mut query = FilteredQuery::new("SELECT * FROM calendar_events")
+        .eq("category", category)
+        .order_by("start_at, start_time");
+
+    let events = query
+        .build_query_as::<CalendarEvent>()
         .fetch_all(pool)
         .await
-    };
-
-    events.map_err(|e| {
-        log::error!("Failed to fetch calendar events: {}", e);
-        ApiError::from_sqlx(e, "Failed to fetch calendar events")
-    })
+        .map_err(|e| {
+            log::error!("Failed to fetch calendar events: {}", e);
+            ApiError::from_sqlx(e, "Failed to fetch calendar events")
+        })?;
+    Ok(events)
 }
 
 #[tauri::command]
@@ -176,10 +185,10 @@ pub async fn update_calendar_event(
     id: i64,
     data: CalendarEventInput,
 ) -> Result<CalendarEvent, ApiError> {
-    update_calendar_event_for_test(&state.0, id, data).await
+    update_calendar_event_inner(&state.0, id, data).await
 }
 
-async fn update_calendar_event_for_test(
+pub(crate) async fn update_calendar_event_inner(
     pool: &sqlx::Pool<sqlx::Sqlite>,
     id: i64,
     data: CalendarEventInput,
@@ -281,6 +290,177 @@ pub async fn delete_calendar_event(
 
     Ok(true)
 }
+#[derive(Debug, serde::Serialize)]
+pub struct IcsImportResult {
+    pub source: String,
+    pub created: usize,
+    pub updated: usize,
+    pub unchanged: usize,
+    /// Rows of this source whose event or occurrence left the file
+    pub removed: usize,
+    /// Events skipped or imported only in part
+    pub warnings: Vec<String>,
+}
+
+/// Import the events of an .ics file, e.g. a semester schedule
+///
+/// Rows are tagged with `source` (the file name by default). Importing a
+/// file again under the same source updates its events in place and drops
+/// occurrences that were cancelled or removed; events of that source missing
+/// from the file entirely are kept.
+#[tauri::command]
+pub async fn import_ics_file(
+    state: State<'_, DbState>,
+    path: String,
+    source: Option<String>,
+    category: Option<String>,
+) -> Result<IcsImportResult, ApiError> {
+    let path = std::path::PathBuf::from(path.trim());
+    if !path.is_absolute() || !path.is_file() {
+        return Err(ApiError::validation("Import path must be an existing file"));
+    }
+    let size = tokio::fs::metadata(&path)
+        .await
+        .map_err(|e| ApiError::internal(format!("Failed to read {}: {}", path.display(), e)))?
+        .len();
+    if size > MAX_ICS_BYTES {
+        return Err(ApiError::validation("File is too large to be a calendar file"));
+    }
+    let text = tokio::fs::read_to_string(&path)
+        .await
+        .map_err(|e| ApiError::internal(format!("Failed to read {}: {}", path.display(), e)))?;
+
+    let source = source
+        .or_else(|| path.file_stem().map(|s| s.to_string_lossy().into_owned()))
+        .unwrap_or_default();
+    import_ics_inner(&state.0, &text, &source, category.as_deref(), clock::now().date_naive()).await
+}
+
+pub(crate) async fn import_ics_inner(
+    pool: &sqlx::Pool<sqlx::Sqlite>,
+    text: &str,
+    source: &str,
+    category: Option<&str>,
+    today: NaiveDate,
+) -> Result<IcsImportResult, ApiError> {
+    let source = source.trim();
+    if source.is_empty() || source.len() > MAX_SOURCE_LENGTH {
+        return Err(ApiError::validation(format!(
+            "Source must be between 1 and {} characters",
+            MAX_SOURCE_LENGTH
+        )));
+    }
+    let category = category.map(str::trim).filter(|c| !c.is_empty()).unwrap_or("busy");
+    let window = Duration::days(ICS_WINDOW_DAYS);
+    let calendar = ics_import::parse(text, today - window, today + window)?;
+
+    let mut result = IcsImportResult {
+        source: source.to_string(),
+        created: 0,
+        updated: 0,
+        unchanged: 0,
+        removed: 0,
+        warnings: calendar.warnings,
+    };
+
+    let mut tx = pool.begin().await.map_err(ApiError::from)?;
+    let rows: Vec<(i64, String, String)> = sqlx::query_as(
+        "SELECT id, external_uid, external_occurrence FROM calendar_events WHERE source = ? AND external_uid IS NOT NULL",
+    )
+    .bind(source)
+    .fetch_all(&mut *tx)
+    .await
+    .map_err(ApiError::from)?;
+    let mut existing: HashMap<(String, String), i64> =
+        rows.into_iter().map(|(id, uid, occurrence)| ((uid, occurrence), id)).collect();
+
+    for occurrence in &calendar.occurrences {
+        let (start_at, end_at) = ics_bounds(occurrence);
+        match existing.remove(&(occurrence.uid.clone(), occurrence.occurrence.clone())) {
+            Some(id) => {
+                let before = change_log::snapshot(&mut tx, "calendar_events", id).await?;
+                let changed = sqlx::query(
+                    r#"UPDATE calendar_events SET title = ?, start_at = ?, end_at = ?, notes = ?
+                       WHERE id = ? AND (title IS NOT ? OR start_at IS NOT ? OR end_at IS NOT ? OR notes IS NOT ?)"#,
+                )
+                .bind(&occurrence.title)
+                .bind(&start_at)
+                .bind(&end_at)
+                .bind(&occurrence.notes)
+                .bind(id)
+                .bind(&occurrence.title)
+                .bind(&start_at)
+                .bind(&end_at)
+                .bind(&occurrence.notes)
+                .execute(&mut *tx)
+                .await
+                .map_err(ApiError::from)?
+                .rows_affected()
+                    > 0;
+                if changed {
+                    let summary = format!("Updated \"{}\" from {}", occurrence.title, source);
+                    change_log::record(&mut tx, Source::IcsImport, "calendar_events", id, before, &summary).await?;
+                    result.updated += 1;
+                } else {
+                    result.unchanged += 1;
+                }
+            }
+            None => {
+                let id: i64 = sqlx::query_scalar(
+                    r#"INSERT INTO calendar_events (title, start_at, end_at, category, locked, notes, source, external_uid, external_occurrence)
+                       VALUES (?, ?, ?, ?, 1, ?, ?, ?, ?)
+                       RETURNING id"#,
+                )
+                .bind(&occurrence.title)
+                .bind(&start_at)
+                .bind(&end_at)
+                .bind(category)
+                .bind(&occurrence.notes)
+                .bind(source)
+                .bind(&occurrence.uid)
+                .bind(&occurrence.occurrence)
+                .fetch_one(&mut *tx)
+                .await
+                .map_err(|e| ApiError::from_sqlx(e, "Failed to import calendar event"))?;
+                let summary = format!("Imported \"{}\" from {}", occurrence.title, source);
+                change_log::record(&mut tx, Source::IcsImport, "calendar_events", id, None, &summary).await?;
+                result.created += 1;
+            }
+        }
+    }
+
+    // Occurrences of events still in the file that were cancelled, excluded
+    // or moved out of the rule
+    for ((uid, _), id) in existing {
+        if !calendar.uids.contains(&uid) {
+            continue;
+        }
+        let before = change_log::snapshot(&mut tx, "calendar_events", id).await?;
+        sqlx::query("DELETE FROM calendar_events WHERE id = ?")
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .map_err(ApiError::from)?;
+        change_log::record(&mut tx, Source::IcsImport, "calendar_events", id, before, &format!("Removed by {}", source)).await?;
+        result.removed += 1;
+    }
+
+    tx.commit().await.map_err(ApiError::from)?;
+    Ok(result)
+}
+
+/// Stored start and end: dates for all-day events, local RFC 3339 otherwise
+fn ics_bounds(occurrence: &IcsOccurrence) -> (String, String) {
+    if occurrence.all_day {
+        return (occurrence.start.date().to_string(), occurrence.end.date().to_string());
+    }
+    let stamp = |at: NaiveDateTime| {
+        let naive = at.format("%Y-%m-%dT%H:%M:%S").to_string();
+        parse_datetime_to_rfc3339(&naive).unwrap_or(naive)
+    };
+    (stamp(occurrence.start), stamp(occurrence.end))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -325,7 +505,7 @@ mod tests {
             notes: None,
         };
 
-        let created = create_calendar_event_for_test(&pool, input).await.unwrap();
+        let created = create_calendar_event_inner(&pool, input).await.unwrap();
 
         let start_at = created.start_at.expect("start_at missing");
         let end_at = created.end_at.expect("end_at missing");
@@ -333,4 +513,47 @@ mod tests {
         assert!(start_at.contains('Z') || start_at.contains('+'));
         assert!(end_at.contains('Z') || end_at.contains('+'));
     }
+
+    #[tokio::test]
+    async fn importing_an_ics_file_again_updates_instead_of_duplicating() {
+        let pool = setup_db().await;
+        let today = NaiveDate::from_ymd_opt(2030, 9, 1).unwrap();
+        let calendar = |until: &str, room: &str| {
+            format!(
+                "BEGIN:VCALENDAR\r\nBEGIN:VEVENT\r\nUID:ml@uni.example\r\nSUMMARY:Machine learning\r\n\
+                 DTSTART:20300903T090000\r\nDTEND:20300903T103000\r\nLOCATION:{}\r\n\
+                 RRULE:FREQ=WEEKLY;UNTIL={}\r\nEND:VEVENT\r\n\
+                 BEGIN:VEVENT\r\nUID:exam@uni.example\r\nSUMMARY:Exam registration\r\nDTSTART;VALUE=DATE:20300915\r\nEND:VEVENT\r\n\
+                 END:VCALENDAR\r\n",
+                room, until
+            )
+        };
+
+        let first = import_ics_inner(&pool, &calendar("20300924T235959", "A1"), "uni_portal", None, today).await.unwrap();
+        assert_eq!((first.created, first.updated, first.unchanged, first.removed), (5, 0, 0, 0));
+
+        let same = import_ics_inner(&pool, &calendar("20300924T235959", "A1"), "uni_portal", None, today).await.unwrap();
+        assert_eq!((same.created, same.updated, same.unchanged, same.removed), (0, 0, 5, 0));
+
+        // The course ends a week earlier and moves rooms
+        let changed = import_ics_inner(&pool, &calendar("20300917T235959", "B2"), "uni_portal", None, today).await.unwrap();
+        assert_eq!((changed.created, changed.updated, changed.unchanged, changed.removed), (0, 3, 1, 1));
+
+        let rows: Vec<(String, Option<String>, Option<String>, Option<i64>)> = sqlx::query_as(
+            "SELECT start_at, end_at, notes, locked FROM calendar_events WHERE source = 'uni_portal' ORDER BY start_at",
+        )
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        assert_eq!(rows.len(), 4);
+        assert_eq!(rows[0].2.as_deref(), Some("Location: B2"));
+        assert_eq!(rows[0].3, Some(1));
+        assert!(rows.iter().any(|r| r.0 == "2030-09-15" && r.1.as_deref() == Some("2030-09-16")));
+
+        let changes: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM change_log WHERE source = 'ics_import'")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(changes, 5 + 3 + 1);
+    }
 }
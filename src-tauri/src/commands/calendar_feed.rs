@@ -0,0 +1,26 @@
+//! Calendar feed commands
+
+use tauri::State;
+
+use crate::services::calendar_feed::{self, CalendarFeedStatus};
+use crate::{error::ApiError, DbState};
+
+/// Whether the feed is on, and its URL and file
+#[tauri::command]
+pub async fn get_calendar_feed_status(state: State<'_, DbState>) -> Result<CalendarFeedStatus, ApiError> {
+    calendar_feed::status(&state.0).await
+}
+
+/// Create a new feed token; anyone subscribed to the old URL loses access
+#[tauri::command]
+pub async fn regenerate_calendar_feed_token(state: State<'_, DbState>) -> Result<CalendarFeedStatus, ApiError> {
+    calendar_feed::regenerate_token()?;
+    calendar_feed::status(&state.0).await
+}
+
+/// Write the feed file now; returns its path, or null without a feed path
+#[tauri::command]
+pub async fn publish_calendar_feed(state: State<'_, DbState>) -> Result<Option<String>, ApiError> {
+    let path = calendar_feed::publish(&state.0).await?;
+    Ok(path.map(|p| p.display().to_string()))
+}
@@ -0,0 +1,524 @@
+//! API Capabilities
+//!
+//! Lets a frontend (possibly older or newer than this backend) discover which
+//! commands exist, at which version, and which integrations and features are
+//! enabled, instead of invoking a missing command and getting an opaque error.
+
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+use sqlx::{Pool, Sqlite};
+use tauri::State;
+
+use crate::ml::embedding::EmbeddingService;
+use crate::services::settings;
+use crate::{error::ApiError, DbState};
+
+/// Version of the capability document itself; bump on incompatible changes
+pub const API_VERSION: u32 = 1;
+
+/// Every registered command with its version. Bump a command's version when
+/// its arguments or result change incompatibly; add new commands here when
+/// registering them in `lib.rs`.
+pub const COMMANDS: &[(&str, u32)] = &[
+    // courses
+    ("create_course", 1),
+    ("get_courses", 1),
+    ("get_course", 1),
+    ("update_course", 1),
+    ("set_course_schedule_prefs", 1),
+    ("delete_course", 1),
+    ("get_courses_with_progress", 1),
+    ("get_course_analytics", 1),
+    // exams
+    ("create_exam", 1),
+    ("get_exams", 1),
+    ("get_exam", 1),
+    ("update_exam", 1),
+    ("delete_exam", 1),
+    ("get_upcoming_exams", 1),
+    ("record_exam_result", 1),
+    ("get_exam_readiness", 1),
+    // course_meetings
+    ("create_course_meeting", 1),
+    ("get_course_meetings", 1),
+    ("update_course_meeting", 1),
+    ("delete_course_meeting", 1),
+    // calendar_events
+    ("create_calendar_event", 1),
+    ("get_calendar_events", 1),
+    ("get_calendar_event", 1),
+    ("update_calendar_event", 1),
+    ("delete_calendar_event", 1),
+    ("import_ics_file", 1),
+    // weekly_tasks
+    ("create_weekly_task", 1),
+    ("get_weekly_tasks", 1),
+    ("update_weekly_task", 1),
+    ("toggle_weekly_task", 1),
+    ("delete_weekly_task", 1),
+    // week_plan_blocks
+    ("create_week_plan_block", 1),
+    ("get_week_plan_blocks", 1),
+    ("update_week_plan_block", 1),
+    ("accept_week_plan_block", 1),
+    ("lock_week_plan_block", 1),
+    ("delete_week_plan_block", 1),
+    ("clear_suggested_blocks", 1),
+    ("bulk_create_plan_blocks", 1),
+    ("get_planned_hours_by_deliverable", 1),
+    ("split_plan_blocks", 1),
+    ("start_session_from_block", 1),
+    // calendar
+    ("get_calendar_items", 1),
+    ("export_calendar_ics", 1),
+    // categories
+    ("get_categories", 1),
+    ("create_category", 1),
+    ("update_category", 1),
+    ("delete_category", 1),
+    // free_slots
+    ("find_free_slots", 1),
+    // course_workload
+    ("recompute_course_targets", 1),
+    // assignments
+    ("create_assignment", 2),
+    ("get_assignments", 1),
+    ("update_assignment", 1),
+    ("delete_assignment", 1),
+    // 2: refuses to complete while required checklist items remain, unless forced
+    ("toggle_assignment", 2),
+    ("get_assignment_checklist", 1),
+    ("add_checklist_item", 1),
+    ("toggle_checklist_item", 1),
+    ("delete_checklist_item", 1),
+    ("get_estimate_calibration", 1),
+    ("get_procrastination_report", 1),
+    // sessions
+    ("start_session", 2),
+    ("end_session", 1),
+    ("get_sessions", 1),
+    ("log_session_interruption", 3),
+    // session types
+    ("get_session_types", 1),
+    ("create_session_type", 1),
+    ("delete_session_type", 1),
+    // lecture notes
+    ("start_meeting_session", 1),
+    ("add_lecture_note", 1),
+    ("get_lecture_notes", 1),
+    ("update_lecture_note", 1),
+    ("delete_lecture_note", 1),
+    // study techniques
+    ("get_techniques", 1),
+    ("create_technique", 1),
+    ("delete_technique", 1),
+    ("get_session_techniques", 1),
+    ("set_session_techniques", 1),
+    ("get_technique_stats", 1),
+    // skills
+    ("create_skill", 1),
+    ("get_skills", 1),
+    ("update_skill", 1),
+    ("delete_skill", 1),
+    // practice
+    ("log_practice", 1),
+    ("get_practice_logs", 1),
+    ("get_skill_practice_needs", 1),
+    // skill portfolio
+    ("export_skill_portfolio", 1),
+    // workouts
+    ("create_workout", 2),
+    ("get_workouts", 1),
+    ("get_workout", 1),
+    ("delete_workout", 1),
+    // workout_exercises
+    ("add_exercise_to_workout", 1),
+    ("update_workout_exercise", 1),
+    ("remove_exercise", 1),
+    ("get_workout_exercises", 1),
+    // workouts
+    ("update_workout", 1),
+    // workout_templates
+    ("get_workout_templates", 1),
+    ("get_template_exercises", 1),
+    ("create_workout_template", 1),
+    ("update_workout_template", 1),
+    ("delete_workout_template", 1),
+    ("set_workout_template_schedule", 1),
+    ("suggest_workout_blocks", 1),
+    // exercises
+    ("search_exercises", 1),
+    ("fetch_and_cache_exercises", 1),
+    ("create_custom_exercise", 1),
+    // checkins
+    ("create_checkin", 1),
+    ("get_today_checkin", 1),
+    ("get_checkins", 1),
+    // weekly_reviews
+    ("create_weekly_review", 1),
+    ("get_weekly_reviews", 1),
+    // analytics
+    ("get_stats", 1),
+    ("get_streaks", 1),
+    ("get_user_settings", 1),
+    ("update_user_settings", 1),
+    ("get_detailed_stats", 1),
+    ("get_workout_heatmap", 1),
+    ("get_weekly_attention_report", 1),
+    ("get_pomodoro_stats", 1),
+    ("get_session_type_stats", 1),
+    ("get_personal_records", 1),
+    ("check_and_update_prs", 1),
+    ("get_achievements", 1),
+    ("check_achievements", 1),
+    // gamification
+    ("get_gamification_status", 1),
+    // challenges
+    ("create_challenge", 1),
+    ("get_challenges", 1),
+    ("delete_challenge", 1),
+    // trackers
+    ("get_trackers", 1),
+    ("create_tracker", 1),
+    ("delete_tracker", 1),
+    ("add_tracker_entity", 1),
+    ("get_tracker_entities", 1),
+    ("delete_tracker_entity", 1),
+    ("log_tracker_entry", 1),
+    ("get_tracker_logs", 1),
+    ("delete_tracker_log", 1),
+    // meditation
+    ("start_meditation_timer", 1),
+    ("get_meditation_timer", 1),
+    ("cancel_meditation_timer", 1),
+    ("finish_meditation_timer", 1),
+    ("log_meditation", 1),
+    ("get_meditation_stats", 1),
+    // reading
+    ("get_books", 1),
+    ("add_book", 1),
+    ("log_reading_session", 1),
+    ("get_reading_stats", 1),
+    // language learning
+    ("create_language_skill", 1),
+    ("get_language_skills", 1),
+    ("add_flashcard_deck", 1),
+    ("log_flashcard_review", 1),
+    ("get_language_proficiency", 1),
+    // spending
+    ("log_spending_checkin", 1),
+    ("delete_spending_checkin", 1),
+    ("get_spending_summary", 1),
+    // cycle
+    ("log_cycle_period", 1),
+    ("get_cycle_periods", 1),
+    ("delete_cycle_period", 1),
+    ("get_cycle_prediction", 1),
+    ("get_cycle_forecast", 1),
+    // social
+    ("log_social_event", 1),
+    ("get_social_events", 1),
+    ("get_social_stats", 1),
+    // debug
+    ("get_db_path", 1),
+    ("reset_local_db", 1),
+    ("simulate_history", 1),
+    ("clear_exercises_cache", 1),
+    ("get_exercise_cache_stats", 1),
+    ("get_db_pool_status", 1),
+    // sample_data
+    ("load_sample_data", 1),
+    ("remove_sample_data", 1),
+    ("get_sample_data_status", 1),
+    // onboarding
+    ("get_onboarding_status", 1),
+    ("complete_onboarding_step", 1),
+    ("apply_onboarding_settings", 1),
+    ("create_onboarding_courses", 1),
+    ("reset_onboarding", 1),
+    // settings
+    ("get_setting", 1),
+    ("set_setting", 1),
+    ("get_settings", 1),
+    ("get_settings_schema", 1),
+    // capabilities
+    ("get_api_capabilities", 1),
+    // quick_actions
+    ("get_quick_actions", 1),
+    ("run_quick_action", 1),
+    // glance
+    ("get_glance_data", 1),
+    // focus
+    ("get_session_distraction_report", 1),
+    // energy
+    ("get_energy_budgets", 1),
+    // export
+    ("export_analytics_csv", 1),
+    // telemetry
+    ("get_telemetry_report", 1),
+    ("export_telemetry", 1),
+    ("clear_telemetry", 1),
+    // shared deadlines
+    ("export_course_deadlines", 1),
+    ("import_course_deadlines", 1),
+    // http_api
+    ("get_http_api_status", 1),
+    ("regenerate_http_api_token", 1),
+    // calendar feed
+    ("get_calendar_feed_status", 1),
+    ("regenerate_calendar_feed_token", 1),
+    ("publish_calendar_feed", 1),
+    // mcp
+    ("get_mcp_audit_log", 1),
+    // grades
+    ("simulate_grade", 1),
+    // efficiency
+    ("get_effort_efficiency", 1),
+    // meeting focus
+    ("get_meeting_focus_stats", 1),
+    // capacity
+    ("get_capacity_report", 1),
+    // terms
+    ("get_terms", 1),
+    ("archive_term", 1),
+    ("get_term_snapshots", 1),
+    // semester review
+    ("get_semester_review", 1),
+    // custom metrics
+    ("create_custom_metric", 1),
+    ("get_custom_metrics", 1),
+    ("update_custom_metric", 1),
+    ("delete_custom_metric", 1),
+    ("evaluate_metric", 1),
+    // plan realism
+    ("get_plan_realism", 1),
+    // planner mode
+    ("get_planner_mode", 1),
+    ("set_planner_mode", 1),
+    // next task
+    ("get_next_best_task", 1),
+    // daily shutdown
+    ("run_daily_shutdown", 1),
+    // morning briefing
+    ("get_morning_briefing", 1),
+    // course digests
+    ("get_course_digests", 1),
+    ("get_due_course_digests", 1),
+    ("ack_course_digests", 1),
+    // accountability reports
+    ("preview_accountability_report", 1),
+    ("send_accountability_report", 1),
+    ("get_due_accountability_draft", 1),
+    ("ack_accountability_draft", 1),
+    ("get_accountability_deliveries", 1),
+    // focus profiles
+    ("get_focus_profiles", 1),
+    ("create_focus_profile", 1),
+    ("update_focus_profile", 1),
+    ("delete_focus_profile", 1),
+    ("get_focus_profile_stats", 1),
+    // attachments
+    ("attach_file", 1),
+    ("get_attachments", 1),
+    ("delete_attachment", 1),
+    ("gc_attachments", 1),
+    // insights
+    ("get_insights", 1),
+    ("record_insight_feedback", 1),
+    ("dismiss_insight", 1),
+    ("get_muted_insight_categories", 1),
+    ("set_insight_category_muted", 1),
+    ("run_pattern_analysis", 1),
+    ("get_user_profile", 1),
+    // intelligence
+    ("get_agent_recommendations", 1),
+    ("get_agent_recommendation", 1),
+    ("record_recommendation_feedback", 1),
+    ("record_action_completed", 1),
+    ("get_agent_status", 1),
+    ("get_ml_readiness", 1),
+    ("get_rich_context", 1),
+    ("get_big_three", 1),
+    ("set_big_three", 1),
+    ("complete_big_three", 1),
+    ("run_agent_maintenance", 1),
+    ("get_maintenance_log", 1),
+    ("get_maintenance_hour", 1),
+    ("set_maintenance_hour", 1),
+    ("get_agent_shadow_mode", 1),
+    ("set_agent_shadow_mode", 1),
+    ("get_shadow_report", 1),
+    ("get_feature_names", 1),
+    ("search_similar_experiences", 1),
+    ("get_reward_weights", 1),
+    ("set_reward_weights", 1),
+    ("get_reward_weight_presets", 1),
+    ("apply_reward_weight_preset", 1),
+    ("get_reward_weight_history", 1),
+    ("set_exploration_rate", 1),
+    // google_calendar
+    ("set_google_client_id", 1),
+    ("google_oauth_begin", 1),
+    ("google_oauth_complete", 1),
+    ("google_sync_now", 1),
+    ("google_sync_preview", 1),
+    ("get_google_sync_status", 1),
+    ("disconnect_google", 1),
+    // change log
+    ("get_change_log", 1),
+    ("revert_external_change", 1),
+];
+
+#[derive(Debug, Serialize)]
+pub struct CommandInfo {
+    pub name: &'static str,
+    pub version: u32,
+}
+
+#[derive(Debug, Serialize)]
+pub struct IntegrationStatus {
+    pub name: &'static str,
+    /// Usable in this build on this device
+    pub available: bool,
+    /// Set up by the user (credentials, downloaded model, ...)
+    pub configured: bool,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ApiCapabilities {
+    pub api_version: u32,
+    pub backend_version: &'static str,
+    /// Latest applied database migration
+    pub schema_version: Option<i64>,
+    pub commands: Vec<CommandInfo>,
+    pub integrations: Vec<IntegrationStatus>,
+    pub features: BTreeMap<&'static str, bool>,
+}
+
+/// Describe what this backend supports
+#[tauri::command]
+pub async fn get_api_capabilities(state: State<'_, DbState>) -> Result<ApiCapabilities, ApiError> {
+    capabilities(&state.0).await
+}
+
+async fn capabilities(pool: &Pool<Sqlite>) -> Result<ApiCapabilities, ApiError> {
+    let schema_version: Option<i64> =
+        sqlx::query_scalar("SELECT MAX(version) FROM _sqlx_migrations WHERE success = 1")
+            .fetch_one(pool)
+            .await
+            .map_err(ApiError::from)?;
+
+    let google_connected: bool = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM google_accounts WHERE user_id = 1)")
+        .fetch_one(pool)
+        .await
+        .map_err(ApiError::from)?;
+    let google_client_id = settings::get_string(pool, "google_client_id").await?;
+    let semantic_memory = EmbeddingService::is_model_available();
+
+    let integrations = vec![
+        IntegrationStatus {
+            name: "google_calendar",
+            available: true,
+            configured: google_client_id.is_some() && google_connected,
+        },
+        IntegrationStatus {
+            name: "wger_exercises",
+            available: true,
+            configured: true,
+        },
+        IntegrationStatus {
+            name: "semantic_memory",
+            available: semantic_memory,
+            configured: semantic_memory,
+        },
+    ];
+
+    let features = BTreeMap::from([
+        ("debug_commands", cfg!(debug_assertions)),
+        ("agent_v2", true),
+        ("agent_shadow_mode", settings::get_bool(pool, "agent_shadow_mode").await?),
+        ("calendar_feed", settings::get_bool(pool, "calendar_feed_enabled").await?),
+        ("deep_links", true),
+        ("focus_mode", settings::get_bool(pool, "focus_mode_enabled").await?),
+        ("gamification", settings::get_bool(pool, "gamification_enabled").await?),
+        ("global_shortcuts", true),
+        ("http_api", settings::get_bool(pool, "http_api_enabled").await?),
+        ("mcp", settings::get_bool(pool, "mcp_enabled").await?),
+        ("onboarding", true),
+        ("sample_data", true),
+        ("settings_events", true),
+        ("telemetry", settings::get_bool(pool, "telemetry_enabled").await?),
+    ]);
+
+    Ok(ApiCapabilities {
+        api_version: API_VERSION,
+        backend_version: env!("CARGO_PKG_VERSION"),
+        schema_version,
+        commands: COMMANDS
+            .iter()
+            .map(|&(name, version)| CommandInfo { name, version })
+            .collect(),
+        integrations,
+        features,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+    use std::str::FromStr;
+
+    async fn setup_pool_with_migrations() -> Pool<Sqlite> {
+        let options = SqliteConnectOptions::from_str("sqlite::memory:")
+            .unwrap()
+            .foreign_keys(false);
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect_with(options)
+            .await
+            .expect("Failed to connect to in-memory DB");
+
+        crate::db::migrations::run_migrations(&pool)
+            .await
+            .expect("Failed to run migrations");
+
+        pool
+    }
+
+    #[test]
+    fn command_list_matches_registered_handlers() {
+        let lib = include_str!("../lib.rs");
+        let handlers = &lib[lib.find("generate_handler![").unwrap()..];
+        let registered: Vec<&str> = handlers[..handlers.find("])").unwrap()]
+            .lines()
+            .map(str::trim)
+            .filter(|l| !l.starts_with("//") && l.ends_with(','))
+            .filter_map(|l| l.trim_end_matches(',').rsplit("::").next())
+            .collect();
+
+        for name in &registered {
+            assert!(COMMANDS.iter().any(|(c, _)| c == name), "{} missing from COMMANDS", name);
+        }
+        for (name, _) in COMMANDS {
+            assert!(registered.contains(name), "{} is not registered", name);
+        }
+    }
+
+    #[tokio::test]
+    async fn capabilities_reflect_state() {
+        let pool = setup_pool_with_migrations().await;
+
+        let caps = capabilities(&pool).await.unwrap();
+        assert_eq!(caps.api_version, API_VERSION);
+        assert!(caps.schema_version.is_some());
+        let google = caps.integrations.iter().find(|i| i.name == "google_calendar").unwrap();
+        assert!(!google.configured);
+        assert!(!caps.features["agent_shadow_mode"]);
+
+        settings::set(&pool, "agent_shadow_mode", true.into()).await.unwrap();
+        let caps = capabilities(&pool).await.unwrap();
+        assert!(caps.features["agent_shadow_mode"]);
+    }
+}
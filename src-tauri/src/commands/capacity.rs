@@ -0,0 +1,402 @@
+//! Capacity Planning
+//!
+//! Adds up what a week already asks for (classes, calendar events, weekly
+//! study/skill/workout targets, plan blocks and estimated assignment effort)
+//! and compares it with the waking hours left after sleep. Only
+//! `capacity_limit_percent` of waking time is treated as available; the rest
+//! covers meals, commuting and rest. Plan blocks are how targets get
+//! scheduled, so they are not added on top of them: the larger of the two
+//! counts. The report is computed both for the plan as it stands and as if
+//! every suggested block were accepted, so the planner can warn before a
+//! generated plan is taken.
+
+use chrono::{DateTime, Datelike, Days, Local, NaiveDate};
+use serde::Serialize;
+use sqlx::{Pool, Sqlite};
+use tauri::State;
+
+use crate::{
+    commands::calendar::{get_calendar_items_for_pool, CalendarQuery},
+    error::ApiError,
+    services::{estimates, focus, settings},
+    DbState,
+};
+
+/// Workout length assumed when there is no recent history
+const DEFAULT_WORKOUT_MINUTES: f64 = 45.0;
+/// History used for the typical workout length
+const WORKOUT_HISTORY_DAYS: i64 = 56;
+/// Utilization from which a week is reported as tight
+const TIGHT_UTILIZATION: f64 = 0.9;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CapacityStatus {
+    Ok,
+    Tight,
+    Overcommitted,
+}
+
+impl CapacityStatus {
+    fn from_utilization(utilization: f64) -> Self {
+        if utilization > 1.0 {
+            Self::Overcommitted
+        } else if utilization >= TIGHT_UTILIZATION {
+            Self::Tight
+        } else {
+            Self::Ok
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CapacityDay {
+    pub date: String,
+    /// Classes and calendar events
+    pub fixed_hours: f64,
+    /// Accepted and locked plan blocks
+    pub planned_hours: f64,
+    pub suggested_hours: f64,
+    pub available_hours: f64,
+    /// Fixed and planned time exceed the day's capacity
+    pub overcommitted: bool,
+    /// Same, once suggested blocks are accepted
+    pub overcommitted_with_suggested: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CapacityReport {
+    pub week_start: String,
+    pub week_end: String,
+    pub waking_hours: f64,
+    pub available_hours: f64,
+    pub class_hours: f64,
+    pub event_hours: f64,
+    pub study_target_hours: f64,
+    pub skill_target_hours: f64,
+    pub workout_target_hours: f64,
+    /// Estimated effort of open assignments due this week, calibrated per course
+    pub assignment_hours: f64,
+    /// Open assignments due this week without an estimate
+    pub unestimated_assignments: i64,
+    pub planned_hours: f64,
+    pub suggested_hours: f64,
+    pub committed_hours: f64,
+    pub utilization: f64,
+    pub status: CapacityStatus,
+    pub committed_hours_with_suggested: f64,
+    pub utilization_with_suggested: f64,
+    pub status_with_suggested: CapacityStatus,
+    pub days: Vec<CapacityDay>,
+    pub warnings: Vec<String>,
+}
+
+/// Committed vs available hours for the week containing `week`
+/// (YYYY-MM-DD, defaults to the current week)
+#[tauri::command]
+pub async fn get_capacity_report(
+    state: State<'_, DbState>,
+    week: Option<String>,
+) -> Result<CapacityReport, ApiError> {
+    let pool = &state.0;
+
+    let date = match week {
+        Some(s) => NaiveDate::parse_from_str(&s, "%Y-%m-%d")
+            .map_err(|_| ApiError::validation("week must be a YYYY-MM-DD date"))?,
+        None => Local::now().date_naive(),
+    };
+    let week_start = focus::week_start_for(pool, date).await?;
+
+    capacity_report(pool, week_start).await
+}
+
+pub(crate) async fn capacity_report(pool: &Pool<Sqlite>, week_start: NaiveDate) -> Result<CapacityReport, ApiError> {
+    let week_end = week_start + Days::new(6);
+
+    let sleep_hours = settings::get_i64(pool, "sleep_hours").await? as f64;
+    let limit = settings::get_i64(pool, "capacity_limit_percent").await? as f64 / 100.0;
+    let daily_available = (24.0 - sleep_hours) * limit;
+
+    let mut days: Vec<CapacityDay> = (0..7)
+        .map(|i| CapacityDay {
+            date: (week_start + Days::new(i)).to_string(),
+            fixed_hours: 0.0,
+            planned_hours: 0.0,
+            suggested_hours: 0.0,
+            available_hours: daily_available,
+            overcommitted: false,
+            overcommitted_with_suggested: false,
+        })
+        .collect();
+
+    let items = get_calendar_items_for_pool(
+        pool,
+        CalendarQuery {
+            start_date: week_start.to_string(),
+            end_date: week_end.to_string(),
+            include_assignments: Some(false),
+            include_exams: Some(false),
+        },
+    )
+    .await?;
+
+    let (mut class_hours, mut event_hours) = (0.0, 0.0);
+    for item in items.iter().filter(|i| !i.all_day) {
+        let (Some(start), Some(end)) = (local_time(&item.start_at), local_time(&item.end_at)) else {
+            continue;
+        };
+        let hours = (end - start).num_minutes().max(0) as f64 / 60.0;
+        let Some(day) = days.get_mut((start.date_naive() - week_start).num_days() as usize) else {
+            continue;
+        };
+        match (item.source.as_str(), item.status.as_deref()) {
+            // Free categories (breaks, free-marked events) are not commitments
+            _ if !item.busy => {}
+            ("course_meeting", _) => {
+                class_hours += hours;
+                day.fixed_hours += hours;
+            }
+            ("calendar_event", _) => {
+                event_hours += hours;
+                day.fixed_hours += hours;
+            }
+            ("plan_block", Some("suggested")) => day.suggested_hours += hours,
+            ("plan_block", _) => day.planned_hours += hours,
+            _ => {}
+        }
+    }
+
+    let (study_target_hours,): (f64,) =
+        sqlx::query_as("SELECT COALESCE(SUM(target_weekly_hours), 0.0) FROM courses WHERE is_active = 1")
+            .fetch_one(pool)
+            .await
+            .map_err(ApiError::from)?;
+    let (skill_target_hours,): (f64,) =
+        sqlx::query_as("SELECT COALESCE(SUM(target_weekly_hours), 0.0) FROM skills WHERE archived_at IS NULL")
+            .fetch_one(pool)
+            .await
+            .map_err(ApiError::from)?;
+
+    let (average_workout,): (Option<f64>,) = sqlx::query_as(
+        "SELECT AVG(duration_minutes) FROM workouts WHERE duration_minutes > 0 AND logged_at >= datetime('now', ?)",
+    )
+    .bind(format!("-{} days", WORKOUT_HISTORY_DAYS))
+    .fetch_one(pool)
+    .await
+    .map_err(ApiError::from)?;
+    let workout_target_hours = settings::get_i64(pool, "weekly_workout_target").await? as f64
+        * average_workout.unwrap_or(DEFAULT_WORKOUT_MINUTES)
+        / 60.0;
+
+    let assignment_rows: Vec<(i64, i64, i64)> = sqlx::query_as(
+        r#"
+        SELECT a.course_id, COALESCE(SUM(a.estimated_minutes), 0), COALESCE(SUM(a.estimated_minutes IS NULL), 0)
+        FROM assignments a
+        JOIN courses c ON c.id = a.course_id
+        WHERE a.is_completed = 0 AND c.is_active = 1
+          AND date(a.due_date) >= ? AND date(a.due_date) <= ?
+        GROUP BY a.course_id
+        "#,
+    )
+    .bind(week_start.to_string())
+    .bind(week_end.to_string())
+    .fetch_all(pool)
+    .await
+    .map_err(ApiError::from)?;
+    // Estimates corrected by each course's track record
+    let ratios = estimates::ratios(pool).await?;
+    let assignment_hours: f64 = assignment_rows
+        .iter()
+        .map(|(course_id, minutes, _)| *minutes as f64 * ratios.get(course_id).copied().unwrap_or(1.0) / 60.0)
+        .sum();
+    let unestimated_assignments: i64 = assignment_rows.iter().map(|(_, _, n)| n).sum();
+
+    for day in &mut days {
+        day.overcommitted = day.fixed_hours + day.planned_hours > day.available_hours;
+        day.overcommitted_with_suggested =
+            day.fixed_hours + day.planned_hours + day.suggested_hours > day.available_hours;
+    }
+
+    let waking_hours = (24.0 - sleep_hours) * 7.0;
+    let available_hours = daily_available * 7.0;
+    let planned_hours: f64 = days.iter().map(|d| d.planned_hours).sum();
+    let suggested_hours: f64 = days.iter().map(|d| d.suggested_hours).sum();
+    let fixed = class_hours + event_hours;
+    let demand = study_target_hours + skill_target_hours + workout_target_hours + assignment_hours;
+
+    let committed_hours = fixed + demand.max(planned_hours);
+    let committed_hours_with_suggested = fixed + demand.max(planned_hours + suggested_hours);
+    let utilization = ratio(committed_hours, available_hours);
+    let utilization_with_suggested = ratio(committed_hours_with_suggested, available_hours);
+    let status = CapacityStatus::from_utilization(utilization);
+    let status_with_suggested = CapacityStatus::from_utilization(utilization_with_suggested);
+
+    let mut warnings = Vec::new();
+    match status {
+        CapacityStatus::Overcommitted => warnings.push(format!(
+            "This week is overcommitted: {:.1}h committed against {:.1}h available",
+            committed_hours, available_hours
+        )),
+        CapacityStatus::Tight => warnings.push(format!(
+            "This week is nearly full: {:.0}% of available hours are committed",
+            utilization * 100.0
+        )),
+        CapacityStatus::Ok if status_with_suggested == CapacityStatus::Overcommitted => warnings.push(format!(
+            "Accepting the suggested plan would overcommit the week: {:.1}h against {:.1}h available",
+            committed_hours_with_suggested, available_hours
+        )),
+        CapacityStatus::Ok => {}
+    }
+    for day in &days {
+        if day.overcommitted {
+            warnings.push(format!("{} is overbooked", weekday_name(&day.date)));
+        } else if day.overcommitted_with_suggested {
+            warnings.push(format!("Suggested blocks overbook {}", weekday_name(&day.date)));
+        }
+    }
+    if unestimated_assignments > 0 {
+        warnings.push(format!(
+            "{} assignment(s) due this week have no time estimate and are not counted",
+            unestimated_assignments
+        ));
+    }
+
+    Ok(CapacityReport {
+        week_start: week_start.to_string(),
+        week_end: week_end.to_string(),
+        waking_hours,
+        available_hours,
+        class_hours,
+        event_hours,
+        study_target_hours,
+        skill_target_hours,
+        workout_target_hours,
+        assignment_hours,
+        unestimated_assignments,
+        planned_hours,
+        suggested_hours,
+        committed_hours,
+        utilization,
+        status,
+        committed_hours_with_suggested,
+        utilization_with_suggested,
+        status_with_suggested,
+        days,
+        warnings,
+    })
+}
+
+fn local_time(value: &str) -> Option<DateTime<Local>> {
+    DateTime::parse_from_rfc3339(value)
+        .ok()
+        .map(|dt| dt.with_timezone(&Local))
+}
+
+fn ratio(committed: f64, available: f64) -> f64 {
+    if available > 0.0 {
+        committed / available
+    } else {
+        0.0
+    }
+}
+
+fn weekday_name(date: &str) -> String {
+    NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .map(|d| d.weekday().to_string())
+        .unwrap_or_else(|_| date.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+    use std::str::FromStr;
+
+    async fn setup_pool_with_migrations() -> Pool<Sqlite> {
+        let options = SqliteConnectOptions::from_str("sqlite::memory:")
+            .unwrap()
+            .foreign_keys(false);
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect_with(options)
+            .await
+            .expect("Failed to connect to in-memory DB");
+
+        crate::db::migrations::run_migrations(&pool)
+            .await
+            .expect("Failed to run migrations");
+
+        pool
+    }
+
+    async fn plan_block(pool: &Pool<Sqlite>, day: &str, start: &str, end: &str, block_type: &str, status: &str) {
+        sqlx::query(
+            "INSERT INTO week_plan_blocks (week_start_date, start_at, end_at, block_type, status) VALUES ('2026-01-05', ?, ?, ?, ?)",
+        )
+        .bind(format!("{}T{}:00", day, start))
+        .bind(format!("{}T{}:00", day, end))
+        .bind(block_type)
+        .bind(status)
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn warns_when_suggested_plan_would_overcommit_the_week() {
+        let pool = setup_pool_with_migrations().await;
+        let week_start = NaiveDate::from_ymd_opt(2026, 1, 5).unwrap();
+
+        let course: i64 =
+            sqlx::query_scalar("INSERT INTO courses (name, target_weekly_hours) VALUES ('Physics', 10) RETURNING id")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        // Monday 09:00-12:00
+        sqlx::query(
+            "INSERT INTO course_meetings (course_id, day_of_week, start_time, end_time) VALUES (?, 1, '09:00', '12:00')",
+        )
+        .bind(course)
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO assignments (course_id, title, due_date, estimated_minutes) VALUES (?, 'Lab report', '2026-01-08', 120), (?, 'Reading', '2026-01-09', NULL)",
+        )
+        .bind(course)
+        .bind(course)
+        .execute(&pool)
+        .await
+        .unwrap();
+        plan_block(&pool, "2026-01-05", "13:00", "15:00", "study", "accepted").await;
+        plan_block(&pool, "2026-01-05", "15:00", "15:30", "break", "accepted").await;
+        for i in 0..7 {
+            let day = (week_start + Days::new(i)).to_string();
+            plan_block(&pool, &day, "08:00", "20:00", "study", "suggested").await;
+        }
+
+        let report = capacity_report(&pool, week_start).await.unwrap();
+
+        // 16 waking hours a day, 75% of them available
+        assert_eq!(report.waking_hours, 112.0);
+        assert_eq!(report.available_hours, 84.0);
+        assert_eq!(report.class_hours, 3.0);
+        assert_eq!(report.assignment_hours, 2.0);
+        assert_eq!(report.unestimated_assignments, 1);
+        assert_eq!(report.planned_hours, 2.0);
+        assert_eq!(report.suggested_hours, 84.0);
+        assert_eq!(report.workout_target_hours, 2.25);
+        // Classes plus targets; the accepted block is within the study target
+        assert_eq!(report.committed_hours, 3.0 + 10.0 + 2.25 + 2.0);
+        assert_eq!(report.status, CapacityStatus::Ok);
+        assert_eq!(report.committed_hours_with_suggested, 3.0 + 86.0);
+        assert_eq!(report.status_with_suggested, CapacityStatus::Overcommitted);
+
+        let monday = &report.days[0];
+        assert_eq!(monday.fixed_hours, 3.0);
+        assert!(!monday.overcommitted);
+        assert!(monday.overcommitted_with_suggested);
+        assert!(report.warnings[0].starts_with("Accepting the suggested plan"));
+        assert!(report.warnings.iter().any(|w| w.contains("no time estimate")));
+    }
+}
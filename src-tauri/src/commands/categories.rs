@@ -0,0 +1,225 @@
+//! Calendar categories
+//!
+//! A category gives every calendar event, plan block type and synthetic
+//! calendar item (class, deadline, exam) a user-chosen color and a busy/free
+//! default. Course colors still take precedence for course-linked items.
+//! Names are the join key, so they cannot be changed once created — built-in
+//! categories can be recolored but not deleted.
+
+use std::collections::HashMap;
+
+use serde::Deserialize;
+use sqlx::{Pool, Sqlite};
+use tauri::State;
+
+use crate::{error::ApiError, models::category::Category, DbState};
+
+const MAX_NAME_LENGTH: usize = 40;
+
+#[derive(Debug, Deserialize)]
+pub struct CategoryInput {
+    pub name: String,
+    pub color: String,
+    #[serde(default)]
+    pub is_busy: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CategoryUpdate {
+    #[serde(default)]
+    pub color: Option<String>,
+    #[serde(default)]
+    pub is_busy: Option<bool>,
+}
+
+/// Lowercased `#rrggbb`
+fn normalize_color(color: &str) -> Result<String, ApiError> {
+    let color = color.trim();
+    let valid = color.len() == 7
+        && color.starts_with('#')
+        && color[1..].chars().all(|c| c.is_ascii_hexdigit());
+    if !valid {
+        return Err(ApiError::validation("Color must be a hex color like #3b82f6"));
+    }
+    Ok(color.to_ascii_lowercase())
+}
+
+fn normalize_name(name: &str) -> Result<String, ApiError> {
+    let name = name.trim().to_lowercase();
+    if name.is_empty() {
+        return Err(ApiError::validation("Category name is required"));
+    }
+    if name.chars().count() > MAX_NAME_LENGTH {
+        return Err(ApiError::validation(format!(
+            "Category name must be at most {} characters",
+            MAX_NAME_LENGTH
+        )));
+    }
+    Ok(name)
+}
+
+pub(crate) async fn list_categories(pool: &Pool<Sqlite>) -> Result<Vec<Category>, ApiError> {
+    sqlx::query_as::<_, Category>("SELECT * FROM categories ORDER BY is_builtin DESC, name")
+        .fetch_all(pool)
+        .await
+        .map_err(ApiError::from)
+}
+
+/// Categories keyed by name, for resolving colors and busy/free
+pub(crate) async fn category_map(pool: &Pool<Sqlite>) -> Result<HashMap<String, Category>, ApiError> {
+    Ok(list_categories(pool)
+        .await?
+        .into_iter()
+        .map(|c| (c.name.clone(), c))
+        .collect())
+}
+
+pub(crate) async fn insert_category(pool: &Pool<Sqlite>, data: &CategoryInput) -> Result<Category, ApiError> {
+    let name = normalize_name(&data.name)?;
+    let color = normalize_color(&data.color)?;
+    sqlx::query_as::<_, Category>(
+        "INSERT INTO categories (name, color, is_busy) VALUES (?, ?, ?) RETURNING *",
+    )
+    .bind(&name)
+    .bind(&color)
+    .bind(data.is_busy.unwrap_or(true))
+    .fetch_one(pool)
+    .await
+    .map_err(|e| ApiError::from_sqlx(e, format!("Category '{}' already exists", name)))
+}
+
+pub(crate) async fn modify_category(
+    pool: &Pool<Sqlite>,
+    id: i64,
+    data: &CategoryUpdate,
+) -> Result<Category, ApiError> {
+    let color = data.color.as_deref().map(normalize_color).transpose()?;
+    sqlx::query_as::<_, Category>(
+        "UPDATE categories SET color = COALESCE(?, color), is_busy = COALESCE(?, is_busy) WHERE id = ? RETURNING *",
+    )
+    .bind(&color)
+    .bind(data.is_busy)
+    .bind(id)
+    .fetch_optional(pool)
+    .await
+    .map_err(ApiError::from)?
+    .ok_or_else(|| ApiError::not_found("Category not found"))
+}
+
+pub(crate) async fn remove_category(pool: &Pool<Sqlite>, id: i64) -> Result<(), ApiError> {
+    let category = sqlx::query_as::<_, Category>("SELECT * FROM categories WHERE id = ?")
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+        .map_err(ApiError::from)?
+        .ok_or_else(|| ApiError::not_found("Category not found"))?;
+    if category.is_builtin {
+        return Err(ApiError::validation("Built-in categories cannot be deleted"));
+    }
+
+    let (in_use,): (i64,) = sqlx::query_as("SELECT COUNT(*) FROM calendar_events WHERE category = ?")
+        .bind(&category.name)
+        .fetch_one(pool)
+        .await
+        .map_err(ApiError::from)?;
+    if in_use > 0 {
+        return Err(ApiError::conflict(format!(
+            "Category '{}' is used by {} calendar event(s)",
+            category.name, in_use
+        )));
+    }
+
+    sqlx::query("DELETE FROM categories WHERE id = ?")
+        .bind(id)
+        .execute(pool)
+        .await
+        .map_err(ApiError::from)?;
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn get_categories(state: State<'_, DbState>) -> Result<Vec<Category>, ApiError> {
+    list_categories(&state.0).await
+}
+
+#[tauri::command]
+pub async fn create_category(state: State<'_, DbState>, data: CategoryInput) -> Result<Category, ApiError> {
+    insert_category(&state.0, &data).await
+}
+
+#[tauri::command]
+pub async fn update_category(
+    state: State<'_, DbState>,
+    id: i64,
+    data: CategoryUpdate,
+) -> Result<Category, ApiError> {
+    modify_category(&state.0, id, &data).await
+}
+
+#[tauri::command]
+pub async fn delete_category(state: State<'_, DbState>, id: i64) -> Result<(), ApiError> {
+    remove_category(&state.0, id).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+    use std::str::FromStr;
+
+    async fn setup_pool_with_migrations() -> Pool<Sqlite> {
+        let options = SqliteConnectOptions::from_str("sqlite::memory:")
+            .unwrap()
+            .foreign_keys(false);
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect_with(options)
+            .await
+            .expect("Failed to connect to in-memory DB");
+
+        crate::db::migrations::run_migrations(&pool)
+            .await
+            .expect("Failed to run migrations");
+
+        pool
+    }
+
+    #[tokio::test]
+    async fn categories_are_seeded_editable_and_protected() {
+        let pool = setup_pool_with_migrations().await;
+        let map = category_map(&pool).await.unwrap();
+        assert!(map["busy"].is_busy);
+        assert!(!map["break"].is_busy);
+
+        let created = insert_category(
+            &pool,
+            &CategoryInput { name: " Errands ".to_string(), color: "#AABBCC".to_string(), is_busy: Some(false) },
+        )
+        .await
+        .unwrap();
+        assert_eq!(created.name, "errands");
+        assert_eq!(created.color, "#aabbcc");
+        assert!(!created.is_busy);
+
+        let duplicate = CategoryInput { name: "errands".to_string(), color: "#000000".to_string(), is_busy: None };
+        assert!(insert_category(&pool, &duplicate).await.is_err());
+        let bad_color = CategoryInput { name: "gym".to_string(), color: "red".to_string(), is_busy: None };
+        assert!(insert_category(&pool, &bad_color).await.is_err());
+
+        let updated = modify_category(&pool, created.id, &CategoryUpdate { color: Some("#112233".to_string()), is_busy: None })
+            .await
+            .unwrap();
+        assert_eq!(updated.color, "#112233");
+        assert!(!updated.is_busy);
+
+        sqlx::query("INSERT INTO calendar_events (title, start_at, end_at, category) VALUES ('Post office', '2026-03-02T10:00:00', '2026-03-02T10:30:00', 'errands')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        assert!(remove_category(&pool, created.id).await.is_err());
+        sqlx::query("DELETE FROM calendar_events").execute(&pool).await.unwrap();
+        remove_category(&pool, created.id).await.unwrap();
+
+        assert!(remove_category(&pool, map["busy"].id).await.is_err());
+    }
+}
@@ -0,0 +1,85 @@
+//! Challenge commands
+
+use chrono::{Duration, NaiveDate};
+use serde::Deserialize;
+use tauri::State;
+
+use crate::services::challenges::{self, Challenge, ChallengeMetric};
+use crate::services::clock;
+use crate::{error::ApiError, DbState};
+
+const MAX_TITLE_LENGTH: usize = 100;
+const MAX_DAYS: i64 = 365;
+
+#[derive(Debug, Deserialize)]
+pub struct ChallengeInput {
+    pub title: String,
+    pub metric: ChallengeMetric,
+    /// Total over the challenge, or per day with `per_day`
+    pub target: f64,
+    #[serde(default)]
+    pub per_day: bool,
+    /// YYYY-MM-DD; defaults to today
+    #[serde(default)]
+    pub start_date: Option<String>,
+    pub days: i64,
+}
+
+#[tauri::command]
+pub async fn create_challenge(state: State<'_, DbState>, data: ChallengeInput) -> Result<Challenge, ApiError> {
+    let pool = &state.0;
+    let title = data.title.trim();
+    if title.is_empty() || title.len() > MAX_TITLE_LENGTH {
+        return Err(ApiError::validation(format!(
+            "Challenge title must be between 1 and {} characters",
+            MAX_TITLE_LENGTH
+        )));
+    }
+    if !(data.target.is_finite() && data.target > 0.0) {
+        return Err(ApiError::validation("Target must be greater than 0"));
+    }
+    if !(1..=MAX_DAYS).contains(&data.days) {
+        return Err(ApiError::validation(format!("days must be between 1 and {}", MAX_DAYS)));
+    }
+    let start = match &data.start_date {
+        Some(date) => NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .map_err(|_| ApiError::validation("start_date must be YYYY-MM-DD"))?,
+        None => clock::now().date_naive(),
+    };
+    let end = start + Duration::days(data.days - 1);
+
+    let id: i64 = sqlx::query_scalar(
+        "INSERT INTO challenges (title, metric, target, per_day, start_date, end_date) VALUES (?, ?, ?, ?, ?, ?) RETURNING id",
+    )
+    .bind(title)
+    .bind(data.metric.as_str())
+    .bind(data.target)
+    .bind(data.per_day)
+    .bind(start.format("%Y-%m-%d").to_string())
+    .bind(end.format("%Y-%m-%d").to_string())
+    .fetch_one(pool)
+    .await
+    .map_err(|e| ApiError::from_sqlx(e, "Failed to create challenge"))?;
+
+    challenges::get(pool, id).await
+}
+
+/// Upcoming and active challenges with progress; completed and failed ones too
+/// with `include_finished`
+#[tauri::command]
+pub async fn get_challenges(
+    state: State<'_, DbState>,
+    include_finished: Option<bool>,
+) -> Result<Vec<Challenge>, ApiError> {
+    challenges::list(&state.0, include_finished.unwrap_or(false)).await
+}
+
+#[tauri::command]
+pub async fn delete_challenge(state: State<'_, DbState>, id: i64) -> Result<bool, ApiError> {
+    let result = sqlx::query("DELETE FROM challenges WHERE id = ?")
+        .bind(id)
+        .execute(&state.0)
+        .await
+        .map_err(ApiError::from)?;
+    Ok(result.rows_affected() > 0)
+}
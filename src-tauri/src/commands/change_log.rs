@@ -0,0 +1,34 @@
+//! Change log commands: review and revert what sync and imports changed
+
+use tauri::State;
+
+use crate::services::change_log::{self, ChangeLogEntry};
+use crate::{error::ApiError, DbState};
+
+/// Entries returned when no limit is given
+const DEFAULT_LIMIT: i64 = 100;
+
+/// External changes, newest first; `source` is e.g. 'google', 'deadline_import' or 'ics_import'
+#[tauri::command]
+pub async fn get_change_log(
+    state: State<'_, DbState>,
+    source: Option<String>,
+    limit: Option<i64>,
+) -> Result<Vec<ChangeLogEntry>, ApiError> {
+    let limit = limit.unwrap_or(DEFAULT_LIMIT);
+    if limit < 1 {
+        return Err(ApiError::validation("limit must be at least 1"));
+    }
+    change_log::list(&state.0, source.as_deref(), limit).await
+}
+
+/// Undo one external change. Fails with a conflict when the record changed
+/// again since, unless `force` is set.
+#[tauri::command]
+pub async fn revert_external_change(
+    state: State<'_, DbState>,
+    id: i64,
+    force: Option<bool>,
+) -> Result<ChangeLogEntry, ApiError> {
+    change_log::revert(&state.0, id, force.unwrap_or(false)).await
+}
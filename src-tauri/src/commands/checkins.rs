@@ -1,6 +1,12 @@
+use sqlx::{Pool, Sqlite};
 use tauri::State;
 
-use crate::{DbState, error::ApiError, models::checkin::CheckIn};
+use crate::{
+    DbState,
+    error::ApiError,
+    models::checkin::CheckIn,
+    services::{aggregates, events::{self, ActivityEvent}},
+};
 
 #[derive(Debug, serde::Deserialize)]
 pub struct CheckInInput {
@@ -12,7 +18,10 @@ pub struct CheckInInput {
 
 #[tauri::command]
 pub async fn create_checkin(state: State<'_, DbState>, data: CheckInInput) -> Result<CheckIn, ApiError> {
-    let pool = &state.0;
+    insert_checkin(&state.0, &data).await
+}
+
+pub(crate) async fn insert_checkin(pool: &Pool<Sqlite>, data: &CheckInInput) -> Result<CheckIn, ApiError> {
     let rec = sqlx::query_as::<_, CheckIn>(
         "INSERT INTO check_ins (user_id, mood, energy, notes) VALUES (?, ?, ?, ?) RETURNING id, user_id, mood, energy, notes, checked_in_at"
     )
@@ -23,6 +32,8 @@ pub async fn create_checkin(state: State<'_, DbState>, data: CheckInInput) -> Re
     .fetch_one(pool)
     .await
     .map_err(ApiError::from)?;
+    aggregates::invalidate();
+    events::publish(ActivityEvent::CheckInRecorded { checkin_id: rec.id });
     Ok(rec)
 }
 
@@ -0,0 +1,332 @@
+//! Weekly Course Digests
+//!
+//! One digest per active course: study hours logged over the past week
+//! against the weekly target, what is due in the coming week, and where
+//! grades are heading. `text` is a short plain-text version for a
+//! notification, like the morning briefing's.
+//!
+//! There is no notification service on the backend, so delivery is pull
+//! based: the frontend asks `get_due_course_digests` at startup, which
+//! returns the digests only on the `course_digest_day` setting's weekday and
+//! only until `ack_course_digests` records that they were shown.
+
+use chrono::{Datelike, Local};
+use serde::Serialize;
+use sqlx::{Pool, Sqlite};
+use tauri::State;
+
+use crate::commands::briefing::DueItem;
+use crate::services::{settings, working_hours::WEEKDAYS};
+use crate::{error::ApiError, DbState};
+
+const DIGEST_DAYS: i64 = 7;
+/// Latest results compared against the earlier ones for the trajectory
+const RECENT_RESULTS: usize = 3;
+/// Grade change (points) that counts as rising or falling
+const TRAJECTORY_THRESHOLD: f64 = 2.0;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GradeTrajectory {
+    Rising,
+    Steady,
+    Falling,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CourseDigest {
+    pub course_id: i64,
+    pub course_name: String,
+    pub course_code: Option<String>,
+    pub hours_logged: f64,
+    pub target_weekly_hours: Option<f64>,
+    /// Unfinished assignments and exams in the coming week, soonest first
+    pub upcoming: Vec<DueItem>,
+    pub current_grade: Option<f64>,
+    /// Graded exam and assignment scores, oldest first
+    pub recent_grades: Vec<f64>,
+    /// None with fewer than two graded results
+    pub trajectory: Option<GradeTrajectory>,
+    /// Plain-text digest for a notification
+    pub text: String,
+}
+
+/// (id, name, code, target weekly hours, current grade, study minutes this week)
+type CourseRow = (i64, String, Option<String>, Option<f64>, Option<f64>, i64);
+
+/// This week's digest for every active course
+#[tauri::command]
+pub async fn get_course_digests(state: State<'_, DbState>) -> Result<Vec<CourseDigest>, ApiError> {
+    let pool = &state.0;
+    course_digests(pool).await
+}
+
+/// Digests to show now: empty unless today is the digest day and they have
+/// not been acknowledged yet
+#[tauri::command]
+pub async fn get_due_course_digests(state: State<'_, DbState>) -> Result<Vec<CourseDigest>, ApiError> {
+    let pool = &state.0;
+    due_course_digests(pool, &Local::now().date_naive().to_string()).await
+}
+
+/// Record that today's digests were shown
+#[tauri::command]
+pub async fn ack_course_digests(state: State<'_, DbState>, courses: i64) -> Result<(), ApiError> {
+    let pool = &state.0;
+    sqlx::query("INSERT OR REPLACE INTO course_digest_deliveries (delivered_on, courses) VALUES (date('now', 'localtime'), ?)")
+        .bind(courses)
+        .execute(pool)
+        .await
+        .map_err(ApiError::from)?;
+    Ok(())
+}
+
+pub(crate) async fn due_course_digests(pool: &Pool<Sqlite>, today: &str) -> Result<Vec<CourseDigest>, ApiError> {
+    let date = chrono::NaiveDate::parse_from_str(today, "%Y-%m-%d")
+        .map_err(|_| ApiError::validation("Invalid date"))?;
+    let day = settings::get(pool, "course_digest_day").await?;
+    if day.as_str() != Some(WEEKDAYS[date.weekday().num_days_from_sunday() as usize]) {
+        return Ok(Vec::new());
+    }
+
+    let delivered: Option<String> =
+        sqlx::query_scalar("SELECT delivered_on FROM course_digest_deliveries WHERE delivered_on = ?")
+            .bind(today)
+            .fetch_optional(pool)
+            .await
+            .map_err(ApiError::from)?;
+    if delivered.is_some() {
+        return Ok(Vec::new());
+    }
+    course_digests(pool).await
+}
+
+pub(crate) async fn course_digests(pool: &Pool<Sqlite>) -> Result<Vec<CourseDigest>, ApiError> {
+    let courses: Vec<CourseRow> = sqlx::query_as(
+        r#"
+        SELECT c.id, c.name, c.code, c.target_weekly_hours, c.current_grade,
+               (SELECT COALESCE(SUM(s.duration_minutes), 0) FROM sessions s
+                WHERE s.session_type = 'study' AND s.reference_type = 'course' AND s.reference_id = c.id
+                  AND s.started_at >= datetime('now', ?))
+        FROM courses c
+        WHERE c.is_active = 1
+        ORDER BY c.name
+        "#,
+    )
+    .bind(format!("-{} days", DIGEST_DAYS))
+    .fetch_all(pool)
+    .await
+    .map_err(ApiError::from)?;
+
+    let mut digests = Vec::with_capacity(courses.len());
+    for (course_id, course_name, code, target_weekly_hours, current_grade, minutes) in courses {
+        let upcoming = upcoming_items(pool, course_id, &course_name).await?;
+        let recent_grades: Vec<f64> = sqlx::query_scalar(
+            r#"
+            SELECT score FROM (
+                SELECT exam_date AS at, grade AS score FROM exams
+                WHERE course_id = ?1 AND grade IS NOT NULL AND exam_date IS NOT NULL
+                UNION ALL
+                SELECT COALESCE(completed_at, due_date), score FROM assignments
+                WHERE course_id = ?1 AND score IS NOT NULL AND COALESCE(completed_at, due_date) IS NOT NULL
+            )
+            ORDER BY datetime(at)
+            "#,
+        )
+        .bind(course_id)
+        .fetch_all(pool)
+        .await
+        .map_err(ApiError::from)?;
+
+        let mut digest = CourseDigest {
+            course_id,
+            course_name,
+            course_code: code.filter(|c| !c.trim().is_empty()),
+            hours_logged: (minutes as f64 / 60.0 * 10.0).round() / 10.0,
+            target_weekly_hours,
+            upcoming,
+            current_grade,
+            trajectory: trajectory(&recent_grades),
+            recent_grades,
+            text: String::new(),
+        };
+        digest.text = render_text(&digest);
+        digests.push(digest);
+    }
+    Ok(digests)
+}
+
+async fn upcoming_items(pool: &Pool<Sqlite>, course_id: i64, course_name: &str) -> Result<Vec<DueItem>, ApiError> {
+    let rows: Vec<(String, i64, String, String, i64)> = sqlx::query_as(
+        r#"
+        SELECT 'assignment', id, title, due_date,
+               CAST(julianday(date(due_date)) - julianday(date('now', 'localtime')) AS INTEGER) AS days_left
+        FROM assignments
+        WHERE course_id = ?1 AND is_completed = 0 AND due_date IS NOT NULL
+          AND date(due_date) BETWEEN date('now', 'localtime') AND date('now', 'localtime', ?2)
+        UNION ALL
+        SELECT 'exam', id, title, exam_date,
+               CAST(julianday(date(exam_date)) - julianday(date('now', 'localtime')) AS INTEGER)
+        FROM exams
+        WHERE course_id = ?1 AND exam_date IS NOT NULL
+          AND date(exam_date) BETWEEN date('now', 'localtime') AND date('now', 'localtime', ?2)
+        ORDER BY days_left, 1 DESC
+        "#,
+    )
+    .bind(course_id)
+    .bind(format!("+{} days", DIGEST_DAYS))
+    .fetch_all(pool)
+    .await
+    .map_err(ApiError::from)?;
+
+    Ok(rows
+        .into_iter()
+        .map(|(kind, id, title, due_at, days_left)| DueItem {
+            kind,
+            id,
+            title,
+            course_name: course_name.to_string(),
+            due_at,
+            days_left,
+        })
+        .collect())
+}
+
+/// Latest `RECENT_RESULTS` grades against the ones before them
+fn trajectory(grades: &[f64]) -> Option<GradeTrajectory> {
+    if grades.len() < 2 {
+        return None;
+    }
+    let split = grades.len().saturating_sub(RECENT_RESULTS).max(1);
+    let (earlier, recent) = grades.split_at(split);
+    let mean = |values: &[f64]| values.iter().sum::<f64>() / values.len() as f64;
+    let change = mean(recent) - mean(earlier);
+    Some(if change >= TRAJECTORY_THRESHOLD {
+        GradeTrajectory::Rising
+    } else if change <= -TRAJECTORY_THRESHOLD {
+        GradeTrajectory::Falling
+    } else {
+        GradeTrajectory::Steady
+    })
+}
+
+fn render_text(digest: &CourseDigest) -> String {
+    let mut lines = Vec::new();
+
+    lines.push(match digest.target_weekly_hours {
+        Some(target) if target > 0.0 => format!("{:.1} h studied this week of {:.1} h planned", digest.hours_logged, target),
+        _ => format!("{:.1} h studied this week", digest.hours_logged),
+    });
+
+    if digest.upcoming.is_empty() {
+        lines.push("Nothing due in the next week".to_string());
+    } else {
+        let due: Vec<String> = digest
+            .upcoming
+            .iter()
+            .map(|d| match d.days_left {
+                0 => format!("{} (today)", d.title),
+                1 => format!("{} (tomorrow)", d.title),
+                n => format!("{} (in {} days)", d.title, n),
+            })
+            .collect();
+        lines.push(format!("Due: {}", due.join(", ")));
+    }
+
+    if let (Some(trajectory), Some(latest)) = (digest.trajectory, digest.recent_grades.last()) {
+        let direction = match trajectory {
+            GradeTrajectory::Rising => "rising",
+            GradeTrajectory::Steady => "steady",
+            GradeTrajectory::Falling => "falling",
+        };
+        lines.push(format!("Grades {} (latest {:.0}%)", direction, latest));
+    }
+
+    lines.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+    use std::str::FromStr;
+
+    async fn setup_pool_with_migrations() -> Pool<Sqlite> {
+        let options = SqliteConnectOptions::from_str("sqlite::memory:")
+            .unwrap()
+            .foreign_keys(false);
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect_with(options)
+            .await
+            .expect("Failed to connect to in-memory DB");
+
+        crate::db::migrations::run_migrations(&pool)
+            .await
+            .expect("Failed to run migrations");
+
+        pool
+    }
+
+    #[test]
+    fn trajectory_compares_recent_results_with_earlier_ones() {
+        assert_eq!(trajectory(&[80.0]), None);
+        assert_eq!(trajectory(&[70.0, 75.0, 80.0, 85.0]), Some(GradeTrajectory::Rising));
+        assert_eq!(trajectory(&[90.0, 80.0]), Some(GradeTrajectory::Falling));
+        assert_eq!(trajectory(&[85.0, 86.0, 84.0]), Some(GradeTrajectory::Steady));
+    }
+
+    #[tokio::test]
+    async fn digests_summarize_each_course_and_respect_the_digest_day() {
+        let pool = setup_pool_with_migrations().await;
+        sqlx::query("INSERT INTO courses (id, name, code, target_weekly_hours) VALUES (1, 'Statistics', 'STAT 200', 6.0), (2, 'Old', NULL, 3.0)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("UPDATE courses SET is_active = 0 WHERE id = 2").execute(&pool).await.unwrap();
+        sqlx::query(
+            r#"INSERT INTO sessions (session_type, reference_id, reference_type, started_at, duration_minutes)
+               VALUES ('study', 1, 'course', datetime('now', '-2 days'), 150),
+                      ('study', 1, 'course', datetime('now', '-10 days'), 600)"#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            r#"INSERT INTO assignments (course_id, title, due_date, is_completed, score)
+               VALUES (1, 'HW 4', date('now', 'localtime', '+2 days'), 0, NULL),
+                      (1, 'HW 1', date('now', '-30 days'), 1, 70),
+                      (1, 'HW 2', date('now', '-20 days'), 1, 85),
+                      (1, 'HW 3', date('now', '-10 days'), 1, 90)"#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let digests = course_digests(&pool).await.unwrap();
+        assert_eq!(digests.len(), 1);
+        let digest = &digests[0];
+        assert_eq!(digest.course_code.as_deref(), Some("STAT 200"));
+        assert_eq!(digest.hours_logged, 2.5);
+        assert_eq!(digest.upcoming.len(), 1);
+        assert_eq!(digest.trajectory, Some(GradeTrajectory::Rising));
+        assert_eq!(
+            digest.text,
+            "2.5 h studied this week of 6.0 h planned\nDue: HW 4 (in 2 days)\nGrades rising (latest 90%)"
+        );
+
+        // 2026-03-01 is a Sunday, the default digest day
+        assert_eq!(due_course_digests(&pool, "2026-03-01").await.unwrap().len(), 1);
+        assert!(due_course_digests(&pool, "2026-03-02").await.unwrap().is_empty());
+        settings::set(&pool, "course_digest_day", json!("monday")).await.unwrap();
+        assert_eq!(due_course_digests(&pool, "2026-03-02").await.unwrap().len(), 1);
+        sqlx::query("INSERT INTO course_digest_deliveries (delivered_on, courses) VALUES ('2026-03-02', 1)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        assert!(due_course_digests(&pool, "2026-03-02").await.unwrap().is_empty());
+        settings::set(&pool, "course_digest_day", json!("off")).await.unwrap();
+        assert!(due_course_digests(&pool, "2026-03-09").await.unwrap().is_empty());
+    }
+}
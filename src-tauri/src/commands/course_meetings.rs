@@ -23,8 +23,13 @@ pub async fn create_course_meeting(
     state: State<'_, DbState>,
     data: CourseMeetingInput,
 ) -> Result<CourseMeeting, ApiError> {
-    let pool = &state.0;
+    create_course_meeting_inner(&state.0, data).await
+}
 
+pub(crate) async fn create_course_meeting_inner(
+    pool: &sqlx::Pool<sqlx::Sqlite>,
+    data: CourseMeetingInput,
+) -> Result<CourseMeeting, ApiError> {
     // Validate day_of_week
     if data.day_of_week < 0 || data.day_of_week > 6 {
         return Err(ApiError::validation(
@@ -82,7 +87,7 @@ pub async fn get_course_meetings(
         .await
     } else {
         sqlx::query_as::<_, CourseMeeting>(
-            "SELECT * FROM course_meetings ORDER BY day_of_week, start_time"
+            "SELECT * FROM course_meetings WHERE archived_at IS NULL ORDER BY day_of_week, start_time"
         )
         .fetch_all(pool)
         .await
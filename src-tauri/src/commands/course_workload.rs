@@ -0,0 +1,345 @@
+//! Course Workload Templates
+//!
+//! Maps credit hours to a suggested weekly study target and a default
+//! meeting pattern, kept in the `course_workload_templates` setting. New
+//! courses without an explicit target get the template's hours; with
+//! `apply_meeting_pattern` they also get its meetings, moved later in the
+//! day until they don't clash with other active courses.
+//! `recompute_course_targets` re-applies the hours when credit loads change
+//! mid-semester.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::{Pool, Sqlite};
+use tauri::State;
+
+use crate::models::course_meeting::CourseMeeting;
+use crate::services::settings;
+use crate::utils::is_valid_time;
+use crate::{error::ApiError, DbState};
+
+const SETTING_KEY: &str = "course_workload_templates";
+const MAX_CREDIT_HOURS: i64 = 12;
+const MAX_MEETINGS: usize = 7;
+/// Used when no template is close enough to scale from
+const DEFAULT_HOURS_PER_CREDIT: f64 = 2.0;
+/// Later starts tried (in hours) before accepting a clash
+const MAX_MEETING_SHIFT_HOURS: u32 = 8;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct MeetingPattern {
+    /// 0 = Sunday
+    pub day_of_week: i64,
+    pub start_time: String,
+    pub end_time: String,
+    #[serde(default)]
+    pub meeting_type: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct WorkloadTemplate {
+    pub credit_hours: i64,
+    pub weekly_hours: f64,
+    #[serde(default)]
+    pub meetings: Vec<MeetingPattern>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TargetChange {
+    pub course_id: i64,
+    pub name: String,
+    pub credit_hours: i64,
+    pub previous_hours: Option<f64>,
+    pub target_weekly_hours: f64,
+}
+
+/// Validate the `course_workload_templates` setting, sorted by credit hours
+pub fn validate_templates(value: Value) -> Result<Value, ApiError> {
+    let mut templates: Vec<WorkloadTemplate> = serde_json::from_value(value)
+        .map_err(|e| ApiError::validation(format!("Invalid workload templates: {}", e)))?;
+
+    for (i, template) in templates.iter().enumerate() {
+        if !(0..=MAX_CREDIT_HOURS).contains(&template.credit_hours) {
+            return Err(ApiError::validation(format!(
+                "Template credit hours must be between 0 and {}",
+                MAX_CREDIT_HOURS
+            )));
+        }
+        if templates[..i].iter().any(|t| t.credit_hours == template.credit_hours) {
+            return Err(ApiError::validation(format!(
+                "Duplicate workload template for {} credit hours",
+                template.credit_hours
+            )));
+        }
+        if !(0.0..=168.0).contains(&template.weekly_hours) {
+            return Err(ApiError::validation("Template weekly hours must be between 0 and 168"));
+        }
+        if template.meetings.len() > MAX_MEETINGS {
+            return Err(ApiError::validation(format!(
+                "At most {} meetings per template",
+                MAX_MEETINGS
+            )));
+        }
+        for meeting in &template.meetings {
+            if !(0..=6).contains(&meeting.day_of_week) {
+                return Err(ApiError::validation("day_of_week must be 0-6 (Sunday-Saturday)"));
+            }
+            if !is_valid_time(&meeting.start_time)
+                || !is_valid_time(&meeting.end_time)
+                || meeting.start_time >= meeting.end_time
+            {
+                return Err(ApiError::validation(
+                    "Template meetings need HH:MM times with start before end",
+                ));
+            }
+        }
+    }
+
+    templates.sort_by_key(|t| t.credit_hours);
+    serde_json::to_value(templates).map_err(|e| ApiError::internal(e.to_string()))
+}
+
+pub(crate) async fn load(pool: &Pool<Sqlite>) -> Result<Vec<WorkloadTemplate>, ApiError> {
+    Ok(serde_json::from_value(settings::get(pool, SETTING_KEY).await?).unwrap_or_default())
+}
+
+/// Weekly hours for a course load: the matching template, else scaled from
+/// the closest one
+pub(crate) fn suggested_weekly_hours(templates: &[WorkloadTemplate], credit_hours: i64) -> f64 {
+    if let Some(t) = templates.iter().find(|t| t.credit_hours == credit_hours) {
+        return t.weekly_hours;
+    }
+    let per_credit = templates
+        .iter()
+        .filter(|t| t.credit_hours > 0)
+        .min_by_key(|t| (t.credit_hours - credit_hours).abs())
+        .map(|t| t.weekly_hours / t.credit_hours as f64)
+        .unwrap_or(DEFAULT_HOURS_PER_CREDIT);
+    (per_credit * credit_hours as f64 * 10.0).round() / 10.0
+}
+
+/// "HH:MM" moved `hours` later, or None past midnight
+fn shift_time(time: &str, hours: u32) -> Option<String> {
+    let (h, m) = time.split_once(':')?;
+    let h: u32 = h.parse::<u32>().ok()? + hours;
+    (h < 24).then(|| format!("{:02}:{}", h, m))
+}
+
+/// Add the template meetings for `credit_hours` to a course, at the first
+/// hour offset that doesn't overlap another active course's meetings
+pub(crate) async fn apply_meeting_pattern(
+    pool: &Pool<Sqlite>,
+    course_id: i64,
+    credit_hours: i64,
+) -> Result<Vec<CourseMeeting>, ApiError> {
+    let templates = load(pool).await?;
+    let Some(template) = templates.iter().find(|t| t.credit_hours == credit_hours) else {
+        return Ok(Vec::new());
+    };
+
+    let taken: Vec<(i64, String, String)> = sqlx::query_as(
+        r#"
+        SELECT cm.day_of_week, cm.start_time, cm.end_time
+        FROM course_meetings cm
+        JOIN courses c ON c.id = cm.course_id
+        WHERE c.is_active = 1 AND cm.archived_at IS NULL AND cm.course_id <> ?
+        "#,
+    )
+    .bind(course_id)
+    .fetch_all(pool)
+    .await
+    .map_err(ApiError::from)?;
+
+    let shifted = |hours: u32| -> Option<Vec<MeetingPattern>> {
+        template
+            .meetings
+            .iter()
+            .map(|m| {
+                Some(MeetingPattern {
+                    start_time: shift_time(&m.start_time, hours)?,
+                    end_time: shift_time(&m.end_time, hours)?,
+                    ..m.clone()
+                })
+            })
+            .collect()
+    };
+    let clashes = |pattern: &[MeetingPattern]| {
+        pattern.iter().any(|m| {
+            taken
+                .iter()
+                .any(|(day, start, end)| *day == m.day_of_week && m.start_time < *end && *start < m.end_time)
+        })
+    };
+    let meetings = (0..=MAX_MEETING_SHIFT_HOURS)
+        .filter_map(shifted)
+        .find(|pattern| !clashes(pattern))
+        .unwrap_or_else(|| template.meetings.clone());
+
+    let mut created = Vec::with_capacity(meetings.len());
+    for meeting in meetings {
+        let rec = sqlx::query_as::<_, CourseMeeting>(
+            r#"INSERT INTO course_meetings (course_id, day_of_week, start_time, end_time, meeting_type)
+               VALUES (?, ?, ?, ?, ?)
+               RETURNING *"#,
+        )
+        .bind(course_id)
+        .bind(meeting.day_of_week)
+        .bind(&meeting.start_time)
+        .bind(&meeting.end_time)
+        .bind(meeting.meeting_type.as_deref().unwrap_or("lecture"))
+        .fetch_one(pool)
+        .await
+        .map_err(ApiError::from)?;
+        created.push(rec);
+    }
+    Ok(created)
+}
+
+/// Re-apply template hours to every active course; with `dry_run`, only
+/// report what would change
+pub(crate) async fn recompute_targets(pool: &Pool<Sqlite>, dry_run: bool) -> Result<Vec<TargetChange>, ApiError> {
+    let templates = load(pool).await?;
+    let courses: Vec<(i64, String, i64, Option<f64>)> = sqlx::query_as(
+        "SELECT id, name, credit_hours, target_weekly_hours FROM courses WHERE is_active = 1 AND credit_hours IS NOT NULL ORDER BY name",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(ApiError::from)?;
+
+    let mut changes = Vec::new();
+    for (course_id, name, credit_hours, previous_hours) in courses {
+        let target = suggested_weekly_hours(&templates, credit_hours);
+        if previous_hours.is_some_and(|h| (h - target).abs() < 0.05) {
+            continue;
+        }
+        if !dry_run {
+            sqlx::query("UPDATE courses SET target_weekly_hours = ? WHERE id = ?")
+                .bind(target)
+                .bind(course_id)
+                .execute(pool)
+                .await
+                .map_err(ApiError::from)?;
+        }
+        changes.push(TargetChange {
+            course_id,
+            name,
+            credit_hours,
+            previous_hours,
+            target_weekly_hours: target,
+        });
+    }
+
+    if !dry_run && !changes.is_empty() {
+        log::info!("Recomputed weekly targets for {} courses", changes.len());
+    }
+    Ok(changes)
+}
+
+/// Set active courses' weekly targets from their credit hours
+#[tauri::command]
+pub async fn recompute_course_targets(
+    state: State<'_, DbState>,
+    dry_run: Option<bool>,
+) -> Result<Vec<TargetChange>, ApiError> {
+    recompute_targets(&state.0, dry_run.unwrap_or(false)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::courses::{insert_course, CourseInput};
+    use serde_json::json;
+    use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+    use std::str::FromStr;
+
+    async fn setup_pool_with_migrations() -> Pool<Sqlite> {
+        let options = SqliteConnectOptions::from_str("sqlite::memory:")
+            .unwrap()
+            .foreign_keys(false);
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect_with(options)
+            .await
+            .expect("Failed to connect to in-memory DB");
+
+        crate::db::migrations::run_migrations(&pool)
+            .await
+            .expect("Failed to run migrations");
+
+        pool
+    }
+
+    fn course(name: &str, credit_hours: i64, apply_meeting_pattern: bool) -> CourseInput {
+        CourseInput {
+            name: Some(name.to_string()),
+            credit_hours: Some(credit_hours),
+            apply_meeting_pattern: Some(apply_meeting_pattern),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn templates_validate_and_scale() {
+        let templates: Vec<WorkloadTemplate> = serde_json::from_value(
+            validate_templates(json!([
+                { "credit_hours": 4, "weekly_hours": 10 },
+                { "credit_hours": 2, "weekly_hours": 4 },
+            ]))
+            .unwrap(),
+        )
+        .unwrap();
+        assert_eq!(templates[0].credit_hours, 2);
+        assert_eq!(suggested_weekly_hours(&templates, 4), 10.0);
+        // Scaled from the closest template (4 credits -> 2.5 h/credit)
+        assert_eq!(suggested_weekly_hours(&templates, 6), 15.0);
+        assert_eq!(suggested_weekly_hours(&[], 3), 6.0);
+
+        assert!(validate_templates(json!([{ "credit_hours": 3, "weekly_hours": 6 }, { "credit_hours": 3, "weekly_hours": 9 }])).is_err());
+        assert!(validate_templates(json!([{ "credit_hours": 3, "weekly_hours": 6, "meetings": [{ "day_of_week": 7, "start_time": "10:00", "end_time": "11:00" }] }])).is_err());
+    }
+
+    #[tokio::test]
+    async fn courses_get_template_targets_and_non_clashing_meetings() {
+        let pool = setup_pool_with_migrations().await;
+
+        let first = insert_course(&pool, course("Calculus", 4, true)).await.unwrap();
+        assert_eq!(first.target_weekly_hours, Some(8.0));
+        let second = insert_course(&pool, course("Algebra", 4, true)).await.unwrap();
+        let third = insert_course(&pool, course("Seminar", 1, false)).await.unwrap();
+        assert_eq!(third.target_weekly_hours, Some(2.0));
+
+        let meetings: Vec<(i64, i64, String)> =
+            sqlx::query_as("SELECT course_id, day_of_week, start_time FROM course_meetings ORDER BY course_id, day_of_week")
+                .fetch_all(&pool)
+                .await
+                .unwrap();
+        let starts = |id: i64| -> Vec<String> {
+            meetings.iter().filter(|m| m.0 == id).map(|m| m.2.clone()).collect()
+        };
+        assert_eq!(starts(first.id), vec!["10:00"; 4]);
+        // Same pattern, an hour later so it doesn't overlap Calculus
+        assert_eq!(starts(second.id), vec!["11:00"; 4]);
+        assert!(starts(third.id).is_empty());
+
+        // The load changed: 4-credit courses now need more time
+        settings::set(
+            &pool,
+            SETTING_KEY,
+            json!([{ "credit_hours": 1, "weekly_hours": 2 }, { "credit_hours": 4, "weekly_hours": 10 }]),
+        )
+        .await
+        .unwrap();
+        let preview = recompute_targets(&pool, true).await.unwrap();
+        assert_eq!(preview.len(), 2);
+        let unchanged: f64 = sqlx::query_scalar("SELECT target_weekly_hours FROM courses WHERE id = ?")
+            .bind(first.id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(unchanged, 8.0);
+
+        let applied = recompute_targets(&pool, false).await.unwrap();
+        assert!(applied.iter().all(|c| c.target_weekly_hours == 10.0 && c.previous_hours == Some(8.0)));
+        assert!(recompute_targets(&pool, false).await.unwrap().is_empty());
+    }
+}
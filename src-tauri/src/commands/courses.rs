@@ -1,6 +1,6 @@
 use tauri::State;
 
-use crate::{DbState, error::ApiError, models::course::Course};
+use crate::{DbState, commands::course_workload, error::ApiError, models::course::Course};
 
 /// Maximum allowed length for string fields
 const MAX_NAME_LENGTH: usize = 255;
@@ -51,53 +51,34 @@ pub struct WeeklyHours {
 
 #[tauri::command]
 pub async fn create_course(state: State<'_, DbState>, data: CourseInput) -> Result<Course, ApiError> {
-    let pool = &state.0;
-    
-    let name = data.name.unwrap_or_else(|| "Untitled Course".to_string());
-    
+    insert_course(&state.0, data).await
+}
+
+pub(crate) async fn insert_course(pool: &sqlx::Pool<sqlx::Sqlite>, data: CourseInput) -> Result<Course, ApiError> {
     // Input validation
-    if name.len() > MAX_NAME_LENGTH {
-        return Err(ApiError::validation(format!(
-            "Course name too long (max {} characters)",
-            MAX_NAME_LENGTH
-        )));
-    }
-    if let Some(ref code) = data.code {
-        if code.len() > MAX_CODE_LENGTH {
-            return Err(ApiError::validation(format!(
-                "Course code too long (max {} characters)",
-                MAX_CODE_LENGTH
-            )));
-        }
-    }
-    if let Some(hours) = data.credit_hours {
-        if hours < 0 || hours > 12 {
-            return Err(ApiError::validation(
-                "Credit hours must be between 0 and 12",
-            ));
-        }
-    }
-    if let Some(target) = data.target_weekly_hours {
-        if target < 0.0 || target > 168.0 {
-            return Err(ApiError::validation(
-                "Target weekly hours must be between 0 and 168",
-            ));
-        }
-    }
+    validate_course_input(&data)?;
+
+    let name = data.name.unwrap_or_else(|| "Untitled Course".to_string());
+    let credit_hours = data.credit_hours.unwrap_or(3);
+    // Without an explicit target, the workload template for the credit hours decides
+    let target_weekly_hours = match data.target_weekly_hours {
+        Some(hours) => hours,
+        None => course_workload::suggested_weekly_hours(&course_workload::load(pool).await?, credit_hours),
+    };
     
     log::debug!("Creating course: {}", name);
     
     let rec = sqlx::query_as::<_, Course>(
         "INSERT INTO courses (user_id, name, code, color, credit_hours, target_weekly_hours, is_active, current_grade, target_grade) 
          VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?) 
-         RETURNING id, user_id, name, code, color, credit_hours, target_weekly_hours, is_active, created_at, current_grade, target_grade"
+         RETURNING id, user_id, name, code, color, credit_hours, target_weekly_hours, is_active, created_at, current_grade, target_grade, min_block_minutes, preferred_days"
     )
     .bind(data.user_id.unwrap_or(1))
     .bind(&name)
     .bind(&data.code)
     .bind(data.color.unwrap_or_else(|| "#3b82f6".to_string()))
-    .bind(data.credit_hours.unwrap_or(3))
-    .bind(data.target_weekly_hours.unwrap_or(6.0))
+    .bind(credit_hours)
+    .bind(target_weekly_hours)
     .bind(data.is_active.unwrap_or(1))
     .bind(data.current_grade)
     .bind(data.target_grade.unwrap_or(90.0))
@@ -107,6 +88,10 @@ pub async fn create_course(state: State<'_, DbState>, data: CourseInput) -> Resu
         log::error!("Failed to create course: {}", e);
         ApiError::from_sqlx(e, "Failed to create course")
     })?;
+
+    if data.apply_meeting_pattern.unwrap_or(false) {
+        course_workload::apply_meeting_pattern(pool, rec.id, credit_hours).await?;
+    }
     
     log::info!("Course created successfully: id={}", rec.id);
     Ok(rec)
@@ -144,39 +129,10 @@ pub async fn update_course(state: State<'_, DbState>, id: i64, data: CourseInput
     let pool = &state.0;
     
     // Input validation
-    if let Some(ref name) = data.name {
-        if name.len() > MAX_NAME_LENGTH {
-            return Err(ApiError::validation(format!(
-                "Course name too long (max {} characters)",
-                MAX_NAME_LENGTH
-            )));
-        }
-    }
-    if let Some(ref code) = data.code {
-        if code.len() > MAX_CODE_LENGTH {
-            return Err(ApiError::validation(format!(
-                "Course code too long (max {} characters)",
-                MAX_CODE_LENGTH
-            )));
-        }
-    }
-    if let Some(hours) = data.credit_hours {
-        if hours < 0 || hours > 12 {
-            return Err(ApiError::validation(
-                "Credit hours must be between 0 and 12",
-            ));
-        }
-    }
-    if let Some(target) = data.target_weekly_hours {
-        if target < 0.0 || target > 168.0 {
-            return Err(ApiError::validation(
-                "Target weekly hours must be between 0 and 168",
-            ));
-        }
-    }
+    validate_course_input(&data)?;
     
     let rec = sqlx::query_as::<_, Course>(
-        "UPDATE courses SET name = COALESCE(?, name), code = COALESCE(?, code), color = COALESCE(?, color), credit_hours = COALESCE(?, credit_hours), target_weekly_hours = COALESCE(?, target_weekly_hours), is_active = COALESCE(?, is_active), current_grade = COALESCE(?, current_grade), target_grade = COALESCE(?, target_grade) WHERE id = ? RETURNING id, user_id, name, code, color, credit_hours, target_weekly_hours, is_active, created_at, current_grade, target_grade"
+        "UPDATE courses SET name = COALESCE(?, name), code = COALESCE(?, code), color = COALESCE(?, color), credit_hours = COALESCE(?, credit_hours), target_weekly_hours = COALESCE(?, target_weekly_hours), is_active = COALESCE(?, is_active), current_grade = COALESCE(?, current_grade), target_grade = COALESCE(?, target_grade) WHERE id = ? RETURNING id, user_id, name, code, color, credit_hours, target_weekly_hours, is_active, created_at, current_grade, target_grade, min_block_minutes, preferred_days"
     )
     .bind(&data.name)
     .bind(&data.code)
@@ -218,6 +174,41 @@ pub async fn delete_course(state: State<'_, DbState>, id: i64) -> Result<bool, A
     Ok(true)
 }
 
+/// Validate the fields of a course input that are present
+pub(crate) fn validate_course_input(data: &CourseInput) -> Result<(), ApiError> {
+    if let Some(ref name) = data.name {
+        if name.len() > MAX_NAME_LENGTH {
+            return Err(ApiError::validation(format!(
+                "Course name too long (max {} characters)",
+                MAX_NAME_LENGTH
+            )));
+        }
+    }
+    if let Some(ref code) = data.code {
+        if code.len() > MAX_CODE_LENGTH {
+            return Err(ApiError::validation(format!(
+                "Course code too long (max {} characters)",
+                MAX_CODE_LENGTH
+            )));
+        }
+    }
+    if let Some(hours) = data.credit_hours {
+        if hours < 0 || hours > 12 {
+            return Err(ApiError::validation(
+                "Credit hours must be between 0 and 12",
+            ));
+        }
+    }
+    if let Some(target) = data.target_weekly_hours {
+        if target < 0.0 || target > 168.0 {
+            return Err(ApiError::validation(
+                "Target weekly hours must be between 0 and 168",
+            ));
+        }
+    }
+    Ok(())
+}
+
 #[derive(Debug, serde::Deserialize)]
 pub struct CourseInput {
     #[serde(default)]
@@ -238,6 +229,58 @@ pub struct CourseInput {
     pub current_grade: Option<f64>,
     #[serde(default)]
     pub target_grade: Option<f64>,
+    /// Add the workload template's meetings (create only)
+    #[serde(default)]
+    pub apply_meeting_pattern: Option<bool>,
+}
+
+#[derive(Debug, serde::Deserialize)]
+pub struct CourseSchedulePrefsInput {
+    /// Shortest study block worth planning; none uses the planner's block size
+    #[serde(default)]
+    pub min_block_minutes: Option<i64>,
+    /// Weekdays, 0 = Sunday; empty means any day
+    #[serde(default)]
+    pub preferred_days: Vec<i64>,
+}
+
+/// Set how the week planner schedules study for a course
+#[tauri::command]
+pub async fn set_course_schedule_prefs(
+    state: State<'_, DbState>,
+    id: i64,
+    prefs: CourseSchedulePrefsInput,
+) -> Result<Course, ApiError> {
+    update_schedule_prefs(&state.0, id, prefs).await
+}
+
+pub(crate) async fn update_schedule_prefs(
+    pool: &sqlx::Pool<sqlx::Sqlite>,
+    id: i64,
+    prefs: CourseSchedulePrefsInput,
+) -> Result<Course, ApiError> {
+    if prefs.min_block_minutes.is_some_and(|m| !(15..=240).contains(&m)) {
+        return Err(ApiError::validation("Minimum block length must be between 15 and 240 minutes"));
+    }
+    if prefs.preferred_days.iter().any(|d| !(0..=6).contains(d)) {
+        return Err(ApiError::validation("Preferred days must be between 0 (Sunday) and 6 (Saturday)"));
+    }
+    let mut days = prefs.preferred_days;
+    days.sort_unstable();
+    days.dedup();
+    let days_json = (!days.is_empty()).then(|| serde_json::json!(days).to_string());
+
+    sqlx::query_as::<_, Course>(
+        "UPDATE courses SET min_block_minutes = ?, preferred_days = ? WHERE id = ?
+         RETURNING id, user_id, name, code, color, credit_hours, target_weekly_hours, is_active, created_at, current_grade, target_grade, min_block_minutes, preferred_days"
+    )
+    .bind(prefs.min_block_minutes)
+    .bind(days_json)
+    .bind(id)
+    .fetch_optional(pool)
+    .await
+    .map_err(ApiError::from)?
+    .ok_or_else(|| ApiError::not_found("Course not found"))
 }
 
 // ============================================================================
@@ -461,6 +504,7 @@ mod tests {
             is_active: Some(1),
             current_grade: Some(85.0),
             target_grade: Some(90.0),
+            apply_meeting_pattern: None,
         }
     }
 
@@ -688,6 +732,32 @@ mod tests {
         assert!(json.contains("\"sessions_count\":15"));
         assert!(json.contains("weekly_history"));
     }
+
+    // =========================================================================
+    // Scheduling Preference Tests
+    // =========================================================================
+
+    #[tokio::test]
+    async fn test_schedule_prefs_are_validated_and_stored() {
+        let pool = crate::test_support::pool().await;
+        let course = crate::test_support::course("Algorithms").create(&pool).await;
+
+        let invalid = CourseSchedulePrefsInput { min_block_minutes: Some(5), preferred_days: vec![] };
+        assert!(update_schedule_prefs(&pool, course.id, invalid).await.is_err());
+        let invalid = CourseSchedulePrefsInput { min_block_minutes: None, preferred_days: vec![7] };
+        assert!(update_schedule_prefs(&pool, course.id, invalid).await.is_err());
+
+        let prefs = CourseSchedulePrefsInput { min_block_minutes: Some(90), preferred_days: vec![4, 2, 2] };
+        let updated = update_schedule_prefs(&pool, course.id, prefs).await.unwrap();
+        assert_eq!(updated.min_block_minutes, Some(90));
+        assert_eq!(updated.preferred_days.as_deref(), Some("[2,4]"));
+
+        // An empty list clears the preference
+        let prefs = CourseSchedulePrefsInput { min_block_minutes: None, preferred_days: vec![] };
+        let cleared = update_schedule_prefs(&pool, course.id, prefs).await.unwrap();
+        assert_eq!(cleared.min_block_minutes, None);
+        assert_eq!(cleared.preferred_days, None);
+    }
 }
 
 // Implement Default for CourseInput to support tests
@@ -703,6 +773,7 @@ impl Default for CourseInput {
             is_active: None,
             current_grade: None,
             target_grade: None,
+            apply_meeting_pattern: None,
         }
     }
 }
@@ -0,0 +1,628 @@
+//! Custom dashboard metrics
+//!
+//! A custom metric is a saved aggregation — entity, filters, aggregation and
+//! a rolling window — that `evaluate_metric` turns into SQL, so a dashboard
+//! can chart e.g. "hours on MATH201 after 8pm" without a dedicated endpoint.
+//! Entities, fields and operators come from fixed lists below; user values
+//! are only ever bound as parameters.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::{Pool, Sqlite};
+use tauri::State;
+
+use crate::{error::ApiError, services::settings, DbState};
+
+const MAX_NAME_LENGTH: usize = 100;
+const MAX_FILTERS: usize = 10;
+const MAX_WINDOW_DAYS: i64 = 365;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MetricEntity {
+    Sessions,
+    Practice,
+    Workouts,
+    Checkins,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MetricAggregation {
+    Count,
+    Sum,
+    Avg,
+    Min,
+    Max,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MetricBucket {
+    /// A single value for the whole window
+    #[default]
+    None,
+    Day,
+    Week,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FilterOp {
+    Eq,
+    Ne,
+    Gt,
+    Gte,
+    Lt,
+    Lte,
+    /// Case-insensitive substring match (text fields only)
+    Contains,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize)]
+pub struct MetricFilter {
+    pub field: String,
+    pub op: FilterOp,
+    pub value: Value,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CustomMetricInput {
+    pub name: String,
+    pub entity: MetricEntity,
+    #[serde(default)]
+    pub filters: Vec<MetricFilter>,
+    pub aggregation: MetricAggregation,
+    /// Aggregated field; not needed for `count`
+    #[serde(default)]
+    pub field: Option<String>,
+    pub window_days: i64,
+    #[serde(default)]
+    pub bucket: MetricBucket,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CustomMetric {
+    pub id: i64,
+    pub name: String,
+    pub entity: MetricEntity,
+    pub filters: Vec<MetricFilter>,
+    pub aggregation: MetricAggregation,
+    pub field: Option<String>,
+    pub window_days: i64,
+    pub bucket: MetricBucket,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MetricPoint {
+    /// Day, or first day of the week (YYYY-MM-DD)
+    pub bucket: String,
+    pub value: f64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct MetricEvaluation {
+    pub metric_id: i64,
+    pub name: String,
+    /// Over the whole window; None when nothing matched
+    pub value: Option<f64>,
+    /// Empty unless the metric is bucketed
+    pub points: Vec<MetricPoint>,
+}
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum FieldKind {
+    Number,
+    Text,
+}
+
+/// (name, SQL expression, kind)
+type FieldDef = (&'static str, &'static str, FieldKind);
+
+impl MetricEntity {
+    fn tables(self) -> &'static str {
+        match self {
+            MetricEntity::Sessions => {
+                "sessions s LEFT JOIN courses c ON s.reference_type = 'course' AND c.id = s.reference_id"
+            }
+            MetricEntity::Practice => "practice_logs p LEFT JOIN skills sk ON sk.id = p.skill_id",
+            MetricEntity::Workouts => "workouts w",
+            MetricEntity::Checkins => "check_ins ci",
+        }
+    }
+
+    fn timestamp(self) -> &'static str {
+        match self {
+            MetricEntity::Sessions => "s.started_at",
+            MetricEntity::Practice => "p.logged_at",
+            MetricEntity::Workouts => "w.logged_at",
+            MetricEntity::Checkins => "ci.checked_in_at",
+        }
+    }
+
+    /// Fields specific to the entity; `hour` and `weekday` are added for all
+    fn fields(self) -> &'static [FieldDef] {
+        use FieldKind::*;
+        match self {
+            MetricEntity::Sessions => &[
+                ("session_type", "s.session_type", Text),
+                ("course", "c.name", Text),
+                ("course_code", "c.code", Text),
+                ("duration_minutes", "s.duration_minutes", Number),
+                ("duration_hours", "s.duration_minutes / 60.0", Number),
+                ("planned_minutes", "s.planned_minutes", Number),
+                ("focus_rating", "s.focus_rating", Number),
+            ],
+            MetricEntity::Practice => &[
+                ("skill", "sk.name", Text),
+                ("skill_category", "sk.category", Text),
+                ("duration_minutes", "p.duration_minutes", Number),
+                ("duration_hours", "p.duration_minutes / 60.0", Number),
+            ],
+            MetricEntity::Workouts => &[
+                ("name", "w.name", Text),
+                ("duration_minutes", "w.duration_minutes", Number),
+                ("duration_hours", "w.duration_minutes / 60.0", Number),
+            ],
+            MetricEntity::Checkins => &[("mood", "ci.mood", Number), ("energy", "ci.energy", Number)],
+        }
+    }
+
+    /// SQL expression and kind of a field, including the local `hour` (0-23)
+    /// and `weekday` (0 = Sunday) of the entity's timestamp
+    fn field(self, name: &str) -> Option<(String, FieldKind)> {
+        match name {
+            "hour" => Some((
+                format!("CAST(strftime('%H', {}, 'localtime') AS INTEGER)", self.timestamp()),
+                FieldKind::Number,
+            )),
+            "weekday" => Some((
+                format!("CAST(strftime('%w', {}, 'localtime') AS INTEGER)", self.timestamp()),
+                FieldKind::Number,
+            )),
+            _ => self
+                .fields()
+                .iter()
+                .find(|(n, _, _)| *n == name)
+                .map(|(_, expr, kind)| (expr.to_string(), *kind)),
+        }
+    }
+
+    fn field_names(self) -> Vec<&'static str> {
+        self.fields()
+            .iter()
+            .map(|(n, _, _)| *n)
+            .chain(["hour", "weekday"])
+            .collect()
+    }
+}
+
+impl MetricAggregation {
+    fn sql(self) -> &'static str {
+        match self {
+            MetricAggregation::Count => "COUNT",
+            MetricAggregation::Sum => "SUM",
+            MetricAggregation::Avg => "AVG",
+            MetricAggregation::Min => "MIN",
+            MetricAggregation::Max => "MAX",
+        }
+    }
+}
+
+impl FilterOp {
+    fn sql(self) -> &'static str {
+        match self {
+            FilterOp::Eq => "=",
+            FilterOp::Ne => "<>",
+            FilterOp::Gt => ">",
+            FilterOp::Gte => ">=",
+            FilterOp::Lt => "<",
+            FilterOp::Lte => "<=",
+            FilterOp::Contains => "LIKE",
+        }
+    }
+}
+
+/// A filter value ready to bind
+enum BindValue {
+    Number(f64),
+    Text(String),
+}
+
+#[tauri::command]
+pub async fn create_custom_metric(
+    state: State<'_, DbState>,
+    data: CustomMetricInput,
+) -> Result<CustomMetric, ApiError> {
+    let pool = &state.0;
+    validate_input(&data)?;
+
+    let id: i64 = sqlx::query_scalar(
+        "INSERT INTO custom_metrics (name, entity, filters_json, aggregation, field, window_days, bucket)
+         VALUES (?, ?, ?, ?, ?, ?, ?) RETURNING id",
+    )
+    .bind(data.name.trim())
+    .bind(enum_text(&data.entity))
+    .bind(serde_json::to_string(&data.filters).map_err(|e| ApiError::internal(e.to_string()))?)
+    .bind(enum_text(&data.aggregation))
+    .bind(&data.field)
+    .bind(data.window_days)
+    .bind(enum_text(&data.bucket))
+    .fetch_one(pool)
+    .await
+    .map_err(|e| ApiError::from_sqlx(e, "Failed to create custom metric"))?;
+
+    fetch_metric(pool, id).await
+}
+
+#[tauri::command]
+pub async fn get_custom_metrics(state: State<'_, DbState>) -> Result<Vec<CustomMetric>, ApiError> {
+    let pool = &state.0;
+    let rows: Vec<MetricRow> = sqlx::query_as(&format!("{} ORDER BY name, id", SELECT_METRIC))
+        .fetch_all(pool)
+        .await
+        .map_err(ApiError::from)?;
+    rows.into_iter().map(MetricRow::into_metric).collect()
+}
+
+#[tauri::command]
+pub async fn update_custom_metric(
+    state: State<'_, DbState>,
+    id: i64,
+    data: CustomMetricInput,
+) -> Result<CustomMetric, ApiError> {
+    let pool = &state.0;
+    validate_input(&data)?;
+
+    let result = sqlx::query(
+        "UPDATE custom_metrics
+         SET name = ?, entity = ?, filters_json = ?, aggregation = ?, field = ?, window_days = ?, bucket = ?,
+             updated_at = datetime('now')
+         WHERE id = ?",
+    )
+    .bind(data.name.trim())
+    .bind(enum_text(&data.entity))
+    .bind(serde_json::to_string(&data.filters).map_err(|e| ApiError::internal(e.to_string()))?)
+    .bind(enum_text(&data.aggregation))
+    .bind(&data.field)
+    .bind(data.window_days)
+    .bind(enum_text(&data.bucket))
+    .bind(id)
+    .execute(pool)
+    .await
+    .map_err(|e| ApiError::from_sqlx(e, "Failed to update custom metric"))?;
+    if result.rows_affected() == 0 {
+        return Err(ApiError::not_found("Custom metric not found"));
+    }
+
+    fetch_metric(pool, id).await
+}
+
+#[tauri::command]
+pub async fn delete_custom_metric(state: State<'_, DbState>, id: i64) -> Result<bool, ApiError> {
+    let pool = &state.0;
+    let result = sqlx::query("DELETE FROM custom_metrics WHERE id = ?")
+        .bind(id)
+        .execute(pool)
+        .await
+        .map_err(ApiError::from)?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Run a saved metric over its window
+#[tauri::command]
+pub async fn evaluate_metric(state: State<'_, DbState>, id: i64) -> Result<MetricEvaluation, ApiError> {
+    let pool = &state.0;
+    let metric = fetch_metric(pool, id).await?;
+    evaluate(pool, &metric).await
+}
+
+const SELECT_METRIC: &str = "SELECT id, name, entity, filters_json, aggregation, field, window_days, bucket, created_at, updated_at FROM custom_metrics";
+
+#[derive(sqlx::FromRow)]
+struct MetricRow {
+    id: i64,
+    name: String,
+    entity: String,
+    filters_json: String,
+    aggregation: String,
+    field: Option<String>,
+    window_days: i64,
+    bucket: String,
+    created_at: String,
+    updated_at: String,
+}
+
+impl MetricRow {
+    fn into_metric(self) -> Result<CustomMetric, ApiError> {
+        let id = self.id;
+        let invalid = |e: serde_json::Error| ApiError::internal(format!("Invalid custom metric {}: {}", id, e));
+        Ok(CustomMetric {
+            id: self.id,
+            name: self.name,
+            entity: parse_enum(self.entity).map_err(invalid)?,
+            filters: serde_json::from_str(&self.filters_json).map_err(invalid)?,
+            aggregation: parse_enum(self.aggregation).map_err(invalid)?,
+            field: self.field,
+            window_days: self.window_days,
+            bucket: parse_enum(self.bucket).map_err(invalid)?,
+            created_at: self.created_at,
+            updated_at: self.updated_at,
+        })
+    }
+}
+
+async fn fetch_metric(pool: &Pool<Sqlite>, id: i64) -> Result<CustomMetric, ApiError> {
+    let row: Option<MetricRow> = sqlx::query_as(&format!("{} WHERE id = ?", SELECT_METRIC))
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+        .map_err(ApiError::from)?;
+    row.ok_or_else(|| ApiError::not_found("Custom metric not found"))?
+        .into_metric()
+}
+
+/// snake_case name of a unit enum variant
+fn enum_text<T: Serialize>(value: &T) -> String {
+    match serde_json::to_value(value) {
+        Ok(Value::String(s)) => s,
+        _ => String::new(),
+    }
+}
+
+fn parse_enum<T: serde::de::DeserializeOwned>(text: String) -> Result<T, serde_json::Error> {
+    serde_json::from_value(Value::String(text))
+}
+
+fn validate_input(data: &CustomMetricInput) -> Result<(), ApiError> {
+    let name = data.name.trim();
+    if name.is_empty() || name.len() > MAX_NAME_LENGTH {
+        return Err(ApiError::validation(format!(
+            "Metric name must be between 1 and {} characters",
+            MAX_NAME_LENGTH
+        )));
+    }
+    if !(1..=MAX_WINDOW_DAYS).contains(&data.window_days) {
+        return Err(ApiError::validation(format!(
+            "window_days must be between 1 and {}",
+            MAX_WINDOW_DAYS
+        )));
+    }
+    aggregate_expr(data.entity, data.aggregation, data.field.as_deref())?;
+    filter_clauses(data.entity, &data.filters)?;
+    Ok(())
+}
+
+/// `AGG(expr)` for the metric, checking the field fits the aggregation
+fn aggregate_expr(
+    entity: MetricEntity,
+    aggregation: MetricAggregation,
+    field: Option<&str>,
+) -> Result<String, ApiError> {
+    match (aggregation, field) {
+        (MetricAggregation::Count, None) => Ok("CAST(COUNT(*) AS REAL)".to_string()),
+        (_, None) => Err(ApiError::validation("A field is required for this aggregation")),
+        (_, Some(name)) => {
+            let (expr, kind) = entity.field(name).ok_or_else(|| unknown_field(entity, name))?;
+            if aggregation != MetricAggregation::Count && kind != FieldKind::Number {
+                return Err(ApiError::validation(format!("Field '{}' is not numeric", name)));
+            }
+            Ok(format!("CAST({}({}) AS REAL)", aggregation.sql(), expr))
+        }
+    }
+}
+
+/// SQL conditions and their bind values
+fn filter_clauses(entity: MetricEntity, filters: &[MetricFilter]) -> Result<(Vec<String>, Vec<BindValue>), ApiError> {
+    if filters.len() > MAX_FILTERS {
+        return Err(ApiError::validation(format!("At most {} filters are allowed", MAX_FILTERS)));
+    }
+
+    let mut clauses = Vec::with_capacity(filters.len());
+    let mut binds = Vec::with_capacity(filters.len());
+    for filter in filters {
+        let (expr, kind) = entity
+            .field(&filter.field)
+            .ok_or_else(|| unknown_field(entity, &filter.field))?;
+        let bind = match (kind, filter.op, &filter.value) {
+            (FieldKind::Text, FilterOp::Contains, Value::String(s)) => BindValue::Text(format!("%{}%", s)),
+            (FieldKind::Text, FilterOp::Eq | FilterOp::Ne, Value::String(s)) => BindValue::Text(s.clone()),
+            (FieldKind::Number, op, Value::Number(n)) if op != FilterOp::Contains => {
+                BindValue::Number(n.as_f64().unwrap_or_default())
+            }
+            _ => {
+                return Err(ApiError::validation(format!(
+                    "Invalid filter on '{}': {:?} with {}",
+                    filter.field, filter.op, filter.value
+                )))
+            }
+        };
+        // NULLs never match, including for "ne"
+        clauses.push(format!("{} IS NOT NULL AND {} {} ?", expr, expr, filter.op.sql()));
+        binds.push(bind);
+    }
+    Ok((clauses, binds))
+}
+
+fn unknown_field(entity: MetricEntity, name: &str) -> ApiError {
+    ApiError::validation(format!(
+        "Unknown field '{}'. {} fields: {:?}",
+        name,
+        enum_text(&entity),
+        entity.field_names()
+    ))
+}
+
+pub(crate) async fn evaluate(pool: &Pool<Sqlite>, metric: &CustomMetric) -> Result<MetricEvaluation, ApiError> {
+    let aggregate = aggregate_expr(metric.entity, metric.aggregation, metric.field.as_deref())?;
+    let (clauses, binds) = filter_clauses(metric.entity, &metric.filters)?;
+    let timestamp = metric.entity.timestamp();
+
+    let mut conditions = vec![format!(
+        "date({}, 'localtime') > date('now', 'localtime', ?)",
+        timestamp
+    )];
+    conditions.extend(clauses);
+    let where_clause = conditions.join(" AND ");
+    let window = format!("-{} days", metric.window_days);
+
+    let sql = format!("SELECT {} FROM {} WHERE {}", aggregate, metric.entity.tables(), where_clause);
+    let mut total = sqlx::query_scalar::<_, Option<f64>>(&sql).bind(&window);
+    for bind in &binds {
+        total = match bind {
+            BindValue::Number(n) => total.bind(*n),
+            BindValue::Text(s) => total.bind(s.as_str()),
+        };
+    }
+    let value = total.fetch_one(pool).await.map_err(ApiError::from)?;
+
+    let bucket_expr = match metric.bucket {
+        MetricBucket::None => None,
+        MetricBucket::Day => Some(format!("date({}, 'localtime')", timestamp)),
+        MetricBucket::Week => {
+            // SQLite weekday modifiers: 0 = Sunday, 1 = Monday
+            let start = match settings::get_string(pool, "week_start_day").await?.as_deref() {
+                Some("sunday") => 0,
+                _ => 1,
+            };
+            Some(format!("date({}, 'localtime', '-6 days', 'weekday {}')", timestamp, start))
+        }
+    };
+
+    let mut points = Vec::new();
+    if let Some(bucket_expr) = bucket_expr {
+        let sql = format!(
+            "SELECT {} AS bucket, {} FROM {} WHERE {} GROUP BY bucket ORDER BY bucket",
+            bucket_expr,
+            aggregate,
+            metric.entity.tables(),
+            where_clause
+        );
+        let mut query = sqlx::query_as::<_, (String, Option<f64>)>(&sql).bind(&window);
+        for bind in &binds {
+            query = match bind {
+                BindValue::Number(n) => query.bind(*n),
+                BindValue::Text(s) => query.bind(s.as_str()),
+            };
+        }
+        points = query
+            .fetch_all(pool)
+            .await
+            .map_err(ApiError::from)?
+            .into_iter()
+            .filter_map(|(bucket, value)| value.map(|value| MetricPoint { bucket, value }))
+            .collect();
+    }
+
+    Ok(MetricEvaluation {
+        metric_id: metric.id,
+        name: metric.name.clone(),
+        value,
+        points,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+    use std::str::FromStr;
+
+    async fn setup_pool_with_migrations() -> Pool<Sqlite> {
+        let options = SqliteConnectOptions::from_str("sqlite::memory:")
+            .unwrap()
+            .foreign_keys(false);
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect_with(options)
+            .await
+            .expect("Failed to connect to in-memory DB");
+
+        crate::db::migrations::run_migrations(&pool)
+            .await
+            .expect("Failed to run migrations");
+
+        pool
+    }
+
+    fn metric(filters: Vec<MetricFilter>, aggregation: MetricAggregation, field: Option<&str>) -> CustomMetric {
+        CustomMetric {
+            id: 1,
+            name: "Late MATH201".into(),
+            entity: MetricEntity::Sessions,
+            filters,
+            aggregation,
+            field: field.map(str::to_string),
+            window_days: 30,
+            bucket: MetricBucket::Day,
+            created_at: String::new(),
+            updated_at: String::new(),
+        }
+    }
+
+    #[tokio::test]
+    async fn evaluates_hours_on_a_course_after_8pm() {
+        let pool = setup_pool_with_migrations().await;
+        let math: i64 =
+            sqlx::query_scalar("INSERT INTO courses (name, code) VALUES ('Calculus II', 'MATH201') RETURNING id")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        let other: i64 = sqlx::query_scalar("INSERT INTO courses (name, code) VALUES ('History', 'HIST101') RETURNING id")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+
+        // (course, local time, minutes)
+        for (course, at, minutes) in [
+            (math, "-2 days", 90),       // late
+            (math, "-1 days", 30),       // late
+            (math, "-3 days", 120),      // morning
+            (other, "-2 days", 60),      // late, other course
+            (math, "-60 days", 45),      // late, outside window
+        ] {
+            let hour = if minutes == 120 { "09:00" } else { "21:30" };
+            sqlx::query(
+                "INSERT INTO sessions (session_type, reference_id, reference_type, started_at, duration_minutes)
+                 VALUES ('study', ?, 'course', datetime(date('now', 'localtime', ?) || ' ' || ?, 'utc'), ?)",
+            )
+            .bind(course)
+            .bind(at)
+            .bind(hour)
+            .bind(minutes)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+
+        let late_math = vec![
+            MetricFilter { field: "course_code".into(), op: FilterOp::Eq, value: "MATH201".into() },
+            MetricFilter { field: "hour".into(), op: FilterOp::Gte, value: 20.into() },
+        ];
+        let result = evaluate(&pool, &metric(late_math.clone(), MetricAggregation::Sum, Some("duration_hours")))
+            .await
+            .unwrap();
+        assert_eq!(result.value, Some(2.0));
+        assert_eq!(result.points.len(), 2);
+        assert_eq!(result.points[0].value, 1.5);
+
+        let count = evaluate(&pool, &metric(late_math, MetricAggregation::Count, None)).await.unwrap();
+        assert_eq!(count.value, Some(2.0));
+
+        // Values are bound, never spliced into SQL
+        let sneaky = vec![MetricFilter {
+            field: "course".into(),
+            op: FilterOp::Contains,
+            value: "' OR 1=1 --".into(),
+        }];
+        let none = evaluate(&pool, &metric(sneaky, MetricAggregation::Count, None)).await.unwrap();
+        assert_eq!(none.value, Some(0.0));
+
+        let bad_field = metric(vec![], MetricAggregation::Sum, Some("notes; DROP TABLE sessions"));
+        assert!(evaluate(&pool, &bad_field).await.is_err());
+        let text_sum = metric(vec![], MetricAggregation::Avg, Some("course"));
+        assert!(evaluate(&pool, &text_sum).await.is_err());
+    }
+}
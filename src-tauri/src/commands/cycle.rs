@@ -0,0 +1,53 @@
+//! Cycle tracking commands
+//!
+//! All of them fail while `cycle_tracking_enabled` is off, except deleting,
+//! so logged data can always be removed.
+
+use tauri::State;
+
+use crate::services::clock;
+use crate::services::cycle::{self, CycleDay, CyclePeriod, CyclePeriodInput, CyclePrediction};
+use crate::{error::ApiError, DbState};
+
+const MAX_FORECAST_DAYS: i64 = 90;
+
+/// Log a period, or update the one starting that day
+#[tauri::command]
+pub async fn log_cycle_period(state: State<'_, DbState>, data: CyclePeriodInput) -> Result<CyclePeriod, ApiError> {
+    cycle::log_period(&state.0, &data, clock::now().date_naive()).await
+}
+
+#[tauri::command]
+pub async fn get_cycle_periods(state: State<'_, DbState>) -> Result<Vec<CyclePeriod>, ApiError> {
+    cycle::periods(&state.0).await
+}
+
+#[tauri::command]
+pub async fn delete_cycle_period(state: State<'_, DbState>, id: i64) -> Result<bool, ApiError> {
+    cycle::delete_period(&state.0, id).await
+}
+
+/// Today's phase and the next period; null until a period is logged
+#[tauri::command]
+pub async fn get_cycle_prediction(state: State<'_, DbState>) -> Result<Option<CyclePrediction>, ApiError> {
+    let pool = &state.0;
+    if !cycle::enabled(pool).await? {
+        return Err(ApiError::validation("Cycle tracking is off"));
+    }
+    cycle::prediction(pool, clock::now().date_naive()).await
+}
+
+/// Predicted phase of each of the next `days` days, starting today
+#[tauri::command]
+pub async fn get_cycle_forecast(state: State<'_, DbState>, days: Option<i64>) -> Result<Vec<CycleDay>, ApiError> {
+    let pool = &state.0;
+    let days = days.unwrap_or(28);
+    if !(1..=MAX_FORECAST_DAYS).contains(&days) {
+        return Err(ApiError::validation(format!("days must be between 1 and {}", MAX_FORECAST_DAYS)));
+    }
+    if !cycle::enabled(pool).await? {
+        return Err(ApiError::validation("Cycle tracking is off"));
+    }
+    let today = clock::now().date_naive();
+    cycle::days(pool, today, today + chrono::Duration::days(days - 1), today).await
+}
@@ -0,0 +1,418 @@
+//! Shared deadline sets
+//!
+//! A course's assignment and exam schedule can be exported as a small JSON
+//! file and imported by classmates into their own copy of the course. Only
+//! the schedule travels: titles, dates and logistics, never scores,
+//! completion or private notes. Importing merges: an item whose title
+//! (ignoring case) and date match one already in the course is skipped, so
+//! the same file, or overlapping files, can be imported more than once.
+
+use std::collections::HashSet;
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use sqlx::{Pool, Sqlite};
+use tauri::State;
+
+use crate::services::{
+    change_log::{self, Source},
+    progress::ProgressReporter,
+};
+use crate::{commands::export::validate_path, error::ApiError, DbState};
+
+const FORMAT: &str = "life-os-deadlines";
+const VERSION: i64 = 1;
+/// Deadline files are small; anything bigger is not one
+const MAX_FILE_BYTES: u64 = 1024 * 1024;
+const MAX_ITEMS: usize = 500;
+const MAX_TITLE_LENGTH: usize = 200;
+const PRIORITIES: [&str; 3] = ["low", "medium", "high"];
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SharedCourse {
+    pub name: String,
+    #[serde(default)]
+    pub code: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct SharedAssignment {
+    pub title: String,
+    #[serde(default)]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub due_date: Option<String>,
+    #[serde(default)]
+    pub priority: Option<String>,
+    #[serde(default)]
+    pub estimated_minutes: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, sqlx::FromRow)]
+pub struct SharedExam {
+    pub title: String,
+    #[serde(default)]
+    pub exam_date: Option<String>,
+    #[serde(default)]
+    pub location: Option<String>,
+    #[serde(default)]
+    pub duration_minutes: Option<i64>,
+    #[serde(default)]
+    pub weight: Option<f64>,
+}
+
+/// The file format
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeadlineSet {
+    pub format: String,
+    pub version: i64,
+    #[serde(default)]
+    pub exported_at: Option<String>,
+    pub course: SharedCourse,
+    #[serde(default)]
+    pub assignments: Vec<SharedAssignment>,
+    #[serde(default)]
+    pub exams: Vec<SharedExam>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct DeadlineExportResult {
+    pub path: String,
+    pub assignments: usize,
+    pub exams: usize,
+}
+
+#[derive(Debug, Default, Serialize)]
+pub struct DeadlineImportResult {
+    /// Course name in the file, for confirming the right set was imported
+    pub source_course: String,
+    pub assignments_added: usize,
+    pub exams_added: usize,
+    /// Items already in the course (same title and date)
+    pub duplicates_skipped: usize,
+}
+
+/// Merge key: trimmed lowercase title and the YYYY-MM-DD date, if any
+fn merge_key(title: &str, date: Option<&str>) -> (String, Option<String>) {
+    (title.trim().to_lowercase(), date.and_then(date_part))
+}
+
+fn date_part(value: &str) -> Option<String> {
+    let prefix = value.trim().get(..10)?;
+    NaiveDate::parse_from_str(prefix, "%Y-%m-%d").ok().map(|d| d.to_string())
+}
+
+fn validate_item(title: &str, date: Option<&str>, kind: &str) -> Result<(), ApiError> {
+    let title = title.trim();
+    if title.is_empty() || title.chars().count() > MAX_TITLE_LENGTH {
+        return Err(ApiError::validation(format!(
+            "Every {} needs a title of at most {} characters",
+            kind, MAX_TITLE_LENGTH
+        )));
+    }
+    if let Some(date) = date.filter(|d| date_part(d).is_none()) {
+        return Err(ApiError::validation(format!("Invalid date '{}' for {} '{}'", date, kind, title)));
+    }
+    Ok(())
+}
+
+fn validate_set(set: &DeadlineSet) -> Result<(), ApiError> {
+    if set.format != FORMAT {
+        return Err(ApiError::validation("Not a deadline file"));
+    }
+    if set.version > VERSION {
+        return Err(ApiError::validation(
+            "This deadline file was made by a newer version of the app",
+        ));
+    }
+    if set.assignments.len() + set.exams.len() > MAX_ITEMS {
+        return Err(ApiError::validation(format!("A deadline file holds at most {} items", MAX_ITEMS)));
+    }
+    for a in &set.assignments {
+        validate_item(&a.title, a.due_date.as_deref(), "assignment")?;
+        if a.estimated_minutes.is_some_and(|m| m <= 0) {
+            return Err(ApiError::validation("Estimated minutes must be positive"));
+        }
+    }
+    for e in &set.exams {
+        validate_item(&e.title, e.exam_date.as_deref(), "exam")?;
+        if e.duration_minutes.is_some_and(|m| m <= 0) {
+            return Err(ApiError::validation("Exam duration must be positive"));
+        }
+    }
+    Ok(())
+}
+
+/// A course's schedule as a deadline set
+pub(crate) async fn build_set(pool: &Pool<Sqlite>, course_id: i64) -> Result<DeadlineSet, ApiError> {
+    let course: SharedCourse = sqlx::query_as::<_, (String, Option<String>)>("SELECT name, code FROM courses WHERE id = ?")
+        .bind(course_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(ApiError::from)?
+        .map(|(name, code)| SharedCourse { name, code })
+        .ok_or_else(|| ApiError::not_found("Course not found"))?;
+
+    let assignments = sqlx::query_as::<_, SharedAssignment>(
+        r#"SELECT title, description, due_date, priority, estimated_minutes FROM assignments
+           WHERE course_id = ? AND is_sample = 0
+           ORDER BY due_date IS NULL, due_date, id"#,
+    )
+    .bind(course_id)
+    .fetch_all(pool)
+    .await
+    .map_err(ApiError::from)?;
+    let exams = sqlx::query_as::<_, SharedExam>(
+        r#"SELECT title, exam_date, location, duration_minutes, weight FROM exams
+           WHERE course_id = ?
+           ORDER BY exam_date IS NULL, exam_date, id"#,
+    )
+    .bind(course_id)
+    .fetch_all(pool)
+    .await
+    .map_err(ApiError::from)?;
+
+    Ok(DeadlineSet {
+        format: FORMAT.to_string(),
+        version: VERSION,
+        exported_at: Some(chrono::Utc::now().to_rfc3339()),
+        course,
+        assignments,
+        exams,
+    })
+}
+
+/// Add the set's items to a course, skipping ones it already has
+pub(crate) async fn merge_set(
+    pool: &Pool<Sqlite>,
+    course_id: i64,
+    set: &DeadlineSet,
+    progress: &mut ProgressReporter,
+) -> Result<DeadlineImportResult, ApiError> {
+    validate_set(set)?;
+    progress.set_total((set.assignments.len() + set.exams.len()) as u64);
+    let mut tx = pool.begin().await.map_err(ApiError::from)?;
+
+    let exists: Option<i64> = sqlx::query_scalar("SELECT id FROM courses WHERE id = ?")
+        .bind(course_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(ApiError::from)?;
+    if exists.is_none() {
+        return Err(ApiError::not_found("Course not found"));
+    }
+
+    let mut result = DeadlineImportResult { source_course: set.course.name.clone(), ..Default::default() };
+
+    let existing: Vec<(String, Option<String>)> =
+        sqlx::query_as("SELECT title, due_date FROM assignments WHERE course_id = ?")
+            .bind(course_id)
+            .fetch_all(&mut *tx)
+            .await
+            .map_err(ApiError::from)?;
+    let mut seen: HashSet<_> = existing.iter().map(|(t, d)| merge_key(t, d.as_deref())).collect();
+    for a in &set.assignments {
+        progress.advance(1);
+        if !seen.insert(merge_key(&a.title, a.due_date.as_deref())) {
+            result.duplicates_skipped += 1;
+            continue;
+        }
+        let priority = a
+            .priority
+            .as_deref()
+            .map(|p| p.trim().to_lowercase())
+            .filter(|p| PRIORITIES.contains(&p.as_str()))
+            .unwrap_or_else(|| "medium".to_string());
+        let id: i64 = sqlx::query_scalar(
+            "INSERT INTO assignments (course_id, title, description, due_date, priority, estimated_minutes) VALUES (?, ?, ?, ?, ?, ?) RETURNING id",
+        )
+        .bind(course_id)
+        .bind(a.title.trim())
+        .bind(&a.description)
+        .bind(&a.due_date)
+        .bind(&priority)
+        .bind(a.estimated_minutes)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(ApiError::from)?;
+        change_log::record(&mut tx, Source::DeadlineImport, "assignments", id, None, &format!("Imported \"{}\"", a.title.trim())).await?;
+        result.assignments_added += 1;
+    }
+
+    let existing: Vec<(String, Option<String>)> =
+        sqlx::query_as("SELECT title, exam_date FROM exams WHERE course_id = ?")
+            .bind(course_id)
+            .fetch_all(&mut *tx)
+            .await
+            .map_err(ApiError::from)?;
+    let mut seen: HashSet<_> = existing.iter().map(|(t, d)| merge_key(t, d.as_deref())).collect();
+    for e in &set.exams {
+        progress.advance(1);
+        if !seen.insert(merge_key(&e.title, e.exam_date.as_deref())) {
+            result.duplicates_skipped += 1;
+            continue;
+        }
+        let id: i64 = sqlx::query_scalar(
+            "INSERT INTO exams (course_id, title, exam_date, location, duration_minutes, weight) VALUES (?, ?, ?, ?, ?, ?) RETURNING id",
+        )
+        .bind(course_id)
+        .bind(e.title.trim())
+        .bind(&e.exam_date)
+        .bind(&e.location)
+        .bind(e.duration_minutes)
+        .bind(e.weight)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(ApiError::from)?;
+        change_log::record(&mut tx, Source::DeadlineImport, "exams", id, None, &format!("Imported \"{}\"", e.title.trim())).await?;
+        result.exams_added += 1;
+    }
+
+    tx.commit().await.map_err(ApiError::from)?;
+    log::info!(
+        "Imported deadlines into course {}: {} assignments, {} exams, {} duplicates skipped",
+        course_id,
+        result.assignments_added,
+        result.exams_added,
+        result.duplicates_skipped
+    );
+    Ok(result)
+}
+
+/// Write a course's assignment and exam schedule to a JSON file at `path`
+#[tauri::command]
+pub async fn export_course_deadlines(
+    state: State<'_, DbState>,
+    course_id: i64,
+    path: String,
+) -> Result<DeadlineExportResult, ApiError> {
+    let pool = &state.0;
+    let path = validate_path(&path)?;
+
+    let set = build_set(pool, course_id).await?;
+    let json = serde_json::to_string_pretty(&set).map_err(|e| ApiError::internal(e.to_string()))?;
+    tokio::fs::write(&path, json)
+        .await
+        .map_err(|e| ApiError::internal(format!("Failed to write {}: {}", path.display(), e)))?;
+
+    Ok(DeadlineExportResult {
+        path: path.to_string_lossy().into_owned(),
+        assignments: set.assignments.len(),
+        exams: set.exams.len(),
+    })
+}
+
+/// Merge a deadline file into a course
+///
+/// Progress events carry `task_id` when given.
+#[tauri::command]
+pub async fn import_course_deadlines(
+    state: State<'_, DbState>,
+    course_id: i64,
+    path: String,
+    task_id: Option<String>,
+) -> Result<DeadlineImportResult, ApiError> {
+    let pool = &state.0;
+    let path = std::path::PathBuf::from(path.trim());
+    if !path.is_absolute() || !path.is_file() {
+        return Err(ApiError::validation("Import path must be an existing file"));
+    }
+    let size = tokio::fs::metadata(&path)
+        .await
+        .map_err(|e| ApiError::internal(format!("Failed to read {}: {}", path.display(), e)))?
+        .len();
+    if size > MAX_FILE_BYTES {
+        return Err(ApiError::validation("File is too large to be a deadline file"));
+    }
+
+    let text = tokio::fs::read_to_string(&path)
+        .await
+        .map_err(|e| ApiError::internal(format!("Failed to read {}: {}", path.display(), e)))?;
+    let set: DeadlineSet =
+        serde_json::from_str(&text).map_err(|e| ApiError::validation(format!("Not a valid deadline file: {}", e)))?;
+    let mut progress = ProgressReporter::new("import_course_deadlines", task_id);
+    match merge_set(pool, course_id, &set, &mut progress).await {
+        Ok(result) => {
+            progress.finish();
+            Ok(result)
+        }
+        Err(e) => Err(progress.fail(e)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+    use std::str::FromStr;
+
+    async fn setup_pool_with_migrations() -> Pool<Sqlite> {
+        let options = SqliteConnectOptions::from_str("sqlite::memory:")
+            .unwrap()
+            .foreign_keys(false);
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect_with(options)
+            .await
+            .expect("Failed to connect to in-memory DB");
+
+        crate::db::migrations::run_migrations(&pool)
+            .await
+            .expect("Failed to run migrations");
+
+        pool
+    }
+
+    #[tokio::test]
+    async fn deadline_sets_round_trip_and_merge_without_duplicates() {
+        let pool = setup_pool_with_migrations().await;
+        sqlx::query("INSERT INTO courses (id, name, code) VALUES (1, 'Organic Chemistry', 'CHEM 210'), (2, 'Orgo (mine)', NULL)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query(
+            r#"INSERT INTO assignments (course_id, title, description, due_date, priority, score, is_completed)
+               VALUES (1, 'Problem Set 1', 'Ch. 1-2', '2026-02-10T23:59:00', 'high', 92, 1),
+                      (1, 'Lab report', NULL, '2026-02-20', NULL, NULL, 0),
+                      (2, 'problem set 1 ', NULL, '2026-02-10', 'low', NULL, 0)"#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO exams (course_id, title, exam_date, location, notes, grade) VALUES (1, 'Midterm', '2026-03-05 09:00:00', 'Hall B', 'private', 81)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let set = build_set(&pool, 1).await.unwrap();
+        assert_eq!(set.course.code.as_deref(), Some("CHEM 210"));
+        assert_eq!((set.assignments.len(), set.exams.len()), (2, 1));
+        let json = serde_json::to_string(&set).unwrap();
+        // Only the schedule is shared
+        assert!(!json.contains("score") && !json.contains("grade") && !json.contains("private"));
+
+        let parsed: DeadlineSet = serde_json::from_str(&json).unwrap();
+        let mut progress = ProgressReporter::new("import_course_deadlines", None);
+        let first = merge_set(&pool, 2, &parsed, &mut progress).await.unwrap();
+        // Problem Set 1 already exists in course 2 under different case and time
+        assert_eq!((first.assignments_added, first.exams_added, first.duplicates_skipped), (1, 1, 1));
+        let again = merge_set(&pool, 2, &parsed, &mut progress).await.unwrap();
+        assert_eq!((again.assignments_added, again.exams_added, again.duplicates_skipped), (0, 0, 3));
+
+        let priority: String = sqlx::query_scalar("SELECT priority FROM assignments WHERE course_id = 2 AND title = 'Lab report'")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(priority, "medium");
+
+        let mut foreign = parsed.clone();
+        foreign.format = "something-else".to_string();
+        assert!(merge_set(&pool, 2, &foreign, &mut progress).await.is_err());
+        let mut bad_date = parsed.clone();
+        bad_date.exams[0].exam_date = Some("next tuesday".to_string());
+        assert!(merge_set(&pool, 2, &bad_date, &mut progress).await.is_err());
+        assert!(merge_set(&pool, 99, &parsed, &mut progress).await.is_err());
+    }
+}
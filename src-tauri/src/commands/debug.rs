@@ -1,5 +1,10 @@
+use serde::Serialize;
 use tauri::{Manager, State};
 
+use crate::agent::simulation::SimulationSummary;
+#[cfg(debug_assertions)]
+use crate::agent::simulation::{self, Persona, MAX_SIMULATION_DAYS, PERSONAS};
+use crate::db::connection::PoolConfig;
 use crate::{db::migrations::run_migrations, error::ApiError, DbState};
 
 #[tauri::command]
@@ -48,6 +53,49 @@ pub async fn reset_local_db(_app: tauri::AppHandle) -> Result<bool, ApiError> {
     ))
 }
 
+/// Generate `days` days of synthetic history for a persona
+/// (balanced, night_owl, crammer or athlete).
+/// This command is only available in debug builds.
+#[cfg(debug_assertions)]
+#[tauri::command]
+pub async fn simulate_history(
+    state: State<'_, DbState>,
+    days: i64,
+    persona: String,
+    seed: Option<u64>,
+) -> Result<SimulationSummary, ApiError> {
+    let pool = &state.0;
+    let persona = Persona::by_name(&persona).ok_or_else(|| {
+        let names: Vec<&str> = PERSONAS.iter().map(|p| p.name).collect();
+        ApiError::validation(format!("Unknown persona. Expected one of: {}", names.join(", ")))
+    })?;
+    if !(1..=MAX_SIMULATION_DAYS).contains(&days) {
+        return Err(ApiError::validation(format!(
+            "Days must be between 1 and {}",
+            MAX_SIMULATION_DAYS
+        )));
+    }
+
+    log::warn!("simulate_history called - inserting synthetic data");
+    simulation::simulate_history(pool, days, persona, seed)
+        .await
+        .map_err(ApiError::internal)
+}
+
+/// Stub for release builds - returns error if called
+#[cfg(not(debug_assertions))]
+#[tauri::command]
+pub async fn simulate_history(
+    _state: State<'_, DbState>,
+    _days: i64,
+    _persona: String,
+    _seed: Option<u64>,
+) -> Result<SimulationSummary, ApiError> {
+    Err(ApiError::validation(
+        "This operation is only available in development builds",
+    ))
+}
+
 /// Clears just the exercises cache (keeps everything else).
 #[tauri::command]
 pub async fn clear_exercises_cache(state: State<'_, DbState>) -> Result<i64, ApiError> {
@@ -83,3 +131,49 @@ pub async fn get_exercise_cache_stats(state: State<'_, DbState>) -> Result<serde
         }).collect::<Vec<_>>()
     }))
 }
+
+#[derive(Debug, Serialize)]
+pub struct DbPoolStatus {
+    pub max_connections: u32,
+    /// Connections currently open
+    pub open_connections: u32,
+    pub idle_connections: usize,
+    pub journal_mode: String,
+    pub busy_timeout_ms: i64,
+    /// 0 = off, 1 = normal, 2 = full, 3 = extra
+    pub synchronous: i64,
+    /// From settings; differs from the values above until the next start
+    pub configured: PoolConfig,
+}
+
+/// Debug: current pragma values and pool utilization
+#[tauri::command]
+pub async fn get_db_pool_status(state: State<'_, DbState>) -> Result<DbPoolStatus, ApiError> {
+    let pool = &state.0;
+
+    // Pragmas are per connection; every connection is opened with the same options
+    let mut conn = pool.acquire().await.map_err(ApiError::from)?;
+    let journal_mode: String = sqlx::query_scalar("PRAGMA journal_mode")
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(ApiError::from)?;
+    let busy_timeout_ms: i64 = sqlx::query_scalar("PRAGMA busy_timeout")
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(ApiError::from)?;
+    let synchronous: i64 = sqlx::query_scalar("PRAGMA synchronous")
+        .fetch_one(&mut *conn)
+        .await
+        .map_err(ApiError::from)?;
+    drop(conn);
+
+    Ok(DbPoolStatus {
+        max_connections: pool.options().get_max_connections(),
+        open_connections: pool.size(),
+        idle_connections: pool.num_idle(),
+        journal_mode: journal_mode.to_lowercase(),
+        busy_timeout_ms,
+        synchronous,
+        configured: PoolConfig::load(pool).await?,
+    })
+}
@@ -0,0 +1,292 @@
+//! Effort-to-Grade Efficiency
+//!
+//! Compares the study hours put into each active course with the grades that
+//! came back (graded exams and assignment scores). Each result is paired with
+//! the hours studied for its course in the `PREP_WINDOW_DAYS` before it; the
+//! least-squares slope of score on those hours estimates how many points an
+//! extra hour buys. Courses getting a lot of time for little return are
+//! flagged so the planner can move hours elsewhere.
+
+use serde::Serialize;
+use sqlx::{Pool, Sqlite};
+use tauri::State;
+
+use crate::{error::ApiError, DbState};
+
+const DEFAULT_WEEKS: i64 = 12;
+const MAX_WEEKS: i64 = 52;
+/// Study time before a result that counts as preparation for it
+pub(crate) const PREP_WINDOW_DAYS: i64 = 14;
+/// Results needed before a slope is estimated
+const MIN_RESULTS_FOR_SLOPE: usize = 3;
+/// Below this many points per extra hour, more hours barely help
+const LOW_RETURN_POINTS_PER_HOUR: f64 = 0.25;
+/// Weekly hours above which a low return is worth flagging
+const MIN_FLAGGED_WEEKLY_HOURS: f64 = 2.0;
+/// Cut suggested for courses with diminishing returns
+const DIMINISHING_HOURS_FACTOR: f64 = 0.8;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EfficiencyStatus {
+    /// Many hours, little grade movement
+    DiminishingReturns,
+    /// Below target while studying less than planned
+    UnderInvested,
+    OnTrack,
+    InsufficientData,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GradedResult {
+    pub kind: String,
+    pub title: String,
+    pub date: String,
+    pub score: f64,
+    /// Study hours for the course in the preparation window before it
+    pub prep_hours: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CourseEfficiency {
+    pub course_id: i64,
+    pub course_name: String,
+    pub study_hours: f64,
+    pub hours_per_week: f64,
+    pub target_weekly_hours: Option<f64>,
+    pub current_grade: Option<f64>,
+    pub target_grade: Option<f64>,
+    pub average_score: Option<f64>,
+    /// Estimated grade points per extra preparation hour
+    pub points_per_hour: Option<f64>,
+    pub status: EfficiencyStatus,
+    /// Weekly hours to plan for this course
+    pub suggested_weekly_hours: f64,
+    /// Oldest first
+    pub results: Vec<GradedResult>,
+}
+
+type CourseRow = (i64, String, Option<f64>, Option<f64>, Option<f64>, i64);
+
+/// Study effort vs grade outcomes per active course over the last `weeks` weeks
+#[tauri::command]
+pub async fn get_effort_efficiency(
+    state: State<'_, DbState>,
+    weeks: Option<i64>,
+) -> Result<Vec<CourseEfficiency>, ApiError> {
+    let pool = &state.0;
+    effort_efficiency(pool, weeks.unwrap_or(DEFAULT_WEEKS)).await
+}
+
+pub(crate) async fn effort_efficiency(pool: &Pool<Sqlite>, weeks: i64) -> Result<Vec<CourseEfficiency>, ApiError> {
+    if !(1..=MAX_WEEKS).contains(&weeks) {
+        return Err(ApiError::validation(format!("weeks must be between 1 and {}", MAX_WEEKS)));
+    }
+    let since = format!("-{} days", weeks * 7);
+
+    let courses: Vec<CourseRow> = sqlx::query_as(
+        r#"
+        SELECT c.id, c.name, c.target_weekly_hours, c.current_grade, c.target_grade,
+               (SELECT COALESCE(SUM(s.duration_minutes), 0) FROM sessions s
+                WHERE s.session_type = 'study' AND s.reference_type = 'course' AND s.reference_id = c.id
+                  AND s.started_at >= datetime('now', ?))
+        FROM courses c
+        WHERE c.is_active = 1
+        ORDER BY c.name
+        "#,
+    )
+    .bind(&since)
+    .fetch_all(pool)
+    .await
+    .map_err(ApiError::from)?;
+
+    let mut report = Vec::with_capacity(courses.len());
+    for (course_id, course_name, target_weekly_hours, current_grade, target_grade, minutes) in courses {
+        let results = graded_results(pool, course_id, &since).await?;
+
+        let hours_per_week = minutes as f64 / 60.0 / weeks as f64;
+        let average_score =
+            (!results.is_empty()).then(|| results.iter().map(|r| r.score).sum::<f64>() / results.len() as f64);
+        let points_per_hour = slope(&results);
+        let grade = current_grade.or(average_score);
+        let below_target = matches!((grade, target_grade), (Some(g), Some(t)) if g < t);
+
+        let status = match points_per_hour {
+            Some(p) if p < LOW_RETURN_POINTS_PER_HOUR && hours_per_week >= MIN_FLAGGED_WEEKLY_HOURS => {
+                EfficiencyStatus::DiminishingReturns
+            }
+            _ if below_target && target_weekly_hours.is_some_and(|t| hours_per_week < t) => {
+                EfficiencyStatus::UnderInvested
+            }
+            _ if grade.is_none() => EfficiencyStatus::InsufficientData,
+            _ => EfficiencyStatus::OnTrack,
+        };
+        let suggested_weekly_hours = match status {
+            EfficiencyStatus::DiminishingReturns => hours_per_week * DIMINISHING_HOURS_FACTOR,
+            EfficiencyStatus::UnderInvested => target_weekly_hours.unwrap_or(hours_per_week),
+            _ => target_weekly_hours.unwrap_or(hours_per_week).max(hours_per_week),
+        };
+
+        report.push(CourseEfficiency {
+            course_id,
+            course_name,
+            study_hours: minutes as f64 / 60.0,
+            hours_per_week,
+            target_weekly_hours,
+            current_grade,
+            target_grade,
+            average_score,
+            points_per_hour,
+            status,
+            suggested_weekly_hours: (suggested_weekly_hours * 2.0).round() / 2.0,
+            results,
+        });
+    }
+
+    Ok(report)
+}
+
+/// Graded exams and assignments since `since`, each with its preparation hours
+async fn graded_results(pool: &Pool<Sqlite>, course_id: i64, since: &str) -> Result<Vec<GradedResult>, ApiError> {
+    let rows: Vec<(String, String, String, f64, i64)> = sqlx::query_as(
+        r#"
+        WITH results(kind, title, at, score) AS (
+            SELECT 'exam', title, exam_date, grade FROM exams
+            WHERE course_id = ?1 AND grade IS NOT NULL AND exam_date IS NOT NULL
+            UNION ALL
+            SELECT 'assignment', title, COALESCE(completed_at, due_date), score FROM assignments
+            WHERE course_id = ?1 AND score IS NOT NULL AND COALESCE(completed_at, due_date) IS NOT NULL
+        )
+        SELECT kind, title, date(at), score,
+               (SELECT COALESCE(SUM(s.duration_minutes), 0) FROM sessions s
+                WHERE s.session_type = 'study' AND s.reference_type = 'course' AND s.reference_id = ?1
+                  AND s.started_at < datetime(at) AND s.started_at >= datetime(at, ?3))
+        FROM results
+        WHERE datetime(at) >= datetime('now', ?2)
+        ORDER BY at
+        "#,
+    )
+    .bind(course_id)
+    .bind(since)
+    .bind(format!("-{} days", PREP_WINDOW_DAYS))
+    .fetch_all(pool)
+    .await
+    .map_err(ApiError::from)?;
+
+    Ok(rows
+        .into_iter()
+        .map(|(kind, title, date, score, minutes)| GradedResult {
+            kind,
+            title,
+            date,
+            score,
+            prep_hours: minutes as f64 / 60.0,
+        })
+        .collect())
+}
+
+/// Least-squares slope of score on preparation hours
+fn slope(results: &[GradedResult]) -> Option<f64> {
+    if results.len() < MIN_RESULTS_FOR_SLOPE {
+        return None;
+    }
+    let n = results.len() as f64;
+    let mean_x = results.iter().map(|r| r.prep_hours).sum::<f64>() / n;
+    let mean_y = results.iter().map(|r| r.score).sum::<f64>() / n;
+    let sxx: f64 = results.iter().map(|r| (r.prep_hours - mean_x).powi(2)).sum();
+    if sxx < 1e-9 {
+        return None;
+    }
+    let sxy: f64 = results
+        .iter()
+        .map(|r| (r.prep_hours - mean_x) * (r.score - mean_y))
+        .sum();
+    Some(sxy / sxx)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+    use std::str::FromStr;
+
+    async fn setup_pool_with_migrations() -> Pool<Sqlite> {
+        let options = SqliteConnectOptions::from_str("sqlite::memory:")
+            .unwrap()
+            .foreign_keys(false);
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect_with(options)
+            .await
+            .expect("Failed to connect to in-memory DB");
+
+        crate::db::migrations::run_migrations(&pool)
+            .await
+            .expect("Failed to run migrations");
+
+        pool
+    }
+
+    /// A graded exam `days_ago` with `prep_minutes` of study in the days before it
+    async fn exam_with_prep(pool: &Pool<Sqlite>, course_id: i64, days_ago: i64, grade: f64, prep_minutes: i64) {
+        sqlx::query("INSERT INTO exams (course_id, title, exam_date, grade) VALUES (?, 'Exam', datetime('now', ?), ?)")
+            .bind(course_id)
+            .bind(format!("-{} days", days_ago))
+            .bind(grade)
+            .execute(pool)
+            .await
+            .unwrap();
+        sqlx::query(
+            "INSERT INTO sessions (session_type, reference_id, reference_type, started_at, duration_minutes) VALUES ('study', ?, 'course', datetime('now', ?), ?)",
+        )
+        .bind(course_id)
+        .bind(format!("-{} days", days_ago + 2))
+        .bind(prep_minutes)
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn flags_courses_where_hours_stop_paying_off() {
+        let pool = setup_pool_with_migrations().await;
+        let course = |name: &'static str| {
+            sqlx::query_scalar::<_, i64>(
+                "INSERT INTO courses (name, target_weekly_hours, target_grade) VALUES (?, 3, 90) RETURNING id",
+            )
+            .bind(name)
+            .fetch_one(&pool)
+        };
+        let flat = course("Chemistry").await.unwrap();
+        let steep = course("History").await.unwrap();
+
+        // Chemistry: 10-20 hours per exam, scores barely move
+        for (days_ago, grade, minutes) in [(60, 78.0, 600), (40, 79.0, 900), (20, 78.5, 1200)] {
+            exam_with_prep(&pool, flat, days_ago, grade, minutes).await;
+        }
+        // History: each extra hour shows up in the grade
+        for (days_ago, grade, minutes) in [(60, 70.0, 60), (40, 80.0, 180), (20, 88.0, 300)] {
+            exam_with_prep(&pool, steep, days_ago, grade, minutes).await;
+        }
+        sqlx::query("INSERT INTO assignments (course_id, title, due_date, score) VALUES (?, 'Essay', date('now', '-5 days'), 91)")
+            .bind(steep)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let report = effort_efficiency(&pool, 12).await.unwrap();
+        let chemistry = report.iter().find(|c| c.course_id == flat).unwrap();
+        assert_eq!(chemistry.status, EfficiencyStatus::DiminishingReturns);
+        assert!(chemistry.points_per_hour.unwrap().abs() < LOW_RETURN_POINTS_PER_HOUR);
+        assert!(chemistry.suggested_weekly_hours < chemistry.hours_per_week);
+
+        let history = report.iter().find(|c| c.course_id == steep).unwrap();
+        assert_eq!(history.results.len(), 4);
+        assert_eq!(history.results[3].kind, "assignment");
+        assert!(history.points_per_hour.unwrap() > LOW_RETURN_POINTS_PER_HOUR);
+        assert_eq!(history.status, EfficiencyStatus::UnderInvested);
+        assert_eq!(history.suggested_weekly_hours, 3.0);
+
+        assert!(effort_efficiency(&pool, 0).await.is_err());
+    }
+}
@@ -0,0 +1,37 @@
+//! Energy budget commands
+
+use chrono::NaiveDate;
+use tauri::State;
+
+use crate::services::energy::{self, EnergyBudget};
+use crate::{error::ApiError, DbState};
+
+/// Longest range of days returned at once
+const MAX_DAYS: i64 = 31;
+
+/// Each day's energy budget in `start_date..=end_date` (YYYY-MM-DD)
+#[tauri::command]
+pub async fn get_energy_budgets(
+    state: State<'_, DbState>,
+    start_date: String,
+    end_date: String,
+) -> Result<Vec<EnergyBudget>, ApiError> {
+    let parse = |value: &str, name: &str| {
+        NaiveDate::parse_from_str(value, "%Y-%m-%d")
+            .map_err(|_| ApiError::validation(format!("{} must be YYYY-MM-DD", name)))
+    };
+    let start = parse(&start_date, "start_date")?;
+    let end = parse(&end_date, "end_date")?;
+    if end < start || (end - start).num_days() >= MAX_DAYS {
+        return Err(ApiError::validation(format!(
+            "end_date must be on or up to {} days after start_date",
+            MAX_DAYS - 1
+        )));
+    }
+
+    let mut budgets = Vec::new();
+    for date in start.iter_days().take_while(|d| *d <= end) {
+        budgets.push(energy::energy_budget(&state.0, date).await?);
+    }
+    Ok(budgets)
+}
@@ -0,0 +1,234 @@
+//! Exam Readiness
+//!
+//! A 0-100 estimate of how prepared each upcoming exam is, from two signals:
+//! - revision progress: completed vs planned hours of the exam's accepted or
+//!   locked prep blocks (a replaced missed block counts through its
+//!   replacement)
+//! - past performance: the course's current grade, or the average of its
+//!   graded exams and assignments
+//!
+//! Flashcard maturity would be a third signal, but decks aren't tracked yet.
+//! Signals without data are left out of the weighted average.
+//!
+//! In an exam's final week, `planner_weight` boosts its revision in the week
+//! planner by how far it is from ready.
+
+use serde::Serialize;
+use sqlx::{Pool, Sqlite};
+use tauri::State;
+
+use crate::{error::ApiError, services::clock, DbState};
+
+/// Exams this many days ahead are included by default
+const DEFAULT_HORIZON_DAYS: i64 = 28;
+
+/// Exams this close get their revision re-weighted by readiness
+const FINAL_WEEK_DAYS: f64 = 7.0;
+
+/// Share of the readiness score from each signal, when both are known
+const REVISION_WEIGHT: f64 = 0.6;
+const PERFORMANCE_WEIGHT: f64 = 0.4;
+
+#[derive(Debug, Serialize)]
+pub struct ExamReadiness {
+    pub exam_id: i64,
+    pub course_id: i64,
+    pub course_name: String,
+    pub title: String,
+    pub exam_date: String,
+    pub days_until: f64,
+    pub planned_revision_hours: f64,
+    pub completed_revision_hours: f64,
+    /// Completed share of planned revision (0-1); None with nothing planned
+    pub revision_progress: Option<f64>,
+    /// Percent standing in the course; None before anything is graded
+    pub course_performance: Option<f64>,
+    /// 0-100; None when neither signal has data
+    pub readiness: Option<f64>,
+    /// Multiplies the exam's revision score in the planner: 1 outside the
+    /// final week, up to 2 for an unready exam within it
+    pub planner_weight: f64,
+}
+
+/// (id, course_id, course name, title, exam date, days until, planned minutes, completed minutes, performance)
+type ExamRow = (i64, i64, String, String, String, f64, i64, i64, Option<f64>);
+
+#[tauri::command]
+pub async fn get_exam_readiness(state: State<'_, DbState>, days: Option<i64>) -> Result<Vec<ExamReadiness>, ApiError> {
+    exam_readiness(&state.0, days.unwrap_or(DEFAULT_HORIZON_DAYS)).await
+}
+
+/// Readiness of ungraded exams in the next `days` days, soonest first
+pub(crate) async fn exam_readiness(pool: &Pool<Sqlite>, days: i64) -> Result<Vec<ExamReadiness>, ApiError> {
+    if days < 1 {
+        return Err(ApiError::validation("days must be at least 1"));
+    }
+    let now = clock::now().naive_local().format("%Y-%m-%d %H:%M:%S").to_string();
+
+    let rows: Vec<ExamRow> = sqlx::query_as(
+        r#"
+        WITH prep AS (
+            SELECT b.exam_id,
+                   (julianday(b.end_at) - julianday(b.start_at)) * 1440 AS planned,
+                   COALESCE(b.actual_minutes,
+                            CASE WHEN b.progress = 'done' THEN (julianday(b.end_at) - julianday(b.start_at)) * 1440 END,
+                            0) AS completed
+            FROM week_plan_blocks b
+            WHERE b.exam_id IS NOT NULL AND b.status IN ('accepted', 'locked') AND b.rescheduled_to_id IS NULL
+        )
+        SELECT e.id, e.course_id, c.name, e.title, e.exam_date,
+               julianday(e.exam_date) - julianday(?1),
+               CAST(COALESCE((SELECT ROUND(SUM(p.planned)) FROM prep p WHERE p.exam_id = e.id), 0) AS INTEGER),
+               CAST(COALESCE((SELECT ROUND(SUM(p.completed)) FROM prep p WHERE p.exam_id = e.id), 0) AS INTEGER),
+               COALESCE(c.current_grade, (
+                   SELECT AVG(g) FROM (
+                       SELECT grade AS g FROM exams WHERE course_id = e.course_id AND grade IS NOT NULL
+                       UNION ALL
+                       SELECT score FROM assignments WHERE course_id = e.course_id AND score IS NOT NULL
+                   )
+               ))
+        FROM exams e
+        JOIN courses c ON c.id = e.course_id
+        WHERE c.is_active = 1 AND e.score IS NULL AND e.exam_date IS NOT NULL
+          AND julianday(e.exam_date) >= julianday(?1)
+          AND julianday(e.exam_date) <= julianday(?1, '+' || ?2 || ' days')
+        ORDER BY julianday(e.exam_date)
+        "#,
+    )
+    .bind(&now)
+    .bind(days)
+    .fetch_all(pool)
+    .await
+    .map_err(ApiError::from)?;
+
+    Ok(rows.into_iter().map(readiness).collect())
+}
+
+fn readiness(row: ExamRow) -> ExamReadiness {
+    let (exam_id, course_id, course_name, title, exam_date, days_until, planned, completed, performance) = row;
+    let revision_progress = (planned > 0).then(|| (completed as f64 / planned as f64).min(1.0));
+
+    let signals: Vec<(f64, f64)> = [
+        revision_progress.map(|p| (p * 100.0, REVISION_WEIGHT)),
+        performance.map(|p| (p.clamp(0.0, 100.0), PERFORMANCE_WEIGHT)),
+    ]
+    .into_iter()
+    .flatten()
+    .collect();
+    let total_weight: f64 = signals.iter().map(|s| s.1).sum();
+    let readiness = (total_weight > 0.0)
+        .then(|| (signals.iter().map(|(score, weight)| score * weight).sum::<f64>() / total_weight).round());
+
+    let planner_weight = match readiness {
+        Some(score) if days_until <= FINAL_WEEK_DAYS => ((2.0 - score / 100.0) * 100.0).round() / 100.0,
+        _ => 1.0,
+    };
+
+    ExamReadiness {
+        exam_id,
+        course_id,
+        course_name,
+        title,
+        exam_date,
+        days_until: (days_until * 10.0).round() / 10.0,
+        planned_revision_hours: (planned as f64 / 6.0).round() / 10.0,
+        completed_revision_hours: (completed as f64 / 6.0).round() / 10.0,
+        revision_progress,
+        course_performance: performance,
+        readiness,
+        planner_weight,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Local, TimeZone};
+
+    async fn exam(pool: &Pool<Sqlite>, course_id: i64, date: &str) -> i64 {
+        sqlx::query_scalar("INSERT INTO exams (course_id, title, exam_date) VALUES (?, 'Midterm', ?) RETURNING id")
+            .bind(course_id)
+            .bind(date)
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    async fn prep_block(pool: &Pool<Sqlite>, exam_id: i64, day: &str, progress: Option<&str>, actual: Option<i64>) {
+        sqlx::query(
+            r#"INSERT INTO week_plan_blocks (week_start_date, start_at, end_at, block_type, title, status, exam_id, progress, actual_minutes)
+               VALUES ('2030-05-06', ?1 || 'T10:00:00', ?1 || 'T12:00:00', 'exam_prep', 'Prep', 'accepted', ?2, ?3, ?4)"#,
+        )
+        .bind(day)
+        .bind(exam_id)
+        .bind(progress)
+        .bind(actual)
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn combines_revision_progress_and_course_performance() {
+        let pool = crate::test_support::pool().await;
+        let _clock = clock::freeze(Local.with_ymd_and_hms(2030, 5, 8, 9, 0, 0).unwrap());
+        let course = crate::test_support::course("Chemistry").create(&pool).await;
+        let near = exam(&pool, course.id, "2030-05-12 09:00:00").await;
+        let far = exam(&pool, course.id, "2030-05-30 09:00:00").await;
+
+        // 2h done of 4h planned
+        prep_block(&pool, near, "2030-05-06", Some("done"), Some(90)).await;
+        prep_block(&pool, near, "2030-05-07", Some("done"), None).await;
+        prep_block(&pool, near, "2030-05-09", None, None).await;
+        prep_block(&pool, near, "2030-05-10", None, None).await;
+
+        let report = exam_readiness(&pool, 28).await.unwrap();
+        assert_eq!(report.len(), 2);
+        let first = &report[0];
+        assert_eq!(first.exam_id, near);
+        assert_eq!(first.planned_revision_hours, 8.0);
+        assert_eq!(first.completed_revision_hours, 3.5);
+        assert_eq!(first.course_performance, None);
+        // Revision alone: 3.5 of 8 hours
+        assert_eq!(first.readiness, Some(44.0));
+        assert_eq!(first.planner_weight, 1.56);
+        // Nothing known about the later exam, and it isn't in its final week
+        assert_eq!(report[1].exam_id, far);
+        assert_eq!(report[1].readiness, None);
+        assert_eq!(report[1].planner_weight, 1.0);
+
+        sqlx::query("UPDATE courses SET current_grade = 84 WHERE id = ?")
+            .bind(course.id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        let report = exam_readiness(&pool, 28).await.unwrap();
+        // 0.6 * 43.75 + 0.4 * 84
+        assert_eq!(report[0].readiness, Some(60.0));
+        assert_eq!(report[0].planner_weight, 1.4);
+        assert_eq!(report[1].readiness, Some(84.0));
+        assert_eq!(report[1].planner_weight, 1.0);
+    }
+
+    #[tokio::test]
+    async fn graded_and_past_exams_are_left_out() {
+        let pool = crate::test_support::pool().await;
+        let _clock = clock::freeze(Local.with_ymd_and_hms(2030, 5, 8, 9, 0, 0).unwrap());
+        let course = crate::test_support::course("Biology").create(&pool).await;
+        exam(&pool, course.id, "2030-05-01 09:00:00").await;
+        let graded = exam(&pool, course.id, "2030-05-10 09:00:00").await;
+        sqlx::query("UPDATE exams SET score = 40, max_score = 50, grade = 80 WHERE id = ?")
+            .bind(graded)
+            .execute(&pool)
+            .await
+            .unwrap();
+        let open = exam(&pool, course.id, "2030-05-11 09:00:00").await;
+
+        let report = exam_readiness(&pool, 28).await.unwrap();
+        assert_eq!(report.len(), 1);
+        assert_eq!(report[0].exam_id, open);
+        // The graded exam still counts as past performance
+        assert_eq!(report[0].course_performance, Some(80.0));
+        assert!(exam_readiness(&pool, 0).await.is_err());
+    }
+}
@@ -1,6 +1,13 @@
+use sqlx::{Pool, Sqlite};
 use tauri::State;
 
-use crate::{DbState, error::ApiError, models::exam::Exam};
+use crate::{
+    DbState,
+    commands::week_plan_blocks::{release_remaining_blocks, Deliverable},
+    db::filter::FilteredQuery,
+    error::ApiError,
+    models::exam::Exam,
+};
 
 #[derive(Debug, serde::Deserialize)]
 pub struct ExamInput {
@@ -35,7 +42,7 @@ pub async fn create_exam(state: State<'_, DbState>, data: ExamInput) -> Result<E
         r#"
         INSERT INTO exams (course_id, title, exam_date, location, duration_minutes, notes, grade, weight)
         VALUES (?, ?, ?, ?, ?, ?, ?, ?)
-        RETURNING id, course_id, title, exam_date, location, duration_minutes, notes, grade, weight, created_at
+        RETURNING id, course_id, title, exam_date, location, duration_minutes, notes, grade, weight, score, max_score, post_mortem, created_at
         "#
     )
     .bind(course_id)
@@ -61,24 +68,17 @@ pub async fn create_exam(state: State<'_, DbState>, data: ExamInput) -> Result<E
 pub async fn get_exams(state: State<'_, DbState>, course_id: Option<i64>) -> Result<Vec<Exam>, ApiError> {
     let pool = &state.0;
     
-    let exams = if let Some(cid) = course_id {
-        sqlx::query_as::<_, Exam>(
-            "SELECT * FROM exams WHERE course_id = ? ORDER BY exam_date ASC, created_at DESC"
-        )
-        .bind(cid)
+    let mut query = FilteredQuery::new("SELECT * FROM exams")
+        .eq("course_id", course_id)
+        .order_by("exam_date ASC, created_at DESC");
+    let exams = query
+        .build_query_as::<Exam>()
         .fetch_all(pool)
         .await
-    } else {
-        sqlx::query_as::<_, Exam>(
-            "SELECT * FROM exams ORDER BY exam_date ASC, created_at DESC"
-        )
-        .fetch_all(pool)
-        .await
-    }
-    .map_err(|e| {
-        log::error!("Failed to fetch exams: {}", e);
-        ApiError::from_sqlx(e, "Failed to fetch exams")
-    })?;
+        .map_err(|e| {
+            log::error!("Failed to fetch exams: {}", e);
+            ApiError::from_sqlx(e, "Failed to fetch exams")
+        })?;
     
     Ok(exams)
 }
@@ -114,7 +114,7 @@ pub async fn update_exam(state: State<'_, DbState>, id: i64, data: ExamInput) ->
             grade = COALESCE(?, grade),
             weight = COALESCE(?, weight)
         WHERE id = ?
-        RETURNING id, course_id, title, exam_date, location, duration_minutes, notes, grade, weight, created_at
+        RETURNING id, course_id, title, exam_date, location, duration_minutes, notes, grade, weight, score, max_score, post_mortem, created_at
         "#
     )
     .bind(&data.title)
@@ -136,6 +136,67 @@ pub async fn update_exam(state: State<'_, DbState>, id: i64, data: ExamInput) ->
     Ok(rec)
 }
 
+#[derive(Debug, serde::Deserialize)]
+pub struct ExamResultInput {
+    pub score: f64,
+    /// Defaults to 100, so a bare score is a percentage
+    #[serde(default)]
+    pub max_score: Option<f64>,
+    #[serde(default)]
+    pub post_mortem: Option<String>,
+}
+
+/// Record the achieved score; the exam's grade becomes its percentage
+#[tauri::command]
+pub async fn record_exam_result(state: State<'_, DbState>, id: i64, data: ExamResultInput) -> Result<Exam, ApiError> {
+    let pool = &state.0;
+    record_result(pool, id, data).await
+}
+
+pub(crate) async fn record_result(pool: &Pool<Sqlite>, id: i64, data: ExamResultInput) -> Result<Exam, ApiError> {
+    let max_score = data.max_score.unwrap_or(100.0);
+    if !max_score.is_finite() || max_score <= 0.0 {
+        return Err(ApiError::validation("max_score must be greater than 0"));
+    }
+    // Extra credit can go past the maximum, but not by more than half
+    if !data.score.is_finite() || data.score < 0.0 || data.score > max_score * 1.5 {
+        return Err(ApiError::validation("score must be between 0 and 150% of max_score"));
+    }
+    let grade = (data.score / max_score * 1000.0).round() / 10.0;
+    let post_mortem = data
+        .post_mortem
+        .map(|p| p.trim().to_string())
+        .filter(|p| !p.is_empty());
+
+    let rec = sqlx::query_as::<_, Exam>(
+        r#"
+        UPDATE exams SET
+            score = ?,
+            max_score = ?,
+            grade = ?,
+            post_mortem = COALESCE(?, post_mortem)
+        WHERE id = ?
+        RETURNING id, course_id, title, exam_date, location, duration_minutes, notes, grade, weight, score, max_score, post_mortem, created_at
+        "#
+    )
+    .bind(data.score)
+    .bind(max_score)
+    .bind(grade)
+    .bind(&post_mortem)
+    .bind(id)
+    .fetch_optional(pool)
+    .await
+    .map_err(|e| {
+        log::error!("Failed to record result for exam {}: {}", id, e);
+        ApiError::from_sqlx(e, "Failed to record exam result")
+    })?
+    .ok_or_else(|| ApiError::not_found("Exam not found"))?;
+
+    log::info!("Exam result recorded: id={} grade={}", id, grade);
+    release_remaining_blocks(pool, Deliverable::Exam(id)).await?;
+    Ok(rec)
+}
+
 #[tauri::command]
 pub async fn delete_exam(state: State<'_, DbState>, id: i64) -> Result<bool, ApiError> {
     let pool = &state.0;
@@ -0,0 +1,423 @@
+//! Analytics CSV export
+//!
+//! Writes raw sessions, practice logs, workouts, check-ins or per-day
+//! aggregates to a CSV file for analysis in pandas, R or a spreadsheet. The
+//! cycle log is left out unless `cycle_include_in_exports` is on.
+//! Every export starts with a local `date` column (YYYY-MM-DD) so files can
+//! be joined on it; empty cells mean NULL.
+//!
+//! Rows are streamed from the database to the file, so memory stays flat
+//! however large the export; progress is reported as `task://progress`.
+
+use std::path::PathBuf;
+
+use chrono::NaiveDate;
+use futures::TryStreamExt;
+use serde::{Deserialize, Serialize};
+use sqlx::{Pool, Row, Sqlite};
+use tauri::State;
+use tokio::io::{AsyncWrite, AsyncWriteExt, BufWriter};
+
+use crate::services::{progress::ProgressReporter, settings};
+use crate::{error::ApiError, DbState};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ExportMetric {
+    Sessions,
+    Practice,
+    Workouts,
+    Checkins,
+    /// One row per day with activity
+    Daily,
+    /// One row per logged period
+    Cycle,
+}
+
+/// Inclusive date range (YYYY-MM-DD); open ends export everything
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct ExportRange {
+    pub from: Option<String>,
+    pub to: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ExportResult {
+    pub path: String,
+    pub metric: ExportMetric,
+    /// Data rows written (excluding the header)
+    pub rows: usize,
+}
+
+impl ExportMetric {
+    fn columns(self) -> &'static [&'static str] {
+        match self {
+            ExportMetric::Sessions => &[
+                "date",
+                "id",
+                "session_type",
+                "course",
+                "started_at",
+                "ended_at",
+                "duration_minutes",
+                "planned_minutes",
+                "focus_rating",
+                "notes",
+            ],
+            ExportMetric::Practice => &[
+                "date",
+                "id",
+                "skill",
+                "skill_category",
+                "logged_at",
+                "duration_minutes",
+                "notes",
+            ],
+            ExportMetric::Workouts => &[
+                "date",
+                "id",
+                "name",
+                "logged_at",
+                "duration_minutes",
+                "exercise_count",
+                "notes",
+            ],
+            ExportMetric::Checkins => &["date", "id", "checked_in_at", "mood", "energy", "notes"],
+            ExportMetric::Daily => &[
+                "date",
+                "study_minutes",
+                "practice_minutes",
+                "workout_count",
+                "workout_minutes",
+                "avg_mood",
+                "avg_energy",
+            ],
+            ExportMetric::Cycle => &["date", "end_date", "period_days", "note"],
+        }
+    }
+
+    /// Query selecting `columns()` in order, filtered by two date binds
+    fn query(self) -> &'static str {
+        match self {
+            ExportMetric::Sessions => {
+                r#"
+                SELECT date(s.started_at, 'localtime'), s.id, s.session_type,
+                       CASE WHEN s.reference_type = 'course' THEN c.name END,
+                       s.started_at, s.ended_at, s.duration_minutes, s.planned_minutes,
+                       s.focus_rating, s.notes
+                FROM sessions s
+                LEFT JOIN courses c ON c.id = s.reference_id
+                WHERE date(s.started_at, 'localtime') BETWEEN ? AND ?
+                ORDER BY s.started_at, s.id
+                "#
+            }
+            ExportMetric::Practice => {
+                r#"
+                SELECT date(p.logged_at, 'localtime'), p.id, sk.name, sk.category,
+                       p.logged_at, p.duration_minutes, p.notes
+                FROM practice_logs p
+                LEFT JOIN skills sk ON sk.id = p.skill_id
+                WHERE date(p.logged_at, 'localtime') BETWEEN ? AND ?
+                ORDER BY p.logged_at, p.id
+                "#
+            }
+            ExportMetric::Workouts => {
+                r#"
+                SELECT date(w.logged_at, 'localtime'), w.id, w.name, w.logged_at, w.duration_minutes,
+                       (SELECT COUNT(*) FROM workout_exercises we WHERE we.workout_id = w.id),
+                       w.notes
+                FROM workouts w
+                WHERE date(w.logged_at, 'localtime') BETWEEN ? AND ?
+                ORDER BY w.logged_at, w.id
+                "#
+            }
+            ExportMetric::Checkins => {
+                r#"
+                SELECT date(checked_in_at, 'localtime'), id, checked_in_at, mood, energy, notes
+                FROM check_ins
+                WHERE date(checked_in_at, 'localtime') BETWEEN ? AND ?
+                ORDER BY checked_in_at, id
+                "#
+            }
+            ExportMetric::Daily => {
+                r#"
+                WITH days(day) AS (
+                    SELECT date(started_at, 'localtime') FROM sessions
+                    UNION SELECT date(logged_at, 'localtime') FROM practice_logs
+                    UNION SELECT date(logged_at, 'localtime') FROM workouts
+                    UNION SELECT date(checked_in_at, 'localtime') FROM check_ins
+                )
+                SELECT day,
+                       (SELECT COALESCE(SUM(duration_minutes), 0) FROM sessions
+                        WHERE session_type = 'study' AND date(started_at, 'localtime') = day),
+                       (SELECT COALESCE(SUM(duration_minutes), 0) FROM practice_logs
+                        WHERE date(logged_at, 'localtime') = day),
+                       (SELECT COUNT(*) FROM workouts WHERE date(logged_at, 'localtime') = day),
+                       (SELECT COALESCE(SUM(duration_minutes), 0) FROM workouts
+                        WHERE date(logged_at, 'localtime') = day),
+                       (SELECT printf('%.2f', AVG(mood)) FROM check_ins
+                        WHERE mood IS NOT NULL AND date(checked_in_at, 'localtime') = day),
+                       (SELECT printf('%.2f', AVG(energy)) FROM check_ins
+                        WHERE energy IS NOT NULL AND date(checked_in_at, 'localtime') = day)
+                FROM days
+                WHERE day BETWEEN ? AND ?
+                ORDER BY day
+                "#
+            }
+            ExportMetric::Cycle => {
+                r#"
+                SELECT start_date, end_date,
+                       CAST(julianday(end_date) - julianday(start_date) AS INTEGER) + 1, note
+                FROM cycle_periods
+                WHERE start_date BETWEEN ? AND ?
+                ORDER BY start_date
+                "#
+            }
+        }
+    }
+}
+
+/// Export one metric to a CSV file at `path`
+///
+/// Progress events carry `task_id` when given.
+#[tauri::command]
+pub async fn export_analytics_csv(
+    state: State<'_, DbState>,
+    metric: ExportMetric,
+    range: Option<ExportRange>,
+    path: String,
+    task_id: Option<String>,
+) -> Result<ExportResult, ApiError> {
+    let pool = &state.0;
+    let path = validate_path(&path)?;
+    let mut progress = ProgressReporter::new("export_analytics_csv", task_id);
+
+    let result = async {
+        let file = tokio::fs::File::create(&path)
+            .await
+            .map_err(|e| ApiError::internal(format!("Failed to create {}: {}", path.display(), e)))?;
+        let mut out = BufWriter::new(file);
+        write_csv(pool, metric, &range.unwrap_or_default(), &mut out, &mut progress).await
+    }
+    .await;
+
+    match result {
+        Ok(rows) => {
+            progress.finish();
+            Ok(ExportResult {
+                path: path.to_string_lossy().into_owned(),
+                metric,
+                rows,
+            })
+        }
+        Err(e) => {
+            // Don't leave a truncated file behind
+            let _ = tokio::fs::remove_file(&path).await;
+            Err(progress.fail(e))
+        }
+    }
+}
+
+pub(crate) fn validate_path(path: &str) -> Result<PathBuf, ApiError> {
+    let path = PathBuf::from(path.trim());
+    if !path.is_absolute() {
+        return Err(ApiError::validation("Export path must be absolute"));
+    }
+    if path.is_dir() {
+        return Err(ApiError::validation("Export path is a directory"));
+    }
+    match path.parent() {
+        Some(parent) if parent.is_dir() => Ok(path),
+        _ => Err(ApiError::validation("Export directory does not exist")),
+    }
+}
+
+fn parse_bound(value: Option<&str>, name: &str, default: &str) -> Result<String, ApiError> {
+    match value.map(str::trim).filter(|v| !v.is_empty()) {
+        Some(v) => NaiveDate::parse_from_str(v, "%Y-%m-%d")
+            .map(|d| d.to_string())
+            .map_err(|_| ApiError::validation(format!("'{}' must be a YYYY-MM-DD date", name))),
+        None => Ok(default.to_string()),
+    }
+}
+
+/// Stream the CSV into `out`; returns the number of data rows
+pub(crate) async fn write_csv<W: AsyncWrite + Unpin>(
+    pool: &Pool<Sqlite>,
+    metric: ExportMetric,
+    range: &ExportRange,
+    out: &mut W,
+    progress: &mut ProgressReporter,
+) -> Result<usize, ApiError> {
+    let from = parse_bound(range.from.as_deref(), "from", "0000-01-01")?;
+    let to = parse_bound(range.to.as_deref(), "to", "9999-12-31")?;
+    if from > to {
+        return Err(ApiError::validation("'from' must not be after 'to'"));
+    }
+    if metric == ExportMetric::Cycle && !settings::get_bool(pool, "cycle_include_in_exports").await? {
+        return Err(ApiError::validation(
+            "The cycle log is left out of exports; turn on cycle_include_in_exports to export it",
+        ));
+    }
+
+    let total: i64 = sqlx::query_scalar(&format!("SELECT COUNT(*) FROM ({})", metric.query()))
+        .bind(&from)
+        .bind(&to)
+        .fetch_one(pool)
+        .await
+        .map_err(ApiError::from)?;
+    progress.set_total(total.max(0) as u64);
+
+    let columns = metric.columns();
+    let mut line = columns.join(",");
+    line.push('\n');
+    write(out, &line).await?;
+
+    let mut rows = sqlx::query(metric.query()).bind(&from).bind(&to).fetch(pool);
+    let mut count = 0;
+    while let Some(row) = rows.try_next().await.map_err(ApiError::from)? {
+        let cells: Vec<String> = (0..columns.len())
+            .map(|i| cell(&row, i).map(|v| escape(&v)).unwrap_or_default())
+            .collect();
+        line = cells.join(",");
+        line.push('\n');
+        write(out, &line).await?;
+        count += 1;
+        progress.advance(1);
+    }
+
+    out.flush()
+        .await
+        .map_err(|e| ApiError::internal(format!("Failed to write export: {}", e)))?;
+    Ok(count)
+}
+
+async fn write<W: AsyncWrite + Unpin>(out: &mut W, text: &str) -> Result<(), ApiError> {
+    out.write_all(text.as_bytes())
+        .await
+        .map_err(|e| ApiError::internal(format!("Failed to write export: {}", e)))
+}
+
+/// Build the CSV text; returns it with the number of data rows
+#[cfg(test)]
+async fn render_csv(
+    pool: &Pool<Sqlite>,
+    metric: ExportMetric,
+    range: &ExportRange,
+) -> Result<(String, usize), ApiError> {
+    let mut out = Vec::new();
+    let mut progress = ProgressReporter::new("export_analytics_csv", None);
+    let rows = write_csv(pool, metric, range, &mut out, &mut progress).await?;
+    Ok((String::from_utf8(out).expect("CSV is UTF-8"), rows))
+}
+
+/// Read a column as text whatever its SQLite storage class
+fn cell(row: &sqlx::sqlite::SqliteRow, i: usize) -> Option<String> {
+    if let Ok(v) = row.try_get::<Option<i64>, _>(i) {
+        return v.map(|n| n.to_string());
+    }
+    if let Ok(v) = row.try_get::<Option<f64>, _>(i) {
+        return v.map(|n| n.to_string());
+    }
+    row.try_get::<Option<String>, _>(i).ok().flatten()
+}
+
+/// RFC 4180 quoting
+fn escape(value: &str) -> String {
+    if value.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_string()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+    use std::str::FromStr;
+
+    async fn setup_pool_with_migrations() -> Pool<Sqlite> {
+        let options = SqliteConnectOptions::from_str("sqlite::memory:")
+            .unwrap()
+            .foreign_keys(false);
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect_with(options)
+            .await
+            .expect("Failed to connect to in-memory DB");
+
+        crate::db::migrations::run_migrations(&pool)
+            .await
+            .expect("Failed to run migrations");
+
+        pool
+    }
+
+    #[test]
+    fn test_escape() {
+        assert_eq!(escape("plain"), "plain");
+        assert_eq!(escape("a,b"), "\"a,b\"");
+        assert_eq!(escape("say \"hi\"\nbye"), "\"say \"\"hi\"\"\nbye\"");
+    }
+
+    #[tokio::test]
+    async fn exports_rows_with_consistent_columns() {
+        let pool = setup_pool_with_migrations().await;
+        sqlx::query(
+            r#"
+            INSERT INTO courses (id, name) VALUES (1, 'Algorithms');
+            INSERT INTO sessions (session_type, reference_id, reference_type, started_at, duration_minutes, notes)
+            VALUES ('study', 1, 'course', datetime('now'), 50, 'graphs, trees');
+            INSERT INTO workouts (name, duration_minutes, logged_at) VALUES ('Run', 30, datetime('now'));
+            INSERT INTO check_ins (mood, energy, checked_in_at) VALUES (6, 7, datetime('now')), (8, 5, datetime('now'));
+            "#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let (csv, rows) = render_csv(&pool, ExportMetric::Sessions, &ExportRange::default())
+            .await
+            .unwrap();
+        assert_eq!(rows, 1);
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(lines[0], ExportMetric::Sessions.columns().join(","));
+        assert!(lines[1].contains(",study,Algorithms,"));
+        assert!(lines[1].ends_with(",50,,,\"graphs, trees\""));
+
+        let (csv, rows) = render_csv(&pool, ExportMetric::Daily, &ExportRange::default())
+            .await
+            .unwrap();
+        assert_eq!(rows, 1);
+        assert!(csv.lines().nth(1).unwrap().ends_with(",50,0,1,30,7.00,6.00"));
+
+        for metric in [ExportMetric::Practice, ExportMetric::Workouts, ExportMetric::Checkins] {
+            let (csv, _) = render_csv(&pool, metric, &ExportRange::default()).await.unwrap();
+            assert!(csv.starts_with("date,"));
+        }
+
+        let past = ExportRange {
+            from: Some("2000-01-01".into()),
+            to: Some("2000-12-31".into()),
+        };
+        let (_, rows) = render_csv(&pool, ExportMetric::Checkins, &past).await.unwrap();
+        assert_eq!(rows, 0);
+
+        let bad = ExportRange {
+            from: Some("yesterday".into()),
+            to: None,
+        };
+        assert!(render_csv(&pool, ExportMetric::Daily, &bad).await.is_err());
+
+        // The cycle log needs its own opt-in
+        sqlx::query("INSERT INTO cycle_periods (start_date, end_date) VALUES ('2030-01-03', '2030-01-07')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        assert!(render_csv(&pool, ExportMetric::Cycle, &ExportRange::default()).await.is_err());
+        settings::set(&pool, "cycle_include_in_exports", serde_json::json!(true)).await.unwrap();
+        let (csv, _) = render_csv(&pool, ExportMetric::Cycle, &ExportRange::default()).await.unwrap();
+        assert_eq!(csv.lines().nth(1), Some("2030-01-03,2030-01-07,5,"));
+    }
+}
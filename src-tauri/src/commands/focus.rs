@@ -0,0 +1,16 @@
+//! Focus mode commands
+
+use tauri::State;
+
+use crate::services::focus::{self, DistractionReport};
+use crate::{error::ApiError, DbState};
+
+/// Time spent per app during a session, with the distracted share
+#[tauri::command]
+pub async fn get_session_distraction_report(
+    state: State<'_, DbState>,
+    session_id: i64,
+) -> Result<DistractionReport, ApiError> {
+    let pool = &state.0;
+    focus::session_report(pool, session_id).await
+}
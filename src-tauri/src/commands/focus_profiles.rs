@@ -0,0 +1,416 @@
+//! Focus Profiles
+//!
+//! Named timer setups (work/break lengths, auto-start, a tag) kept in the
+//! `focus_profiles` setting, so they sync and broadcast like any other
+//! setting. A session started with `focus_profile_id` records which profile
+//! it ran under, which is what `get_focus_profile_stats` compares.
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::{Pool, Sqlite};
+use tauri::State;
+
+use crate::agent::outcomes::session_outcome;
+use crate::services::settings;
+use crate::{error::ApiError, DbState};
+
+const SETTING_KEY: &str = "focus_profiles";
+const MAX_PROFILES: usize = 20;
+const MAX_NAME_LENGTH: usize = 60;
+const MAX_TAG_LENGTH: usize = 40;
+const DEFAULT_STATS_DAYS: i64 = 90;
+
+/// (focus_profile_id, duration_minutes, planned_minutes, focus_rating)
+type SessionRow = (i64, Option<i64>, Option<i64>, Option<i64>);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct FocusProfile {
+    pub id: i64,
+    pub name: String,
+    pub work_minutes: i64,
+    pub break_minutes: i64,
+    pub long_break_minutes: i64,
+    /// Work blocks before a long break
+    pub long_break_every: i64,
+    /// Start the break as soon as a work block ends
+    pub auto_start_breaks: bool,
+    /// Start the next work block as soon as a break ends
+    pub auto_start_work: bool,
+    /// Free-form label, e.g. the soundscape played
+    pub tag: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct FocusProfileInput {
+    pub name: String,
+    pub work_minutes: i64,
+    pub break_minutes: i64,
+    #[serde(default)]
+    pub long_break_minutes: Option<i64>,
+    #[serde(default)]
+    pub long_break_every: Option<i64>,
+    #[serde(default)]
+    pub auto_start_breaks: bool,
+    #[serde(default)]
+    pub auto_start_work: bool,
+    #[serde(default)]
+    pub tag: Option<String>,
+}
+
+impl FocusProfileInput {
+    fn into_profile(self, id: i64) -> FocusProfile {
+        FocusProfile {
+            id,
+            name: self.name,
+            work_minutes: self.work_minutes,
+            break_minutes: self.break_minutes,
+            long_break_minutes: self.long_break_minutes.unwrap_or(self.break_minutes * 3),
+            long_break_every: self.long_break_every.unwrap_or(4),
+            auto_start_breaks: self.auto_start_breaks,
+            auto_start_work: self.auto_start_work,
+            tag: self.tag,
+        }
+    }
+}
+
+fn normalize(mut profile: FocusProfile) -> Result<FocusProfile, ApiError> {
+    profile.name = profile.name.trim().to_string();
+    if profile.name.is_empty() || profile.name.len() > MAX_NAME_LENGTH {
+        return Err(ApiError::validation(format!(
+            "Focus profile name must be 1-{} characters",
+            MAX_NAME_LENGTH
+        )));
+    }
+    let ranges = [
+        ("work_minutes", profile.work_minutes, 1, 240),
+        ("break_minutes", profile.break_minutes, 0, 120),
+        ("long_break_minutes", profile.long_break_minutes, 0, 240),
+        ("long_break_every", profile.long_break_every, 1, 12),
+    ];
+    for (field, value, min, max) in ranges {
+        if !(min..=max).contains(&value) {
+            return Err(ApiError::validation(format!(
+                "{} must be between {} and {}",
+                field, min, max
+            )));
+        }
+    }
+    profile.tag = profile
+        .tag
+        .map(|t| t.trim().to_string())
+        .filter(|t| !t.is_empty());
+    if profile.tag.as_ref().is_some_and(|t| t.len() > MAX_TAG_LENGTH) {
+        return Err(ApiError::validation(format!(
+            "Focus profile tag must be at most {} characters",
+            MAX_TAG_LENGTH
+        )));
+    }
+    Ok(profile)
+}
+
+/// Validate the `focus_profiles` setting: well-formed, unique ids and names
+pub fn validate_profiles(value: Value) -> Result<Value, ApiError> {
+    let profiles: Vec<FocusProfile> = serde_json::from_value(value)
+        .map_err(|e| ApiError::validation(format!("Invalid focus profiles: {}", e)))?;
+    if profiles.len() > MAX_PROFILES {
+        return Err(ApiError::validation(format!("At most {} focus profiles", MAX_PROFILES)));
+    }
+
+    let profiles = profiles.into_iter().map(normalize).collect::<Result<Vec<_>, _>>()?;
+    for (i, profile) in profiles.iter().enumerate() {
+        if profiles[..i].iter().any(|p| p.id == profile.id) {
+            return Err(ApiError::validation(format!("Duplicate focus profile id {}", profile.id)));
+        }
+        if profiles[..i].iter().any(|p| p.name.eq_ignore_ascii_case(&profile.name)) {
+            return Err(ApiError::conflict(format!(
+                "A focus profile named \"{}\" already exists",
+                profile.name
+            )));
+        }
+    }
+
+    serde_json::to_value(profiles).map_err(|e| ApiError::internal(e.to_string()))
+}
+
+pub(crate) async fn load(pool: &Pool<Sqlite>) -> Result<Vec<FocusProfile>, ApiError> {
+    Ok(serde_json::from_value(settings::get(pool, SETTING_KEY).await?).unwrap_or_default())
+}
+
+async fn store(pool: &Pool<Sqlite>, profiles: &[FocusProfile]) -> Result<(), ApiError> {
+    let value = serde_json::to_value(profiles).map_err(|e| ApiError::internal(e.to_string()))?;
+    settings::set(pool, SETTING_KEY, value).await?;
+    Ok(())
+}
+
+/// A profile by id
+pub(crate) async fn find(pool: &Pool<Sqlite>, id: i64) -> Result<FocusProfile, ApiError> {
+    load(pool)
+        .await?
+        .into_iter()
+        .find(|p| p.id == id)
+        .ok_or_else(|| ApiError::not_found("Focus profile not found"))
+}
+
+#[tauri::command]
+pub async fn get_focus_profiles(state: State<'_, DbState>) -> Result<Vec<FocusProfile>, ApiError> {
+    load(&state.0).await
+}
+
+#[tauri::command]
+pub async fn create_focus_profile(
+    state: State<'_, DbState>,
+    data: FocusProfileInput,
+) -> Result<FocusProfile, ApiError> {
+    create(&state.0, data).await
+}
+
+pub(crate) async fn create(pool: &Pool<Sqlite>, data: FocusProfileInput) -> Result<FocusProfile, ApiError> {
+    let mut profiles = load(pool).await?;
+    // Ids are never reused, so sessions keep pointing at the right profile
+    let used: i64 = sqlx::query_scalar("SELECT COALESCE(MAX(focus_profile_id), 0) FROM sessions")
+        .fetch_one(pool)
+        .await
+        .map_err(ApiError::from)?;
+    let id = profiles.iter().map(|p| p.id).max().unwrap_or(0).max(used) + 1;
+
+    let profile = normalize(data.into_profile(id))?;
+    profiles.push(profile.clone());
+    store(pool, &profiles).await?;
+    Ok(profile)
+}
+
+#[tauri::command]
+pub async fn update_focus_profile(
+    state: State<'_, DbState>,
+    id: i64,
+    data: FocusProfileInput,
+) -> Result<FocusProfile, ApiError> {
+    let pool = &state.0;
+    let mut profiles = load(pool).await?;
+    let slot = profiles
+        .iter_mut()
+        .find(|p| p.id == id)
+        .ok_or_else(|| ApiError::not_found("Focus profile not found"))?;
+    let profile = normalize(data.into_profile(id))?;
+    *slot = profile.clone();
+    store(pool, &profiles).await?;
+    Ok(profile)
+}
+
+/// Delete a profile; sessions run with it keep its id
+#[tauri::command]
+pub async fn delete_focus_profile(state: State<'_, DbState>, id: i64) -> Result<(), ApiError> {
+    let pool = &state.0;
+    let mut profiles = load(pool).await?;
+    let before = profiles.len();
+    profiles.retain(|p| p.id != id);
+    if profiles.len() == before {
+        return Err(ApiError::not_found("Focus profile not found"));
+    }
+    store(pool, &profiles).await
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FocusProfileStats {
+    pub profile_id: i64,
+    /// None once the profile has been deleted
+    pub name: Option<String>,
+    pub tag: Option<String>,
+    pub sessions: i64,
+    pub total_minutes: i64,
+    pub avg_minutes: f64,
+    /// Average 1-5 rating over rated sessions
+    pub avg_focus_rating: Option<f64>,
+    /// Average share of the planned minutes actually done (capped at 1)
+    pub completion_rate: Option<f64>,
+    /// Average outcome score (0-1), as reported to the agent
+    pub avg_outcome: f64,
+}
+
+/// Compare finished sessions by the focus profile they ran under
+#[tauri::command]
+pub async fn get_focus_profile_stats(
+    state: State<'_, DbState>,
+    days: Option<i64>,
+) -> Result<Vec<FocusProfileStats>, ApiError> {
+    profile_stats(&state.0, days.unwrap_or(DEFAULT_STATS_DAYS)).await
+}
+
+pub(crate) async fn profile_stats(pool: &Pool<Sqlite>, days: i64) -> Result<Vec<FocusProfileStats>, ApiError> {
+    if !(1..=3650).contains(&days) {
+        return Err(ApiError::validation("days must be between 1 and 3650"));
+    }
+
+    let rows: Vec<SessionRow> = sqlx::query_as(
+        r#"
+        SELECT focus_profile_id, duration_minutes, planned_minutes, focus_rating
+        FROM sessions
+        WHERE focus_profile_id IS NOT NULL AND ended_at IS NOT NULL
+          AND started_at >= datetime('now', ?)
+        "#,
+    )
+    .bind(format!("-{} days", days))
+    .fetch_all(pool)
+    .await
+    .map_err(ApiError::from)?;
+
+    #[derive(Default)]
+    struct Acc {
+        sessions: i64,
+        minutes: i64,
+        ratings: Vec<f64>,
+        completions: Vec<f64>,
+        outcome: f64,
+    }
+
+    let mut by_profile: HashMap<i64, Acc> = HashMap::new();
+    for (profile_id, duration, planned, rating) in rows {
+        let acc = by_profile.entry(profile_id).or_default();
+        acc.sessions += 1;
+        acc.minutes += duration.unwrap_or(0).max(0);
+        if let Some(rating) = rating {
+            acc.ratings.push(rating as f64);
+        }
+        if let Some(planned) = planned.filter(|p| *p > 0) {
+            acc.completions.push((duration.unwrap_or(0).max(0) as f64 / planned as f64).min(1.0));
+        }
+        acc.outcome += session_outcome(duration, planned, rating) as f64;
+    }
+
+    let profiles = load(pool).await?;
+    let mean = |values: &[f64]| (!values.is_empty()).then(|| values.iter().sum::<f64>() / values.len() as f64);
+    let mut stats: Vec<FocusProfileStats> = by_profile
+        .into_iter()
+        .map(|(profile_id, acc)| {
+            let profile = profiles.iter().find(|p| p.id == profile_id);
+            FocusProfileStats {
+                profile_id,
+                name: profile.map(|p| p.name.clone()),
+                tag: profile.and_then(|p| p.tag.clone()),
+                sessions: acc.sessions,
+                total_minutes: acc.minutes,
+                avg_minutes: acc.minutes as f64 / acc.sessions as f64,
+                avg_focus_rating: mean(&acc.ratings),
+                completion_rate: mean(&acc.completions),
+                avg_outcome: acc.outcome / acc.sessions as f64,
+            }
+        })
+        .collect();
+    stats.sort_by(|a, b| b.avg_outcome.total_cmp(&a.avg_outcome).then(a.profile_id.cmp(&b.profile_id)));
+    Ok(stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+    use std::str::FromStr;
+
+    async fn setup_pool_with_migrations() -> Pool<Sqlite> {
+        let options = SqliteConnectOptions::from_str("sqlite::memory:")
+            .unwrap()
+            .foreign_keys(false);
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect_with(options)
+            .await
+            .expect("Failed to connect to in-memory DB");
+
+        crate::db::migrations::run_migrations(&pool)
+            .await
+            .expect("Failed to run migrations");
+
+        pool
+    }
+
+    fn input(name: &str, work: i64) -> FocusProfileInput {
+        FocusProfileInput {
+            name: name.to_string(),
+            work_minutes: work,
+            break_minutes: 10,
+            long_break_minutes: None,
+            long_break_every: None,
+            auto_start_breaks: true,
+            auto_start_work: false,
+            tag: Some(" rain ".to_string()),
+        }
+    }
+
+    #[test]
+    fn validation_rejects_duplicates_and_bad_lengths() {
+        let profile = json!({
+            "id": 1, "name": "Deep", "work_minutes": 50, "break_minutes": 10,
+            "long_break_minutes": 30, "long_break_every": 3,
+            "auto_start_breaks": false, "auto_start_work": false, "tag": null
+        });
+        assert!(validate_profiles(json!([profile])).is_ok());
+
+        let mut renamed = profile.clone();
+        renamed["id"] = json!(2);
+        renamed["name"] = json!("deep");
+        assert!(validate_profiles(json!([profile, renamed])).is_err());
+
+        let mut too_long = profile.clone();
+        too_long["work_minutes"] = json!(600);
+        assert!(validate_profiles(json!([too_long])).is_err());
+    }
+
+    #[tokio::test]
+    async fn sessions_are_compared_by_profile() {
+        let pool = setup_pool_with_migrations().await;
+        let deep = create(&pool, input("Deep work", 50)).await.unwrap();
+        assert_eq!(deep.tag.as_deref(), Some("rain"));
+        assert_eq!(deep.long_break_minutes, 30);
+
+        let session = crate::commands::sessions::insert_session(
+            &pool,
+            &crate::commands::sessions::SessionInput {
+                user_id: None,
+                session_type: crate::models::session::SessionType::Study,
+                reference_id: None,
+                reference_type: None,
+                started_at: None,
+                notes: None,
+                planned_minutes: None,
+                focus_profile_id: Some(deep.id),
+                title: None,
+                course_meeting_id: None,
+                meeting_date: None,
+                week_plan_block_id: None,
+            },
+        )
+        .await
+        .unwrap();
+        // The profile's work length becomes the plan
+        assert_eq!(session.planned_minutes, Some(50));
+        assert_eq!(session.focus_profile_id, Some(deep.id));
+
+        sqlx::query(
+            "INSERT INTO sessions (session_type, started_at, ended_at, duration_minutes, planned_minutes, focus_rating, focus_profile_id) VALUES
+             ('study', datetime('now', '-1 day'), datetime('now', '-1 day'), 50, 50, 5, ?),
+             ('study', datetime('now', '-1 day'), datetime('now', '-1 day'), 10, 25, NULL, 99)",
+        )
+        .bind(deep.id)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let stats = profile_stats(&pool, 30).await.unwrap();
+        assert_eq!(stats.len(), 2);
+        assert_eq!(stats[0].name.as_deref(), Some("Deep work"));
+        assert_eq!(stats[0].sessions, 1);
+        assert_eq!(stats[0].completion_rate, Some(1.0));
+        assert!((stats[0].avg_outcome - 1.0).abs() < 1e-6);
+        // A deleted profile still shows up, unnamed
+        assert_eq!(stats[1].profile_id, 99);
+        assert!(stats[1].name.is_none());
+
+        // New ids skip past ids still referenced by sessions
+        let next = create(&pool, input("Sprint", 15)).await.unwrap();
+        assert_eq!(next.id, 100);
+        assert!(create(&pool, input("sprint", 20)).await.is_err());
+    }
+}
@@ -0,0 +1,235 @@
+//! Free Slot Finder
+//!
+//! Open time between busy calendar items, limited to working hours and
+//! outside blackout windows (see `services::working_hours`). The planner's
+//! auto-scheduler places suggested blocks into these slots.
+
+use chrono::{DateTime, Duration, Local, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Timelike};
+use serde::{Deserialize, Serialize};
+use sqlx::{Pool, Sqlite};
+use tauri::State;
+
+use crate::{
+    commands::calendar::{get_calendar_items_for_pool, CalendarQuery},
+    error::ApiError,
+    services::working_hours::{subtract, Schedule},
+    DbState,
+};
+
+const MAX_RANGE_DAYS: i64 = 31;
+const DEFAULT_MIN_MINUTES: i64 = 30;
+/// Slots start on a quarter hour
+const SLOT_STEP_MINUTES: i64 = 15;
+
+#[derive(Debug, Deserialize)]
+pub struct FreeSlotQuery {
+    pub start_date: String, // YYYY-MM-DD
+    pub end_date: String,   // YYYY-MM-DD, inclusive
+    #[serde(default)]
+    pub min_minutes: Option<i64>,
+    /// Treat suggested plan blocks as busy (default: they are being replaced)
+    #[serde(default)]
+    pub include_suggested: Option<bool>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct FreeSlot {
+    pub date: String,
+    pub start_at: String, // RFC3339
+    pub end_at: String,   // RFC3339
+    pub minutes: i64,
+}
+
+#[tauri::command]
+pub async fn find_free_slots(state: State<'_, DbState>, query: FreeSlotQuery) -> Result<Vec<FreeSlot>, ApiError> {
+    free_slots(&state.0, query).await
+}
+
+pub(crate) fn local_naive(value: &str) -> Option<NaiveDateTime> {
+    DateTime::parse_from_rfc3339(value)
+        .ok()
+        .map(|dt| dt.with_timezone(&Local).naive_local())
+}
+
+pub(crate) fn round_up_to_step(at: NaiveDateTime) -> NaiveDateTime {
+    let minutes = at.time().num_seconds_from_midnight() as i64 / 60;
+    let remainder = minutes % SLOT_STEP_MINUTES;
+    let floored = at.date().and_time(NaiveTime::MIN) + Duration::minutes(minutes);
+    if remainder == 0 && at.second() == 0 {
+        floored
+    } else {
+        floored + Duration::minutes(SLOT_STEP_MINUTES - remainder)
+    }
+}
+
+fn to_rfc3339(at: NaiveDateTime) -> String {
+    Local
+        .from_local_datetime(&at)
+        .earliest()
+        .map(|dt| dt.to_rfc3339())
+        .unwrap_or_else(|| at.format("%Y-%m-%dT%H:%M:%S").to_string())
+}
+
+pub(crate) async fn free_slots(pool: &Pool<Sqlite>, query: FreeSlotQuery) -> Result<Vec<FreeSlot>, ApiError> {
+    let start_date = NaiveDate::parse_from_str(&query.start_date, "%Y-%m-%d")
+        .map_err(|_| ApiError::validation("Invalid start_date format"))?;
+    let end_date = NaiveDate::parse_from_str(&query.end_date, "%Y-%m-%d")
+        .map_err(|_| ApiError::validation("Invalid end_date format"))?;
+    if end_date < start_date || (end_date - start_date).num_days() >= MAX_RANGE_DAYS {
+        return Err(ApiError::validation(format!(
+            "end_date must be on or after start_date and within {} days",
+            MAX_RANGE_DAYS
+        )));
+    }
+    let min_minutes = query.min_minutes.unwrap_or(DEFAULT_MIN_MINUTES);
+    if !(1..=24 * 60).contains(&min_minutes) {
+        return Err(ApiError::validation("min_minutes must be between 1 and 1440"));
+    }
+    let include_suggested = query.include_suggested.unwrap_or(false);
+
+    let schedule = Schedule::load(pool).await?;
+    let items = get_calendar_items_for_pool(
+        pool,
+        CalendarQuery {
+            start_date: query.start_date.clone(),
+            end_date: query.end_date.clone(),
+            include_assignments: Some(false),
+            include_exams: Some(true),
+        },
+    )
+    .await?;
+
+    let mut busy: Vec<(NaiveDateTime, NaiveDateTime)> = Vec::new();
+    for item in items.iter().filter(|i| i.busy) {
+        if item.source == "plan_block" && item.status.as_deref() == Some("suggested") && !include_suggested {
+            continue;
+        }
+        let (Some(start), Some(end)) = (local_naive(&item.start_at), local_naive(&item.end_at)) else {
+            continue;
+        };
+        if item.all_day {
+            let day = start.date().and_time(NaiveTime::MIN);
+            busy.push((day, day + Duration::days(1)));
+        } else {
+            busy.push((start, end));
+        }
+    }
+
+    let mut slots = Vec::new();
+    let mut date = start_date;
+    while date <= end_date {
+        for (start, end) in subtract(&schedule.schedulable(date), &busy) {
+            let start = round_up_to_step(start);
+            let minutes = (end - start).num_minutes();
+            if minutes >= min_minutes {
+                slots.push(FreeSlot {
+                    date: date.to_string(),
+                    start_at: to_rfc3339(start),
+                    end_at: to_rfc3339(end),
+                    minutes,
+                });
+            }
+        }
+        date += Duration::days(1);
+    }
+
+    Ok(slots)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::settings;
+    use serde_json::json;
+    use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+    use std::str::FromStr;
+
+    async fn setup_pool_with_migrations() -> Pool<Sqlite> {
+        let options = SqliteConnectOptions::from_str("sqlite::memory:")
+            .unwrap()
+            .foreign_keys(false);
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect_with(options)
+            .await
+            .expect("Failed to connect to in-memory DB");
+
+        crate::db::migrations::run_migrations(&pool)
+            .await
+            .expect("Failed to run migrations");
+
+        pool
+    }
+
+    fn query(date: &str) -> FreeSlotQuery {
+        FreeSlotQuery {
+            start_date: date.to_string(),
+            end_date: date.to_string(),
+            min_minutes: Some(30),
+            include_suggested: None,
+        }
+    }
+
+    fn local_hm(value: &str) -> String {
+        local_naive(value).unwrap().format("%H:%M").to_string()
+    }
+
+    #[tokio::test]
+    async fn slots_respect_working_hours_blackouts_and_busy_items() {
+        let pool = setup_pool_with_migrations().await;
+        // 2026-03-02 is a Monday
+        settings::set(
+            &pool,
+            "working_hours",
+            json!({ "monday": { "start": "09:00", "end": "17:00" } }),
+        )
+        .await
+        .unwrap();
+        settings::set(
+            &pool,
+            "blackout_windows",
+            json!([{ "label": "Lunch", "start": "12:00", "end": "13:00" }]),
+        )
+        .await
+        .unwrap();
+        sqlx::query(
+            r#"INSERT INTO calendar_events (title, start_at, end_at, category)
+               VALUES ('Meeting', '2026-03-02T10:00:00', '2026-03-02T10:50:00', 'work'),
+                      ('Coffee', '2026-03-02T14:00:00', '2026-03-02T15:00:00', 'break')"#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO week_plan_blocks (week_start_date, start_at, end_at, block_type, status) VALUES ('2026-03-02', '2026-03-02T15:00:00', '2026-03-02T16:00:00', 'study', 'suggested')",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let slots = free_slots(&pool, query("2026-03-02")).await.unwrap();
+        let ranges: Vec<(String, String)> = slots
+            .iter()
+            .map(|s| (local_hm(&s.start_at), local_hm(&s.end_at)))
+            .collect();
+        // The free "break" event and the suggested block don't block time
+        assert_eq!(
+            ranges,
+            vec![
+                ("09:00".to_string(), "10:00".to_string()),
+                ("11:00".to_string(), "12:00".to_string()),
+                ("13:00".to_string(), "17:00".to_string()),
+            ]
+        );
+
+        let mut with_suggested = query("2026-03-02");
+        with_suggested.include_suggested = Some(true);
+        assert_eq!(free_slots(&pool, with_suggested).await.unwrap().len(), 4);
+
+        // Sunday is off by the new working hours
+        assert!(free_slots(&pool, query("2026-03-01")).await.unwrap().is_empty());
+        assert!(free_slots(&pool, FreeSlotQuery { end_date: "2026-02-01".to_string(), ..query("2026-03-02") })
+            .await
+            .is_err());
+    }
+}
@@ -0,0 +1,13 @@
+//! Gamification commands
+
+use tauri::State;
+
+use crate::services::gamification::{self, GamificationStatus};
+use crate::{error::ApiError, DbState};
+
+/// XP, overall and domain levels, and recent level-ups; computed even while
+/// gamification is off so the settings page can preview it
+#[tauri::command]
+pub async fn get_gamification_status(state: State<'_, DbState>) -> Result<GamificationStatus, ApiError> {
+    gamification::status(&state.0).await
+}
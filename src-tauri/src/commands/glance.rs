@@ -0,0 +1,172 @@
+//! Glance data
+//!
+//! One small payload for the tray popover and OS widgets. Streaks and the
+//! calendar come from the aggregates cache; only the running session and
+//! today's Big Three are read live (both single indexed queries), which keeps
+//! the call well under 50ms.
+
+use chrono::{DateTime, Local};
+use serde::Serialize;
+use sqlx::{Pool, Sqlite};
+use tauri::State;
+
+use crate::commands::analytics::Streaks;
+use crate::services::aggregates;
+use crate::{error::ApiError, DbState};
+
+#[derive(Debug, Serialize)]
+pub struct GlanceSession {
+    pub id: i64,
+    pub session_type: String,
+    pub course_name: Option<String>,
+    pub started_at: String,
+    /// Seconds since the session started, at the time of the call
+    pub elapsed_seconds: i64,
+    pub planned_minutes: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GlanceItem {
+    pub title: String,
+    pub source: String,
+    pub start_at: String,
+    pub end_at: String,
+    pub color: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GlanceData {
+    pub active_session: Option<GlanceSession>,
+    pub next_item: Option<GlanceItem>,
+    pub big_three_completed: i64,
+    pub big_three_total: i64,
+    pub streaks: Streaks,
+}
+
+/// (id, session_type, course_name, started_at, elapsed_seconds, planned_minutes)
+type SessionRow = (i64, String, Option<String>, String, i64, Option<i64>);
+
+/// Compact status for a tray popover or widget
+#[tauri::command]
+pub async fn get_glance_data(state: State<'_, DbState>) -> Result<GlanceData, ApiError> {
+    glance_data(&state.0).await
+}
+
+async fn glance_data(pool: &Pool<Sqlite>) -> Result<GlanceData, ApiError> {
+    let cached = aggregates::get(pool).await?;
+
+    let session: Option<SessionRow> = sqlx::query_as(
+        r#"
+        SELECT s.id, s.session_type, c.name, s.started_at,
+               CAST(strftime('%s', 'now') - strftime('%s', s.started_at) AS INTEGER),
+               s.planned_minutes
+        FROM sessions s
+        LEFT JOIN courses c ON s.reference_type = 'course' AND c.id = s.reference_id
+        WHERE s.ended_at IS NULL
+        ORDER BY s.started_at DESC, s.id DESC
+        LIMIT 1
+        "#,
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(ApiError::from)?;
+
+    let (big_three_completed, big_three_total): (i64, i64) = sqlx::query_as(
+        "SELECT COALESCE(SUM(is_completed), 0), COUNT(*) FROM agent_big_three WHERE date = ?",
+    )
+    .bind(Local::now().format("%Y-%m-%d").to_string())
+    .fetch_one(pool)
+    .await
+    .map_err(ApiError::from)?;
+
+    // Next timed item that hasn't started yet
+    let now = Local::now();
+    let next_item = cached
+        .upcoming
+        .iter()
+        .filter(|i| !i.all_day)
+        .find(|i| {
+            DateTime::parse_from_rfc3339(&i.start_at)
+                .map(|start| start > now)
+                .unwrap_or(false)
+        })
+        .map(|i| GlanceItem {
+            title: i.title.clone(),
+            source: i.source.clone(),
+            start_at: i.start_at.clone(),
+            end_at: i.end_at.clone(),
+            color: i.color.clone(),
+        });
+
+    Ok(GlanceData {
+        active_session: session.map(
+            |(id, session_type, course_name, started_at, elapsed_seconds, planned_minutes)| GlanceSession {
+                id,
+                session_type,
+                course_name,
+                started_at,
+                elapsed_seconds: elapsed_seconds.max(0),
+                planned_minutes,
+            },
+        ),
+        next_item,
+        big_three_completed,
+        big_three_total,
+        streaks: cached.streaks.clone(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+    use std::str::FromStr;
+    use std::time::{Duration, Instant};
+
+    async fn setup_pool_with_migrations() -> Pool<Sqlite> {
+        let options = SqliteConnectOptions::from_str("sqlite::memory:")
+            .unwrap()
+            .foreign_keys(false);
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect_with(options)
+            .await
+            .expect("Failed to connect to in-memory DB");
+
+        crate::db::migrations::run_migrations(&pool)
+            .await
+            .expect("Failed to run migrations");
+
+        pool
+    }
+
+    #[tokio::test]
+    async fn glance_reports_running_session_within_budget() {
+        let pool = setup_pool_with_migrations().await;
+        for day in 0..60 {
+            sqlx::query(
+                "INSERT INTO sessions (session_type, started_at, ended_at, duration_minutes)
+                 VALUES ('study', datetime('now', ?), datetime('now', ?), 60)",
+            )
+            .bind(format!("-{} days", day + 1))
+            .bind(format!("-{} days", day + 1))
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+        sqlx::query("INSERT INTO sessions (session_type, started_at) VALUES ('study', datetime('now', '-10 minutes'))")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let glance = glance_data(&pool).await.unwrap();
+        let session = glance.active_session.unwrap();
+        assert!((590..=610).contains(&session.elapsed_seconds));
+        assert_eq!(glance.big_three_total, 0);
+
+        // Warm cache
+        let started = Instant::now();
+        glance_data(&pool).await.unwrap();
+        assert!(started.elapsed() < Duration::from_millis(50));
+    }
+}
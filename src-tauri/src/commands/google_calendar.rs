@@ -1,14 +1,16 @@
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicU32, Ordering};
 use std::sync::Arc;
 
 use base64::Engine as _;
 use chrono::{DateTime, Datelike, Duration, Local, NaiveDate, NaiveDateTime, TimeZone, Utc};
-use keyring::Entry;
+use futures::StreamExt;
 use rand::{distributions::Alphanumeric, Rng};
-use reqwest::Client;
+use reqwest::{Client, Method, RequestBuilder, StatusCode};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use sha2::Digest;
 use tauri::State;
+#[cfg(desktop)]
 use tokio::io::{AsyncReadExt, AsyncWriteExt};
 use tokio::sync::Mutex;
 use url::Url;
@@ -16,7 +18,16 @@ use url::Url;
 use crate::{
     DbState,
     error::ApiError,
-    models::google_account::GoogleAccount,
+    commands::categories::category_map,
+    models::{category::Category, google_account::GoogleAccount},
+    platform,
+    services::{
+        calendar_feed,
+        change_log::{self, Source},
+        clock,
+        google_sync_journal::{self, JournalEntry, Operation, Resolution},
+        settings,
+    },
     utils::parse_datetime_to_rfc3339,
 };
 
@@ -26,9 +37,32 @@ const GOOGLE_USERINFO_URL: &str = "https://openidconnect.googleapis.com/v1/useri
 const GOOGLE_CALENDAR_API: &str = "https://www.googleapis.com/calendar/v3";
 const GOOGLE_SCOPES: &str = "https://www.googleapis.com/auth/calendar openid email";
 const LIFE_OS_PLAN_CALENDAR: &str = "Life OS Plan";
+/// Setting that exports plan blocks as untitled busy events
+const BUSY_ONLY_SETTING: &str = "google_export_busy_only";
+const BUSY_TITLE: &str = "Busy";
 const TOKEN_EXPIRY_BUFFER_SECONDS: i64 = 60;
 const WINDOW_PAST_DAYS: i64 = 30;
 const WINDOW_FUTURE_DAYS: i64 = 90;
+/// Plan event writes in flight at once during a sync
+const WRITE_CONCURRENCY: usize = 4;
+/// Retries for a rate limited or failed (5xx) calendar API call
+const MAX_RETRIES: u32 = 5;
+const BACKOFF_BASE_MS: u64 = 500;
+const BACKOFF_MAX_MS: u64 = 32_000;
+/// Google's fixed event palette: (colorId, background hex)
+const GOOGLE_EVENT_COLORS: [(&str, (u8, u8, u8)); 11] = [
+    ("1", (0x79, 0x86, 0xcb)),
+    ("2", (0x33, 0xb6, 0x79)),
+    ("3", (0x8e, 0x24, 0xaa)),
+    ("4", (0xe6, 0x7c, 0x73)),
+    ("5", (0xf6, 0xbf, 0x26)),
+    ("6", (0xf4, 0x51, 0x1e)),
+    ("7", (0x03, 0x9b, 0xe5)),
+    ("8", (0x61, 0x61, 0x61)),
+    ("9", (0x3f, 0x51, 0xb5)),
+    ("10", (0x0b, 0x80, 0x43)),
+    ("11", (0xd5, 0x00, 0x00)),
+];
 
 #[derive(Clone)]
 pub struct GoogleState {
@@ -73,6 +107,8 @@ pub struct GoogleSyncStatus {
     pub connected: bool,
     pub email: Option<String>,
     pub last_sync: Option<String>,
+    /// Calendar API calls made by the last sync
+    pub last_sync_calls: Option<SyncApiCalls>,
     pub client_id_set: bool,
     pub client_id: Option<String>,
 }
@@ -122,6 +158,9 @@ struct GoogleEvent {
     etag: Option<String>,
     start: GoogleEventTime,
     end: GoogleEventTime,
+    #[serde(rename = "colorId")]
+    color_id: Option<String>,
+    transparency: Option<String>,
     #[serde(rename = "extendedProperties")]
     extended_properties: Option<GoogleExtendedProperties>,
 }
@@ -147,6 +186,10 @@ struct GoogleEventInsert {
     summary: String,
     start: GoogleEventTimeInsert,
     end: GoogleEventTimeInsert,
+    #[serde(rename = "colorId", skip_serializing_if = "Option::is_none")]
+    color_id: Option<String>,
+    /// "opaque" (busy) or "transparent" (free)
+    transparency: String,
     #[serde(rename = "extendedProperties")]
     extended_properties: GoogleExtendedPropertiesInsert,
 }
@@ -163,28 +206,141 @@ struct GoogleExtendedPropertiesInsert {
     private_props: HashMap<String, String>,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ChangeAction {
+    Create,
+    Update,
+    Delete,
+}
+
+/// One change a sync would make
+#[derive(Debug, Serialize)]
+pub struct SyncChange {
+    pub action: ChangeAction,
+    /// "calendar_event" or "week_plan_block"
+    pub local_type: String,
+    /// None for items that do not exist locally yet
+    pub local_id: Option<i64>,
+    pub google_calendar_id: String,
+    /// None for events Google has not created yet
+    pub google_event_id: Option<String>,
+    pub title: Option<String>,
+    pub start_at: Option<String>,
+    pub end_at: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct GoogleSyncPreview {
+    /// Changes to calendar events and plan blocks on this device
+    pub local: Vec<SyncChange>,
+    /// Changes to events on Google
+    pub remote: Vec<SyncChange>,
+    /// The "Life OS Plan" calendar does not exist yet and would be created
+    pub creates_plan_calendar: bool,
+    /// Operations interrupted by a crash that the sync reconciles first
+    pub interrupted_operations: usize,
+    /// Calendar API calls made to compute the preview
+    pub calls: SyncApiCalls,
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct SyncApiCalls {
+    pub reads: u32,
+    pub writes: u32,
+    /// Calls repeated after a rate limit or server error
+    pub retries: u32,
+}
+
+/// Calendar API access for one sync
+///
+/// Every call goes through `send`, which counts it and retries rate limit
+/// (429, 403 `rateLimitExceeded`) and 5xx responses with jittered
+/// exponential backoff, honoring `Retry-After` when Google sends one.
+struct GoogleApi {
+    client: Client,
+    access_token: String,
+    reads: AtomicU32,
+    writes: AtomicU32,
+    retries: AtomicU32,
+}
+
+impl GoogleApi {
+    fn new(access_token: String) -> Self {
+        Self {
+            client: Client::new(),
+            access_token,
+            reads: AtomicU32::new(0),
+            writes: AtomicU32::new(0),
+            retries: AtomicU32::new(0),
+        }
+    }
+
+    fn calls(&self) -> SyncApiCalls {
+        SyncApiCalls {
+            reads: self.reads.load(Ordering::Relaxed),
+            writes: self.writes.load(Ordering::Relaxed),
+            retries: self.retries.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Send a request built by `build`, retrying while Google asks to back off
+    async fn send(&self, build: impl Fn(&Client) -> RequestBuilder) -> Result<(StatusCode, String), ApiError> {
+        let mut attempt = 0;
+        loop {
+            let request = build(&self.client)
+                .bearer_auth(&self.access_token)
+                .build()
+                .map_err(ApiError::from)?;
+            let counter = if request.method() == Method::GET { &self.reads } else { &self.writes };
+            counter.fetch_add(1, Ordering::Relaxed);
+
+            let res = self.client.execute(request).await.map_err(ApiError::from)?;
+            let status = res.status();
+            let retry_after = res
+                .headers()
+                .get(reqwest::header::RETRY_AFTER)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.parse::<u64>().ok());
+            let body = res.text().await.map_err(ApiError::from)?;
+
+            if attempt >= MAX_RETRIES || !should_retry(status, &body) {
+                return Ok((status, body));
+            }
+            self.retries.fetch_add(1, Ordering::Relaxed);
+            tokio::time::sleep(backoff_delay(attempt, retry_after)).await;
+            attempt += 1;
+        }
+    }
+
+    async fn json<T: DeserializeOwned>(&self, build: impl Fn(&Client) -> RequestBuilder) -> Result<T, ApiError> {
+        let (status, body) = self.send(build).await?;
+        decode_json_response(status, &body)
+    }
+}
+
+fn should_retry(status: StatusCode, body: &str) -> bool {
+    status == StatusCode::TOO_MANY_REQUESTS
+        || status.is_server_error()
+        || (status == StatusCode::FORBIDDEN
+            && (body.contains("rateLimitExceeded") || body.contains("userRateLimitExceeded")))
+}
+
+/// Full jitter: a random delay up to the exponential cap for this attempt
+fn backoff_delay(attempt: u32, retry_after_secs: Option<u64>) -> std::time::Duration {
+    let cap = BACKOFF_BASE_MS.saturating_mul(1 << attempt.min(16)).min(BACKOFF_MAX_MS);
+    let jittered = clock::rng().gen_range(0..=cap);
+    let floor = retry_after_secs.map_or(0, |s| s.saturating_mul(1000));
+    std::time::Duration::from_millis(jittered.max(floor))
+}
+
 #[tauri::command]
 pub async fn set_google_client_id(
     state: State<'_, DbState>,
     client_id: String,
 ) -> Result<bool, ApiError> {
     let pool = &state.0;
-    let trimmed = client_id.trim().to_string();
-    let stored: Option<String> = if trimmed.is_empty() { None } else { Some(trimmed) };
-
-    sqlx::query(
-        r#"
-        INSERT INTO user_settings (id, user_id, google_client_id, updated_at)
-        VALUES (1, 1, ?, CURRENT_TIMESTAMP)
-        ON CONFLICT(id) DO UPDATE SET
-            google_client_id = excluded.google_client_id,
-            updated_at = CURRENT_TIMESTAMP
-        "#,
-    )
-    .bind(stored)
-    .execute(pool)
-    .await
-    .map_err(ApiError::from)?;
+    settings::set(pool, "google_client_id", serde_json::Value::String(client_id)).await?;
 
     Ok(true)
 }
@@ -201,13 +357,11 @@ pub async fn google_oauth_begin(
     let code_challenge = code_challenge_from_verifier(&code_verifier);
     let state_token = random_token(32);
 
-    let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
-        .await
-        .map_err(ApiError::from)?;
-    let local_addr = listener
-        .local_addr()
-        .map_err(|e| ApiError::internal(e.to_string()))?;
-    let redirect_uri = format!("http://127.0.0.1:{}/callback", local_addr.port());
+    #[cfg(desktop)]
+    let redirect_uri = listen_for_redirect(google_state.oauth.clone()).await?;
+    #[cfg(mobile)]
+    let redirect_uri = mobile_redirect_uri(&client_id)
+        .ok_or_else(|| ApiError::validation("Google client ID must end in .apps.googleusercontent.com"))?;
 
     let auth_url = format!(
         "{base}?client_id={client_id}&response_type=code&redirect_uri={redirect}&scope={scope}&state={state}&code_challenge={challenge}&code_challenge_method=S256&access_type=offline&prompt=consent",
@@ -232,7 +386,23 @@ pub async fn google_oauth_begin(
         *lock = Some(session);
     }
 
-    let oauth_handle = google_state.oauth.clone();
+    Ok(GoogleAuthBeginResponse { auth_url, redirect_uri })
+}
+
+/// Catch the redirect on a loopback port; returns the redirect URI
+///
+/// The callback URL is stored on the pending session for
+/// `google_oauth_complete` to pick up.
+#[cfg(desktop)]
+async fn listen_for_redirect(oauth: Arc<Mutex<Option<OAuthSession>>>) -> Result<String, ApiError> {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+        .await
+        .map_err(ApiError::from)?;
+    let local_addr = listener
+        .local_addr()
+        .map_err(|e| ApiError::internal(e.to_string()))?;
+    let redirect_uri = format!("http://127.0.0.1:{}/callback", local_addr.port());
+
     tokio::spawn(async move {
         if let Ok((mut socket, _)) = listener.accept().await {
             let mut buf = [0u8; 2048];
@@ -244,7 +414,7 @@ pub async fn google_oauth_begin(
                         let path = parts[1];
                         if path.contains("code=") {
                             let callback_url = format!("http://{}{}", local_addr, path);
-                            let mut lock = oauth_handle.lock().await;
+                            let mut lock = oauth.lock().await;
                             if let Some(ref mut session) = *lock {
                                 session.callback_url = Some(callback_url);
                             }
@@ -257,7 +427,43 @@ pub async fn google_oauth_begin(
         }
     });
 
-    Ok(GoogleAuthBeginResponse { auth_url, redirect_uri })
+    Ok(redirect_uri)
+}
+
+/// Path of the custom-scheme redirect used on mobile
+pub const MOBILE_REDIRECT_PATH: &str = "/oauth2redirect";
+
+/// Redirect URI for Google's installed-app flow on mobile
+///
+/// Google redirects mobile clients to the reversed client ID as a URL
+/// scheme (`com.googleusercontent.apps.<id>:/oauth2redirect`); that scheme
+/// has to be registered under `plugins.deep-link.mobile` for the app to
+/// receive it.
+#[cfg(any(mobile, test))]
+fn mobile_redirect_uri(client_id: &str) -> Option<String> {
+    let id = client_id.trim().strip_suffix(".apps.googleusercontent.com")?;
+    if id.is_empty() || !id.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+        return None;
+    }
+    Some(format!("com.googleusercontent.apps.{}:{}", id, MOBILE_REDIRECT_PATH))
+}
+
+/// Hand an OAuth redirect that arrived as a deep link to the pending session
+///
+/// Returns false if no sign-in is pending or the state does not match.
+pub async fn receive_redirect(google_state: &GoogleState, url: &Url) -> bool {
+    let mut lock = google_state.oauth.lock().await;
+    let Some(session) = lock.as_mut() else {
+        return false;
+    };
+    let state_matches = url
+        .query_pairs()
+        .any(|(k, v)| k == "state" && v == session.state.as_str());
+    if !state_matches {
+        return false;
+    }
+    session.callback_url = Some(url.to_string());
+    true
 }
 
 #[tauri::command]
@@ -367,44 +573,181 @@ pub async fn google_sync_now(
         .ok_or_else(|| ApiError::validation("Google client ID not set"))?;
 
     let access_token = ensure_access_token(&google_state, &client_id).await?;
-    let client = Client::new();
+    let api = GoogleApi::new(access_token);
 
-    let calendar_list = parse_json_response::<GoogleCalendarList>(
-        client
-            .get(format!("{}/users/me/calendarList", GOOGLE_CALENDAR_API))
-            .bearer_auth(&access_token)
-            .send()
-            .await
-            .map_err(ApiError::from)?,
-    )
-    .await?;
+    recover_sync_journal(&state.0, &api).await?;
+
+    let calendar_list = api
+        .json::<GoogleCalendarList>(|c| c.get(format!("{}/users/me/calendarList", GOOGLE_CALENDAR_API)))
+        .await?;
 
     let calendars = calendar_list.items.unwrap_or_default();
     if calendars.is_empty() {
         return Ok(true);
     }
 
-    let export_calendar_id = ensure_life_os_plan_calendar(&state.0, &client, &access_token, &calendars).await?;
+    let export_calendar_id = ensure_life_os_plan_calendar(&state.0, &api, &calendars).await?;
 
     let (time_min, time_max, date_min, date_max) = sync_window_range();
 
     for calendar in &calendars {
-        let events = fetch_events(&client, &access_token, &calendar.id, &time_min, &time_max).await?;
+        let events = fetch_events(&api, &calendar.id, &time_min, &time_max).await?;
         if calendar.id == export_calendar_id {
-            sync_plan_calendar(&state.0, &client, &access_token, &calendar.id, &date_min, &date_max, events).await?;
+            sync_plan_calendar(&state.0, &api, &calendar.id, &date_min, &date_max, events).await?;
         } else {
             sync_external_calendar(&state.0, &calendar.id, events).await?;
         }
     }
 
-    sqlx::query("UPDATE google_calendar_prefs SET updated_at = datetime('now') WHERE user_id = 1")
-        .execute(&state.0)
-        .await
-        .map_err(ApiError::from)?;
+    let calls = api.calls();
+    sqlx::query(
+        r#"UPDATE google_calendar_prefs
+           SET updated_at = datetime('now'), last_sync_reads = ?, last_sync_writes = ?, last_sync_retries = ?
+           WHERE user_id = 1"#,
+    )
+    .bind(calls.reads)
+    .bind(calls.writes)
+    .bind(calls.retries)
+    .execute(&state.0)
+    .await
+    .map_err(ApiError::from)?;
+
+    // The sync itself succeeded; a feed that can't be written shouldn't fail it
+    if let Err(e) = calendar_feed::publish(&state.0).await {
+        log::warn!("Calendar feed not written: {}", e.message);
+    }
 
     Ok(true)
 }
 
+/// What `google_sync_now` would change, without writing anything
+///
+/// Reads the same calendars and runs the same comparisons. Plan blocks that
+/// Google would update locally are not also listed as pushes, since after
+/// the pull they match Google.
+#[tauri::command]
+pub async fn google_sync_preview(
+    state: State<'_, DbState>,
+    google_state: State<'_, GoogleState>,
+) -> Result<GoogleSyncPreview, ApiError> {
+    let _sync_guard = google_state.sync_lock.lock().await;
+    let pool = &state.0;
+    let client_id = get_google_client_id(pool).await?
+        .ok_or_else(|| ApiError::validation("Google client ID not set"))?;
+
+    let access_token = ensure_access_token(&google_state, &client_id).await?;
+    let api = GoogleApi::new(access_token);
+
+    let calendar_list = api
+        .json::<GoogleCalendarList>(|c| c.get(format!("{}/users/me/calendarList", GOOGLE_CALENDAR_API)))
+        .await?;
+    let calendars = calendar_list.items.unwrap_or_default();
+
+    let plan_calendar_id = match saved_plan_calendar(pool).await? {
+        Some(id) => Some(id),
+        None => plan_calendar_by_name(&calendars).map(|c| c.id.clone()),
+    };
+    let mut preview = GoogleSyncPreview {
+        local: Vec::new(),
+        remote: Vec::new(),
+        creates_plan_calendar: !calendars.is_empty() && plan_calendar_id.is_none(),
+        interrupted_operations: google_sync_journal::pending(pool).await?.len(),
+        calls: SyncApiCalls::default(),
+    };
+    if calendars.is_empty() {
+        preview.calls = api.calls();
+        return Ok(preview);
+    }
+
+    let (time_min, time_max, date_min, date_max) = sync_window_range();
+    for calendar in &calendars {
+        let events = fetch_events(&api, &calendar.id, &time_min, &time_max).await?;
+        if plan_calendar_id.as_deref() == Some(calendar.id.as_str()) {
+            preview_plan_calendar(pool, &calendar.id, &date_min, &date_max, events, &mut preview).await?;
+        } else {
+            preview_external_calendar(pool, &calendar.id, events, &mut preview).await?;
+        }
+    }
+    if plan_calendar_id.is_none() {
+        // Every accepted block goes to the calendar the sync creates
+        for write in plan_writes(pool, &date_min, &date_max, &HashMap::new(), &HashSet::new()).await? {
+            preview.remote.push(write.into_change(LIFE_OS_PLAN_CALENDAR));
+        }
+    }
+
+    preview.calls = api.calls();
+    Ok(preview)
+}
+
+async fn preview_external_calendar(
+    pool: &sqlx::Pool<sqlx::Sqlite>,
+    calendar_id: &str,
+    events: Vec<GoogleEvent>,
+    preview: &mut GoogleSyncPreview,
+) -> Result<(), ApiError> {
+    for change in diff_external_calendar(pool, calendar_id, events).await? {
+        let (action, local_id, google_event_id, title, start_at, end_at) = match change {
+            LocalEventChange::Create { google_event_id, title, start_at, end_at, .. } => {
+                (ChangeAction::Create, None, Some(google_event_id), Some(title), Some(start_at), Some(end_at))
+            }
+            LocalEventChange::Update { changed: false, .. } => continue,
+            LocalEventChange::Update { local_id, title, start_at, end_at, .. } => {
+                (ChangeAction::Update, Some(local_id), None, Some(title), Some(start_at), Some(end_at))
+            }
+            LocalEventChange::Delete { local_id, title, .. } => {
+                (ChangeAction::Delete, Some(local_id), None, title, None, None)
+            }
+        };
+        preview.local.push(SyncChange {
+            action,
+            local_type: "calendar_event".to_string(),
+            local_id,
+            google_calendar_id: calendar_id.to_string(),
+            google_event_id,
+            title,
+            start_at,
+            end_at,
+        });
+    }
+    Ok(())
+}
+
+async fn preview_plan_calendar(
+    pool: &sqlx::Pool<sqlx::Sqlite>,
+    calendar_id: &str,
+    date_min: &str,
+    date_max: &str,
+    events: Vec<GoogleEvent>,
+    preview: &mut GoogleSyncPreview,
+) -> Result<(), ApiError> {
+    let google_events_by_id = live_events(events);
+    let busy_only = settings::get_bool(pool, BUSY_ONLY_SETTING).await?;
+    let mut pulled = HashSet::new();
+    for pull in plan_pulls(&google_events_by_id, busy_only) {
+        pulled.insert(pull.local_id);
+        let action = match compare_plan_block(pool, pull.local_id, pull.title.as_deref(), &pull.start_at, &pull.end_at).await? {
+            PlanBlockPull::Unchanged => continue,
+            PlanBlockPull::Update => ChangeAction::Update,
+            PlanBlockPull::Create => ChangeAction::Create,
+        };
+        preview.local.push(SyncChange {
+            action,
+            local_type: "week_plan_block".to_string(),
+            local_id: Some(pull.local_id),
+            google_calendar_id: calendar_id.to_string(),
+            google_event_id: Some(pull.google_event_id),
+            title: pull.title,
+            start_at: Some(pull.start_at),
+            end_at: Some(pull.end_at),
+        });
+    }
+
+    for write in plan_writes(pool, date_min, date_max, &google_events_by_id, &pulled).await? {
+        preview.remote.push(write.into_change(calendar_id));
+    }
+    Ok(())
+}
+
 #[tauri::command]
 pub async fn get_google_sync_status(state: State<'_, DbState>) -> Result<GoogleSyncStatus, ApiError> {
     let pool = &state.0;
@@ -417,18 +760,25 @@ pub async fn get_google_sync_status(state: State<'_, DbState>) -> Result<GoogleS
     .await
     .map_err(ApiError::from)?;
 
-    let last_sync = sqlx::query_scalar::<_, Option<String>>(
-        "SELECT updated_at FROM google_calendar_prefs WHERE user_id = 1",
+    let prefs = sqlx::query_as::<_, (Option<String>, Option<u32>, Option<u32>, Option<u32>)>(
+        "SELECT updated_at, last_sync_reads, last_sync_writes, last_sync_retries FROM google_calendar_prefs WHERE user_id = 1",
     )
     .fetch_optional(pool)
     .await
-    .map_err(ApiError::from)?
-    .flatten();
+    .map_err(ApiError::from)?;
+    let (last_sync, last_sync_calls) = match prefs {
+        Some((updated_at, Some(reads), Some(writes), Some(retries))) => {
+            (updated_at, Some(SyncApiCalls { reads, writes, retries }))
+        }
+        Some((updated_at, ..)) => (updated_at, None),
+        None => (None, None),
+    };
 
     Ok(GoogleSyncStatus {
         connected: account.is_some(),
         email: account.as_ref().and_then(|a| a.email.clone()),
         last_sync,
+        last_sync_calls,
         client_id_set: client_id.is_some(),
         client_id,
     })
@@ -461,14 +811,7 @@ pub async fn disconnect_google(state: State<'_, DbState>) -> Result<bool, ApiErr
 }
 
 async fn get_google_client_id(pool: &sqlx::Pool<sqlx::Sqlite>) -> Result<Option<String>, ApiError> {
-    let row = sqlx::query_scalar::<_, Option<String>>(
-        "SELECT google_client_id FROM user_settings WHERE user_id = 1",
-    )
-    .fetch_optional(pool)
-    .await
-    .map_err(ApiError::from)?;
-
-    Ok(row.flatten())
+    settings::get_string(pool, "google_client_id").await
 }
 
 fn generate_code_verifier() -> String {
@@ -490,31 +833,18 @@ fn random_token(len: usize) -> String {
         .collect()
 }
 
-fn keyring_entry() -> Result<Entry, ApiError> {
-    Entry::new("life-os", "google_refresh_token")
-        .map_err(|e| ApiError::internal(e.to_string()))
-}
+const REFRESH_TOKEN_SECRET: &str = "google_refresh_token";
 
 fn store_refresh_token(token: &str) -> Result<(), ApiError> {
-    keyring_entry()?
-        .set_password(token)
-        .map_err(|e| ApiError::internal(e.to_string()))
+    platform::set_secret(REFRESH_TOKEN_SECRET, token)
 }
 
 fn load_refresh_token() -> Result<Option<String>, ApiError> {
-    match keyring_entry()?.get_password() {
-        Ok(token) => Ok(Some(token)),
-        Err(keyring::Error::NoEntry) => Ok(None),
-        Err(e) => Err(ApiError::internal(e.to_string())),
-    }
+    platform::get_secret(REFRESH_TOKEN_SECRET)
 }
 
 fn clear_refresh_token() -> Result<(), ApiError> {
-    match keyring_entry()?.delete_credential() {
-        Ok(_) => Ok(()),
-        Err(keyring::Error::NoEntry) => Ok(()),
-        Err(e) => Err(ApiError::internal(e.to_string())),
-    }
+    platform::delete_secret(REFRESH_TOKEN_SECRET)
 }
 
 async fn ensure_access_token(
@@ -586,8 +916,7 @@ fn sync_window_range() -> (String, String, String, String) {
 }
 
 async fn fetch_events(
-    client: &Client,
-    access_token: &str,
+    api: &GoogleApi,
     calendar_id: &str,
     time_min: &str,
     time_max: &str,
@@ -601,24 +930,20 @@ async fn fetch_events(
     let mut page_token: Option<String> = None;
 
     loop {
-        let mut req = client
-            .get(&url)
-            .bearer_auth(access_token)
-            .query(&[
-                ("singleEvents", "true"),
-                ("orderBy", "startTime"),
-                ("timeMin", time_min),
-                ("timeMax", time_max),
-            ]);
-
-        if let Some(ref token) = page_token {
-            req = req.query(&[("pageToken", token.as_str())]);
-        }
-
-        let res = parse_json_response::<GoogleEventList>(
-            req.send().await.map_err(ApiError::from)?,
-        )
-        .await?;
+        let res = api
+            .json::<GoogleEventList>(|c| {
+                let req = c.get(&url).query(&[
+                    ("singleEvents", "true"),
+                    ("orderBy", "startTime"),
+                    ("timeMin", time_min),
+                    ("timeMax", time_max),
+                ]);
+                match &page_token {
+                    Some(token) => req.query(&[("pageToken", token.as_str())]),
+                    None => req,
+                }
+            })
+            .await?;
 
         events.extend(res.items.unwrap_or_default());
         if let Some(next) = res.next_page_token {
@@ -631,25 +956,31 @@ async fn fetch_events(
     Ok(events)
 }
 
-async fn ensure_life_os_plan_calendar(
-    pool: &sqlx::Pool<sqlx::Sqlite>,
-    client: &Client,
-    access_token: &str,
-    calendars: &[GoogleCalendarListItem],
-) -> Result<String, ApiError> {
-    let existing_pref = sqlx::query_scalar::<_, Option<String>>(
+async fn saved_plan_calendar(pool: &sqlx::Pool<sqlx::Sqlite>) -> Result<Option<String>, ApiError> {
+    let id = sqlx::query_scalar::<_, Option<String>>(
         "SELECT export_calendar_id FROM google_calendar_prefs WHERE user_id = 1",
     )
     .fetch_optional(pool)
     .await
     .map_err(ApiError::from)?
     .flatten();
+    Ok(id)
+}
+
+fn plan_calendar_by_name(calendars: &[GoogleCalendarListItem]) -> Option<&GoogleCalendarListItem> {
+    calendars.iter().find(|c| c.summary.as_deref() == Some(LIFE_OS_PLAN_CALENDAR))
+}
 
-    if let Some(id) = existing_pref {
+async fn ensure_life_os_plan_calendar(
+    pool: &sqlx::Pool<sqlx::Sqlite>,
+    api: &GoogleApi,
+    calendars: &[GoogleCalendarListItem],
+) -> Result<String, ApiError> {
+    if let Some(id) = saved_plan_calendar(pool).await? {
         return Ok(id);
     }
 
-    if let Some(found) = calendars.iter().find(|c| c.summary.as_deref() == Some(LIFE_OS_PLAN_CALENDAR)) {
+    if let Some(found) = plan_calendar_by_name(calendars) {
         sqlx::query(
             "INSERT INTO google_calendar_prefs (user_id, import_all, export_calendar_id, updated_at)
              VALUES (1, 1, ?, datetime('now'))
@@ -665,19 +996,14 @@ async fn ensure_life_os_plan_calendar(
     }
 
     let create_url = format!("{}/calendars", GOOGLE_CALENDAR_API);
-    let created = parse_json_response::<GoogleCalendarListItem>(
-        client
-            .post(create_url)
-            .bearer_auth(access_token)
-            .json(&serde_json::json!({
+    let created = api
+        .json::<GoogleCalendarListItem>(|c| {
+            c.post(&create_url).json(&serde_json::json!({
                 "summary": LIFE_OS_PLAN_CALENDAR,
                 "timeZone": "UTC",
             }))
-            .send()
-            .await
-            .map_err(ApiError::from)?,
-    )
-    .await?;
+        })
+        .await?;
 
     sqlx::query(
         "INSERT INTO google_calendar_prefs (user_id, import_all, export_calendar_id, updated_at)
@@ -694,14 +1020,56 @@ async fn ensure_life_os_plan_calendar(
     Ok(created.id)
 }
 
-async fn sync_external_calendar(
+/// A local change from an imported (read-only) calendar
+enum LocalEventChange {
+    Create {
+        google_event_id: String,
+        title: String,
+        start_at: String,
+        end_at: String,
+        etag: Option<String>,
+    },
+    /// Linked events are always rewritten; `changed` is whether that
+    /// changes anything the user sees
+    Update {
+        link_id: i64,
+        local_id: i64,
+        title: String,
+        start_at: String,
+        end_at: String,
+        etag: Option<String>,
+        changed: bool,
+    },
+    Delete {
+        link_id: i64,
+        local_id: i64,
+        title: Option<String>,
+    },
+}
+
+async fn diff_external_calendar(
     pool: &sqlx::Pool<sqlx::Sqlite>,
     calendar_id: &str,
     events: Vec<GoogleEvent>,
-) -> Result<(), ApiError> {
+) -> Result<Vec<LocalEventChange>, ApiError> {
+    let mut changes = Vec::new();
     for event in events {
+        let link = sqlx::query_as::<_, (i64, i64, Option<String>, Option<String>, Option<String>)>(
+            r#"SELECT l.id, l.local_id, e.title, e.start_at, e.end_at
+               FROM google_event_links l
+               LEFT JOIN calendar_events e ON e.id = l.local_id
+               WHERE l.google_calendar_id = ? AND l.google_event_id = ? AND l.local_type = 'calendar_event'"#,
+        )
+        .bind(calendar_id)
+        .bind(&event.id)
+        .fetch_optional(pool)
+        .await
+        .map_err(ApiError::from)?;
+
         if event.status.as_deref() == Some("cancelled") {
-            delete_linked_event(pool, calendar_id, &event.id, "calendar_event").await?;
+            if let Some((link_id, local_id, title, _, _)) = link {
+                changes.push(LocalEventChange::Delete { link_id, local_id, title });
+            }
             continue;
         }
 
@@ -711,109 +1079,193 @@ async fn sync_external_calendar(
         };
 
         let title = event.summary.clone().unwrap_or_else(|| "(No title)".to_string());
-        let link = sqlx::query_as::<_, crate::models::google_event_link::GoogleEventLink>(
-            "SELECT * FROM google_event_links WHERE google_calendar_id = ? AND google_event_id = ? AND local_type = 'calendar_event'",
-        )
-        .bind(calendar_id)
-        .bind(&event.id)
-        .fetch_optional(pool)
-        .await
-        .map_err(ApiError::from)?;
-
-        if let Some(existing) = link {
-            sqlx::query(
-                "UPDATE calendar_events SET title = ?, start_at = ?, end_at = ?, locked = 1, category = 'busy', domain = 'google' WHERE id = ?",
-            )
-            .bind(&title)
-            .bind(&start_at)
-            .bind(&end_at)
-            .bind(existing.local_id)
-            .execute(pool)
-            .await
-            .map_err(ApiError::from)?;
-
-            update_link(pool, existing.id, event.etag.as_deref()).await?;
-        } else {
-            let rec_id: i64 = sqlx::query_scalar(
-                r#"INSERT INTO calendar_events (user_id, title, start_at, end_at, category, domain, locked)
-                   VALUES (1, ?, ?, ?, 'busy', 'google', 1)
-                   RETURNING id"#,
-            )
-            .bind(&title)
-            .bind(&start_at)
-            .bind(&end_at)
-            .fetch_one(pool)
-            .await
-            .map_err(ApiError::from)?;
-
-            sqlx::query(
-                r#"INSERT INTO google_event_links (local_type, local_id, google_calendar_id, google_event_id, etag, last_synced_at)
-                   VALUES ('calendar_event', ?, ?, ?, ?, datetime('now'))"#,
-            )
-            .bind(rec_id)
-            .bind(calendar_id)
-            .bind(&event.id)
-            .bind(&event.etag)
-            .execute(pool)
-            .await
-            .map_err(ApiError::from)?;
+        match link {
+            Some((link_id, local_id, curr_title, curr_start, curr_end)) => {
+                let changed = curr_title.as_deref() != Some(title.as_str())
+                    || curr_start.as_deref() != Some(start_at.as_str())
+                    || curr_end.as_deref() != Some(end_at.as_str());
+                changes.push(LocalEventChange::Update {
+                    link_id,
+                    local_id,
+                    title,
+                    start_at,
+                    end_at,
+                    etag: event.etag,
+                    changed,
+                });
+            }
+            None => changes.push(LocalEventChange::Create {
+                google_event_id: event.id,
+                title,
+                start_at,
+                end_at,
+                etag: event.etag,
+            }),
         }
     }
-
-    Ok(())
+    Ok(changes)
 }
 
-async fn sync_plan_calendar(
+async fn sync_external_calendar(
     pool: &sqlx::Pool<sqlx::Sqlite>,
-    client: &Client,
-    access_token: &str,
     calendar_id: &str,
-    date_min: &str,
-    date_max: &str,
     events: Vec<GoogleEvent>,
 ) -> Result<(), ApiError> {
-    let mut google_events_by_id: HashMap<String, GoogleEvent> = HashMap::new();
-    for event in events.into_iter() {
-        if event.status.as_deref() == Some("cancelled") {
-            continue;
-        }
-        google_events_by_id.insert(event.id.clone(), event);
-    }
+    for change in diff_external_calendar(pool, calendar_id, events).await? {
+        match change {
+            LocalEventChange::Update { link_id, local_id, title, start_at, end_at, etag, .. } => {
+                let mut tx = pool.begin().await.map_err(ApiError::from)?;
+                let before = change_log::snapshot(&mut tx, "calendar_events", local_id).await?;
+                sqlx::query(
+                    "UPDATE calendar_events SET title = ?, start_at = ?, end_at = ?, locked = 1, category = 'busy', domain = 'google' WHERE id = ?",
+                )
+                .bind(&title)
+                .bind(&start_at)
+                .bind(&end_at)
+                .bind(local_id)
+                .execute(&mut *tx)
+                .await
+                .map_err(ApiError::from)?;
+                change_log::record(&mut tx, Source::Google, "calendar_events", local_id, before, &format!("Updated \"{}\" from Google", title)).await?;
+                tx.commit().await.map_err(ApiError::from)?;
 
-    // Update local plan blocks from Google events
-    for event in google_events_by_id.values() {
-        if let Some(lifeos_id) = extract_lifeos_id(event) {
-            if let Some(local_id) = parse_lifeos_id(&lifeos_id) {
-                let (start_at, end_at) = match event_times(event) {
-                    Some(times) => times,
-                    None => continue,
-                };
-                let title = event.summary.clone().unwrap_or_else(|| "Planned block".to_string());
-                let _ = apply_google_update_to_plan_block(
-                    pool,
-                    local_id,
-                    &title,
-                    &start_at,
-                    &end_at,
+                update_link(pool, link_id, etag.as_deref()).await?;
+            }
+            LocalEventChange::Create { google_event_id, title, start_at, end_at, etag } => {
+                // The event and its link are written together so a crash cannot
+                // leave an unlinked copy that the next sync imports again
+                let mut tx = pool.begin().await.map_err(ApiError::from)?;
+                let rec_id: i64 = sqlx::query_scalar(
+                    r#"INSERT INTO calendar_events (user_id, title, start_at, end_at, category, domain, locked)
+                       VALUES (1, ?, ?, ?, 'busy', 'google', 1)
+                       RETURNING id"#,
                 )
-                .await?;
-                upsert_plan_link(pool, local_id, calendar_id, &event.id, event.etag.as_deref()).await?;
+                .bind(&title)
+                .bind(&start_at)
+                .bind(&end_at)
+                .fetch_one(&mut *tx)
+                .await
+                .map_err(ApiError::from)?;
+
+                sqlx::query(
+                    r#"INSERT INTO google_event_links (local_type, local_id, google_calendar_id, google_event_id, etag, last_synced_at)
+                       VALUES ('calendar_event', ?, ?, ?, ?, datetime('now'))"#,
+                )
+                .bind(rec_id)
+                .bind(calendar_id)
+                .bind(&google_event_id)
+                .bind(&etag)
+                .execute(&mut *tx)
+                .await
+                .map_err(ApiError::from)?;
+                change_log::record(&mut tx, Source::Google, "calendar_events", rec_id, None, &format!("Imported \"{}\" from Google", title)).await?;
+                tx.commit().await.map_err(ApiError::from)?;
+            }
+            LocalEventChange::Delete { link_id, local_id, title } => {
+                let mut tx = pool.begin().await.map_err(ApiError::from)?;
+                let before = change_log::snapshot(&mut tx, "calendar_events", local_id).await?;
+                sqlx::query("DELETE FROM calendar_events WHERE id = ?")
+                    .bind(local_id)
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(ApiError::from)?;
+                sqlx::query("DELETE FROM google_event_links WHERE id = ?")
+                    .bind(link_id)
+                    .execute(&mut *tx)
+                    .await
+                    .map_err(ApiError::from)?;
+                let summary = format!("Removed \"{}\", cancelled on Google", title.as_deref().unwrap_or("(No title)"));
+                change_log::record(&mut tx, Source::Google, "calendar_events", local_id, before, &summary).await?;
+                tx.commit().await.map_err(ApiError::from)?;
             }
         }
     }
 
-    // Push accepted/locked blocks to Google
-    let blocks = sqlx::query_as::<_, (i64, String, String, Option<String>, Option<String>, Option<String>)>(
-        "SELECT id, start_at, end_at, title, status, block_type FROM week_plan_blocks WHERE status IN ('accepted', 'locked') AND date(start_at) >= ? AND date(start_at) <= ?",
-    )
-    .bind(date_min)
-    .bind(date_max)
-    .fetch_all(pool)
-    .await
-    .map_err(ApiError::from)?;
+    Ok(())
+}
 
-    for (id, start_at, end_at, title, status, block_type) in blocks {
-        let event_title = title.unwrap_or_else(|| block_type.unwrap_or_else(|| "Planned block".to_string()));
+/// Non-cancelled events on a calendar by id
+fn live_events(events: Vec<GoogleEvent>) -> HashMap<String, GoogleEvent> {
+    events
+        .into_iter()
+        .filter(|event| event.status.as_deref() != Some("cancelled"))
+        .map(|event| (event.id.clone(), event))
+        .collect()
+}
+
+/// A plan block as it is on Google, to copy locally
+struct PlanPull {
+    local_id: i64,
+    google_event_id: String,
+    etag: Option<String>,
+    /// None when exporting busy-only, so the local title is kept
+    title: Option<String>,
+    start_at: String,
+    end_at: String,
+}
+
+/// Plan calendar events that belong to a local plan block
+fn plan_pulls(google_events_by_id: &HashMap<String, GoogleEvent>, busy_only: bool) -> Vec<PlanPull> {
+    google_events_by_id
+        .values()
+        .filter_map(|event| {
+            let local_id = parse_lifeos_id(&extract_lifeos_id(event)?)?;
+            let (start_at, end_at) = event_times(event)?;
+            Some(PlanPull {
+                local_id,
+                google_event_id: event.id.clone(),
+                etag: event.etag.clone(),
+                title: (!busy_only).then(|| event.summary.clone().unwrap_or_else(|| "Planned block".to_string())),
+                start_at,
+                end_at,
+            })
+        })
+        .collect()
+}
+
+/// Writes that push accepted/locked blocks to Google, leaving out `skip`
+async fn plan_writes(
+    pool: &sqlx::Pool<sqlx::Sqlite>,
+    date_min: &str,
+    date_max: &str,
+    google_events_by_id: &HashMap<String, GoogleEvent>,
+    skip: &HashSet<i64>,
+) -> Result<Vec<PlanWrite>, ApiError> {
+    let rows = sqlx::query_as::<_, (i64, String, String, Option<String>, Option<String>, Option<String>, Option<String>)>(
+        "SELECT id, start_at, end_at, title, status, block_type, split_group FROM week_plan_blocks WHERE status IN ('accepted', 'locked') AND date(start_at) >= ? AND date(start_at) <= ? ORDER BY start_at",
+    )
+    .bind(date_min)
+    .bind(date_max)
+    .fetch_all(pool)
+    .await
+    .map_err(ApiError::from)?;
+
+    // Pomodoro sub-blocks go out as one event, under the first one's id
+    let mut blocks: Vec<(i64, String, String, Option<String>, Option<String>, Option<String>)> = Vec::new();
+    let mut groups: HashMap<String, usize> = HashMap::new();
+    for (id, start_at, end_at, title, status, block_type, split_group) in rows {
+        if let Some(group) = split_group {
+            if let Some(&i) = groups.get(&group) {
+                if end_at > blocks[i].2 {
+                    blocks[i].2 = end_at;
+                }
+                continue;
+            }
+            groups.insert(group, blocks.len());
+        }
+        blocks.push((id, start_at, end_at, title, status, block_type));
+    }
+
+    let categories = category_map(pool).await?;
+    let busy_only = settings::get_bool(pool, BUSY_ONLY_SETTING).await?;
+    let mut writes = Vec::new();
+    for (id, start_at, end_at, title, _status, block_type) in blocks {
+        if skip.contains(&id) {
+            continue;
+        }
+        let category = block_type.as_deref().and_then(|t| categories.get(t));
+        let event_title = title.unwrap_or_else(|| block_type.unwrap_or_else(|| "Planned block".to_string()));
+        let payload = plan_event_payload(&event_title, &start_at, &end_at, id, category, busy_only);
         let link = sqlx::query_as::<_, crate::models::google_event_link::GoogleEventLink>(
             "SELECT * FROM google_event_links WHERE local_type = 'week_plan_block' AND local_id = ?",
         )
@@ -829,72 +1281,249 @@ async fn sync_plan_calendar(
                     None => continue,
                 };
 
-                if g_start != start_at || g_end != end_at || google_event.summary.clone().unwrap_or_default() != event_title {
+                let style_changed = google_event.color_id != payload.color_id
+                    || google_event.transparency.as_deref().unwrap_or("opaque") != payload.transparency;
+                if !same_instant(&g_start, &start_at) || !same_instant(&g_end, &end_at) || google_event.summary.clone().unwrap_or_default() != payload.summary || style_changed {
                     // Update Google with local changes
-                    patch_google_event(
-                        client,
-                        access_token,
-                        calendar_id,
-                        &existing.google_event_id,
-                        &event_title,
-                        &start_at,
-                        &end_at,
-                        id,
-                    )
-                    .await?;
-                    update_link(pool, existing.id, google_event.etag.as_deref()).await?;
+                    writes.push(PlanWrite::Patch {
+                        block_id: id,
+                        link_id: existing.id,
+                        google_event_id: existing.google_event_id.clone(),
+                        etag: google_event.etag.clone(),
+                        payload,
+                    });
                 }
             } else {
                 // Event missing from Google within window, recreate
-                let new_id = insert_google_event(
-                    client,
-                    access_token,
-                    calendar_id,
-                    &event_title,
-                    &start_at,
-                    &end_at,
-                    id,
-                )
-                .await?;
-                sqlx::query(
-                    r#"UPDATE google_event_links SET google_event_id = ?, google_calendar_id = ?, last_synced_at = datetime('now') WHERE id = ?"#,
-                )
-                .bind(new_id)
-                .bind(calendar_id)
-                .bind(existing.id)
-                .execute(pool)
-                .await
-                .map_err(ApiError::from)?;
+                writes.push(PlanWrite::Recreate { block_id: id, link_id: existing.id, payload });
             }
         } else {
-            let google_event_id = insert_google_event(
-                client,
-                access_token,
-                calendar_id,
-                &event_title,
-                &start_at,
-                &end_at,
-                id,
+            writes.push(PlanWrite::Create { block_id: id, payload });
+        }
+    }
+
+    Ok(writes)
+}
+
+async fn sync_plan_calendar(
+    pool: &sqlx::Pool<sqlx::Sqlite>,
+    api: &GoogleApi,
+    calendar_id: &str,
+    date_min: &str,
+    date_max: &str,
+    events: Vec<GoogleEvent>,
+) -> Result<(), ApiError> {
+    let google_events_by_id = live_events(events);
+    let busy_only = settings::get_bool(pool, BUSY_ONLY_SETTING).await?;
+
+    // Update local plan blocks from Google events
+    for pull in plan_pulls(&google_events_by_id, busy_only) {
+        apply_google_update_to_plan_block(pool, pull.local_id, pull.title.as_deref(), &pull.start_at, &pull.end_at).await?;
+        upsert_plan_link(pool, pull.local_id, calendar_id, &pull.google_event_id, pull.etag.as_deref()).await?;
+    }
+
+    // Push accepted/locked blocks to Google
+    let writes = plan_writes(pool, date_min, date_max, &google_events_by_id, &HashSet::new()).await?;
+
+    // Bounded concurrency; every write runs to completion before the first
+    // error is returned so no journal entry is left open needlessly
+    futures::stream::iter(writes)
+        .map(|write| apply_plan_write(pool, api, calendar_id, write))
+        .buffer_unordered(WRITE_CONCURRENCY)
+        .collect::<Vec<_>>()
+        .await
+        .into_iter()
+        .collect::<Result<(), ApiError>>()
+}
+
+/// A change to push to the plan calendar
+enum PlanWrite {
+    Patch {
+        block_id: i64,
+        link_id: i64,
+        google_event_id: String,
+        etag: Option<String>,
+        payload: GoogleEventInsert,
+    },
+    Recreate {
+        block_id: i64,
+        link_id: i64,
+        payload: GoogleEventInsert,
+    },
+    Create {
+        block_id: i64,
+        payload: GoogleEventInsert,
+    },
+}
+
+impl PlanWrite {
+    fn into_change(self, calendar_id: &str) -> SyncChange {
+        let (action, block_id, google_event_id, payload) = match self {
+            PlanWrite::Patch { block_id, google_event_id, payload, .. } => {
+                (ChangeAction::Update, block_id, Some(google_event_id), payload)
+            }
+            PlanWrite::Recreate { block_id, payload, .. } | PlanWrite::Create { block_id, payload } => {
+                (ChangeAction::Create, block_id, None, payload)
+            }
+        };
+        SyncChange {
+            action,
+            local_type: "week_plan_block".to_string(),
+            local_id: Some(block_id),
+            google_calendar_id: calendar_id.to_string(),
+            google_event_id,
+            title: Some(payload.summary),
+            start_at: Some(payload.start.date_time),
+            end_at: Some(payload.end.date_time),
+        }
+    }
+}
+
+async fn apply_plan_write(
+    pool: &sqlx::Pool<sqlx::Sqlite>,
+    api: &GoogleApi,
+    calendar_id: &str,
+    write: PlanWrite,
+) -> Result<(), ApiError> {
+    match write {
+        PlanWrite::Patch { link_id, google_event_id, etag, payload, .. } => {
+            patch_google_event(api, calendar_id, &google_event_id, &payload).await?;
+            update_link(pool, link_id, etag.as_deref()).await?;
+        }
+        PlanWrite::Recreate { block_id, link_id, payload } => {
+            let entry = google_sync_journal::begin(pool, Operation::RecreateEvent, "week_plan_block", block_id, calendar_id).await?;
+            let new_id = insert_google_event(api, calendar_id, &payload).await?;
+            google_sync_journal::record_remote(pool, entry, &new_id).await?;
+
+            let mut tx = pool.begin().await.map_err(ApiError::from)?;
+            sqlx::query(
+                r#"UPDATE google_event_links SET google_event_id = ?, google_calendar_id = ?, last_synced_at = datetime('now') WHERE id = ?"#,
             )
-            .await?;
+            .bind(new_id)
+            .bind(calendar_id)
+            .bind(link_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(ApiError::from)?;
+            google_sync_journal::complete(&mut *tx, entry, Resolution::Applied).await?;
+            tx.commit().await.map_err(ApiError::from)?;
+        }
+        PlanWrite::Create { block_id, payload } => {
+            let entry = google_sync_journal::begin(pool, Operation::CreateEvent, "week_plan_block", block_id, calendar_id).await?;
+            let google_event_id = insert_google_event(api, calendar_id, &payload).await?;
+            google_sync_journal::record_remote(pool, entry, &google_event_id).await?;
 
+            let mut tx = pool.begin().await.map_err(ApiError::from)?;
             sqlx::query(
                 r#"INSERT INTO google_event_links (local_type, local_id, google_calendar_id, google_event_id, last_synced_at)
                    VALUES ('week_plan_block', ?, ?, ?, datetime('now'))"#,
             )
-            .bind(id)
+            .bind(block_id)
             .bind(calendar_id)
             .bind(&google_event_id)
-            .execute(pool)
+            .execute(&mut *tx)
             .await
             .map_err(ApiError::from)?;
+            google_sync_journal::complete(&mut *tx, entry, Resolution::Applied).await?;
+            tx.commit().await.map_err(ApiError::from)?;
         }
+    }
+    Ok(())
+}
 
-        if status.as_deref() == Some("locked") {
-            // No-op for now; future: set transparency/busy
-        }
+/// Reconcile journal entries a crash left incomplete
+///
+/// An entry with a recorded event id only lost its local write, which is
+/// replayed. Without one, Google may or may not have created the event, so
+/// the calendar is searched for it by `lifeos_id`. Extra copies from
+/// repeated interrupted attempts are deleted.
+async fn recover_sync_journal(
+    pool: &sqlx::Pool<sqlx::Sqlite>,
+    api: &GoogleApi,
+) -> Result<usize, ApiError> {
+    let entries = google_sync_journal::pending(pool).await?;
+    for entry in &entries {
+        let google_event_id = match &entry.google_event_id {
+            Some(id) => Some(id.clone()),
+            None => {
+                let mut found = find_plan_events(api, &entry.google_calendar_id, entry.local_id).await?;
+                let first = found.pop();
+                for duplicate in found {
+                    delete_google_event(api, &entry.google_calendar_id, &duplicate).await?;
+                }
+                first
+            }
+        };
+        resolve_journal_entry(pool, entry, google_event_id.as_deref()).await?;
     }
+    if !entries.is_empty() {
+        log::info!("Recovered {} interrupted Google sync operations", entries.len());
+    }
+    google_sync_journal::prune(pool).await?;
+    Ok(entries.len())
+}
+
+async fn resolve_journal_entry(
+    pool: &sqlx::Pool<sqlx::Sqlite>,
+    entry: &JournalEntry,
+    google_event_id: Option<&str>,
+) -> Result<(), ApiError> {
+    let mut tx = pool.begin().await.map_err(ApiError::from)?;
+    let resolution = match google_event_id {
+        Some(event_id) => {
+            upsert_plan_link(&mut *tx, entry.local_id, &entry.google_calendar_id, event_id, None).await?;
+            Resolution::Relinked
+        }
+        None => Resolution::NotCreated,
+    };
+    google_sync_journal::complete(&mut *tx, entry.id, resolution).await?;
+    tx.commit().await.map_err(ApiError::from)?;
+    Ok(())
+}
+
+/// Ids of events on `calendar_id` created for plan block `local_id`
+async fn find_plan_events(
+    api: &GoogleApi,
+    calendar_id: &str,
+    local_id: i64,
+) -> Result<Vec<String>, ApiError> {
+    let url = format!(
+        "{}/calendars/{}/events",
+        GOOGLE_CALENDAR_API,
+        urlencoding::encode(calendar_id)
+    );
+    let res = api
+        .json::<GoogleEventList>(|c| {
+            c.get(&url)
+                .query(&[("privateExtendedProperty", format!("lifeos_id=wpb_{}", local_id))])
+        })
+        .await?;
+
+    Ok(res
+        .items
+        .unwrap_or_default()
+        .into_iter()
+        .filter(|e| e.status.as_deref() != Some("cancelled"))
+        .map(|e| e.id)
+        .collect())
+}
 
+async fn delete_google_event(
+    api: &GoogleApi,
+    calendar_id: &str,
+    event_id: &str,
+) -> Result<(), ApiError> {
+    let url = format!(
+        "{}/calendars/{}/events/{}",
+        GOOGLE_CALENDAR_API,
+        urlencoding::encode(calendar_id),
+        urlencoding::encode(event_id)
+    );
+    let (status, body) = api.send(|c| c.delete(&url)).await?;
+    // Already gone is as good as deleted
+    if !status.is_success() && status != StatusCode::GONE && status != StatusCode::NOT_FOUND {
+        return Err(ApiError::internal(format!("Google API error {}: {}", status, body)));
+    }
     Ok(())
 }
 
@@ -916,56 +1545,185 @@ fn parse_lifeos_id(value: &str) -> Option<i64> {
     }
 }
 
-async fn apply_google_update_to_plan_block(
-    pool: &sqlx::Pool<sqlx::Sqlite>,
-    local_id: i64,
-    title: &str,
-    start_at: &str,
-    end_at: &str,
-) -> Result<bool, ApiError> {
-    let existing = sqlx::query_as::<_, (String, String, Option<String>)>(
-        "SELECT start_at, end_at, title FROM week_plan_blocks WHERE id = ?",
+/// What copying a plan calendar event would do to its local block
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum PlanBlockPull {
+    Unchanged,
+    Update,
+    /// The block was deleted locally and will be recreated
+    Create,
+}
+
+/// `title` None leaves the local title out of the comparison
+/// A plan block as exported: split blocks span their whole group
+struct PlanBlockSpan {
+    start_at: String,
+    end_at: String,
+    title: Option<String>,
+    split_group: Option<String>,
+}
+
+async fn plan_block_span(pool: &sqlx::Pool<sqlx::Sqlite>, local_id: i64) -> Result<Option<PlanBlockSpan>, ApiError> {
+    let span = sqlx::query_as::<_, (String, String, Option<String>, Option<String>)>(
+        r#"SELECT MIN(g.start_at), MAX(g.end_at), b.title, b.split_group
+           FROM week_plan_blocks b
+           JOIN week_plan_blocks g ON g.id = b.id OR g.split_group = b.split_group
+           WHERE b.id = ?
+           GROUP BY b.id"#,
     )
     .bind(local_id)
     .fetch_optional(pool)
     .await
     .map_err(ApiError::from)?;
 
-    if let Some((curr_start, curr_end, curr_title)) = existing {
-        if curr_start == start_at && curr_end == end_at && curr_title.as_deref() == Some(title) {
-            return Ok(false);
+    Ok(span.map(|(start_at, end_at, title, split_group)| PlanBlockSpan { start_at, end_at, title, split_group }))
+}
+
+/// Block times are naive local, Google's carry an offset
+fn local_naive(value: &str) -> Option<NaiveDateTime> {
+    if let Ok(dt) = DateTime::parse_from_rfc3339(value) {
+        return Some(dt.with_timezone(&Local).naive_local());
+    }
+    NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S").ok()
+}
+
+fn same_instant(a: &str, b: &str) -> bool {
+    match (local_naive(a), local_naive(b)) {
+        (Some(a), Some(b)) => a == b,
+        _ => a == b,
+    }
+}
+
+async fn compare_plan_block(
+    pool: &sqlx::Pool<sqlx::Sqlite>,
+    local_id: i64,
+    title: Option<&str>,
+    start_at: &str,
+    end_at: &str,
+) -> Result<PlanBlockPull, ApiError> {
+    Ok(match plan_block_span(pool, local_id).await? {
+        Some(span) => {
+            let same_title = title.map_or(true, |t| span.title.as_deref() == Some(t));
+            if same_instant(&span.start_at, start_at) && same_instant(&span.end_at, end_at) && same_title {
+                PlanBlockPull::Unchanged
+            } else {
+                PlanBlockPull::Update
+            }
         }
+        None => PlanBlockPull::Create,
+    })
+}
 
-        let week_start = week_start_date_from(start_at);
-        sqlx::query(
-            "UPDATE week_plan_blocks SET week_start_date = ?, start_at = ?, end_at = ?, title = COALESCE(?, title) WHERE id = ?",
-        )
-        .bind(week_start)
-        .bind(start_at)
-        .bind(end_at)
-        .bind(title)
-        .bind(local_id)
-        .execute(pool)
-        .await
-        .map_err(ApiError::from)?;
+/// Move every sub-block of a split block with its merged Google event
+///
+/// Only the start is taken from Google; the pomodoro layout is kept.
+async fn move_split_group(
+    pool: &sqlx::Pool<sqlx::Sqlite>,
+    local_id: i64,
+    title: Option<&str>,
+    start_at: &str,
+) -> Result<(), ApiError> {
+    let Some(span) = plan_block_span(pool, local_id).await? else {
+        return Ok(());
+    };
+    let (Some(new_start), Some(old_start)) = (local_naive(start_at), local_naive(&span.start_at)) else {
+        return Ok(());
+    };
+    let shift = new_start - old_start;
 
-        Ok(true)
-    } else {
-        // Create new plan block if missing
-        let week_start = week_start_date_from(start_at);
-        sqlx::query(
-            "INSERT INTO week_plan_blocks (id, user_id, week_start_date, start_at, end_at, block_type, title, status)
-             VALUES (?, 1, ?, ?, ?, 'study', ?, 'accepted')",
-        )
-        .bind(local_id)
-        .bind(week_start)
-        .bind(start_at)
-        .bind(end_at)
-        .bind(title)
-        .execute(pool)
-        .await
-        .map_err(ApiError::from)?;
-        Ok(true)
+    let members = sqlx::query_as::<_, (i64, String, String)>(
+        "SELECT id, start_at, end_at FROM week_plan_blocks WHERE split_group = ?",
+    )
+    .bind(&span.split_group)
+    .fetch_all(pool)
+    .await
+    .map_err(ApiError::from)?;
+
+    let mut tx = pool.begin().await.map_err(ApiError::from)?;
+    let mut before = Vec::with_capacity(members.len());
+    for (id, _, _) in &members {
+        before.push((*id, change_log::snapshot(&mut tx, "week_plan_blocks", *id).await?));
+    }
+    for (id, member_start, member_end) in members {
+        let (Some(member_start), Some(member_end)) = (local_naive(&member_start), local_naive(&member_end)) else {
+            continue;
+        };
+        let moved_start = (member_start + shift).format("%Y-%m-%dT%H:%M:%S").to_string();
+        sqlx::query("UPDATE week_plan_blocks SET week_start_date = ?, start_at = ?, end_at = ? WHERE id = ?")
+            .bind(week_start_date_from(&moved_start))
+            .bind(&moved_start)
+            .bind((member_end + shift).format("%Y-%m-%dT%H:%M:%S").to_string())
+            .bind(id)
+            .execute(&mut *tx)
+            .await
+            .map_err(ApiError::from)?;
+    }
+    if let Some(title) = title {
+        sqlx::query("UPDATE week_plan_blocks SET title = ? WHERE id = ?")
+            .bind(title)
+            .bind(local_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(ApiError::from)?;
+    }
+    for (id, before) in before {
+        change_log::record(&mut tx, Source::Google, "week_plan_blocks", id, before, "Moved with its Google event").await?;
+    }
+    tx.commit().await.map_err(ApiError::from)?;
+    Ok(())
+}
+
+async fn apply_google_update_to_plan_block(
+    pool: &sqlx::Pool<sqlx::Sqlite>,
+    local_id: i64,
+    title: Option<&str>,
+    start_at: &str,
+    end_at: &str,
+) -> Result<bool, ApiError> {
+    let week_start = week_start_date_from(start_at);
+    match compare_plan_block(pool, local_id, title, start_at, end_at).await? {
+        PlanBlockPull::Unchanged => Ok(false),
+        PlanBlockPull::Update if plan_block_span(pool, local_id).await?.is_some_and(|s| s.split_group.is_some()) => {
+            move_split_group(pool, local_id, title, start_at).await?;
+            Ok(true)
+        }
+        PlanBlockPull::Update => {
+            let mut tx = pool.begin().await.map_err(ApiError::from)?;
+            let before = change_log::snapshot(&mut tx, "week_plan_blocks", local_id).await?;
+            sqlx::query(
+                "UPDATE week_plan_blocks SET week_start_date = ?, start_at = ?, end_at = ?, title = COALESCE(?, title) WHERE id = ?",
+            )
+            .bind(week_start)
+            .bind(start_at)
+            .bind(end_at)
+            .bind(title)
+            .bind(local_id)
+            .execute(&mut *tx)
+            .await
+            .map_err(ApiError::from)?;
+            change_log::record(&mut tx, Source::Google, "week_plan_blocks", local_id, before, "Moved or renamed on Google").await?;
+            tx.commit().await.map_err(ApiError::from)?;
+            Ok(true)
+        }
+        PlanBlockPull::Create => {
+            // Create new plan block if missing
+            let mut tx = pool.begin().await.map_err(ApiError::from)?;
+            sqlx::query(
+                "INSERT INTO week_plan_blocks (id, user_id, week_start_date, start_at, end_at, block_type, title, status)
+                 VALUES (?, 1, ?, ?, ?, 'study', ?, 'accepted')",
+            )
+            .bind(local_id)
+            .bind(week_start)
+            .bind(start_at)
+            .bind(end_at)
+            .bind(title)
+            .execute(&mut *tx)
+            .await
+            .map_err(ApiError::from)?;
+            change_log::record(&mut tx, Source::Google, "week_plan_blocks", local_id, None, "Restored from Google").await?;
+            tx.commit().await.map_err(ApiError::from)?;
+            Ok(true)
+        }
     }
 }
 
@@ -1006,40 +1764,6 @@ fn event_times(event: &GoogleEvent) -> Option<(String, String)> {
     None
 }
 
-async fn delete_linked_event(
-    pool: &sqlx::Pool<sqlx::Sqlite>,
-    calendar_id: &str,
-    google_event_id: &str,
-    local_type: &str,
-) -> Result<(), ApiError> {
-    let link = sqlx::query_as::<_, crate::models::google_event_link::GoogleEventLink>(
-        "SELECT * FROM google_event_links WHERE google_calendar_id = ? AND google_event_id = ? AND local_type = ?",
-    )
-    .bind(calendar_id)
-    .bind(google_event_id)
-    .bind(local_type)
-    .fetch_optional(pool)
-    .await
-    .map_err(ApiError::from)?;
-
-        if let Some(existing) = link {
-            if local_type == "calendar_event" {
-                sqlx::query("DELETE FROM calendar_events WHERE id = ?")
-                    .bind(existing.local_id)
-                    .execute(pool)
-                    .await
-                    .map_err(ApiError::from)?;
-            }
-            sqlx::query("DELETE FROM google_event_links WHERE id = ?")
-                .bind(existing.id)
-                .execute(pool)
-                .await
-                .map_err(ApiError::from)?;
-        }
-
-    Ok(())
-}
-
 async fn update_link(pool: &sqlx::Pool<sqlx::Sqlite>, link_id: i64, etag: Option<&str>) -> Result<(), ApiError> {
     sqlx::query("UPDATE google_event_links SET etag = ?, last_synced_at = datetime('now') WHERE id = ?")
         .bind(etag)
@@ -1050,8 +1774,8 @@ async fn update_link(pool: &sqlx::Pool<sqlx::Sqlite>, link_id: i64, etag: Option
     Ok(())
 }
 
-async fn upsert_plan_link(
-    pool: &sqlx::Pool<sqlx::Sqlite>,
+async fn upsert_plan_link<'c>(
+    executor: impl sqlx::SqliteExecutor<'c>,
     local_id: i64,
     calendar_id: &str,
     google_event_id: &str,
@@ -1070,27 +1794,55 @@ async fn upsert_plan_link(
     .bind(calendar_id)
     .bind(google_event_id)
     .bind(etag)
-    .execute(pool)
+    .execute(executor)
     .await
     .map_err(ApiError::from)?;
 
     Ok(())
 }
 
-async fn insert_google_event(
-    client: &Client,
-    access_token: &str,
-    calendar_id: &str,
+/// Nearest Google palette entry to a `#rrggbb` category color
+fn google_color_id(hex: &str) -> Option<String> {
+    let channel = |i: usize| hex.get(i..i + 2).and_then(|c| u8::from_str_radix(c, 16).ok());
+    let (r, g, b) = (channel(1)?, channel(3)?, channel(5)?);
+    GOOGLE_EVENT_COLORS
+        .iter()
+        .min_by_key(|(_, (pr, pg, pb))| {
+            let d = |a: u8, b: u8| (a as i32 - b as i32).pow(2);
+            d(r, *pr) + d(g, *pg) + d(b, *pb)
+        })
+        .map(|(id, _)| id.to_string())
+}
+
+fn plan_event_payload(
     title: &str,
     start_at: &str,
     end_at: &str,
     local_id: i64,
-) -> Result<String, ApiError> {
+    category: Option<&Category>,
+    busy_only: bool,
+) -> GoogleEventInsert {
     let mut private_props = HashMap::new();
     private_props.insert("lifeos_id".to_string(), format!("wpb_{}", local_id));
     private_props.insert("lifeos_type".to_string(), "week_plan_block".to_string());
 
-    let payload = GoogleEventInsert {
+    if busy_only {
+        // Only the time is shared; title, course and category stay local
+        return GoogleEventInsert {
+            summary: BUSY_TITLE.to_string(),
+            start: GoogleEventTimeInsert {
+                date_time: normalize_datetime(start_at),
+            },
+            end: GoogleEventTimeInsert {
+                date_time: normalize_datetime(end_at),
+            },
+            color_id: None,
+            transparency: "opaque".to_string(),
+            extended_properties: GoogleExtendedPropertiesInsert { private_props },
+        };
+    }
+
+    GoogleEventInsert {
         summary: title.to_string(),
         start: GoogleEventTimeInsert {
             date_time: normalize_datetime(start_at),
@@ -1098,54 +1850,34 @@ async fn insert_google_event(
         end: GoogleEventTimeInsert {
             date_time: normalize_datetime(end_at),
         },
+        color_id: category.and_then(|c| google_color_id(&c.color)),
+        transparency: if category.map_or(true, |c| c.is_busy) { "opaque" } else { "transparent" }.to_string(),
         extended_properties: GoogleExtendedPropertiesInsert { private_props },
-    };
+    }
+}
 
+async fn insert_google_event(
+    api: &GoogleApi,
+    calendar_id: &str,
+    payload: &GoogleEventInsert,
+) -> Result<String, ApiError> {
     let url = format!(
         "{}/calendars/{}/events",
         GOOGLE_CALENDAR_API,
         urlencoding::encode(calendar_id)
     );
 
-    let res = parse_json_response::<GoogleEvent>(
-        client
-            .post(url)
-            .bearer_auth(access_token)
-            .json(&payload)
-            .send()
-            .await
-            .map_err(ApiError::from)?,
-    )
-    .await?;
+    let res = api.json::<GoogleEvent>(|c| c.post(&url).json(payload)).await?;
 
     Ok(res.id)
 }
 
 async fn patch_google_event(
-    client: &Client,
-    access_token: &str,
+    api: &GoogleApi,
     calendar_id: &str,
     event_id: &str,
-    title: &str,
-    start_at: &str,
-    end_at: &str,
-    local_id: i64,
+    payload: &GoogleEventInsert,
 ) -> Result<(), ApiError> {
-    let mut private_props = HashMap::new();
-    private_props.insert("lifeos_id".to_string(), format!("wpb_{}", local_id));
-    private_props.insert("lifeos_type".to_string(), "week_plan_block".to_string());
-
-    let payload = GoogleEventInsert {
-        summary: title.to_string(),
-        start: GoogleEventTimeInsert {
-            date_time: normalize_datetime(start_at),
-        },
-        end: GoogleEventTimeInsert {
-            date_time: normalize_datetime(end_at),
-        },
-        extended_properties: GoogleExtendedPropertiesInsert { private_props },
-    };
-
     let url = format!(
         "{}/calendars/{}/events/{}",
         GOOGLE_CALENDAR_API,
@@ -1153,16 +1885,7 @@ async fn patch_google_event(
         urlencoding::encode(event_id)
     );
 
-    parse_json_response::<serde_json::Value>(
-        client
-            .patch(url)
-            .bearer_auth(access_token)
-            .json(&payload)
-            .send()
-            .await
-            .map_err(ApiError::from)?,
-    )
-    .await?;
+    api.json::<serde_json::Value>(|c| c.patch(&url).json(payload)).await?;
 
     Ok(())
 }
@@ -1170,13 +1893,17 @@ async fn patch_google_event(
 async fn parse_json_response<T: DeserializeOwned>(res: reqwest::Response) -> Result<T, ApiError> {
     let status = res.status();
     let body = res.text().await.map_err(ApiError::from)?;
+    decode_json_response(status, &body)
+}
+
+fn decode_json_response<T: DeserializeOwned>(status: StatusCode, body: &str) -> Result<T, ApiError> {
     if !status.is_success() {
         return Err(ApiError::internal(format!(
             "Google API error {}: {}",
             status, body
         )));
     }
-    serde_json::from_str::<T>(&body).map_err(|e| {
+    serde_json::from_str::<T>(body).map_err(|e| {
         ApiError::internal(format!("error decoding response body: {e}; body: {body}"))
     })
 }
@@ -1187,15 +1914,252 @@ fn normalize_datetime(value: &str) -> String {
 
 #[cfg(test)]
 mod tests {
-    use super::{GoogleState, normalize_datetime};
+    use super::{backoff_delay, google_color_id, plan_event_payload, plan_writes, PlanWrite, mobile_redirect_uri, preview_external_calendar, receive_redirect, recover_sync_journal, should_retry, week_start_date_from, ChangeAction, GoogleApi, GoogleEvent, GoogleState, GoogleSyncPreview, OAuthSession, SyncApiCalls, normalize_datetime, BACKOFF_MAX_MS};
+    use reqwest::StatusCode;
+    use crate::services::google_sync_journal::{self, Operation};
+    use chrono::{Datelike, FixedOffset, NaiveDate, TimeZone, Weekday};
+    use proptest::prelude::*;
     use tokio::time::{sleep, timeout, Duration};
 
+    proptest! {
+        #[test]
+        fn week_start_is_the_monday_on_or_before(
+            minutes in 0i64..130 * 366 * 24 * 60,
+            offset_quarters in -48i32..=56,
+        ) {
+            let offset = FixedOffset::east_opt(offset_quarters * 15 * 60).unwrap();
+            let utc = chrono::Utc.timestamp_opt(minutes * 60, 0).unwrap();
+            let start_at = offset.from_utc_datetime(&utc.naive_utc()).to_rfc3339();
+
+            let monday = NaiveDate::parse_from_str(&week_start_date_from(&start_at), "%Y-%m-%d").unwrap();
+            let date = utc.with_timezone(&offset).date_naive();
+            prop_assert_eq!(monday.weekday(), Weekday::Mon);
+            prop_assert!(monday <= date && (date - monday).num_days() < 7, "{} -> {}", start_at, monday);
+        }
+
+        #[test]
+        fn normalize_datetime_always_outputs_rfc3339(value in "\\PC{0,30}") {
+            let output = normalize_datetime(&value);
+            prop_assert!(chrono::DateTime::parse_from_rfc3339(&output).is_ok(), "{:?} -> {}", value, output);
+        }
+
+        #[test]
+        fn normalize_datetime_keeps_rfc3339_instants(
+            minutes in 0i64..130 * 366 * 24 * 60,
+            offset_quarters in -48i32..=56,
+        ) {
+            let offset = FixedOffset::east_opt(offset_quarters * 15 * 60).unwrap();
+            let utc = chrono::Utc.timestamp_opt(minutes * 60, 0).unwrap();
+            let input = offset.from_utc_datetime(&utc.naive_utc()).to_rfc3339();
+
+            let output = chrono::DateTime::parse_from_rfc3339(&normalize_datetime(&input)).unwrap();
+            prop_assert_eq!(output, utc);
+        }
+    }
+
     #[test]
     fn google_calendar_normalize_datetime_outputs_rfc3339() {
         let output = normalize_datetime("2026-02-07T09:30");
         assert!(output.contains('Z') || output.contains('+'));
     }
 
+    #[test]
+    fn category_colors_map_to_nearest_google_color() {
+        assert_eq!(google_color_id("#d50000").as_deref(), Some("11"));
+        assert_eq!(google_color_id("#3b82f6").as_deref(), Some("7"));
+        assert_eq!(google_color_id("#64748b").as_deref(), Some("8"));
+        assert_eq!(google_color_id("blue"), None);
+    }
+
+    #[test]
+    fn busy_only_export_hides_title_and_category() {
+        let category = crate::models::category::Category {
+            id: 1,
+            name: "gym".to_string(),
+            color: "#d50000".to_string(),
+            is_busy: false,
+            is_builtin: false,
+            created_at: None,
+        };
+        let detailed = plan_event_payload("CS201 problem set", "2030-09-02T09:00:00", "2030-09-02T10:00:00", 5, Some(&category), false);
+        assert_eq!(detailed.summary, "CS201 problem set");
+        assert_eq!(detailed.color_id.as_deref(), Some("11"));
+        assert_eq!(detailed.transparency, "transparent");
+
+        let busy = plan_event_payload("CS201 problem set", "2030-09-02T09:00:00", "2030-09-02T10:00:00", 5, Some(&category), true);
+        assert_eq!(busy.summary, "Busy");
+        assert_eq!(busy.color_id, None);
+        assert_eq!(busy.transparency, "opaque");
+        assert_eq!(busy.start.date_time, detailed.start.date_time);
+        assert_eq!(busy.extended_properties.private_props["lifeos_id"], "wpb_5");
+    }
+
+    #[test]
+    fn mobile_redirect_uses_the_reversed_client_id() {
+        assert_eq!(
+            mobile_redirect_uri("1234-abc.apps.googleusercontent.com").as_deref(),
+            Some("com.googleusercontent.apps.1234-abc:/oauth2redirect")
+        );
+        assert_eq!(mobile_redirect_uri("1234-abc"), None);
+        assert_eq!(mobile_redirect_uri("a/b.apps.googleusercontent.com"), None);
+    }
+
+    #[tokio::test]
+    async fn redirects_are_only_accepted_for_the_pending_state() {
+        let state = GoogleState::default();
+        let url = url::Url::parse("com.googleusercontent.apps.1:/oauth2redirect?code=c&state=s1").unwrap();
+        assert!(!receive_redirect(&state, &url).await);
+
+        *state.oauth.lock().await = Some(OAuthSession {
+            state: "s2".into(),
+            code_verifier: String::new(),
+            redirect_uri: String::new(),
+            client_id: String::new(),
+            callback_url: None,
+        });
+        assert!(!receive_redirect(&state, &url).await);
+        state.oauth.lock().await.as_mut().unwrap().state = "s1".into();
+        assert!(receive_redirect(&state, &url).await);
+        assert_eq!(state.oauth.lock().await.as_ref().unwrap().callback_url.as_deref(), Some(url.as_str()));
+    }
+
+    #[test]
+    fn only_rate_limits_and_server_errors_are_retried() {
+        assert!(should_retry(StatusCode::TOO_MANY_REQUESTS, ""));
+        assert!(should_retry(StatusCode::SERVICE_UNAVAILABLE, ""));
+        assert!(should_retry(StatusCode::FORBIDDEN, r#"{"error":{"errors":[{"reason":"userRateLimitExceeded"}]}}"#));
+        assert!(!should_retry(StatusCode::FORBIDDEN, r#"{"error":{"errors":[{"reason":"forbidden"}]}}"#));
+        assert!(!should_retry(StatusCode::NOT_FOUND, ""));
+        assert!(!should_retry(StatusCode::OK, ""));
+    }
+
+    #[test]
+    fn backoff_is_capped_and_honors_retry_after() {
+        let _seed = crate::services::clock::seed(7);
+        for attempt in 0..20 {
+            assert!(backoff_delay(attempt, None).as_millis() <= BACKOFF_MAX_MS as u128);
+        }
+        assert!(backoff_delay(0, None).as_millis() <= 500);
+        assert!(backoff_delay(0, Some(3)).as_millis() >= 3000);
+    }
+
+    fn google_event(json: serde_json::Value) -> GoogleEvent {
+        serde_json::from_value(json).unwrap()
+    }
+
+    #[tokio::test]
+    async fn preview_lists_local_changes_without_applying_them() {
+        let pool = crate::test_support::pool().await;
+        for (title, event_id) in [("Standup", "same"), ("Lunch", "moved"), ("Dentist", "gone")] {
+            let local_id: i64 = sqlx::query_scalar(
+                "INSERT INTO calendar_events (user_id, title, start_at, end_at, category, domain, locked) VALUES (1, ?, '2030-09-02T09:00:00Z', '2030-09-02T10:00:00Z', 'busy', 'google', 1) RETURNING id",
+            )
+            .bind(title)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+            sqlx::query("INSERT INTO google_event_links (local_type, local_id, google_calendar_id, google_event_id) VALUES ('calendar_event', ?, 'work', ?)")
+                .bind(local_id)
+                .bind(event_id)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+        let at = |start: &str, end: &str| serde_json::json!({"start": {"dateTime": start}, "end": {"dateTime": end}});
+        let mut events = Vec::new();
+        for (id, summary, times) in [
+            ("same", "Standup", at("2030-09-02T09:00:00Z", "2030-09-02T10:00:00Z")),
+            ("moved", "Lunch", at("2030-09-02T12:00:00Z", "2030-09-02T13:00:00Z")),
+            ("new", "Review", at("2030-09-03T15:00:00Z", "2030-09-03T16:00:00Z")),
+        ] {
+            let mut event = times;
+            event["id"] = id.into();
+            event["summary"] = summary.into();
+            events.push(google_event(event));
+        }
+        events.push(google_event(serde_json::json!({"id": "gone", "status": "cancelled", "start": {}, "end": {}})));
+
+        let mut preview = GoogleSyncPreview {
+            local: Vec::new(),
+            remote: Vec::new(),
+            creates_plan_calendar: false,
+            interrupted_operations: 0,
+            calls: SyncApiCalls::default(),
+        };
+        preview_external_calendar(&pool, "work", events, &mut preview).await.unwrap();
+
+        let summary: Vec<(ChangeAction, Option<&str>)> =
+            preview.local.iter().map(|c| (c.action, c.title.as_deref())).collect();
+        assert_eq!(
+            summary,
+            vec![
+                (ChangeAction::Update, Some("Lunch")),
+                (ChangeAction::Create, Some("Review")),
+                (ChangeAction::Delete, Some("Dentist")),
+            ]
+        );
+        assert!(preview.remote.is_empty());
+
+        // Nothing was written
+        let events: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM calendar_events").fetch_one(&pool).await.unwrap();
+        assert_eq!(events, 3);
+    }
+
+    #[tokio::test]
+    async fn split_blocks_export_as_one_event() {
+        let pool = crate::test_support::pool().await;
+        let mut block = crate::test_support::plan_block("2030-09-02", "2030-09-02T09:00:00", "2030-09-02T10:00:00", None);
+        block.status = Some("accepted".to_string());
+        block.title = Some("Deep work".to_string());
+        let profile = crate::commands::focus_profiles::load(&pool).await.unwrap().remove(0);
+        let parts = crate::commands::week_plan_blocks::split_block(block, &profile).unwrap();
+        assert_eq!(parts.len(), 3);
+        let created = crate::commands::week_plan_blocks::bulk_create_plan_blocks_inner(&pool, parts).await.unwrap();
+
+        let writes = plan_writes(&pool, "2030-09-01", "2030-09-03", &Default::default(), &Default::default())
+            .await
+            .unwrap();
+        assert_eq!(writes.len(), 1);
+        match &writes[0] {
+            PlanWrite::Create { block_id, payload } => {
+                assert_eq!(*block_id, created[0].id);
+                assert_eq!(payload.summary, "Deep work");
+                assert_eq!(payload.start.date_time, normalize_datetime("2030-09-02T09:00:00"));
+                assert_eq!(payload.end.date_time, normalize_datetime("2030-09-02T10:00:00"));
+            }
+            _ => panic!("expected a create"),
+        }
+    }
+
+    #[tokio::test]
+    async fn recovery_links_events_created_before_a_crash() {
+        let pool = crate::test_support::pool().await;
+        // Google returned the event, then the app died before linking it
+        let entry = google_sync_journal::begin(&pool, Operation::CreateEvent, "week_plan_block", 42, "plan-cal")
+            .await
+            .unwrap();
+        google_sync_journal::record_remote(&pool, entry, "evt-42").await.unwrap();
+
+        // Entries with a recorded event id never reach Google
+        let recovered = recover_sync_journal(&pool, &GoogleApi::new("unused".to_string())).await.unwrap();
+        assert_eq!(recovered, 1);
+        assert!(google_sync_journal::pending(&pool).await.unwrap().is_empty());
+
+        let link: (String, String) = sqlx::query_as(
+            "SELECT google_calendar_id, google_event_id FROM google_event_links WHERE local_type = 'week_plan_block' AND local_id = 42",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(link, ("plan-cal".to_string(), "evt-42".to_string()));
+        let resolution: String = sqlx::query_scalar("SELECT resolution FROM google_sync_journal WHERE id = ?")
+            .bind(entry)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(resolution, "relinked");
+    }
+
     #[tokio::test]
     async fn google_sync_lock_is_exclusive() {
         let state = GoogleState::default();
@@ -0,0 +1,338 @@
+//! Grade What-If Simulator
+//!
+//! Projects a course's final grade from the weighted grade model: the course's
+//! standing covers everything graded so far, and each ungraded exam adds its
+//! weight (percent of the final grade). Hypothetical scores are plugged in
+//! as given; exams without one are drawn around the current standing, with
+//! the spread of the graded exams (or `DEFAULT_SCORE_SD`), over
+//! `SIMULATION_RUNS` seeded runs so the same inputs give the same answer.
+
+use std::collections::HashMap;
+
+use rand::rngs::StdRng;
+use rand::SeedableRng;
+use rand_distr::{Distribution, Normal};
+use serde::Serialize;
+use sqlx::{Pool, Sqlite};
+use tauri::State;
+
+use crate::{error::ApiError, DbState};
+
+const SIMULATION_RUNS: usize = 10_000;
+const SIMULATION_SEED: u64 = 0x5EED;
+/// Spread of projected exam scores when fewer than two exams are graded
+const DEFAULT_SCORE_SD: f64 = 10.0;
+
+/// Letter grade cut-offs (percent), best first
+const LETTER_GRADES: &[(&str, f64)] = &[("A", 90.0), ("B", 80.0), ("C", 70.0), ("D", 60.0), ("F", 0.0)];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ScoreSource {
+    Graded,
+    Hypothetical,
+    Projected,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SimulatedAssessment {
+    pub exam_id: i64,
+    pub title: String,
+    pub weight: f64,
+    /// Fixed score; None when projected
+    pub score: Option<f64>,
+    pub source: ScoreSource,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LetterProbability {
+    pub letter: &'static str,
+    pub min_grade: f64,
+    pub probability: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GradeSimulation {
+    pub course_id: i64,
+    /// Standing on the work graded so far
+    pub current_grade: f64,
+    pub target_grade: Option<f64>,
+    /// Share of the final grade already decided (0-100)
+    pub completed_weight: f64,
+    pub assessments: Vec<SimulatedAssessment>,
+    pub mean: f64,
+    pub p10: f64,
+    pub p50: f64,
+    pub p90: f64,
+    pub letter_probabilities: Vec<LetterProbability>,
+    pub target_probability: Option<f64>,
+    /// Average needed on the projected exams to reach the target exactly;
+    /// None without a target or projected exams (may exceed 100)
+    pub required_average: Option<f64>,
+}
+
+type ExamRow = (i64, String, Option<f64>, Option<f64>);
+
+/// Final-grade distribution for a course given hypothetical exam scores
+/// (exam id -> percent) for some of its ungraded exams
+#[tauri::command]
+pub async fn simulate_grade(
+    state: State<'_, DbState>,
+    course_id: i64,
+    hypothetical_scores: HashMap<i64, f64>,
+) -> Result<GradeSimulation, ApiError> {
+    let pool = &state.0;
+    simulate_grade_for_pool(pool, course_id, &hypothetical_scores).await
+}
+
+pub(crate) async fn simulate_grade_for_pool(
+    pool: &Pool<Sqlite>,
+    course_id: i64,
+    hypothetical_scores: &HashMap<i64, f64>,
+) -> Result<GradeSimulation, ApiError> {
+    let course: Option<(Option<f64>, Option<f64>)> =
+        sqlx::query_as("SELECT current_grade, target_grade FROM courses WHERE id = ?")
+            .bind(course_id)
+            .fetch_optional(pool)
+            .await
+            .map_err(ApiError::from)?;
+    let (current_grade, target_grade) = course.ok_or_else(|| ApiError::not_found("Course not found"))?;
+
+    let exams: Vec<ExamRow> = sqlx::query_as(
+        "SELECT id, title, grade, weight FROM exams WHERE course_id = ? AND weight > 0 ORDER BY exam_date IS NULL, exam_date, id",
+    )
+    .bind(course_id)
+    .fetch_all(pool)
+    .await
+    .map_err(ApiError::from)?;
+
+    for (&exam_id, &score) in hypothetical_scores {
+        if !(0.0..=100.0).contains(&score) {
+            return Err(ApiError::validation(format!("Score for exam {} must be between 0 and 100", exam_id)));
+        }
+        match exams.iter().find(|e| e.0 == exam_id) {
+            Some((_, title, Some(_), _)) => {
+                return Err(ApiError::validation(format!("'{}' is already graded", title)));
+            }
+            Some(_) => {}
+            None => {
+                return Err(ApiError::validation(format!(
+                    "Exam {} is not a weighted exam of this course",
+                    exam_id
+                )))
+            }
+        }
+    }
+
+    let graded: Vec<(f64, f64)> = exams.iter().filter_map(|e| Some((e.2?, e.3?))).collect();
+    let remaining_weight: f64 = exams.iter().filter(|e| e.2.is_none()).filter_map(|e| e.3).sum();
+    if remaining_weight > 100.0 {
+        return Err(ApiError::validation("Ungraded exam weights add up to more than 100%"));
+    }
+
+    // The course grade, when set, already reflects all graded work
+    let graded_weight: f64 = graded.iter().map(|g| g.1).sum();
+    let standing = match current_grade {
+        Some(grade) => grade,
+        None if graded_weight > 0.0 => graded.iter().map(|(g, w)| g * w).sum::<f64>() / graded_weight,
+        None => {
+            return Err(ApiError::validation(
+                "Set the course's current grade or grade an exam before simulating",
+            ))
+        }
+    };
+    let spread = if graded.len() >= 2 {
+        let mean = graded.iter().map(|g| g.0).sum::<f64>() / graded.len() as f64;
+        (graded.iter().map(|g| (g.0 - mean).powi(2)).sum::<f64>() / (graded.len() - 1) as f64)
+            .sqrt()
+            .max(1.0)
+    } else {
+        DEFAULT_SCORE_SD
+    };
+
+    let assessments: Vec<SimulatedAssessment> = exams
+        .into_iter()
+        .map(|(exam_id, title, grade, weight)| {
+            let (score, source) = match (grade, hypothetical_scores.get(&exam_id)) {
+                (Some(g), _) => (Some(g), ScoreSource::Graded),
+                (None, Some(&h)) => (Some(h), ScoreSource::Hypothetical),
+                (None, None) => (None, ScoreSource::Projected),
+            };
+            SimulatedAssessment {
+                exam_id,
+                title,
+                weight: weight.unwrap_or(0.0),
+                score,
+                source,
+            }
+        })
+        .collect();
+
+    // Points already settled by the grade so far and the hypothetical scores
+    let completed_weight = 100.0 - remaining_weight;
+    let fixed = standing * completed_weight / 100.0
+        + assessments
+            .iter()
+            .filter(|a| a.source == ScoreSource::Hypothetical)
+            .map(|a| a.score.unwrap_or(0.0) * a.weight / 100.0)
+            .sum::<f64>();
+    let projected: Vec<f64> = assessments
+        .iter()
+        .filter(|a| a.source == ScoreSource::Projected)
+        .map(|a| a.weight)
+        .collect();
+
+    let finals = sample_finals(fixed, &projected, standing, spread)?;
+    let projected_weight: f64 = projected.iter().sum();
+    let required_average = match target_grade {
+        Some(target) if projected_weight > 0.0 => Some(((target - fixed) * 100.0 / projected_weight).max(0.0)),
+        _ => None,
+    };
+
+    let percentile = |p: f64| finals[((finals.len() - 1) as f64 * p).round() as usize];
+    let share_at_least = |grade: f64| finals.iter().filter(|&&f| f >= grade).count() as f64 / finals.len() as f64;
+
+    let mut letter_probabilities = Vec::new();
+    let mut above = 0.0;
+    for &(letter, min_grade) in LETTER_GRADES {
+        let at_least = share_at_least(min_grade);
+        letter_probabilities.push(LetterProbability {
+            letter,
+            min_grade,
+            probability: at_least - above,
+        });
+        above = at_least;
+    }
+
+    Ok(GradeSimulation {
+        course_id,
+        current_grade: standing,
+        target_grade,
+        completed_weight,
+        assessments,
+        mean: finals.iter().sum::<f64>() / finals.len() as f64,
+        p10: percentile(0.1),
+        p50: percentile(0.5),
+        p90: percentile(0.9),
+        letter_probabilities,
+        target_probability: target_grade.map(share_at_least),
+        required_average,
+    })
+}
+
+/// Simulated final grades, sorted ascending: `fixed` points plus a drawn
+/// score for each projected exam weight
+fn sample_finals(fixed: f64, projected: &[f64], standing: f64, spread: f64) -> Result<Vec<f64>, ApiError> {
+    if projected.is_empty() {
+        return Ok(vec![fixed]);
+    }
+
+    let score = Normal::new(standing, spread).map_err(|e| ApiError::internal(e.to_string()))?;
+    let mut rng = StdRng::seed_from_u64(SIMULATION_SEED);
+    let mut finals: Vec<f64> = (0..SIMULATION_RUNS)
+        .map(|_| {
+            fixed
+                + projected
+                    .iter()
+                    .map(|w| score.sample(&mut rng).clamp(0.0, 100.0) * w / 100.0)
+                    .sum::<f64>()
+        })
+        .collect();
+    finals.sort_by(|a, b| a.total_cmp(b));
+    Ok(finals)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+    use std::str::FromStr;
+
+    async fn setup_pool_with_migrations() -> Pool<Sqlite> {
+        let options = SqliteConnectOptions::from_str("sqlite::memory:")
+            .unwrap()
+            .foreign_keys(false);
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect_with(options)
+            .await
+            .expect("Failed to connect to in-memory DB");
+
+        crate::db::migrations::run_migrations(&pool)
+            .await
+            .expect("Failed to run migrations");
+
+        pool
+    }
+
+    async fn course_with_exams(pool: &Pool<Sqlite>) -> (i64, i64, i64) {
+        let course_id: i64 = sqlx::query_scalar(
+            "INSERT INTO courses (name, current_grade, target_grade) VALUES ('Physics', 80.0, 85.0) RETURNING id",
+        )
+        .fetch_one(pool)
+        .await
+        .unwrap();
+        let exam = |title: &'static str, grade: Option<f64>, weight: f64| {
+            sqlx::query_scalar::<_, i64>(
+                "INSERT INTO exams (course_id, title, grade, weight) VALUES (?, ?, ?, ?) RETURNING id",
+            )
+            .bind(course_id)
+            .bind(title)
+            .bind(grade)
+            .bind(weight)
+            .fetch_one(pool)
+        };
+        exam("Midterm", Some(80.0), 30.0).await.unwrap();
+        let quiz = exam("Quiz", None, 10.0).await.unwrap();
+        let final_exam = exam("Final", None, 40.0).await.unwrap();
+        (course_id, quiz, final_exam)
+    }
+
+    #[tokio::test]
+    async fn hypothetical_scores_fix_the_final_grade() {
+        let pool = setup_pool_with_migrations().await;
+        let (course_id, quiz, final_exam) = course_with_exams(&pool).await;
+
+        // 80 on the decided 50%, 100 on the quiz and 90 on the final
+        let scores = HashMap::from([(quiz, 100.0), (final_exam, 90.0)]);
+        let sim = simulate_grade_for_pool(&pool, course_id, &scores).await.unwrap();
+        assert_eq!(sim.completed_weight, 50.0);
+        assert!((sim.mean - 86.0).abs() < 1e-9);
+        assert_eq!(sim.p10, sim.p90);
+        assert_eq!(sim.target_probability, Some(1.0));
+        assert_eq!(sim.letter_probabilities[1].letter, "B");
+        assert_eq!(sim.letter_probabilities[1].probability, 1.0);
+        assert_eq!(sim.required_average, None);
+    }
+
+    #[tokio::test]
+    async fn projects_remaining_exams_around_current_standing() {
+        let pool = setup_pool_with_migrations().await;
+        let (course_id, quiz, final_exam) = course_with_exams(&pool).await;
+
+        let sim = simulate_grade_for_pool(&pool, course_id, &HashMap::from([(quiz, 100.0)]))
+            .await
+            .unwrap();
+        assert_eq!(sim.assessments.iter().filter(|a| a.source == ScoreSource::Projected).count(), 1);
+        // 40 + 10 fixed, final projected around 80 -> about 82
+        assert!((sim.p50 - 82.0).abs() < 1.0, "p50 = {}", sim.p50);
+        assert!(sim.p10 < sim.p50 && sim.p50 < sim.p90);
+        let total: f64 = sim.letter_probabilities.iter().map(|l| l.probability).sum();
+        assert!((total - 1.0).abs() < 1e-9);
+        // (85 - 50) / 0.4
+        assert!((sim.required_average.unwrap() - 87.5).abs() < 1e-9);
+
+        // Same inputs, same answer
+        let again = simulate_grade_for_pool(&pool, course_id, &HashMap::from([(quiz, 100.0)]))
+            .await
+            .unwrap();
+        assert_eq!(sim.p50, again.p50);
+
+        assert!(simulate_grade_for_pool(&pool, course_id, &HashMap::from([(final_exam, 120.0)]))
+            .await
+            .is_err());
+        assert!(simulate_grade_for_pool(&pool, course_id, &HashMap::from([(999, 50.0)]))
+            .await
+            .is_err());
+    }
+}
@@ -0,0 +1,20 @@
+//! Local HTTP API commands
+
+use tauri::State;
+
+use crate::http_api::{self, HttpApiStatus};
+use crate::{error::ApiError, DbState};
+
+/// Whether the local API is enabled and running, and where
+#[tauri::command]
+pub async fn get_http_api_status(state: State<'_, DbState>) -> Result<HttpApiStatus, ApiError> {
+    let pool = &state.0;
+    http_api::status(pool).await
+}
+
+/// Create a new bearer token, revoking the previous one; returned only here
+#[tauri::command]
+pub async fn regenerate_http_api_token(state: State<'_, DbState>) -> Result<String, ApiError> {
+    let pool = &state.0;
+    http_api::regenerate_token(pool).await
+}
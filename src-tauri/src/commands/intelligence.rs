@@ -6,10 +6,17 @@
 use serde::{Deserialize, Serialize};
 use tauri::State;
 
+use crate::agent::preferences;
+use crate::agent::scheduler::{self, MaintenanceRun};
+use crate::agent::shadow::{self, ShadowReport};
 use crate::agent::{
     AgentRecommendation, AgentStatus, BigThreeGoal, IntelligenceAgent,
 };
 use crate::error::ApiError;
+use crate::ml::models::{
+    RewardEngine, RewardWeightChange, RewardWeights, REWARD_WEIGHT_PRESETS,
+};
+use crate::ml::readiness::{self, MlReadiness};
 use crate::ml::{RichContext, RichFeatureStore};
 use crate::DbState;
 
@@ -21,9 +28,12 @@ pub async fn get_agent_recommendations(
 ) -> Result<Vec<AgentRecommendation>, ApiError> {
     let pool = &state.0;
     let n = count.unwrap_or(3);
-    IntelligenceAgent::get_recommendations(pool, n)
+    let recommendations = IntelligenceAgent::get_recommendations(pool, n)
         .await
-        .map_err(ApiError::internal)
+        .map_err(ApiError::internal)?;
+
+    // Shadow recommendations are only logged
+    Ok(recommendations.into_iter().filter(|r| !r.is_shadow).collect())
 }
 
 /// Get the top recommendation
@@ -32,12 +42,23 @@ pub async fn get_agent_recommendation(
     state: State<'_, DbState>,
 ) -> Result<AgentRecommendation, ApiError> {
     let pool = &state.0;
-    IntelligenceAgent::get_recommendation(pool)
+    let recommendation = IntelligenceAgent::get_recommendation(pool)
         .await
-        .map_err(ApiError::internal)
+        .map_err(ApiError::internal)?;
+
+    if recommendation.is_shadow {
+        return Err(ApiError::not_found("Recommendations are hidden in shadow mode"));
+    }
+    Ok(recommendation)
 }
 
+/// Longest allowed snooze (one week)
+const MAX_SNOOZE_HOURS: f32 = 168.0;
+
 /// Record feedback on a recommendation
+///
+/// Passing `snooze_hours` records a "not now" instead: the action is hidden for
+/// that long without a negative reward, and `accepted` is ignored.
 #[tauri::command]
 pub async fn record_recommendation_feedback(
     state: State<'_, DbState>,
@@ -46,8 +67,20 @@ pub async fn record_recommendation_feedback(
     alternative_chosen: Option<String>,
     feedback_score: Option<i32>,
     outcome_score: Option<f32>,
+    snooze_hours: Option<f32>,
 ) -> Result<(), ApiError> {
     let pool = &state.0;
+
+    if let Some(hours) = snooze_hours {
+        if !(hours > 0.0 && hours <= MAX_SNOOZE_HOURS) {
+            return Err(ApiError::validation("Snooze must be between 0 and 168 hours"));
+        }
+        return IntelligenceAgent::snooze_recommendation(pool, recommendation_id, hours)
+            .await
+            .map(|_| ())
+            .map_err(ApiError::internal);
+    }
+
     IntelligenceAgent::record_feedback(
         pool,
         recommendation_id,
@@ -84,6 +117,15 @@ pub async fn get_agent_status(state: State<'_, DbState>) -> Result<AgentStatus,
         .map_err(ApiError::internal)
 }
 
+/// Whether background ML initialization has finished
+///
+/// Until it has, recommendations come without similar experiences and
+/// insights from the rule-based pipeline.
+#[tauri::command]
+pub async fn get_ml_readiness() -> Result<MlReadiness, ApiError> {
+    Ok(readiness::status())
+}
+
 /// Get current rich context (50+ features)
 #[tauri::command]
 pub async fn get_rich_context(state: State<'_, DbState>) -> Result<RichContext, ApiError> {
@@ -138,15 +180,81 @@ pub async fn complete_big_three(
         .map_err(ApiError::internal)
 }
 
-/// Run daily maintenance (reward updates, cleanup)
+/// Run daily maintenance now (reward updates, pattern mining, cleanup)
 #[tauri::command]
-pub async fn run_agent_maintenance(state: State<'_, DbState>) -> Result<(), ApiError> {
+pub async fn run_agent_maintenance(state: State<'_, DbState>) -> Result<MaintenanceRun, ApiError> {
     let pool = &state.0;
     IntelligenceAgent::daily_maintenance(pool)
         .await
         .map_err(ApiError::internal)
 }
 
+/// Get recent maintenance runs (newest first)
+#[tauri::command]
+pub async fn get_maintenance_log(
+    state: State<'_, DbState>,
+    limit: Option<i64>,
+) -> Result<Vec<MaintenanceRun>, ApiError> {
+    let pool = &state.0;
+    scheduler::get_maintenance_log(pool, limit.unwrap_or(20).clamp(1, 200))
+        .await
+        .map_err(ApiError::internal)
+}
+
+/// Get the local hour at which scheduled maintenance runs
+#[tauri::command]
+pub async fn get_maintenance_hour(state: State<'_, DbState>) -> Result<u32, ApiError> {
+    let pool = &state.0;
+    scheduler::get_maintenance_hour(pool)
+        .await
+        .map_err(ApiError::internal)
+}
+
+/// Set the local hour (0-23) at which scheduled maintenance runs
+#[tauri::command]
+pub async fn set_maintenance_hour(state: State<'_, DbState>, hour: u32) -> Result<(), ApiError> {
+    let pool = &state.0;
+    if hour > 23 {
+        return Err(ApiError::validation("Maintenance hour must be between 0 and 23"));
+    }
+    scheduler::set_maintenance_hour(pool, hour)
+        .await
+        .map_err(ApiError::internal)
+}
+
+/// Whether the agent runs in shadow mode
+#[tauri::command]
+pub async fn get_agent_shadow_mode(state: State<'_, DbState>) -> Result<bool, ApiError> {
+    let pool = &state.0;
+    preferences::is_shadow_mode(pool)
+        .await
+        .map_err(ApiError::internal)
+}
+
+/// Turn shadow mode on or off
+///
+/// In shadow mode the agent logs what it would recommend and the inferred
+/// outcome, but nothing is shown and the bandit isn't trained on it.
+#[tauri::command]
+pub async fn set_agent_shadow_mode(state: State<'_, DbState>, enabled: bool) -> Result<(), ApiError> {
+    let pool = &state.0;
+    preferences::set_shadow_mode(pool, enabled)
+        .await
+        .map_err(ApiError::internal)
+}
+
+/// Summarize recommendations logged in shadow mode over the last `days` days
+#[tauri::command]
+pub async fn get_shadow_report(
+    state: State<'_, DbState>,
+    days: Option<i64>,
+) -> Result<ShadowReport, ApiError> {
+    let pool = &state.0;
+    shadow::get_report(pool, days.unwrap_or(14).clamp(1, 365))
+        .await
+        .map_err(ApiError::internal)
+}
+
 /// Get feature names for UI display
 #[tauri::command]
 pub fn get_feature_names() -> Vec<String> {
@@ -157,6 +265,8 @@ pub fn get_feature_names() -> Vec<String> {
 }
 
 /// Search semantic memory for similar experiences
+///
+/// Empty while semantic memory is loading or unavailable.
 #[tauri::command]
 pub async fn search_similar_experiences(
     _state: State<'_, DbState>,
@@ -165,6 +275,9 @@ pub async fn search_similar_experiences(
 ) -> Result<Vec<SimilarExperience>, ApiError> {
     use crate::ml::SemanticMemory;
 
+    if !readiness::semantic_memory_usable() {
+        return Ok(Vec::new());
+    }
     let memory = SemanticMemory::global().await.map_err(ApiError::internal)?;
     let results = memory
         .search_similar(&query, limit.unwrap_or(5), None)
@@ -192,6 +305,25 @@ pub struct SimilarExperience {
     pub similarity: f32,
 }
 
+/// Get the current reward weights
+#[tauri::command]
+pub async fn get_reward_weights(state: State<'_, DbState>) -> Result<RewardWeightsView, ApiError> {
+    let pool = &state.0;
+    let weights = RewardEngine::get_weights(pool)
+        .await
+        .map_err(ApiError::internal)?;
+    let preset = weights.matching_preset().map(String::from);
+
+    Ok(RewardWeightsView { weights, preset })
+}
+
+#[derive(Debug, Serialize)]
+pub struct RewardWeightsView {
+    pub weights: RewardWeights,
+    /// Preset the current weights match, if any
+    pub preset: Option<String>,
+}
+
 /// Update reward weights (for tuning)
 #[tauri::command]
 pub async fn set_reward_weights(
@@ -202,29 +334,72 @@ pub async fn set_reward_weights(
     monthly: f32,
 ) -> Result<(), ApiError> {
     let pool = &state.0;
-    
-    // Validate weights sum to ~1.0
-    let total = immediate + daily + weekly + monthly;
-    if (total - 1.0).abs() > 0.01 {
-        return Err(ApiError::validation("Weights must sum to 1.0"));
-    }
 
-    let weights = serde_json::json!({
-        "immediate": immediate,
-        "daily": daily,
-        "weekly": weekly,
-        "monthly": monthly
-    });
+    let weights = RewardWeights {
+        immediate,
+        daily,
+        weekly,
+        monthly,
+    };
+    weights.validate().map_err(ApiError::validation)?;
 
-    sqlx::query(
-        "UPDATE agent_state SET value_json = ?, updated_at = datetime('now') WHERE key = 'reward_weights'",
-    )
-    .bind(weights.to_string())
-    .execute(pool)
-    .await
-    .map_err(ApiError::from)?;
+    RewardEngine::set_weights(pool, &weights, None)
+        .await
+        .map_err(ApiError::internal)
+}
 
-    Ok(())
+/// List available reward weight presets
+#[tauri::command]
+pub fn get_reward_weight_presets() -> Vec<RewardWeightPreset> {
+    REWARD_WEIGHT_PRESETS
+        .iter()
+        .filter_map(|(name, label, description)| {
+            RewardWeights::preset(name).map(|weights| RewardWeightPreset {
+                name: name.to_string(),
+                label: label.to_string(),
+                description: description.to_string(),
+                weights,
+            })
+        })
+        .collect()
+}
+
+#[derive(Debug, Serialize)]
+pub struct RewardWeightPreset {
+    pub name: String,
+    pub label: String,
+    pub description: String,
+    pub weights: RewardWeights,
+}
+
+/// Apply a named reward weight preset
+#[tauri::command]
+pub async fn apply_reward_weight_preset(
+    state: State<'_, DbState>,
+    preset: String,
+) -> Result<RewardWeights, ApiError> {
+    let pool = &state.0;
+
+    let weights = RewardWeights::preset(&preset)
+        .ok_or_else(|| ApiError::validation(format!("Unknown reward weight preset: {}", preset)))?;
+
+    RewardEngine::set_weights(pool, &weights, Some(&preset))
+        .await
+        .map_err(ApiError::internal)?;
+
+    Ok(weights)
+}
+
+/// Get history of reward weight changes (newest first)
+#[tauri::command]
+pub async fn get_reward_weight_history(
+    state: State<'_, DbState>,
+    limit: Option<i64>,
+) -> Result<Vec<RewardWeightChange>, ApiError> {
+    let pool = &state.0;
+    RewardEngine::get_weight_history(pool, limit.unwrap_or(50).clamp(1, 500))
+        .await
+        .map_err(ApiError::internal)
 }
 
 /// Set exploration rate (beta for UCB)
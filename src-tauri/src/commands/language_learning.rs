@@ -0,0 +1,47 @@
+//! Language learning commands
+
+use tauri::State;
+
+use crate::services::clock;
+use crate::services::language_learning::{
+    self, FlashcardDeck, FlashcardDeckInput, FlashcardReview, FlashcardReviewInput, LanguageProficiency,
+    LanguageSkill, LanguageSkillInput,
+};
+use crate::{error::ApiError, DbState};
+
+/// Create a skill from the language template with its decks and the
+/// listening and speaking session types
+#[tauri::command]
+pub async fn create_language_skill(state: State<'_, DbState>, data: LanguageSkillInput) -> Result<LanguageSkill, ApiError> {
+    language_learning::create(&state.0, &data).await
+}
+
+#[tauri::command]
+pub async fn get_language_skills(state: State<'_, DbState>) -> Result<Vec<LanguageSkill>, ApiError> {
+    language_learning::list(&state.0).await
+}
+
+#[tauri::command]
+pub async fn add_flashcard_deck(state: State<'_, DbState>, data: FlashcardDeckInput) -> Result<FlashcardDeck, ApiError> {
+    language_learning::add_deck(&state.0, &data).await
+}
+
+/// Record a deck review; its minutes are logged as practice on the skill
+#[tauri::command]
+pub async fn log_flashcard_review(state: State<'_, DbState>, data: FlashcardReviewInput) -> Result<FlashcardReview, ApiError> {
+    language_learning::log_review(&state.0, &data).await
+}
+
+/// Vocabulary, listening and speaking scores over the last `days` days
+#[tauri::command]
+pub async fn get_language_proficiency(
+    state: State<'_, DbState>,
+    skill_id: Option<i64>,
+    days: Option<i64>,
+) -> Result<Vec<LanguageProficiency>, ApiError> {
+    let days = days.unwrap_or(30);
+    if !(1..=365).contains(&days) {
+        return Err(ApiError::validation("days must be between 1 and 365"));
+    }
+    language_learning::proficiency(&state.0, skill_id, days, clock::now().date_naive()).await
+}
@@ -0,0 +1,410 @@
+//! Lecture note-taking
+//!
+//! `start_meeting_session` opens a study session for one occurrence of a
+//! course meeting (a meeting plus the date it is held), titled and referenced
+//! to the course automatically. Notes taken during it are stored against that
+//! occurrence, so a lecture's notes can be pulled up later by date or topic.
+
+use chrono::{Datelike, Local, NaiveDate, NaiveTime};
+use serde::Deserialize;
+use sqlx::{Pool, Sqlite};
+use tauri::State;
+
+use crate::{
+    commands::sessions::{insert_session, SessionInput},
+    error::ApiError,
+    models::{lecture_note::LectureNote, session::{Session, SessionType}},
+    services::working_hours::WEEKDAYS,
+    DbState,
+};
+
+const MAX_TOPIC_LENGTH: usize = 200;
+const MAX_CONTENT_LENGTH: usize = 100_000;
+
+#[derive(Debug, Deserialize)]
+pub struct MeetingSessionInput {
+    pub meeting_id: i64,
+    /// YYYY-MM-DD; defaults to today
+    #[serde(default)]
+    pub date: Option<String>,
+    #[serde(default)]
+    pub focus_profile_id: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LectureNoteInput {
+    /// A session started from a meeting; supplies the meeting and date
+    #[serde(default)]
+    pub session_id: Option<i64>,
+    #[serde(default)]
+    pub course_meeting_id: Option<i64>,
+    #[serde(default)]
+    pub meeting_date: Option<String>,
+    #[serde(default)]
+    pub topic: Option<String>,
+    pub content: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LectureNoteUpdate {
+    #[serde(default)]
+    pub topic: Option<String>,
+    #[serde(default)]
+    pub content: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct LectureNoteQuery {
+    #[serde(default)]
+    pub course_id: Option<i64>,
+    #[serde(default)]
+    pub course_meeting_id: Option<i64>,
+    #[serde(default)]
+    pub start_date: Option<String>,
+    #[serde(default)]
+    pub end_date: Option<String>,
+    /// Case-insensitive match on topic or content
+    #[serde(default)]
+    pub search: Option<String>,
+}
+
+/// (course id, day of week, start, end, meeting type, course code, course name)
+type MeetingRow = (i64, i64, String, String, Option<String>, Option<String>, String);
+
+fn parse_date(value: &str, field: &str) -> Result<NaiveDate, ApiError> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .map_err(|_| ApiError::validation(format!("{} must be YYYY-MM-DD", field)))
+}
+
+fn normalize_topic(topic: Option<String>) -> Result<Option<String>, ApiError> {
+    let topic = topic.map(|t| t.trim().to_string()).filter(|t| !t.is_empty());
+    if topic.as_ref().is_some_and(|t| t.chars().count() > MAX_TOPIC_LENGTH) {
+        return Err(ApiError::validation(format!("Topic must be at most {} characters", MAX_TOPIC_LENGTH)));
+    }
+    Ok(topic)
+}
+
+fn validate_content(content: &str) -> Result<(), ApiError> {
+    if content.trim().is_empty() {
+        return Err(ApiError::validation("Note content is required"));
+    }
+    if content.chars().count() > MAX_CONTENT_LENGTH {
+        return Err(ApiError::validation(format!(
+            "Note content must be at most {} characters",
+            MAX_CONTENT_LENGTH
+        )));
+    }
+    Ok(())
+}
+
+async fn find_meeting(pool: &Pool<Sqlite>, meeting_id: i64) -> Result<MeetingRow, ApiError> {
+    sqlx::query_as(
+        r#"SELECT cm.course_id, cm.day_of_week, cm.start_time, cm.end_time, cm.meeting_type, c.code, c.name
+           FROM course_meetings cm
+           JOIN courses c ON c.id = cm.course_id
+           WHERE cm.id = ?"#,
+    )
+    .bind(meeting_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(ApiError::from)?
+    .ok_or_else(|| ApiError::not_found("Course meeting not found"))
+}
+
+/// Check that the meeting is held on `date`, returning the meeting
+async fn find_occurrence(pool: &Pool<Sqlite>, meeting_id: i64, date: NaiveDate) -> Result<MeetingRow, ApiError> {
+    let meeting = find_meeting(pool, meeting_id).await?;
+    if date.weekday().num_days_from_sunday() as i64 != meeting.1 {
+        let day = WEEKDAYS.get(meeting.1 as usize).copied().unwrap_or("another day");
+        return Err(ApiError::validation(format!("This meeting is held on {}s, not {}", day, date)));
+    }
+    Ok(meeting)
+}
+
+/// Start (or return the already open) session for a meeting occurrence
+#[tauri::command]
+pub async fn start_meeting_session(state: State<'_, DbState>, data: MeetingSessionInput) -> Result<Session, ApiError> {
+    let pool = &state.0;
+    start_for_meeting(pool, data).await
+}
+
+pub(crate) async fn start_for_meeting(pool: &Pool<Sqlite>, data: MeetingSessionInput) -> Result<Session, ApiError> {
+    let date = match data.date {
+        Some(ref d) => parse_date(d, "date")?,
+        None => Local::now().date_naive(),
+    };
+    let (course_id, _, start_time, end_time, meeting_type, code, name) =
+        find_occurrence(pool, data.meeting_id, date).await?;
+    let meeting_date = date.format("%Y-%m-%d").to_string();
+
+    let open: Option<Session> = sqlx::query_as(
+        "SELECT * FROM sessions WHERE course_meeting_id = ? AND meeting_date = ? AND ended_at IS NULL ORDER BY id DESC LIMIT 1",
+    )
+    .bind(data.meeting_id)
+    .bind(&meeting_date)
+    .fetch_optional(pool)
+    .await
+    .map_err(ApiError::from)?;
+    if let Some(session) = open {
+        return Ok(session);
+    }
+
+    let meeting_type = meeting_type.unwrap_or_else(|| "lecture".to_string());
+    let title = format!(
+        "{} {} · {}",
+        code.filter(|c| !c.trim().is_empty()).unwrap_or(name),
+        meeting_type.replace('_', " "),
+        date.format("%a %b %-d")
+    );
+    let planned_minutes = match (
+        NaiveTime::parse_from_str(&start_time, "%H:%M"),
+        NaiveTime::parse_from_str(&end_time, "%H:%M"),
+    ) {
+        (Ok(start), Ok(end)) => Some((end - start).num_minutes()),
+        _ => None,
+    };
+
+    insert_session(
+        pool,
+        &SessionInput {
+            user_id: None,
+            session_type: SessionType::Study,
+            reference_id: Some(course_id),
+            reference_type: Some("course".to_string()),
+            started_at: None,
+            notes: None,
+            planned_minutes,
+            focus_profile_id: data.focus_profile_id,
+            title: Some(title),
+            course_meeting_id: Some(data.meeting_id),
+            meeting_date: Some(meeting_date),
+            week_plan_block_id: None,
+        },
+    )
+    .await
+}
+
+pub(crate) async fn insert_note(pool: &Pool<Sqlite>, data: LectureNoteInput) -> Result<LectureNote, ApiError> {
+    validate_content(&data.content)?;
+    let topic = normalize_topic(data.topic)?;
+
+    let (meeting_id, meeting_date) = match data.session_id {
+        Some(session_id) => {
+            let linked: Option<(Option<i64>, Option<String>)> =
+                sqlx::query_as("SELECT course_meeting_id, meeting_date FROM sessions WHERE id = ?")
+                    .bind(session_id)
+                    .fetch_optional(pool)
+                    .await
+                    .map_err(ApiError::from)?;
+            match linked {
+                Some((Some(meeting_id), Some(date))) => (meeting_id, date),
+                Some(_) => return Err(ApiError::validation("Session was not started from a course meeting")),
+                None => return Err(ApiError::not_found("Session not found")),
+            }
+        }
+        None => match (data.course_meeting_id, data.meeting_date) {
+            (Some(meeting_id), Some(date)) => (meeting_id, date),
+            _ => {
+                return Err(ApiError::validation(
+                    "Either session_id or course_meeting_id and meeting_date are required",
+                ))
+            }
+        },
+    };
+    let date = parse_date(&meeting_date, "meeting_date")?;
+    let (course_id, ..) = find_occurrence(pool, meeting_id, date).await?;
+
+    sqlx::query_as::<_, LectureNote>(
+        r#"INSERT INTO lecture_notes (course_id, course_meeting_id, meeting_date, session_id, topic, content)
+           VALUES (?, ?, ?, ?, ?, ?)
+           RETURNING *"#,
+    )
+    .bind(course_id)
+    .bind(meeting_id)
+    .bind(date.format("%Y-%m-%d").to_string())
+    .bind(data.session_id)
+    .bind(&topic)
+    .bind(&data.content)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| ApiError::from_sqlx(e, "Failed to save lecture note"))
+}
+
+pub(crate) async fn list_notes(pool: &Pool<Sqlite>, query: LectureNoteQuery) -> Result<Vec<LectureNote>, ApiError> {
+    if let Some(ref d) = query.start_date {
+        parse_date(d, "start_date")?;
+    }
+    if let Some(ref d) = query.end_date {
+        parse_date(d, "end_date")?;
+    }
+    let search = query.search.map(|s| s.trim().to_lowercase()).filter(|s| !s.is_empty());
+
+    sqlx::query_as::<_, LectureNote>(
+        r#"SELECT * FROM lecture_notes
+           WHERE (?1 IS NULL OR course_id = ?1)
+             AND (?2 IS NULL OR course_meeting_id = ?2)
+             AND (?3 IS NULL OR meeting_date >= ?3)
+             AND (?4 IS NULL OR meeting_date <= ?4)
+             AND (?5 IS NULL OR instr(lower(COALESCE(topic, '')), ?5) > 0 OR instr(lower(content), ?5) > 0)
+           ORDER BY meeting_date DESC, created_at, id"#,
+    )
+    .bind(query.course_id)
+    .bind(query.course_meeting_id)
+    .bind(&query.start_date)
+    .bind(&query.end_date)
+    .bind(&search)
+    .fetch_all(pool)
+    .await
+    .map_err(ApiError::from)
+}
+
+pub(crate) async fn modify_note(pool: &Pool<Sqlite>, id: i64, data: LectureNoteUpdate) -> Result<LectureNote, ApiError> {
+    if let Some(ref content) = data.content {
+        validate_content(content)?;
+    }
+    let topic = normalize_topic(data.topic)?;
+    sqlx::query_as::<_, LectureNote>(
+        r#"UPDATE lecture_notes SET
+               topic = COALESCE(?, topic),
+               content = COALESCE(?, content),
+               updated_at = datetime('now')
+           WHERE id = ?
+           RETURNING *"#,
+    )
+    .bind(&topic)
+    .bind(&data.content)
+    .bind(id)
+    .fetch_optional(pool)
+    .await
+    .map_err(ApiError::from)?
+    .ok_or_else(|| ApiError::not_found("Lecture note not found"))
+}
+
+#[tauri::command]
+pub async fn add_lecture_note(state: State<'_, DbState>, data: LectureNoteInput) -> Result<LectureNote, ApiError> {
+    let pool = &state.0;
+    insert_note(pool, data).await
+}
+
+/// Notes by course, meeting, date range and/or topic, newest lecture first
+#[tauri::command]
+pub async fn get_lecture_notes(state: State<'_, DbState>, query: LectureNoteQuery) -> Result<Vec<LectureNote>, ApiError> {
+    let pool = &state.0;
+    list_notes(pool, query).await
+}
+
+#[tauri::command]
+pub async fn update_lecture_note(
+    state: State<'_, DbState>,
+    id: i64,
+    data: LectureNoteUpdate,
+) -> Result<LectureNote, ApiError> {
+    let pool = &state.0;
+    modify_note(pool, id, data).await
+}
+
+#[tauri::command]
+pub async fn delete_lecture_note(state: State<'_, DbState>, id: i64) -> Result<(), ApiError> {
+    let pool = &state.0;
+    let result = sqlx::query("DELETE FROM lecture_notes WHERE id = ?")
+        .bind(id)
+        .execute(pool)
+        .await
+        .map_err(ApiError::from)?;
+    if result.rows_affected() == 0 {
+        return Err(ApiError::not_found("Lecture note not found"));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+    use std::str::FromStr;
+
+    async fn setup_pool_with_migrations() -> Pool<Sqlite> {
+        let options = SqliteConnectOptions::from_str("sqlite::memory:")
+            .unwrap()
+            .foreign_keys(false);
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect_with(options)
+            .await
+            .expect("Failed to connect to in-memory DB");
+
+        crate::db::migrations::run_migrations(&pool)
+            .await
+            .expect("Failed to run migrations");
+
+        pool
+    }
+
+    fn note(session_id: Option<i64>, topic: &str, content: &str) -> LectureNoteInput {
+        LectureNoteInput {
+            session_id,
+            course_meeting_id: None,
+            meeting_date: None,
+            topic: Some(topic.to_string()),
+            content: content.to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn meeting_sessions_and_notes_are_linked_to_the_occurrence() {
+        let pool = setup_pool_with_migrations().await;
+        sqlx::query("INSERT INTO courses (id, name, code) VALUES (1, 'Linear Algebra', 'MATH 221')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        // Mondays
+        sqlx::query("INSERT INTO course_meetings (id, course_id, day_of_week, start_time, end_time) VALUES (1, 1, 1, '10:00', '10:50')")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let start = |date: &str| MeetingSessionInput { meeting_id: 1, date: Some(date.to_string()), focus_profile_id: None };
+        let session = start_for_meeting(&pool, start("2026-03-02")).await.unwrap();
+        assert_eq!(session.title.as_deref(), Some("MATH 221 lecture · Mon Mar 2"));
+        assert_eq!((session.reference_id, session.reference_type.as_deref()), (Some(1), Some("course")));
+        assert_eq!(session.planned_minutes, Some(50));
+        assert_eq!(session.meeting_date.as_deref(), Some("2026-03-02"));
+        // Starting again while it is open returns the same session
+        assert_eq!(start_for_meeting(&pool, start("2026-03-02")).await.unwrap().id, session.id);
+        assert!(start_for_meeting(&pool, start("2026-03-03")).await.is_err());
+
+        let first = insert_note(&pool, note(Some(session.id), " Eigenvalues ", "det(A - λI) = 0")).await.unwrap();
+        assert_eq!((first.course_id, first.course_meeting_id), (1, Some(1)));
+        assert_eq!(first.meeting_date, "2026-03-02");
+        assert_eq!(first.topic.as_deref(), Some("Eigenvalues"));
+
+        let later = LectureNoteInput {
+            course_meeting_id: Some(1),
+            meeting_date: Some("2026-03-09".to_string()),
+            ..note(None, "Diagonalization", "A = PDP^-1 when there are n independent eigenvectors")
+        };
+        insert_note(&pool, later).await.unwrap();
+        assert!(insert_note(&pool, note(None, "Orphan", "no meeting")).await.is_err());
+        assert!(insert_note(&pool, note(Some(session.id), "Empty", "  ")).await.is_err());
+
+        let by_topic = list_notes(&pool, LectureNoteQuery { search: Some("EIGEN".to_string()), ..Default::default() })
+            .await
+            .unwrap();
+        assert_eq!(by_topic.len(), 2);
+        assert_eq!(by_topic[0].meeting_date, "2026-03-09");
+        let by_date = list_notes(
+            &pool,
+            LectureNoteQuery { start_date: Some("2026-03-01".to_string()), end_date: Some("2026-03-05".to_string()), ..Default::default() },
+        )
+        .await
+        .unwrap();
+        assert_eq!(by_date.len(), 1);
+        assert_eq!(by_date[0].id, first.id);
+
+        let edited = modify_note(&pool, first.id, LectureNoteUpdate { topic: None, content: Some("Characteristic polynomial".to_string()) })
+            .await
+            .unwrap();
+        assert_eq!(edited.topic.as_deref(), Some("Eigenvalues"));
+        assert_eq!(edited.content, "Characteristic polynomial");
+    }
+}
@@ -0,0 +1,13 @@
+//! MCP commands
+
+use tauri::State;
+
+use crate::mcp::{self, McpAuditEntry};
+use crate::{error::ApiError, DbState};
+
+/// Recent MCP tool calls, newest first (default 100)
+#[tauri::command]
+pub async fn get_mcp_audit_log(state: State<'_, DbState>, limit: Option<i64>) -> Result<Vec<McpAuditEntry>, ApiError> {
+    let pool = &state.0;
+    mcp::audit_log(pool, limit.unwrap_or(100)).await
+}
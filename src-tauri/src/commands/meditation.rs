@@ -0,0 +1,53 @@
+//! Meditation commands
+
+use tauri::State;
+
+use crate::services::clock;
+use crate::services::meditation::{self, MeditationInput, MeditationStats, MeditationStyle, MeditationTimer};
+use crate::services::trackers::TrackerLog;
+use crate::{error::ApiError, DbState};
+
+/// Start the guided timer; bells arrive as `meditation://timer` events
+#[tauri::command]
+pub async fn start_meditation_timer(
+    style: MeditationStyle,
+    planned_minutes: i64,
+    interval_minutes: Option<i64>,
+) -> Result<MeditationTimer, ApiError> {
+    meditation::start_timer(style, planned_minutes, interval_minutes)
+}
+
+#[tauri::command]
+pub async fn get_meditation_timer() -> Result<Option<MeditationTimer>, ApiError> {
+    Ok(meditation::timer())
+}
+
+/// Stop the timer without logging the sit
+#[tauri::command]
+pub async fn cancel_meditation_timer() -> Result<bool, ApiError> {
+    Ok(meditation::cancel_timer())
+}
+
+/// Stop the timer and log the time sat with the calm rating
+#[tauri::command]
+pub async fn finish_meditation_timer(
+    state: State<'_, DbState>,
+    calm_rating: Option<i64>,
+    note: Option<String>,
+) -> Result<TrackerLog, ApiError> {
+    meditation::finish_timer(&state.0, calm_rating, note).await
+}
+
+#[tauri::command]
+pub async fn log_meditation(state: State<'_, DbState>, data: MeditationInput) -> Result<TrackerLog, ApiError> {
+    meditation::log_sit(&state.0, &data).await
+}
+
+#[tauri::command]
+pub async fn get_meditation_stats(state: State<'_, DbState>, days: Option<i64>) -> Result<MeditationStats, ApiError> {
+    let days = days.unwrap_or(30);
+    if !(1..=365).contains(&days) {
+        return Err(ApiError::validation("days must be between 1 and 365"));
+    }
+    meditation::stats(&state.0, days, clock::now().date_naive()).await
+}
@@ -0,0 +1,367 @@
+//! Focus by Meeting Type
+//!
+//! How well each course meeting is attended and how focused its sessions
+//! are, over the last `weeks` weeks, broken down by meeting type (lecture,
+//! lab, discussion...) and time slot. A meeting counts as attended on a date
+//! when a session was started for it (see `lecture_notes`).
+//!
+//! Each meeting is compared with self-study for its course in the same time
+//! slot: sessions on the course not tied to a meeting. A lecture whose
+//! sessions rate low while that self-study rates clearly higher is marked as
+//! a `substitute`, and the week planner suggests a self-study block in its
+//! place.
+
+use std::collections::{BTreeMap, HashMap};
+
+use chrono::{Datelike, Duration, NaiveDate};
+use serde::Serialize;
+use sqlx::{Pool, Sqlite};
+use tauri::State;
+
+use crate::{error::ApiError, services::clock, DbState};
+
+const DEFAULT_WEEKS: i64 = 12;
+const MAX_WEEKS: i64 = 52;
+/// Local hours at which the afternoon and evening slots start
+const AFTERNOON_HOUR: u32 = 12;
+const EVENING_HOUR: u32 = 17;
+/// Rated sessions needed on both sides before a meeting is judged
+const MIN_RATED_SESSIONS: i64 = 3;
+/// Average focus rating (1-5) at or below which a meeting is low value
+const LOW_VALUE_FOCUS: f64 = 2.5;
+/// How much higher self-study has to rate to stand in for a meeting
+const MIN_FOCUS_GAIN: f64 = 1.0;
+/// Meeting types self-study may stand in for; labs and the like need attending
+const SUBSTITUTABLE_TYPES: &[&str] = &["lecture"];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MeetingFocus {
+    pub meeting_id: i64,
+    pub course_id: i64,
+    pub course_name: String,
+    pub meeting_type: String,
+    /// 0 = Sunday
+    pub day_of_week: i64,
+    pub start_time: String,
+    pub end_time: String,
+    /// 'morning', 'afternoon' or 'evening'
+    pub time_slot: String,
+    /// Occurrences in the window
+    pub held: i64,
+    /// Occurrences a session was started for
+    pub attended: i64,
+    pub attendance_rate: Option<f64>,
+    /// Average focus rating (1-5) of the meeting's sessions
+    pub avg_focus: Option<f64>,
+    pub rated_sessions: i64,
+    /// Average focus rating of the course's self-study in the same time slot
+    pub self_study_focus: Option<f64>,
+    pub self_study_sessions: i64,
+    /// Self-study has historically beaten this meeting
+    pub substitute: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MeetingTypeFocus {
+    pub meeting_type: String,
+    pub time_slot: String,
+    pub meetings: i64,
+    pub held: i64,
+    pub attended: i64,
+    pub attendance_rate: Option<f64>,
+    pub avg_focus: Option<f64>,
+    pub rated_sessions: i64,
+    /// Self-study focus for the same courses and time slot
+    pub self_study_focus: Option<f64>,
+    pub self_study_sessions: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MeetingFocusReport {
+    pub weeks: i64,
+    /// By meeting type, then time slot
+    pub by_type: Vec<MeetingTypeFocus>,
+    pub meetings: Vec<MeetingFocus>,
+}
+
+/// (meeting id, course id, course name, meeting type, weekday, start, end, created on)
+type MeetingRow = (i64, i64, String, String, i64, String, String, Option<String>);
+
+/// Attendance and focus per course meeting and meeting type over the last `weeks` weeks
+#[tauri::command]
+pub async fn get_meeting_focus_stats(
+    state: State<'_, DbState>,
+    weeks: Option<i64>,
+) -> Result<MeetingFocusReport, ApiError> {
+    let pool = &state.0;
+    meeting_focus(pool, weeks.unwrap_or(DEFAULT_WEEKS)).await
+}
+
+fn time_slot(hour: u32) -> &'static str {
+    match hour {
+        h if h < AFTERNOON_HOUR => "morning",
+        h if h < EVENING_HOUR => "afternoon",
+        _ => "evening",
+    }
+}
+
+fn ratio(part: i64, whole: i64) -> Option<f64> {
+    (whole > 0).then(|| part as f64 / whole as f64)
+}
+
+pub(crate) async fn meeting_focus(pool: &Pool<Sqlite>, weeks: i64) -> Result<MeetingFocusReport, ApiError> {
+    if !(1..=MAX_WEEKS).contains(&weeks) {
+        return Err(ApiError::validation(format!("weeks must be between 1 and {}", MAX_WEEKS)));
+    }
+    // Up to yesterday; today's meetings may still be ahead
+    let end = clock::now().date_naive();
+    let start = end - Duration::weeks(weeks);
+
+    let meetings: Vec<MeetingRow> = sqlx::query_as(
+        r#"
+        SELECT cm.id, cm.course_id, c.name, COALESCE(cm.meeting_type, 'lecture'), cm.day_of_week,
+               cm.start_time, cm.end_time, date(cm.created_at)
+        FROM course_meetings cm
+        JOIN courses c ON c.id = cm.course_id
+        WHERE c.is_active = 1 AND cm.archived_at IS NULL
+        ORDER BY c.name, cm.day_of_week, cm.start_time
+        "#,
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(ApiError::from)?;
+
+    // meeting id -> (dates attended, rated sessions, rating sum)
+    let attendance: HashMap<i64, (i64, i64, i64)> = sqlx::query_as::<_, (i64, i64, i64, i64)>(
+        r#"
+        SELECT course_meeting_id, COUNT(DISTINCT meeting_date), COUNT(focus_rating), COALESCE(SUM(focus_rating), 0)
+        FROM sessions
+        WHERE course_meeting_id IS NOT NULL AND meeting_date >= ? AND meeting_date < ?
+        GROUP BY course_meeting_id
+        "#,
+    )
+    .bind(start.to_string())
+    .bind(end.to_string())
+    .fetch_all(pool)
+    .await
+    .map_err(ApiError::from)?
+    .into_iter()
+    .map(|(id, attended, rated, sum)| (id, (attended, rated, sum)))
+    .collect();
+
+    // (course id, time slot) -> (rated sessions, rating sum)
+    let self_study: HashMap<(i64, String), (i64, i64)> = sqlx::query_as::<_, (i64, String, i64, i64)>(
+        r#"
+        SELECT reference_id,
+               CASE WHEN CAST(strftime('%H', started_at, 'localtime') AS INTEGER) < ?3 THEN 'morning'
+                    WHEN CAST(strftime('%H', started_at, 'localtime') AS INTEGER) < ?4 THEN 'afternoon'
+                    ELSE 'evening' END,
+               COUNT(*), SUM(focus_rating)
+        FROM sessions
+        WHERE session_type = 'study' AND reference_type = 'course' AND reference_id IS NOT NULL
+          AND course_meeting_id IS NULL AND focus_rating IS NOT NULL
+          AND date(started_at, 'localtime') >= ?1 AND date(started_at, 'localtime') < ?2
+        GROUP BY 1, 2
+        "#,
+    )
+    .bind(start.to_string())
+    .bind(end.to_string())
+    .bind(AFTERNOON_HOUR)
+    .bind(EVENING_HOUR)
+    .fetch_all(pool)
+    .await
+    .map_err(ApiError::from)?
+    .into_iter()
+    .map(|(course, slot, rated, sum)| ((course, slot), (rated, sum)))
+    .collect();
+
+    let mut report = Vec::with_capacity(meetings.len());
+    for (meeting_id, course_id, course_name, meeting_type, day_of_week, start_time, end_time, created_on) in meetings
+    {
+        let hour = start_time.get(..2).and_then(|h| h.parse().ok()).unwrap_or(0);
+        let slot = time_slot(hour);
+        let from = created_on
+            .and_then(|d| NaiveDate::parse_from_str(&d, "%Y-%m-%d").ok())
+            .map_or(start, |d| d.max(start));
+        let held = from
+            .iter_days()
+            .take_while(|d| *d < end)
+            .filter(|d| d.weekday().num_days_from_sunday() as i64 == day_of_week)
+            .count() as i64;
+        let (attended, rated, focus_sum) = attendance.get(&meeting_id).copied().unwrap_or_default();
+        // Sessions logged before the meeting was (re)created still count as held
+        let held = held.max(attended);
+        let (study_rated, study_sum) = self_study.get(&(course_id, slot.to_string())).copied().unwrap_or_default();
+
+        let avg_focus = (rated > 0).then(|| focus_sum as f64 / rated as f64);
+        let self_study_focus = (study_rated > 0).then(|| study_sum as f64 / study_rated as f64);
+        let substitute = SUBSTITUTABLE_TYPES.contains(&meeting_type.as_str())
+            && rated >= MIN_RATED_SESSIONS
+            && study_rated >= MIN_RATED_SESSIONS
+            && matches!((avg_focus, self_study_focus),
+                (Some(m), Some(s)) if m <= LOW_VALUE_FOCUS && s >= m + MIN_FOCUS_GAIN);
+
+        report.push(MeetingFocus {
+            meeting_id,
+            course_id,
+            course_name,
+            meeting_type,
+            day_of_week,
+            start_time,
+            end_time,
+            time_slot: slot.to_string(),
+            held,
+            attended,
+            attendance_rate: ratio(attended, held),
+            avg_focus,
+            rated_sessions: rated,
+            self_study_focus,
+            self_study_sessions: study_rated,
+            substitute,
+        });
+    }
+
+    Ok(MeetingFocusReport {
+        weeks,
+        by_type: by_type(&report, &self_study),
+        meetings: report,
+    })
+}
+
+/// Sum meetings by type and time slot; self-study counts once per course
+fn by_type(meetings: &[MeetingFocus], self_study: &HashMap<(i64, String), (i64, i64)>) -> Vec<MeetingTypeFocus> {
+    #[derive(Default)]
+    struct Totals {
+        meetings: i64,
+        held: i64,
+        attended: i64,
+        rated: i64,
+        focus_sum: f64,
+        courses: Vec<i64>,
+    }
+
+    let mut groups: BTreeMap<(String, String), Totals> = BTreeMap::new();
+    for m in meetings {
+        let totals = groups.entry((m.meeting_type.clone(), m.time_slot.clone())).or_default();
+        totals.meetings += 1;
+        totals.held += m.held;
+        totals.attended += m.attended;
+        totals.rated += m.rated_sessions;
+        totals.focus_sum += m.avg_focus.unwrap_or(0.0) * m.rated_sessions as f64;
+        if !totals.courses.contains(&m.course_id) {
+            totals.courses.push(m.course_id);
+        }
+    }
+
+    groups
+        .into_iter()
+        .map(|((meeting_type, time_slot), t)| {
+            let (study_rated, study_sum) = t
+                .courses
+                .iter()
+                .filter_map(|c| self_study.get(&(*c, time_slot.clone())))
+                .fold((0, 0), |(r, s), (rated, sum)| (r + rated, s + sum));
+            MeetingTypeFocus {
+                meeting_type,
+                time_slot,
+                meetings: t.meetings,
+                held: t.held,
+                attended: t.attended,
+                attendance_rate: ratio(t.attended, t.held),
+                avg_focus: (t.rated > 0).then(|| t.focus_sum / t.rated as f64),
+                rated_sessions: t.rated,
+                self_study_focus: (study_rated > 0).then(|| study_sum as f64 / study_rated as f64),
+                self_study_sessions: study_rated,
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Local, TimeZone, Utc};
+
+    /// `hour`:00 local on 2030-01-`day`, as stored (UTC)
+    fn utc(day: u32, hour: u32) -> String {
+        Local
+            .with_ymd_and_hms(2030, 1, day, hour, 0, 0)
+            .unwrap()
+            .with_timezone(&Utc)
+            .format("%Y-%m-%d %H:%M:%S")
+            .to_string()
+    }
+
+    async fn session(pool: &Pool<Sqlite>, course: i64, meeting: Option<(i64, u32)>, day: u32, hour: u32, focus: i64) {
+        sqlx::query(
+            "INSERT INTO sessions (session_type, reference_type, reference_id, started_at, ended_at, duration_minutes, focus_rating, course_meeting_id, meeting_date) VALUES ('study', 'course', ?, ?, ?, 50, ?, ?, ?)",
+        )
+        .bind(course)
+        .bind(utc(day, hour))
+        .bind(utc(day, hour + 1))
+        .bind(focus)
+        .bind(meeting.map(|(id, _)| id))
+        .bind(meeting.map(|(_, d)| format!("2030-01-{:02}", d)))
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn low_focus_lectures_beaten_by_self_study_are_substitutes() {
+        let pool = crate::test_support::pool().await;
+        let _clock = clock::freeze(Local.with_ymd_and_hms(2030, 1, 31, 12, 0, 0).unwrap());
+        let course: i64 = sqlx::query_scalar("INSERT INTO courses (name) VALUES ('Statistics') RETURNING id")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        let meeting = |day: i64, start: &'static str, end: &'static str, kind: &'static str| {
+            sqlx::query_scalar::<_, i64>(
+                "INSERT INTO course_meetings (course_id, day_of_week, start_time, end_time, meeting_type, created_at) VALUES (?, ?, ?, ?, ?, '2029-01-01 00:00:00') RETURNING id",
+            )
+            .bind(course)
+            .bind(day)
+            .bind(start)
+            .bind(end)
+            .bind(kind)
+            .fetch_one(&pool)
+        };
+        // Mondays 09:00 and Wednesday afternoons
+        let lecture = meeting(1, "09:00", "10:00", "lecture").await.unwrap();
+        let lab = meeting(3, "14:00", "16:00", "lab").await.unwrap();
+
+        // Three of the four January Mondays before the 31st, all rated poorly
+        for day in [7, 14, 21] {
+            session(&pool, course, Some((lecture, day)), day, 9, 2).await;
+        }
+        for day in [2, 9, 16, 23, 30] {
+            session(&pool, course, Some((lab, day)), day, 14, 4).await;
+        }
+        // Morning self-study goes well; afternoon self-study doesn't count for the lecture
+        for day in [8, 15, 22] {
+            session(&pool, course, None, day, 10, 4).await;
+        }
+        session(&pool, course, None, 24, 15, 1).await;
+
+        let report = meeting_focus(&pool, 4).await.unwrap();
+        let lecture = report.meetings.iter().find(|m| m.meeting_id == lecture).unwrap();
+        assert_eq!((lecture.held, lecture.attended, lecture.time_slot.as_str()), (4, 3, "morning"));
+        assert_eq!(lecture.avg_focus, Some(2.0));
+        assert_eq!((lecture.self_study_focus, lecture.self_study_sessions), (Some(4.0), 3));
+        assert!(lecture.substitute);
+
+        let lab = report.meetings.iter().find(|m| m.meeting_id == lab).unwrap();
+        assert_eq!(lab.attendance_rate, Some(1.0));
+        assert!(!lab.substitute);
+
+        let kinds: Vec<(&str, &str)> = report
+            .by_type
+            .iter()
+            .map(|t| (t.meeting_type.as_str(), t.time_slot.as_str()))
+            .collect();
+        assert_eq!(kinds, vec![("lab", "afternoon"), ("lecture", "morning")]);
+        assert_eq!(report.by_type[0].self_study_focus, Some(1.0));
+
+        assert!(meeting_focus(&pool, 0).await.is_err());
+    }
+}
@@ -1,21 +1,72 @@
 pub mod courses;
+pub mod course_workload;
 pub mod course_meetings;
 pub mod calendar_events;
 pub mod calendar;
+pub mod calendar_feed;
+pub mod categories;
+pub mod free_slots;
 pub mod weekly_tasks;
 pub mod week_plan_blocks;
 pub mod assignments;
+pub mod assignment_checklist;
 pub mod sessions;
+pub mod session_types;
+pub mod lecture_notes;
+pub mod techniques;
 pub mod skills;
 pub mod practice;
+pub mod skill_portfolio;
 pub mod workouts;
 pub mod workout_exercises;
+pub mod workout_planning;
 pub mod workout_templates;
 pub mod exercises;
 pub mod checkins;
 pub mod weekly_reviews;
 pub mod analytics;
+pub mod gamification;
+pub mod challenges;
+pub mod trackers;
+pub mod meditation;
+pub mod reading;
+pub mod language_learning;
+pub mod spending;
+pub mod cycle;
+pub mod social;
 pub mod debug;
+pub mod sample_data;
+pub mod onboarding;
+pub mod settings;
+pub mod capabilities;
+pub mod quick_actions;
+pub mod glance;
+pub mod focus;
+pub mod energy;
+pub mod export;
+pub mod deadline_share;
+pub mod http_api;
+pub mod mcp;
+pub mod grades;
+pub mod exam_readiness;
+pub mod efficiency;
+pub mod meeting_focus;
+pub mod capacity;
+pub mod terms;
+pub mod semester_review;
+pub mod custom_metrics;
+pub mod plan_realism;
+pub mod procrastination;
+pub mod planner_mode;
+pub mod next_task;
+pub mod shutdown;
+pub mod briefing;
+pub mod course_digest;
+pub mod accountability;
+pub mod telemetry;
+pub mod focus_profiles;
+pub mod attachments;
 pub mod intelligence;
 pub mod exams;
 pub mod google_calendar;
+pub mod change_log;
@@ -0,0 +1,339 @@
+//! Next Best Task ("what now")
+//!
+//! Merges the sources that each answer "what should I do next" into one
+//! ranked list: the plan block running right now, today's unfinished Big
+//! Three, open assignments (priority, urgency and calibrated effort) and the
+//! agent's recommendation. Every entry carries the reasons it ranked where it
+//! did, and its suggested minutes are capped by the time available.
+
+use chrono::{Local, Utc};
+use serde::Serialize;
+use sqlx::{Pool, Sqlite};
+use tauri::State;
+
+use crate::agent::{AgentRecommendation, IntelligenceAgent};
+use crate::services::estimates;
+use crate::utils::parse_datetime_utc;
+use crate::{error::ApiError, DbState};
+
+const MAX_AVAILABLE_MINUTES: i64 = 24 * 60;
+const MAX_TASKS: usize = 10;
+/// Assignments due further out are left to the planner
+const ASSIGNMENT_HORIZON_DAYS: i64 = 14;
+/// Smallest chunk worth suggesting for a task that doesn't fit
+const MIN_CHUNK_MINUTES: i64 = 15;
+/// Assumed effort of an assignment without an estimate
+const DEFAULT_ASSIGNMENT_MINUTES: f64 = 60.0;
+
+type BlockRow = (i64, String, String, String, Option<i64>, Option<String>, Option<String>);
+type AssignmentRow = (i64, i64, String, String, Option<String>, Option<i64>, i64);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum NextTaskKind {
+    PlanBlock,
+    BigThree,
+    Assignment,
+    Recommendation,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct NextTask {
+    pub kind: NextTaskKind,
+    /// Plan block, Big Three goal, assignment or recommendation id
+    pub reference_id: Option<i64>,
+    pub title: String,
+    pub course_id: Option<i64>,
+    /// 0-100, higher first
+    pub score: f64,
+    /// Time to spend now, within the available minutes
+    pub suggested_minutes: Option<i64>,
+    /// Whether the whole task fits in the available time
+    pub fits: bool,
+    pub reasons: Vec<String>,
+}
+
+/// Ranked next actions for the next `available_minutes`
+#[tauri::command]
+pub async fn get_next_best_task(
+    state: State<'_, DbState>,
+    available_minutes: i64,
+) -> Result<Vec<NextTask>, ApiError> {
+    let pool = &state.0;
+
+    // The agent is one input among several; don't fail the list without it
+    let recommendation = match IntelligenceAgent::get_recommendation(pool).await {
+        Ok(r) if !r.is_shadow => Some(r),
+        Ok(_) => None,
+        Err(e) => {
+            log::warn!("No agent recommendation for next task: {}", e);
+            None
+        }
+    };
+
+    next_best_tasks(pool, available_minutes, recommendation.as_ref()).await
+}
+
+pub(crate) async fn next_best_tasks(
+    pool: &Pool<Sqlite>,
+    available_minutes: i64,
+    recommendation: Option<&AgentRecommendation>,
+) -> Result<Vec<NextTask>, ApiError> {
+    if !(1..=MAX_AVAILABLE_MINUTES).contains(&available_minutes) {
+        return Err(ApiError::validation(format!(
+            "available_minutes must be between 1 and {}",
+            MAX_AVAILABLE_MINUTES
+        )));
+    }
+
+    let mut tasks = Vec::new();
+    tasks.extend(current_plan_blocks(pool, available_minutes).await?);
+    tasks.extend(big_three(pool).await?);
+    tasks.extend(assignments(pool, available_minutes).await?);
+    if let Some(rec) = recommendation {
+        tasks.push(NextTask {
+            kind: NextTaskKind::Recommendation,
+            reference_id: rec.recommendation_id,
+            title: rec.action.description.clone(),
+            course_id: None,
+            score: 40.0 + 30.0 * rec.expected_reward.clamp(0.0, 1.0) as f64,
+            suggested_minutes: None,
+            fits: true,
+            reasons: vec![
+                format!("Agent suggestion ({} confidence)", rec.confidence_level),
+                rec.explanation.clone(),
+            ],
+        });
+    }
+
+    tasks.sort_by(|a, b| b.score.total_cmp(&a.score));
+    tasks.truncate(MAX_TASKS);
+    Ok(tasks)
+}
+
+/// Accepted or locked blocks that are running now
+async fn current_plan_blocks(pool: &Pool<Sqlite>, available_minutes: i64) -> Result<Vec<NextTask>, ApiError> {
+    let rows: Vec<BlockRow> = sqlx::query_as(
+        r#"
+        SELECT wpb.id, wpb.start_at, wpb.end_at, wpb.block_type, wpb.course_id, wpb.title, c.name
+        FROM week_plan_blocks wpb
+        LEFT JOIN courses c ON c.id = wpb.course_id
+        WHERE wpb.status IN ('accepted', 'locked') AND wpb.block_type <> 'break'
+          AND date(wpb.start_at) BETWEEN date('now', 'localtime', '-1 day') AND date('now', 'localtime')
+        "#,
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(ApiError::from)?;
+
+    let now = Utc::now();
+    Ok(rows
+        .into_iter()
+        .filter_map(|(id, start_at, end_at, block_type, course_id, title, course_name)| {
+            let start = parse_datetime_utc(&start_at)?;
+            let end = parse_datetime_utc(&end_at)?;
+            if !(start <= now && now < end) {
+                return None;
+            }
+            let remaining = (end - now).num_minutes().max(1);
+            let title = title
+                .or_else(|| course_name.map(|c| format!("{} ({})", c, block_type.replace('_', " "))))
+                .unwrap_or_else(|| block_type.replace('_', " "));
+            Some(NextTask {
+                kind: NextTaskKind::PlanBlock,
+                reference_id: Some(id),
+                title,
+                course_id,
+                score: 100.0,
+                suggested_minutes: Some(remaining.min(available_minutes)),
+                fits: remaining <= available_minutes,
+                reasons: vec![format!(
+                    "Planned for now, until {}",
+                    end.with_timezone(&Local).format("%H:%M")
+                )],
+            })
+        })
+        .collect())
+}
+
+/// Today's unfinished Big Three, in their order
+async fn big_three(pool: &Pool<Sqlite>) -> Result<Vec<NextTask>, ApiError> {
+    let goals = IntelligenceAgent::get_big_three(pool).await.map_err(ApiError::internal)?;
+    Ok(goals
+        .into_iter()
+        .filter(|g| !g.is_completed)
+        .map(|g| NextTask {
+            kind: NextTaskKind::BigThree,
+            reference_id: Some(g.id),
+            title: g.title,
+            course_id: None,
+            score: 85.0 - 5.0 * (g.priority - 1) as f64,
+            suggested_minutes: None,
+            fits: true,
+            reasons: vec![format!("Big Three #{} for today", g.priority)],
+        })
+        .collect())
+}
+
+/// Open assignments due soon, scored by priority and urgency
+async fn assignments(pool: &Pool<Sqlite>, available_minutes: i64) -> Result<Vec<NextTask>, ApiError> {
+    let rows: Vec<AssignmentRow> = sqlx::query_as(
+        r#"
+        SELECT a.id, a.course_id, a.title, c.name, a.priority, a.estimated_minutes,
+               CAST(julianday(date(a.due_date)) - julianday(date('now', 'localtime')) AS INTEGER)
+        FROM assignments a
+        JOIN courses c ON c.id = a.course_id
+        WHERE a.is_completed = 0 AND c.is_active = 1 AND a.due_date IS NOT NULL
+          AND date(a.due_date) <= date('now', 'localtime', ?)
+        "#,
+    )
+    .bind(format!("+{} days", ASSIGNMENT_HORIZON_DAYS))
+    .fetch_all(pool)
+    .await
+    .map_err(ApiError::from)?;
+
+    let ratios = estimates::ratios(pool).await?;
+
+    Ok(rows
+        .into_iter()
+        .map(|(id, course_id, title, course_name, priority, estimate, days_left)| {
+            let priority = priority.as_deref().unwrap_or("medium");
+            let priority_weight = match priority {
+                "high" => 1.0,
+                "low" => 0.3,
+                _ => 0.6,
+            };
+            // 1 when due today or overdue, halving with each extra day or so
+            let urgency = 1.0 / (1.0 + days_left.max(0) as f64);
+            let ratio = ratios.get(&course_id).copied().unwrap_or(1.0);
+            let needed = (estimate.map(|m| m as f64).unwrap_or(DEFAULT_ASSIGNMENT_MINUTES) * ratio).round() as i64;
+            let fits = needed <= available_minutes;
+
+            let mut reasons = vec![match days_left {
+                d if d < 0 => format!("Overdue by {} day{}", -d, if d == -1 { "" } else { "s" }),
+                0 => "Due today".to_string(),
+                1 => "Due tomorrow".to_string(),
+                d => format!("Due in {} days", d),
+            }];
+            reasons.push(format!("{} priority, {}", capitalize(priority), course_name));
+            if (ratio - 1.0).abs() >= 0.1 && estimate.is_some() {
+                reasons.push(format!(
+                    "{} work usually takes {:.1}x the estimate: about {} min",
+                    course_name, ratio, needed
+                ));
+            }
+            if !fits {
+                reasons.push(format!("Needs about {} min; make a start now", needed));
+            }
+
+            let mut score = 20.0 + 35.0 * urgency + 25.0 * priority_weight;
+            if !fits {
+                score *= 0.85;
+            }
+            NextTask {
+                kind: NextTaskKind::Assignment,
+                reference_id: Some(id),
+                title,
+                course_id: Some(course_id),
+                score,
+                suggested_minutes: Some(needed.min(available_minutes).max(MIN_CHUNK_MINUTES.min(available_minutes))),
+                fits,
+                reasons,
+            }
+        })
+        .collect())
+}
+
+fn capitalize(s: &str) -> String {
+    let mut chars = s.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+    use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+    use std::str::FromStr;
+
+    async fn setup_pool_with_migrations() -> Pool<Sqlite> {
+        let options = SqliteConnectOptions::from_str("sqlite::memory:")
+            .unwrap()
+            .foreign_keys(false);
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect_with(options)
+            .await
+            .expect("Failed to connect to in-memory DB");
+
+        crate::db::migrations::run_migrations(&pool)
+            .await
+            .expect("Failed to run migrations");
+
+        pool
+    }
+
+    #[tokio::test]
+    async fn ranks_current_block_then_big_three_then_assignments() {
+        let pool = setup_pool_with_migrations().await;
+        let course: i64 = sqlx::query_scalar("INSERT INTO courses (name) VALUES ('Chemistry') RETURNING id")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+
+        let now = Utc::now();
+        sqlx::query(
+            "INSERT INTO week_plan_blocks (week_start_date, start_at, end_at, block_type, course_id, status) VALUES (date('now'), ?, ?, 'study', ?, 'accepted')",
+        )
+        .bind((now - Duration::minutes(20)).to_rfc3339())
+        .bind((now + Duration::minutes(40)).to_rfc3339())
+        .bind(course)
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO agent_big_three (date, priority, title) VALUES (date('now', 'localtime'), 1, 'Call advisor'), (date('now', 'localtime'), 2, 'Gym')",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO assignments (course_id, title, due_date, priority, estimated_minutes) VALUES
+             (?, 'Lab report', date('now', 'localtime', '+1 day'), 'high', 120),
+             (?, 'Reading', date('now', 'localtime', '+10 days'), 'low', 20),
+             (?, 'Far future', date('now', 'localtime', '+40 days'), 'high', 30)",
+        )
+        .bind(course)
+        .bind(course)
+        .bind(course)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let tasks = next_best_tasks(&pool, 45, None).await.unwrap();
+        let kinds: Vec<NextTaskKind> = tasks.iter().map(|t| t.kind).collect();
+        assert_eq!(
+            kinds,
+            vec![
+                NextTaskKind::PlanBlock,
+                NextTaskKind::BigThree,
+                NextTaskKind::BigThree,
+                NextTaskKind::Assignment,
+                NextTaskKind::Assignment,
+            ]
+        );
+        assert!(tasks[0].suggested_minutes.unwrap() <= 40);
+
+        let lab = &tasks[3];
+        assert_eq!(lab.title, "Lab report");
+        assert!(!lab.fits);
+        assert_eq!(lab.suggested_minutes, Some(45));
+        assert_eq!(lab.reasons[0], "Due tomorrow");
+        assert!(tasks[4].fits);
+
+        assert!(next_best_tasks(&pool, 0, None).await.is_err());
+    }
+}
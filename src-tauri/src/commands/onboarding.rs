@@ -0,0 +1,399 @@
+//! Onboarding wizard backend
+//!
+//! Records completed wizard steps, applies the initial settings, optionally
+//! creates the first term and courses, and reports where the user left off.
+
+use serde::{Deserialize, Serialize};
+use sqlx::{Pool, Sqlite};
+use tauri::State;
+
+use crate::commands::course_workload;
+use crate::commands::courses::{validate_course_input, CourseInput};
+use crate::services::settings;
+use crate::{error::ApiError, DbState};
+
+/// Wizard steps, in order
+pub const ONBOARDING_STEPS: &[&str] = &["welcome", "preferences", "courses", "integrations", "finish"];
+
+const MAX_TERM_NAME_LENGTH: usize = 100;
+
+#[derive(Debug, Serialize)]
+pub struct OnboardingStatus {
+    pub completed_steps: Vec<String>,
+    /// First step not yet completed (None once finished)
+    pub next_step: Option<String>,
+    pub is_complete: bool,
+    pub completed_at: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OnboardingSettingsInput {
+    pub week_start_day: Option<String>,
+    pub weekly_workout_target: Option<i64>,
+    pub weekly_active_skills_target: Option<i64>,
+    /// IANA timezone name, e.g. "America/Chicago"
+    pub timezone: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TermInput {
+    pub name: String,
+    pub start_date: Option<String>,
+    pub end_date: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct OnboardingCoursesInput {
+    #[serde(default)]
+    pub term: Option<TermInput>,
+    #[serde(default)]
+    pub courses: Vec<CourseInput>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct OnboardingCoursesResult {
+    pub term_id: Option<i64>,
+    pub course_ids: Vec<i64>,
+}
+
+#[tauri::command]
+pub async fn get_onboarding_status(state: State<'_, DbState>) -> Result<OnboardingStatus, ApiError> {
+    let pool = &state.0;
+    onboarding_status(pool).await
+}
+
+/// Mark a wizard step as completed; completing "finish" ends onboarding
+#[tauri::command]
+pub async fn complete_onboarding_step(
+    state: State<'_, DbState>,
+    step: String,
+) -> Result<OnboardingStatus, ApiError> {
+    let pool = &state.0;
+    validate_step(&step)?;
+    mark_step(pool, &step).await?;
+    onboarding_status(pool).await
+}
+
+/// Apply the initial settings and complete the "preferences" step
+#[tauri::command]
+pub async fn apply_onboarding_settings(
+    state: State<'_, DbState>,
+    data: OnboardingSettingsInput,
+) -> Result<OnboardingStatus, ApiError> {
+    let pool = &state.0;
+    apply_settings(pool, &data).await?;
+    mark_step(pool, "preferences").await?;
+    onboarding_status(pool).await
+}
+
+/// Create the first term and courses and complete the "courses" step
+#[tauri::command]
+pub async fn create_onboarding_courses(
+    state: State<'_, DbState>,
+    data: OnboardingCoursesInput,
+) -> Result<OnboardingCoursesResult, ApiError> {
+    let pool = &state.0;
+    let result = create_courses(pool, data).await?;
+    mark_step(pool, "courses").await?;
+    Ok(result)
+}
+
+/// Forget progress so the wizard can be run again
+#[tauri::command]
+pub async fn reset_onboarding(state: State<'_, DbState>) -> Result<OnboardingStatus, ApiError> {
+    let pool = &state.0;
+    sqlx::query("DELETE FROM onboarding_steps")
+        .execute(pool)
+        .await
+        .map_err(ApiError::from)?;
+    settings::set(pool, "onboarding_completed_at", serde_json::Value::Null).await?;
+    onboarding_status(pool).await
+}
+
+fn validate_step(step: &str) -> Result<(), ApiError> {
+    if !ONBOARDING_STEPS.contains(&step) {
+        return Err(ApiError::validation(format!(
+            "Invalid step '{}'. Must be one of: {:?}",
+            step, ONBOARDING_STEPS
+        )));
+    }
+    Ok(())
+}
+
+/// Validated setting values for the fields that were provided
+fn validate_settings(data: &OnboardingSettingsInput) -> Result<Vec<(&'static str, serde_json::Value)>, ApiError> {
+    let fields = [
+        ("week_start_day", data.week_start_day.clone().map(serde_json::Value::from)),
+        ("weekly_workout_target", data.weekly_workout_target.map(serde_json::Value::from)),
+        ("weekly_active_skills_target", data.weekly_active_skills_target.map(serde_json::Value::from)),
+        ("timezone", data.timezone.clone().map(serde_json::Value::from)),
+    ];
+
+    fields
+        .into_iter()
+        .filter_map(|(key, value)| value.map(|v| (key, v)))
+        .map(|(key, value)| Ok((key, settings::definition(key)?.validate(value)?)))
+        .collect()
+}
+
+pub(crate) fn validate_term(term: &TermInput) -> Result<(), ApiError> {
+    let name = term.name.trim();
+    if name.is_empty() || name.len() > MAX_TERM_NAME_LENGTH {
+        return Err(ApiError::validation(format!(
+            "Term name must be between 1 and {} characters",
+            MAX_TERM_NAME_LENGTH
+        )));
+    }
+    let parse = |d: &Option<String>| -> Result<Option<chrono::NaiveDate>, ApiError> {
+        d.as_deref()
+            .map(|s| {
+                chrono::NaiveDate::parse_from_str(s, "%Y-%m-%d")
+                    .map_err(|_| ApiError::validation(format!("Invalid date '{}' (expected YYYY-MM-DD)", s)))
+            })
+            .transpose()
+    };
+    if let (Some(start), Some(end)) = (parse(&term.start_date)?, parse(&term.end_date)?) {
+        if start > end {
+            return Err(ApiError::validation("Term start date must be before its end date"));
+        }
+    }
+    Ok(())
+}
+
+async fn onboarding_status(pool: &Pool<Sqlite>) -> Result<OnboardingStatus, ApiError> {
+    let completed: Vec<String> = sqlx::query_scalar("SELECT step FROM onboarding_steps")
+        .fetch_all(pool)
+        .await
+        .map_err(ApiError::from)?;
+    let completed_at = settings::get_string(pool, "onboarding_completed_at").await?;
+
+    // Report steps in wizard order
+    let completed_steps: Vec<String> = ONBOARDING_STEPS
+        .iter()
+        .filter(|s| completed.iter().any(|c| c == *s))
+        .map(|s| s.to_string())
+        .collect();
+    let is_complete = completed_at.is_some();
+    let next_step = if is_complete {
+        None
+    } else {
+        ONBOARDING_STEPS
+            .iter()
+            .find(|s| !completed_steps.iter().any(|c| c == *s))
+            .map(|s| s.to_string())
+    };
+
+    Ok(OnboardingStatus {
+        completed_steps,
+        next_step,
+        is_complete,
+        completed_at,
+    })
+}
+
+async fn mark_step(pool: &Pool<Sqlite>, step: &str) -> Result<(), ApiError> {
+    sqlx::query("INSERT OR IGNORE INTO onboarding_steps (step) VALUES (?)")
+        .bind(step)
+        .execute(pool)
+        .await
+        .map_err(ApiError::from)?;
+
+    if step == "finish" && settings::get_string(pool, "onboarding_completed_at").await?.is_none() {
+        let now = chrono::Utc::now().format("%Y-%m-%d %H:%M:%S").to_string();
+        settings::set(pool, "onboarding_completed_at", now.into()).await?;
+    }
+
+    Ok(())
+}
+
+async fn apply_settings(pool: &Pool<Sqlite>, data: &OnboardingSettingsInput) -> Result<(), ApiError> {
+    // Validate everything before writing anything
+    for (key, value) in validate_settings(data)? {
+        settings::set(pool, key, value).await?;
+    }
+    Ok(())
+}
+
+async fn create_courses(
+    pool: &Pool<Sqlite>,
+    data: OnboardingCoursesInput,
+) -> Result<OnboardingCoursesResult, ApiError> {
+    if let Some(ref term) = data.term {
+        validate_term(term)?;
+    }
+    for course in &data.courses {
+        validate_course_input(course)?;
+        if course.name.as_deref().map(str::trim).unwrap_or("").is_empty() {
+            return Err(ApiError::validation("Every course needs a name"));
+        }
+    }
+
+    let templates = course_workload::load(pool).await?;
+    let mut tx = pool.begin().await.map_err(ApiError::from)?;
+
+    let term_id = match data.term {
+        Some(term) => {
+            // The new term becomes the current one
+            sqlx::query("UPDATE terms SET is_current = 0 WHERE user_id = 1")
+                .execute(&mut *tx)
+                .await
+                .map_err(ApiError::from)?;
+            let id: i64 = sqlx::query_scalar(
+                "INSERT INTO terms (user_id, name, start_date, end_date, is_current) VALUES (1, ?, ?, ?, 1) RETURNING id",
+            )
+            .bind(term.name.trim())
+            .bind(&term.start_date)
+            .bind(&term.end_date)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(ApiError::from)?;
+            Some(id)
+        }
+        None => None,
+    };
+
+    let mut course_ids = Vec::with_capacity(data.courses.len());
+    let mut patterns = Vec::new();
+    for course in data.courses {
+        let credit_hours = course.credit_hours.unwrap_or(3);
+        let id: i64 = sqlx::query_scalar(
+            "INSERT INTO courses (user_id, name, code, color, credit_hours, target_weekly_hours, is_active, current_grade, target_grade, term_id)
+             VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+             RETURNING id",
+        )
+        .bind(course.user_id.unwrap_or(1))
+        .bind(course.name.as_deref().map(str::trim))
+        .bind(&course.code)
+        .bind(course.color.unwrap_or_else(|| "#3b82f6".to_string()))
+        .bind(credit_hours)
+        .bind(
+            course
+                .target_weekly_hours
+                .unwrap_or_else(|| course_workload::suggested_weekly_hours(&templates, credit_hours)),
+        )
+        .bind(course.is_active.unwrap_or(1))
+        .bind(course.current_grade)
+        .bind(course.target_grade.unwrap_or(90.0))
+        .bind(term_id)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(|e| ApiError::from_sqlx(e, "Failed to create course"))?;
+        course_ids.push(id);
+        if course.apply_meeting_pattern.unwrap_or(false) {
+            patterns.push((id, credit_hours));
+        }
+    }
+
+    tx.commit().await.map_err(ApiError::from)?;
+
+    // Placed after commit so each course sees the meetings of the ones before it
+    for (course_id, credit_hours) in patterns {
+        course_workload::apply_meeting_pattern(pool, course_id, credit_hours).await?;
+    }
+
+    log::info!("Onboarding created term {:?} and {} courses", term_id, course_ids.len());
+    Ok(OnboardingCoursesResult { term_id, course_ids })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+    use std::str::FromStr;
+
+    async fn setup_pool_with_migrations() -> Pool<Sqlite> {
+        let options = SqliteConnectOptions::from_str("sqlite::memory:")
+            .unwrap()
+            .foreign_keys(false);
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect_with(options)
+            .await
+            .expect("Failed to connect to in-memory DB");
+
+        crate::db::migrations::run_migrations(&pool)
+            .await
+            .expect("Failed to run migrations");
+
+        pool
+    }
+
+    #[tokio::test]
+    async fn onboarding_resumes_at_next_step() {
+        let pool = setup_pool_with_migrations().await;
+
+        let status = onboarding_status(&pool).await.unwrap();
+        assert!(!status.is_complete);
+        assert_eq!(status.next_step.as_deref(), Some("welcome"));
+
+        mark_step(&pool, "welcome").await.unwrap();
+        apply_settings(
+            &pool,
+            &OnboardingSettingsInput {
+                week_start_day: Some("sunday".into()),
+                weekly_workout_target: Some(4),
+                weekly_active_skills_target: None,
+                timezone: Some("America/Chicago".into()),
+            },
+        )
+        .await
+        .unwrap();
+        mark_step(&pool, "preferences").await.unwrap();
+
+        let status = onboarding_status(&pool).await.unwrap();
+        assert_eq!(status.completed_steps, vec!["welcome", "preferences"]);
+        assert_eq!(status.next_step.as_deref(), Some("courses"));
+
+        let week_start = settings::get_string(&pool, "week_start_day").await.unwrap();
+        let workouts = settings::get_i64(&pool, "weekly_workout_target").await.unwrap();
+        let skills = settings::get_i64(&pool, "weekly_active_skills_target").await.unwrap();
+        assert_eq!((week_start.as_deref(), workouts, skills), (Some("sunday"), 4, 5));
+
+        mark_step(&pool, "finish").await.unwrap();
+        let status = onboarding_status(&pool).await.unwrap();
+        assert!(status.is_complete);
+        assert_eq!(status.next_step, None);
+    }
+
+    #[tokio::test]
+    async fn onboarding_creates_term_and_courses() {
+        let pool = setup_pool_with_migrations().await;
+
+        let input: OnboardingCoursesInput = serde_json::from_value(serde_json::json!({
+            "term": { "name": "Fall 2026", "start_date": "2026-08-24", "end_date": "2026-12-11" },
+            "courses": [
+                { "name": "Linear Algebra", "code": "MATH 221" },
+                { "name": "Organic Chemistry", "credit_hours": 4 }
+            ]
+        }))
+        .unwrap();
+        let result = create_courses(&pool, input).await.unwrap();
+        assert_eq!(result.course_ids.len(), 2);
+
+        let linked: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM courses WHERE term_id = ?")
+            .bind(result.term_id.unwrap())
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(linked, 2);
+
+        let bad_term: OnboardingCoursesInput = serde_json::from_value(serde_json::json!({
+            "term": { "name": "Backwards", "start_date": "2026-12-11", "end_date": "2026-08-24" }
+        }))
+        .unwrap();
+        assert!(create_courses(&pool, bad_term).await.is_err());
+    }
+
+    #[test]
+    fn test_settings_validation() {
+        let input = |week_start: &str, tz: &str| OnboardingSettingsInput {
+            week_start_day: Some(week_start.into()),
+            weekly_workout_target: None,
+            weekly_active_skills_target: None,
+            timezone: Some(tz.into()),
+        };
+        assert!(validate_settings(&input("monday", "Europe/Berlin")).is_ok());
+        assert!(validate_settings(&input("friday", "Europe/Berlin")).is_err());
+        assert!(validate_settings(&input("monday", "Robert'); DROP TABLE")).is_err());
+    }
+}
@@ -0,0 +1,390 @@
+//! Plan Realism
+//!
+//! Measures how many accepted or locked study blocks actually turned into
+//! deep work: a study session for the block's course that starts around the
+//! block and holds at least `MIN_FOCUSED_MINUTES` focused minutes (focus-mode
+//! samples when present, otherwise the session length). A session started
+//! from a block belongs to that block whenever it starts. The realized share,
+//! smoothed for short histories, is the plan realism score; `plan_scale`
+//! tells the auto-scheduler how much of its usual plan to propose.
+//!
+//! Blocks worked through `start_session_from_block` also record how long the
+//! session actually ran, reported as adherence: actual vs planned minutes.
+
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use sqlx::{Pool, Sqlite};
+use tauri::State;
+
+use crate::{error::ApiError, utils::parse_datetime_utc, DbState};
+
+const DEFAULT_WEEKS: i64 = 8;
+const MAX_WEEKS: i64 = 52;
+/// Focused minutes for a block to count as deep work
+const MIN_FOCUSED_MINUTES: f64 = 25.0;
+/// How early a session may start and still belong to a block
+const EARLY_START_MINUTES: i64 = 30;
+/// Blocks assumed realized before any history, so a few misses don't gut the plan
+const PRIOR_BLOCKS: f64 = 2.0;
+/// Never shrink the plan below this share
+const MIN_PLAN_SCALE: f64 = 0.5;
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct RealizationStats {
+    pub planned_blocks: i64,
+    pub realized_blocks: i64,
+    pub planned_hours: f64,
+    /// Focused hours in the sessions that realized blocks
+    pub realized_hours: f64,
+    /// realized / planned blocks; None without planned blocks
+    pub ratio: Option<f64>,
+}
+
+/// Blocks whose session was started from the block and has ended
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct BlockAdherence {
+    pub blocks: i64,
+    pub planned_hours: f64,
+    pub actual_hours: f64,
+    /// actual / planned hours; None without such blocks
+    pub ratio: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CourseRealization {
+    pub course_id: Option<i64>,
+    pub course_name: Option<String>,
+    #[serde(flatten)]
+    pub stats: RealizationStats,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WeekRealization {
+    pub week_start: String,
+    #[serde(flatten)]
+    pub stats: RealizationStats,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PlanRealismReport {
+    pub weeks: i64,
+    #[serde(flatten)]
+    pub overall: RealizationStats,
+    pub by_course: Vec<CourseRealization>,
+    /// Oldest first
+    pub by_week: Vec<WeekRealization>,
+    /// Actual vs planned length of sessions started from blocks
+    pub adherence: BlockAdherence,
+    /// Smoothed share of planned blocks that became deep work (0-1)
+    pub realism_score: f64,
+    /// Share of the usual plan the auto-scheduler should propose
+    pub plan_scale: f64,
+}
+
+impl RealizationStats {
+    fn add(&mut self, hours: f64, realized: Option<f64>) {
+        self.planned_blocks += 1;
+        self.planned_hours += hours;
+        if let Some(focused) = realized {
+            self.realized_blocks += 1;
+            self.realized_hours += focused;
+        }
+        self.ratio = Some(self.realized_blocks as f64 / self.planned_blocks as f64);
+    }
+}
+
+/// (id, week_start_date, start_at, end_at, course_id, course name)
+type BlockRow = (i64, String, String, String, Option<i64>, Option<String>);
+/// (course_id, week_plan_block_id, started_at, duration_minutes, focused seconds, focus samples)
+type SessionRow = (Option<i64>, Option<i64>, String, Option<i64>, Option<i64>, i64);
+
+struct Block {
+    id: i64,
+    course_id: Option<i64>,
+    course_name: Option<String>,
+    week_start: String,
+    start: DateTime<Utc>,
+    end: DateTime<Utc>,
+}
+
+struct StudySession {
+    course_id: Option<i64>,
+    /// Block the session was started from
+    block_id: Option<i64>,
+    start: DateTime<Utc>,
+    focused_minutes: f64,
+    used: bool,
+}
+
+/// Planned vs realized study blocks over the last `weeks` weeks (default 8)
+#[tauri::command]
+pub async fn get_plan_realism(state: State<'_, DbState>, weeks: Option<i64>) -> Result<PlanRealismReport, ApiError> {
+    let pool = &state.0;
+    plan_realism(pool, weeks.unwrap_or(DEFAULT_WEEKS)).await
+}
+
+pub(crate) async fn plan_realism(pool: &Pool<Sqlite>, weeks: i64) -> Result<PlanRealismReport, ApiError> {
+    if !(1..=MAX_WEEKS).contains(&weeks) {
+        return Err(ApiError::validation(format!("weeks must be between 1 and {}", MAX_WEEKS)));
+    }
+    let since = format!("-{} days", weeks * 7);
+    let now = Utc::now();
+
+    let block_rows: Vec<BlockRow> = sqlx::query_as(
+        r#"
+        SELECT wpb.id, wpb.week_start_date, wpb.start_at, wpb.end_at, wpb.course_id, c.name
+        FROM week_plan_blocks wpb
+        LEFT JOIN courses c ON c.id = wpb.course_id
+        WHERE wpb.block_type IN ('study', 'exam_prep')
+          AND wpb.status IN ('accepted', 'locked')
+          AND date(wpb.start_at) >= date('now', ?)
+        ORDER BY wpb.start_at
+        "#,
+    )
+    .bind(&since)
+    .fetch_all(pool)
+    .await
+    .map_err(ApiError::from)?;
+
+    // Only blocks that are over can have been realized
+    let blocks: Vec<Block> = block_rows
+        .into_iter()
+        .filter_map(|(id, week_start, start_at, end_at, course_id, course_name)| {
+            Some(Block {
+                id,
+                course_id,
+                course_name,
+                week_start,
+                start: parse_datetime_utc(&start_at)?,
+                end: parse_datetime_utc(&end_at)?,
+            })
+        })
+        .filter(|b| b.end <= now && b.end > b.start)
+        .collect();
+
+    let session_rows: Vec<SessionRow> = sqlx::query_as(
+        r#"
+        SELECT CASE WHEN s.reference_type = 'course' THEN s.reference_id END,
+               s.week_plan_block_id, s.started_at, s.duration_minutes,
+               (SELECT SUM(f.seconds) FROM focus_samples f WHERE f.session_id = s.id AND f.is_distraction = 0),
+               (SELECT COUNT(*) FROM focus_samples f WHERE f.session_id = s.id)
+        FROM sessions s
+        WHERE s.session_type = 'study' AND s.started_at >= datetime('now', ?, '-1 day')
+        ORDER BY s.started_at
+        "#,
+    )
+    .bind(&since)
+    .fetch_all(pool)
+    .await
+    .map_err(ApiError::from)?;
+
+    let mut sessions: Vec<StudySession> = session_rows
+        .into_iter()
+        .filter_map(|(course_id, block_id, started_at, duration, focused_seconds, samples)| {
+            let focused_minutes = if samples > 0 {
+                focused_seconds.unwrap_or(0) as f64 / 60.0
+            } else {
+                duration.unwrap_or(0) as f64
+            };
+            Some(StudySession {
+                course_id,
+                block_id,
+                start: parse_datetime_utc(&started_at)?,
+                focused_minutes,
+                used: false,
+            })
+        })
+        .collect();
+
+    let mut overall = RealizationStats::default();
+    let mut by_course: BTreeMap<Option<i64>, CourseRealization> = BTreeMap::new();
+    let mut by_week: BTreeMap<String, RealizationStats> = BTreeMap::new();
+
+    for block in &blocks {
+        let hours = (block.end - block.start).num_minutes() as f64 / 60.0;
+        let window_start = block.start - chrono::Duration::minutes(EARLY_START_MINUTES);
+        // Each session realizes at most one block; one started from the
+        // block is its session, otherwise match by time and course
+        let linked = sessions.iter().position(|s| s.block_id == Some(block.id));
+        let realized = linked
+            .or_else(|| {
+                sessions.iter().position(|s| {
+                    !s.used
+                        && s.block_id.is_none()
+                        && s.start >= window_start
+                        && s.start < block.end
+                        && s.focused_minutes >= MIN_FOCUSED_MINUTES
+                        && (block.course_id.is_none() || s.course_id == block.course_id)
+                })
+            })
+            .map(|i| &mut sessions[i])
+            .filter(|s| !s.used && s.focused_minutes >= MIN_FOCUSED_MINUTES)
+            .map(|s| {
+                s.used = true;
+                s.focused_minutes / 60.0
+            });
+
+        overall.add(hours, realized);
+        by_course
+            .entry(block.course_id)
+            .or_insert_with(|| CourseRealization {
+                course_id: block.course_id,
+                course_name: block.course_name.clone(),
+                stats: RealizationStats::default(),
+            })
+            .stats
+            .add(hours, realized);
+        by_week.entry(block.week_start.clone()).or_default().add(hours, realized);
+    }
+
+    let adherence = block_adherence(pool, &since).await?;
+
+    let realism_score =
+        (overall.realized_blocks as f64 + PRIOR_BLOCKS) / (overall.planned_blocks as f64 + PRIOR_BLOCKS);
+
+    Ok(PlanRealismReport {
+        weeks,
+        overall,
+        by_course: by_course.into_values().collect(),
+        by_week: by_week
+            .into_iter()
+            .map(|(week_start, stats)| WeekRealization { week_start, stats })
+            .collect(),
+        adherence,
+        realism_score,
+        plan_scale: realism_score.clamp(MIN_PLAN_SCALE, 1.0),
+    })
+}
+
+async fn block_adherence(pool: &Pool<Sqlite>, since: &str) -> Result<BlockAdherence, ApiError> {
+    let (blocks, planned_minutes, actual_minutes): (i64, Option<f64>, Option<i64>) = sqlx::query_as(
+        r#"
+        SELECT COUNT(*),
+               SUM((julianday(end_at) - julianday(start_at)) * 1440.0),
+               SUM(actual_minutes)
+        FROM week_plan_blocks
+        WHERE progress = 'done' AND actual_minutes IS NOT NULL
+          AND date(start_at) >= date('now', ?)
+        "#,
+    )
+    .bind(since)
+    .fetch_one(pool)
+    .await
+    .map_err(ApiError::from)?;
+
+    let planned_hours = planned_minutes.unwrap_or(0.0) / 60.0;
+    let actual_hours = actual_minutes.unwrap_or(0) as f64 / 60.0;
+    Ok(BlockAdherence {
+        blocks,
+        planned_hours,
+        actual_hours,
+        ratio: (planned_hours > 0.0).then(|| actual_hours / planned_hours),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+    use std::str::FromStr;
+
+    async fn setup_pool_with_migrations() -> Pool<Sqlite> {
+        let options = SqliteConnectOptions::from_str("sqlite::memory:")
+            .unwrap()
+            .foreign_keys(false);
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect_with(options)
+            .await
+            .expect("Failed to connect to in-memory DB");
+
+        crate::db::migrations::run_migrations(&pool)
+            .await
+            .expect("Failed to run migrations");
+
+        pool
+    }
+
+    /// A 90 minute accepted block `days_ago` at 10:00 UTC
+    async fn block(pool: &Pool<Sqlite>, course_id: i64, days_ago: i64) {
+        sqlx::query(
+            r#"INSERT INTO week_plan_blocks (week_start_date, start_at, end_at, block_type, course_id, status)
+               VALUES (date('now', ?1), strftime('%Y-%m-%dT10:00:00Z', 'now', ?1),
+                       strftime('%Y-%m-%dT11:30:00Z', 'now', ?1), 'study', ?2, 'accepted')"#,
+        )
+        .bind(format!("-{} days", days_ago))
+        .bind(course_id)
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    /// A study session `days_ago` starting at `time` UTC
+    async fn session(pool: &Pool<Sqlite>, course_id: i64, days_ago: i64, time: &str, minutes: i64) -> i64 {
+        sqlx::query_scalar(
+            r#"INSERT INTO sessions (session_type, reference_id, reference_type, started_at, duration_minutes)
+               VALUES ('study', ?, 'course', date('now', ?) || ' ' || ?, ?) RETURNING id"#,
+        )
+        .bind(course_id)
+        .bind(format!("-{} days", days_ago))
+        .bind(time)
+        .bind(minutes)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn counts_blocks_that_became_deep_work() {
+        let pool = setup_pool_with_migrations().await;
+        let math: i64 = sqlx::query_scalar("INSERT INTO courses (name) VALUES ('Math') RETURNING id")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        let art: i64 = sqlx::query_scalar("INSERT INTO courses (name) VALUES ('Art') RETURNING id")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+
+        for days_ago in [2, 3, 4, 5] {
+            block(&pool, math, days_ago).await;
+        }
+        block(&pool, art, 3).await;
+
+        // Realized: on time, and slightly early
+        session(&pool, math, 2, "10:05:00", 60).await;
+        session(&pool, math, 3, "09:45:00", 40).await;
+        // Too short
+        session(&pool, math, 4, "10:00:00", 15).await;
+        // Long, but mostly distracted per focus samples
+        let distracted = session(&pool, math, 5, "10:00:00", 60).await;
+        sqlx::query(
+            "INSERT INTO focus_samples (session_id, sampled_at, app_name, is_distraction, seconds) VALUES (?, datetime('now'), 'Discord', 1, 3000), (?, datetime('now'), 'Code', 0, 600)",
+        )
+        .bind(distracted)
+        .bind(distracted)
+        .execute(&pool)
+        .await
+        .unwrap();
+        // Right time, wrong course
+        session(&pool, math, 3, "10:10:00", 50).await;
+
+        let report = plan_realism(&pool, 4).await.unwrap();
+        assert_eq!(report.overall.planned_blocks, 5);
+        assert_eq!(report.overall.realized_blocks, 2);
+        assert_eq!(report.overall.planned_hours, 7.5);
+
+        let math_stats = &report.by_course.iter().find(|c| c.course_id == Some(math)).unwrap().stats;
+        assert_eq!((math_stats.planned_blocks, math_stats.realized_blocks), (4, 2));
+        assert_eq!(math_stats.ratio, Some(0.5));
+        let art_stats = &report.by_course.iter().find(|c| c.course_id == Some(art)).unwrap().stats;
+        assert_eq!(art_stats.realized_blocks, 0);
+
+        // (2 + 2) / (5 + 2)
+        assert!((report.realism_score - 4.0 / 7.0).abs() < 1e-9);
+        assert_eq!(report.plan_scale, report.realism_score);
+        assert!(!report.by_week.is_empty());
+    }
+}
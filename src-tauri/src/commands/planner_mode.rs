@@ -0,0 +1,211 @@
+//! Planner Modes
+//!
+//! A named mode (`planner_mode`) changes how the week planner weighs each
+//! domain: an exam period plans more revision, less skill practice and
+//! lighter workouts; a break plans little study and more practice.
+//!
+//! The mode is only switched by the user, but `get_planner_mode` suggests a
+//! switch when an exam is near, or when today falls between terms.
+
+use chrono::Duration;
+use serde::Serialize;
+use sqlx::{Pool, Sqlite};
+use tauri::State;
+
+use crate::{
+    error::ApiError,
+    services::{clock, settings},
+    DbState,
+};
+
+pub const MODE_SETTING: &str = "planner_mode";
+pub const MODES: &[&str] = &["normal", "exam_period", "break"];
+
+/// An ungraded exam this close suggests the exam period mode
+const EXAM_PERIOD_DAYS: i64 = 14;
+
+#[derive(Debug, Clone, Serialize, PartialEq)]
+pub struct PlannerWeights {
+    /// Multiplies the weekly study cap
+    pub study_scale: f64,
+    /// Multiplies the weekly practice cap
+    pub practice_scale: f64,
+    /// Multiplies the weekly workout target
+    pub workout_scale: f64,
+    /// Multiplies how strongly the planner favors exam revision blocks
+    pub revision_weight: f64,
+    /// Whether heavy workout templates are placed
+    pub heavy_workouts: bool,
+}
+
+impl PlannerWeights {
+    pub fn for_mode(mode: &str) -> Self {
+        match mode {
+            "exam_period" => PlannerWeights {
+                study_scale: 1.5,
+                practice_scale: 0.5,
+                workout_scale: 0.67,
+                revision_weight: 2.0,
+                heavy_workouts: false,
+            },
+            "break" => PlannerWeights {
+                study_scale: 0.25,
+                practice_scale: 1.5,
+                workout_scale: 1.0,
+                revision_weight: 1.0,
+                heavy_workouts: true,
+            },
+            _ => PlannerWeights {
+                study_scale: 1.0,
+                practice_scale: 1.0,
+                workout_scale: 1.0,
+                revision_weight: 1.0,
+                heavy_workouts: true,
+            },
+        }
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct PlannerModeStatus {
+    pub mode: String,
+    pub weights: PlannerWeights,
+    /// A better fitting mode, when there is one
+    pub suggested_mode: Option<String>,
+    /// Why the suggested mode fits, for display
+    pub reason: Option<String>,
+}
+
+#[tauri::command]
+pub async fn get_planner_mode(state: State<'_, DbState>) -> Result<PlannerModeStatus, ApiError> {
+    mode_status(&state.0).await
+}
+
+#[tauri::command]
+pub async fn set_planner_mode(state: State<'_, DbState>, mode: String) -> Result<PlannerModeStatus, ApiError> {
+    let pool = &state.0;
+    settings::set(pool, MODE_SETTING, serde_json::json!(mode)).await?;
+    mode_status(pool).await
+}
+
+/// The current mode's weights
+pub(crate) async fn current_weights(pool: &Pool<Sqlite>) -> Result<PlannerWeights, ApiError> {
+    Ok(PlannerWeights::for_mode(&current_mode(pool).await?))
+}
+
+async fn current_mode(pool: &Pool<Sqlite>) -> Result<String, ApiError> {
+    Ok(settings::get_string(pool, MODE_SETTING)
+        .await?
+        .unwrap_or_else(|| "normal".to_string()))
+}
+
+pub(crate) async fn mode_status(pool: &Pool<Sqlite>) -> Result<PlannerModeStatus, ApiError> {
+    let mode = current_mode(pool).await?;
+    let (fitting, reason) = fitting_mode(pool).await?;
+    let suggested = (fitting != mode).then_some(fitting);
+    Ok(PlannerModeStatus {
+        weights: PlannerWeights::for_mode(&mode),
+        reason: suggested.and(reason),
+        suggested_mode: suggested.map(str::to_string),
+        mode,
+    })
+}
+
+/// The mode today's calendar calls for, with the reason for anything but normal
+async fn fitting_mode(pool: &Pool<Sqlite>) -> Result<(&'static str, Option<String>), ApiError> {
+    let today = clock::now().date_naive();
+
+    let next_exam: Option<(String, String)> = sqlx::query_as(
+        r#"
+        SELECT e.title, date(e.exam_date) AS day
+        FROM exams e
+        JOIN courses c ON c.id = e.course_id
+        WHERE c.is_active = 1 AND e.score IS NULL AND e.exam_date IS NOT NULL
+          AND date(e.exam_date) BETWEEN ?1 AND ?2
+        ORDER BY day
+        LIMIT 1
+        "#,
+    )
+    .bind(today.to_string())
+    .bind((today + Duration::days(EXAM_PERIOD_DAYS)).to_string())
+    .fetch_optional(pool)
+    .await
+    .map_err(ApiError::from)?;
+    if let Some((title, day)) = next_exam {
+        return Ok(("exam_period", Some(format!("{} is on {}", title, day))));
+    }
+
+    // Between terms: terms have dates, but none covers today
+    let (dated, covering): (i64, i64) = sqlx::query_as(
+        r#"
+        SELECT COUNT(*),
+               COALESCE(SUM(CASE WHEN start_date <= ?1 AND end_date >= ?1 THEN 1 ELSE 0 END), 0)
+        FROM terms
+        WHERE start_date IS NOT NULL AND end_date IS NOT NULL
+        "#,
+    )
+    .bind(today.to_string())
+    .fetch_one(pool)
+    .await
+    .map_err(ApiError::from)?;
+    if dated > 0 && covering == 0 {
+        return Ok(("break", Some("No term is in session".to_string())));
+    }
+
+    Ok(("normal", None))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Local, TimeZone};
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn a_near_exam_suggests_the_exam_period_until_it_is_graded() {
+        let pool = crate::test_support::pool().await;
+        let _clock = clock::freeze(Local.with_ymd_and_hms(2030, 5, 1, 9, 0, 0).unwrap());
+        let course = crate::test_support::course("Physics").create(&pool).await;
+
+        let status = mode_status(&pool).await.unwrap();
+        assert_eq!(status.mode, "normal");
+        assert_eq!(status.suggested_mode, None);
+
+        sqlx::query("INSERT INTO exams (course_id, title, exam_date) VALUES (?, 'Final', '2030-05-10 09:00:00')")
+            .bind(course.id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        let status = mode_status(&pool).await.unwrap();
+        assert_eq!(status.suggested_mode.as_deref(), Some("exam_period"));
+        assert_eq!(status.reason.as_deref(), Some("Final is on 2030-05-10"));
+
+        settings::set(&pool, MODE_SETTING, json!("exam_period")).await.unwrap();
+        let status = mode_status(&pool).await.unwrap();
+        assert_eq!(status.suggested_mode, None);
+        assert!(!status.weights.heavy_workouts);
+        assert!(status.weights.study_scale > 1.0 && status.weights.practice_scale < 1.0);
+
+        // Once graded, the period is over
+        sqlx::query("UPDATE exams SET score = 80")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let status = mode_status(&pool).await.unwrap();
+        assert_eq!(status.suggested_mode.as_deref(), Some("normal"));
+    }
+
+    #[tokio::test]
+    async fn days_between_terms_suggest_a_break() {
+        let pool = crate::test_support::pool().await;
+        let _clock = clock::freeze(Local.with_ymd_and_hms(2030, 7, 15, 9, 0, 0).unwrap());
+        sqlx::query("INSERT INTO terms (name, start_date, end_date) VALUES ('Spring', '2030-01-15', '2030-06-01')")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let status = mode_status(&pool).await.unwrap();
+        assert_eq!(status.suggested_mode.as_deref(), Some("break"));
+        assert!(settings::set(&pool, MODE_SETTING, json!("holiday")).await.is_err());
+    }
+}
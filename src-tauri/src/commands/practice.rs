@@ -1,6 +1,11 @@
+use chrono::{Duration, NaiveDate};
+use sqlx::{Pool, Sqlite};
 use tauri::State;
 
-use crate::{DbState, error::ApiError};
+use crate::{DbState, error::ApiError, services::{aggregates, clock}};
+
+/// Days without practice after which a skill is half decayed
+const DECAY_HALF_LIFE_DAYS: f64 = 7.0;
 
 #[derive(Debug, serde::Deserialize)]
 pub struct PracticeInput {
@@ -41,6 +46,7 @@ pub async fn log_practice(state: State<'_, DbState>, data: PracticeInput) -> Res
         .map_err(ApiError::from)?;
 
     tx.commit().await.map_err(ApiError::from)?;
+    aggregates::invalidate();
     Ok(rec)
 }
 
@@ -60,3 +66,154 @@ pub async fn get_practice_logs(state: State<'_, DbState>, skill_id: Option<i64>)
     };
     Ok(rows)
 }
+
+/// How much practice a skill still needs in a week
+#[derive(Debug, serde::Serialize)]
+pub struct SkillPracticeNeed {
+    pub skill_id: i64,
+    pub name: String,
+    pub category: Option<String>,
+    pub target_weekly_hours: f64,
+    /// Logged this week
+    pub practiced_hours: f64,
+    /// Accepted or locked practice blocks still ahead this week
+    pub planned_hours: f64,
+    pub remaining_hours: f64,
+    /// None when never practiced
+    pub days_since_practice: Option<f64>,
+    /// 0 right after practice, approaching 1 as the skill goes unpracticed
+    pub decay: f64,
+    /// Share of the target still open, weighted up by decay
+    pub priority: f64,
+}
+
+/// Skills with practice left to plan in a week, most urgent first
+#[tauri::command]
+pub async fn get_skill_practice_needs(
+    state: State<'_, DbState>,
+    week_start_date: String,
+) -> Result<Vec<SkillPracticeNeed>, ApiError> {
+    skill_practice_needs(&state.0, &week_start_date).await
+}
+
+pub(crate) async fn skill_practice_needs(
+    pool: &Pool<Sqlite>,
+    week_start_date: &str,
+) -> Result<Vec<SkillPracticeNeed>, ApiError> {
+    let week_start = NaiveDate::parse_from_str(week_start_date, "%Y-%m-%d")
+        .map_err(|_| ApiError::validation("week_start_date must be YYYY-MM-DD"))?;
+    let week_end = week_start + Duration::days(7);
+    let now = clock::now();
+    // Logs are stored in UTC, plan blocks in naive local time
+    let now_utc = now.with_timezone(&chrono::Utc).format("%Y-%m-%d %H:%M:%S").to_string();
+    let now_local = now.naive_local().format("%Y-%m-%dT%H:%M:%S").to_string();
+
+    let rows = sqlx::query_as::<_, (i64, String, Option<String>, f64, f64, f64, Option<f64>)>(
+        r#"
+        SELECT s.id, s.name, s.category, s.target_weekly_hours,
+               COALESCE((SELECT SUM(p.duration_minutes) FROM practice_logs p
+                         WHERE p.skill_id = s.id
+                           AND date(p.logged_at, 'localtime') >= ?1
+                           AND date(p.logged_at, 'localtime') < ?2), 0) / 60.0,
+               COALESCE((SELECT SUM((julianday(b.end_at) - julianday(b.start_at)) * 24.0)
+                         FROM week_plan_blocks b
+                         WHERE b.skill_id = s.id AND b.status IN ('accepted', 'locked')
+                           AND b.missed_at IS NULL AND b.start_at > ?3 AND b.start_at < ?2), 0.0),
+               julianday(?4) - julianday((SELECT MAX(p.logged_at) FROM practice_logs p WHERE p.skill_id = s.id))
+        FROM skills s
+        WHERE s.archived_at IS NULL AND COALESCE(s.target_weekly_hours, 0) > 0
+        "#,
+    )
+    .bind(week_start.to_string())
+    .bind(week_end.to_string())
+    .bind(&now_local)
+    .bind(&now_utc)
+    .fetch_all(pool)
+    .await
+    .map_err(ApiError::from)?;
+
+    let mut needs: Vec<SkillPracticeNeed> = rows
+        .into_iter()
+        .filter_map(|(skill_id, name, category, target, practiced, planned, days_since)| {
+            let remaining = target - practiced - planned;
+            if remaining <= 0.0 {
+                return None;
+            }
+            let decay = match days_since {
+                Some(days) => 1.0 - 0.5f64.powf(days.max(0.0) / DECAY_HALF_LIFE_DAYS),
+                None => 1.0,
+            };
+            Some(SkillPracticeNeed {
+                skill_id,
+                name,
+                category,
+                target_weekly_hours: target,
+                practiced_hours: practiced,
+                planned_hours: planned,
+                remaining_hours: remaining,
+                days_since_practice: days_since,
+                decay,
+                priority: remaining / target * (1.0 + decay),
+            })
+        })
+        .collect();
+    needs.sort_by(|a, b| b.priority.total_cmp(&a.priority));
+    Ok(needs)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Local, TimeZone};
+
+    async fn skill(pool: &Pool<Sqlite>, name: &str, target: f64) -> i64 {
+        sqlx::query_scalar("INSERT INTO skills (name, target_weekly_hours) VALUES (?, ?) RETURNING id")
+            .bind(name)
+            .bind(target)
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn needs_count_practice_and_planned_blocks_and_rank_neglected_skills_first() {
+        let pool = crate::test_support::pool().await;
+        let _clock = clock::freeze(Local.with_ymd_and_hms(2030, 9, 4, 12, 0, 0).unwrap());
+        let guitar = skill(&pool, "Guitar", 3.0).await;
+        let spanish = skill(&pool, "Spanish", 2.0).await;
+        let chess = skill(&pool, "Chess", 1.0).await;
+
+        // Guitar: an hour practiced this week, an hour planned ahead
+        let logged = Local.with_ymd_and_hms(2030, 9, 3, 18, 0, 0).unwrap().with_timezone(&chrono::Utc);
+        sqlx::query("INSERT INTO practice_logs (skill_id, duration_minutes, logged_at) VALUES (?, 60, ?)")
+            .bind(guitar)
+            .bind(logged.format("%Y-%m-%d %H:%M:%S").to_string())
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query(
+            r#"INSERT INTO week_plan_blocks (week_start_date, start_at, end_at, block_type, skill_id, status)
+               VALUES ('2030-09-02', '2030-09-05T18:00:00', '2030-09-05T19:00:00', 'practice', ?, 'accepted')"#,
+        )
+        .bind(guitar)
+        .execute(&pool)
+        .await
+        .unwrap();
+        // Chess: target already met
+        sqlx::query("INSERT INTO practice_logs (skill_id, duration_minutes, logged_at) VALUES (?, 60, ?)")
+            .bind(chess)
+            .bind(logged.format("%Y-%m-%d %H:%M:%S").to_string())
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let needs = skill_practice_needs(&pool, "2030-09-02").await.unwrap();
+        let ids: Vec<i64> = needs.iter().map(|n| n.skill_id).collect();
+        // Spanish was never practiced, so it ranks first
+        assert_eq!(ids, vec![spanish, guitar]);
+        assert_eq!(needs[0].decay, 1.0);
+        assert!((needs[1].remaining_hours - 1.0).abs() < 1e-9);
+        assert!((needs[1].days_since_practice.unwrap() - 18.0 / 24.0).abs() < 1e-6);
+        assert!(needs[1].decay < 0.1);
+    }
+}
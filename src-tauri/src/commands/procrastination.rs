@@ -0,0 +1,248 @@
+//! Procrastination Metrics
+//!
+//! How early assignments get started and how close to the deadline they get
+//! done, over the current term (or the last `DEFAULT_WEEKS` weeks without
+//! one):
+//! - start delay: days from creating the assignment to its first session
+//! - start lead: days before the deadline that first session started
+//! - completion margin: hours between marking it done and the deadline,
+//!   negative when late
+//!
+//! A session counts for an assignment when it references it or was started
+//! from one of its plan blocks. Date-only deadlines mean the end of that day.
+
+use std::collections::BTreeMap;
+
+use chrono::Duration;
+use serde::Serialize;
+use sqlx::{Pool, Sqlite};
+use tauri::State;
+
+use crate::{error::ApiError, services::clock, DbState};
+
+/// Window used when no current term has a start date
+const DEFAULT_WEEKS: i64 = 16;
+
+#[derive(Debug, Default, Serialize, PartialEq)]
+pub struct ProcrastinationStats {
+    pub assignments: i64,
+    /// Average days from creation to the first session
+    pub avg_start_delay_days: Option<f64>,
+    /// Average days before the deadline the first session started
+    pub avg_start_lead_days: Option<f64>,
+    /// Average hours between completion and the deadline; negative when late
+    pub avg_completion_margin_hours: Option<f64>,
+    /// Finished after the deadline, or still open past it
+    pub late: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct CourseProcrastination {
+    pub course_id: i64,
+    pub course_name: String,
+    pub stats: ProcrastinationStats,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ProcrastinationWeek {
+    /// Monday of the week the assignments were due
+    pub week_start: String,
+    pub stats: ProcrastinationStats,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ProcrastinationReport {
+    /// First due date included (YYYY-MM-DD)
+    pub since: String,
+    pub overall: ProcrastinationStats,
+    /// Courses with at least one assignment, least lead first
+    pub by_course: Vec<CourseProcrastination>,
+    /// Oldest week first
+    pub trend: Vec<ProcrastinationWeek>,
+}
+
+/// (course_id, course_name, due week, start delay days, start lead days, margin hours, late)
+type AssignmentRow = (i64, String, String, Option<f64>, Option<f64>, Option<f64>, bool);
+
+#[tauri::command]
+pub async fn get_procrastination_report(state: State<'_, DbState>) -> Result<ProcrastinationReport, ApiError> {
+    procrastination_report(&state.0).await
+}
+
+pub(crate) async fn procrastination_report(pool: &Pool<Sqlite>) -> Result<ProcrastinationReport, ApiError> {
+    let today = clock::now().date_naive();
+    let term_start: Option<String> = sqlx::query_scalar(
+        "SELECT start_date FROM terms WHERE is_current = 1 AND archived_at IS NULL AND start_date IS NOT NULL ORDER BY start_date DESC LIMIT 1",
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(ApiError::from)?;
+    let since = term_start.unwrap_or_else(|| (today - Duration::weeks(DEFAULT_WEEKS)).to_string());
+    let now = clock::now().naive_local().format("%Y-%m-%d %H:%M:%S").to_string();
+
+    // Assignments due in the window that are done or already past due
+    let rows: Vec<AssignmentRow> = sqlx::query_as(
+        r#"
+        WITH due AS (
+            SELECT a.id, a.course_id, a.created_at, a.completed_at, a.is_completed,
+                   CASE WHEN length(a.due_date) = 10 THEN datetime(a.due_date, '+1 day') ELSE a.due_date END AS due_at
+            FROM assignments a
+            WHERE a.due_date IS NOT NULL AND date(a.due_date) >= ?1
+        ),
+        first_session AS (
+            SELECT d.id,
+                   (SELECT MIN(s.started_at) FROM sessions s
+                    WHERE (s.reference_type = 'assignment' AND s.reference_id = d.id)
+                       OR s.week_plan_block_id IN (SELECT b.id FROM week_plan_blocks b WHERE b.assignment_id = d.id)
+                   ) AS started_at
+            FROM due d
+        )
+        SELECT d.course_id, c.name, date(d.due_at, '-6 days', 'weekday 1'),
+               julianday(f.started_at, 'localtime') - julianday(d.created_at, 'localtime'),
+               julianday(d.due_at) - julianday(f.started_at, 'localtime'),
+               CASE WHEN d.is_completed = 1
+                    THEN (julianday(d.due_at) - julianday(d.completed_at, 'localtime')) * 24 END,
+               CASE WHEN d.is_completed = 1
+                    THEN COALESCE(julianday(d.completed_at, 'localtime') > julianday(d.due_at), 0)
+                    ELSE 1 END
+        FROM due d
+        JOIN first_session f ON f.id = d.id
+        JOIN courses c ON c.id = d.course_id
+        WHERE d.is_completed = 1 OR julianday(d.due_at) < julianday(?2)
+        ORDER BY d.due_at
+        "#,
+    )
+    .bind(&since)
+    .bind(&now)
+    .fetch_all(pool)
+    .await
+    .map_err(ApiError::from)?;
+
+    let overall = stats(rows.iter());
+
+    let mut courses: BTreeMap<i64, (String, Vec<&AssignmentRow>)> = BTreeMap::new();
+    let mut weeks: BTreeMap<&str, Vec<&AssignmentRow>> = BTreeMap::new();
+    for row in &rows {
+        courses.entry(row.0).or_insert_with(|| (row.1.clone(), Vec::new())).1.push(row);
+        weeks.entry(row.2.as_str()).or_default().push(row);
+    }
+    let mut by_course: Vec<CourseProcrastination> = courses
+        .into_iter()
+        .map(|(course_id, (course_name, rows))| CourseProcrastination {
+            course_id,
+            course_name,
+            stats: stats(rows.into_iter()),
+        })
+        .collect();
+    by_course.sort_by(|a, b| {
+        let lead = |c: &CourseProcrastination| c.stats.avg_start_lead_days.unwrap_or(f64::INFINITY);
+        lead(a).total_cmp(&lead(b))
+    });
+    let trend = weeks
+        .into_iter()
+        .map(|(week_start, rows)| ProcrastinationWeek {
+            week_start: week_start.to_string(),
+            stats: stats(rows.into_iter()),
+        })
+        .collect();
+
+    Ok(ProcrastinationReport {
+        since,
+        overall,
+        by_course,
+        trend,
+    })
+}
+
+fn stats<'a>(rows: impl Iterator<Item = &'a AssignmentRow>) -> ProcrastinationStats {
+    let rows: Vec<&AssignmentRow> = rows.collect();
+    let average = |values: Vec<f64>| {
+        (!values.is_empty()).then(|| (values.iter().sum::<f64>() / values.len() as f64 * 10.0).round() / 10.0)
+    };
+    ProcrastinationStats {
+        assignments: rows.len() as i64,
+        avg_start_delay_days: average(rows.iter().filter_map(|r| r.3).collect()),
+        avg_start_lead_days: average(rows.iter().filter_map(|r| r.4).collect()),
+        avg_completion_margin_hours: average(rows.iter().filter_map(|r| r.5).collect()),
+        late: rows.iter().filter(|r| r.6).count() as i64,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Local, TimeZone, Utc};
+
+    /// Local time as a UTC timestamp, the way sessions and completions are stored
+    fn utc(day: u32, hour: u32) -> String {
+        Local
+            .with_ymd_and_hms(2030, 3, day, hour, 0, 0)
+            .unwrap()
+            .with_timezone(&Utc)
+            .format("%Y-%m-%d %H:%M:%S")
+            .to_string()
+    }
+
+    async fn assignment(pool: &Pool<Sqlite>, course_id: i64, created: u32, due: &str, completed: Option<u32>) -> i64 {
+        sqlx::query_scalar(
+            "INSERT INTO assignments (course_id, title, due_date, created_at, is_completed, completed_at) VALUES (?, 'Essay', ?, ?, ?, ?) RETURNING id",
+        )
+        .bind(course_id)
+        .bind(due)
+        .bind(utc(created, 9))
+        .bind(completed.is_some())
+        .bind(completed.map(|day| utc(day, 20)))
+        .fetch_one(pool)
+        .await
+        .unwrap()
+    }
+
+    async fn session(pool: &Pool<Sqlite>, assignment_id: i64, day: u32) {
+        sqlx::query(
+            "INSERT INTO sessions (session_type, reference_type, reference_id, started_at, ended_at, duration_minutes) VALUES ('study', 'assignment', ?, ?, ?, 60)",
+        )
+        .bind(assignment_id)
+        .bind(utc(day, 12))
+        .bind(utc(day, 13))
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn start_lead_and_completion_margin_are_averaged_per_course_and_week() {
+        let pool = crate::test_support::pool().await;
+        let _clock = clock::freeze(Local.with_ymd_and_hms(2030, 3, 20, 9, 0, 0).unwrap());
+        let essays = crate::test_support::course("Essays").create(&pool).await;
+        let labs = crate::test_support::course("Labs").create(&pool).await;
+
+        // Started two days before a 12:00 deadline, done the evening before
+        let early = assignment(&pool, essays.id, 1, "2030-03-06T12:00", Some(5)).await;
+        session(&pool, early, 4).await;
+        // Started the day it was due (date-only: end of day), finished late
+        let late = assignment(&pool, essays.id, 2, "2030-03-12", Some(13)).await;
+        session(&pool, late, 12).await;
+        // Past due and still open, never started
+        assignment(&pool, labs.id, 3, "2030-03-15", None).await;
+        // Not due yet: not counted
+        assignment(&pool, labs.id, 3, "2030-03-30", None).await;
+
+        let report = procrastination_report(&pool).await.unwrap();
+        assert_eq!(report.overall.assignments, 3);
+        assert_eq!(report.overall.late, 2);
+        // Leads: 2.0 days and 0.5 days
+        assert_eq!(report.overall.avg_start_lead_days, Some(1.3));
+        // Delays: 3 days 3h and 10 days 3h
+        assert_eq!(report.overall.avg_start_delay_days, Some(6.6));
+        // Margins: +16h and -20h
+        assert_eq!(report.overall.avg_completion_margin_hours, Some(-2.0));
+
+        let essay_stats = &report.by_course.iter().find(|c| c.course_id == essays.id).unwrap().stats;
+        assert_eq!(essay_stats.assignments, 2);
+        let lab_stats = &report.by_course.iter().find(|c| c.course_id == labs.id).unwrap().stats;
+        assert_eq!(lab_stats.avg_start_lead_days, None);
+
+        let weeks: Vec<&str> = report.trend.iter().map(|w| w.week_start.as_str()).collect();
+        assert_eq!(weeks, vec!["2030-03-04", "2030-03-11"]);
+    }
+}
@@ -0,0 +1,25 @@
+//! Quick action commands
+//!
+//! Lets the settings UI show shortcut bindings (and whether the OS accepted
+//! them) and lets the frontend trigger the same actions as the tray menu.
+
+use tauri::State;
+
+use crate::deep_link::DeepLinkOutcome;
+use crate::quick_actions::{self, QuickAction, QuickActionStatus, ShortcutState};
+use crate::{error::ApiError, DbState};
+
+/// Quick actions with their shortcuts and registration status
+#[tauri::command]
+pub async fn get_quick_actions(
+    state: State<'_, DbState>,
+    shortcuts: State<'_, ShortcutState>,
+) -> Result<Vec<QuickActionStatus>, ApiError> {
+    quick_actions::status(&state.0, &shortcuts).await
+}
+
+/// Run a quick action, as if its shortcut had been pressed
+#[tauri::command]
+pub async fn run_quick_action(state: State<'_, DbState>, action: QuickAction) -> Result<DeepLinkOutcome, ApiError> {
+    quick_actions::run(&state.0, action).await
+}
@@ -0,0 +1,35 @@
+//! Reading commands
+
+use tauri::State;
+
+use crate::services::clock;
+use crate::services::reading::{self, Book, BookInput, ReadingSessionInput, ReadingStats};
+use crate::services::trackers::TrackerLog;
+use crate::{error::ApiError, DbState};
+
+/// Books with their progress and projected finish, ones being read first
+#[tauri::command]
+pub async fn get_books(state: State<'_, DbState>, include_finished: Option<bool>) -> Result<Vec<Book>, ApiError> {
+    reading::books(&state.0, include_finished.unwrap_or(false), clock::now().date_naive()).await
+}
+
+#[tauri::command]
+pub async fn add_book(state: State<'_, DbState>, data: BookInput) -> Result<Book, ApiError> {
+    reading::add_book(&state.0, &data, clock::now().date_naive()).await
+}
+
+/// Log pages read, or the page reached; reaching the last page finishes the book
+#[tauri::command]
+pub async fn log_reading_session(state: State<'_, DbState>, data: ReadingSessionInput) -> Result<TrackerLog, ApiError> {
+    reading::log_session(&state.0, &data).await
+}
+
+/// Reading over the last `days` days with streaks and the yearly goal
+#[tauri::command]
+pub async fn get_reading_stats(state: State<'_, DbState>, days: Option<i64>) -> Result<ReadingStats, ApiError> {
+    let days = days.unwrap_or(30);
+    if !(1..=365).contains(&days) {
+        return Err(ApiError::validation("days must be between 1 and 365"));
+    }
+    reading::stats(&state.0, days, clock::now().date_naive()).await
+}
@@ -0,0 +1,262 @@
+//! Sample Data
+//!
+//! A small, realistic data set for onboarding screenshots and UI testing.
+//! Every inserted row is flagged `is_sample = 1` so it can be removed in one
+//! call without touching the user's own data.
+
+use chrono::{Datelike, Duration, Local, NaiveDate};
+use sqlx::{Pool, Sqlite};
+use tauri::State;
+
+use crate::{error::ApiError, DbState};
+
+/// Number of sample rows per table
+#[derive(Debug, Clone, Default, serde::Serialize)]
+pub struct SampleDataCounts {
+    pub courses: i64,
+    pub assignments: i64,
+    pub workouts: i64,
+    pub plan_blocks: i64,
+}
+
+/// (name, code, color, target weekly hours)
+const SAMPLE_COURSES: &[(&str, &str, &str, f64)] = &[
+    ("Calculus II", "MATH 152", "#3b82f6", 8.0),
+    ("Intro to Psychology", "PSYC 101", "#a855f7", 5.0),
+    ("Data Structures", "CS 201", "#10b981", 9.0),
+];
+
+/// (course index, title, days from today, priority, completed)
+const SAMPLE_ASSIGNMENTS: &[(usize, &str, i64, &str, bool)] = &[
+    (0, "Problem Set 4: Integration by Parts", 2, "high", false),
+    (0, "Problem Set 3: Substitution", -5, "medium", true),
+    (1, "Reading Response: Memory", 4, "low", false),
+    (1, "Midterm Study Guide", 9, "medium", false),
+    (2, "Lab 5: Binary Search Trees", 1, "high", false),
+    (2, "Lab 4: Linked Lists", -3, "medium", true),
+    (2, "Project Proposal", -1, "high", false),
+];
+
+/// (name, days ago, duration minutes)
+const SAMPLE_WORKOUTS: &[(&str, i64, i64)] = &[
+    ("Upper Body", 1, 45),
+    ("Morning Run", 3, 30),
+    ("Leg Day", 4, 50),
+    ("Yoga", 6, 25),
+];
+
+/// (weekday from Monday, start hour, hours, block type, course index, title)
+const SAMPLE_PLAN_BLOCKS: &[(i64, u32, u32, &str, Option<usize>, Option<&str>)] = &[
+    (0, 9, 2, "study", Some(0), None),
+    (0, 14, 1, "assignment", Some(2), Some("Lab 5")),
+    (1, 10, 2, "study", Some(2), None),
+    (2, 9, 1, "study", Some(1), None),
+    (2, 12, 1, "break", None, Some("Lunch walk")),
+    (3, 15, 2, "exam_prep", Some(1), Some("Midterm review")),
+    (4, 9, 2, "assignment", Some(0), Some("Problem Set 4")),
+];
+
+/// Load the sample data set (fails if it is already loaded)
+#[tauri::command]
+pub async fn load_sample_data(state: State<'_, DbState>) -> Result<SampleDataCounts, ApiError> {
+    load_sample_data_for_pool(&state.0).await
+}
+
+/// Remove every sample row; returns what was deleted
+#[tauri::command]
+pub async fn remove_sample_data(state: State<'_, DbState>) -> Result<SampleDataCounts, ApiError> {
+    remove_sample_data_for_pool(&state.0).await
+}
+
+/// Count sample rows currently in the database
+#[tauri::command]
+pub async fn get_sample_data_status(state: State<'_, DbState>) -> Result<SampleDataCounts, ApiError> {
+    count_sample_data(&state.0).await
+}
+
+async fn load_sample_data_for_pool(pool: &Pool<Sqlite>) -> Result<SampleDataCounts, ApiError> {
+    let existing = count_sample_data(pool).await?;
+    if existing.courses + existing.assignments + existing.workouts + existing.plan_blocks > 0 {
+        return Err(ApiError::validation("Sample data is already loaded"));
+    }
+
+    let today = Local::now().date_naive();
+    let week_start = today - Duration::days(today.weekday().num_days_from_monday() as i64);
+
+    let mut tx = pool.begin().await.map_err(ApiError::from)?;
+
+    let mut course_ids = Vec::with_capacity(SAMPLE_COURSES.len());
+    for (name, code, color, weekly_hours) in SAMPLE_COURSES {
+        let id: i64 = sqlx::query_scalar(
+            "INSERT INTO courses (user_id, name, code, color, target_weekly_hours, is_sample) VALUES (1, ?, ?, ?, ?, 1) RETURNING id",
+        )
+        .bind(name)
+        .bind(code)
+        .bind(color)
+        .bind(weekly_hours)
+        .fetch_one(&mut *tx)
+        .await
+        .map_err(ApiError::from)?;
+        course_ids.push(id);
+    }
+
+    for (course, title, due_in, priority, completed) in SAMPLE_ASSIGNMENTS {
+        let due = today + Duration::days(*due_in);
+        sqlx::query(
+            "INSERT INTO assignments (course_id, title, due_date, priority, is_completed, completed_at, is_sample) VALUES (?, ?, ?, ?, ?, ?, 1)",
+        )
+        .bind(course_ids[*course])
+        .bind(title)
+        .bind(iso_date(due))
+        .bind(priority)
+        .bind(completed)
+        .bind(completed.then(|| format!("{} 18:00:00", iso_date(due - Duration::days(1)))))
+        .execute(&mut *tx)
+        .await
+        .map_err(ApiError::from)?;
+    }
+
+    for (name, days_ago, minutes) in SAMPLE_WORKOUTS {
+        sqlx::query(
+            "INSERT INTO workouts (user_id, name, duration_minutes, logged_at, is_sample) VALUES (1, ?, ?, ?, 1)",
+        )
+        .bind(name)
+        .bind(minutes)
+        .bind(format!("{} 17:30:00", iso_date(today - Duration::days(*days_ago))))
+        .execute(&mut *tx)
+        .await
+        .map_err(ApiError::from)?;
+    }
+
+    for (weekday, hour, hours, block_type, course, title) in SAMPLE_PLAN_BLOCKS {
+        let day = iso_date(week_start + Duration::days(*weekday));
+        sqlx::query(
+            r#"INSERT INTO week_plan_blocks (user_id, week_start_date, start_at, end_at, block_type, course_id, title, status, is_sample)
+               VALUES (1, ?, ?, ?, ?, ?, ?, 'accepted', 1)"#,
+        )
+        .bind(iso_date(week_start))
+        .bind(format!("{}T{:02}:00:00", day, hour))
+        .bind(format!("{}T{:02}:00:00", day, hour + hours))
+        .bind(block_type)
+        .bind(course.map(|c| course_ids[c]))
+        .bind(title)
+        .execute(&mut *tx)
+        .await
+        .map_err(ApiError::from)?;
+    }
+
+    tx.commit().await.map_err(ApiError::from)?;
+
+    count_sample_data(pool).await
+}
+
+async fn remove_sample_data_for_pool(pool: &Pool<Sqlite>) -> Result<SampleDataCounts, ApiError> {
+    let mut tx = pool.begin().await.map_err(ApiError::from)?;
+
+    // Children first, so this works whether or not foreign keys cascade
+    let plan_blocks = delete_sample_rows(&mut tx, "week_plan_blocks").await?;
+    let assignments = delete_sample_rows(&mut tx, "assignments").await?;
+    sqlx::query("DELETE FROM workout_exercises WHERE workout_id IN (SELECT id FROM workouts WHERE is_sample = 1)")
+        .execute(&mut *tx)
+        .await
+        .map_err(ApiError::from)?;
+    let workouts = delete_sample_rows(&mut tx, "workouts").await?;
+    let courses = delete_sample_rows(&mut tx, "courses").await?;
+
+    tx.commit().await.map_err(ApiError::from)?;
+
+    Ok(SampleDataCounts {
+        courses,
+        assignments,
+        workouts,
+        plan_blocks,
+    })
+}
+
+async fn delete_sample_rows(
+    tx: &mut sqlx::Transaction<'_, Sqlite>,
+    table: &'static str,
+) -> Result<i64, ApiError> {
+    let result = sqlx::query(&format!("DELETE FROM {} WHERE is_sample = 1", table))
+        .execute(&mut **tx)
+        .await
+        .map_err(ApiError::from)?;
+    Ok(result.rows_affected() as i64)
+}
+
+async fn count_sample_data(pool: &Pool<Sqlite>) -> Result<SampleDataCounts, ApiError> {
+    let (courses, assignments, workouts, plan_blocks): (i64, i64, i64, i64) = sqlx::query_as(
+        r#"
+        SELECT
+            (SELECT COUNT(*) FROM courses WHERE is_sample = 1),
+            (SELECT COUNT(*) FROM assignments WHERE is_sample = 1),
+            (SELECT COUNT(*) FROM workouts WHERE is_sample = 1),
+            (SELECT COUNT(*) FROM week_plan_blocks WHERE is_sample = 1)
+        "#,
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(ApiError::from)?;
+
+    Ok(SampleDataCounts {
+        courses,
+        assignments,
+        workouts,
+        plan_blocks,
+    })
+}
+
+fn iso_date(date: NaiveDate) -> String {
+    date.format("%Y-%m-%d").to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+    use std::str::FromStr;
+
+    async fn setup_pool_with_migrations() -> Pool<Sqlite> {
+        let options = SqliteConnectOptions::from_str("sqlite::memory:")
+            .unwrap()
+            .foreign_keys(false);
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect_with(options)
+            .await
+            .expect("Failed to connect to in-memory DB");
+
+        crate::db::migrations::run_migrations(&pool)
+            .await
+            .expect("Failed to run migrations");
+
+        pool
+    }
+
+    #[tokio::test]
+    async fn sample_data_loads_once_and_removes_cleanly() {
+        let pool = setup_pool_with_migrations().await;
+        sqlx::query("INSERT INTO courses (name) VALUES ('My Course')")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let loaded = load_sample_data_for_pool(&pool).await.unwrap();
+        assert_eq!(loaded.courses, SAMPLE_COURSES.len() as i64);
+        assert_eq!(loaded.assignments, SAMPLE_ASSIGNMENTS.len() as i64);
+        assert_eq!(loaded.workouts, SAMPLE_WORKOUTS.len() as i64);
+        assert_eq!(loaded.plan_blocks, SAMPLE_PLAN_BLOCKS.len() as i64);
+
+        assert!(load_sample_data_for_pool(&pool).await.is_err());
+
+        let removed = remove_sample_data_for_pool(&pool).await.unwrap();
+        assert_eq!(removed.courses, loaded.courses);
+        assert_eq!(removed.plan_blocks, loaded.plan_blocks);
+
+        let remaining: Vec<String> = sqlx::query_scalar("SELECT name FROM courses")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+        assert_eq!(remaining, vec!["My Course".to_string()]);
+    }
+}
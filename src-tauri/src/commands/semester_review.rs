@@ -0,0 +1,349 @@
+//! Semester Review
+//!
+//! Looks back over a term's graded exams and how each was prepared for: the
+//! study hours logged in the `PREP_WINDOW_DAYS` before it, against the prep
+//! the accepted plan had scheduled. Correlating those with the grades (and
+//! reading the post-mortems alongside) shows whether more hours, or sticking
+//! to the plan, actually moved outcomes.
+
+use serde::Serialize;
+use sqlx::{Pool, Sqlite};
+use tauri::State;
+
+use crate::{commands::efficiency::PREP_WINDOW_DAYS, error::ApiError, DbState};
+
+/// Graded exams needed before prep hours and grades are correlated
+const MIN_EXAMS_FOR_CORRELATION: usize = 3;
+/// Share of planned prep that counts as sticking to the plan
+const ON_PLAN_ADHERENCE: f64 = 0.8;
+/// Correlation strong enough to call out
+const NOTABLE_CORRELATION: f64 = 0.3;
+/// Grade gap (points) between on- and off-plan exams worth calling out
+const NOTABLE_GRADE_GAP: f64 = 5.0;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ExamOutcome {
+    pub exam_id: i64,
+    pub course_id: i64,
+    pub course_name: String,
+    pub title: String,
+    pub exam_date: String,
+    /// Percent
+    pub grade: f64,
+    pub score: Option<f64>,
+    pub max_score: Option<f64>,
+    /// Study hours logged for the course in the preparation window
+    pub prep_hours: f64,
+    /// Accepted or locked study/prep blocks for the course in the same window
+    pub planned_prep_hours: f64,
+    /// `prep_hours / planned_prep_hours`; None when nothing was planned
+    pub adherence: Option<f64>,
+    pub post_mortem: Option<String>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SemesterReview {
+    /// None when there are no terms and active courses were reviewed
+    pub term_id: Option<i64>,
+    pub term_name: Option<String>,
+    pub exams: Vec<ExamOutcome>,
+    pub average_grade: Option<f64>,
+    pub average_prep_hours: Option<f64>,
+    /// Pearson correlation of prep hours with grade
+    pub prep_grade_correlation: Option<f64>,
+    /// Average grade where at least `ON_PLAN_ADHERENCE` of the planned prep happened
+    pub on_plan_average_grade: Option<f64>,
+    pub off_plan_average_grade: Option<f64>,
+    pub insights: Vec<String>,
+}
+
+/// (exam id, course id, course name, title, exam date, grade, score, max score,
+/// post-mortem, prep minutes, planned prep minutes)
+type ExamRow = (
+    i64,
+    i64,
+    String,
+    String,
+    String,
+    f64,
+    Option<f64>,
+    Option<f64>,
+    Option<String>,
+    i64,
+    f64,
+);
+
+/// Exam outcomes for a term (default: the current one)
+#[tauri::command]
+pub async fn get_semester_review(state: State<'_, DbState>, term_id: Option<i64>) -> Result<SemesterReview, ApiError> {
+    let pool = &state.0;
+    semester_review(pool, term_id).await
+}
+
+pub(crate) async fn semester_review(pool: &Pool<Sqlite>, term_id: Option<i64>) -> Result<SemesterReview, ApiError> {
+    let term: Option<(i64, String)> = match term_id {
+        Some(id) => Some(
+            sqlx::query_as("SELECT id, name FROM terms WHERE id = ?")
+                .bind(id)
+                .fetch_optional(pool)
+                .await
+                .map_err(ApiError::from)?
+                .ok_or_else(|| ApiError::not_found("Term not found"))?,
+        ),
+        None => sqlx::query_as("SELECT id, name FROM terms WHERE is_current = 1 ORDER BY id DESC LIMIT 1")
+            .fetch_optional(pool)
+            .await
+            .map_err(ApiError::from)?,
+    };
+
+    let rows: Vec<ExamRow> = sqlx::query_as(
+        r#"
+        SELECT e.id, e.course_id, c.name, e.title, date(e.exam_date), e.grade, e.score, e.max_score, e.post_mortem,
+               (SELECT COALESCE(SUM(s.duration_minutes), 0) FROM sessions s
+                WHERE s.session_type = 'study' AND s.reference_type = 'course' AND s.reference_id = e.course_id
+                  AND s.started_at < datetime(e.exam_date) AND s.started_at >= datetime(e.exam_date, ?1)),
+               (SELECT COALESCE(SUM((julianday(b.end_at) - julianday(b.start_at)) * 1440.0), 0.0) FROM week_plan_blocks b
+                WHERE b.block_type IN ('study', 'exam_prep') AND b.status IN ('accepted', 'locked')
+                  AND b.course_id = e.course_id
+                  AND datetime(b.start_at) < datetime(e.exam_date) AND datetime(b.start_at) >= datetime(e.exam_date, ?1))
+        FROM exams e
+        JOIN courses c ON c.id = e.course_id
+        WHERE e.grade IS NOT NULL AND e.exam_date IS NOT NULL
+          AND CASE WHEN ?2 IS NULL THEN c.is_active = 1 ELSE c.term_id = ?2 END
+        ORDER BY e.exam_date, e.id
+        "#,
+    )
+    .bind(format!("-{} days", PREP_WINDOW_DAYS))
+    .bind(term.as_ref().map(|(id, _)| *id))
+    .fetch_all(pool)
+    .await
+    .map_err(ApiError::from)?;
+
+    let exams: Vec<ExamOutcome> = rows
+        .into_iter()
+        .map(
+            |(exam_id, course_id, course_name, title, exam_date, grade, score, max_score, post_mortem, prep, planned)| {
+                let prep_hours = prep as f64 / 60.0;
+                let planned_prep_hours = planned / 60.0;
+                ExamOutcome {
+                    exam_id,
+                    course_id,
+                    course_name,
+                    title,
+                    exam_date,
+                    grade,
+                    score,
+                    max_score,
+                    prep_hours: round1(prep_hours),
+                    planned_prep_hours: round1(planned_prep_hours),
+                    adherence: (planned_prep_hours > 0.0)
+                        .then(|| (prep_hours / planned_prep_hours * 100.0).round() / 100.0),
+                    post_mortem,
+                }
+            },
+        )
+        .collect();
+
+    let (on_plan, off_plan): (Vec<&ExamOutcome>, Vec<&ExamOutcome>) = exams
+        .iter()
+        .filter(|e| e.adherence.is_some())
+        .partition(|e| e.adherence.unwrap_or(0.0) >= ON_PLAN_ADHERENCE);
+    let on_plan_average_grade = mean(on_plan.iter().map(|e| e.grade)).map(round1);
+    let off_plan_average_grade = mean(off_plan.iter().map(|e| e.grade)).map(round1);
+    let prep_grade_correlation = correlation(&exams).map(|r| (r * 100.0).round() / 100.0);
+
+    let mut insights = Vec::new();
+    match prep_grade_correlation {
+        Some(r) if r >= NOTABLE_CORRELATION => {
+            insights.push(format!("Exams you prepared longer for went better (r = {:.2})", r))
+        }
+        Some(r) if r <= -NOTABLE_CORRELATION => insights.push(format!(
+            "More prep hours did not mean better grades (r = {:.2}); look at how you study, not just how long",
+            r
+        )),
+        Some(_) => insights.push("Prep hours and grades were only loosely related this term".to_string()),
+        None => {}
+    }
+    if let (Some(on), Some(off)) = (on_plan_average_grade, off_plan_average_grade) {
+        if on - off >= NOTABLE_GRADE_GAP {
+            insights.push(format!(
+                "Sticking to the prep plan was worth {:.0} points on average ({:.0}% vs {:.0}%)",
+                on - off,
+                on,
+                off
+            ));
+        } else if off - on >= NOTABLE_GRADE_GAP {
+            insights.push(format!(
+                "Exams where you went off-plan scored {:.0} points higher; the plan may be over-budgeting prep",
+                off - on
+            ));
+        }
+    }
+    let missing_post_mortems = exams.iter().filter(|e| e.post_mortem.is_none()).count();
+    if missing_post_mortems > 0 {
+        insights.push(format!("{} graded exam(s) have no post-mortem yet", missing_post_mortems));
+    }
+
+    Ok(SemesterReview {
+        term_id: term.as_ref().map(|(id, _)| *id),
+        term_name: term.map(|(_, name)| name),
+        average_grade: mean(exams.iter().map(|e| e.grade)).map(round1),
+        average_prep_hours: mean(exams.iter().map(|e| e.prep_hours)).map(round1),
+        prep_grade_correlation,
+        on_plan_average_grade,
+        off_plan_average_grade,
+        insights,
+        exams,
+    })
+}
+
+fn round1(value: f64) -> f64 {
+    (value * 10.0).round() / 10.0
+}
+
+fn mean(values: impl Iterator<Item = f64>) -> Option<f64> {
+    let (sum, n) = values.fold((0.0, 0usize), |(sum, n), v| (sum + v, n + 1));
+    (n > 0).then(|| sum / n as f64)
+}
+
+/// Pearson correlation of prep hours with grade
+fn correlation(exams: &[ExamOutcome]) -> Option<f64> {
+    if exams.len() < MIN_EXAMS_FOR_CORRELATION {
+        return None;
+    }
+    let n = exams.len() as f64;
+    let mean_x = exams.iter().map(|e| e.prep_hours).sum::<f64>() / n;
+    let mean_y = exams.iter().map(|e| e.grade).sum::<f64>() / n;
+    let sxx: f64 = exams.iter().map(|e| (e.prep_hours - mean_x).powi(2)).sum();
+    let syy: f64 = exams.iter().map(|e| (e.grade - mean_y).powi(2)).sum();
+    if sxx < 1e-9 || syy < 1e-9 {
+        return None;
+    }
+    let sxy: f64 = exams
+        .iter()
+        .map(|e| (e.prep_hours - mean_x) * (e.grade - mean_y))
+        .sum();
+    Some(sxy / (sxx * syy).sqrt())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::exams::{record_result, ExamResultInput};
+    use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+    use std::str::FromStr;
+
+    async fn setup_pool_with_migrations() -> Pool<Sqlite> {
+        let options = SqliteConnectOptions::from_str("sqlite::memory:")
+            .unwrap()
+            .foreign_keys(false);
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect_with(options)
+            .await
+            .expect("Failed to connect to in-memory DB");
+
+        crate::db::migrations::run_migrations(&pool)
+            .await
+            .expect("Failed to run migrations");
+
+        pool
+    }
+
+    async fn course(pool: &Pool<Sqlite>, name: &str, term_id: i64) -> i64 {
+        sqlx::query_scalar("INSERT INTO courses (name, term_id) VALUES (?, ?) RETURNING id")
+            .bind(name)
+            .bind(term_id)
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    /// An exam on `date` with `studied` and `planned` minutes of prep the day before
+    async fn exam(pool: &Pool<Sqlite>, course_id: i64, date: &str, studied: i64, planned: i64) -> i64 {
+        let id: i64 = sqlx::query_scalar("INSERT INTO exams (course_id, title, exam_date) VALUES (?, 'Midterm', ?) RETURNING id")
+            .bind(course_id)
+            .bind(format!("{} 09:00:00", date))
+            .fetch_one(pool)
+            .await
+            .unwrap();
+        sqlx::query(
+            "INSERT INTO sessions (session_type, reference_id, reference_type, started_at, duration_minutes) VALUES ('study', ?, 'course', datetime(?, '-1 day'), ?)",
+        )
+        .bind(course_id)
+        .bind(format!("{} 09:00:00", date))
+        .bind(studied)
+        .execute(pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            r#"INSERT INTO week_plan_blocks (week_start_date, start_at, end_at, block_type, course_id, status)
+               VALUES (?1, datetime(?2, '-1 day'), datetime(?2, '-1 day', ?3), 'exam_prep', ?4, 'accepted')"#,
+        )
+        .bind(date)
+        .bind(format!("{} 09:00:00", date))
+        .bind(format!("+{} minutes", planned))
+        .bind(course_id)
+        .execute(pool)
+        .await
+        .unwrap();
+        id
+    }
+
+    #[tokio::test]
+    async fn review_links_prep_and_plan_adherence_to_grades() {
+        let pool = setup_pool_with_migrations().await;
+        sqlx::query("INSERT INTO terms (id, name, is_current) VALUES (1, 'Fall', 0), (2, 'Spring', 1)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let old = course(&pool, "History", 1).await;
+        let math = course(&pool, "Math", 2).await;
+        let physics = course(&pool, "Physics", 2).await;
+
+        let old_exam = exam(&pool, old, "2025-10-01", 60, 60).await;
+        record_result(&pool, old_exam, ExamResultInput { score: 50.0, max_score: None, post_mortem: None })
+            .await
+            .unwrap();
+
+        // Followed the plan and did well; skipped it and did badly
+        let a = exam(&pool, math, "2026-03-10", 240, 240).await;
+        let b = exam(&pool, physics, "2026-03-12", 60, 300).await;
+        let c = exam(&pool, math, "2026-04-20", 360, 300).await;
+        let recorded = record_result(
+            &pool,
+            a,
+            ExamResultInput { score: 45.0, max_score: Some(50.0), post_mortem: Some("  Practice tests helped ".to_string()) },
+        )
+        .await
+        .unwrap();
+        assert_eq!(recorded.grade, Some(90.0));
+        assert_eq!(recorded.post_mortem.as_deref(), Some("Practice tests helped"));
+        record_result(&pool, b, ExamResultInput { score: 60.0, max_score: None, post_mortem: None })
+            .await
+            .unwrap();
+        record_result(&pool, c, ExamResultInput { score: 95.0, max_score: None, post_mortem: Some("Solid".to_string()) })
+            .await
+            .unwrap();
+        assert!(record_result(&pool, c, ExamResultInput { score: -1.0, max_score: None, post_mortem: None })
+            .await
+            .is_err());
+
+        let review = semester_review(&pool, None).await.unwrap();
+        assert_eq!(review.term_name.as_deref(), Some("Spring"));
+        assert_eq!(review.exams.len(), 3);
+        let first = &review.exams[0];
+        assert_eq!((first.prep_hours, first.planned_prep_hours, first.adherence), (4.0, 4.0, Some(1.0)));
+        assert_eq!(review.exams[1].adherence, Some(0.2));
+        assert_eq!(review.on_plan_average_grade, Some(92.5));
+        assert_eq!(review.off_plan_average_grade, Some(60.0));
+        assert!(review.prep_grade_correlation.unwrap() > NOTABLE_CORRELATION);
+        assert!(review.insights.iter().any(|i| i.starts_with("Sticking to the prep plan")));
+        assert!(review.insights.iter().any(|i| i.starts_with("1 graded exam")));
+
+        let fall = semester_review(&pool, Some(1)).await.unwrap();
+        assert_eq!(fall.exams.len(), 1);
+        assert_eq!(fall.prep_grade_correlation, None);
+        assert!(semester_review(&pool, Some(99)).await.is_err());
+    }
+}
@@ -0,0 +1,22 @@
+//! Session type commands
+
+use tauri::State;
+
+use crate::services::session_types::{self, SessionTypeDef, SessionTypeInput};
+use crate::{error::ApiError, DbState};
+
+#[tauri::command]
+pub async fn get_session_types(state: State<'_, DbState>) -> Result<Vec<SessionTypeDef>, ApiError> {
+    session_types::list(&state.0).await
+}
+
+#[tauri::command]
+pub async fn create_session_type(state: State<'_, DbState>, data: SessionTypeInput) -> Result<SessionTypeDef, ApiError> {
+    session_types::create(&state.0, &data).await
+}
+
+/// Delete a custom type; fails for built-in types and types with sessions
+#[tauri::command]
+pub async fn delete_session_type(state: State<'_, DbState>, name: String) -> Result<bool, ApiError> {
+    session_types::delete(&state.0, &name).await
+}
@@ -1,9 +1,14 @@
+use sqlx::{Pool, Sqlite};
 use tauri::State;
 
 use crate::{
     DbState,
+    agent::outcomes::link_session_outcome,
+    commands::{focus_profiles, week_plan_blocks},
+    db::filter::FilteredQuery,
     error::ApiError,
     models::session::{Session, SessionType},
+    services::{aggregates, dedupe, events::{self, ActivityEvent}, pomodoro::{self, Interruption, InterruptionReason}, session_types},
 };
 
 #[derive(Debug, serde::Deserialize)]
@@ -14,13 +19,40 @@ pub struct SessionInput {
     pub reference_type: Option<String>,
     pub started_at: Option<String>,
     pub notes: Option<String>,
+    pub planned_minutes: Option<i64>,
+    /// Focus profile the session runs under; its work length is the default plan
+    #[serde(default)]
+    pub focus_profile_id: Option<i64>,
+    #[serde(default)]
+    pub title: Option<String>,
+    /// Only set by `start_meeting_session`, which checks the occurrence
+    #[serde(skip)]
+    pub course_meeting_id: Option<i64>,
+    #[serde(skip)]
+    pub meeting_date: Option<String>,
+    /// Only set by `start_session_from_block`
+    #[serde(skip)]
+    pub week_plan_block_id: Option<i64>,
 }
 
+/// Start a session. A matching session already running at the start time
+/// fails with a conflict, unless `force` is set.
 #[tauri::command]
-pub async fn start_session(state: State<'_, DbState>, data: SessionInput) -> Result<Session, ApiError> {
-    let pool = &state.0;
+pub async fn start_session(state: State<'_, DbState>, data: SessionInput, force: Option<bool>) -> Result<Session, ApiError> {
+    if !force.unwrap_or(false) {
+        dedupe::check_session(&state.0, &data).await?;
+    }
+    insert_session(&state.0, &data).await
+}
+
+pub(crate) async fn insert_session(pool: &Pool<Sqlite>, data: &SessionInput) -> Result<Session, ApiError> {
+    session_types::ensure_registered(pool, &data.session_type).await?;
+    let planned_minutes = match data.focus_profile_id {
+        Some(id) => Some(data.planned_minutes.unwrap_or(focus_profiles::find(pool, id).await?.work_minutes)),
+        None => data.planned_minutes,
+    };
     let rec = sqlx::query_as::<_, Session>(
-        "INSERT INTO sessions (user_id, session_type, reference_id, reference_type, started_at, notes) VALUES (?, ?, ?, ?, COALESCE(?, CURRENT_TIMESTAMP), ?) RETURNING id, user_id, session_type, reference_id, reference_type, started_at, ended_at, duration_minutes, notes"
+        "INSERT INTO sessions (user_id, session_type, reference_id, reference_type, started_at, notes, planned_minutes, focus_profile_id, title, course_meeting_id, meeting_date, week_plan_block_id) VALUES (?, ?, ?, ?, COALESCE(?, CURRENT_TIMESTAMP), ?, ?, ?, ?, ?, ?, ?) RETURNING id, user_id, session_type, reference_id, reference_type, started_at, ended_at, duration_minutes, notes, planned_minutes, focus_rating, focus_profile_id, title, course_meeting_id, meeting_date, week_plan_block_id"
     )
     .bind(data.user_id.unwrap_or(1))
     .bind(&data.session_type)
@@ -28,39 +60,91 @@ pub async fn start_session(state: State<'_, DbState>, data: SessionInput) -> Res
     .bind(&data.reference_type)
     .bind(&data.started_at)
     .bind(&data.notes)
+    .bind(planned_minutes)
+    .bind(data.focus_profile_id)
+    .bind(&data.title)
+    .bind(data.course_meeting_id)
+    .bind(&data.meeting_date)
+    .bind(data.week_plan_block_id)
     .fetch_one(pool)
     .await
     .map_err(ApiError::from)?;
+    aggregates::invalidate();
     Ok(rec)
 }
 
+/// End a session and report its outcome to the agent
+///
+/// `focus_rating` (1-5) is optional; the outcome is only reported the first
+/// time a session is ended.
 #[tauri::command]
-pub async fn end_session(state: State<'_, DbState>, id: i64) -> Result<Session, ApiError> {
-    let pool = &state.0;
+pub async fn end_session(state: State<'_, DbState>, id: i64, focus_rating: Option<i64>) -> Result<Session, ApiError> {
+    close_session(&state.0, id, focus_rating).await
+}
+
+pub(crate) async fn close_session(pool: &Pool<Sqlite>, id: i64, focus_rating: Option<i64>) -> Result<Session, ApiError> {
+    if let Some(rating) = focus_rating {
+        if !(1..=5).contains(&rating) {
+            return Err(ApiError::validation("Focus rating must be between 1 and 5"));
+        }
+    }
+
+    let was_open: Option<bool> = sqlx::query_scalar("SELECT ended_at IS NULL FROM sessions WHERE id = ?")
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+        .map_err(ApiError::from)?;
+    let Some(was_open) = was_open else {
+        return Err(ApiError::not_found("Session not found"));
+    };
+
     let rec = sqlx::query_as::<_, Session>(
-        "UPDATE sessions SET ended_at = COALESCE(ended_at, CURRENT_TIMESTAMP), duration_minutes = CAST((strftime('%s', COALESCE(ended_at, CURRENT_TIMESTAMP)) - strftime('%s', started_at)) / 60 AS INTEGER) WHERE id = ? RETURNING id, user_id, session_type, reference_id, reference_type, started_at, ended_at, duration_minutes, notes"
+        "UPDATE sessions SET ended_at = COALESCE(ended_at, CURRENT_TIMESTAMP), duration_minutes = CAST((strftime('%s', COALESCE(ended_at, CURRENT_TIMESTAMP)) - strftime('%s', started_at)) / 60 AS INTEGER), focus_rating = COALESCE(?, focus_rating) WHERE id = ? RETURNING id, user_id, session_type, reference_id, reference_type, started_at, ended_at, duration_minutes, notes, planned_minutes, focus_rating, focus_profile_id, title, course_meeting_id, meeting_date, week_plan_block_id"
     )
+    .bind(focus_rating)
     .bind(id)
     .fetch_one(pool)
     .await
     .map_err(ApiError::from)?;
+
+    if was_open {
+        // Actual vs planned length of a block-started session, for adherence
+        if let Some(block_id) = rec.week_plan_block_id {
+            week_plan_blocks::finish_block(pool, block_id, rec.duration_minutes).await?;
+        }
+        let interruptions = pomodoro::interruption_count(pool, rec.id).await?;
+        link_session_outcome(pool, &rec, interruptions);
+        aggregates::invalidate();
+        let counts_toward = session_types::counts_toward(pool, &rec.session_type).await?;
+        events::publish(ActivityEvent::SessionEnded { session_id: rec.id, counts_toward });
+    }
     Ok(rec)
 }
 
+/// Log an interruption of a running session, e.g. a pomodoro
+#[tauri::command]
+pub async fn log_session_interruption(
+    state: State<'_, DbState>,
+    session_id: i64,
+    reason: InterruptionReason,
+    note: Option<String>,
+) -> Result<Interruption, ApiError> {
+    pomodoro::log_interruption(&state.0, session_id, reason, note.as_deref()).await
+}
+
 #[tauri::command]
 pub async fn get_sessions(state: State<'_, DbState>, reference_id: Option<i64>, reference_type: Option<String>) -> Result<Vec<Session>, ApiError> {
     let pool = &state.0;
-    let rows = match (reference_id, reference_type) {
-        (Some(id), Some(rtype)) => sqlx::query_as::<_, Session>("SELECT * FROM sessions WHERE reference_id = ? AND reference_type = ? ORDER BY started_at DESC")
-            .bind(id)
-            .bind(&rtype)
-            .fetch_all(pool)
-            .await
-            .map_err(ApiError::from)?,
-        _ => sqlx::query_as::<_, Session>("SELECT * FROM sessions ORDER BY started_at DESC")
-            .fetch_all(pool)
-            .await
-            .map_err(ApiError::from)?,
-    };
+    // The reference only filters when both halves are given
+    let (reference_id, reference_type) = reference_id.zip(reference_type).unzip();
+    let mut query = FilteredQuery::new("SELECT * FROM sessions")
+        .eq("reference_id", reference_id)
+        .eq("reference_type", reference_type)
+        .order_by("started_at DESC");
+    let rows = query
+        .build_query_as::<Session>()
+        .fetch_all(pool)
+        .await
+        .map_err(ApiError::from)?;
     Ok(rows)
 }
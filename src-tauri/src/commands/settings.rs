@@ -0,0 +1,34 @@
+//! Settings commands
+//!
+//! Generic access to the typed settings in `services::settings`. Changes made
+//! here are also broadcast to the frontend as `settings-changed` events.
+
+use serde_json::Value;
+use tauri::State;
+
+use crate::services::settings::{self, SettingDef, SETTINGS};
+use crate::{error::ApiError, DbState};
+
+/// Get one setting (its default when unset)
+#[tauri::command]
+pub async fn get_setting(state: State<'_, DbState>, key: String) -> Result<Value, ApiError> {
+    settings::get(&state.0, &key).await
+}
+
+/// Validate and store one setting; returns the stored (normalized) value
+#[tauri::command]
+pub async fn set_setting(state: State<'_, DbState>, key: String, value: Value) -> Result<Value, ApiError> {
+    settings::set(&state.0, &key, value).await
+}
+
+/// All settings with their current values
+#[tauri::command]
+pub async fn get_settings(state: State<'_, DbState>) -> Result<serde_json::Map<String, Value>, ApiError> {
+    settings::get_all(&state.0).await
+}
+
+/// Declared settings with their types, bounds and defaults
+#[tauri::command]
+pub async fn get_settings_schema() -> Result<Vec<SettingDef>, ApiError> {
+    Ok(SETTINGS.to_vec())
+}
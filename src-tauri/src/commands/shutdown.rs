@@ -0,0 +1,477 @@
+//! Daily Shutdown
+//!
+//! One call that ends the day: closes running sessions, applies the user's
+//! decision on unfinished Big Three goals (or returns them so the UI can ask),
+//! puts tomorrow's check-in reminder on the calendar, suggests some reading
+//! when none was done, asks for the spending check-in while that habit is
+//! active, summarizes the day and records it as an end-of-day memory event.
+//!
+//! Running it again the same day is safe: the reminder and the memory event
+//! are updated rather than duplicated.
+
+use chrono::{Duration, Local, NaiveTime, TimeZone};
+use serde::{Deserialize, Serialize};
+use sqlx::{Pool, Sqlite};
+use tauri::State;
+
+use crate::agent::{BigThreeGoal, IntelligenceAgent};
+use crate::commands::sessions::close_session;
+use crate::ml::SemanticMemory;
+use crate::models::calendar_event::CalendarEvent;
+use crate::models::session::Session;
+use crate::services::{reading, settings, spending, working_hours::Schedule};
+use crate::{error::ApiError, DbState};
+
+const MEMORY_EVENT_TYPE: &str = "daily_shutdown";
+const REMINDER_TITLE: &str = "Morning check-in";
+const REMINDER_MINUTES: i64 = 10;
+
+/// What to do with an unfinished Big Three goal
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BigThreeDisposition {
+    /// It was done after all
+    Complete,
+    /// Make it one of tomorrow's Big Three
+    CarryForward,
+    /// Let it go
+    Drop,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct BigThreeDecision {
+    pub goal_id: i64,
+    pub disposition: BigThreeDisposition,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct ShutdownInput {
+    #[serde(default)]
+    pub big_three: Vec<BigThreeDecision>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DaySummary {
+    pub date: String,
+    pub study_minutes: i64,
+    pub sessions: i64,
+    pub practice_minutes: i64,
+    pub workouts: i64,
+    pub assignments_completed: i64,
+    pub big_three_completed: i64,
+    pub big_three_total: i64,
+    pub mood: Option<i64>,
+    pub energy: Option<i64>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct ShutdownResult {
+    pub closed_sessions: Vec<Session>,
+    /// Unfinished goals not decided in this call; ask the user and call again
+    pub pending_big_three: Vec<BigThreeGoal>,
+    pub carried_forward: Vec<BigThreeGoal>,
+    pub checkin_reminder: CalendarEvent,
+    /// Set when a book is in progress and nothing was read today
+    pub reading_suggestion: Option<String>,
+    /// Set while spending check-ins are kept up and today's is missing
+    pub spending_prompt: Option<String>,
+    pub summary: DaySummary,
+    pub memory_event_id: i64,
+}
+
+/// Run the end-of-day routine
+#[tauri::command]
+pub async fn run_daily_shutdown(
+    state: State<'_, DbState>,
+    data: Option<ShutdownInput>,
+) -> Result<ShutdownResult, ApiError> {
+    let pool = &state.0;
+    let (result, new_event) = shutdown(pool, data.unwrap_or_default()).await?;
+
+    // The vector store is optional (needs the embedding model); the SQL row is
+    // the record of the day
+    if new_event {
+        let id = result.memory_event_id;
+        let content = summary_text(&result.summary);
+        tauri::async_runtime::spawn(async move {
+            let memory = match SemanticMemory::global().await {
+                Ok(memory) => memory,
+                Err(e) => {
+                    log::warn!("Semantic memory unavailable for daily shutdown: {}", e);
+                    return;
+                }
+            };
+            if let Err(e) = memory
+                .add_event(id, &Local::now().to_rfc3339(), MEMORY_EVENT_TYPE, &content, None, None)
+                .await
+            {
+                log::warn!("Failed to add daily shutdown to semantic memory: {}", e);
+            }
+        });
+    }
+
+    Ok(result)
+}
+
+/// Returns the result and whether today's memory event was newly created
+pub(crate) async fn shutdown(pool: &Pool<Sqlite>, data: ShutdownInput) -> Result<(ShutdownResult, bool), ApiError> {
+    let open: Vec<i64> = sqlx::query_scalar("SELECT id FROM sessions WHERE ended_at IS NULL ORDER BY started_at")
+        .fetch_all(pool)
+        .await
+        .map_err(ApiError::from)?;
+    let mut closed_sessions = Vec::with_capacity(open.len());
+    for id in open {
+        closed_sessions.push(close_session(pool, id, None).await?);
+    }
+
+    let today = IntelligenceAgent::get_big_three(pool).await.map_err(ApiError::internal)?;
+    let carried_forward = apply_decisions(pool, &today, &data.big_three).await?;
+    let pending_big_three = IntelligenceAgent::get_big_three(pool)
+        .await
+        .map_err(ApiError::internal)?
+        .into_iter()
+        .filter(|g| !g.is_completed && !data.big_three.iter().any(|d| d.goal_id == g.id))
+        .collect();
+
+    let checkin_reminder = schedule_checkin_reminder(pool).await?;
+    let reading_suggestion = reading::evening_suggestion(pool, Local::now().date_naive()).await?;
+    let spending_prompt = spending::evening_prompt(pool, Local::now().date_naive()).await?;
+    let summary = day_summary(pool).await?;
+    let (memory_event_id, new_event) = record_memory_event(pool, &summary).await?;
+
+    Ok((
+        ShutdownResult {
+            closed_sessions,
+            pending_big_three,
+            carried_forward,
+            checkin_reminder,
+            reading_suggestion,
+            spending_prompt,
+            summary,
+            memory_event_id,
+        },
+        new_event,
+    ))
+}
+
+/// Apply decisions on today's goals; returns tomorrow's carried goals
+async fn apply_decisions(
+    pool: &Pool<Sqlite>,
+    today: &[BigThreeGoal],
+    decisions: &[BigThreeDecision],
+) -> Result<Vec<BigThreeGoal>, ApiError> {
+    let tomorrow = (Local::now().date_naive() + Duration::days(1)).format("%Y-%m-%d").to_string();
+    let mut carried = Vec::new();
+
+    for decision in decisions {
+        let Some(goal) = today.iter().find(|g| g.id == decision.goal_id) else {
+            return Err(ApiError::not_found(format!(
+                "Big Three goal {} is not one of today's goals",
+                decision.goal_id
+            )));
+        };
+        if goal.is_completed {
+            continue;
+        }
+
+        match decision.disposition {
+            BigThreeDisposition::Complete => {
+                IntelligenceAgent::complete_big_three(pool, goal.id, None)
+                    .await
+                    .map_err(ApiError::internal)?;
+            }
+            BigThreeDisposition::Drop => {}
+            BigThreeDisposition::CarryForward => {
+                let taken: Vec<(i32, String)> =
+                    sqlx::query_as("SELECT priority, title FROM agent_big_three WHERE date = ?")
+                        .bind(&tomorrow)
+                        .fetch_all(pool)
+                        .await
+                        .map_err(ApiError::from)?;
+                // Already carried by an earlier run
+                if taken.iter().any(|(_, title)| title == &goal.title) {
+                    continue;
+                }
+                let Some(priority) = (1..=3).find(|p| !taken.iter().any(|(taken, _)| taken == p)) else {
+                    return Err(ApiError::conflict(format!(
+                        "Tomorrow already has three goals; can't carry forward \"{}\"",
+                        goal.title
+                    )));
+                };
+                let id: i64 = sqlx::query_scalar(
+                    r#"
+                    INSERT INTO agent_big_three (date, priority, title, description, category)
+                    VALUES (?, ?, ?, ?, ?)
+                    RETURNING id
+                    "#,
+                )
+                .bind(&tomorrow)
+                .bind(priority)
+                .bind(&goal.title)
+                .bind(&goal.description)
+                .bind(&goal.category)
+                .fetch_one(pool)
+                .await
+                .map_err(ApiError::from)?;
+                carried.push(BigThreeGoal {
+                    id,
+                    priority,
+                    ..goal.clone()
+                });
+            }
+        }
+    }
+
+    Ok(carried)
+}
+
+/// Calendar event at `checkin_reminder_hour` tomorrow (or when the blackout
+/// window it falls in ends), created once
+async fn schedule_checkin_reminder(pool: &Pool<Sqlite>) -> Result<CalendarEvent, ApiError> {
+    let hour = settings::get_i64(pool, "checkin_reminder_hour").await?;
+    let tomorrow = Local::now().date_naive() + Duration::days(1);
+    let time = NaiveTime::from_hms_opt(hour as u32, 0, 0)
+        .ok_or_else(|| ApiError::internal("Invalid check-in reminder hour"))?;
+    let at = Schedule::load(pool).await?.quiet_until(tomorrow.and_time(time));
+    let start = Local
+        .from_local_datetime(&at)
+        .earliest()
+        .ok_or_else(|| ApiError::internal("Check-in reminder time doesn't exist locally"))?;
+    let start_at = start.to_rfc3339();
+    let end_at = (start + Duration::minutes(REMINDER_MINUTES)).to_rfc3339();
+
+    let existing = sqlx::query_as::<_, CalendarEvent>(
+        "SELECT * FROM calendar_events WHERE title = ? AND domain = 'wellness' AND rrule IS NULL AND date(start_at) = ?",
+    )
+    .bind(REMINDER_TITLE)
+    .bind(at.date().format("%Y-%m-%d").to_string())
+    .fetch_optional(pool)
+    .await
+    .map_err(ApiError::from)?;
+    if let Some(event) = existing {
+        return Ok(event);
+    }
+
+    sqlx::query_as::<_, CalendarEvent>(
+        r#"
+        INSERT INTO calendar_events (title, start_at, end_at, category, domain, notes)
+        VALUES (?, ?, ?, 'personal', 'wellness', 'Scheduled by the daily shutdown')
+        RETURNING *
+        "#,
+    )
+    .bind(REMINDER_TITLE)
+    .bind(&start_at)
+    .bind(&end_at)
+    .fetch_one(pool)
+    .await
+    .map_err(ApiError::from)
+}
+
+async fn day_summary(pool: &Pool<Sqlite>) -> Result<DaySummary, ApiError> {
+    let (study_minutes, sessions): (i64, i64) = sqlx::query_as(
+        r#"
+        SELECT COALESCE(SUM(CASE WHEN session_type = 'study' THEN duration_minutes ELSE 0 END), 0), COUNT(*)
+        FROM sessions
+        WHERE date(started_at, 'localtime') = date('now', 'localtime')
+        "#,
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(ApiError::from)?;
+
+    let practice_minutes: i64 = sqlx::query_scalar(
+        "SELECT COALESCE(SUM(duration_minutes), 0) FROM practice_logs WHERE date(logged_at, 'localtime') = date('now', 'localtime')",
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(ApiError::from)?;
+
+    let workouts: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM workouts WHERE date(logged_at, 'localtime') = date('now', 'localtime')",
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(ApiError::from)?;
+
+    let assignments_completed: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM assignments WHERE is_completed = 1 AND date(completed_at, 'localtime') = date('now', 'localtime')",
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(ApiError::from)?;
+
+    let date = Local::now().format("%Y-%m-%d").to_string();
+    let (big_three_completed, big_three_total): (i64, i64) = sqlx::query_as(
+        "SELECT COALESCE(SUM(is_completed), 0), COUNT(*) FROM agent_big_three WHERE date = ?",
+    )
+    .bind(&date)
+    .fetch_one(pool)
+    .await
+    .map_err(ApiError::from)?;
+
+    let checkin: Option<(Option<i64>, Option<i64>)> = sqlx::query_as(
+        r#"
+        SELECT mood, energy FROM check_ins
+        WHERE date(checked_in_at, 'localtime') = date('now', 'localtime')
+        ORDER BY checked_in_at DESC LIMIT 1
+        "#,
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(ApiError::from)?;
+    let (mood, energy) = checkin.unwrap_or((None, None));
+
+    Ok(DaySummary {
+        date,
+        study_minutes,
+        sessions,
+        practice_minutes,
+        workouts,
+        assignments_completed,
+        big_three_completed,
+        big_three_total,
+        mood,
+        energy,
+    })
+}
+
+fn summary_text(summary: &DaySummary) -> String {
+    let mut parts = vec![
+        format!("{} min study over {} sessions", summary.study_minutes, summary.sessions),
+        format!("{} min practice", summary.practice_minutes),
+        format!("{} workouts", summary.workouts),
+        format!("{} assignments done", summary.assignments_completed),
+        format!("Big Three {}/{}", summary.big_three_completed, summary.big_three_total),
+    ];
+    if let Some(mood) = summary.mood {
+        parts.push(format!("mood {}/10", mood));
+    }
+    format!("Day shutdown {}: {}", summary.date, parts.join(", "))
+}
+
+/// Upsert today's end-of-day event; the outcome is the Big Three completion
+async fn record_memory_event(pool: &Pool<Sqlite>, summary: &DaySummary) -> Result<(i64, bool), ApiError> {
+    let content = summary_text(summary);
+    let metadata = serde_json::to_string(summary).map_err(|e| ApiError::internal(e.to_string()))?;
+    let outcome = (summary.big_three_total > 0)
+        .then(|| summary.big_three_completed as f64 / summary.big_three_total as f64);
+
+    let existing: Option<i64> = sqlx::query_scalar(
+        "SELECT id FROM agent_memory_events WHERE event_type = ? AND date(timestamp, 'localtime') = date('now', 'localtime')",
+    )
+    .bind(MEMORY_EVENT_TYPE)
+    .fetch_optional(pool)
+    .await
+    .map_err(ApiError::from)?;
+
+    if let Some(id) = existing {
+        sqlx::query("UPDATE agent_memory_events SET content = ?, metadata_json = ?, outcome_immediate = ? WHERE id = ?")
+            .bind(&content)
+            .bind(&metadata)
+            .bind(outcome)
+            .bind(id)
+            .execute(pool)
+            .await
+            .map_err(ApiError::from)?;
+        return Ok((id, false));
+    }
+
+    let id: i64 = sqlx::query_scalar(
+        "INSERT INTO agent_memory_events (event_type, content, metadata_json, outcome_immediate) VALUES (?, ?, ?, ?) RETURNING id",
+    )
+    .bind(MEMORY_EVENT_TYPE)
+    .bind(&content)
+    .bind(&metadata)
+    .bind(outcome)
+    .fetch_one(pool)
+    .await
+    .map_err(ApiError::from)?;
+    Ok((id, true))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+    use std::str::FromStr;
+
+    async fn setup_pool_with_migrations() -> Pool<Sqlite> {
+        let options = SqliteConnectOptions::from_str("sqlite::memory:")
+            .unwrap()
+            .foreign_keys(false);
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect_with(options)
+            .await
+            .expect("Failed to connect to in-memory DB");
+
+        crate::db::migrations::run_migrations(&pool)
+            .await
+            .expect("Failed to run migrations");
+
+        pool
+    }
+
+    #[tokio::test]
+    async fn shutdown_closes_sessions_and_is_repeatable() {
+        let pool = setup_pool_with_migrations().await;
+        sqlx::query(
+            "INSERT INTO sessions (session_type, started_at) VALUES ('study', datetime('now', '-50 minutes'))",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        let today = Local::now().format("%Y-%m-%d").to_string();
+        sqlx::query(
+            "INSERT INTO agent_big_three (date, priority, title, is_completed) VALUES (?, 1, 'Essay draft', 1), (?, 2, 'Call advisor', 0), (?, 3, 'Laundry', 0)",
+        )
+        .bind(&today)
+        .bind(&today)
+        .bind(&today)
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        // First pass: nothing decided yet, so both unfinished goals come back
+        let (first, new_event) = shutdown(&pool, ShutdownInput::default()).await.unwrap();
+        assert!(new_event);
+        assert_eq!(first.closed_sessions.len(), 1);
+        assert!(first.closed_sessions[0].ended_at.is_some());
+        assert_eq!(first.pending_big_three.len(), 2);
+        assert_eq!(first.summary.sessions, 1);
+        assert!(first.summary.study_minutes >= 49);
+        assert_eq!((first.summary.big_three_completed, first.summary.big_three_total), (1, 3));
+        let reminder_start = first.checkin_reminder.start_at.clone().unwrap();
+        assert!(reminder_start.contains("T08:00:00"));
+
+        let advisor = first.pending_big_three.iter().find(|g| g.title == "Call advisor").unwrap().id;
+        let laundry = first.pending_big_three.iter().find(|g| g.title == "Laundry").unwrap().id;
+        let decisions = vec![
+            BigThreeDecision { goal_id: advisor, disposition: BigThreeDisposition::CarryForward },
+            BigThreeDecision { goal_id: laundry, disposition: BigThreeDisposition::Complete },
+        ];
+        let (second, new_event) = shutdown(&pool, ShutdownInput { big_three: decisions.clone() }).await.unwrap();
+        assert!(!new_event);
+        assert!(second.closed_sessions.is_empty());
+        assert!(second.pending_big_three.is_empty());
+        assert_eq!(second.carried_forward.len(), 1);
+        assert_eq!(second.carried_forward[0].priority, 1);
+        assert_eq!(second.summary.big_three_completed, 2);
+        assert_eq!(second.checkin_reminder.id, first.checkin_reminder.id);
+        assert_eq!(second.memory_event_id, first.memory_event_id);
+
+        // Carrying the same goal again doesn't duplicate it
+        shutdown(&pool, ShutdownInput { big_three: decisions }).await.unwrap();
+        let tomorrow: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM agent_big_three WHERE date > ?")
+            .bind(&today)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(tomorrow, 1);
+        let events: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM agent_memory_events WHERE event_type = 'daily_shutdown'")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(events, 1);
+    }
+}
@@ -0,0 +1,368 @@
+//! Skill Portfolio Export
+//!
+//! A resume-ready summary of every skill: hours practiced (practice logs plus
+//! finished practice sessions), level, milestones and notable projects, written
+//! as JSON or Markdown for applications and self-review.
+//!
+//! Milestones are the level achievements already awarded plus the date the
+//! practice history first crossed each of `HOUR_MILESTONES`. Projects are
+//! picked from practice notes: notes mentioning finished work
+//! (`PROJECT_WORDS`) first, then the longest, up to `MAX_PROJECTS` a skill.
+
+use std::path::Path;
+
+use chrono::Duration;
+use serde::{Deserialize, Serialize};
+use sqlx::{Pool, Sqlite};
+use tauri::State;
+
+use crate::{commands::export::validate_path, error::ApiError, services::clock, DbState};
+
+const HOUR_MILESTONES: &[i64] = &[10, 25, 50, 100, 250, 500, 1000];
+/// Words that mark a practice note as describing finished work
+const PROJECT_WORDS: &[&str] = &[
+    "built", "finished", "completed", "shipped", "released", "published", "deployed", "performed", "wrote",
+    "project",
+];
+/// Notes shorter than this don't describe a project
+const MIN_PROJECT_NOTE_CHARS: usize = 20;
+const MAX_PROJECTS: usize = 5;
+/// Window for recent hours
+const RECENT_DAYS: i64 = 90;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PortfolioFormat {
+    Json,
+    Markdown,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PortfolioMilestone {
+    /// YYYY-MM-DD
+    pub date: String,
+    pub title: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PortfolioProject {
+    pub date: String,
+    pub note: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PortfolioSkill {
+    pub skill_id: i64,
+    pub name: String,
+    pub category: Option<String>,
+    pub description: Option<String>,
+    pub level: i64,
+    pub total_hours: f64,
+    /// Hours in the last `RECENT_DAYS` days
+    pub recent_hours: f64,
+    pub practice_count: i64,
+    pub first_practiced: Option<String>,
+    pub last_practiced: Option<String>,
+    /// Oldest first
+    pub milestones: Vec<PortfolioMilestone>,
+    pub projects: Vec<PortfolioProject>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SkillPortfolio {
+    pub generated_on: String,
+    pub total_hours: f64,
+    /// Most practiced first
+    pub skills: Vec<PortfolioSkill>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct SkillPortfolioExportResult {
+    pub path: String,
+    pub skills: usize,
+    pub total_hours: f64,
+}
+
+/// Write the skill portfolio to `path`; the format defaults to Markdown for
+/// `.md` files and JSON otherwise
+#[tauri::command]
+pub async fn export_skill_portfolio(
+    state: State<'_, DbState>,
+    path: String,
+    format: Option<PortfolioFormat>,
+    include_archived: Option<bool>,
+) -> Result<SkillPortfolioExportResult, ApiError> {
+    let pool = &state.0;
+    let path = validate_path(&path)?;
+    let format = format.unwrap_or_else(|| default_format(&path));
+
+    let portfolio = build_portfolio(pool, include_archived.unwrap_or(false)).await?;
+    let contents = match format {
+        PortfolioFormat::Json => {
+            serde_json::to_string_pretty(&portfolio).map_err(|e| ApiError::internal(e.to_string()))?
+        }
+        PortfolioFormat::Markdown => render_markdown(&portfolio),
+    };
+    tokio::fs::write(&path, contents)
+        .await
+        .map_err(|e| ApiError::internal(format!("Failed to write {}: {}", path.display(), e)))?;
+
+    Ok(SkillPortfolioExportResult {
+        path: path.to_string_lossy().into_owned(),
+        skills: portfolio.skills.len(),
+        total_hours: portfolio.total_hours,
+    })
+}
+
+fn default_format(path: &Path) -> PortfolioFormat {
+    match path.extension().and_then(|e| e.to_str()) {
+        Some(ext) if ext.eq_ignore_ascii_case("md") || ext.eq_ignore_ascii_case("markdown") => {
+            PortfolioFormat::Markdown
+        }
+        _ => PortfolioFormat::Json,
+    }
+}
+
+pub(crate) async fn build_portfolio(pool: &Pool<Sqlite>, include_archived: bool) -> Result<SkillPortfolio, ApiError> {
+    let today = clock::now().date_naive();
+    let recent_since = (today - Duration::days(RECENT_DAYS)).to_string();
+
+    let skills: Vec<(i64, String, Option<String>, Option<String>, i64)> = sqlx::query_as(
+        r#"SELECT id, name, category, description, COALESCE(current_level, 1)
+           FROM skills
+           WHERE ? OR archived_at IS NULL"#,
+    )
+    .bind(include_archived)
+    .fetch_all(pool)
+    .await
+    .map_err(ApiError::from)?;
+
+    let mut portfolio = Vec::with_capacity(skills.len());
+    for (skill_id, name, category, description, level) in skills {
+        // Practice logs and finished practice sessions, oldest first
+        let practice: Vec<(String, i64, Option<String>)> = sqlx::query_as(
+            r#"
+            SELECT date(logged_at, 'localtime'), duration_minutes, notes FROM practice_logs WHERE skill_id = ?1
+            UNION ALL
+            SELECT date(started_at, 'localtime'), COALESCE(duration_minutes, 0), notes FROM sessions
+            WHERE session_type = 'practice' AND reference_type = 'skill' AND reference_id = ?1 AND ended_at IS NOT NULL
+            ORDER BY 1
+            "#,
+        )
+        .bind(skill_id)
+        .fetch_all(pool)
+        .await
+        .map_err(ApiError::from)?;
+
+        let mut milestones = hour_milestones(&practice);
+        let levels: Vec<(String, String)> = sqlx::query_as(
+            r#"SELECT date(achieved_at, 'localtime'), title FROM achievements
+               WHERE achievement_type = 'skill_level' AND json_extract(metadata, '$.skill') = ?"#,
+        )
+        .bind(&name)
+        .fetch_all(pool)
+        .await
+        .map_err(ApiError::from)?;
+        milestones.extend(levels.into_iter().map(|(date, title)| PortfolioMilestone { date, title }));
+        milestones.sort_by(|a, b| a.date.cmp(&b.date));
+
+        let minutes: i64 = practice.iter().map(|(_, m, _)| m).sum();
+        let recent: i64 = practice
+            .iter()
+            .filter(|(date, ..)| *date >= recent_since)
+            .map(|(_, m, _)| m)
+            .sum();
+
+        portfolio.push(PortfolioSkill {
+            skill_id,
+            name,
+            category,
+            description,
+            level,
+            total_hours: round_hours(minutes),
+            recent_hours: round_hours(recent),
+            practice_count: practice.len() as i64,
+            first_practiced: practice.first().map(|(date, ..)| date.clone()),
+            last_practiced: practice.last().map(|(date, ..)| date.clone()),
+            milestones,
+            projects: projects(&practice),
+        });
+    }
+    portfolio.sort_by(|a, b| b.total_hours.total_cmp(&a.total_hours).then_with(|| a.name.cmp(&b.name)));
+
+    Ok(SkillPortfolio {
+        generated_on: today.to_string(),
+        total_hours: (portfolio.iter().map(|s| s.total_hours).sum::<f64>() * 10.0).round() / 10.0,
+        skills: portfolio,
+    })
+}
+
+fn round_hours(minutes: i64) -> f64 {
+    (minutes as f64 / 6.0).round() / 10.0
+}
+
+/// The date cumulative practice first reached each hour milestone
+fn hour_milestones(practice: &[(String, i64, Option<String>)]) -> Vec<PortfolioMilestone> {
+    let mut milestones = Vec::new();
+    let mut minutes = 0;
+    let mut next = HOUR_MILESTONES.iter().peekable();
+    for (date, duration, _) in practice {
+        minutes += duration;
+        while let Some(&&hours) = next.peek() {
+            if minutes < hours * 60 {
+                break;
+            }
+            milestones.push(PortfolioMilestone {
+                date: date.clone(),
+                title: format!("{} hours of practice", hours),
+            });
+            next.next();
+        }
+    }
+    milestones
+}
+
+/// The most notable practice notes, in date order
+fn projects(practice: &[(String, i64, Option<String>)]) -> Vec<PortfolioProject> {
+    let mut notes: Vec<(bool, &str, &str)> = practice
+        .iter()
+        .filter_map(|(date, _, notes)| {
+            let note = notes.as_deref()?.trim();
+            (note.chars().count() >= MIN_PROJECT_NOTE_CHARS).then_some((date.as_str(), note))
+        })
+        .map(|(date, note)| {
+            let lower = note.to_lowercase();
+            (PROJECT_WORDS.iter().any(|w| lower.contains(w)), date, note)
+        })
+        .collect();
+    notes.sort_by(|a, b| b.0.cmp(&a.0).then_with(|| b.2.len().cmp(&a.2.len())));
+    notes.truncate(MAX_PROJECTS);
+    notes.sort_by(|a, b| a.1.cmp(b.1));
+    notes
+        .into_iter()
+        .map(|(_, date, note)| PortfolioProject {
+            date: date.to_string(),
+            note: note.to_string(),
+        })
+        .collect()
+}
+
+fn render_markdown(portfolio: &SkillPortfolio) -> String {
+    let mut out = vec![
+        "# Skill Portfolio".to_string(),
+        String::new(),
+        format!(
+            "_Generated {} · {:.1} hours across {} skills_",
+            portfolio.generated_on,
+            portfolio.total_hours,
+            portfolio.skills.len()
+        ),
+    ];
+    for skill in &portfolio.skills {
+        out.push(String::new());
+        let category = skill.category.as_deref().map(|c| format!(" ({})", c)).unwrap_or_default();
+        out.push(format!("## {} — Level {}{}", skill.name, skill.level, category));
+        if let Some(description) = skill.description.as_deref().filter(|d| !d.trim().is_empty()) {
+            out.push(String::new());
+            out.push(description.trim().to_string());
+        }
+        out.push(String::new());
+        out.push(format!(
+            "- **Hours:** {:.1} total, {:.1} in the last {} days",
+            skill.total_hours, skill.recent_hours, RECENT_DAYS
+        ));
+        if let (Some(first), Some(last)) = (&skill.first_practiced, &skill.last_practiced) {
+            out.push(format!(
+                "- **Practiced:** {} sessions from {} to {}",
+                skill.practice_count, first, last
+            ));
+        }
+        for (heading, lines) in [
+            (
+                "Milestones",
+                skill.milestones.iter().map(|m| format!("- {} — {}", m.date, m.title)).collect::<Vec<_>>(),
+            ),
+            (
+                "Projects",
+                skill
+                    .projects
+                    .iter()
+                    .map(|p| format!("- {} — {}", p.date, p.note.replace('\n', " ")))
+                    .collect(),
+            ),
+        ] {
+            if !lines.is_empty() {
+                out.push(String::new());
+                out.push(format!("### {}", heading));
+                out.push(String::new());
+                out.extend(lines);
+            }
+        }
+    }
+    out.push(String::new());
+    out.join("\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Local, TimeZone};
+
+    #[tokio::test]
+    async fn portfolio_sums_practice_and_picks_milestones_and_projects() {
+        let pool = crate::test_support::pool().await;
+        let _clock = clock::freeze(Local.with_ymd_and_hms(2030, 6, 1, 12, 0, 0).unwrap());
+        let guitar: i64 = sqlx::query_scalar(
+            "INSERT INTO skills (name, category, current_level) VALUES ('Guitar', 'music', 3) RETURNING id",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        sqlx::query("INSERT INTO skills (name) VALUES ('Chess')").execute(&pool).await.unwrap();
+
+        for (logged_at, minutes, notes) in [
+            ("2029-09-01 12:00:00", 300, Some("Scales")),
+            ("2029-10-01 12:00:00", 360, Some("Finished recording a cover of a favourite song")),
+            ("2030-05-01 12:00:00", 120, Some("Long practice working through the whole chord book")),
+        ] {
+            sqlx::query("INSERT INTO practice_logs (skill_id, duration_minutes, notes, logged_at) VALUES (?, ?, ?, ?)")
+                .bind(guitar)
+                .bind(minutes)
+                .bind(notes)
+                .bind(logged_at)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+        sqlx::query(
+            "INSERT INTO sessions (session_type, reference_type, reference_id, started_at, ended_at, duration_minutes) VALUES ('practice', 'skill', ?, '2030-05-20 12:00:00', '2030-05-20 13:00:00', 60)",
+        )
+        .bind(guitar)
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            r#"INSERT INTO achievements (achievement_type, title, metadata, achieved_at) VALUES ('skill_level', 'Guitar Level 5!', '{"skill":"Guitar","level":5}', '2030-05-21 12:00:00')"#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let portfolio = build_portfolio(&pool, false).await.unwrap();
+        assert_eq!(portfolio.total_hours, 14.0);
+        let guitar = &portfolio.skills[0];
+        assert_eq!(guitar.name, "Guitar");
+        assert_eq!((guitar.total_hours, guitar.recent_hours, guitar.practice_count), (14.0, 3.0, 4));
+        let milestones: Vec<&str> = guitar.milestones.iter().map(|m| m.title.as_str()).collect();
+        assert_eq!(milestones, vec!["10 hours of practice", "Guitar Level 5!"]);
+        assert_eq!(guitar.milestones[0].date, "2029-10-01");
+        // "Scales" is too short to be a project; finished work comes first
+        assert_eq!(guitar.projects.len(), 2);
+        assert!(guitar.projects[0].note.starts_with("Finished"));
+        assert_eq!(portfolio.skills[1].total_hours, 0.0);
+
+        let markdown = render_markdown(&portfolio);
+        assert!(markdown.contains("## Guitar — Level 3 (music)"));
+        assert!(markdown.contains("- 2029-10-01 — Finished recording"));
+    }
+}
@@ -31,7 +31,7 @@ pub async fn create_skill(state: State<'_, DbState>, data: SkillInput) -> Result
 #[tauri::command]
 pub async fn get_skills(state: State<'_, DbState>) -> Result<Vec<Skill>, ApiError> {
     let pool = &state.0;
-    let rows = sqlx::query_as::<_, Skill>("SELECT * FROM skills ORDER BY created_at DESC")
+    let rows = sqlx::query_as::<_, Skill>("SELECT * FROM skills WHERE archived_at IS NULL ORDER BY created_at DESC")
         .fetch_all(pool)
         .await
         .map_err(ApiError::from)?;
@@ -0,0 +1,36 @@
+//! Social time commands
+
+use tauri::State;
+
+use crate::services::clock;
+use crate::services::social::{self, SocialEventInput, SocialStats};
+use crate::services::trackers::TrackerLog;
+use crate::{error::ApiError, DbState};
+
+const DEFAULT_EVENT_LIMIT: i64 = 50;
+const MAX_EVENT_LIMIT: i64 = 500;
+
+#[tauri::command]
+pub async fn log_social_event(state: State<'_, DbState>, data: SocialEventInput) -> Result<TrackerLog, ApiError> {
+    social::log_event(&state.0, &data).await
+}
+
+/// Logged events, newest first
+#[tauri::command]
+pub async fn get_social_events(state: State<'_, DbState>, limit: Option<i64>) -> Result<Vec<TrackerLog>, ApiError> {
+    let limit = limit.unwrap_or(DEFAULT_EVENT_LIMIT);
+    if !(1..=MAX_EVENT_LIMIT).contains(&limit) {
+        return Err(ApiError::validation(format!("limit must be between 1 and {}", MAX_EVENT_LIMIT)));
+    }
+    social::events(&state.0, limit).await
+}
+
+/// Social time with isolation streaks against crunch periods
+#[tauri::command]
+pub async fn get_social_stats(state: State<'_, DbState>, days: Option<i64>) -> Result<SocialStats, ApiError> {
+    let days = days.unwrap_or(30);
+    if !(1..=365).contains(&days) {
+        return Err(ApiError::validation("days must be between 1 and 365"));
+    }
+    social::stats(&state.0, days, clock::now().date_naive()).await
+}
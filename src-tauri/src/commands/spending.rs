@@ -0,0 +1,28 @@
+//! Spending check-in commands
+
+use tauri::State;
+
+use crate::services::clock;
+use crate::services::spending::{self, SpendingCheckIn, SpendingCheckInInput, SpendingSummary};
+use crate::{error::ApiError, DbState};
+
+/// Log the day's discretionary spend, replacing an earlier check-in that day
+#[tauri::command]
+pub async fn log_spending_checkin(state: State<'_, DbState>, data: SpendingCheckInInput) -> Result<SpendingCheckIn, ApiError> {
+    spending::check_in(&state.0, &data, clock::now().date_naive()).await
+}
+
+#[tauri::command]
+pub async fn delete_spending_checkin(state: State<'_, DbState>, date: String) -> Result<bool, ApiError> {
+    spending::delete(&state.0, spending::parse_date(&date)?).await
+}
+
+/// Weekly totals, streaks and how spending lines up with mood and energy
+#[tauri::command]
+pub async fn get_spending_summary(state: State<'_, DbState>, weeks: Option<i64>) -> Result<SpendingSummary, ApiError> {
+    let weeks = weeks.unwrap_or(4);
+    if !(1..=52).contains(&weeks) {
+        return Err(ApiError::validation("weeks must be between 1 and 52"));
+    }
+    spending::summary(&state.0, weeks, clock::now().date_naive()).await
+}
@@ -0,0 +1,462 @@
+//! Study Techniques
+//!
+//! A library of study methods (active recall, the Feynman technique, past
+//! papers, ...) that can be tagged onto sessions. `get_technique_stats`
+//! compares tagged sessions per technique and course: self-rated focus, the
+//! focused share of tracked time, the session outcome score reported to the
+//! agent, and the next grade that came back for the course. The agent turns a
+//! clear winner into an insight (see `best_techniques`).
+
+use std::collections::HashMap;
+
+use serde::{Deserialize, Serialize};
+use sqlx::{Pool, Sqlite};
+use tauri::State;
+
+use crate::agent::outcomes::session_outcome;
+use crate::commands::efficiency::PREP_WINDOW_DAYS;
+use crate::{error::ApiError, models::technique::Technique, DbState};
+
+const MAX_NAME_LENGTH: usize = 60;
+const MAX_DESCRIPTION_LENGTH: usize = 500;
+const MAX_TECHNIQUES_PER_SESSION: usize = 10;
+pub(crate) const DEFAULT_STATS_DAYS: i64 = 90;
+/// Tagged sessions a technique needs in a course before it is compared
+const MIN_SESSIONS_FOR_COMPARISON: i64 = 3;
+/// Outcome lead (0-1 scale) over the runner-up that makes a technique the winner
+const MIN_OUTCOME_LEAD: f64 = 0.1;
+
+/// (technique id, technique name, course id, course name, duration, planned,
+/// focus rating, focused seconds, sampled seconds, next grade)
+type StatsRow = (
+    i64,
+    String,
+    Option<i64>,
+    Option<String>,
+    Option<i64>,
+    Option<i64>,
+    Option<i64>,
+    Option<i64>,
+    Option<i64>,
+    Option<f64>,
+);
+
+#[derive(Debug, Deserialize)]
+pub struct TechniqueInput {
+    pub name: String,
+    #[serde(default)]
+    pub description: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TechniqueStats {
+    pub technique_id: i64,
+    pub technique_name: String,
+    /// None for sessions not tied to a course
+    pub course_id: Option<i64>,
+    pub course_name: Option<String>,
+    pub sessions: i64,
+    pub total_minutes: i64,
+    pub avg_focus_rating: Option<f64>,
+    /// Share of tracked time not spent in distractions
+    pub focus_share: Option<f64>,
+    /// Average outcome score (0-1), as reported to the agent
+    pub avg_outcome: f64,
+    /// Average of the next exam or assignment grade for the course within
+    /// `PREP_WINDOW_DAYS` of each session
+    pub avg_next_grade: Option<f64>,
+}
+
+/// A technique that clearly beats the others in a course
+#[derive(Debug, Clone)]
+pub(crate) struct BestTechnique {
+    pub course_id: i64,
+    pub course_name: String,
+    pub best: TechniqueStats,
+    pub runner_up: TechniqueStats,
+}
+
+pub(crate) async fn list_techniques(pool: &Pool<Sqlite>) -> Result<Vec<Technique>, ApiError> {
+    sqlx::query_as::<_, Technique>("SELECT * FROM techniques ORDER BY is_builtin DESC, name")
+        .fetch_all(pool)
+        .await
+        .map_err(ApiError::from)
+}
+
+pub(crate) async fn insert_technique(pool: &Pool<Sqlite>, data: &TechniqueInput) -> Result<Technique, ApiError> {
+    let name = data.name.trim();
+    if name.is_empty() {
+        return Err(ApiError::validation("Technique name is required"));
+    }
+    if name.chars().count() > MAX_NAME_LENGTH {
+        return Err(ApiError::validation(format!(
+            "Technique name must be at most {} characters",
+            MAX_NAME_LENGTH
+        )));
+    }
+    let description = data
+        .description
+        .as_deref()
+        .map(str::trim)
+        .filter(|d| !d.is_empty());
+    if description.is_some_and(|d| d.chars().count() > MAX_DESCRIPTION_LENGTH) {
+        return Err(ApiError::validation(format!(
+            "Description must be at most {} characters",
+            MAX_DESCRIPTION_LENGTH
+        )));
+    }
+
+    sqlx::query_as::<_, Technique>("INSERT INTO techniques (name, description) VALUES (?, ?) RETURNING *")
+        .bind(name)
+        .bind(description)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| ApiError::from_sqlx(e, format!("Technique '{}' already exists", name)))
+}
+
+pub(crate) async fn remove_technique(pool: &Pool<Sqlite>, id: i64) -> Result<(), ApiError> {
+    let is_builtin: bool = sqlx::query_scalar("SELECT is_builtin FROM techniques WHERE id = ?")
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+        .map_err(ApiError::from)?
+        .ok_or_else(|| ApiError::not_found("Technique not found"))?;
+    if is_builtin {
+        return Err(ApiError::validation("Built-in techniques cannot be deleted"));
+    }
+
+    let mut tx = pool.begin().await.map_err(ApiError::from)?;
+    sqlx::query("DELETE FROM session_techniques WHERE technique_id = ?")
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(ApiError::from)?;
+    sqlx::query("DELETE FROM techniques WHERE id = ?")
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(ApiError::from)?;
+    tx.commit().await.map_err(ApiError::from)
+}
+
+pub(crate) async fn session_techniques(pool: &Pool<Sqlite>, session_id: i64) -> Result<Vec<Technique>, ApiError> {
+    sqlx::query_as::<_, Technique>(
+        r#"SELECT t.* FROM techniques t
+           JOIN session_techniques st ON st.technique_id = t.id
+           WHERE st.session_id = ?
+           ORDER BY t.name"#,
+    )
+    .bind(session_id)
+    .fetch_all(pool)
+    .await
+    .map_err(ApiError::from)
+}
+
+/// Replace the techniques tagged on a session
+pub(crate) async fn tag_session(
+    pool: &Pool<Sqlite>,
+    session_id: i64,
+    technique_ids: &[i64],
+) -> Result<Vec<Technique>, ApiError> {
+    let mut ids = technique_ids.to_vec();
+    ids.sort_unstable();
+    ids.dedup();
+    if ids.len() > MAX_TECHNIQUES_PER_SESSION {
+        return Err(ApiError::validation(format!(
+            "At most {} techniques per session",
+            MAX_TECHNIQUES_PER_SESSION
+        )));
+    }
+
+    let mut tx = pool.begin().await.map_err(ApiError::from)?;
+    let exists: Option<i64> = sqlx::query_scalar("SELECT id FROM sessions WHERE id = ?")
+        .bind(session_id)
+        .fetch_optional(&mut *tx)
+        .await
+        .map_err(ApiError::from)?;
+    if exists.is_none() {
+        return Err(ApiError::not_found("Session not found"));
+    }
+
+    sqlx::query("DELETE FROM session_techniques WHERE session_id = ?")
+        .bind(session_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(ApiError::from)?;
+    for id in &ids {
+        let inserted = sqlx::query(
+            "INSERT INTO session_techniques (session_id, technique_id) SELECT ?, id FROM techniques WHERE id = ?",
+        )
+        .bind(session_id)
+        .bind(id)
+        .execute(&mut *tx)
+        .await
+        .map_err(ApiError::from)?;
+        if inserted.rows_affected() == 0 {
+            return Err(ApiError::not_found(format!("Technique {} not found", id)));
+        }
+    }
+    tx.commit().await.map_err(ApiError::from)?;
+
+    session_techniques(pool, session_id).await
+}
+
+pub(crate) async fn technique_stats(
+    pool: &Pool<Sqlite>,
+    days: i64,
+    course_id: Option<i64>,
+) -> Result<Vec<TechniqueStats>, ApiError> {
+    if !(1..=3650).contains(&days) {
+        return Err(ApiError::validation("days must be between 1 and 3650"));
+    }
+
+    let rows: Vec<StatsRow> = sqlx::query_as(
+        r#"
+        SELECT t.id, t.name, c.id, c.name, s.duration_minutes, s.planned_minutes, s.focus_rating,
+               (SELECT SUM(f.seconds) FROM focus_samples f WHERE f.session_id = s.id AND f.is_distraction = 0),
+               (SELECT SUM(f.seconds) FROM focus_samples f WHERE f.session_id = s.id),
+               (SELECT r.score FROM (
+                    SELECT exam_date AS at, grade AS score FROM exams
+                    WHERE course_id = c.id AND grade IS NOT NULL AND exam_date IS NOT NULL
+                    UNION ALL
+                    SELECT COALESCE(completed_at, due_date), score FROM assignments
+                    WHERE course_id = c.id AND score IS NOT NULL AND COALESCE(completed_at, due_date) IS NOT NULL
+                ) r
+                WHERE datetime(r.at) > datetime(s.started_at)
+                  AND datetime(r.at) <= datetime(s.started_at, ?2)
+                ORDER BY datetime(r.at) LIMIT 1)
+        FROM session_techniques st
+        JOIN techniques t ON t.id = st.technique_id
+        JOIN sessions s ON s.id = st.session_id
+        LEFT JOIN courses c ON s.reference_type = 'course' AND c.id = s.reference_id
+        WHERE s.ended_at IS NOT NULL
+          AND s.started_at >= datetime('now', ?1)
+          AND (?3 IS NULL OR c.id = ?3)
+        "#,
+    )
+    .bind(format!("-{} days", days))
+    .bind(format!("+{} days", PREP_WINDOW_DAYS))
+    .bind(course_id)
+    .fetch_all(pool)
+    .await
+    .map_err(ApiError::from)?;
+
+    #[derive(Default)]
+    struct Acc {
+        technique_name: String,
+        course_name: Option<String>,
+        sessions: i64,
+        minutes: i64,
+        ratings: Vec<f64>,
+        focused_seconds: i64,
+        sampled_seconds: i64,
+        outcome: f64,
+        grades: Vec<f64>,
+    }
+
+    let mut groups: HashMap<(i64, Option<i64>), Acc> = HashMap::new();
+    for (technique_id, technique_name, course_id, course_name, duration, planned, rating, focused, sampled, grade) in rows {
+        let acc = groups.entry((technique_id, course_id)).or_default();
+        acc.technique_name = technique_name;
+        acc.course_name = course_name;
+        acc.sessions += 1;
+        acc.minutes += duration.unwrap_or(0).max(0);
+        if let Some(rating) = rating {
+            acc.ratings.push(rating as f64);
+        }
+        acc.focused_seconds += focused.unwrap_or(0);
+        acc.sampled_seconds += sampled.unwrap_or(0);
+        acc.outcome += session_outcome(duration, planned, rating) as f64;
+        if let Some(grade) = grade {
+            acc.grades.push(grade);
+        }
+    }
+
+    let mean = |values: &[f64]| (!values.is_empty()).then(|| values.iter().sum::<f64>() / values.len() as f64);
+    let mut stats: Vec<TechniqueStats> = groups
+        .into_iter()
+        .map(|((technique_id, course_id), acc)| TechniqueStats {
+            technique_id,
+            technique_name: acc.technique_name,
+            course_id,
+            course_name: acc.course_name,
+            sessions: acc.sessions,
+            total_minutes: acc.minutes,
+            avg_focus_rating: mean(&acc.ratings),
+            focus_share: (acc.sampled_seconds > 0).then(|| acc.focused_seconds as f64 / acc.sampled_seconds as f64),
+            avg_outcome: acc.outcome / acc.sessions as f64,
+            avg_next_grade: mean(&acc.grades),
+        })
+        .collect();
+    stats.sort_by(|a, b| {
+        a.course_name
+            .cmp(&b.course_name)
+            .then(b.avg_outcome.total_cmp(&a.avg_outcome))
+            .then(a.technique_id.cmp(&b.technique_id))
+    });
+    Ok(stats)
+}
+
+/// Per course, the technique that leads the runner-up by `MIN_OUTCOME_LEAD`,
+/// widest lead first
+pub(crate) async fn best_techniques(pool: &Pool<Sqlite>) -> Result<Vec<BestTechnique>, ApiError> {
+    let mut by_course: HashMap<i64, Vec<TechniqueStats>> = HashMap::new();
+    for stat in technique_stats(pool, DEFAULT_STATS_DAYS, None).await? {
+        if let (Some(course_id), true) = (stat.course_id, stat.sessions >= MIN_SESSIONS_FOR_COMPARISON) {
+            by_course.entry(course_id).or_default().push(stat);
+        }
+    }
+
+    let mut winners: Vec<BestTechnique> = by_course
+        .into_iter()
+        .filter_map(|(course_id, mut stats)| {
+            stats.sort_by(|a, b| b.avg_outcome.total_cmp(&a.avg_outcome));
+            let mut ranked = stats.into_iter();
+            let (best, runner_up) = (ranked.next()?, ranked.next()?);
+            (best.avg_outcome - runner_up.avg_outcome >= MIN_OUTCOME_LEAD).then(|| BestTechnique {
+                course_id,
+                course_name: best.course_name.clone().unwrap_or_default(),
+                best,
+                runner_up,
+            })
+        })
+        .collect();
+    winners.sort_by(|a, b| {
+        let lead = |w: &BestTechnique| w.best.avg_outcome - w.runner_up.avg_outcome;
+        lead(b).total_cmp(&lead(a)).then(a.course_id.cmp(&b.course_id))
+    });
+    Ok(winners)
+}
+
+#[tauri::command]
+pub async fn get_techniques(state: State<'_, DbState>) -> Result<Vec<Technique>, ApiError> {
+    list_techniques(&state.0).await
+}
+
+#[tauri::command]
+pub async fn create_technique(state: State<'_, DbState>, data: TechniqueInput) -> Result<Technique, ApiError> {
+    insert_technique(&state.0, &data).await
+}
+
+#[tauri::command]
+pub async fn delete_technique(state: State<'_, DbState>, id: i64) -> Result<(), ApiError> {
+    remove_technique(&state.0, id).await
+}
+
+#[tauri::command]
+pub async fn get_session_techniques(state: State<'_, DbState>, session_id: i64) -> Result<Vec<Technique>, ApiError> {
+    session_techniques(&state.0, session_id).await
+}
+
+#[tauri::command]
+pub async fn set_session_techniques(
+    state: State<'_, DbState>,
+    session_id: i64,
+    technique_ids: Vec<i64>,
+) -> Result<Vec<Technique>, ApiError> {
+    tag_session(&state.0, session_id, &technique_ids).await
+}
+
+/// Compare tagged sessions by technique, per course
+#[tauri::command]
+pub async fn get_technique_stats(
+    state: State<'_, DbState>,
+    days: Option<i64>,
+    course_id: Option<i64>,
+) -> Result<Vec<TechniqueStats>, ApiError> {
+    technique_stats(&state.0, days.unwrap_or(DEFAULT_STATS_DAYS), course_id).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+    use std::str::FromStr;
+
+    async fn setup_pool_with_migrations() -> Pool<Sqlite> {
+        let options = SqliteConnectOptions::from_str("sqlite::memory:")
+            .unwrap()
+            .foreign_keys(false);
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect_with(options)
+            .await
+            .expect("Failed to connect to in-memory DB");
+
+        crate::db::migrations::run_migrations(&pool)
+            .await
+            .expect("Failed to run migrations");
+
+        pool
+    }
+
+    /// A finished 60-minute course session `days_ago`, tagged with `technique`
+    async fn tagged_session(pool: &Pool<Sqlite>, course_id: i64, days_ago: i64, minutes: i64, rating: i64, technique: i64) -> i64 {
+        let id: i64 = sqlx::query_scalar(
+            r#"INSERT INTO sessions (session_type, reference_id, reference_type, started_at, ended_at, duration_minutes, planned_minutes, focus_rating)
+               VALUES ('study', ?1, 'course', datetime('now', ?2), datetime('now', ?2, '+1 hour'), ?3, 60, ?4) RETURNING id"#,
+        )
+        .bind(course_id)
+        .bind(format!("-{} days", days_ago))
+        .bind(minutes)
+        .bind(rating)
+        .fetch_one(pool)
+        .await
+        .unwrap();
+        tag_session(pool, id, &[technique]).await.unwrap();
+        id
+    }
+
+    #[tokio::test]
+    async fn techniques_are_compared_per_course() {
+        let pool = setup_pool_with_migrations().await;
+        let library = list_techniques(&pool).await.unwrap();
+        let id_of = |name: &str| library.iter().find(|t| t.name == name).unwrap().id;
+        let (recall, rereading) = (id_of("Active recall"), id_of("Rereading"));
+
+        let custom = insert_technique(&pool, &TechniqueInput { name: " Mind maps ".to_string(), description: None })
+            .await
+            .unwrap();
+        assert_eq!(custom.name, "Mind maps");
+        assert!(insert_technique(&pool, &TechniqueInput { name: "mind MAPS".to_string(), description: None })
+            .await
+            .is_err());
+        assert!(remove_technique(&pool, recall).await.is_err());
+
+        sqlx::query("INSERT INTO courses (id, name) VALUES (1, 'Chemistry')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        for day in 1..=3 {
+            tagged_session(&pool, 1, day + 10, 60, 5, recall).await;
+            tagged_session(&pool, 1, day + 10, 30, 2, rereading).await;
+        }
+        // Graded a few days after the sessions
+        sqlx::query("INSERT INTO exams (course_id, title, exam_date, grade) VALUES (1, 'Quiz', datetime('now', '-8 days'), 88)")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let stats = technique_stats(&pool, 90, Some(1)).await.unwrap();
+        assert_eq!(stats.len(), 2);
+        assert_eq!(stats[0].technique_id, recall);
+        assert_eq!(stats[0].sessions, 3);
+        assert_eq!(stats[0].avg_focus_rating, Some(5.0));
+        assert_eq!(stats[0].avg_next_grade, Some(88.0));
+        assert!(stats[0].avg_outcome > stats[1].avg_outcome);
+
+        let winners = best_techniques(&pool).await.unwrap();
+        assert_eq!(winners.len(), 1);
+        assert_eq!(winners[0].best.technique_name, "Active recall");
+        assert_eq!(winners[0].runner_up.technique_name, "Rereading");
+
+        // Retagging replaces, and unknown techniques are rejected
+        let session = tagged_session(&pool, 1, 1, 60, 4, recall).await;
+        let tags = tag_session(&pool, session, &[custom.id, rereading, custom.id]).await.unwrap();
+        assert_eq!(tags.len(), 2);
+        assert!(tag_session(&pool, session, &[999]).await.is_err());
+        assert_eq!(session_techniques(&pool, session).await.unwrap().len(), 2);
+        remove_technique(&pool, custom.id).await.unwrap();
+        assert_eq!(session_techniques(&pool, session).await.unwrap().len(), 1);
+    }
+}
@@ -0,0 +1,62 @@
+//! Local Telemetry
+//!
+//! Usage and error counts recorded by `services::telemetry` when
+//! `telemetry_enabled` is on, and a JSON export of them.
+
+use serde::Serialize;
+use tauri::State;
+
+use crate::commands::export::validate_path;
+use crate::services::telemetry::{self, TelemetryReport};
+use crate::{error::ApiError, DbState};
+
+const DEFAULT_DAYS: i64 = 30;
+const MAX_DAYS: i64 = 365;
+
+#[derive(Debug, Serialize)]
+pub struct TelemetryExportResult {
+    pub path: String,
+    pub commands: usize,
+    pub errors: usize,
+}
+
+fn validate_days(days: Option<i64>) -> Result<i64, ApiError> {
+    let days = days.unwrap_or(DEFAULT_DAYS);
+    if !(1..=MAX_DAYS).contains(&days) {
+        return Err(ApiError::validation(format!("days must be between 1 and {}", MAX_DAYS)));
+    }
+    Ok(days)
+}
+
+/// Command and error counts for the last `days` days (default 30)
+#[tauri::command]
+pub async fn get_telemetry_report(state: State<'_, DbState>, days: Option<i64>) -> Result<TelemetryReport, ApiError> {
+    telemetry::report(&state.0, validate_days(days)?).await
+}
+
+/// Write the report as JSON to `path`
+#[tauri::command]
+pub async fn export_telemetry(
+    state: State<'_, DbState>,
+    path: String,
+    days: Option<i64>,
+) -> Result<TelemetryExportResult, ApiError> {
+    let path = validate_path(&path)?;
+    let report = telemetry::report(&state.0, validate_days(days)?).await?;
+    let json = serde_json::to_vec_pretty(&report).map_err(|e| ApiError::internal(e.to_string()))?;
+    tokio::fs::write(&path, json)
+        .await
+        .map_err(|e| ApiError::internal(format!("Failed to write {}: {}", path.display(), e)))?;
+
+    Ok(TelemetryExportResult {
+        path: path.to_string_lossy().into_owned(),
+        commands: report.commands.len(),
+        errors: report.errors.len(),
+    })
+}
+
+/// Delete all recorded counts; returns the rows removed
+#[tauri::command]
+pub async fn clear_telemetry(state: State<'_, DbState>) -> Result<u64, ApiError> {
+    telemetry::clear(&state.0).await
+}
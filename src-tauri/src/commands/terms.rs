@@ -0,0 +1,383 @@
+//! Terms and semester rollover
+//!
+//! `archive_term` closes a term without deleting anything: its courses are
+//! deactivated, their meetings archived (hidden from the calendar), and a
+//! per-course analytics snapshot is frozen in `term_snapshots`. Skills and
+//! workout templates are kept by default; any not carried forward are archived
+//! so they drop out of active lists. A next term can be opened in the same call.
+
+use serde::{Deserialize, Serialize};
+use sqlx::{Pool, Sqlite, SqliteConnection};
+use tauri::State;
+
+use crate::commands::onboarding::{validate_term, TermInput};
+use crate::{error::ApiError, DbState};
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct Term {
+    pub id: i64,
+    pub name: String,
+    pub start_date: Option<String>,
+    pub end_date: Option<String>,
+    pub is_current: bool,
+    pub archived_at: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ArchiveTermInput {
+    /// Defaults to the current term
+    #[serde(default)]
+    pub term_id: Option<i64>,
+    /// Opened as the new current term
+    #[serde(default)]
+    pub next_term: Option<TermInput>,
+    #[serde(default = "default_true")]
+    pub carry_forward_skills: bool,
+    #[serde(default = "default_true")]
+    pub carry_forward_workout_templates: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+#[derive(Debug, Serialize)]
+pub struct ArchiveTermResult {
+    pub term_id: i64,
+    pub archived_course_ids: Vec<i64>,
+    pub archived_meetings: u64,
+    pub archived_skills: u64,
+    pub archived_workout_templates: u64,
+    pub next_term_id: Option<i64>,
+}
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct TermSnapshot {
+    pub id: i64,
+    pub term_id: i64,
+    pub course_id: Option<i64>,
+    pub course_name: String,
+    pub course_code: Option<String>,
+    pub credit_hours: Option<i64>,
+    pub study_minutes: i64,
+    pub study_sessions: i64,
+    pub assignments_total: i64,
+    pub assignments_completed: i64,
+    pub average_assignment_score: Option<f64>,
+    pub exams_graded: i64,
+    pub average_exam_grade: Option<f64>,
+    pub current_grade: Option<f64>,
+    pub target_grade: Option<f64>,
+    pub created_at: String,
+}
+
+#[tauri::command]
+pub async fn get_terms(state: State<'_, DbState>) -> Result<Vec<Term>, ApiError> {
+    let pool = &state.0;
+    sqlx::query_as::<_, Term>(
+        "SELECT id, name, start_date, end_date, is_current, archived_at FROM terms ORDER BY COALESCE(start_date, created_at) DESC, id DESC",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(ApiError::from)
+}
+
+/// Close a term and optionally open the next one
+#[tauri::command]
+pub async fn archive_term(state: State<'_, DbState>, data: ArchiveTermInput) -> Result<ArchiveTermResult, ApiError> {
+    let pool = &state.0;
+    archive(pool, data).await
+}
+
+/// Analytics frozen when a term was archived
+#[tauri::command]
+pub async fn get_term_snapshots(state: State<'_, DbState>, term_id: i64) -> Result<Vec<TermSnapshot>, ApiError> {
+    let pool = &state.0;
+    sqlx::query_as::<_, TermSnapshot>("SELECT * FROM term_snapshots WHERE term_id = ? ORDER BY course_name")
+        .bind(term_id)
+        .fetch_all(pool)
+        .await
+        .map_err(ApiError::from)
+}
+
+pub(crate) async fn archive(pool: &Pool<Sqlite>, data: ArchiveTermInput) -> Result<ArchiveTermResult, ApiError> {
+    if let Some(ref term) = data.next_term {
+        validate_term(term)?;
+    }
+
+    let mut tx = pool.begin().await.map_err(ApiError::from)?;
+
+    let term: Option<(i64, Option<String>)> = match data.term_id {
+        Some(id) => sqlx::query_as("SELECT id, archived_at FROM terms WHERE id = ?")
+            .bind(id)
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(ApiError::from)?,
+        None => sqlx::query_as("SELECT id, archived_at FROM terms WHERE is_current = 1 AND archived_at IS NULL ORDER BY id DESC LIMIT 1")
+            .fetch_optional(&mut *tx)
+            .await
+            .map_err(ApiError::from)?,
+    };
+    let term_id = match term {
+        Some((_, Some(_))) => return Err(ApiError::conflict("Term is already archived")),
+        Some((id, None)) => id,
+        None if data.term_id.is_some() => return Err(ApiError::not_found("Term not found")),
+        // No terms yet: group the active courses that have none into one
+        None => {
+            let (unassigned,): (i64,) =
+                sqlx::query_as("SELECT COUNT(*) FROM courses WHERE is_active = 1 AND term_id IS NULL")
+                    .fetch_one(&mut *tx)
+                    .await
+                    .map_err(ApiError::from)?;
+            if unassigned == 0 {
+                return Err(ApiError::validation("There is no current term to archive"));
+            }
+            let id: i64 = sqlx::query_scalar(
+                r#"
+                INSERT INTO terms (user_id, name, start_date, end_date, is_current)
+                VALUES (1, 'Term ending ' || date('now', 'localtime'),
+                        (SELECT date(MIN(created_at)) FROM courses WHERE is_active = 1 AND term_id IS NULL),
+                        date('now', 'localtime'), 1)
+                RETURNING id
+                "#,
+            )
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(ApiError::from)?;
+            sqlx::query("UPDATE courses SET term_id = ? WHERE is_active = 1 AND term_id IS NULL")
+                .bind(id)
+                .execute(&mut *tx)
+                .await
+                .map_err(ApiError::from)?;
+            id
+        }
+    };
+
+    let archived_course_ids: Vec<i64> =
+        sqlx::query_scalar("SELECT id FROM courses WHERE term_id = ? AND is_active = 1 ORDER BY id")
+            .bind(term_id)
+            .fetch_all(&mut *tx)
+            .await
+            .map_err(ApiError::from)?;
+
+    // Freeze analytics before the courses leave active views
+    sqlx::query(
+        r#"
+        INSERT INTO term_snapshots (
+            term_id, course_id, course_name, course_code, credit_hours,
+            study_minutes, study_sessions, assignments_total, assignments_completed,
+            average_assignment_score, exams_graded, average_exam_grade, current_grade, target_grade
+        )
+        SELECT ?1, c.id, c.name, c.code, c.credit_hours,
+               (SELECT COALESCE(SUM(s.duration_minutes), 0) FROM sessions s
+                WHERE s.session_type = 'study' AND s.reference_type = 'course' AND s.reference_id = c.id),
+               (SELECT COUNT(*) FROM sessions s
+                WHERE s.session_type = 'study' AND s.reference_type = 'course' AND s.reference_id = c.id),
+               (SELECT COUNT(*) FROM assignments a WHERE a.course_id = c.id),
+               (SELECT COUNT(*) FROM assignments a WHERE a.course_id = c.id AND a.is_completed = 1),
+               (SELECT AVG(a.score) FROM assignments a WHERE a.course_id = c.id AND a.score IS NOT NULL),
+               (SELECT COUNT(*) FROM exams e WHERE e.course_id = c.id AND e.grade IS NOT NULL),
+               (SELECT AVG(e.grade) FROM exams e WHERE e.course_id = c.id AND e.grade IS NOT NULL),
+               c.current_grade, c.target_grade
+        FROM courses c
+        WHERE c.term_id = ?1 AND c.is_active = 1
+        "#,
+    )
+    .bind(term_id)
+    .execute(&mut *tx)
+    .await
+    .map_err(ApiError::from)?;
+
+    let archived_meetings = sqlx::query(
+        r#"
+        UPDATE course_meetings SET archived_at = datetime('now')
+        WHERE archived_at IS NULL
+          AND course_id IN (SELECT id FROM courses WHERE term_id = ? AND is_active = 1)
+        "#,
+    )
+    .bind(term_id)
+    .execute(&mut *tx)
+    .await
+    .map_err(ApiError::from)?
+    .rows_affected();
+
+    sqlx::query("UPDATE courses SET is_active = 0 WHERE term_id = ? AND is_active = 1")
+        .bind(term_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(ApiError::from)?;
+
+    sqlx::query("UPDATE terms SET archived_at = datetime('now'), is_current = 0 WHERE id = ?")
+        .bind(term_id)
+        .execute(&mut *tx)
+        .await
+        .map_err(ApiError::from)?;
+
+    let archived_skills = archive_unless(&mut tx, "skills", data.carry_forward_skills).await?;
+    let archived_workout_templates =
+        archive_unless(&mut tx, "workout_templates", data.carry_forward_workout_templates).await?;
+
+    let next_term_id = match data.next_term {
+        Some(term) => {
+            sqlx::query("UPDATE terms SET is_current = 0 WHERE user_id = 1")
+                .execute(&mut *tx)
+                .await
+                .map_err(ApiError::from)?;
+            let id: i64 = sqlx::query_scalar(
+                "INSERT INTO terms (user_id, name, start_date, end_date, is_current) VALUES (1, ?, ?, ?, 1) RETURNING id",
+            )
+            .bind(term.name.trim())
+            .bind(&term.start_date)
+            .bind(&term.end_date)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(ApiError::from)?;
+            Some(id)
+        }
+        None => None,
+    };
+
+    tx.commit().await.map_err(ApiError::from)?;
+
+    log::info!(
+        "Archived term {} ({} courses, {} meetings); next term {:?}",
+        term_id,
+        archived_course_ids.len(),
+        archived_meetings,
+        next_term_id
+    );
+    Ok(ArchiveTermResult {
+        term_id,
+        archived_course_ids,
+        archived_meetings,
+        archived_skills,
+        archived_workout_templates,
+        next_term_id,
+    })
+}
+
+/// Archive every active row of `table` unless it is carried forward
+async fn archive_unless(conn: &mut SqliteConnection, table: &str, carry_forward: bool) -> Result<u64, ApiError> {
+    if carry_forward {
+        return Ok(0);
+    }
+    sqlx::query(&format!(
+        "UPDATE {} SET archived_at = datetime('now') WHERE archived_at IS NULL",
+        table
+    ))
+    .execute(conn)
+    .await
+    .map(|r| r.rows_affected())
+    .map_err(ApiError::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+    use std::str::FromStr;
+
+    async fn setup_pool_with_migrations() -> Pool<Sqlite> {
+        let options = SqliteConnectOptions::from_str("sqlite::memory:")
+            .unwrap()
+            .foreign_keys(false);
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect_with(options)
+            .await
+            .expect("Failed to connect to in-memory DB");
+
+        crate::db::migrations::run_migrations(&pool)
+            .await
+            .expect("Failed to run migrations");
+
+        pool
+    }
+
+    #[tokio::test]
+    async fn archiving_a_term_keeps_history_and_clears_active_views() {
+        let pool = setup_pool_with_migrations().await;
+
+        let course: i64 = sqlx::query_scalar(
+            "INSERT INTO courses (name, current_grade) VALUES ('Biology', 88) RETURNING id",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        sqlx::query("INSERT INTO course_meetings (course_id, day_of_week, start_time, end_time) VALUES (?, 2, '10:00', '11:00')")
+            .bind(course)
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO assignments (course_id, title, due_date, is_completed, score) VALUES (?, 'Lab', '2026-01-10', 1, 90), (?, 'Essay', '2026-01-20', 0, NULL)")
+            .bind(course)
+            .bind(course)
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO sessions (session_type, reference_id, reference_type, started_at, duration_minutes) VALUES ('study', ?, 'course', datetime('now'), 90)")
+            .bind(course)
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO skills (name) VALUES ('Guitar')").execute(&pool).await.unwrap();
+        sqlx::query("INSERT INTO workout_templates (name) VALUES ('Push day')").execute(&pool).await.unwrap();
+
+        // No term yet: the unassigned course is grouped into one
+        let result = archive(
+            &pool,
+            ArchiveTermInput {
+                term_id: None,
+                next_term: Some(TermInput {
+                    name: "Spring 2026".into(),
+                    start_date: Some("2026-02-01".into()),
+                    end_date: Some("2026-05-31".into()),
+                }),
+                carry_forward_skills: true,
+                carry_forward_workout_templates: false,
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(result.archived_course_ids, vec![course]);
+        assert_eq!(result.archived_meetings, 1);
+        assert_eq!((result.archived_skills, result.archived_workout_templates), (0, 1));
+
+        let is_active: i64 = sqlx::query_scalar("SELECT is_active FROM courses WHERE id = ?")
+            .bind(course)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(is_active, 0);
+
+        let snapshot: TermSnapshot = sqlx::query_as("SELECT * FROM term_snapshots WHERE term_id = ?")
+            .bind(result.term_id)
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(snapshot.course_name, "Biology");
+        assert_eq!((snapshot.study_minutes, snapshot.study_sessions), (90, 1));
+        assert_eq!((snapshot.assignments_total, snapshot.assignments_completed), (2, 1));
+        assert_eq!(snapshot.average_assignment_score, Some(90.0));
+        assert_eq!(snapshot.current_grade, Some(88.0));
+
+        let current: Vec<(i64, bool)> = sqlx::query_as("SELECT id, is_current FROM terms ORDER BY id")
+            .fetch_all(&pool)
+            .await
+            .unwrap();
+        assert_eq!(current, vec![(result.term_id, false), (result.next_term_id.unwrap(), true)]);
+
+        let err = archive(
+            &pool,
+            ArchiveTermInput {
+                term_id: Some(result.term_id),
+                next_term: None,
+                carry_forward_skills: true,
+                carry_forward_workout_templates: true,
+            },
+        )
+        .await
+        .unwrap_err();
+        assert_eq!(err.message, "Term is already archived");
+    }
+}
@@ -0,0 +1,68 @@
+//! Tracker commands
+
+use tauri::State;
+
+use crate::services::clock;
+use crate::services::trackers::{
+    self, Tracker, TrackerEntity, TrackerEntityInput, TrackerInput, TrackerLog, TrackerLogInput, TrackerSummary,
+};
+use crate::{error::ApiError, DbState};
+
+const DEFAULT_LOG_LIMIT: i64 = 50;
+const MAX_LOG_LIMIT: i64 = 500;
+
+/// Every tracker with its week figures and streaks
+#[tauri::command]
+pub async fn get_trackers(state: State<'_, DbState>) -> Result<Vec<TrackerSummary>, ApiError> {
+    trackers::summaries(&state.0, clock::now().date_naive()).await
+}
+
+#[tauri::command]
+pub async fn create_tracker(state: State<'_, DbState>, data: TrackerInput) -> Result<Tracker, ApiError> {
+    trackers::create(&state.0, &data).await
+}
+
+/// Delete a tracker with its entries and logs; built-in trackers stay
+#[tauri::command]
+pub async fn delete_tracker(state: State<'_, DbState>, id: i64) -> Result<bool, ApiError> {
+    trackers::delete(&state.0, id).await
+}
+
+#[tauri::command]
+pub async fn add_tracker_entity(state: State<'_, DbState>, data: TrackerEntityInput) -> Result<TrackerEntity, ApiError> {
+    trackers::add_entity(&state.0, &data).await
+}
+
+#[tauri::command]
+pub async fn get_tracker_entities(
+    state: State<'_, DbState>,
+    tracker_id: i64,
+    include_completed: Option<bool>,
+) -> Result<Vec<TrackerEntity>, ApiError> {
+    trackers::entities(&state.0, tracker_id, include_completed.unwrap_or(false)).await
+}
+
+#[tauri::command]
+pub async fn delete_tracker_entity(state: State<'_, DbState>, id: i64) -> Result<bool, ApiError> {
+    trackers::delete_entity(&state.0, id).await
+}
+
+#[tauri::command]
+pub async fn log_tracker_entry(state: State<'_, DbState>, data: TrackerLogInput) -> Result<TrackerLog, ApiError> {
+    trackers::record(&state.0, &data).await
+}
+
+/// A tracker's logs, newest first
+#[tauri::command]
+pub async fn get_tracker_logs(state: State<'_, DbState>, tracker_id: i64, limit: Option<i64>) -> Result<Vec<TrackerLog>, ApiError> {
+    let limit = limit.unwrap_or(DEFAULT_LOG_LIMIT);
+    if !(1..=MAX_LOG_LIMIT).contains(&limit) {
+        return Err(ApiError::validation(format!("limit must be between 1 and {}", MAX_LOG_LIMIT)));
+    }
+    trackers::logs(&state.0, tracker_id, limit).await
+}
+
+#[tauri::command]
+pub async fn delete_tracker_log(state: State<'_, DbState>, id: i64) -> Result<bool, ApiError> {
+    trackers::delete_log(&state.0, id).await
+}
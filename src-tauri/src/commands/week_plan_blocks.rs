@@ -1,7 +1,28 @@
+use chrono::{Duration, NaiveDateTime};
+use rand::Rng;
+use serde::Serialize;
+use sqlx::{Pool, Sqlite};
 use tauri::State;
-use crate::{DbState, error::ApiError, models::week_plan_block::WeekPlanBlock};
-
-#[derive(Debug, serde::Deserialize)]
+use crate::{
+    DbState,
+    commands::{
+        focus_profiles::{self, FocusProfile},
+        sessions::{insert_session, SessionInput},
+    },
+    error::ApiError,
+    models::{
+        session::{Session, SessionType},
+        week_plan_block::WeekPlanBlock,
+    },
+    services::clock,
+};
+
+/// Plan block times are naive local datetimes
+const BLOCK_TIME_FORMAT: &str = "%Y-%m-%dT%H:%M:%S";
+/// Shortest work segment worth a break before it
+const MIN_TAIL_MINUTES: i64 = 10;
+
+#[derive(Debug, Clone, serde::Deserialize, Serialize)]
 pub struct WeekPlanBlockInput {
     #[serde(default)]
     pub user_id: Option<i64>,
@@ -19,9 +40,19 @@ pub struct WeekPlanBlockInput {
     pub status: Option<String>,
     #[serde(default)]
     pub rationale_json: Option<String>,
+    #[serde(default)]
+    pub assignment_id: Option<i64>,
+    #[serde(default)]
+    pub exam_id: Option<i64>,
+    #[serde(default)]
+    pub split_group: Option<String>,
+    #[serde(default)]
+    pub skill_id: Option<i64>,
+    #[serde(default)]
+    pub workout_template_id: Option<i64>,
 }
 
-const VALID_BLOCK_TYPES: &[&str] = &["study", "assignment", "exam_prep", "break", "weekly_task"];
+const VALID_BLOCK_TYPES: &[&str] = &["study", "assignment", "exam_prep", "break", "weekly_task", "practice", "workout"];
 const VALID_STATUSES: &[&str] = &["suggested", "accepted", "locked"];
 
 fn validate_block_type(block_type: &str) -> Result<(), ApiError> {
@@ -34,6 +65,17 @@ fn validate_block_type(block_type: &str) -> Result<(), ApiError> {
     Ok(())
 }
 
+/// A block works toward at most one deliverable
+fn validate_deliverable(data: &WeekPlanBlockInput) -> Result<(), ApiError> {
+    if data.assignment_id.is_some() && data.exam_id.is_some() {
+        return Err(ApiError::validation("A block can link an assignment or an exam, not both"));
+    }
+    if data.skill_id.is_some() && (data.assignment_id.is_some() || data.exam_id.is_some()) {
+        return Err(ApiError::validation("A skill practice block can't link an assignment or exam"));
+    }
+    Ok(())
+}
+
 fn validate_status(status: &str) -> Result<(), ApiError> {
     if !VALID_STATUSES.contains(&status) {
         return Err(ApiError::validation(format!(
@@ -49,10 +91,16 @@ pub async fn create_week_plan_block(
     state: State<'_, DbState>,
     data: WeekPlanBlockInput,
 ) -> Result<WeekPlanBlock, ApiError> {
-    let pool = &state.0;
+    create_week_plan_block_inner(&state.0, data).await
+}
 
+pub(crate) async fn create_week_plan_block_inner(
+    pool: &sqlx::Pool<sqlx::Sqlite>,
+    data: WeekPlanBlockInput,
+) -> Result<WeekPlanBlock, ApiError> {
     // Validate block_type
     validate_block_type(&data.block_type)?;
+    validate_deliverable(&data)?;
 
     // Validate status if provided
     let status = data.status.unwrap_or_else(|| "suggested".to_string());
@@ -61,8 +109,8 @@ pub async fn create_week_plan_block(
     let user_id = data.user_id.unwrap_or(1);
 
     let rec = sqlx::query_as::<_, WeekPlanBlock>(
-        r#"INSERT INTO week_plan_blocks (user_id, week_start_date, start_at, end_at, block_type, course_id, weekly_task_id, title, status, rationale_json)
-           VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+        r#"INSERT INTO week_plan_blocks (user_id, week_start_date, start_at, end_at, block_type, course_id, weekly_task_id, title, status, rationale_json, assignment_id, exam_id, split_group, skill_id, workout_template_id)
+           VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
            RETURNING *"#
     )
     .bind(user_id)
@@ -75,6 +123,11 @@ pub async fn create_week_plan_block(
     .bind(&data.title)
     .bind(&status)
     .bind(&data.rationale_json)
+    .bind(data.assignment_id)
+    .bind(data.exam_id)
+    .bind(&data.split_group)
+    .bind(data.skill_id)
+    .bind(data.workout_template_id)
     .fetch_one(pool)
     .await
     .map_err(|e| {
@@ -116,6 +169,7 @@ pub async fn update_week_plan_block(
 
     // Validate block_type
     validate_block_type(&data.block_type)?;
+    validate_deliverable(&data)?;
 
     // Validate status if provided
     if let Some(ref status) = data.status {
@@ -133,7 +187,11 @@ pub async fn update_week_plan_block(
                weekly_task_id = COALESCE(?, weekly_task_id),
                title = COALESCE(?, title),
                status = COALESCE(?, status),
-               rationale_json = COALESCE(?, rationale_json)
+               rationale_json = COALESCE(?, rationale_json),
+               assignment_id = COALESCE(?, assignment_id),
+               exam_id = COALESCE(?, exam_id),
+               skill_id = COALESCE(?, skill_id),
+               workout_template_id = COALESCE(?, workout_template_id)
            WHERE id = ?
            RETURNING *"#
     )
@@ -147,6 +205,10 @@ pub async fn update_week_plan_block(
     .bind(&data.title)
     .bind(&data.status)
     .bind(&data.rationale_json)
+    .bind(data.assignment_id)
+    .bind(data.exam_id)
+    .bind(data.skill_id)
+    .bind(data.workout_template_id)
     .bind(id)
     .fetch_one(pool)
     .await
@@ -253,7 +315,13 @@ pub async fn bulk_create_plan_blocks(
     state: State<'_, DbState>,
     blocks: Vec<WeekPlanBlockInput>,
 ) -> Result<Vec<WeekPlanBlock>, ApiError> {
-    let pool = &state.0;
+    bulk_create_plan_blocks_inner(&state.0, blocks).await
+}
+
+pub(crate) async fn bulk_create_plan_blocks_inner(
+    pool: &sqlx::Pool<sqlx::Sqlite>,
+    blocks: Vec<WeekPlanBlockInput>,
+) -> Result<Vec<WeekPlanBlock>, ApiError> {
 
     // Validate all blocks first
     for (i, block) in blocks.iter().enumerate() {
@@ -263,6 +331,8 @@ pub async fn bulk_create_plan_blocks(
             validate_status(status)
                 .map_err(|e| ApiError::validation(format!("Block {}: {}", i, e.message)))?;
         }
+        validate_deliverable(block)
+            .map_err(|e| ApiError::validation(format!("Block {}: {}", i, e.message)))?;
     }
 
     if blocks.is_empty() {
@@ -275,7 +345,7 @@ pub async fn bulk_create_plan_blocks(
     })?;
 
     let mut qb = sqlx::QueryBuilder::<sqlx::Sqlite>::new(
-        "INSERT INTO week_plan_blocks (user_id, week_start_date, start_at, end_at, block_type, course_id, weekly_task_id, title, status, rationale_json) ",
+        "INSERT INTO week_plan_blocks (user_id, week_start_date, start_at, end_at, block_type, course_id, weekly_task_id, title, status, rationale_json, assignment_id, exam_id, split_group, skill_id, workout_template_id) ",
     );
 
     qb.push_values(blocks.iter(), |mut b, data| {
@@ -291,7 +361,12 @@ pub async fn bulk_create_plan_blocks(
             .push_bind(data.weekly_task_id)
             .push_bind(&data.title)
             .push_bind(status)
-            .push_bind(&data.rationale_json);
+            .push_bind(&data.rationale_json)
+            .push_bind(data.assignment_id)
+            .push_bind(data.exam_id)
+            .push_bind(&data.split_group)
+            .push_bind(data.skill_id)
+            .push_bind(data.workout_template_id);
     });
 
     qb.push(" RETURNING *");
@@ -312,3 +387,506 @@ pub async fn bulk_create_plan_blocks(
 
     Ok(created_blocks)
 }
+
+/// What a plan block was scheduled for
+#[derive(Debug, Clone, Copy)]
+pub enum Deliverable {
+    Assignment(i64),
+    Exam(i64),
+}
+
+impl Deliverable {
+    fn column(self) -> &'static str {
+        match self {
+            Deliverable::Assignment(_) => "assignment_id",
+            Deliverable::Exam(_) => "exam_id",
+        }
+    }
+
+    fn id(self) -> i64 {
+        match self {
+            Deliverable::Assignment(id) | Deliverable::Exam(id) => id,
+        }
+    }
+}
+
+/// Remove the blocks still ahead for a finished deliverable
+///
+/// Blocks that already started stay, so their hours remain attributed.
+/// Locked blocks are the user's own commitments and are kept.
+pub(crate) async fn release_remaining_blocks(pool: &Pool<Sqlite>, deliverable: Deliverable) -> Result<u64, ApiError> {
+    // Blocks are stored in naive local time
+    let now = clock::now().format("%Y-%m-%dT%H:%M:%S").to_string();
+    let result = sqlx::query(&format!(
+        "DELETE FROM week_plan_blocks WHERE {} = ? AND start_at > ? AND COALESCE(status, 'suggested') != 'locked'",
+        deliverable.column()
+    ))
+    .bind(deliverable.id())
+    .bind(&now)
+    .execute(pool)
+    .await
+    .map_err(|e| ApiError::from_sqlx(e, "Failed to release plan blocks"))?;
+
+    if result.rows_affected() > 0 {
+        log::info!("Released {} plan blocks for {:?}", result.rows_affected(), deliverable);
+    }
+    Ok(result.rows_affected())
+}
+
+#[derive(Debug, Serialize)]
+pub struct DeliverablePlanHours {
+    /// "assignment" or "exam"
+    pub kind: String,
+    pub id: i64,
+    pub title: String,
+    pub course_id: i64,
+    /// Accepted or locked hours that have already started
+    pub planned_past_hours: f64,
+    /// Accepted or locked hours still ahead
+    pub planned_upcoming_hours: f64,
+}
+
+/// Accepted and locked plan hours per linked assignment and exam
+#[tauri::command]
+pub async fn get_planned_hours_by_deliverable(
+    state: State<'_, DbState>,
+    course_id: Option<i64>,
+) -> Result<Vec<DeliverablePlanHours>, ApiError> {
+    planned_hours_by_deliverable(&state.0, course_id).await
+}
+
+pub(crate) async fn planned_hours_by_deliverable(
+    pool: &Pool<Sqlite>,
+    course_id: Option<i64>,
+) -> Result<Vec<DeliverablePlanHours>, ApiError> {
+    let now = clock::now().format("%Y-%m-%dT%H:%M:%S").to_string();
+    let rows = sqlx::query_as::<_, (String, i64, String, i64, f64, f64)>(
+        r#"
+        WITH planned AS (
+            SELECT assignment_id, exam_id, start_at,
+                   (julianday(end_at) - julianday(start_at)) * 24.0 AS hours
+            FROM week_plan_blocks
+            WHERE status IN ('accepted', 'locked')
+              AND (assignment_id IS NOT NULL OR exam_id IS NOT NULL)
+        )
+        SELECT 'assignment', a.id, a.title, a.course_id,
+               COALESCE(SUM(CASE WHEN p.start_at <= ?1 THEN p.hours END), 0.0),
+               COALESCE(SUM(CASE WHEN p.start_at > ?1 THEN p.hours END), 0.0)
+        FROM planned p JOIN assignments a ON a.id = p.assignment_id
+        WHERE ?2 IS NULL OR a.course_id = ?2
+        GROUP BY a.id
+        UNION ALL
+        SELECT 'exam', e.id, e.title, e.course_id,
+               COALESCE(SUM(CASE WHEN p.start_at <= ?1 THEN p.hours END), 0.0),
+               COALESCE(SUM(CASE WHEN p.start_at > ?1 THEN p.hours END), 0.0)
+        FROM planned p JOIN exams e ON e.id = p.exam_id
+        WHERE ?2 IS NULL OR e.course_id = ?2
+        GROUP BY e.id
+        ORDER BY 1, 2
+        "#,
+    )
+    .bind(&now)
+    .bind(course_id)
+    .fetch_all(pool)
+    .await
+    .map_err(|e| ApiError::from_sqlx(e, "Failed to compute planned hours"))?;
+
+    Ok(rows
+        .into_iter()
+        .map(|(kind, id, title, course_id, past, upcoming)| DeliverablePlanHours {
+            kind,
+            id,
+            title,
+            course_id,
+            planned_past_hours: past,
+            planned_upcoming_hours: upcoming,
+        })
+        .collect())
+}
+
+/// Start (or return the already open) session for a plan block
+///
+/// The session is referenced to the block's assignment, or else its course,
+/// and planned for the block's length. A suggested block counts as accepted
+/// once work on it starts.
+#[tauri::command]
+pub async fn start_session_from_block(
+    state: State<'_, DbState>,
+    block_id: i64,
+    focus_profile_id: Option<i64>,
+) -> Result<Session, ApiError> {
+    start_block_session(&state.0, block_id, focus_profile_id).await
+}
+
+pub(crate) async fn start_block_session(
+    pool: &Pool<Sqlite>,
+    block_id: i64,
+    focus_profile_id: Option<i64>,
+) -> Result<Session, ApiError> {
+    let block = sqlx::query_as::<_, WeekPlanBlock>("SELECT * FROM week_plan_blocks WHERE id = ?")
+        .bind(block_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(ApiError::from)?
+        .ok_or_else(|| ApiError::not_found("Plan block not found"))?;
+    match block.block_type.as_str() {
+        "break" => return Err(ApiError::validation("Breaks can't be started as sessions")),
+        "workout" => return Err(ApiError::validation("Workout blocks are logged as workouts, not sessions")),
+        _ => {}
+    }
+
+    let open: Option<Session> = sqlx::query_as(
+        "SELECT * FROM sessions WHERE week_plan_block_id = ? AND ended_at IS NULL ORDER BY id DESC LIMIT 1",
+    )
+    .bind(block_id)
+    .fetch_optional(pool)
+    .await
+    .map_err(ApiError::from)?;
+    if let Some(session) = open {
+        return Ok(session);
+    }
+
+    let (session_type, reference_id, reference_type) = match (block.skill_id, block.assignment_id, block.course_id) {
+        (Some(skill_id), _, _) => (SessionType::Practice, Some(skill_id), Some("skill".to_string())),
+        (None, Some(assignment_id), _) => (SessionType::Study, Some(assignment_id), Some("assignment".to_string())),
+        (None, None, Some(course_id)) => (SessionType::Study, Some(course_id), Some("course".to_string())),
+        (None, None, None) => (SessionType::Study, None, None),
+    };
+    let planned_minutes = match (parse_block_time(&block.start_at), parse_block_time(&block.end_at)) {
+        (Ok(start), Ok(end)) => Some((end - start).num_minutes()),
+        _ => None,
+    };
+
+    let session = insert_session(
+        pool,
+        &SessionInput {
+            user_id: None,
+            session_type,
+            reference_id,
+            reference_type,
+            started_at: None,
+            notes: None,
+            planned_minutes,
+            focus_profile_id,
+            title: block.title,
+            course_meeting_id: None,
+            meeting_date: None,
+            week_plan_block_id: Some(block_id),
+        },
+    )
+    .await?;
+
+    sqlx::query(
+        r#"UPDATE week_plan_blocks
+           SET progress = 'in_progress', actual_minutes = NULL,
+               status = CASE WHEN COALESCE(status, 'suggested') = 'suggested' THEN 'accepted' ELSE status END
+           WHERE id = ?"#,
+    )
+    .bind(block_id)
+    .execute(pool)
+    .await
+    .map_err(|e| ApiError::from_sqlx(e, "Failed to record plan block progress"))?;
+    Ok(session)
+}
+
+/// Mark a block done with how long its session actually ran
+pub(crate) async fn finish_block(pool: &Pool<Sqlite>, block_id: i64, actual_minutes: Option<i64>) -> Result<(), ApiError> {
+    sqlx::query("UPDATE week_plan_blocks SET progress = 'done', actual_minutes = ? WHERE id = ?")
+        .bind(actual_minutes)
+        .bind(block_id)
+        .execute(pool)
+        .await
+        .map_err(|e| ApiError::from_sqlx(e, "Failed to record plan block progress"))?;
+    Ok(())
+}
+
+/// Split work blocks longer than one pomodoro into work and break sub-blocks
+///
+/// Uses the given focus profile, or the first one. Returns the inputs to
+/// pass to `bulk_create_plan_blocks`; breaks and blocks that fit in one
+/// pomodoro come back unchanged.
+#[tauri::command]
+pub async fn split_plan_blocks(
+    state: State<'_, DbState>,
+    blocks: Vec<WeekPlanBlockInput>,
+    focus_profile_id: Option<i64>,
+) -> Result<Vec<WeekPlanBlockInput>, ApiError> {
+    let profile = match focus_profile_id {
+        Some(id) => focus_profiles::find(&state.0, id).await?,
+        None => focus_profiles::load(&state.0)
+            .await?
+            .into_iter()
+            .next()
+            .ok_or_else(|| ApiError::validation("No focus profile to split blocks with"))?,
+    };
+
+    let mut split = Vec::with_capacity(blocks.len());
+    for (i, block) in blocks.into_iter().enumerate() {
+        let parts = split_block(block, &profile)
+            .map_err(|e| ApiError::validation(format!("Block {}: {}", i, e.message)))?;
+        split.extend(parts);
+    }
+    Ok(split)
+}
+
+fn parse_block_time(value: &str) -> Result<NaiveDateTime, ApiError> {
+    NaiveDateTime::parse_from_str(value, BLOCK_TIME_FORMAT)
+        .map_err(|_| ApiError::validation(format!("Invalid block time '{}'", value)))
+}
+
+/// Work, break, work, ... until the block's end
+///
+/// The block always ends on work: when what is left after a pomodoro would
+/// not fit a break plus `MIN_TAIL_MINUTES` of work, the last pomodoro runs
+/// to the end instead.
+pub(crate) fn split_block(block: WeekPlanBlockInput, profile: &FocusProfile) -> Result<Vec<WeekPlanBlockInput>, ApiError> {
+    let start = parse_block_time(&block.start_at)?;
+    let end = parse_block_time(&block.end_at)?;
+    let work = Duration::minutes(profile.work_minutes);
+    if block.block_type == "break" || end - start <= work {
+        return Ok(vec![block]);
+    }
+
+    // (start, end, break kind)
+    let mut segments: Vec<(NaiveDateTime, NaiveDateTime, Option<&str>)> = Vec::new();
+    let mut cursor = start;
+    let mut pomodoros = 0;
+    loop {
+        let work_end = (cursor + work).min(end);
+        segments.push((cursor, work_end, None));
+        cursor = work_end;
+        pomodoros += 1;
+
+        let long = pomodoros % profile.long_break_every == 0;
+        let break_len = Duration::minutes(if long { profile.long_break_minutes } else { profile.break_minutes });
+        if end - cursor <= break_len + Duration::minutes(MIN_TAIL_MINUTES) {
+            if let Some(last) = segments.last_mut() {
+                last.1 = end;
+            }
+            break;
+        }
+        if break_len > Duration::zero() {
+            segments.push((cursor, cursor + break_len, Some(if long { "Long break" } else { "Break" })));
+            cursor += break_len;
+        }
+    }
+
+    if segments.len() <= 1 {
+        return Ok(vec![block]);
+    }
+
+    let group = format!("{:016x}", clock::rng().gen::<u64>());
+    Ok(segments
+        .into_iter()
+        .map(|(seg_start, seg_end, break_title)| {
+            let mut part = block.clone();
+            part.start_at = seg_start.format(BLOCK_TIME_FORMAT).to_string();
+            part.end_at = seg_end.format(BLOCK_TIME_FORMAT).to_string();
+            part.split_group = Some(group.clone());
+            if let Some(title) = break_title {
+                part.block_type = "break".to_string();
+                part.title = Some(title.to_string());
+                part.course_id = None;
+                part.weekly_task_id = None;
+                part.assignment_id = None;
+                part.exam_id = None;
+                part.skill_id = None;
+                part.workout_template_id = None;
+            }
+            part
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ErrorCode;
+    use crate::test_support::{self, course, plan_block};
+    use chrono::TimeZone;
+
+    async fn assignment(pool: &Pool<Sqlite>, course_id: i64, title: &str) -> i64 {
+        sqlx::query_scalar("INSERT INTO assignments (course_id, title) VALUES (?, ?) RETURNING id")
+            .bind(course_id)
+            .bind(title)
+            .fetch_one(pool)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn finishing_an_assignment_releases_only_its_upcoming_blocks() {
+        let pool = test_support::pool().await;
+        let _clock = clock::freeze(chrono::Local.with_ymd_and_hms(2030, 9, 4, 12, 0, 0).unwrap());
+        let algorithms = course("Algorithms").create(&pool).await;
+        let essay = assignment(&pool, algorithms.id, "Essay").await;
+        let other = assignment(&pool, algorithms.id, "Problem set").await;
+
+        let linked = |start: &str, end: &str, assignment_id: i64, status: &str| {
+            let mut block = plan_block("2030-09-02", start, end, Some(algorithms.id));
+            block.block_type = "assignment".to_string();
+            block.assignment_id = Some(assignment_id);
+            block.status = Some(status.to_string());
+            block
+        };
+        let blocks = vec![
+            linked("2030-09-02T09:00:00", "2030-09-02T11:00:00", essay, "accepted"),
+            linked("2030-09-05T09:00:00", "2030-09-05T10:30:00", essay, "accepted"),
+            linked("2030-09-06T09:00:00", "2030-09-06T10:00:00", essay, "locked"),
+            linked("2030-09-05T14:00:00", "2030-09-05T15:00:00", other, "accepted"),
+        ];
+        bulk_create_plan_blocks_inner(&pool, blocks).await.unwrap();
+
+        let hours = planned_hours_by_deliverable(&pool, Some(algorithms.id)).await.unwrap();
+        let essay_hours = hours.iter().find(|h| h.id == essay && h.kind == "assignment").unwrap();
+        assert!((essay_hours.planned_past_hours - 2.0).abs() < 1e-9);
+        assert!((essay_hours.planned_upcoming_hours - 2.5).abs() < 1e-9);
+
+        assert_eq!(release_remaining_blocks(&pool, Deliverable::Assignment(essay)).await.unwrap(), 1);
+        let remaining: Vec<(String, Option<i64>)> =
+            sqlx::query_as("SELECT start_at, assignment_id FROM week_plan_blocks ORDER BY start_at")
+                .fetch_all(&pool)
+                .await
+                .unwrap();
+        assert_eq!(
+            remaining,
+            vec![
+                ("2030-09-02T09:00:00".to_string(), Some(essay)),
+                ("2030-09-05T14:00:00".to_string(), Some(other)),
+                ("2030-09-06T09:00:00".to_string(), Some(essay)),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn a_session_started_from_a_block_records_its_actual_length() {
+        let pool = test_support::pool().await;
+        let algorithms = course("Algorithms").create(&pool).await;
+        let essay = assignment(&pool, algorithms.id, "Essay").await;
+        let mut block = plan_block("2030-09-02", "2030-09-02T09:00:00", "2030-09-02T10:30:00", Some(algorithms.id));
+        block.block_type = "assignment".to_string();
+        block.assignment_id = Some(essay);
+        block.title = Some("Essay draft".to_string());
+        let block = create_week_plan_block_inner(&pool, block).await.unwrap();
+
+        let session = start_block_session(&pool, block.id, None).await.unwrap();
+        assert_eq!(session.reference_type.as_deref(), Some("assignment"));
+        assert_eq!(session.reference_id, Some(essay));
+        assert_eq!(session.planned_minutes, Some(90));
+        assert_eq!(session.title.as_deref(), Some("Essay draft"));
+        // Starting again returns the open session
+        assert_eq!(start_block_session(&pool, block.id, None).await.unwrap().id, session.id);
+
+        let (status, progress): (Option<String>, Option<String>) =
+            sqlx::query_as("SELECT status, progress FROM week_plan_blocks WHERE id = ?")
+                .bind(block.id)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(status.as_deref(), Some("accepted"));
+        assert_eq!(progress.as_deref(), Some("in_progress"));
+
+        sqlx::query("UPDATE sessions SET started_at = datetime('now', '-75 minutes') WHERE id = ?")
+            .bind(session.id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        crate::commands::sessions::close_session(&pool, session.id, Some(4)).await.unwrap();
+
+        let (progress, actual): (Option<String>, Option<i64>) =
+            sqlx::query_as("SELECT progress, actual_minutes FROM week_plan_blocks WHERE id = ?")
+                .bind(block.id)
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(progress.as_deref(), Some("done"));
+        assert_eq!(actual, Some(75));
+    }
+
+    #[tokio::test]
+    async fn breaks_cannot_be_started() {
+        let pool = test_support::pool().await;
+        let mut block = plan_block("2030-09-02", "2030-09-02T09:00:00", "2030-09-02T09:05:00", None);
+        block.block_type = "break".to_string();
+        let block = create_week_plan_block_inner(&pool, block).await.unwrap();
+
+        let err = start_block_session(&pool, block.id, None).await.unwrap_err();
+        assert_eq!(err.code, ErrorCode::Validation);
+        let err = start_block_session(&pool, block.id + 1, None).await.unwrap_err();
+        assert_eq!(err.code, ErrorCode::NotFound);
+    }
+
+    fn pomodoro() -> FocusProfile {
+        FocusProfile {
+            id: 1,
+            name: "Pomodoro".to_string(),
+            work_minutes: 25,
+            break_minutes: 5,
+            long_break_minutes: 15,
+            long_break_every: 4,
+            auto_start_breaks: false,
+            auto_start_work: false,
+            tag: None,
+        }
+    }
+
+    fn spans(parts: &[WeekPlanBlockInput]) -> Vec<(&str, &str, &str)> {
+        parts
+            .iter()
+            .map(|p| (&p.start_at[11..16], &p.end_at[11..16], p.block_type.as_str()))
+            .collect()
+    }
+
+    #[test]
+    fn long_blocks_split_into_pomodoros_with_a_long_break() {
+        let mut block = plan_block("2030-09-02", "2030-09-02T09:00:00", "2030-09-02T11:30:00", Some(3));
+        block.assignment_id = Some(8);
+        let parts = split_block(block, &pomodoro()).unwrap();
+        assert_eq!(
+            spans(&parts),
+            vec![
+                ("09:00", "09:25", "study"),
+                ("09:25", "09:30", "break"),
+                ("09:30", "09:55", "study"),
+                ("09:55", "10:00", "break"),
+                ("10:00", "10:25", "study"),
+                ("10:25", "10:30", "break"),
+                ("10:30", "10:55", "study"),
+                ("10:55", "11:10", "break"),
+                ("11:10", "11:30", "study"),
+            ]
+        );
+        let group = parts[0].split_group.clone().unwrap();
+        assert!(parts.iter().all(|p| p.split_group.as_deref() == Some(group.as_str())));
+        assert_eq!(parts[1].course_id, None);
+        assert_eq!(parts[1].assignment_id, None);
+        assert_eq!(parts[8].assignment_id, Some(8));
+    }
+
+    #[test]
+    fn short_tails_are_folded_into_the_last_pomodoro() {
+        // 25 + 5 + 25 leaves 5 minutes: not worth a break
+        let block = plan_block("2030-09-02", "2030-09-02T09:00:00", "2030-09-02T10:00:00", None);
+        let parts = split_block(block, &pomodoro()).unwrap();
+        assert_eq!(
+            spans(&parts),
+            vec![("09:00", "09:25", "study"), ("09:25", "09:30", "break"), ("09:30", "10:00", "study")]
+        );
+
+        // Fits in one pomodoro and a short tail: unchanged
+        let block = plan_block("2030-09-02", "2030-09-02T09:00:00", "2030-09-02T09:35:00", None);
+        let parts = split_block(block, &pomodoro()).unwrap();
+        assert_eq!(spans(&parts), vec![("09:00", "09:35", "study")]);
+        assert_eq!(parts[0].split_group, None);
+    }
+
+    #[tokio::test]
+    async fn a_block_links_one_deliverable() {
+        let pool = test_support::pool().await;
+        let mut block = plan_block("2030-09-02", "2030-09-02T09:00:00", "2030-09-02T10:00:00", None);
+        block.assignment_id = Some(1);
+        block.exam_id = Some(1);
+        let err = create_week_plan_block_inner(&pool, block).await.unwrap_err();
+        assert_eq!(err.code, ErrorCode::Validation);
+    }
+}
@@ -0,0 +1,423 @@
+//! Workout Planning
+//!
+//! Suggests workout blocks for a week: as many as the weekly workout target
+//! still needs after logged workouts and accepted workout blocks, at most one
+//! a day. Templates with a program schedule only go on their weekdays, and
+//! the least used template that fits a day goes first.
+//!
+//! Recovery rules: a heavy template is not placed the day after a heavy
+//! workout for the same muscle group, nor in the morning after a late
+//! evening (anything busy until `LATE_HOUR` or later). The planner mode
+//! scales the target, and the exam period leaves heavy templates out. With
+//! cycle tracking on, heavy templates also stay off days predicted to be
+//! menstrual or premenstrual.
+
+use std::collections::{HashMap, HashSet};
+
+use chrono::{Datelike, Duration, Local, NaiveDate, NaiveDateTime, NaiveTime, Timelike};
+use sqlx::{Pool, Sqlite};
+use tauri::State;
+
+use crate::{
+    commands::{
+        calendar::{get_calendar_items_for_pool, CalendarItem, CalendarQuery},
+        free_slots::{free_slots, local_naive, round_up_to_step, FreeSlotQuery},
+        planner_mode,
+        week_plan_blocks::WeekPlanBlockInput,
+    },
+    error::ApiError,
+    services::{clock, cycle, settings},
+    utils::parse_datetime_utc,
+    DbState,
+};
+
+const DEFAULT_WORKOUT_MINUTES: i64 = 60;
+/// Busy until this hour or later makes an evening late
+const LATE_HOUR: u32 = 21;
+/// Heavy workouts after a late evening start no earlier than this
+const RECOVERED_HOUR: u32 = 12;
+
+const BLOCK_TIME_FORMAT: &str = "%Y-%m-%dT%H:%M:%S";
+
+/// (id, name, schedule_days, muscle_group, is_heavy, planned_minutes)
+type TemplateRow = (i64, String, Option<String>, Option<String>, bool, Option<i64>);
+
+struct Template {
+    id: i64,
+    name: String,
+    /// Weekdays, 0 = Sunday; empty means any day
+    days: Vec<u32>,
+    muscle_group: Option<String>,
+    is_heavy: bool,
+    minutes: i64,
+}
+
+impl Template {
+    fn from_row((id, name, days, muscle_group, is_heavy, minutes): TemplateRow) -> Self {
+        Template {
+            id,
+            name,
+            days: days
+                .and_then(|d| serde_json::from_str::<Vec<u32>>(&d).ok())
+                .unwrap_or_default(),
+            muscle_group,
+            is_heavy,
+            minutes: minutes.unwrap_or(DEFAULT_WORKOUT_MINUTES),
+        }
+    }
+
+    fn fits(&self, day: NaiveDate) -> bool {
+        self.days.is_empty() || self.days.contains(&day.weekday().num_days_from_sunday())
+    }
+}
+
+/// Suggested workout blocks for the week starting `week_start_date`; pass
+/// them to `bulk_create_plan_blocks` to keep them
+#[tauri::command]
+pub async fn suggest_workout_blocks(
+    state: State<'_, DbState>,
+    week_start_date: String,
+) -> Result<Vec<WeekPlanBlockInput>, ApiError> {
+    workout_suggestions(&state.0, &week_start_date).await
+}
+
+pub(crate) async fn workout_suggestions(
+    pool: &Pool<Sqlite>,
+    week_start_date: &str,
+) -> Result<Vec<WeekPlanBlockInput>, ApiError> {
+    let week_start = NaiveDate::parse_from_str(week_start_date, "%Y-%m-%d")
+        .map_err(|_| ApiError::validation("week_start_date must be YYYY-MM-DD"))?;
+    let week_end = week_start + Duration::days(6);
+    let now = clock::now().naive_local();
+    let first_day = week_start.max(now.date());
+    if first_day > week_end {
+        return Ok(Vec::new());
+    }
+
+    let weights = planner_mode::current_weights(pool).await?;
+    let target = (settings::get_i64(pool, "weekly_workout_target").await? as f64 * weights.workout_scale).round() as i64;
+    let templates: Vec<Template> = sqlx::query_as::<_, TemplateRow>(
+        "SELECT id, name, schedule_days, muscle_group, is_heavy, planned_minutes FROM workout_templates WHERE archived_at IS NULL ORDER BY id",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(ApiError::from)?
+    .into_iter()
+    .map(Template::from_row)
+    .collect();
+
+    // Logged workouts and kept workout blocks from the day before the week
+    // on: (day, template, logged, block still ahead)
+    let workouts: Vec<(String, Option<i64>, bool, bool)> = sqlx::query_as(
+        r#"
+        SELECT date(w.logged_at, 'localtime'), t.id, 1, 0
+        FROM workouts w LEFT JOIN workout_templates t ON t.name = w.name
+        WHERE date(w.logged_at, 'localtime') BETWEEN ?1 AND ?2
+        UNION ALL
+        SELECT date(b.start_at), b.workout_template_id, 0, b.start_at > ?3
+        FROM week_plan_blocks b
+        WHERE b.block_type = 'workout' AND b.status IN ('accepted', 'locked') AND b.missed_at IS NULL
+          AND date(b.start_at) BETWEEN ?1 AND ?2
+        "#,
+    )
+    .bind((week_start - Duration::days(1)).to_string())
+    .bind(week_end.to_string())
+    .bind(now.format(BLOCK_TIME_FORMAT).to_string())
+    .fetch_all(pool)
+    .await
+    .map_err(ApiError::from)?;
+
+    // One workout a day: days that already have one are taken
+    let mut taken: HashSet<NaiveDate> = HashSet::new();
+    // Muscle groups trained heavy, by day
+    let mut heavy: HashMap<NaiveDate, HashSet<String>> = HashMap::new();
+    let mut uses: HashMap<i64, usize> = HashMap::new();
+    // Logged workouts count, and blocks still ahead unless that day is
+    // already logged; past blocks count once their workout is logged
+    let mut counted = 0;
+    let mut logged_days = HashSet::new();
+    let mut ahead_days = Vec::new();
+    for (day, template_id, logged, ahead) in &workouts {
+        let Ok(day) = NaiveDate::parse_from_str(day, "%Y-%m-%d") else {
+            continue;
+        };
+        let template = template_id.and_then(|id| templates.iter().find(|t| t.id == id));
+        if let Some(t) = template.filter(|t| t.is_heavy) {
+            heavy.entry(day).or_default().insert(t.muscle_group.clone().unwrap_or_default());
+        }
+        if day < week_start {
+            continue;
+        }
+        taken.insert(day);
+        if let Some(t) = template {
+            *uses.entry(t.id).or_default() += 1;
+        }
+        if *logged {
+            counted += 1;
+            logged_days.insert(day);
+        } else if *ahead {
+            ahead_days.push(day);
+        }
+    }
+    counted += ahead_days.iter().filter(|day| !logged_days.contains(day)).count() as i64;
+    let mut remaining = target - counted;
+    if remaining <= 0 {
+        return Ok(Vec::new());
+    }
+
+    let late = late_evenings(pool, first_day - Duration::days(1), week_end).await?;
+    let low_energy: HashSet<NaiveDate> = cycle::days(pool, first_day, week_end, now.date())
+        .await?
+        .into_iter()
+        .filter(|d| d.low_energy)
+        .map(|d| d.date)
+        .collect();
+
+    let mut suggestions = Vec::new();
+    let mut day = first_day;
+    while day <= week_end && remaining > 0 {
+        if taken.contains(&day) {
+            day += Duration::days(1);
+            continue;
+        }
+        let yesterday = day - Duration::days(1);
+        let mut candidates: Vec<Option<&Template>> = if templates.is_empty() {
+            vec![None]
+        } else {
+            let mut fitting: Vec<&Template> = templates
+                .iter()
+                .filter(|t| t.fits(day) && (weights.heavy_workouts || !t.is_heavy))
+                .collect();
+            fitting.sort_by_key(|t| uses.get(&t.id).copied().unwrap_or(0));
+            fitting.into_iter().map(Some).collect()
+        };
+        // Recovery: no heavy work on a muscle group trained heavy yesterday
+        candidates.retain(|t| match t {
+            Some(t) if t.is_heavy => !heavy
+                .get(&yesterday)
+                .is_some_and(|groups| groups.contains(&t.muscle_group.clone().unwrap_or_default())),
+            _ => true,
+        });
+        if low_energy.contains(&day) {
+            candidates.retain(|t| !t.is_some_and(|t| t.is_heavy));
+        }
+
+        for template in candidates {
+            let minutes = template.map_or(DEFAULT_WORKOUT_MINUTES, |t| t.minutes);
+            let mut earliest = day.and_time(NaiveTime::MIN);
+            if template.is_some_and(|t| t.is_heavy) && late.contains(&yesterday) {
+                earliest = day.and_hms_opt(RECOVERED_HOUR, 0, 0).unwrap_or(earliest);
+            }
+            if day == now.date() {
+                earliest = earliest.max(round_up_to_step(now));
+            }
+            let Some(start) = first_fit(pool, day, minutes, earliest).await? else {
+                continue;
+            };
+            let end = start + Duration::minutes(minutes);
+
+            if let Some(t) = template {
+                *uses.entry(t.id).or_default() += 1;
+                if t.is_heavy {
+                    heavy.entry(day).or_default().insert(t.muscle_group.clone().unwrap_or_default());
+                }
+            }
+            suggestions.push(WeekPlanBlockInput {
+                user_id: None,
+                week_start_date: week_start_date.to_string(),
+                start_at: start.format(BLOCK_TIME_FORMAT).to_string(),
+                end_at: end.format(BLOCK_TIME_FORMAT).to_string(),
+                block_type: "workout".to_string(),
+                course_id: None,
+                weekly_task_id: None,
+                title: Some(template.map_or_else(|| "Workout".to_string(), |t| t.name.clone())),
+                status: Some("suggested".to_string()),
+                rationale_json: Some(
+                    serde_json::json!({ "reason": "Weekly workout target", "target": target }).to_string(),
+                ),
+                assignment_id: None,
+                exam_id: None,
+                split_group: None,
+                skill_id: None,
+                workout_template_id: template.map(|t| t.id),
+            });
+            remaining -= 1;
+            break;
+        }
+        day += Duration::days(1);
+    }
+
+    Ok(suggestions)
+}
+
+/// Start of the first free time on `day` that fits `minutes` from `earliest` on
+async fn first_fit(
+    pool: &Pool<Sqlite>,
+    day: NaiveDate,
+    minutes: i64,
+    earliest: NaiveDateTime,
+) -> Result<Option<NaiveDateTime>, ApiError> {
+    let slots = free_slots(
+        pool,
+        FreeSlotQuery {
+            start_date: day.to_string(),
+            end_date: day.to_string(),
+            min_minutes: Some(minutes),
+            include_suggested: Some(true),
+        },
+    )
+    .await?;
+    Ok(slots.iter().find_map(|slot| {
+        let start = local_naive(&slot.start_at)?.max(earliest);
+        let end = local_naive(&slot.end_at)?;
+        (end - start >= Duration::minutes(minutes)).then_some(start)
+    }))
+}
+
+/// Days busy until `LATE_HOUR` or later, from the calendar and ended sessions
+async fn late_evenings(pool: &Pool<Sqlite>, from: NaiveDate, to: NaiveDate) -> Result<HashSet<NaiveDate>, ApiError> {
+    let is_late = |start: NaiveDateTime, end: NaiveDateTime| end.hour() >= LATE_HOUR || end.date() > start.date();
+    let mut late = HashSet::new();
+
+    let items = get_calendar_items_for_pool(
+        pool,
+        CalendarQuery {
+            start_date: from.to_string(),
+            end_date: to.to_string(),
+            include_assignments: Some(false),
+            include_exams: Some(true),
+        },
+    )
+    .await?;
+    // Suggested plan blocks are being replaced
+    let kept = |i: &&CalendarItem| !(i.source == "plan_block" && i.status.as_deref() == Some("suggested"));
+    for item in items.iter().filter(|i| i.busy && !i.all_day).filter(kept) {
+        if let (Some(start), Some(end)) = (local_naive(&item.start_at), local_naive(&item.end_at)) {
+            if is_late(start, end) {
+                late.insert(start.date());
+            }
+        }
+    }
+
+    // Session times are stored in UTC; a day of slack covers any offset
+    let sessions: Vec<(String, String)> = sqlx::query_as(
+        "SELECT started_at, ended_at FROM sessions WHERE ended_at IS NOT NULL AND started_at >= ?",
+    )
+    .bind((from - Duration::days(1)).to_string())
+    .fetch_all(pool)
+    .await
+    .map_err(ApiError::from)?;
+    for (started_at, ended_at) in sessions {
+        let (Some(start), Some(end)) = (parse_datetime_utc(&started_at), parse_datetime_utc(&ended_at)) else {
+            continue;
+        };
+        let (start, end) = (start.with_timezone(&Local).naive_local(), end.with_timezone(&Local).naive_local());
+        if start.date() >= from && is_late(start, end) {
+            late.insert(start.date());
+        }
+    }
+    Ok(late)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{TimeZone, Utc};
+    use serde_json::json;
+
+    async fn template(pool: &Pool<Sqlite>, name: &str, days: &str, muscle_group: &str, is_heavy: bool) -> i64 {
+        sqlx::query_scalar(
+            "INSERT INTO workout_templates (name, schedule_days, muscle_group, is_heavy, planned_minutes) VALUES (?, ?, ?, ?, 60) RETURNING id",
+        )
+        .bind(name)
+        .bind(days)
+        .bind(muscle_group)
+        .bind(is_heavy)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+    }
+
+    fn day_of(block: &WeekPlanBlockInput) -> String {
+        block.start_at[..10].to_string()
+    }
+
+    #[tokio::test]
+    async fn workouts_follow_the_program_and_wait_after_a_late_evening() {
+        let pool = crate::test_support::pool().await;
+        let _clock = clock::freeze(Local.with_ymd_and_hms(2030, 9, 2, 7, 0, 0).unwrap());
+        settings::set(&pool, "weekly_workout_target", json!(3)).await.unwrap();
+        let legs = template(&pool, "Legs", "[1,3,5]", "legs", true).await;
+        let upper = template(&pool, "Upper", "[]", "upper", false).await;
+
+        // Studying until 22:30 on Tuesday
+        let at = |h, m| {
+            Local
+                .with_ymd_and_hms(2030, 9, 3, h, m, 0)
+                .unwrap()
+                .with_timezone(&Utc)
+                .format("%Y-%m-%d %H:%M:%S")
+                .to_string()
+        };
+        sqlx::query("INSERT INTO sessions (session_type, started_at, ended_at, duration_minutes) VALUES ('study', ?, ?, 90)")
+            .bind(at(21, 0))
+            .bind(at(22, 30))
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let blocks = workout_suggestions(&pool, "2030-09-02").await.unwrap();
+        let placed: Vec<(String, Option<i64>)> = blocks.iter().map(|b| (day_of(b), b.workout_template_id)).collect();
+        assert_eq!(
+            placed,
+            vec![
+                ("2030-09-02".to_string(), Some(legs)),
+                ("2030-09-03".to_string(), Some(upper)),
+                ("2030-09-04".to_string(), Some(legs)),
+            ]
+        );
+        assert!(blocks.iter().all(|b| b.block_type == "workout" && b.status.as_deref() == Some("suggested")));
+        // Heavy legs the morning after the late session waits until noon
+        assert!(&blocks[2].start_at[11..] >= "12:00:00");
+    }
+
+    #[tokio::test]
+    async fn heavy_days_for_one_muscle_group_are_not_back_to_back() {
+        let pool = crate::test_support::pool().await;
+        let _clock = clock::freeze(Local.with_ymd_and_hms(2030, 9, 2, 7, 0, 0).unwrap());
+        settings::set(&pool, "weekly_workout_target", json!(3)).await.unwrap();
+        template(&pool, "Legs", "[]", "legs", true).await;
+
+        // Monday is already logged, so two more are placed
+        sqlx::query("INSERT INTO workouts (name, duration_minutes, logged_at) VALUES ('Legs', 60, ?)")
+            .bind(
+                Local
+                    .with_ymd_and_hms(2030, 9, 2, 6, 0, 0)
+                    .unwrap()
+                    .with_timezone(&Utc)
+                    .format("%Y-%m-%d %H:%M:%S")
+                    .to_string(),
+            )
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let blocks = workout_suggestions(&pool, "2030-09-02").await.unwrap();
+        let days: Vec<String> = blocks.iter().map(day_of).collect();
+        assert_eq!(days, vec!["2030-09-04", "2030-09-06"]);
+    }
+
+    #[tokio::test]
+    async fn the_exam_period_plans_fewer_and_lighter_workouts() {
+        let pool = crate::test_support::pool().await;
+        let _clock = clock::freeze(Local.with_ymd_and_hms(2030, 9, 2, 7, 0, 0).unwrap());
+        settings::set(&pool, "weekly_workout_target", json!(3)).await.unwrap();
+        settings::set(&pool, "planner_mode", json!("exam_period")).await.unwrap();
+        template(&pool, "Legs", "[]", "legs", true).await;
+        let mobility = template(&pool, "Mobility", "[]", "full", false).await;
+
+        let blocks = workout_suggestions(&pool, "2030-09-02").await.unwrap();
+        assert_eq!(blocks.len(), 2);
+        assert!(blocks.iter().all(|b| b.workout_template_id == Some(mobility)));
+    }
+}
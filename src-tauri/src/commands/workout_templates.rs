@@ -20,7 +20,7 @@ pub struct TemplateExerciseInput {
 pub async fn get_workout_templates(state: State<'_, DbState>) -> Result<Vec<WorkoutTemplate>, ApiError> {
     let pool = &state.0;
     let rows = sqlx::query_as::<_, WorkoutTemplate>(
-        "SELECT id, user_id, name, created_at, updated_at FROM workout_templates ORDER BY updated_at DESC"
+        "SELECT id, user_id, name, created_at, updated_at, schedule_days, muscle_group, is_heavy, planned_minutes FROM workout_templates WHERE archived_at IS NULL ORDER BY updated_at DESC"
     )
     .fetch_all(pool)
     .await
@@ -56,7 +56,7 @@ pub async fn create_workout_template(
     // Create the template
     let template = sqlx::query_as::<_, WorkoutTemplate>(
         "INSERT INTO workout_templates (user_id, name) VALUES (1, ?) 
-         RETURNING id, user_id, name, created_at, updated_at"
+         RETURNING id, user_id, name, created_at, updated_at, schedule_days, muscle_group, is_heavy, planned_minutes"
     )
     .bind(&name)
     .fetch_one(pool)
@@ -96,7 +96,7 @@ pub async fn update_workout_template(
     // Update the template name and updated_at
     let template = sqlx::query_as::<_, WorkoutTemplate>(
         "UPDATE workout_templates SET name = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ? 
-         RETURNING id, user_id, name, created_at, updated_at"
+         RETURNING id, user_id, name, created_at, updated_at, schedule_days, muscle_group, is_heavy, planned_minutes"
     )
     .bind(&name)
     .bind(id)
@@ -147,3 +147,54 @@ pub async fn delete_workout_template(state: State<'_, DbState>, id: i64) -> Resu
 
     Ok(true)
 }
+
+#[derive(Debug, serde::Deserialize)]
+pub struct TemplateScheduleInput {
+    /// Program weekdays, 0 = Sunday; empty means any day
+    #[serde(default)]
+    pub days: Vec<i64>,
+    #[serde(default)]
+    pub muscle_group: Option<String>,
+    #[serde(default)]
+    pub is_heavy: bool,
+    #[serde(default)]
+    pub planned_minutes: Option<i64>,
+}
+
+/// Set when a template is done in the program and how hard it is, for the planner
+#[tauri::command]
+pub async fn set_workout_template_schedule(
+    state: State<'_, DbState>,
+    id: i64,
+    schedule: TemplateScheduleInput,
+) -> Result<WorkoutTemplate, ApiError> {
+    let pool = &state.0;
+    if schedule.days.iter().any(|d| !(0..=6).contains(d)) {
+        return Err(ApiError::validation("Schedule days must be between 0 (Sunday) and 6 (Saturday)"));
+    }
+    if schedule.planned_minutes.is_some_and(|m| !(10..=300).contains(&m)) {
+        return Err(ApiError::validation("Planned minutes must be between 10 and 300"));
+    }
+    let mut days = schedule.days;
+    days.sort_unstable();
+    days.dedup();
+    let days_json = (!days.is_empty()).then(|| serde_json::json!(days).to_string());
+    let muscle_group = schedule
+        .muscle_group
+        .map(|g| g.trim().to_lowercase())
+        .filter(|g| !g.is_empty());
+
+    sqlx::query_as::<_, WorkoutTemplate>(
+        "UPDATE workout_templates SET schedule_days = ?, muscle_group = ?, is_heavy = ?, planned_minutes = ?, updated_at = CURRENT_TIMESTAMP WHERE id = ?
+         RETURNING id, user_id, name, created_at, updated_at, schedule_days, muscle_group, is_heavy, planned_minutes"
+    )
+    .bind(days_json)
+    .bind(muscle_group)
+    .bind(schedule.is_heavy)
+    .bind(schedule.planned_minutes)
+    .bind(id)
+    .fetch_optional(pool)
+    .await
+    .map_err(ApiError::from)?
+    .ok_or_else(|| ApiError::not_found("Workout template not found"))
+}
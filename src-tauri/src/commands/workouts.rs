@@ -1,6 +1,19 @@
+use std::path::Path;
+
+use serde::Serialize;
 use tauri::State;
 
-use crate::{DbState, error::ApiError, models::workout::Workout};
+use crate::{
+    DbState,
+    agent::outcomes::link_workout_outcome,
+    commands::attachments::attachments_dir,
+    error::ApiError,
+    models::workout::{Workout, WorkoutExercise},
+    services::{aggregates, attachments::{self, StoredAttachment}, dedupe, events::{self, ActivityEvent}},
+};
+
+/// Markdown notes are for technique write-ups, not essays
+const MAX_NOTES_MD_LENGTH: usize = 20_000;
 
 #[derive(Debug, serde::Deserialize)]
 pub struct WorkoutInput {
@@ -8,30 +21,66 @@ pub struct WorkoutInput {
     pub name: Option<String>,
     pub duration_minutes: Option<i64>,
     pub notes: Option<String>,
+    #[serde(default)]
+    pub notes_md: Option<String>,
     pub logged_at: Option<String>,
 }
 
+/// A workout with its exercises and attached photos/recordings
+#[derive(Debug, Serialize)]
+pub struct WorkoutDetail {
+    #[serde(flatten)]
+    pub workout: Workout,
+    pub exercises: Vec<WorkoutExercise>,
+    pub attachments: Vec<StoredAttachment>,
+}
+
+fn validate_notes_md(notes_md: &Option<String>) -> Result<(), ApiError> {
+    if notes_md.as_ref().is_some_and(|n| n.chars().count() > MAX_NOTES_MD_LENGTH) {
+        return Err(ApiError::validation(format!(
+            "Workout notes must be at most {} characters",
+            MAX_NOTES_MD_LENGTH
+        )));
+    }
+    Ok(())
+}
+
+/// Log a workout. A matching workout logged minutes earlier fails with a
+/// conflict, unless `force` is set.
 #[tauri::command]
-pub async fn create_workout(state: State<'_, DbState>, data: WorkoutInput) -> Result<Workout, ApiError> {
-    let pool = &state.0;
+pub async fn create_workout(state: State<'_, DbState>, data: WorkoutInput, force: Option<bool>) -> Result<Workout, ApiError> {
+    if !force.unwrap_or(false) {
+        dedupe::check_workout(&state.0, &data).await?;
+    }
+    insert_workout(&state.0, &data).await
+}
+
+pub(crate) async fn insert_workout(pool: &sqlx::Pool<sqlx::Sqlite>, data: &WorkoutInput) -> Result<Workout, ApiError> {
+    validate_notes_md(&data.notes_md)?;
     let rec = sqlx::query_as::<_, Workout>(
-        "INSERT INTO workouts (user_id, name, duration_minutes, notes, logged_at) VALUES (?, ?, ?, ?, COALESCE(?, CURRENT_TIMESTAMP)) RETURNING id, user_id, name, duration_minutes, notes, logged_at"
+        "INSERT INTO workouts (user_id, name, duration_minutes, notes, notes_md, logged_at) VALUES (?, ?, ?, ?, ?, COALESCE(?, CURRENT_TIMESTAMP)) RETURNING id, user_id, name, duration_minutes, notes, logged_at, notes_md"
     )
     .bind(data.user_id.unwrap_or(1))
     .bind(&data.name)
     .bind(data.duration_minutes)
     .bind(&data.notes)
+    .bind(&data.notes_md)
     .bind(&data.logged_at)
     .fetch_one(pool)
     .await
     .map_err(ApiError::from)?;
+
+    // Logging a workout is the end of the workout flow
+    link_workout_outcome(pool, &rec);
+    aggregates::invalidate();
+    events::publish(ActivityEvent::WorkoutLogged { workout_id: rec.id });
     Ok(rec)
 }
 
 #[tauri::command]
 pub async fn get_workouts(state: State<'_, DbState>) -> Result<Vec<Workout>, ApiError> {
     let pool = &state.0;
-    let rows = sqlx::query_as::<_, Workout>("SELECT id, user_id, name, duration_minutes, notes, logged_at FROM workouts ORDER BY logged_at DESC")
+    let rows = sqlx::query_as::<_, Workout>("SELECT id, user_id, name, duration_minutes, notes, logged_at, notes_md FROM workouts ORDER BY logged_at DESC")
         .fetch_all(pool)
         .await
         .map_err(ApiError::from)?;
@@ -39,14 +88,31 @@ pub async fn get_workouts(state: State<'_, DbState>) -> Result<Vec<Workout>, Api
 }
 
 #[tauri::command]
-pub async fn get_workout(state: State<'_, DbState>, id: i64) -> Result<Workout, ApiError> {
-    let pool = &state.0;
-    let row = sqlx::query_as::<_, Workout>("SELECT id, user_id, name, duration_minutes, notes, logged_at FROM workouts WHERE id = ?")
+pub async fn get_workout(app: tauri::AppHandle, state: State<'_, DbState>, id: i64) -> Result<WorkoutDetail, ApiError> {
+    workout_detail(&state.0, &attachments_dir(&app)?, id).await
+}
+
+pub(crate) async fn workout_detail(
+    pool: &sqlx::Pool<sqlx::Sqlite>,
+    attachments_root: &Path,
+    id: i64,
+) -> Result<WorkoutDetail, ApiError> {
+    let workout = sqlx::query_as::<_, Workout>("SELECT id, user_id, name, duration_minutes, notes, logged_at, notes_md FROM workouts WHERE id = ?")
         .bind(id)
-        .fetch_one(pool)
+        .fetch_optional(pool)
         .await
-        .map_err(ApiError::from)?;
-    Ok(row)
+        .map_err(ApiError::from)?
+        .ok_or_else(|| ApiError::not_found("Workout not found"))?;
+    let exercises = sqlx::query_as::<_, WorkoutExercise>(
+        "SELECT id, workout_id, exercise_id, exercise_name, sets, reps, weight, notes FROM workout_exercises WHERE workout_id = ? ORDER BY id"
+    )
+    .bind(id)
+    .fetch_all(pool)
+    .await
+    .map_err(ApiError::from)?;
+    let attachments = attachments::list(pool, attachments_root, "workout", id).await?;
+
+    Ok(WorkoutDetail { workout, exercises, attachments })
 }
 
 #[tauri::command]
@@ -62,6 +128,7 @@ pub async fn delete_workout(state: State<'_, DbState>, id: i64) -> Result<bool,
         return Err(ApiError::not_found("Workout not found"));
     }
 
+    aggregates::invalidate();
     Ok(true)
 }
 
@@ -72,18 +139,21 @@ pub async fn update_workout(
     data: WorkoutInput,
 ) -> Result<Workout, ApiError> {
     let pool = &state.0;
+    validate_notes_md(&data.notes_md)?;
     let rec = sqlx::query_as::<_, Workout>(
-        "UPDATE workouts SET 
+        "UPDATE workouts SET
             name = COALESCE(?, name),
             duration_minutes = COALESCE(?, duration_minutes),
             notes = COALESCE(?, notes),
+            notes_md = COALESCE(?, notes_md),
             logged_at = COALESCE(?, logged_at)
-         WHERE id = ? 
-         RETURNING id, user_id, name, duration_minutes, notes, logged_at"
+         WHERE id = ?
+         RETURNING id, user_id, name, duration_minutes, notes, logged_at, notes_md"
     )
     .bind(&data.name)
     .bind(data.duration_minutes)
     .bind(&data.notes)
+    .bind(&data.notes_md)
     .bind(&data.logged_at)
     .bind(id)
     .fetch_one(pool)
@@ -91,3 +161,79 @@ pub async fn update_workout(
     .map_err(ApiError::from)?;
     Ok(rec)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+    use sqlx::{Pool, Sqlite};
+    use std::str::FromStr;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    async fn setup_pool_with_migrations() -> Pool<Sqlite> {
+        let options = SqliteConnectOptions::from_str("sqlite::memory:")
+            .unwrap()
+            .foreign_keys(false);
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect_with(options)
+            .await
+            .expect("Failed to connect to in-memory DB");
+
+        crate::db::migrations::run_migrations(&pool)
+            .await
+            .expect("Failed to run migrations");
+
+        pool
+    }
+
+    #[tokio::test]
+    async fn detail_includes_markdown_notes_exercises_and_photos() {
+        let pool = setup_pool_with_migrations().await;
+        let workout = insert_workout(
+            &pool,
+            &WorkoutInput {
+                user_id: None,
+                name: Some("Squat day".to_string()),
+                duration_minutes: Some(60),
+                notes: Some("Felt strong".to_string()),
+                notes_md: Some("## Squat\n- brace before unracking\n- knees out".to_string()),
+                logged_at: None,
+            },
+        )
+        .await
+        .unwrap();
+        sqlx::query("INSERT INTO workout_exercises (workout_id, exercise_name, sets, reps, weight) VALUES (?, 'Back squat', 5, 5, 100)")
+            .bind(workout.id)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let nanos = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_nanos();
+        let dir = std::env::temp_dir().join(format!("life-os-workout-{}", nanos));
+        std::fs::create_dir_all(&dir).unwrap();
+        let photo = dir.join("depth.jpg");
+        std::fs::write(&photo, b"jpeg").unwrap();
+        let root = dir.join("attachments");
+        attachments::attach(&pool, &root, "workout", workout.id, &photo).await.unwrap();
+
+        let detail = workout_detail(&pool, &root, workout.id).await.unwrap();
+        assert!(detail.workout.notes_md.unwrap().starts_with("## Squat"));
+        assert_eq!(detail.exercises.len(), 1);
+        assert_eq!(detail.attachments.len(), 1);
+        assert_eq!(detail.attachments[0].attachment.kind, "image");
+
+        assert!(workout_detail(&pool, &root, workout.id + 1).await.is_err());
+        let too_long = WorkoutInput {
+            user_id: None,
+            name: None,
+            duration_minutes: None,
+            notes: None,
+            notes_md: Some("x".repeat(MAX_NOTES_MD_LENGTH + 1)),
+            logged_at: None,
+        };
+        assert!(insert_workout(&pool, &too_long).await.is_err());
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -1,19 +1,85 @@
-use sqlx::{Pool, Sqlite, sqlite::{SqliteConnectOptions, SqlitePoolOptions, SqliteJournalMode}};
+use serde::Serialize;
+use sqlx::{Pool, Sqlite, sqlite::{SqliteConnectOptions, SqlitePoolOptions, SqliteJournalMode, SqliteSynchronous}};
 use std::path::PathBuf;
 use std::str::FromStr;
+use std::time::Duration;
+
+use crate::error::ApiError;
+use crate::services::settings;
+
+/// Prepared statements kept per connection. Queries are cached by their SQL
+/// text, and the app has a few hundred distinct ones.
+const STATEMENT_CACHE_CAPACITY: usize = 512;
+
+/// Pool size and pragmas, from the `db_*` settings
+///
+/// Read after migrations; the pool is reopened when they differ from the
+/// defaults, so changes apply on the next start.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct PoolConfig {
+    pub max_connections: u32,
+    pub busy_timeout_ms: u64,
+    pub wal: bool,
+    /// off, normal, full or extra
+    pub synchronous: String,
+}
+
+impl Default for PoolConfig {
+    fn default() -> Self {
+        Self {
+            max_connections: 8,
+            busy_timeout_ms: 5000,
+            wal: true,
+            synchronous: "normal".to_string(),
+        }
+    }
+}
+
+impl PoolConfig {
+    pub async fn load(pool: &Pool<Sqlite>) -> Result<Self, ApiError> {
+        Ok(Self {
+            max_connections: settings::get_i64(pool, "db_max_connections").await? as u32,
+            busy_timeout_ms: settings::get_i64(pool, "db_busy_timeout_ms").await? as u64,
+            wal: settings::get_bool(pool, "db_wal_mode").await?,
+            synchronous: settings::get_string(pool, "db_synchronous")
+                .await?
+                .unwrap_or_else(|| "normal".to_string()),
+        })
+    }
+
+    fn synchronous_mode(&self) -> SqliteSynchronous {
+        match self.synchronous.as_str() {
+            "off" => SqliteSynchronous::Off,
+            "full" => SqliteSynchronous::Full,
+            "extra" => SqliteSynchronous::Extra,
+            _ => SqliteSynchronous::Normal,
+        }
+    }
+}
 
 pub async fn establish_pool(db_path: PathBuf) -> Result<Pool<Sqlite>, sqlx::Error> {
+    establish_pool_with(db_path, &PoolConfig::default()).await
+}
+
+pub async fn establish_pool_with(db_path: PathBuf, config: &PoolConfig) -> Result<Pool<Sqlite>, sqlx::Error> {
     // Ensure the parent directory exists
     if let Some(parent) = db_path.parent() {
         std::fs::create_dir_all(parent).ok();
     }
     
     let options = SqliteConnectOptions::from_str(&format!("sqlite:{}?mode=rwc", db_path.display()))?
-        .busy_timeout(std::time::Duration::from_secs(3))
-        .journal_mode(SqliteJournalMode::Wal);
+        .busy_timeout(Duration::from_millis(config.busy_timeout_ms))
+        .journal_mode(if config.wal { SqliteJournalMode::Wal } else { SqliteJournalMode::Delete })
+        .synchronous(config.synchronous_mode())
+        .statement_cache_capacity(STATEMENT_CACHE_CAPACITY);
 
+    // Connections are never retired, so their prepared statements survive
+    // between calls instead of being re-prepared after an idle period
     SqlitePoolOptions::new()
-        .max_connections(8)
+        .max_connections(config.max_connections)
+        .min_connections(1)
+        .idle_timeout(None)
+        .max_lifetime(None)
         .connect_with(options)
         .await
 }
@@ -57,4 +123,47 @@ mod tests {
         drop(pool);
         let _ = std::fs::remove_file(path);
     }
+
+    #[tokio::test]
+    async fn establish_pool_with_applies_the_configured_pragmas() {
+        let mut path = std::env::temp_dir();
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .expect("time should move forward")
+            .as_nanos();
+        path.push(format!("life-os-test-config-{}.sqlite", nanos));
+
+        let config = PoolConfig {
+            max_connections: 2,
+            busy_timeout_ms: 1500,
+            wal: false,
+            synchronous: "full".to_string(),
+        };
+        let pool = establish_pool_with(path.clone(), &config).await.unwrap();
+        crate::db::migrations::run_migrations(&pool).await.unwrap();
+
+        let journal_mode: String = sqlx::query_scalar("PRAGMA journal_mode")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(journal_mode.to_lowercase(), "delete");
+        let busy_timeout: i64 = sqlx::query_scalar("PRAGMA busy_timeout")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(busy_timeout, 1500);
+        // 2 = FULL
+        let synchronous: i64 = sqlx::query_scalar("PRAGMA synchronous")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(synchronous, 2);
+        assert_eq!(pool.options().get_max_connections(), 2);
+
+        // Unset settings load as the defaults
+        assert_eq!(PoolConfig::load(&pool).await.unwrap(), PoolConfig::default());
+
+        drop(pool);
+        let _ = std::fs::remove_file(path);
+    }
 }
@@ -0,0 +1,66 @@
+//! Dynamic Filters
+//!
+//! List commands take optional filters. Rather than a hand-written query per
+//! combination, `FilteredQuery` appends `WHERE`/`AND column = ?` for the
+//! filters that are present. Each combination always produces the same SQL
+//! text, so it is prepared once per connection and then served from sqlx's
+//! statement cache.
+
+use sqlx::{Encode, QueryBuilder, Sqlite, Type};
+
+/// A `SELECT` with optional equality filters
+pub struct FilteredQuery<'args> {
+    builder: QueryBuilder<'args, Sqlite>,
+    has_where: bool,
+}
+
+impl<'args> FilteredQuery<'args> {
+    /// `select` must not have a `WHERE` clause of its own
+    pub fn new(select: &str) -> Self {
+        Self {
+            builder: QueryBuilder::new(select),
+            has_where: false,
+        }
+    }
+
+    /// `column = value` when the value is present
+    pub fn eq<T>(mut self, column: &str, value: Option<T>) -> Self
+    where
+        T: 'args + Encode<'args, Sqlite> + Type<Sqlite>,
+    {
+        if let Some(value) = value {
+            self.builder.push(if self.has_where { " AND " } else { " WHERE " });
+            self.builder.push(column).push(" = ").push_bind(value);
+            self.has_where = true;
+        }
+        self
+    }
+
+    /// Append the ordering and hand back the builder to run
+    pub fn order_by(mut self, order: &str) -> QueryBuilder<'args, Sqlite> {
+        self.builder.push(" ORDER BY ").push(order);
+        self.builder
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_present_filters_are_added() {
+        let none = FilteredQuery::new("SELECT * FROM exams")
+            .eq("course_id", None::<i64>)
+            .order_by("exam_date");
+        assert_eq!(none.sql(), "SELECT * FROM exams ORDER BY exam_date");
+
+        let both = FilteredQuery::new("SELECT * FROM sessions")
+            .eq("reference_id", Some(3))
+            .eq("reference_type", Some("course"))
+            .order_by("started_at DESC");
+        assert_eq!(
+            both.sql(),
+            "SELECT * FROM sessions WHERE reference_id = ? AND reference_type = ? ORDER BY started_at DESC"
+        );
+    }
+}
@@ -0,0 +1,7 @@
+-- Pre-exam wellness protocol
+-- Tracks proximity to the next exam in legacy snapshots and seeds the protocol arm
+
+ALTER TABLE agent_feature_snapshots ADD COLUMN days_to_next_exam INTEGER; -- NULL when no upcoming exam
+
+INSERT OR IGNORE INTO agent_bandit_arms (arm_name, category) VALUES
+  ('pre_exam_protocol', 'wellness');
@@ -0,0 +1,8 @@
+-- Deadline features for the rich context vector (50 -> 52 dimensions)
+-- Stored 50-dim context blobs and bandit parameters are upgraded on read:
+-- contexts are padded with defaults, bandit weights are embedded with prior precision.
+
+ALTER TABLE agent_rich_context ADD COLUMN days_to_next_exam REAL;   -- Days until next exam, normalized
+ALTER TABLE agent_rich_context ADD COLUMN deadline_pressure REAL;   -- Effort-weighted inverse days-to-due
+
+UPDATE agent_state SET value_json = '52', updated_at = datetime('now') WHERE key = 'feature_dim';
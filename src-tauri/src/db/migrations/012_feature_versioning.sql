@@ -0,0 +1,8 @@
+-- Feature-vector versioning
+-- Rich context blobs now carry a leading version byte; bandit parameters record
+-- the layout they were learned under. NULL means "written before versioning" and
+-- the layout is inferred from the vector length when upgrading.
+
+ALTER TABLE agent_linear_bandit ADD COLUMN feature_version INTEGER;
+
+INSERT OR IGNORE INTO agent_state (key, value_json) VALUES ('feature_version', '2');
@@ -0,0 +1,26 @@
+-- Reward weight change history
+-- Every change of the multi-scale reward weights is recorded so shifts in agent
+-- behavior can be correlated with tuning.
+
+CREATE TABLE IF NOT EXISTS agent_reward_weight_history (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    immediate REAL NOT NULL,
+    daily REAL NOT NULL,
+    weekly REAL NOT NULL,
+    monthly REAL NOT NULL,
+    preset TEXT,                                -- Preset name when applied from / matching a preset
+    changed_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_reward_weight_history_changed
+    ON agent_reward_weight_history(changed_at);
+
+-- Baseline entry with the weights in effect when history tracking started
+INSERT INTO agent_reward_weight_history (immediate, daily, weekly, monthly)
+SELECT
+    json_extract(value_json, '$.immediate'),
+    json_extract(value_json, '$.daily'),
+    json_extract(value_json, '$.weekly'),
+    json_extract(value_json, '$.monthly')
+FROM agent_state
+WHERE key = 'reward_weights';
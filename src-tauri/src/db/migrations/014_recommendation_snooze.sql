@@ -0,0 +1,15 @@
+-- Recommendation snooze ("not now") feedback
+-- Snoozing suppresses an action for a while without a negative reward.
+
+ALTER TABLE agent_recommendations ADD COLUMN feedback_type TEXT;   -- 'accepted', 'rejected', 'snoozed'
+
+CREATE TABLE IF NOT EXISTS agent_action_snoozes (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    action_name TEXT NOT NULL,
+    recommendation_id INTEGER,
+    snoozed_until TEXT NOT NULL,
+    created_at TEXT DEFAULT (datetime('now')),
+    FOREIGN KEY (recommendation_id) REFERENCES agent_recommendations(id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_action_snoozes_until ON agent_action_snoozes(snoozed_until);
@@ -0,0 +1,10 @@
+-- Implicit feedback from subsequent behavior
+-- Context snapshots keep the full (versioned) feature vector so rewards inferred
+-- later can be attributed to the context the recommendation was made in.
+
+ALTER TABLE agent_rich_context ADD COLUMN context_features BLOB;
+
+ALTER TABLE agent_recommendations ADD COLUMN implicit_checked_at TEXT;   -- When implicit feedback was evaluated
+
+CREATE INDEX IF NOT EXISTS idx_recommendations_implicit
+    ON agent_recommendations(feedback_type, implicit_checked_at);
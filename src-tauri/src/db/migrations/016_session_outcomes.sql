@@ -0,0 +1,6 @@
+-- Session outcome linking
+-- Planned length and self-rated focus let the backend score a session when it
+-- ends and report it to the agent.
+
+ALTER TABLE sessions ADD COLUMN planned_minutes INTEGER;
+ALTER TABLE sessions ADD COLUMN focus_rating INTEGER CHECK(focus_rating IS NULL OR (focus_rating >= 1 AND focus_rating <= 5));
@@ -0,0 +1,16 @@
+-- Agent maintenance scheduler
+-- Daily maintenance runs in the background at a configurable quiet hour; every
+-- run (scheduled or manual) is logged with per-step results.
+
+CREATE TABLE IF NOT EXISTS agent_maintenance_log (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    trigger TEXT NOT NULL,                      -- 'scheduled' or 'manual'
+    started_at TEXT NOT NULL DEFAULT (datetime('now')),
+    finished_at TEXT,
+    status TEXT NOT NULL,                       -- 'running', 'ok', 'partial', 'failed'
+    steps_json TEXT                             -- [{step, ok, detail}]
+);
+
+CREATE INDEX IF NOT EXISTS idx_maintenance_log_started ON agent_maintenance_log(started_at);
+
+INSERT OR IGNORE INTO agent_state (key, value_json) VALUES ('maintenance_hour', '3');
@@ -0,0 +1,15 @@
+-- Insight deduplication and frequency caps
+-- Every shown insight (bandit arm, mined pattern or rule fallback) is logged by
+-- a stable key so the same nudge isn't repeated across sessions.
+
+CREATE TABLE IF NOT EXISTS agent_shown_insights (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    insight_key TEXT NOT NULL,                  -- 'arm:<name>', 'pattern:<name>', 'rule:<name>'
+    category TEXT,
+    shown_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_shown_insights_key_time ON agent_shown_insights(insight_key, shown_at);
+
+-- Max times an arm's insight is shown per rolling day (NULL = default of 1)
+ALTER TABLE agent_bandit_arms ADD COLUMN daily_cap INTEGER;
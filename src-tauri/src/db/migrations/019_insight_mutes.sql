@@ -0,0 +1,12 @@
+-- Dismissible insights and per-category mutes
+-- Muted categories apply to both legacy insights and intelligence recommendations.
+
+ALTER TABLE user_settings ADD COLUMN muted_categories TEXT DEFAULT '[]';   -- JSON array of category names
+
+CREATE TABLE IF NOT EXISTS agent_insight_dismissals (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    insight_key TEXT NOT NULL,                  -- Same keys as agent_shown_insights
+    dismissed_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_insight_dismissals_key ON agent_insight_dismissals(insight_key, dismissed_at);
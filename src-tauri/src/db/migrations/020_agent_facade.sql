@@ -0,0 +1,60 @@
+-- Merge the legacy insight arms into the intelligence agent
+-- Insights are now selected from agent_linear_bandit via AgentFacade. The
+-- legacy tables (agent_bandit_arms, agent_insights, agent_feature_snapshots)
+-- are deprecated: they are kept for history and the fallback pipeline but no
+-- longer drive selection, and will be dropped in a later migration.
+
+-- Legacy-only arms become v2 actions
+INSERT OR IGNORE INTO agent_linear_bandit (action_name, category, description) VALUES
+    ('celebrate_streak', 'motivation', 'Celebrate an active streak'),
+    ('productivity_tip', 'general', 'Try a productivity technique'),
+    ('pre_exam_protocol', 'wellness', 'Follow the pre-exam sleep and recovery protocol');
+
+-- Legacy arm -> v2 action (mirrors LEGACY_ARM_ACTIONS in agent/facade.rs)
+CREATE TABLE IF NOT EXISTS agent_legacy_arm_map (
+    arm_name TEXT PRIMARY KEY,
+    action_name TEXT NOT NULL
+);
+
+INSERT OR IGNORE INTO agent_legacy_arm_map (arm_name, action_name) VALUES
+    ('remind_checkin', 'do_checkin'),
+    ('suggest_pomodoro', 'start_pomodoro'),
+    ('warn_overdue', 'tackle_assignment'),
+    ('recommend_break', 'take_break'),
+    ('suggest_workout', 'do_workout'),
+    ('practice_reminder', 'practice_skill'),
+    ('weekly_reflection', 'weekly_review'),
+    ('celebrate_streak', 'celebrate_streak'),
+    ('productivity_tip', 'productivity_tip'),
+    ('pre_exam_protocol', 'pre_exam_protocol');
+
+-- Legacy statistics are carried over separately: they were learned without
+-- context, so they must not inflate the linear model's sample counts
+ALTER TABLE agent_linear_bandit ADD COLUMN legacy_pulls INTEGER DEFAULT 0;
+ALTER TABLE agent_linear_bandit ADD COLUMN legacy_reward REAL DEFAULT 0.0;
+
+UPDATE agent_linear_bandit
+SET legacy_pulls = COALESCE((
+        SELECT SUM(a.total_pulls) FROM agent_bandit_arms a
+        JOIN agent_legacy_arm_map m ON m.arm_name = a.arm_name
+        WHERE m.action_name = agent_linear_bandit.action_name
+    ), 0),
+    legacy_reward = COALESCE((
+        SELECT SUM(a.total_reward) FROM agent_bandit_arms a
+        JOIN agent_legacy_arm_map m ON m.arm_name = a.arm_name
+        WHERE m.action_name = agent_linear_bandit.action_name
+    ), 0.0);
+
+-- Frequency caps move with the arm
+ALTER TABLE agent_linear_bandit ADD COLUMN daily_cap INTEGER;
+
+UPDATE agent_linear_bandit
+SET daily_cap = (
+    SELECT a.daily_cap FROM agent_bandit_arms a
+    JOIN agent_legacy_arm_map m ON m.arm_name = a.arm_name
+    WHERE m.action_name = agent_linear_bandit.action_name
+);
+
+INSERT OR IGNORE INTO agent_state (key, value_json) VALUES
+    ('legacy_arms_migrated', 'true'),
+    ('legacy_tables_deprecated', '["agent_bandit_arms","agent_insights","agent_feature_snapshots"]');
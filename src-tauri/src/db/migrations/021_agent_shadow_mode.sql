@@ -0,0 +1,10 @@
+-- Agent shadow mode
+-- When enabled, recommendations are still selected and logged (is_shadow = 1)
+-- but never shown. Their outcomes are inferred from later behavior without
+-- updating the bandit, so the log can be reviewed and used as training data.
+
+ALTER TABLE user_settings ADD COLUMN agent_shadow_mode INTEGER NOT NULL DEFAULT 0;
+
+ALTER TABLE agent_recommendations ADD COLUMN is_shadow INTEGER NOT NULL DEFAULT 0;
+
+CREATE INDEX IF NOT EXISTS idx_recommendations_shadow ON agent_recommendations(is_shadow, timestamp);
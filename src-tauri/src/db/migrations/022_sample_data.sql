@@ -0,0 +1,8 @@
+-- Sample data flag
+-- Rows inserted by load_sample_data (onboarding screenshots, UI testing) are
+-- flagged so remove_sample_data can delete exactly those and nothing else.
+
+ALTER TABLE courses ADD COLUMN is_sample INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE assignments ADD COLUMN is_sample INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE workouts ADD COLUMN is_sample INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE week_plan_blocks ADD COLUMN is_sample INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,34 @@
+-- Onboarding wizard
+-- Tracks completed wizard steps, the settings it applies and the first term.
+
+CREATE TABLE IF NOT EXISTS onboarding_steps (
+    step TEXT PRIMARY KEY,                      -- see ONBOARDING_STEPS in commands/onboarding.rs
+    completed_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+ALTER TABLE user_settings ADD COLUMN week_start_day TEXT NOT NULL DEFAULT 'monday'
+    CHECK (week_start_day IN ('monday', 'sunday'));
+ALTER TABLE user_settings ADD COLUMN timezone TEXT;                -- IANA name, NULL = system
+ALTER TABLE user_settings ADD COLUMN onboarding_completed_at TEXT;
+
+-- Academic terms (a course optionally belongs to one)
+CREATE TABLE IF NOT EXISTS terms (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id INTEGER NOT NULL DEFAULT 1,
+    name TEXT NOT NULL,
+    start_date TEXT,
+    end_date TEXT,
+    is_current INTEGER NOT NULL DEFAULT 1,
+    created_at TEXT DEFAULT (datetime('now')),
+    CHECK (start_date IS NULL OR end_date IS NULL OR start_date <= end_date)
+);
+
+ALTER TABLE courses ADD COLUMN term_id INTEGER REFERENCES terms(id) ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS idx_courses_term ON courses(term_id);
+
+-- Existing installs already have data; don't send them through the wizard
+UPDATE user_settings
+SET onboarding_completed_at = datetime('now')
+WHERE onboarding_completed_at IS NULL
+  AND (EXISTS (SELECT 1 FROM courses) OR EXISTS (SELECT 1 FROM sessions) OR EXISTS (SELECT 1 FROM workouts));
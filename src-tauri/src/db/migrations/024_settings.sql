@@ -0,0 +1,43 @@
+-- Typed settings
+-- Key-value store for user settings; keys, types and defaults are declared in
+-- services/settings.rs. Values are JSON; an absent key means the default.
+
+CREATE TABLE IF NOT EXISTS settings (
+    key TEXT PRIMARY KEY,
+    value_json TEXT NOT NULL,
+    updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+-- Copy the existing user_settings columns. The columns are kept for
+-- downgrades but are no longer read or written.
+INSERT OR IGNORE INTO settings (key, value_json)
+SELECT 'weekly_workout_target', CAST(weekly_workout_target AS TEXT)
+FROM user_settings WHERE id = 1 AND weekly_workout_target IS NOT NULL;
+
+INSERT OR IGNORE INTO settings (key, value_json)
+SELECT 'weekly_active_skills_target', CAST(weekly_active_skills_target AS TEXT)
+FROM user_settings WHERE id = 1 AND weekly_active_skills_target IS NOT NULL;
+
+INSERT OR IGNORE INTO settings (key, value_json)
+SELECT 'week_start_day', json_quote(week_start_day)
+FROM user_settings WHERE id = 1;
+
+INSERT OR IGNORE INTO settings (key, value_json)
+SELECT 'timezone', json_quote(timezone)
+FROM user_settings WHERE id = 1 AND timezone IS NOT NULL;
+
+INSERT OR IGNORE INTO settings (key, value_json)
+SELECT 'muted_categories', muted_categories
+FROM user_settings WHERE id = 1 AND muted_categories IS NOT NULL AND json_valid(muted_categories);
+
+INSERT OR IGNORE INTO settings (key, value_json)
+SELECT 'agent_shadow_mode', CASE WHEN agent_shadow_mode THEN 'true' ELSE 'false' END
+FROM user_settings WHERE id = 1;
+
+INSERT OR IGNORE INTO settings (key, value_json)
+SELECT 'google_client_id', json_quote(google_client_id)
+FROM user_settings WHERE id = 1 AND google_client_id IS NOT NULL;
+
+INSERT OR IGNORE INTO settings (key, value_json)
+SELECT 'onboarding_completed_at', json_quote(onboarding_completed_at)
+FROM user_settings WHERE id = 1 AND onboarding_completed_at IS NOT NULL;
@@ -0,0 +1,16 @@
+-- Focus mode
+-- Foreground-app samples taken during study sessions. Window titles are never
+-- stored; `site` only records which configured distraction site matched.
+
+CREATE TABLE IF NOT EXISTS focus_samples (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    session_id INTEGER NOT NULL REFERENCES sessions(id) ON DELETE CASCADE,
+    sampled_at TEXT NOT NULL DEFAULT (datetime('now')),
+    app_name TEXT NOT NULL,
+    site TEXT,
+    is_distraction INTEGER NOT NULL DEFAULT 0,
+    seconds INTEGER NOT NULL CHECK (seconds > 0)
+);
+
+CREATE INDEX IF NOT EXISTS idx_focus_samples_session ON focus_samples(session_id);
+CREATE INDEX IF NOT EXISTS idx_focus_samples_time ON focus_samples(sampled_at);
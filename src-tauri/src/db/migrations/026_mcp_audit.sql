@@ -0,0 +1,16 @@
+-- MCP audit log
+-- Every tool call from an external assistant, including ones refused for
+-- missing scopes, so the user can review what was done on their behalf.
+
+CREATE TABLE IF NOT EXISTS mcp_audit_log (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    tool TEXT NOT NULL,
+    scope TEXT,
+    arguments_json TEXT NOT NULL DEFAULT '{}',
+    allowed INTEGER NOT NULL,
+    is_error INTEGER NOT NULL DEFAULT 0,
+    result_summary TEXT,
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_mcp_audit_created ON mcp_audit_log(created_at);
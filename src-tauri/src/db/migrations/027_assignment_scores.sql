@@ -0,0 +1,5 @@
+-- Assignment scores
+-- Percent score once an assignment is graded, used with exam grades by the
+-- effort-to-grade efficiency analytics.
+
+ALTER TABLE assignments ADD COLUMN score REAL CHECK (score IS NULL OR (score >= 0 AND score <= 100));
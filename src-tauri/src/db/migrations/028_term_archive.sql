@@ -0,0 +1,30 @@
+-- Semester rollover
+-- Archiving a term deactivates its courses, archives their meetings and
+-- freezes per-course analytics so history survives the next term.
+
+ALTER TABLE terms ADD COLUMN archived_at TEXT;
+ALTER TABLE course_meetings ADD COLUMN archived_at TEXT;
+-- Skills and workout templates not carried into the next term
+ALTER TABLE skills ADD COLUMN archived_at TEXT;
+ALTER TABLE workout_templates ADD COLUMN archived_at TEXT;
+
+CREATE TABLE IF NOT EXISTS term_snapshots (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    term_id INTEGER NOT NULL REFERENCES terms(id) ON DELETE CASCADE,
+    course_id INTEGER REFERENCES courses(id) ON DELETE SET NULL,
+    course_name TEXT NOT NULL,
+    course_code TEXT,
+    credit_hours INTEGER,
+    study_minutes INTEGER NOT NULL DEFAULT 0,
+    study_sessions INTEGER NOT NULL DEFAULT 0,
+    assignments_total INTEGER NOT NULL DEFAULT 0,
+    assignments_completed INTEGER NOT NULL DEFAULT 0,
+    average_assignment_score REAL,
+    exams_graded INTEGER NOT NULL DEFAULT 0,
+    average_exam_grade REAL,
+    current_grade REAL,
+    target_grade REAL,
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_term_snapshots_term ON term_snapshots(term_id);
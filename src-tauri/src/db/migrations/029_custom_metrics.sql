@@ -0,0 +1,17 @@
+-- Saved metric queries for configurable dashboards
+-- A metric is an aggregation over one entity within a rolling window; the
+-- allowed entities, fields and operators live in commands/custom_metrics.rs.
+
+CREATE TABLE IF NOT EXISTS custom_metrics (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id INTEGER NOT NULL DEFAULT 1,
+    name TEXT NOT NULL,
+    entity TEXT NOT NULL,                       -- sessions, practice, workouts, checkins
+    filters_json TEXT NOT NULL DEFAULT '[]',    -- [{"field","op","value"}]
+    aggregation TEXT NOT NULL,                  -- count, sum, avg, min, max
+    field TEXT,                                 -- NULL for count
+    window_days INTEGER NOT NULL DEFAULT 30 CHECK (window_days BETWEEN 1 AND 365),
+    bucket TEXT NOT NULL DEFAULT 'none',        -- none, day, week
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
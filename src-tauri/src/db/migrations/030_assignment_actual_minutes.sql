@@ -0,0 +1,4 @@
+-- Time actually spent on an assignment, for calibrating estimated_minutes.
+-- NULL falls back to study sessions logged against the assignment.
+
+ALTER TABLE assignments ADD COLUMN actual_minutes INTEGER CHECK (actual_minutes IS NULL OR actual_minutes >= 0);
@@ -0,0 +1,4 @@
+-- Focus profile (see the focus_profiles setting) a session was run with.
+-- Not a foreign key: profiles live in settings and may be deleted.
+
+ALTER TABLE sessions ADD COLUMN focus_profile_id INTEGER;
@@ -0,0 +1,18 @@
+-- Media attached to practice logs and workouts (photos, recordings).
+-- Files live under <app data>/attachments, named by content hash so the same
+-- file attached twice is stored once. owner is polymorphic, so rows whose
+-- owner is gone are cleaned up by gc_attachments rather than a cascade.
+
+CREATE TABLE IF NOT EXISTS attachments (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    owner_type TEXT NOT NULL CHECK (owner_type IN ('practice_log', 'workout')),
+    owner_id INTEGER NOT NULL,
+    kind TEXT NOT NULL CHECK (kind IN ('image', 'audio', 'video', 'other')),
+    file_name TEXT NOT NULL,   -- original name, for display
+    stored_name TEXT NOT NULL, -- <sha256>.<ext> under the attachments dir
+    size_bytes INTEGER NOT NULL,
+    created_at TEXT DEFAULT (datetime('now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_attachments_owner ON attachments(owner_type, owner_id);
+CREATE INDEX IF NOT EXISTS idx_attachments_stored_name ON attachments(stored_name);
@@ -0,0 +1,4 @@
+-- Longer markdown notes on a workout (technique cues, how it felt, PR
+-- context); `notes` stays the one-line summary shown in lists.
+
+ALTER TABLE workouts ADD COLUMN notes_md TEXT;
@@ -0,0 +1,28 @@
+-- User-definable calendar categories: one color and busy/free default per
+-- category name. Names match calendar_events.category, week_plan_blocks.block_type
+-- and the synthetic categories of get_calendar_items (class, deadline, exam).
+-- Course colors still win for course-linked items; these fill the gaps.
+
+CREATE TABLE IF NOT EXISTS categories (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL UNIQUE,
+    color TEXT NOT NULL,            -- #rrggbb
+    is_busy INTEGER NOT NULL DEFAULT 1,
+    is_builtin INTEGER NOT NULL DEFAULT 0,
+    created_at TEXT DEFAULT (datetime('now'))
+);
+
+INSERT OR IGNORE INTO categories (name, color, is_busy, is_builtin) VALUES
+    ('busy', '#64748b', 1, 1),
+    ('general', '#6366f1', 1, 1),
+    ('work', '#0ea5e9', 1, 1),
+    ('personal', '#22c55e', 1, 1),
+    ('health', '#ef4444', 1, 1),
+    ('class', '#3b82f6', 1, 1),
+    ('deadline', '#f97316', 0, 1),
+    ('exam', '#dc2626', 1, 1),
+    ('study', '#8b5cf6', 1, 1),
+    ('assignment', '#a855f7', 1, 1),
+    ('exam_prep', '#e11d48', 1, 1),
+    ('break', '#94a3b8', 0, 1),
+    ('weekly_task', '#14b8a6', 1, 1);
@@ -0,0 +1,7 @@
+-- Exam outcomes
+-- The raw score behind an exam's percent grade, and a post-mortem note on
+-- what went well or badly, read back in the semester review.
+
+ALTER TABLE exams ADD COLUMN score REAL;
+ALTER TABLE exams ADD COLUMN max_score REAL;
+ALTER TABLE exams ADD COLUMN post_mortem TEXT;
@@ -0,0 +1,24 @@
+-- Lecture note-taking
+-- A session started from a course meeting remembers which meeting and the
+-- date it was held. Notes taken in class go in lecture_notes, linked to that
+-- occurrence so they can be found again by date or topic.
+
+ALTER TABLE sessions ADD COLUMN title TEXT;
+ALTER TABLE sessions ADD COLUMN course_meeting_id INTEGER REFERENCES course_meetings(id) ON DELETE SET NULL;
+ALTER TABLE sessions ADD COLUMN meeting_date TEXT; -- YYYY-MM-DD of the occurrence
+
+CREATE TABLE IF NOT EXISTS lecture_notes (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    course_id INTEGER NOT NULL REFERENCES courses(id) ON DELETE CASCADE,
+    course_meeting_id INTEGER REFERENCES course_meetings(id) ON DELETE SET NULL,
+    meeting_date TEXT NOT NULL, -- YYYY-MM-DD
+    session_id INTEGER REFERENCES sessions(id) ON DELETE SET NULL,
+    topic TEXT,
+    content TEXT NOT NULL,
+    created_at TEXT DEFAULT (datetime('now')),
+    updated_at TEXT DEFAULT (datetime('now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_lecture_notes_course_date ON lecture_notes(course_id, meeting_date);
+CREATE INDEX IF NOT EXISTS idx_lecture_notes_meeting_date ON lecture_notes(course_meeting_id, meeting_date);
+CREATE INDEX IF NOT EXISTS idx_sessions_meeting ON sessions(course_meeting_id, meeting_date);
@@ -0,0 +1,27 @@
+-- Study techniques
+-- A library of study methods that can be tagged onto sessions, so focus and
+-- outcomes can be compared by technique for each course.
+
+CREATE TABLE IF NOT EXISTS techniques (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL UNIQUE COLLATE NOCASE,
+    description TEXT,
+    is_builtin INTEGER NOT NULL DEFAULT 0,
+    created_at TEXT DEFAULT (datetime('now'))
+);
+
+CREATE TABLE IF NOT EXISTS session_techniques (
+    session_id INTEGER NOT NULL REFERENCES sessions(id) ON DELETE CASCADE,
+    technique_id INTEGER NOT NULL REFERENCES techniques(id) ON DELETE CASCADE,
+    PRIMARY KEY (session_id, technique_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_session_techniques_technique ON session_techniques(technique_id);
+
+INSERT OR IGNORE INTO techniques (name, description, is_builtin) VALUES
+    ('Active recall', 'Close the notes and retrieve from memory', 1),
+    ('Feynman technique', 'Explain the topic in plain words to find the gaps', 1),
+    ('Past papers', 'Timed practice on previous exams', 1),
+    ('Spaced repetition', 'Review flashcards on a spacing schedule', 1),
+    ('Practice problems', 'Work through exercises and problem sets', 1),
+    ('Rereading', 'Reread notes or the textbook', 1);
@@ -0,0 +1,8 @@
+-- Weekly per-course digests
+-- One row per day digests were shown, so each digest day notifies once.
+
+CREATE TABLE IF NOT EXISTS course_digest_deliveries (
+    delivered_on TEXT PRIMARY KEY, -- local YYYY-MM-DD
+    courses INTEGER NOT NULL DEFAULT 0,
+    delivered_at TEXT DEFAULT (datetime('now'))
+);
@@ -0,0 +1,11 @@
+-- Local usage telemetry (opt-in)
+-- Daily counts only: which commands ran and where errors were raised.
+-- Nothing here leaves the device unless exported.
+
+CREATE TABLE IF NOT EXISTS telemetry_counts (
+    day TEXT NOT NULL,      -- local YYYY-MM-DD
+    kind TEXT NOT NULL CHECK (kind IN ('command', 'error')),
+    name TEXT NOT NULL,     -- command name, or "module:code" for errors
+    count INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (day, kind, name)
+);
@@ -0,0 +1,21 @@
+-- Write-ahead journal for Google sync writes that touch both Google and the
+-- local link table. An entry is written before the remote call, gets the
+-- Google event id once the call returns, and is completed in the same
+-- transaction as the local write. Entries left incomplete by a crash are
+-- reconciled at the start of the next sync.
+
+CREATE TABLE IF NOT EXISTS google_sync_journal (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    operation TEXT NOT NULL CHECK (operation IN ('create_event', 'recreate_event')),
+    local_type TEXT NOT NULL,
+    local_id INTEGER NOT NULL,
+    google_calendar_id TEXT NOT NULL,
+    google_event_id TEXT,
+    started_at TEXT NOT NULL DEFAULT (datetime('now')),
+    completed_at TEXT,
+    -- 'applied', or how recovery resolved it: 'relinked', 'not_created'
+    resolution TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_google_sync_journal_pending
+    ON google_sync_journal(completed_at);
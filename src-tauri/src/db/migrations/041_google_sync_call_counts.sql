@@ -0,0 +1,4 @@
+-- Calendar API calls made by the last Google sync
+ALTER TABLE google_calendar_prefs ADD COLUMN last_sync_reads INTEGER;
+ALTER TABLE google_calendar_prefs ADD COLUMN last_sync_writes INTEGER;
+ALTER TABLE google_calendar_prefs ADD COLUMN last_sync_retries INTEGER;
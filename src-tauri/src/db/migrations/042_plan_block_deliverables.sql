@@ -0,0 +1,8 @@
+-- Link plan blocks to the assignment or exam they were scheduled for, so
+-- finishing it releases the remaining blocks and planned hours can be
+-- attributed per deliverable
+ALTER TABLE week_plan_blocks ADD COLUMN assignment_id INTEGER REFERENCES assignments(id) ON DELETE SET NULL;
+ALTER TABLE week_plan_blocks ADD COLUMN exam_id INTEGER REFERENCES exams(id) ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS idx_week_plan_blocks_assignment ON week_plan_blocks(assignment_id);
+CREATE INDEX IF NOT EXISTS idx_week_plan_blocks_exam ON week_plan_blocks(exam_id);
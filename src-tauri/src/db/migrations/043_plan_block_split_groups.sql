@@ -0,0 +1,5 @@
+-- Pomodoro sub-blocks split from one study block share a group key, so
+-- calendar export can merge them back into a single event
+ALTER TABLE week_plan_blocks ADD COLUMN split_group TEXT;
+
+CREATE INDEX IF NOT EXISTS idx_week_plan_blocks_split_group ON week_plan_blocks(split_group);
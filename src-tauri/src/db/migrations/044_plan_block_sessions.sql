@@ -0,0 +1,11 @@
+-- Plan block check-in
+-- A session started from a plan block links back to it. The block tracks
+-- whether work on it is in progress or done (separate from status, which is
+-- how firmly it is planned) and how many minutes the session actually ran.
+
+ALTER TABLE sessions ADD COLUMN week_plan_block_id INTEGER REFERENCES week_plan_blocks(id) ON DELETE SET NULL;
+
+ALTER TABLE week_plan_blocks ADD COLUMN progress TEXT CHECK (progress IS NULL OR progress IN ('in_progress', 'done'));
+ALTER TABLE week_plan_blocks ADD COLUMN actual_minutes INTEGER;
+
+CREATE INDEX IF NOT EXISTS idx_sessions_week_plan_block ON sessions(week_plan_block_id);
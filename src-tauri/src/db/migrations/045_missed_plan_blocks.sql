@@ -0,0 +1,7 @@
+-- Missed plan blocks
+-- Nightly maintenance marks accepted blocks that ended without a session as
+-- missed, and may place a replacement later in the same week.
+ALTER TABLE week_plan_blocks ADD COLUMN missed_at TEXT;
+ALTER TABLE week_plan_blocks ADD COLUMN rescheduled_to_id INTEGER REFERENCES week_plan_blocks(id) ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS idx_week_plan_blocks_missed ON week_plan_blocks(missed_at);
@@ -0,0 +1,9 @@
+-- Skill practice in the week planner
+-- Practice blocks link the skill they are for; the category gives them a
+-- color alongside study blocks.
+ALTER TABLE week_plan_blocks ADD COLUMN skill_id INTEGER REFERENCES skills(id) ON DELETE SET NULL;
+
+CREATE INDEX IF NOT EXISTS idx_week_plan_blocks_skill ON week_plan_blocks(skill_id);
+
+INSERT OR IGNORE INTO categories (name, color, is_busy, is_builtin) VALUES
+    ('practice', '#f59e0b', 1, 1);
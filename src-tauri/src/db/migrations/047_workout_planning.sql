@@ -0,0 +1,14 @@
+-- Workout scheduling in the week planner
+-- Templates carry their program schedule (weekdays, 0 = Sunday, as a JSON
+-- array; empty or null means any day), what they train and how hard, so the
+-- planner can place them with recovery in mind. Workout blocks link the
+-- template they suggest.
+ALTER TABLE workout_templates ADD COLUMN schedule_days TEXT;
+ALTER TABLE workout_templates ADD COLUMN muscle_group TEXT;
+ALTER TABLE workout_templates ADD COLUMN is_heavy INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE workout_templates ADD COLUMN planned_minutes INTEGER;
+
+ALTER TABLE week_plan_blocks ADD COLUMN workout_template_id INTEGER REFERENCES workout_templates(id) ON DELETE SET NULL;
+
+INSERT OR IGNORE INTO categories (name, color, is_busy, is_builtin) VALUES
+    ('workout', '#10b981', 1, 1);
@@ -0,0 +1,6 @@
+-- Per-course scheduling preferences for the week planner
+-- min_block_minutes: shortest study block worth planning for the course
+-- preferred_days: weekdays to study it on (JSON array, 0 = Sunday); null
+-- means any day
+ALTER TABLE courses ADD COLUMN min_block_minutes INTEGER;
+ALTER TABLE courses ADD COLUMN preferred_days TEXT;
@@ -0,0 +1,16 @@
+-- Submission checklists ("definition of done") for assignments
+-- Required items gate marking the assignment done; toggle_assignment refuses
+-- while any remain unless forced.
+CREATE TABLE IF NOT EXISTS assignment_checklist_items (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    assignment_id INTEGER NOT NULL,
+    label TEXT NOT NULL,
+    is_required INTEGER NOT NULL DEFAULT 1,
+    is_done INTEGER NOT NULL DEFAULT 0,
+    done_at TEXT,
+    position INTEGER NOT NULL DEFAULT 0,
+    created_at TEXT DEFAULT CURRENT_TIMESTAMP,
+    FOREIGN KEY (assignment_id) REFERENCES assignments(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_assignment_checklist_assignment ON assignment_checklist_items(assignment_id);
@@ -0,0 +1,17 @@
+-- Data retention
+-- Maintenance compresses session rows past `retention_sessions_months` into
+-- one row per local day, type and reference, then deletes them.
+CREATE TABLE IF NOT EXISTS session_daily_aggregates (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    date TEXT NOT NULL,                         -- YYYY-MM-DD (local)
+    session_type TEXT NOT NULL,
+    reference_type TEXT NOT NULL DEFAULT '',    -- '' when the sessions had none
+    reference_id INTEGER NOT NULL DEFAULT 0,    -- 0 when the sessions had none
+    session_count INTEGER NOT NULL,
+    total_minutes INTEGER NOT NULL DEFAULT 0,
+    rated_sessions INTEGER NOT NULL DEFAULT 0,
+    focus_rating_sum INTEGER NOT NULL DEFAULT 0,
+    UNIQUE (date, session_type, reference_type, reference_id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_session_daily_aggregates_date ON session_daily_aggregates(date);
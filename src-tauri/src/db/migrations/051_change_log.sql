@@ -0,0 +1,18 @@
+-- Audit trail of changes made by sync and imports rather than the user
+-- Each row holds the entity before and after the change as JSON, so a change
+-- that clobbered local data can be reviewed and reverted.
+CREATE TABLE IF NOT EXISTS change_log (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    source TEXT NOT NULL,                       -- 'google', 'deadline_import'
+    entity_type TEXT NOT NULL,                  -- table of the changed row
+    entity_id INTEGER NOT NULL,
+    action TEXT NOT NULL,                       -- 'create', 'update', 'delete'
+    summary TEXT,
+    before_json TEXT,                           -- NULL for creates
+    after_json TEXT,                            -- NULL for deletes
+    changed_at TEXT NOT NULL DEFAULT (datetime('now')),
+    reverted_at TEXT
+);
+
+CREATE INDEX IF NOT EXISTS idx_change_log_entity ON change_log(entity_type, entity_id);
+CREATE INDEX IF NOT EXISTS idx_change_log_changed ON change_log(changed_at);
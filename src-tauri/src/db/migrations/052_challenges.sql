@@ -0,0 +1,17 @@
+-- Self-set, time-boxed challenges, e.g. "30 workouts in 60 days"
+-- Progress is computed from the activity tables; only the milestones already
+-- announced and the completion time are stored.
+CREATE TABLE IF NOT EXISTS challenges (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    title TEXT NOT NULL,
+    metric TEXT NOT NULL,                       -- 'workouts', 'study_hours', 'practice_hours', 'checkin_days', 'flashcard_reviews'
+    target REAL NOT NULL,
+    per_day INTEGER NOT NULL DEFAULT 0,         -- 1 = the target must be met every day
+    start_date TEXT NOT NULL,                   -- YYYY-MM-DD (local)
+    end_date TEXT NOT NULL,                     -- YYYY-MM-DD (local), inclusive
+    notified_percent INTEGER NOT NULL DEFAULT 0, -- highest milestone announced
+    completed_at TEXT,
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_challenges_dates ON challenges(start_date, end_date);
@@ -0,0 +1,12 @@
+-- Weekly accountability reports handed to a partner
+-- One row per report week; mailto drafts wait as 'pending' until the
+-- frontend has opened them.
+CREATE TABLE IF NOT EXISTS accountability_deliveries (
+    week_end TEXT PRIMARY KEY,                  -- local YYYY-MM-DD, last day of the report
+    method TEXT NOT NULL,                       -- 'file', 'webhook', 'mailto'
+    target TEXT,                                -- file written, webhook URL or mailto URL
+    status TEXT NOT NULL,                       -- 'sent', 'pending', 'failed'
+    error TEXT,
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    delivered_at TEXT
+);
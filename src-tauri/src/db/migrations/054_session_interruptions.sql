@@ -0,0 +1,10 @@
+-- Interruptions logged while a session (usually a pomodoro) is running
+CREATE TABLE IF NOT EXISTS session_interruptions (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    session_id INTEGER NOT NULL REFERENCES sessions(id) ON DELETE CASCADE,
+    reason TEXT NOT NULL,                       -- 'phone', 'messages', 'person', 'noise', 'wandering', 'other'
+    note TEXT,
+    occurred_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_session_interruptions_session ON session_interruptions(session_id);
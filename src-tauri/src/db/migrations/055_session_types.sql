@@ -0,0 +1,15 @@
+-- Registry of session types. 'study' and 'practice' are built in; others
+-- (e.g. 'research', 'thesis_writing') are added by the user. A type's
+-- sessions count toward the study or practice targets it names, if any.
+CREATE TABLE IF NOT EXISTS session_types (
+    name TEXT PRIMARY KEY,                      -- lowercase slug stored in sessions.session_type
+    label TEXT NOT NULL,
+    domain TEXT NOT NULL,                       -- 'academics', 'skills', 'fitness', 'wellness'
+    counts_toward TEXT,                         -- 'study', 'practice' or NULL
+    builtin INTEGER NOT NULL DEFAULT 0,
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+INSERT OR IGNORE INTO session_types (name, label, domain, counts_toward, builtin) VALUES
+    ('study', 'Study', 'academics', 'study', 1),
+    ('practice', 'Practice', 'skills', 'practice', 1);
@@ -0,0 +1,41 @@
+-- Generic trackers: a life area logged as amounts (minutes, pages, ...)
+-- against a weekly target, with a streak rule. Entities are the optional
+-- things a tracker logs against (a book, a deck) and complete once their
+-- logs reach `target_amount`.
+CREATE TABLE IF NOT EXISTS trackers (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    slug TEXT NOT NULL UNIQUE,
+    name TEXT NOT NULL,
+    domain TEXT NOT NULL,                       -- 'academics', 'skills', 'fitness', 'wellness'
+    unit TEXT NOT NULL DEFAULT 'minutes',       -- what `amount` counts
+    weekly_target REAL,                         -- in `unit`
+    streak_rule TEXT NOT NULL DEFAULT 'daily',  -- 'daily', 'weekly' or 'none'
+    streak_min REAL NOT NULL DEFAULT 0,         -- daily amount a 'daily' streak day needs (any log when 0)
+    builtin INTEGER NOT NULL DEFAULT 0,
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE TABLE IF NOT EXISTS tracker_entities (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    tracker_id INTEGER NOT NULL REFERENCES trackers(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    target_amount REAL,
+    completed_at TEXT,
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE TABLE IF NOT EXISTS tracker_logs (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    tracker_id INTEGER NOT NULL REFERENCES trackers(id) ON DELETE CASCADE,
+    entity_id INTEGER REFERENCES tracker_entities(id) ON DELETE SET NULL,
+    amount REAL NOT NULL,
+    minutes INTEGER,                            -- time spent, when `unit` isn't minutes
+    kind TEXT,                                  -- free-form variant, e.g. a meditation style
+    rating INTEGER,                             -- 1-5
+    note TEXT,
+    logged_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_tracker_entities_tracker ON tracker_entities(tracker_id);
+CREATE INDEX IF NOT EXISTS idx_tracker_logs_tracker_logged ON tracker_logs(tracker_id, logged_at);
+CREATE INDEX IF NOT EXISTS idx_tracker_logs_entity ON tracker_logs(entity_id);
@@ -0,0 +1,5 @@
+-- Meditation is a built-in tracker: minutes in the wellness domain with a
+-- daily streak. Each sit is a tracker log; the style is its `kind` and the
+-- calm felt afterwards (1-5) its `rating`.
+INSERT OR IGNORE INTO trackers (slug, name, domain, unit, streak_rule, builtin)
+VALUES ('meditation', 'Meditation', 'wellness', 'minutes', 'daily', 1);
@@ -0,0 +1,12 @@
+-- Calendar events imported from .ics files carry the source they came from
+-- (a user-chosen tag such as 'uni_portal') and the file's UID. Recurring
+-- events are imported as one row per occurrence, keyed by the occurrence's
+-- original start ('' for single events), so a repeated import updates rows
+-- instead of duplicating them.
+ALTER TABLE calendar_events ADD COLUMN source TEXT;
+ALTER TABLE calendar_events ADD COLUMN external_uid TEXT;
+ALTER TABLE calendar_events ADD COLUMN external_occurrence TEXT NOT NULL DEFAULT '';
+
+CREATE UNIQUE INDEX IF NOT EXISTS idx_calendar_events_external
+    ON calendar_events(source, external_uid, external_occurrence)
+    WHERE external_uid IS NOT NULL;
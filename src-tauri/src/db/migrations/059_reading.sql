@@ -0,0 +1,6 @@
+-- Reading is a built-in tracker counted in pages, with a streak for any day
+-- with a page read. Books are its entities with the page count as target, so
+-- a book is finished once its sessions reach the last page. Each session is a
+-- log with the pages read as `amount` and the time taken as `minutes`.
+INSERT OR IGNORE INTO trackers (slug, name, domain, unit, streak_rule, streak_min, builtin)
+VALUES ('reading', 'Reading', 'wellness', 'pages', 'daily', 1, 1);
@@ -0,0 +1,38 @@
+-- Language skills
+-- A skill created from the language template: the language and CEFR levels,
+-- the flashcard decks studied for it and their review history. Listening and
+-- speaking sessions are ordinary sessions of the 'listening' and 'speaking'
+-- types referencing the skill, registered when the first language skill is
+-- created.
+CREATE TABLE IF NOT EXISTS language_skills (
+    skill_id INTEGER PRIMARY KEY REFERENCES skills(id) ON DELETE CASCADE,
+    language TEXT NOT NULL,
+    current_level TEXT NOT NULL DEFAULT 'A1',   -- CEFR: 'A1' .. 'C2'
+    target_level TEXT,
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE TABLE IF NOT EXISTS flashcard_decks (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    skill_id INTEGER NOT NULL REFERENCES skills(id) ON DELETE CASCADE,
+    name TEXT NOT NULL,
+    source TEXT,                                -- e.g. 'anki'
+    card_count INTEGER NOT NULL DEFAULT 0,
+    mature_count INTEGER NOT NULL DEFAULT 0,    -- cards on an interval of 21 days or more
+    created_at TEXT NOT NULL DEFAULT (datetime('now')),
+    UNIQUE (skill_id, name)
+);
+
+-- One review sitting on a deck; its minutes are also a practice log
+CREATE TABLE IF NOT EXISTS flashcard_reviews (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    deck_id INTEGER NOT NULL REFERENCES flashcard_decks(id) ON DELETE CASCADE,
+    practice_log_id INTEGER REFERENCES practice_logs(id) ON DELETE SET NULL,
+    reviewed INTEGER NOT NULL,
+    correct INTEGER NOT NULL,
+    new_cards INTEGER NOT NULL DEFAULT 0,
+    minutes INTEGER,
+    reviewed_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+CREATE INDEX IF NOT EXISTS idx_flashcard_reviews_deck ON flashcard_reviews(deck_id, reviewed_at);
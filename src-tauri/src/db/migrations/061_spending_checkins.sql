@@ -0,0 +1,11 @@
+-- Spending check-ins
+-- One a day: the discretionary spend and whether the day stayed within
+-- budget. A habit loop rather than a budget, so there are no accounts or
+-- categories.
+CREATE TABLE IF NOT EXISTS spending_checkins (
+    date TEXT PRIMARY KEY,                      -- YYYY-MM-DD (local)
+    amount REAL NOT NULL,
+    within_budget INTEGER NOT NULL,
+    note TEXT,
+    updated_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
@@ -0,0 +1,16 @@
+-- Cycle log
+-- Opt-in (`cycle_tracking_enabled`) menstrual cycle tracking: one row per
+-- period. Phases are predicted from these rows and never stored; the data
+-- stays on this device and is left out of exports unless
+-- `cycle_include_in_exports` is on.
+CREATE TABLE IF NOT EXISTS cycle_periods (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    start_date TEXT NOT NULL UNIQUE,            -- YYYY-MM-DD (local)
+    end_date TEXT,                              -- last day; NULL while ongoing or not logged
+    note TEXT,
+    created_at TEXT NOT NULL DEFAULT (datetime('now'))
+);
+
+-- Rich context feature v3: expected energy of the cycle phase
+ALTER TABLE agent_rich_context ADD COLUMN cycle_phase REAL;
+UPDATE agent_state SET value_json = '53', updated_at = datetime('now') WHERE key = 'feature_dim';
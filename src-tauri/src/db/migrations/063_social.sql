@@ -0,0 +1,6 @@
+-- Social time is a built-in tracker: minutes in the wellness domain, with no
+-- streak (the gaps between events are what matter). Each event is a tracker
+-- log; its name is the `note` and how it left the user, from draining (1)
+-- to energizing (5), its `rating`.
+INSERT OR IGNORE INTO trackers (slug, name, domain, unit, streak_rule, builtin)
+VALUES ('social', 'Social', 'wellness', 'minutes', 'none', 1);
@@ -1,2 +1,3 @@
 pub mod connection;
+pub mod filter;
 pub mod migrations;
@@ -0,0 +1,333 @@
+//! Deep Links
+//!
+//! Handles `lifeos://` URLs so launchers and automations (Raycast, Alfred,
+//! Shortcuts) can trigger quick actions:
+//!
+//! - `lifeos://start-session?course=CS201&minutes=25`
+//! - `lifeos://end-session?focus=4`
+//! - `lifeos://checkin?mood=7&energy=6` (without values, opens the check-in view)
+//!
+//! Every handled link is reported to the frontend as a `deep-link` event so
+//! it can refresh or navigate. On mobile, Google sign-in redirects arrive
+//! here too and are handed to the pending OAuth session instead.
+
+use serde::Serialize;
+use sqlx::{Pool, Sqlite};
+use tauri::{Emitter, Manager};
+use url::Url;
+
+use crate::commands::checkins::{insert_checkin, CheckInInput};
+use crate::commands::google_calendar::{receive_redirect, GoogleState, MOBILE_REDIRECT_PATH};
+use crate::commands::sessions::{close_session, insert_session, SessionInput};
+use crate::error::ApiError;
+use crate::models::checkin::CheckIn;
+use crate::models::session::{Session, SessionType};
+use crate::DbState;
+
+pub const SCHEME: &str = "lifeos";
+
+/// Frontend event carrying a `DeepLinkEvent`
+pub const DEEP_LINK_EVENT: &str = "deep-link";
+
+const MAX_SESSION_MINUTES: i64 = 480;
+
+/// A parsed quick action
+#[derive(Debug, Clone, PartialEq)]
+pub enum DeepLinkAction {
+    StartSession {
+        /// Course code or name
+        course: Option<String>,
+        minutes: Option<i64>,
+    },
+    EndSession {
+        focus: Option<i64>,
+    },
+    Checkin {
+        mood: Option<i64>,
+        energy: Option<i64>,
+    },
+}
+
+/// What a handled link did
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum DeepLinkOutcome {
+    SessionStarted { session: Session },
+    SessionEnded { session: Session },
+    CheckinCreated { checkin: CheckIn },
+    /// The action needs input; the frontend should open this view
+    Open { view: &'static str },
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DeepLinkEvent {
+    pub url: String,
+    pub outcome: Option<DeepLinkOutcome>,
+    pub error: Option<String>,
+}
+
+/// Register the URL scheme and start listening for links
+pub fn register(app: &tauri::App) -> Result<(), Box<dyn std::error::Error>> {
+    use tauri_plugin_deep_link::DeepLinkExt;
+
+    // macOS registers schemes from the bundle; elsewhere it happens at runtime
+    #[cfg(any(windows, target_os = "linux"))]
+    app.deep_link().register_all()?;
+
+    let handle = app.handle().clone();
+    app.deep_link().on_open_url(move |event| {
+        for url in event.urls() {
+            spawn_handle_url(handle.clone(), url);
+        }
+    });
+
+    // Links that launched the app
+    if let Some(urls) = app.deep_link().get_current()? {
+        for url in urls {
+            spawn_handle_url(app.handle().clone(), url);
+        }
+    }
+
+    Ok(())
+}
+
+fn spawn_handle_url(app_handle: tauri::AppHandle, url: Url) {
+    tauri::async_runtime::spawn(async move {
+        // Google sign-in on mobile returns through a custom-scheme redirect
+        if url.scheme() != SCHEME && url.path() == MOBILE_REDIRECT_PATH {
+            let google_state = app_handle.state::<GoogleState>();
+            if !receive_redirect(&google_state, &url).await {
+                log::warn!("Ignoring OAuth redirect without a matching sign-in");
+            }
+            return;
+        }
+
+        let pool = app_handle.state::<DbState>().0.clone();
+        let result = match parse(&url) {
+            Ok(action) => execute(&pool, action).await,
+            Err(e) => Err(e),
+        };
+
+        let event = match result {
+            Ok(outcome) => DeepLinkEvent {
+                url: url.to_string(),
+                outcome: Some(outcome),
+                error: None,
+            },
+            Err(e) => {
+                log::warn!("Deep link {} failed: {}", url, e.message);
+                DeepLinkEvent {
+                    url: url.to_string(),
+                    outcome: None,
+                    error: Some(e.message),
+                }
+            }
+        };
+
+        if let Err(e) = app_handle.emit(DEEP_LINK_EVENT, event) {
+            log::warn!("Failed to emit deep link event: {}", e);
+        }
+    });
+}
+
+/// Parse a `lifeos://` URL into an action
+pub fn parse(url: &Url) -> Result<DeepLinkAction, ApiError> {
+    if url.scheme() != SCHEME {
+        return Err(ApiError::validation(format!("Unsupported scheme '{}'", url.scheme())));
+    }
+
+    // `lifeos://checkin` puts the action in the host, `lifeos:checkin` in the path
+    let action = url
+        .host_str()
+        .unwrap_or_else(|| url.path())
+        .trim_matches('/')
+        .to_ascii_lowercase();
+    let param = |name: &str| {
+        url.query_pairs()
+            .find(|(k, _)| k == name)
+            .map(|(_, v)| v.trim().to_string())
+            .filter(|v| !v.is_empty())
+    };
+    let int_param = |name: &str, min: i64, max: i64| -> Result<Option<i64>, ApiError> {
+        param(name)
+            .map(|v| match v.parse::<i64>() {
+                Ok(n) if (min..=max).contains(&n) => Ok(n),
+                _ => Err(ApiError::validation(format!(
+                    "'{}' must be a number between {} and {}",
+                    name, min, max
+                ))),
+            })
+            .transpose()
+    };
+
+    match action.as_str() {
+        "start-session" => Ok(DeepLinkAction::StartSession {
+            course: param("course"),
+            minutes: int_param("minutes", 1, MAX_SESSION_MINUTES)?,
+        }),
+        "end-session" => Ok(DeepLinkAction::EndSession {
+            focus: int_param("focus", 1, 5)?,
+        }),
+        "checkin" => Ok(DeepLinkAction::Checkin {
+            mood: int_param("mood", 1, 10)?,
+            energy: int_param("energy", 1, 10)?,
+        }),
+        other => Err(ApiError::validation(format!("Unknown deep link action '{}'", other))),
+    }
+}
+
+/// Run an action against the database
+pub async fn execute(pool: &Pool<Sqlite>, action: DeepLinkAction) -> Result<DeepLinkOutcome, ApiError> {
+    match action {
+        DeepLinkAction::StartSession { course, minutes } => {
+            let course_id = match course {
+                Some(ref c) => Some(resolve_course(pool, c).await?),
+                None => None,
+            };
+            let session = insert_session(
+                pool,
+                &SessionInput {
+                    user_id: None,
+                    session_type: SessionType::Study,
+                    reference_id: course_id,
+                    reference_type: course_id.map(|_| "course".to_string()),
+                    started_at: None,
+                    notes: None,
+                    planned_minutes: minutes,
+                    focus_profile_id: None,
+                    title: None,
+                    course_meeting_id: None,
+                    meeting_date: None,
+                    week_plan_block_id: None,
+                },
+            )
+            .await?;
+            Ok(DeepLinkOutcome::SessionStarted { session })
+        }
+        DeepLinkAction::EndSession { focus } => {
+            let open: Option<i64> = sqlx::query_scalar(
+                "SELECT id FROM sessions WHERE ended_at IS NULL ORDER BY started_at DESC, id DESC LIMIT 1",
+            )
+            .fetch_optional(pool)
+            .await
+            .map_err(ApiError::from)?;
+            let id = open.ok_or_else(|| ApiError::not_found("No session is running"))?;
+            let session = close_session(pool, id, focus).await?;
+            Ok(DeepLinkOutcome::SessionEnded { session })
+        }
+        DeepLinkAction::Checkin { mood: None, energy: None } => Ok(DeepLinkOutcome::Open { view: "checkin" }),
+        DeepLinkAction::Checkin { mood, energy } => {
+            let checkin = insert_checkin(
+                pool,
+                &CheckInInput {
+                    user_id: None,
+                    mood,
+                    energy,
+                    notes: None,
+                },
+            )
+            .await?;
+            Ok(DeepLinkOutcome::CheckinCreated { checkin })
+        }
+    }
+}
+
+/// Find an active course by code or name, ignoring case and spaces ("CS201" matches "CS 201")
+pub(crate) async fn resolve_course(pool: &Pool<Sqlite>, query: &str) -> Result<i64, ApiError> {
+    let normalize = |s: &str| -> String {
+        s.chars()
+            .filter(|c| c.is_alphanumeric())
+            .flat_map(char::to_lowercase)
+            .collect()
+    };
+    let wanted = normalize(query);
+
+    let courses: Vec<(i64, String, Option<String>)> =
+        sqlx::query_as("SELECT id, name, code FROM courses WHERE is_active = 1 ORDER BY id")
+            .fetch_all(pool)
+            .await
+            .map_err(ApiError::from)?;
+
+    courses
+        .iter()
+        .find(|(_, _, code)| code.as_deref().map(normalize).as_deref() == Some(wanted.as_str()))
+        .or_else(|| courses.iter().find(|(_, name, _)| normalize(name) == wanted))
+        .map(|(id, _, _)| *id)
+        .ok_or_else(|| ApiError::not_found(format!("No active course matches '{}'", query)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+    use std::str::FromStr;
+
+    async fn setup_pool_with_migrations() -> Pool<Sqlite> {
+        let options = SqliteConnectOptions::from_str("sqlite::memory:")
+            .unwrap()
+            .foreign_keys(false);
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect_with(options)
+            .await
+            .expect("Failed to connect to in-memory DB");
+
+        crate::db::migrations::run_migrations(&pool)
+            .await
+            .expect("Failed to run migrations");
+
+        pool
+    }
+
+    fn parse_str(url: &str) -> Result<DeepLinkAction, ApiError> {
+        parse(&Url::parse(url).unwrap())
+    }
+
+    #[test]
+    fn test_parse() {
+        assert_eq!(
+            parse_str("lifeos://start-session?course=CS201&minutes=25").unwrap(),
+            DeepLinkAction::StartSession {
+                course: Some("CS201".into()),
+                minutes: Some(25)
+            }
+        );
+        assert_eq!(
+            parse_str("lifeos://checkin").unwrap(),
+            DeepLinkAction::Checkin { mood: None, energy: None }
+        );
+        assert_eq!(
+            parse_str("lifeos:end-session?focus=4").unwrap(),
+            DeepLinkAction::EndSession { focus: Some(4) }
+        );
+        assert!(parse_str("lifeos://checkin?mood=11").is_err());
+        assert!(parse_str("lifeos://delete-everything").is_err());
+        assert!(parse_str("https://checkin").is_err());
+    }
+
+    #[tokio::test]
+    async fn start_and_end_session_by_course_code() {
+        let pool = setup_pool_with_migrations().await;
+        let course_id: i64 =
+            sqlx::query_scalar("INSERT INTO courses (name, code) VALUES ('Data Structures', 'CS 201') RETURNING id")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+
+        let action = parse_str("lifeos://start-session?course=cs201").unwrap();
+        let DeepLinkOutcome::SessionStarted { session } = execute(&pool, action).await.unwrap() else {
+            panic!("expected a started session");
+        };
+        assert_eq!(session.reference_id, Some(course_id));
+
+        let action = parse_str("lifeos://end-session?focus=4").unwrap();
+        let DeepLinkOutcome::SessionEnded { session: ended } = execute(&pool, action).await.unwrap() else {
+            panic!("expected an ended session");
+        };
+        assert_eq!(ended.id, session.id);
+        assert_eq!(ended.focus_rating, Some(4));
+
+        let action = parse_str("lifeos://start-session?course=Chemistry").unwrap();
+        assert!(execute(&pool, action).await.is_err());
+    }
+}
@@ -18,55 +18,73 @@ pub struct ApiError {
     pub details: Option<serde_json::Value>,
 }
 
+impl ErrorCode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ErrorCode::Validation => "validation",
+            ErrorCode::NotFound => "not_found",
+            ErrorCode::Conflict => "conflict",
+            ErrorCode::Transient => "transient",
+            ErrorCode::Internal => "internal",
+        }
+    }
+}
+
+// Constructors are `#[track_caller]` so local telemetry can attribute each
+// error to the module that raised it
+
 impl ApiError {
-    pub fn validation(message: impl Into<String>) -> Self {
+    #[track_caller]
+    fn new(code: ErrorCode, message: String) -> Self {
+        crate::services::telemetry::record_error(&code, std::panic::Location::caller());
         Self {
-            code: ErrorCode::Validation,
-            message: message.into(),
+            code,
+            message,
             details: None,
         }
     }
 
+    #[track_caller]
+    pub fn validation(message: impl Into<String>) -> Self {
+        Self::new(ErrorCode::Validation, message.into())
+    }
+
+    #[track_caller]
     pub fn not_found(message: impl Into<String>) -> Self {
-        Self {
-            code: ErrorCode::NotFound,
-            message: message.into(),
-            details: None,
-        }
+        Self::new(ErrorCode::NotFound, message.into())
     }
 
+    #[track_caller]
     pub fn conflict(message: impl Into<String>) -> Self {
-        Self {
-            code: ErrorCode::Conflict,
-            message: message.into(),
-            details: None,
-        }
+        Self::new(ErrorCode::Conflict, message.into())
     }
 
+    #[track_caller]
     pub fn transient(message: impl Into<String>) -> Self {
-        Self {
-            code: ErrorCode::Transient,
-            message: message.into(),
-            details: None,
-        }
+        Self::new(ErrorCode::Transient, message.into())
     }
 
+    #[track_caller]
     pub fn internal(message: impl Into<String>) -> Self {
-        Self {
-            code: ErrorCode::Internal,
-            message: message.into(),
-            details: None,
-        }
+        Self::new(ErrorCode::Internal, message.into())
+    }
+
+    /// Attach structured data the frontend can act on
+    pub fn with_details(mut self, details: serde_json::Value) -> Self {
+        self.details = Some(details);
+        self
     }
 
+    #[track_caller]
     pub fn from_sqlx(err: sqlx::Error, message: impl Into<String>) -> Self {
-        let mut base: ApiError = err.into();
+        let mut base = ApiError::from(err);
         base.message = message.into();
         base
     }
 }
 
 impl From<sqlx::Error> for ApiError {
+    #[track_caller]
     fn from(err: sqlx::Error) -> Self {
         match err {
             sqlx::Error::RowNotFound => ApiError::not_found("Resource not found"),
@@ -86,24 +104,28 @@ impl From<sqlx::Error> for ApiError {
 }
 
 impl From<reqwest::Error> for ApiError {
+    #[track_caller]
     fn from(err: reqwest::Error) -> Self {
         ApiError::internal(err.to_string())
     }
 }
 
 impl From<std::io::Error> for ApiError {
+    #[track_caller]
     fn from(err: std::io::Error) -> Self {
         ApiError::internal(err.to_string())
     }
 }
 
 impl From<sqlx::migrate::MigrateError> for ApiError {
+    #[track_caller]
     fn from(err: sqlx::migrate::MigrateError) -> Self {
         ApiError::internal(err.to_string())
     }
 }
 
 impl From<String> for ApiError {
+    #[track_caller]
     fn from(err: String) -> Self {
         ApiError::internal(err)
     }
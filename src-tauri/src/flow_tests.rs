@@ -0,0 +1,125 @@
+//! End-to-end flows across commands, on the `test_support` harness
+
+use chrono::{DateTime, Duration, Local};
+
+use crate::{
+    commands::{
+        analytics::compute_stats,
+        course_meetings::{create_course_meeting_inner, CourseMeetingInput},
+        courses::get_courses_with_progress_inner,
+        free_slots::{free_slots, FreeSlot, FreeSlotQuery},
+        week_plan_blocks::bulk_create_plan_blocks_inner,
+    },
+    error::ErrorCode,
+    test_support::{self, course, meeting, plan_block, study_session},
+};
+
+/// A Monday, far enough out that nothing else lands on it
+const MONDAY: &str = "2030-09-02";
+
+/// Longest block the test planner puts into one slot
+const MAX_BLOCK_MINUTES: i64 = 90;
+
+fn day(include_suggested: bool) -> FreeSlotQuery {
+    FreeSlotQuery {
+        start_date: MONDAY.to_string(),
+        end_date: MONDAY.to_string(),
+        min_minutes: Some(30),
+        include_suggested: Some(include_suggested),
+    }
+}
+
+/// Fill the start of a slot, the way the planner's auto-scheduler does;
+/// blocks are stored in naive local time
+fn block_times(slot: &FreeSlot) -> (String, String) {
+    let start = DateTime::parse_from_rfc3339(&slot.start_at).unwrap().with_timezone(&Local).naive_local();
+    let end = start + Duration::minutes(slot.minutes.min(MAX_BLOCK_MINUTES));
+    let format = "%Y-%m-%dT%H:%M:%S";
+    (start.format(format).to_string(), end.format(format).to_string())
+}
+
+#[tokio::test]
+async fn course_meetings_plan_session_and_stats() {
+    let pool = test_support::pool().await;
+
+    // Course with a Monday lecture
+    let algorithms = course("Algorithms").code("CS201").target_weekly_hours(6.0).create(&pool).await;
+    meeting(algorithms.id, 1, "09:00", "10:30").create(&pool).await;
+
+    // Working hours (08:00-20:00) minus the lecture
+    let slots = free_slots(&pool, day(false)).await.unwrap();
+    let minutes: Vec<i64> = slots.iter().map(|s| s.minutes).collect();
+    assert_eq!(minutes, vec![60, 570]);
+
+    // Generated plan: a suggested study block at the start of each slot
+    let blocks = slots
+        .iter()
+        .map(|slot| {
+            let (start_at, end_at) = block_times(slot);
+            plan_block(MONDAY, &start_at, &end_at, Some(algorithms.id))
+        })
+        .collect();
+    let created = bulk_create_plan_blocks_inner(&pool, blocks).await.unwrap();
+    assert_eq!(created.len(), 2);
+    assert!(created.iter().all(|b| b.status.as_deref() == Some("suggested")));
+
+    // Suggested blocks are ignored by default, and busy when asked to count
+    assert_eq!(free_slots(&pool, day(false)).await.unwrap().len(), 2);
+    let remaining: Vec<i64> = free_slots(&pool, day(true)).await.unwrap().iter().map(|s| s.minutes).collect();
+    assert_eq!(remaining, vec![480]);
+
+    // Study session on the course
+    let session = study_session(algorithms.id)
+        .planned_minutes(45)
+        .started_minutes_ago(50)
+        .complete(&pool, Some(4))
+        .await;
+    assert!(session.ended_at.is_some());
+    let duration = session.duration_minutes.unwrap();
+    assert!((49..=51).contains(&duration), "duration {}", duration);
+
+    // Stats and course progress pick it up
+    let stats = compute_stats(&pool).await.unwrap();
+    assert!((stats.study_hours_week - duration as f64 / 60.0).abs() < 1e-9);
+
+    let courses = get_courses_with_progress_inner(&pool).await.unwrap();
+    let progress = courses.iter().find(|c| c.id == algorithms.id).unwrap();
+    assert!((progress.total_hours - duration as f64 / 60.0).abs() < 1e-9);
+    assert!(progress.weekly_percent > 0.0);
+}
+
+#[tokio::test]
+async fn fixtures_go_through_command_validation() {
+    let pool = test_support::pool().await;
+    let algorithms = course("Algorithms").create(&pool).await;
+
+    // Meeting input is validated like it is for the frontend
+    let err = create_course_meeting_inner(
+        &pool,
+        CourseMeetingInput {
+            course_id: algorithms.id,
+            day_of_week: 1,
+            start_time: "11:00".to_string(),
+            end_time: "10:00".to_string(),
+            location: None,
+            meeting_type: None,
+        },
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(err.code, ErrorCode::Validation);
+
+    // One invalid block rejects the whole batch
+    let mut bad = plan_block(MONDAY, "2030-09-02T08:00:00", "2030-09-02T09:00:00", Some(algorithms.id));
+    bad.block_type = "nap".to_string();
+    let blocks = vec![
+        plan_block(MONDAY, "2030-09-02T10:00:00", "2030-09-02T11:00:00", Some(algorithms.id)),
+        bad,
+    ];
+    assert!(bulk_create_plan_blocks_inner(&pool, blocks).await.is_err());
+    let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM week_plan_blocks")
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+    assert_eq!(count, 0);
+}
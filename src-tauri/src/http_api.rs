@@ -0,0 +1,364 @@
+//! Local HTTP API
+//!
+//! An optional JSON API on `127.0.0.1` for home dashboards, e-ink displays
+//! and scripts. It only runs while `http_api_enabled` is on and a bearer token
+//! has been generated; the token lives in the OS keychain (app-private
+//! storage on mobile, see `platform`). The REST endpoints are read-only;
+//! writes only happen through scoped MCP tools.
+//!
+//! - `GET /v1/health` (no token): liveness and API version
+//! - `GET /v1/stats`: last-7-days totals
+//! - `GET /v1/streaks`: current streaks
+//! - `GET /v1/agenda`: today's and tomorrow's calendar items
+//! - `POST /mcp`: MCP endpoint while `mcp_enabled` is on (see `mcp`)
+//! - `GET /feed/<feed token>.ics` (no bearer token): the calendar feed while
+//!   `calendar_feed_enabled` is on (see `calendar_feed`)
+//!
+//! The server restarts whenever its settings or the token change.
+
+use std::net::{Ipv4Addr, SocketAddr};
+use std::time::Duration;
+
+use axum::extract::{Path, Request, State};
+use axum::http::{header, StatusCode};
+use axum::middleware::{self, Next};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use once_cell::sync::Lazy;
+use rand::distributions::Alphanumeric;
+use rand::Rng;
+use serde::Serialize;
+use serde_json::json;
+use sha2::{Digest, Sha256};
+use sqlx::{Pool, Sqlite};
+use tokio::net::TcpListener;
+use tokio::sync::{oneshot, Mutex};
+
+use crate::commands::analytics::{compute_stats, StatsSummary, Streaks};
+use crate::commands::calendar::CalendarItem;
+use crate::commands::capabilities::API_VERSION;
+use crate::error::ApiError;
+use crate::platform;
+use crate::services::{aggregates, calendar_feed, settings};
+
+const TOKEN_LENGTH: usize = 40;
+const TOKEN_SECRET: &str = "http_api_token";
+/// How long a restart waits for open connections before rebinding
+const SHUTDOWN_GRACE: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct HttpApiStatus {
+    pub enabled: bool,
+    pub running: bool,
+    pub port: u16,
+    /// Base URL while running
+    pub url: Option<String>,
+    pub has_token: bool,
+    /// Why the server isn't running despite being enabled
+    pub error: Option<String>,
+}
+
+struct RunningServer {
+    port: u16,
+    shutdown: oneshot::Sender<()>,
+    task: tauri::async_runtime::JoinHandle<()>,
+}
+
+static SERVER: Lazy<Mutex<Option<RunningServer>>> = Lazy::new(|| Mutex::new(None));
+static LAST_ERROR: Lazy<parking_lot::Mutex<Option<String>>> = Lazy::new(|| parking_lot::Mutex::new(None));
+
+#[derive(Clone)]
+struct ApiState {
+    pool: Pool<Sqlite>,
+    token_hash: [u8; 32],
+}
+
+/// Start the server if enabled and restart it when its settings change
+pub fn start(pool: Pool<Sqlite>) {
+    tauri::async_runtime::spawn(async move {
+        let mut changes = settings::subscribe();
+        apply(&pool).await;
+        loop {
+            match changes.recv().await {
+                Ok(change) if change.key.starts_with("http_api_") => apply(&pool).await,
+                Ok(_) => {}
+                Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => apply(&pool).await,
+                Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    });
+}
+
+/// Stop any running server and start a new one per the current settings
+pub async fn apply(pool: &Pool<Sqlite>) {
+    let result = restart(pool).await;
+    if let Err(ref e) = result {
+        log::warn!("Local HTTP API not started: {}", e.message);
+    }
+    *LAST_ERROR.lock() = result.err().map(|e| e.message);
+}
+
+async fn restart(pool: &Pool<Sqlite>) -> Result<(), ApiError> {
+    let mut server = SERVER.lock().await;
+    if let Some(running) = server.take() {
+        let _ = running.shutdown.send(());
+        // The port must be free again before rebinding it
+        let _ = tokio::time::timeout(SHUTDOWN_GRACE, running.task).await;
+    }
+
+    if !settings::get_bool(pool, "http_api_enabled").await? {
+        return Ok(());
+    }
+    let token = load_token()?.ok_or_else(|| ApiError::validation("Generate an API token first"))?;
+    let port = settings::get_i64(pool, "http_api_port").await? as u16;
+
+    let listener = TcpListener::bind(SocketAddr::from((Ipv4Addr::LOCALHOST, port)))
+        .await
+        .map_err(|e| ApiError::conflict(format!("Port {} is unavailable: {}", port, e)))?;
+    let (shutdown, stopped) = oneshot::channel();
+    let task = tauri::async_runtime::spawn(serve(listener, pool.clone(), token, stopped));
+    log::info!("Local HTTP API listening on 127.0.0.1:{}", port);
+
+    *server = Some(RunningServer { port, shutdown, task });
+    Ok(())
+}
+
+/// Serve the API on `listener` until `shutdown` fires
+async fn serve(listener: TcpListener, pool: Pool<Sqlite>, token: String, shutdown: oneshot::Receiver<()>) {
+    let state = ApiState {
+        pool,
+        token_hash: Sha256::digest(token.as_bytes()).into(),
+    };
+    let result = axum::serve(listener, router(state))
+        .with_graceful_shutdown(async {
+            let _ = shutdown.await;
+        })
+        .await;
+    if let Err(e) = result {
+        log::warn!("Local HTTP API stopped: {}", e);
+    }
+}
+
+fn router(state: ApiState) -> Router {
+    let protected = Router::new()
+        .route("/v1/stats", get(stats))
+        .route("/v1/streaks", get(streaks))
+        .route("/v1/agenda", get(agenda))
+        .route("/mcp", post(mcp))
+        .route_layer(middleware::from_fn_with_state(state.clone(), require_token));
+
+    Router::new()
+        .route("/v1/health", get(health))
+        .route("/feed/{file}", get(feed))
+        .merge(protected)
+        .with_state(state)
+}
+
+async fn require_token(State(state): State<ApiState>, request: Request, next: Next) -> Response {
+    let presented = request
+        .headers()
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "));
+
+    // Compare digests so response timing says nothing about the token
+    match presented {
+        Some(token) if <[u8; 32]>::from(Sha256::digest(token.trim().as_bytes())) == state.token_hash => {
+            next.run(request).await
+        }
+        _ => (
+            StatusCode::UNAUTHORIZED,
+            [(header::WWW_AUTHENTICATE, "Bearer")],
+            Json(json!({ "error": "Missing or invalid bearer token" })),
+        )
+            .into_response(),
+    }
+}
+
+/// Maps `ApiError` onto an HTTP status
+struct HttpError(ApiError);
+
+impl IntoResponse for HttpError {
+    fn into_response(self) -> Response {
+        use crate::error::ErrorCode;
+
+        let status = match self.0.code {
+            ErrorCode::Validation => StatusCode::BAD_REQUEST,
+            ErrorCode::NotFound => StatusCode::NOT_FOUND,
+            ErrorCode::Conflict => StatusCode::CONFLICT,
+            ErrorCode::Transient => StatusCode::SERVICE_UNAVAILABLE,
+            ErrorCode::Internal => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        (status, Json(self.0)).into_response()
+    }
+}
+
+impl From<ApiError> for HttpError {
+    fn from(e: ApiError) -> Self {
+        HttpError(e)
+    }
+}
+
+async fn health() -> Json<serde_json::Value> {
+    Json(json!({ "ok": true, "api_version": API_VERSION }))
+}
+
+async fn stats(State(state): State<ApiState>) -> Result<Json<StatsSummary>, HttpError> {
+    Ok(Json(compute_stats(&state.pool).await?))
+}
+
+async fn streaks(State(state): State<ApiState>) -> Result<Json<Streaks>, HttpError> {
+    Ok(Json(aggregates::get(&state.pool).await?.streaks.clone()))
+}
+
+async fn agenda(State(state): State<ApiState>) -> Result<Json<Vec<CalendarItem>>, HttpError> {
+    Ok(Json(aggregates::get(&state.pool).await?.upcoming.clone()))
+}
+
+/// The calendar feed; a wrong token looks the same as a disabled feed
+async fn feed(State(state): State<ApiState>, Path(file): Path<String>) -> Result<Response, HttpError> {
+    let token = file.strip_suffix(".ics").unwrap_or(&file);
+    if !settings::get_bool(&state.pool, "calendar_feed_enabled").await? || !calendar_feed::token_matches(token)? {
+        return Err(HttpError(ApiError::not_found("No such feed")));
+    }
+    let ics = calendar_feed::render(&state.pool).await?;
+    Ok(([(header::CONTENT_TYPE, "text/calendar; charset=utf-8")], ics).into_response())
+}
+
+/// Streamable HTTP transport: one JSON-RPC message in, one JSON response out
+async fn mcp(State(state): State<ApiState>, body: String) -> Result<Response, HttpError> {
+    if !settings::get_bool(&state.pool, "mcp_enabled").await? {
+        return Err(HttpError(ApiError::not_found("MCP is disabled in Life OS settings")));
+    }
+    let message = match serde_json::from_str(&body) {
+        Ok(message) => message,
+        Err(e) => return Ok((StatusCode::BAD_REQUEST, Json(crate::mcp::parse_error(&e.to_string()))).into_response()),
+    };
+
+    Ok(match crate::mcp::handle(&state.pool, message).await {
+        Some(response) => Json(response).into_response(),
+        // Notifications are acknowledged without a body
+        None => StatusCode::ACCEPTED.into_response(),
+    })
+}
+
+/// Current server state
+pub async fn status(pool: &Pool<Sqlite>) -> Result<HttpApiStatus, ApiError> {
+    let enabled = settings::get_bool(pool, "http_api_enabled").await?;
+    let configured_port = settings::get_i64(pool, "http_api_port").await? as u16;
+    let running = SERVER.lock().await.as_ref().map(|s| s.port);
+
+    Ok(HttpApiStatus {
+        enabled,
+        running: running.is_some(),
+        port: running.unwrap_or(configured_port),
+        url: running.map(|p| format!("http://127.0.0.1:{}/v1", p)),
+        has_token: load_token()?.is_some(),
+        error: if enabled { LAST_ERROR.lock().clone() } else { None },
+    })
+}
+
+/// Replace the bearer token (invalidating the old one) and restart the server
+pub async fn regenerate_token(pool: &Pool<Sqlite>) -> Result<String, ApiError> {
+    let token: String = rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(TOKEN_LENGTH)
+        .map(char::from)
+        .collect();
+    platform::set_secret(TOKEN_SECRET, &token)?;
+    apply(pool).await;
+    Ok(token)
+}
+
+fn load_token() -> Result<Option<String>, ApiError> {
+    platform::get_secret(TOKEN_SECRET)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+    use std::str::FromStr;
+
+    async fn setup_pool_with_migrations() -> Pool<Sqlite> {
+        let options = SqliteConnectOptions::from_str("sqlite::memory:")
+            .unwrap()
+            .foreign_keys(false);
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect_with(options)
+            .await
+            .expect("Failed to connect to in-memory DB");
+
+        crate::db::migrations::run_migrations(&pool)
+            .await
+            .expect("Failed to run migrations");
+
+        pool
+    }
+
+    #[tokio::test]
+    async fn serves_read_endpoints_only_with_the_token() {
+        let pool = setup_pool_with_migrations().await;
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}/v1", listener.local_addr().unwrap());
+        let (shutdown, stopped) = oneshot::channel();
+        let server = tokio::spawn(serve(listener, pool.clone(), "secret-token".into(), stopped));
+
+        let client = reqwest::Client::new();
+        let health = client.get(format!("{}/health", base)).send().await.unwrap();
+        assert_eq!(health.status(), 200);
+
+        let anonymous = client.get(format!("{}/stats", base)).send().await.unwrap();
+        assert_eq!(anonymous.status(), 401);
+        let wrong = client
+            .get(format!("{}/stats", base))
+            .bearer_auth("guess")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(wrong.status(), 401);
+
+        for path in ["stats", "streaks", "agenda"] {
+            let response = client
+                .get(format!("{}/{}", base, path))
+                .bearer_auth("secret-token")
+                .send()
+                .await
+                .unwrap();
+            assert_eq!(response.status(), 200, "{}", path);
+        }
+        let stats: serde_json::Value = client
+            .get(format!("{}/stats", base))
+            .bearer_auth("secret-token")
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap();
+        assert_eq!(stats["workouts_week"], 0);
+
+        let post = client
+            .post(format!("{}/stats", base))
+            .bearer_auth("secret-token")
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(post.status(), 405);
+
+        let mcp_url = format!("{}/mcp", base.trim_end_matches("/v1"));
+        let mcp = |body: serde_json::Value| client.post(&mcp_url).bearer_auth("secret-token").json(&body).send();
+        let ping = json!({ "jsonrpc": "2.0", "id": 1, "method": "ping" });
+        assert_eq!(mcp(ping.clone()).await.unwrap().status(), 404);
+        settings::set(&pool, "mcp_enabled", json!(true)).await.unwrap();
+        let pong: serde_json::Value = mcp(ping).await.unwrap().json().await.unwrap();
+        assert_eq!(pong["id"], 1);
+        let notification = json!({ "jsonrpc": "2.0", "method": "notifications/initialized" });
+        assert_eq!(mcp(notification).await.unwrap().status(), 202);
+
+        shutdown.send(()).unwrap();
+        server.await.unwrap();
+    }
+}
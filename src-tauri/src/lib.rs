@@ -10,21 +10,45 @@ mod ml;
 mod services;
 mod utils;
 mod error;
+mod deep_link;
+mod platform;
+mod quick_actions;
+mod http_api;
+mod mcp;
 #[cfg(test)]
 mod error_test;
+#[cfg(test)]
+mod test_support;
+#[cfg(test)]
+mod flow_tests;
 
-use db::connection::establish_pool;
+use db::connection::{establish_pool, establish_pool_with, PoolConfig};
 use db::migrations::run_migrations;
 
 pub struct DbState(pub sqlx::Pool<sqlx::Sqlite>);
 
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
-  tauri::Builder::default()
+  let mut builder = tauri::Builder::default();
+
+  // A second launch (e.g. from a deep link) forwards its URL here and exits
+  #[cfg(desktop)]
+  {
+    builder = builder.plugin(tauri_plugin_single_instance::init(|app, _argv, _cwd| {
+      if let Some(window) = app.get_webview_window("main") {
+        let _ = window.unminimize();
+        let _ = window.set_focus();
+      }
+    }));
+  }
+
+  builder
     .plugin(tauri_plugin_log::Builder::default().build())
+    .plugin(tauri_plugin_deep_link::init())
     .setup(|app| {
 
       let app_handle = app.handle().clone();
+      platform::init(&app_handle);
       tauri::async_runtime::block_on(async move {
         let mut app_dir = app_handle
           .path()
@@ -34,23 +58,65 @@ pub fn run() {
 
         log::info!("SQLite DB path: {}", app_dir.to_string_lossy());
 
-        let pool = establish_pool(app_dir).await.expect("failed to connect to sqlite");
+        let mut pool = establish_pool(app_dir.clone()).await.expect("failed to connect to sqlite");
         run_migrations(&pool).await.expect("failed to run migrations");
         crate::db::connection::ensure_default_user(&pool)
           .await
           .expect("failed to ensure default user");
 
+        // Pool size and pragmas are settings, so they are only known once the
+        // database is open
+        match PoolConfig::load(&pool).await {
+          Ok(config) if config != PoolConfig::default() => {
+            log::info!("Reopening database with {:?}", config);
+            pool.close().await;
+            pool = establish_pool_with(app_dir, &config)
+              .await
+              .expect("failed to reconnect to sqlite");
+          }
+          Ok(_) => {}
+          Err(e) => log::warn!("failed to load database pool settings: {}", e.message),
+        }
+        if let Err(e) = ml::bandit_v2::HybridBandit::upgrade_feature_space(&pool).await {
+          log::warn!("failed to upgrade bandit feature space: {}", e);
+        }
+
+        let reduced = services::settings::get_bool(&pool, "reduced_data_mode").await.unwrap_or(false);
+        ml::readiness::set_reduced_data_mode(reduced);
+        ml::readiness::follow_setting();
+        services::telemetry::start(pool.clone());
+        services::telemetry::follow_setting();
+        agent::scheduler::start(pool.clone());
+        services::focus::start(pool.clone());
+        services::achievements::start(pool.clone());
+        http_api::start(pool.clone());
+        services::settings::start_event_forwarder(app_handle.clone());
+        services::progress::start_event_forwarder(app_handle.clone());
+        services::missed_blocks::start_event_forwarder(app_handle.clone());
+        services::gamification::start_event_forwarder(app_handle.clone());
+        services::challenges::start_event_forwarder(app_handle.clone());
+        services::meditation::start_event_forwarder(app_handle.clone());
+
         app_handle.manage(DbState(pool));
         app_handle.manage(commands::google_calendar::GoogleState::default());
       });
 
+      if let Err(e) = deep_link::register(app) {
+        log::warn!("failed to register deep links: {}", e);
+      }
+      let pool = app.state::<DbState>().0.clone();
+      if let Err(e) = quick_actions::register(app, pool) {
+        log::warn!("failed to register quick actions: {}", e);
+      }
+
       Ok(())
     })
-    .invoke_handler(tauri::generate_handler![
+    .invoke_handler(services::telemetry::count_invocations(tauri::generate_handler![
       commands::courses::create_course,
       commands::courses::get_courses,
       commands::courses::get_course,
       commands::courses::update_course,
+      commands::courses::set_course_schedule_prefs,
       commands::courses::delete_course,
       commands::courses::get_courses_with_progress,
       commands::courses::get_course_analytics,
@@ -60,6 +126,8 @@ pub fn run() {
       commands::exams::update_exam,
       commands::exams::delete_exam,
       commands::exams::get_upcoming_exams,
+      commands::exams::record_exam_result,
+      commands::exam_readiness::get_exam_readiness,
       commands::course_meetings::create_course_meeting,
       commands::course_meetings::get_course_meetings,
       commands::course_meetings::update_course_meeting,
@@ -70,6 +138,7 @@ pub fn run() {
       commands::calendar_events::get_calendar_event,
       commands::calendar_events::update_calendar_event,
       commands::calendar_events::delete_calendar_event,
+      commands::calendar_events::import_ics_file,
       // Weekly Tasks
       commands::weekly_tasks::create_weekly_task,
       commands::weekly_tasks::get_weekly_tasks,
@@ -85,22 +154,55 @@ pub fn run() {
       commands::week_plan_blocks::delete_week_plan_block,
       commands::week_plan_blocks::clear_suggested_blocks,
       commands::week_plan_blocks::bulk_create_plan_blocks,
+      commands::week_plan_blocks::get_planned_hours_by_deliverable,
+      commands::week_plan_blocks::split_plan_blocks,
+      commands::week_plan_blocks::start_session_from_block,
       // Calendar Aggregation
       commands::calendar::get_calendar_items,
+      commands::calendar::export_calendar_ics,
+      commands::categories::get_categories,
+      commands::categories::create_category,
+      commands::categories::update_category,
+      commands::categories::delete_category,
+      commands::free_slots::find_free_slots,
+      commands::course_workload::recompute_course_targets,
       commands::assignments::create_assignment,
       commands::assignments::get_assignments,
       commands::assignments::update_assignment,
       commands::assignments::delete_assignment,
       commands::assignments::toggle_assignment,
+      commands::assignment_checklist::get_assignment_checklist,
+      commands::assignment_checklist::add_checklist_item,
+      commands::assignment_checklist::toggle_checklist_item,
+      commands::assignment_checklist::delete_checklist_item,
+      commands::assignments::get_estimate_calibration,
+      commands::procrastination::get_procrastination_report,
       commands::sessions::start_session,
       commands::sessions::end_session,
       commands::sessions::get_sessions,
+      commands::sessions::log_session_interruption,
+      commands::session_types::get_session_types,
+      commands::session_types::create_session_type,
+      commands::session_types::delete_session_type,
+      commands::lecture_notes::start_meeting_session,
+      commands::lecture_notes::add_lecture_note,
+      commands::lecture_notes::get_lecture_notes,
+      commands::lecture_notes::update_lecture_note,
+      commands::lecture_notes::delete_lecture_note,
+      commands::techniques::get_techniques,
+      commands::techniques::create_technique,
+      commands::techniques::delete_technique,
+      commands::techniques::get_session_techniques,
+      commands::techniques::set_session_techniques,
+      commands::techniques::get_technique_stats,
       commands::skills::create_skill,
       commands::skills::get_skills,
       commands::skills::update_skill,
       commands::skills::delete_skill,
       commands::practice::log_practice,
       commands::practice::get_practice_logs,
+      commands::practice::get_skill_practice_needs,
+      commands::skill_portfolio::export_skill_portfolio,
       commands::workouts::create_workout,
       commands::workouts::get_workouts,
       commands::workouts::get_workout,
@@ -115,6 +217,8 @@ pub fn run() {
       commands::workout_templates::create_workout_template,
       commands::workout_templates::update_workout_template,
       commands::workout_templates::delete_workout_template,
+      commands::workout_templates::set_workout_template_schedule,
+      commands::workout_planning::suggest_workout_blocks,
       commands::exercises::search_exercises,
       commands::exercises::fetch_and_cache_exercises,
       commands::exercises::create_custom_exercise,
@@ -129,16 +233,129 @@ pub fn run() {
        commands::analytics::update_user_settings,
        commands::analytics::get_detailed_stats,
        commands::analytics::get_workout_heatmap,
+       commands::analytics::get_weekly_attention_report,
+       commands::analytics::get_pomodoro_stats,
+       commands::analytics::get_session_type_stats,
+       commands::gamification::get_gamification_status,
+       commands::challenges::create_challenge,
+       commands::challenges::get_challenges,
+       commands::challenges::delete_challenge,
+       commands::trackers::get_trackers,
+       commands::trackers::create_tracker,
+       commands::trackers::delete_tracker,
+       commands::trackers::add_tracker_entity,
+       commands::trackers::get_tracker_entities,
+       commands::trackers::delete_tracker_entity,
+       commands::trackers::log_tracker_entry,
+       commands::trackers::get_tracker_logs,
+       commands::trackers::delete_tracker_log,
+       commands::meditation::start_meditation_timer,
+       commands::meditation::get_meditation_timer,
+       commands::meditation::cancel_meditation_timer,
+       commands::meditation::finish_meditation_timer,
+       commands::meditation::log_meditation,
+       commands::meditation::get_meditation_stats,
+       commands::reading::get_books,
+       commands::reading::add_book,
+       commands::reading::log_reading_session,
+       commands::reading::get_reading_stats,
+       commands::language_learning::create_language_skill,
+       commands::language_learning::get_language_skills,
+       commands::language_learning::add_flashcard_deck,
+       commands::language_learning::log_flashcard_review,
+       commands::language_learning::get_language_proficiency,
+       commands::spending::log_spending_checkin,
+       commands::spending::delete_spending_checkin,
+       commands::spending::get_spending_summary,
+       commands::cycle::log_cycle_period,
+       commands::cycle::get_cycle_periods,
+       commands::cycle::delete_cycle_period,
+       commands::cycle::get_cycle_prediction,
+       commands::cycle::get_cycle_forecast,
+       commands::social::log_social_event,
+       commands::social::get_social_events,
+       commands::social::get_social_stats,
+       commands::export::export_analytics_csv,
+       commands::telemetry::get_telemetry_report,
+       commands::telemetry::export_telemetry,
+       commands::telemetry::clear_telemetry,
+       commands::deadline_share::export_course_deadlines,
+       commands::deadline_share::import_course_deadlines,
+       commands::http_api::get_http_api_status,
+       commands::http_api::regenerate_http_api_token,
+       commands::calendar_feed::get_calendar_feed_status,
+       commands::calendar_feed::regenerate_calendar_feed_token,
+       commands::calendar_feed::publish_calendar_feed,
+       commands::mcp::get_mcp_audit_log,
+       commands::grades::simulate_grade,
+       commands::efficiency::get_effort_efficiency,
+       commands::meeting_focus::get_meeting_focus_stats,
+       commands::capacity::get_capacity_report,
+       commands::terms::get_terms,
+       commands::terms::archive_term,
+       commands::terms::get_term_snapshots,
+       commands::semester_review::get_semester_review,
+       commands::custom_metrics::create_custom_metric,
+       commands::custom_metrics::get_custom_metrics,
+       commands::custom_metrics::update_custom_metric,
+       commands::custom_metrics::delete_custom_metric,
+       commands::custom_metrics::evaluate_metric,
+       commands::plan_realism::get_plan_realism,
+       commands::planner_mode::get_planner_mode,
+       commands::planner_mode::set_planner_mode,
+       commands::next_task::get_next_best_task,
+       commands::shutdown::run_daily_shutdown,
+       commands::briefing::get_morning_briefing,
+       commands::course_digest::get_course_digests,
+       commands::course_digest::get_due_course_digests,
+       commands::course_digest::ack_course_digests,
+       commands::accountability::preview_accountability_report,
+       commands::accountability::send_accountability_report,
+       commands::accountability::get_due_accountability_draft,
+       commands::accountability::ack_accountability_draft,
+       commands::accountability::get_accountability_deliveries,
+       commands::focus_profiles::get_focus_profiles,
+       commands::focus_profiles::create_focus_profile,
+       commands::focus_profiles::update_focus_profile,
+       commands::focus_profiles::delete_focus_profile,
+       commands::focus_profiles::get_focus_profile_stats,
+       commands::attachments::attach_file,
+       commands::attachments::get_attachments,
+       commands::attachments::delete_attachment,
+       commands::attachments::gc_attachments,
        commands::analytics::get_personal_records,
        commands::analytics::check_and_update_prs,
        commands::analytics::get_achievements,
        commands::analytics::check_achievements,
        commands::debug::get_db_path,
        commands::debug::reset_local_db,
+       commands::debug::simulate_history,
        commands::debug::clear_exercises_cache,
        commands::debug::get_exercise_cache_stats,
+       commands::debug::get_db_pool_status,
+       commands::sample_data::load_sample_data,
+       commands::sample_data::remove_sample_data,
+       commands::sample_data::get_sample_data_status,
+       commands::onboarding::get_onboarding_status,
+       commands::onboarding::complete_onboarding_step,
+       commands::onboarding::apply_onboarding_settings,
+       commands::onboarding::create_onboarding_courses,
+       commands::onboarding::reset_onboarding,
+       commands::settings::get_setting,
+       commands::settings::set_setting,
+       commands::settings::get_settings,
+       commands::settings::get_settings_schema,
+       commands::capabilities::get_api_capabilities,
+       commands::quick_actions::get_quick_actions,
+       commands::quick_actions::run_quick_action,
+       commands::glance::get_glance_data,
+       commands::focus::get_session_distraction_report,
+       commands::energy::get_energy_budgets,
        agent::insights::get_insights,
        agent::insights::record_insight_feedback,
+       agent::insights::dismiss_insight,
+       agent::insights::get_muted_insight_categories,
+       agent::insights::set_insight_category_muted,
        agent::insights::run_pattern_analysis,
        agent::insights::get_user_profile,
        // Intelligence Agent commands
@@ -147,24 +364,39 @@ pub fn run() {
        commands::intelligence::record_recommendation_feedback,
        commands::intelligence::record_action_completed,
        commands::intelligence::get_agent_status,
+       commands::intelligence::get_ml_readiness,
        commands::intelligence::get_rich_context,
        commands::intelligence::get_big_three,
        commands::intelligence::set_big_three,
        commands::intelligence::complete_big_three,
        commands::intelligence::run_agent_maintenance,
+       commands::intelligence::get_maintenance_log,
+       commands::intelligence::get_maintenance_hour,
+       commands::intelligence::set_maintenance_hour,
+       commands::intelligence::get_agent_shadow_mode,
+       commands::intelligence::set_agent_shadow_mode,
+       commands::intelligence::get_shadow_report,
        commands::intelligence::get_feature_names,
        commands::intelligence::search_similar_experiences,
+       commands::intelligence::get_reward_weights,
        commands::intelligence::set_reward_weights,
+       commands::intelligence::get_reward_weight_presets,
+       commands::intelligence::apply_reward_weight_preset,
+       commands::intelligence::get_reward_weight_history,
        commands::intelligence::set_exploration_rate,
        // Google Calendar sync
        commands::google_calendar::set_google_client_id,
        commands::google_calendar::google_oauth_begin,
        commands::google_calendar::google_oauth_complete,
        commands::google_calendar::google_sync_now,
+       commands::google_calendar::google_sync_preview,
        commands::google_calendar::get_google_sync_status,
        commands::google_calendar::disconnect_google,
+       // External change log
+       commands::change_log::get_change_log,
+       commands::change_log::revert_external_change,
 
-    ])
+    ]))
     .run(tauri::generate_context!())
     .expect("error while running tauri application");
 }
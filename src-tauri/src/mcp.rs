@@ -0,0 +1,483 @@
+//! MCP Server
+//!
+//! Lets AI assistants on this machine use Life OS through the Model Context
+//! Protocol (JSON-RPC over the Streamable HTTP transport). The endpoint is
+//! `POST /mcp` on the local HTTP API, so it shares its localhost binding and
+//! bearer token, and only answers while `mcp_enabled` is on.
+//!
+//! Each tool needs a scope the user granted in `mcp_scopes`; tools outside
+//! the granted scopes are hidden from `tools/list` and refused when called.
+//! Every call, allowed or not, is written to `mcp_audit_log`.
+
+use chrono::{Days, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sqlx::{Pool, Sqlite};
+
+use crate::commands::assignments::{insert_assignment, AssignmentInput};
+use crate::commands::calendar::{get_calendar_items_for_pool, CalendarQuery};
+use crate::commands::workouts::{insert_workout, WorkoutInput};
+use crate::deep_link::resolve_course;
+use crate::error::ApiError;
+use crate::services::settings;
+
+pub const PROTOCOL_VERSION: &str = "2025-03-26";
+
+/// Scopes the user can grant, one per tool family
+pub const MCP_SCOPES: &[&str] = &["agenda:read", "assignments:write", "workouts:write"];
+
+const MAX_AGENDA_DAYS: u64 = 31;
+const MAX_WORKOUT_MINUTES: i64 = 600;
+const MAX_TITLE_LENGTH: usize = 200;
+
+// JSON-RPC error codes
+const PARSE_ERROR: i64 = -32700;
+const INVALID_REQUEST: i64 = -32600;
+const METHOD_NOT_FOUND: i64 = -32601;
+const INVALID_PARAMS: i64 = -32602;
+const INTERNAL_ERROR: i64 = -32603;
+
+struct Tool {
+    name: &'static str,
+    scope: &'static str,
+    description: &'static str,
+    input_schema: fn() -> Value,
+}
+
+const TOOLS: &[Tool] = &[
+    Tool {
+        name: "query_agenda",
+        scope: "agenda:read",
+        description: "List classes, events, plan blocks, assignments and exams for a range of days.",
+        input_schema: || {
+            json!({
+                "type": "object",
+                "properties": {
+                    "start_date": { "type": "string", "description": "YYYY-MM-DD, defaults to today" },
+                    "days": { "type": "integer", "minimum": 1, "maximum": MAX_AGENDA_DAYS, "default": 7 }
+                }
+            })
+        },
+    },
+    Tool {
+        name: "create_assignment",
+        scope: "assignments:write",
+        description: "Add an assignment to an active course.",
+        input_schema: || {
+            json!({
+                "type": "object",
+                "properties": {
+                    "course": { "type": "string", "description": "Course code or name, e.g. CS201" },
+                    "title": { "type": "string" },
+                    "due_date": { "type": "string", "description": "YYYY-MM-DD" },
+                    "priority": { "type": "string", "enum": ["low", "medium", "high"], "default": "medium" },
+                    "description": { "type": "string" }
+                },
+                "required": ["course", "title"]
+            })
+        },
+    },
+    Tool {
+        name: "log_workout",
+        scope: "workouts:write",
+        description: "Log a completed workout.",
+        input_schema: || {
+            json!({
+                "type": "object",
+                "properties": {
+                    "name": { "type": "string", "description": "e.g. Push day, 5k run" },
+                    "duration_minutes": { "type": "integer", "minimum": 1, "maximum": MAX_WORKOUT_MINUTES },
+                    "notes": { "type": "string" }
+                },
+                "required": ["duration_minutes"]
+            })
+        },
+    },
+];
+
+#[derive(Debug, Deserialize)]
+struct RpcRequest {
+    jsonrpc: String,
+    id: Option<Value>,
+    method: String,
+    #[serde(default)]
+    params: Value,
+}
+
+#[derive(Debug, Serialize, sqlx::FromRow)]
+pub struct McpAuditEntry {
+    pub id: i64,
+    pub tool: String,
+    pub scope: Option<String>,
+    pub arguments_json: String,
+    pub allowed: bool,
+    pub is_error: bool,
+    pub result_summary: Option<String>,
+    pub created_at: String,
+}
+
+/// Handle one JSON-RPC message; None for notifications (no response body)
+pub async fn handle(pool: &Pool<Sqlite>, message: Value) -> Option<Value> {
+    let request: RpcRequest = match serde_json::from_value(message) {
+        Ok(r) => r,
+        Err(e) => return Some(rpc_error(Value::Null, INVALID_REQUEST, &e.to_string())),
+    };
+    if request.jsonrpc != "2.0" {
+        return Some(rpc_error(request.id.unwrap_or(Value::Null), INVALID_REQUEST, "jsonrpc must be \"2.0\""));
+    }
+    let id = request.id?;
+
+    let result = match request.method.as_str() {
+        "initialize" => Ok(json!({
+            "protocolVersion": PROTOCOL_VERSION,
+            "capabilities": { "tools": { "listChanged": false } },
+            "serverInfo": { "name": "life-os", "version": env!("CARGO_PKG_VERSION") },
+            "instructions": "Life OS is a personal planner for courses, assignments, study sessions and workouts.",
+        })),
+        "ping" => Ok(json!({})),
+        "tools/list" => list_tools(pool).await,
+        "tools/call" => call_tool(pool, &request.params).await,
+        other => Err((METHOD_NOT_FOUND, format!("Unknown method '{}'", other))),
+    };
+
+    Some(match result {
+        Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+        Err((code, message)) => rpc_error(id, code, &message),
+    })
+}
+
+/// Response for a body that isn't valid JSON
+pub fn parse_error(message: &str) -> Value {
+    rpc_error(Value::Null, PARSE_ERROR, message)
+}
+
+fn rpc_error(id: Value, code: i64, message: &str) -> Value {
+    json!({ "jsonrpc": "2.0", "id": id, "error": { "code": code, "message": message } })
+}
+
+async fn granted_scopes(pool: &Pool<Sqlite>) -> Result<Vec<String>, (i64, String)> {
+    settings::get_string_list(pool, "mcp_scopes")
+        .await
+        .map_err(|e| (INTERNAL_ERROR, e.message))
+}
+
+async fn list_tools(pool: &Pool<Sqlite>) -> Result<Value, (i64, String)> {
+    let scopes = granted_scopes(pool).await?;
+    let tools: Vec<Value> = TOOLS
+        .iter()
+        .filter(|t| scopes.iter().any(|s| s == t.scope))
+        .map(|t| {
+            json!({
+                "name": t.name,
+                "description": t.description,
+                "inputSchema": (t.input_schema)(),
+            })
+        })
+        .collect();
+    Ok(json!({ "tools": tools }))
+}
+
+async fn call_tool(pool: &Pool<Sqlite>, params: &Value) -> Result<Value, (i64, String)> {
+    let name = params["name"].as_str().unwrap_or_default();
+    let arguments = match &params["arguments"] {
+        Value::Null => json!({}),
+        args => args.clone(),
+    };
+    let tool = TOOLS
+        .iter()
+        .find(|t| t.name == name)
+        .ok_or_else(|| (INVALID_PARAMS, format!("Unknown tool '{}'", name)))?;
+
+    let allowed = granted_scopes(pool).await?.iter().any(|s| s == tool.scope);
+    let outcome = if allowed {
+        run_tool(pool, tool.name, &arguments).await
+    } else {
+        Err(ApiError::validation(format!(
+            "Permission denied: the '{}' scope has not been granted in Life OS settings",
+            tool.scope
+        )))
+    };
+
+    let summary = match &outcome {
+        Ok(value) => summarize(value),
+        Err(e) => e.message.clone(),
+    };
+    let logged = sqlx::query(
+        "INSERT INTO mcp_audit_log (tool, scope, arguments_json, allowed, is_error, result_summary) VALUES (?, ?, ?, ?, ?, ?)",
+    )
+    .bind(tool.name)
+    .bind(tool.scope)
+    .bind(arguments.to_string())
+    .bind(allowed)
+    .bind(outcome.is_err())
+    .bind(&summary)
+    .execute(pool)
+    .await;
+    // The call has already run, so a missing audit entry must at least be visible
+    if let Err(e) = logged {
+        log::error!("Failed to write MCP audit log: {}", e);
+    }
+
+    Ok(match outcome {
+        Ok(value) => json!({
+            "content": [{ "type": "text", "text": value.to_string() }],
+            "structuredContent": value,
+            "isError": false,
+        }),
+        Err(e) => json!({
+            "content": [{ "type": "text", "text": e.message }],
+            "isError": true,
+        }),
+    })
+}
+
+fn summarize(value: &Value) -> String {
+    match value {
+        Value::Array(items) => format!("{} items", items.len()),
+        Value::Object(map) => match map.get("id") {
+            Some(id) => format!("created id {}", id),
+            None => "ok".to_string(),
+        },
+        _ => "ok".to_string(),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct AgendaArgs {
+    start_date: Option<String>,
+    days: Option<u64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct CreateAssignmentArgs {
+    course: String,
+    title: String,
+    due_date: Option<String>,
+    priority: Option<String>,
+    description: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LogWorkoutArgs {
+    name: Option<String>,
+    duration_minutes: i64,
+    notes: Option<String>,
+}
+
+fn parse_args<T: serde::de::DeserializeOwned>(arguments: &Value) -> Result<T, ApiError> {
+    serde_json::from_value(arguments.clone()).map_err(|e| ApiError::validation(format!("Invalid arguments: {}", e)))
+}
+
+fn parse_date(value: &str, field: &str) -> Result<NaiveDate, ApiError> {
+    NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d")
+        .map_err(|_| ApiError::validation(format!("'{}' must be a YYYY-MM-DD date", field)))
+}
+
+async fn run_tool(pool: &Pool<Sqlite>, name: &str, arguments: &Value) -> Result<Value, ApiError> {
+    match name {
+        "query_agenda" => {
+            let args: AgendaArgs = parse_args(arguments)?;
+            let start = match args.start_date.as_deref() {
+                Some(d) => parse_date(d, "start_date")?,
+                None => Local::now().date_naive(),
+            };
+            let days = args.days.unwrap_or(7);
+            if !(1..=MAX_AGENDA_DAYS).contains(&days) {
+                return Err(ApiError::validation(format!("'days' must be between 1 and {}", MAX_AGENDA_DAYS)));
+            }
+            let end = start + Days::new(days - 1);
+
+            let mut items = get_calendar_items_for_pool(
+                pool,
+                CalendarQuery {
+                    start_date: start.to_string(),
+                    end_date: end.to_string(),
+                    include_assignments: Some(true),
+                    include_exams: Some(true),
+                },
+            )
+            .await?;
+            items.sort_by(|a, b| a.start_at.cmp(&b.start_at));
+            Ok(serde_json::to_value(items).unwrap_or_default())
+        }
+        "create_assignment" => {
+            let args: CreateAssignmentArgs = parse_args(arguments)?;
+            let title = args.title.trim();
+            if title.is_empty() || title.len() > MAX_TITLE_LENGTH {
+                return Err(ApiError::validation(format!(
+                    "'title' must be 1-{} characters",
+                    MAX_TITLE_LENGTH
+                )));
+            }
+            let priority = args.priority.unwrap_or_else(|| "medium".to_string());
+            if !matches!(priority.as_str(), "low" | "medium" | "high") {
+                return Err(ApiError::validation("'priority' must be low, medium or high"));
+            }
+            let due_date = args
+                .due_date
+                .as_deref()
+                .map(|d| parse_date(d, "due_date").map(|d| d.to_string()))
+                .transpose()?;
+
+            let course_id = resolve_course(pool, &args.course).await?;
+            let assignment = insert_assignment(
+                pool,
+                &AssignmentInput {
+                    course_id,
+                    title: title.to_string(),
+                    description: args.description,
+                    due_date,
+                    priority: Some(priority),
+                    score: None,
+                    estimated_minutes: None,
+                    actual_minutes: None,
+                },
+            )
+            .await?;
+            Ok(serde_json::to_value(assignment).unwrap_or_default())
+        }
+        "log_workout" => {
+            let args: LogWorkoutArgs = parse_args(arguments)?;
+            if !(1..=MAX_WORKOUT_MINUTES).contains(&args.duration_minutes) {
+                return Err(ApiError::validation(format!(
+                    "'duration_minutes' must be between 1 and {}",
+                    MAX_WORKOUT_MINUTES
+                )));
+            }
+            let workout = insert_workout(
+                pool,
+                &WorkoutInput {
+                    user_id: None,
+                    name: args.name.map(|n| n.trim().to_string()).filter(|n| !n.is_empty()),
+                    duration_minutes: Some(args.duration_minutes),
+                    notes: args.notes,
+                    notes_md: None,
+                    logged_at: None,
+                },
+            )
+            .await?;
+            Ok(serde_json::to_value(workout).unwrap_or_default())
+        }
+        other => Err(ApiError::not_found(format!("Unknown tool '{}'", other))),
+    }
+}
+
+/// Most recent tool calls, newest first
+pub async fn audit_log(pool: &Pool<Sqlite>, limit: i64) -> Result<Vec<McpAuditEntry>, ApiError> {
+    sqlx::query_as::<_, McpAuditEntry>(
+        "SELECT id, tool, scope, arguments_json, allowed, is_error, result_summary, created_at FROM mcp_audit_log ORDER BY id DESC LIMIT ?",
+    )
+    .bind(limit.clamp(1, 500))
+    .fetch_all(pool)
+    .await
+    .map_err(ApiError::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+    use std::str::FromStr;
+
+    async fn setup_pool_with_migrations() -> Pool<Sqlite> {
+        let options = SqliteConnectOptions::from_str("sqlite::memory:")
+            .unwrap()
+            .foreign_keys(false);
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect_with(options)
+            .await
+            .expect("Failed to connect to in-memory DB");
+
+        crate::db::migrations::run_migrations(&pool)
+            .await
+            .expect("Failed to run migrations");
+
+        pool
+    }
+
+    async fn call(pool: &Pool<Sqlite>, name: &str, arguments: Value) -> Value {
+        let message = json!({
+            "jsonrpc": "2.0",
+            "id": 1,
+            "method": "tools/call",
+            "params": { "name": name, "arguments": arguments },
+        });
+        handle(pool, message).await.unwrap()["result"].clone()
+    }
+
+    #[tokio::test]
+    async fn lists_only_granted_tools() {
+        let pool = setup_pool_with_migrations().await;
+
+        let init = handle(&pool, json!({ "jsonrpc": "2.0", "id": 0, "method": "initialize", "params": {} }))
+            .await
+            .unwrap();
+        assert_eq!(init["result"]["protocolVersion"], PROTOCOL_VERSION);
+        assert!(handle(&pool, json!({ "jsonrpc": "2.0", "method": "notifications/initialized" }))
+            .await
+            .is_none());
+
+        let list = |pool| async move {
+            let response = handle(pool, json!({ "jsonrpc": "2.0", "id": 1, "method": "tools/list" }))
+                .await
+                .unwrap();
+            response["result"]["tools"]
+                .as_array()
+                .unwrap()
+                .iter()
+                .map(|t| t["name"].as_str().unwrap().to_string())
+                .collect::<Vec<_>>()
+        };
+        assert_eq!(list(&pool).await, vec!["query_agenda"]);
+
+        settings::set(&pool, "mcp_scopes", json!(["agenda:read", "workouts:write"]))
+            .await
+            .unwrap();
+        assert_eq!(list(&pool).await, vec!["query_agenda", "log_workout"]);
+
+        let unknown = handle(&pool, json!({ "jsonrpc": "2.0", "id": 2, "method": "resources/list" }))
+            .await
+            .unwrap();
+        assert_eq!(unknown["error"]["code"], METHOD_NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn tool_calls_respect_scopes_and_are_audited() {
+        let pool = setup_pool_with_migrations().await;
+        sqlx::query("INSERT INTO courses (name, code) VALUES ('Data Structures', 'CS 201')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let assignment = json!({ "course": "cs201", "title": "Lab 6", "due_date": "2026-11-02" });
+
+        let denied = call(&pool, "create_assignment", assignment.clone()).await;
+        assert_eq!(denied["isError"], true);
+        let count: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM assignments")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(count, 0);
+
+        settings::set(&pool, "mcp_scopes", json!(["assignments:write", "workouts:write"]))
+            .await
+            .unwrap();
+        let created = call(&pool, "create_assignment", assignment).await;
+        assert_eq!(created["isError"], false);
+        assert_eq!(created["structuredContent"]["title"], "Lab 6");
+        assert_eq!(created["structuredContent"]["priority"], "medium");
+
+        let invalid = call(&pool, "log_workout", json!({ "duration_minutes": 0 })).await;
+        assert_eq!(invalid["isError"], true);
+        let logged = call(&pool, "log_workout", json!({ "name": "Run", "duration_minutes": 30 })).await;
+        assert_eq!(logged["structuredContent"]["duration_minutes"], 30);
+
+        let log = audit_log(&pool, 10).await.unwrap();
+        assert_eq!(log.len(), 4);
+        assert_eq!(log[3].tool, "create_assignment");
+        assert!(!log[3].allowed);
+        assert!(log[2].allowed && !log[2].is_error);
+        assert!(log[1].is_error);
+        assert_eq!(log[0].result_summary.as_deref(), Some(format!("created id {}", logged["structuredContent"]["id"]).as_str()));
+    }
+}
@@ -11,6 +11,9 @@ use rand_distr::Beta;
 
 use super::models::{BanditArm, Context};
 
+/// Arms that support the pre-exam protocol (sleep consistency, short study blocks, recovery)
+const PRE_EXAM_ARMS: &[&str] = &["pre_exam_protocol", "suggest_pomodoro", "recommend_break", "remind_checkin"];
+
 /// Contextual bandit using Thompson Sampling
 pub struct ContextualBandit;
 
@@ -132,9 +135,43 @@ impl ContextualBandit {
             modifier *= 1.4;
         }
 
+        // Pre-exam protocol: favor rest and short focused blocks, dial back training
+        if ctx.in_pre_exam_window() {
+            if PRE_EXAM_ARMS.contains(&arm.arm_name.as_str()) {
+                modifier *= 1.3;
+            } else if arm.arm_name == "suggest_workout" {
+                modifier *= 0.6;
+            }
+        } else if arm.arm_name == "pre_exam_protocol" {
+            modifier *= 0.1;
+        }
+
         base_score * modifier
     }
 
+    /// Shape a reward using the context the insight was shown in
+    ///
+    /// During the pre-exam window, acting on protocol-aligned nudges is worth more
+    /// and acting on training nudges is worth less, so the arms drift toward the
+    /// protocol even when explicit feedback is sparse.
+    fn shape_reward(reward: f64, arm_name: &str, ctx: Option<&Context>) -> f64 {
+        let Some(ctx) = ctx else {
+            return reward;
+        };
+        if !ctx.in_pre_exam_window() {
+            return reward;
+        }
+
+        let shaped = if PRE_EXAM_ARMS.contains(&arm_name) {
+            reward * 1.2
+        } else if arm_name == "suggest_workout" {
+            reward * 0.8
+        } else {
+            reward
+        };
+        shaped.clamp(0.0, 1.0)
+    }
+
     /// Update arm statistics after receiving feedback
     pub async fn update_arm(
         pool: &Pool<Sqlite>,
@@ -218,10 +255,10 @@ impl ContextualBandit {
         .await
         .map_err(|e| e.to_string())?;
 
-        // Get the arm name for this insight and update it
-        let arm_name: Option<String> = sqlx::query_scalar(
+        // Get the arm name and shown-time context for this insight and update it
+        let row: Option<(String, Option<String>)> = sqlx::query_as(
             r#"
-            SELECT ba.arm_name FROM agent_insights ai
+            SELECT ba.arm_name, ai.context_json FROM agent_insights ai
             JOIN agent_bandit_arms ba ON ai.arm_index = ba.id
             WHERE ai.id = ?
             "#
@@ -231,10 +268,70 @@ impl ContextualBandit {
         .await
         .map_err(|e| e.to_string())?;
 
-        if let Some(name) = arm_name {
+        if let Some((name, context_json)) = row {
+            let context: Option<Context> = context_json
+                .as_deref()
+                .and_then(|json| serde_json::from_str(json).ok());
+            let reward = Self::shape_reward(reward, &name, context.as_ref());
             Self::update_arm(pool, &name, reward).await?;
         }
 
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn arm(name: &str) -> BanditArm {
+        BanditArm {
+            id: 1,
+            arm_name: name.to_string(),
+            category: None,
+            alpha: 1.0,
+            beta: 1.0,
+            total_pulls: 0,
+            total_reward: 0.0,
+            last_pulled: None,
+            is_enabled: true,
+        }
+    }
+
+    #[test]
+    fn pre_exam_window_shifts_arm_scores() {
+        let ctx = Context {
+            days_to_next_exam: Some(2),
+            ..Context::default()
+        };
+
+        let protocol = ContextualBandit::apply_context_modifier(0.5, &arm("pre_exam_protocol"), &ctx);
+        let workout = ContextualBandit::apply_context_modifier(0.5, &arm("suggest_workout"), &ctx);
+        assert!(protocol > 0.5);
+        assert!(workout < 0.5 * 1.3);
+    }
+
+    #[test]
+    fn protocol_arm_is_suppressed_without_upcoming_exam() {
+        let ctx = Context::default();
+        let score = ContextualBandit::apply_context_modifier(0.5, &arm("pre_exam_protocol"), &ctx);
+        assert!(score < 0.1);
+    }
+
+    #[test]
+    fn shape_reward_only_applies_inside_window() {
+        let far = Context {
+            days_to_next_exam: Some(20),
+            ..Context::default()
+        };
+        let near = Context {
+            days_to_next_exam: Some(1),
+            ..Context::default()
+        };
+
+        assert_eq!(ContextualBandit::shape_reward(0.5, "recommend_break", Some(&far)), 0.5);
+        assert!(ContextualBandit::shape_reward(0.5, "recommend_break", Some(&near)) > 0.5);
+        assert!(ContextualBandit::shape_reward(0.5, "suggest_workout", Some(&near)) < 0.5);
+        assert_eq!(ContextualBandit::shape_reward(1.0, "pre_exam_protocol", Some(&near)), 1.0);
+    }
+}
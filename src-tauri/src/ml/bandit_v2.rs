@@ -15,7 +15,10 @@ use rand_distr::Normal;
 use serde::{Deserialize, Serialize};
 use sqlx::{Pool, Sqlite};
 
-use super::rich_features::{RichContext, FEATURE_DIM};
+use super::rich_features::{
+    feature_dim_for_version, version_for_feature_dim, RichContext, FEATURE_DIM, FEATURE_VERSION,
+};
+use crate::services::clock;
 
 /// Exploration parameter for UCB
 const DEFAULT_BETA: f32 = 2.0;
@@ -49,18 +52,30 @@ pub struct ActionSelection {
 }
 
 /// Bayesian Linear Bandit parameters for one action
+///
+/// The covariance (inverse precision) is kept alongside the precision and
+/// updated with Sherman–Morrison on each observation, so predictions never
+/// invert the matrix. Only the precision is stored, as its packed lower
+/// triangle; the covariance is rebuilt once when loading.
 #[derive(Debug, Clone)]
 pub struct LinearBanditParams {
     /// Mean of weight posterior (FEATURE_DIM)
     pub mu: DVector<f64>,
     /// Precision matrix (inverse covariance) of posterior (FEATURE_DIM x FEATURE_DIM)
     pub precision: DMatrix<f64>,
+    /// Covariance of posterior, kept equal to `precision^(-1)`
+    pub covariance: DMatrix<f64>,
     /// Prior precision
     pub prior_precision: f64,
     /// Noise precision
     pub noise_precision: f64,
 }
 
+/// Length of the packed lower triangle of a `dim x dim` matrix
+fn packed_len(dim: usize) -> usize {
+    dim * (dim + 1) / 2
+}
+
 impl LinearBanditParams {
     /// Create new parameters with prior
     pub fn new() -> Self {
@@ -68,17 +83,44 @@ impl LinearBanditParams {
         Self {
             mu: DVector::zeros(dim),
             precision: DMatrix::identity(dim, dim) * PRIOR_PRECISION,
+            covariance: DMatrix::identity(dim, dim) / PRIOR_PRECISION,
             prior_precision: PRIOR_PRECISION,
             noise_precision: NOISE_PRECISION,
         }
     }
 
+    /// Build parameters from a mean and precision, deriving the covariance
+    ///
+    /// Returns `None` if the precision is not positive definite.
+    fn from_posterior(mu: DVector<f64>, precision: DMatrix<f64>, prior_precision: f64) -> Option<Self> {
+        let covariance = precision.clone().cholesky()?.inverse();
+        Some(Self {
+            mu,
+            precision,
+            covariance,
+            prior_precision,
+            noise_precision: NOISE_PRECISION,
+        })
+    }
+
     /// Create from stored bytes
+    ///
+    /// The stored layout version is inferred from the vector length. Parameters
+    /// from another layout are resized into the current one: learned weights for
+    /// shared features are kept, appended features start at the prior and
+    /// features unknown to this build are dropped.
+    ///
+    /// The precision is read as a packed lower triangle, or as the full matrix
+    /// written by older builds.
     pub fn from_bytes(theta_bytes: &[u8], precision_bytes: &[u8]) -> Option<Self> {
-        let dim = FEATURE_DIM;
-
         // Parse theta (mean vector)
-        if theta_bytes.len() != dim * 8 {
+        let dim = theta_bytes.len() / 8;
+        if theta_bytes.len() % 8 != 0 || dim == 0 {
+            return None;
+        }
+        // Shorter vectors must match a known layout; longer ones come from a
+        // newer build and are truncated
+        if dim < FEATURE_DIM && version_for_feature_dim(dim).is_none() {
             return None;
         }
         let theta_vec: Vec<f64> = theta_bytes
@@ -87,25 +129,61 @@ impl LinearBanditParams {
             .collect();
         let mu = DVector::from_vec(theta_vec);
 
-        // Parse precision matrix (stored as full matrix for now)
-        if precision_bytes.len() != dim * dim * 8 {
+        // Parse precision matrix
+        if precision_bytes.len() % 8 != 0 {
             return None;
         }
         let precision_vec: Vec<f64> = precision_bytes
             .chunks_exact(8)
             .map(|chunk| f64::from_le_bytes(chunk.try_into().unwrap()))
             .collect();
-        let precision = DMatrix::from_vec(dim, dim, precision_vec);
+        let precision = if precision_vec.len() == packed_len(dim) {
+            let mut values = precision_vec.into_iter();
+            let mut precision = DMatrix::zeros(dim, dim);
+            for j in 0..dim {
+                for i in j..dim {
+                    let value = values.next()?;
+                    precision[(i, j)] = value;
+                    precision[(j, i)] = value;
+                }
+            }
+            precision
+        } else if precision_vec.len() == dim * dim {
+            DMatrix::from_vec(dim, dim, precision_vec)
+        } else {
+            return None;
+        };
 
-        Some(Self {
-            mu,
-            precision,
-            prior_precision: PRIOR_PRECISION,
-            noise_precision: NOISE_PRECISION,
-        })
+        if dim != FEATURE_DIM {
+            return Self::resized(&mu, &precision, PRIOR_PRECISION, FEATURE_DIM);
+        }
+        Self::from_posterior(mu, precision, PRIOR_PRECISION)
+    }
+
+    /// Resize a posterior to another feature space
+    ///
+    /// The shared leading block is copied; extra dimensions start at the prior.
+    fn resized(
+        old_mu: &DVector<f64>,
+        old_precision: &DMatrix<f64>,
+        prior_precision: f64,
+        dim: usize,
+    ) -> Option<Self> {
+        let shared = old_mu.len().min(dim);
+        let mut mu = DVector::zeros(dim);
+        mu.rows_mut(0, shared).copy_from(&old_mu.rows(0, shared));
+
+        let mut precision = DMatrix::identity(dim, dim) * prior_precision;
+        precision
+            .view_mut((0, 0), (shared, shared))
+            .copy_from(&old_precision.view((0, 0), (shared, shared)));
+
+        Self::from_posterior(mu, precision, prior_precision)
     }
 
     /// Convert to bytes for storage
+    ///
+    /// The precision is written as its packed lower triangle, column by column.
     pub fn to_bytes(&self) -> (Vec<u8>, Vec<u8>) {
         let theta_bytes: Vec<u8> = self
             .mu
@@ -113,11 +191,13 @@ impl LinearBanditParams {
             .flat_map(|f| f.to_le_bytes())
             .collect();
 
-        let precision_bytes: Vec<u8> = self
-            .precision
-            .iter()
-            .flat_map(|f| f.to_le_bytes())
-            .collect();
+        let dim = self.precision.nrows();
+        let mut precision_bytes = Vec::with_capacity(packed_len(dim) * 8);
+        for j in 0..dim {
+            for i in j..dim {
+                precision_bytes.extend_from_slice(&self.precision[(i, j)].to_le_bytes());
+            }
+        }
 
         (theta_bytes, precision_bytes)
     }
@@ -132,73 +212,56 @@ impl LinearBanditParams {
     pub fn uncertainty(&self, features: &Array1<f32>) -> f64 {
         let x = DVector::from_iterator(FEATURE_DIM, features.iter().map(|&f| f as f64));
 
-        // Compute covariance = precision^(-1)
-        // For efficiency, solve precision * cov * x = x
-        // Uncertainty = sqrt(x^T * cov * x) = sqrt(x^T * precision^(-1) * x)
-
-        match self.precision.clone().try_inverse() {
-            Some(cov) => {
-                let var = x.transpose() * &cov * &x;
-                (var[(0, 0)] / self.noise_precision).sqrt()
-            }
-            None => 1.0, // High uncertainty if precision is singular
-        }
+        // Uncertainty = sqrt(x^T * cov * x)
+        let var = x.dot(&(&self.covariance * &x)).max(0.0);
+        (var / self.noise_precision).sqrt()
     }
 
     /// Sample from posterior using Thompson Sampling
     pub fn thompson_sample(&self, features: &Array1<f32>) -> f64 {
-        let mut rng = thread_rng();
-
-        // Sample weights from posterior N(mu, precision^(-1))
-        match self.precision.clone().try_inverse() {
-            Some(cov) => {
-                // Cholesky decomposition for sampling
-                match cov.clone().cholesky() {
-                    Some(chol) => {
-                        let l = chol.l();
-                        let z: DVector<f64> = DVector::from_fn(FEATURE_DIM, |_, _| {
-                            Normal::new(0.0, 1.0).unwrap().sample(&mut rng)
-                        });
-                        let sampled_weights = &self.mu + l * z;
-                        let x = DVector::from_iterator(
-                            FEATURE_DIM,
-                            features.iter().map(|&f| f as f64),
-                        );
-                        sampled_weights.dot(&x)
-                    }
-                    None => self.predict(features), // Fallback to mean
-                }
+        self.thompson_sample_with(features, &mut clock::rng())
+    }
+
+    /// Thompson sample drawing from `rng`
+    pub fn thompson_sample_with<R: Rng + ?Sized>(&self, features: &Array1<f32>, rng: &mut R) -> f64 {
+        // Sample weights from posterior N(mu, cov) via the Cholesky factor of cov
+        match self.covariance.clone().cholesky() {
+            Some(chol) => {
+                let l = chol.l();
+                let z: DVector<f64> = DVector::from_fn(FEATURE_DIM, |_, _| {
+                    Normal::new(0.0, 1.0).unwrap().sample(&mut *rng)
+                });
+                let sampled_weights = &self.mu + l * z;
+                let x = DVector::from_iterator(FEATURE_DIM, features.iter().map(|&f| f as f64));
+                sampled_weights.dot(&x)
             }
-            None => self.predict(features),
+            None => self.predict(features), // Fallback to mean
         }
     }
 
     /// Update posterior with new observation
     /// Uses Bayesian linear regression update:
     /// precision_new = precision_old + noise_precision * x * x^T
-    /// mu_new = precision_new^(-1) * (precision_old * mu_old + noise_precision * y * x)
+    /// mu_new = mu_old + noise_precision * cov_new * x * (y - x^T * mu_old)
+    ///
+    /// The covariance follows by Sherman–Morrison:
+    /// cov_new = cov_old - s * (cov_old x)(cov_old x)^T / (1 + s * x^T cov_old x)
     pub fn update(&mut self, features: &Array1<f32>, reward: f64) {
         let x = DVector::from_iterator(FEATURE_DIM, features.iter().map(|&f| f as f64));
+        let s = self.noise_precision;
 
         // Update precision
         let outer = &x * x.transpose();
-        self.precision += outer * self.noise_precision;
-
-        // Update mean
-        match self.precision.clone().try_inverse() {
-            Some(cov) => {
-                let old_term = &self.precision * &self.mu;
-                let new_term = &x * (reward * self.noise_precision);
-                self.mu = cov * (old_term + new_term);
-            }
-            None => {
-                // If inversion fails, just do gradient step
-                let pred = self.predict(features);
-                let error = reward - pred;
-                let step = 0.01;
-                self.mu += &x * (step * error);
-            }
-        }
+        self.precision += outer * s;
+
+        // Update covariance
+        let cov_x = &self.covariance * &x;
+        let denom = 1.0 + s * x.dot(&cov_x);
+        self.covariance -= (&cov_x * cov_x.transpose()) * (s / denom);
+
+        // Update mean; cov_new * x = cov_x / denom
+        let error = reward - self.mu.dot(&x);
+        self.mu += cov_x * (s * error / denom);
     }
 
     /// Get feature contributions for explainability
@@ -251,6 +314,63 @@ fn parse_bandit_row(
     (action, params)
 }
 
+/// UCB penalty per already-selected action of the same category
+const DIVERSITY_PENALTY: f32 = 0.05;
+
+/// Minimum number of categories in a multi-action recommendation set
+const MIN_CATEGORIES: usize = 2;
+
+/// Pick `n` actions from candidates sorted by UCB, spreading across categories
+///
+/// Selection is greedy on `ucb_score - DIVERSITY_PENALTY * same_category_count`.
+/// If the result still covers fewer than `MIN_CATEGORIES`, the weakest pick is
+/// swapped for the best off-category candidate whose confidence interval
+/// overlaps it, i.e. when the bandit can't really tell them apart.
+fn diversify_selection(mut candidates: Vec<ActionSelection>, n: usize) -> Vec<ActionSelection> {
+    let mut selected: Vec<ActionSelection> = Vec::with_capacity(n);
+
+    while selected.len() < n && !candidates.is_empty() {
+        let adjusted = |a: &ActionSelection| {
+            let same = selected
+                .iter()
+                .filter(|s| s.action.category == a.action.category)
+                .count();
+            a.ucb_score - DIVERSITY_PENALTY * same as f32
+        };
+        let best = candidates
+            .iter()
+            .enumerate()
+            .max_by(|(_, a), (_, b)| {
+                adjusted(a)
+                    .partial_cmp(&adjusted(b))
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            })
+            .map(|(i, _)| i)
+            .unwrap_or(0);
+        selected.push(candidates.remove(best));
+    }
+
+    let mut categories: Vec<&str> = selected.iter().map(|s| s.action.category.as_str()).collect();
+    categories.sort_unstable();
+    categories.dedup();
+
+    if selected.len() >= MIN_CATEGORIES && categories.len() < MIN_CATEGORIES {
+        let weakest = selected.last().expect("selection is non-empty");
+        let weakest_lower = weakest.expected_reward - weakest.uncertainty;
+        let alternative = candidates.iter().position(|c| {
+            !categories.contains(&c.action.category.as_str())
+                && c.expected_reward + c.uncertainty >= weakest_lower
+        });
+
+        if let Some(i) = alternative {
+            let last = selected.len() - 1;
+            selected[last] = candidates.remove(i);
+        }
+    }
+
+    selected
+}
+
 /// Hybrid Contextual Bandit
 pub struct HybridBandit;
 
@@ -353,12 +473,13 @@ impl HybridBandit {
         sqlx::query(
             r#"
             UPDATE agent_linear_bandit 
-            SET theta = ?, precision_matrix = ?, last_pulled = datetime('now')
+            SET theta = ?, precision_matrix = ?, feature_version = ?, last_pulled = datetime('now')
             WHERE action_name = ?
             "#,
         )
         .bind(&theta_bytes)
         .bind(&prec_bytes)
+        .bind(FEATURE_VERSION as i64)
         .bind(action_name)
         .execute(pool)
         .await
@@ -367,6 +488,107 @@ impl HybridBandit {
         Ok(())
     }
 
+    /// Rewrite parameters stored under an older feature layout
+    ///
+    /// Returns the number of actions upgraded. Rows that cannot be parsed are
+    /// left untouched so no learned state is discarded.
+    pub async fn upgrade_feature_space(pool: &Pool<Sqlite>) -> Result<usize, String> {
+        let rows: Vec<(String, Option<i64>, Vec<u8>, Vec<u8>)> = sqlx::query_as(
+            r#"
+            SELECT action_name, feature_version, theta, precision_matrix
+            FROM agent_linear_bandit
+            WHERE theta IS NOT NULL AND precision_matrix IS NOT NULL
+              AND (feature_version IS NULL OR feature_version != ?)
+            "#,
+        )
+        .bind(FEATURE_VERSION as i64)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+        let mut upgraded = 0;
+        for (action_name, version, theta, prec) in rows {
+            let stored_dim = theta.len() / 8;
+            if let Some(expected) = version.and_then(|v| feature_dim_for_version(v as u8)) {
+                if expected != stored_dim {
+                    log::warn!(
+                        "Bandit params for {} have {} features but version {:?} expects {}",
+                        action_name, stored_dim, version, expected
+                    );
+                    continue;
+                }
+            }
+
+            let Some(params) = LinearBanditParams::from_bytes(&theta, &prec) else {
+                log::warn!("Skipping unreadable bandit params for {}", action_name);
+                continue;
+            };
+            let (theta_bytes, prec_bytes) = params.to_bytes();
+
+            sqlx::query(
+                r#"
+                UPDATE agent_linear_bandit
+                SET theta = ?, precision_matrix = ?, feature_version = ?
+                WHERE action_name = ?
+                "#,
+            )
+            .bind(&theta_bytes)
+            .bind(&prec_bytes)
+            .bind(FEATURE_VERSION as i64)
+            .bind(&action_name)
+            .execute(pool)
+            .await
+            .map_err(|e| e.to_string())?;
+            upgraded += 1;
+        }
+
+        if upgraded > 0 {
+            log::info!(
+                "Upgraded {} bandit actions to feature version {}",
+                upgraded, FEATURE_VERSION
+            );
+        }
+
+        Ok(upgraded)
+    }
+
+    /// Suppress an action from selection for the given number of hours
+    ///
+    /// Snoozing is neutral: the bandit parameters are left untouched.
+    pub async fn snooze_action(
+        pool: &Pool<Sqlite>,
+        action_name: &str,
+        hours: f32,
+        recommendation_id: Option<i64>,
+    ) -> Result<String, String> {
+        let minutes = (hours * 60.0).round() as i64;
+        let snoozed_until: String = sqlx::query_scalar(
+            r#"
+            INSERT INTO agent_action_snoozes (action_name, recommendation_id, snoozed_until)
+            VALUES (?, ?, datetime('now', '+' || ? || ' minutes'))
+            RETURNING snoozed_until
+            "#,
+        )
+        .bind(action_name)
+        .bind(recommendation_id)
+        .bind(minutes)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+        Ok(snoozed_until)
+    }
+
+    /// Names of actions with an active snooze
+    pub async fn snoozed_actions(pool: &Pool<Sqlite>) -> Result<Vec<String>, String> {
+        sqlx::query_scalar(
+            "SELECT DISTINCT action_name FROM agent_action_snoozes WHERE snoozed_until > datetime('now')",
+        )
+        .fetch_all(pool)
+        .await
+        .map_err(|e| e.to_string())
+    }
+
     /// Select the best action using UCB
     pub async fn select_action(
         pool: &Pool<Sqlite>,
@@ -383,6 +605,17 @@ impl HybridBandit {
         context: &RichContext,
         n: usize,
         beta: Option<f32>,
+    ) -> Result<Vec<ActionSelection>, String> {
+        Self::select_top_actions_excluding(pool, context, n, beta, &[]).await
+    }
+
+    /// Select top N actions using UCB, skipping the named actions
+    pub async fn select_top_actions_excluding(
+        pool: &Pool<Sqlite>,
+        context: &RichContext,
+        n: usize,
+        beta: Option<f32>,
+        exclude: &[String],
     ) -> Result<Vec<ActionSelection>, String> {
         let beta = beta.unwrap_or(DEFAULT_BETA);
         let features = context.to_feature_vector();
@@ -406,6 +639,14 @@ impl HybridBandit {
             SELECT id, action_name, category, description, total_pulls, total_reward, is_enabled, theta, precision_matrix
             FROM agent_linear_bandit
             WHERE is_enabled = 1
+              AND action_name NOT IN (
+                  SELECT action_name FROM agent_action_snoozes WHERE snoozed_until > datetime('now')
+              )
+              AND COALESCE(category, '') NOT IN (
+                  SELECT value FROM json_each(
+                      COALESCE((SELECT value_json FROM settings WHERE key = 'muted_categories'), '[]')
+                  )
+              )
             "#,
         )
         .fetch_all(pool)
@@ -431,6 +672,9 @@ impl HybridBandit {
             precision,
         ) in rows
         {
+            if exclude.contains(&name) {
+                continue;
+            }
             let (action, params) = parse_bandit_row(
                 id,
                 name,
@@ -471,7 +715,7 @@ impl HybridBandit {
                 .unwrap_or(std::cmp::Ordering::Equal)
         });
 
-        Ok(scored_actions.into_iter().take(n).collect())
+        Ok(diversify_selection(scored_actions, n))
     }
 
     /// Select action using Thompson Sampling (alternative to UCB)
@@ -500,6 +744,14 @@ impl HybridBandit {
             SELECT id, action_name, category, description, total_pulls, total_reward, is_enabled, theta, precision_matrix
             FROM agent_linear_bandit
             WHERE is_enabled = 1
+              AND action_name NOT IN (
+                  SELECT action_name FROM agent_action_snoozes WHERE snoozed_until > datetime('now')
+              )
+              AND COALESCE(category, '') NOT IN (
+                  SELECT value FROM json_each(
+                      COALESCE((SELECT value_json FROM settings WHERE key = 'muted_categories'), '[]')
+                  )
+              )
             "#,
         )
         .fetch_all(pool)
@@ -511,6 +763,7 @@ impl HybridBandit {
         }
 
         let feature_names = RichContext::feature_names();
+        let mut rng = clock::rng();
         let mut best: Option<ActionSelection> = None;
         let mut best_sample = f64::NEG_INFINITY;
 
@@ -538,7 +791,7 @@ impl HybridBandit {
                 precision,
             );
 
-            let sample = params.thompson_sample(&features);
+            let sample = params.thompson_sample_with(&features, &mut rng);
             let expected_reward = params.predict(&features) as f32;
             let uncertainty = params.uncertainty(&features) as f32;
 
@@ -572,6 +825,11 @@ impl HybridBandit {
         context: &RichContext,
         reward: f64,
     ) -> Result<(), String> {
+        // Reduced-data mode: the bandit stops learning
+        if super::readiness::is_disabled() {
+            return Ok(());
+        }
+
         let features = context.to_feature_vector();
 
         // Load current params
@@ -711,6 +969,177 @@ mod tests {
         let orig_pred = params.predict(&features);
         let restored_pred = restored.predict(&features);
         assert!((orig_pred - restored_pred).abs() < 1e-6);
+
+        // Only the lower triangle of the precision is stored
+        assert_eq!(prec_bytes.len(), FEATURE_DIM * (FEATURE_DIM + 1) / 2 * 8);
+        assert!((params.uncertainty(&features) - restored.uncertainty(&features)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_covariance_tracks_precision_inverse() {
+        let mut params = LinearBanditParams::new();
+        for i in 0..20 {
+            let features = Array1::from_iter((0..FEATURE_DIM).map(|j| ((i * 7 + j) % 11) as f32 / 10.0));
+            params.update(&features, (i % 3) as f64 / 2.0);
+        }
+
+        let inverse = params.precision.clone().try_inverse().unwrap();
+        let drift = (&params.covariance - &inverse).abs().max();
+        assert!(drift < 1e-9, "covariance drifted by {}", drift);
+
+        // Loading rebuilds the covariance from the packed precision
+        let restored = {
+            let (theta, prec) = params.to_bytes();
+            LinearBanditParams::from_bytes(&theta, &prec).unwrap()
+        };
+        assert!((&restored.covariance - &inverse).abs().max() < 1e-9);
+    }
+
+    #[test]
+    fn test_thompson_samples_repeat_under_a_seed() {
+        let mut params = LinearBanditParams::new();
+        let features = Array1::from(vec![0.5f32; FEATURE_DIM]);
+        params.update(&features, 0.6);
+
+        let draw = || {
+            let _seed = clock::seed(7);
+            (params.thompson_sample(&features), params.thompson_sample(&features))
+        };
+        let (first, second) = draw();
+        assert_eq!(draw(), (first, second));
+        assert_ne!(first, second);
+
+        let mut rng = StdRng::seed_from_u64(7);
+        let explicit = params.thompson_sample_with(&features, &mut rng);
+        let mut rng = StdRng::seed_from_u64(7);
+        assert_eq!(params.thompson_sample_with(&features, &mut rng), explicit);
+    }
+
+    fn selection(name: &str, category: &str, expected: f32, uncertainty: f32) -> ActionSelection {
+        ActionSelection {
+            action: BanditAction {
+                id: 0,
+                name: name.into(),
+                category: category.into(),
+                description: String::new(),
+                total_pulls: 0,
+                total_reward: 0.0,
+                is_enabled: true,
+            },
+            expected_reward: expected,
+            uncertainty,
+            ucb_score: expected + uncertainty,
+            feature_contributions: vec![],
+        }
+    }
+
+    #[test]
+    fn test_diversify_spans_categories_when_close() {
+        let candidates = vec![
+            selection("a", "productivity", 0.9, 0.1),
+            selection("b", "productivity", 0.88, 0.1),
+            selection("c", "productivity", 0.86, 0.1),
+            selection("d", "wellness", 0.7, 0.1),
+        ];
+
+        let picked = diversify_selection(candidates, 3);
+        assert_eq!(picked.len(), 3);
+        assert_eq!(picked[0].action.name, "a");
+        assert!(picked.iter().any(|p| p.action.category == "wellness"));
+    }
+
+    #[test]
+    fn test_diversify_keeps_clear_winners() {
+        let candidates = vec![
+            selection("a", "productivity", 0.9, 0.01),
+            selection("b", "productivity", 0.88, 0.01),
+            selection("d", "wellness", 0.1, 0.01),
+        ];
+
+        let picked = diversify_selection(candidates, 2);
+        assert!(picked.iter().all(|p| p.action.category == "productivity"));
+    }
+
+    #[test]
+    fn test_legacy_params_are_expanded() {
+        let legacy_dim = feature_dim_for_version(1).unwrap();
+        let legacy_mu = vec![0.25f64; legacy_dim];
+        let mut legacy_precision = DMatrix::<f64>::identity(legacy_dim, legacy_dim);
+        legacy_precision[(0, 0)] = 3.0;
+
+        let theta_bytes: Vec<u8> = legacy_mu.iter().flat_map(|f| f.to_le_bytes()).collect();
+        let prec_bytes: Vec<u8> = legacy_precision.iter().flat_map(|f| f.to_le_bytes()).collect();
+
+        let restored = LinearBanditParams::from_bytes(&theta_bytes, &prec_bytes).unwrap();
+        assert_eq!(restored.mu.len(), FEATURE_DIM);
+        assert_eq!(restored.mu[0], 0.25);
+        assert_eq!(restored.mu[FEATURE_DIM - 1], 0.0);
+        assert_eq!(restored.precision[(0, 0)], 3.0);
+        assert_eq!(restored.precision[(FEATURE_DIM - 1, FEATURE_DIM - 1)], PRIOR_PRECISION);
+        assert_eq!(restored.precision[(0, FEATURE_DIM - 1)], 0.0);
+    }
+
+    #[test]
+    fn test_newer_params_are_truncated() {
+        let dim = FEATURE_DIM + 3;
+        let mu = vec![0.5f64; dim];
+        let precision = DMatrix::<f64>::identity(dim, dim) * 2.0;
+
+        let theta_bytes: Vec<u8> = mu.iter().flat_map(|f| f.to_le_bytes()).collect();
+        let prec_bytes: Vec<u8> = precision.iter().flat_map(|f| f.to_le_bytes()).collect();
+
+        let restored = LinearBanditParams::from_bytes(&theta_bytes, &prec_bytes).unwrap();
+        assert_eq!(restored.mu.len(), FEATURE_DIM);
+        assert_eq!(restored.precision.nrows(), FEATURE_DIM);
+        assert_eq!(restored.precision[(FEATURE_DIM - 1, FEATURE_DIM - 1)], 2.0);
+
+        // Shorter vectors must match a known layout
+        assert!(LinearBanditParams::from_bytes(&theta_bytes[..8 * 7], &prec_bytes[..8 * 49]).is_none());
+    }
+
+    #[test]
+    fn test_context_bytes_are_versioned() {
+        let mut ctx = RichContext::default();
+        ctx.deadline_pressure = 0.7;
+
+        let bytes = ctx.to_bytes();
+        assert_eq!(bytes[0], FEATURE_VERSION);
+        assert_eq!(bytes.len(), 1 + FEATURE_DIM * 4);
+
+        let restored = RichContext::from_bytes(&bytes).unwrap();
+        assert_eq!(restored.deadline_pressure, 0.7);
+    }
+
+    #[test]
+    fn test_legacy_context_bytes_are_padded() {
+        let legacy_dim = feature_dim_for_version(1).unwrap();
+        let legacy: Vec<u8> = RichContext::default().to_feature_vector()
+            .iter()
+            .take(legacy_dim)
+            .flat_map(|f| f.to_le_bytes())
+            .collect();
+
+        // Unversioned blob from before the version byte existed
+        let restored = RichContext::from_bytes(&legacy).unwrap();
+        assert_eq!(restored.days_to_next_exam, RichContext::default().days_to_next_exam);
+        assert!(RichContext::from_bytes(&legacy[..legacy.len() - 4]).is_none());
+
+        // Versioned v1 blob
+        let mut versioned = vec![1u8];
+        versioned.extend_from_slice(&legacy);
+        assert!(RichContext::from_bytes(&versioned).is_some());
+        versioned.truncate(versioned.len() - 4);
+        assert!(RichContext::from_bytes(&versioned).is_none());
+    }
+
+    #[test]
+    fn test_newer_context_bytes_are_truncated() {
+        let mut bytes = RichContext::default().to_bytes();
+        bytes[0] = FEATURE_VERSION + 1;
+        bytes.extend_from_slice(&0.9f32.to_le_bytes());
+
+        let restored = RichContext::from_bytes(&bytes).unwrap();
+        assert_eq!(restored.to_feature_vector().len(), FEATURE_DIM);
     }
 }
 
@@ -753,6 +1182,28 @@ mod benchmarks {
         .await
         .unwrap();
 
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS settings (key TEXT PRIMARY KEY, value_json TEXT NOT NULL, updated_at TEXT)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        sqlx::query(
+            r#"
+            CREATE TABLE IF NOT EXISTS agent_action_snoozes (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                action_name TEXT NOT NULL,
+                recommendation_id INTEGER,
+                snoozed_until TEXT NOT NULL,
+                created_at TEXT DEFAULT (datetime('now'))
+            );
+            "#
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
         pool
     }
 
@@ -786,4 +1237,25 @@ mod benchmarks {
 
         println!("benchmark_select_top_actions took: {:?}", duration);
     }
+
+    #[tokio::test]
+    async fn snoozed_actions_are_skipped() {
+        let pool = setup_db().await;
+        for name in ["focus", "walk"] {
+            sqlx::query("INSERT INTO agent_linear_bandit (action_name, category) VALUES (?, 'test')")
+                .bind(name)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+
+        HybridBandit::snooze_action(&pool, "focus", 2.0, None).await.unwrap();
+        assert_eq!(HybridBandit::snoozed_actions(&pool).await.unwrap(), vec!["focus"]);
+
+        let picked = HybridBandit::select_top_actions(&pool, &RichContext::default(), 5, None)
+            .await
+            .unwrap();
+        assert_eq!(picked.len(), 1);
+        assert_eq!(picked[0].action.name, "walk");
+    }
 }
@@ -8,7 +8,9 @@
 use ndarray::Array2;
 use ort::session::{builder::GraphOptimizationLevel, Session};
 use ort::value::Tensor;
-use parking_lot::RwLock;
+use parking_lot::{Mutex, RwLock};
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
 use std::path::Path;
 use std::sync::Arc;
 use tokio::sync::Semaphore;
@@ -23,6 +25,9 @@ const MAX_SEQ_LENGTH: usize = 512;
 /// Maximum number of concurrent embedding tasks.
 pub const EMBEDDING_MAX_CONCURRENT_TASKS: usize = 2;
 
+/// Number of embeddings kept in the in-memory cache (~4 KB each)
+const EMBEDDING_CACHE_CAPACITY: usize = 1024;
+
 /// Cached embedding service singleton
 static EMBEDDING_SERVICE: once_cell::sync::OnceCell<Arc<EmbeddingService>> =
     once_cell::sync::OnceCell::new();
@@ -118,10 +123,57 @@ where
         .map_err(|e| format!("Embedding task failed: {}", e))?
 }
 
+/// Least-recently-used cache of embeddings keyed by the SHA-256 of the text
+///
+/// Context descriptions repeat constantly, so most lookups skip inference.
+struct EmbeddingCache {
+    capacity: usize,
+    entries: HashMap<[u8; 32], (Vec<f32>, u64)>,
+    /// Incremented on every access; entries remember when they were last used
+    tick: u64,
+}
+
+impl EmbeddingCache {
+    fn new(capacity: usize) -> Self {
+        Self {
+            capacity,
+            entries: HashMap::with_capacity(capacity),
+            tick: 0,
+        }
+    }
+
+    fn key(text: &str) -> [u8; 32] {
+        Sha256::digest(text.as_bytes()).into()
+    }
+
+    fn get(&mut self, key: &[u8; 32]) -> Option<Vec<f32>> {
+        self.tick += 1;
+        let tick = self.tick;
+        self.entries.get_mut(key).map(|(embedding, used)| {
+            *used = tick;
+            embedding.clone()
+        })
+    }
+
+    fn insert(&mut self, key: [u8; 32], embedding: Vec<f32>) {
+        if self.capacity == 0 {
+            return;
+        }
+        if self.entries.len() >= self.capacity && !self.entries.contains_key(&key) {
+            if let Some(oldest) = self.entries.iter().min_by_key(|(_, (_, used))| *used).map(|(k, _)| *k) {
+                self.entries.remove(&oldest);
+            }
+        }
+        self.tick += 1;
+        self.entries.insert(key, (embedding, self.tick));
+    }
+}
+
 /// ONNX-based embedding service
 pub struct EmbeddingService {
     session: RwLock<Session>,
     tokenizer: Tokenizer,
+    cache: Mutex<EmbeddingCache>,
 }
 
 impl EmbeddingService {
@@ -137,23 +189,27 @@ impl EmbeddingService {
             }
         }
         EMBEDDING_SERVICE
-            .get_or_try_init(|| {
-                let model_dir = std::env::var("EMBEDDING_MODEL_PATH").unwrap_or_else(|_| {
-                    dirs::data_dir()
-                        .map(|p| {
-                            p.join("com.tauri.dev")
-                                .join("models")
-                                .join("qwen3-embedding")
-                        })
-                        .unwrap_or_default()
-                        .to_string_lossy()
-                        .to_string()
-                });
-                Self::new(&model_dir)
-            })
+            .get_or_try_init(|| Self::new(&Self::model_dir()))
             .cloned()
     }
 
+    /// Directory the global service loads its model from
+    pub fn model_dir() -> String {
+        std::env::var("EMBEDDING_MODEL_PATH").unwrap_or_else(|_| {
+            crate::platform::data_dir()
+                .join("models")
+                .join("qwen3-embedding")
+                .to_string_lossy()
+                .to_string()
+        })
+    }
+
+    /// Whether the model files are installed (without loading them)
+    pub fn is_model_available() -> bool {
+        let dir = Self::model_dir();
+        Path::new(&dir).join("model_q4.onnx").exists() && Path::new(&dir).join("tokenizer.json").exists()
+    }
+
     /// Create a new embedding service from a model directory
     pub fn new(model_dir: &str) -> Result<Arc<Self>, String> {
         let model_path = Path::new(model_dir).join("model_q4.onnx");
@@ -183,6 +239,7 @@ impl EmbeddingService {
         Ok(Arc::new(Self {
             session: RwLock::new(session),
             tokenizer,
+            cache: Mutex::new(EmbeddingCache::new(EMBEDDING_CACHE_CAPACITY)),
         }))
     }
 
@@ -196,11 +253,40 @@ impl EmbeddingService {
     }
 
     /// Generate embeddings for a batch of texts
+    ///
+    /// Texts embedded recently are served from the cache; only the rest
+    /// go through the model.
     pub fn embed_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, String> {
         if texts.is_empty() {
             return Ok(vec![]);
         }
 
+        let keys: Vec<[u8; 32]> = texts.iter().map(|t| EmbeddingCache::key(t)).collect();
+        let mut results: Vec<Option<Vec<f32>>> = {
+            let mut cache = self.cache.lock();
+            keys.iter().map(|k| cache.get(k)).collect()
+        };
+
+        let misses: Vec<usize> = (0..texts.len()).filter(|&i| results[i].is_none()).collect();
+        if !misses.is_empty() {
+            let miss_texts: Vec<String> = misses.iter().map(|&i| texts[i].clone()).collect();
+            let computed = self.infer_batch(&miss_texts)?;
+            let mut cache = self.cache.lock();
+            for (i, embedding) in misses.into_iter().zip(computed) {
+                cache.insert(keys[i], embedding.clone());
+                results[i] = Some(embedding);
+            }
+        }
+
+        results
+            .into_iter()
+            .map(|r| r.ok_or_else(|| "No embedding generated".to_string()))
+            .collect()
+    }
+
+    /// Run the model over a batch of texts
+    fn infer_batch(&self, texts: &[String]) -> Result<Vec<Vec<f32>>, String> {
+
         // Tokenize all texts
         let encodings = self
             .tokenizer
@@ -379,6 +465,26 @@ mod tests {
         assert_eq!(original, recovered);
     }
 
+    #[test]
+    fn embedding_cache_evicts_least_recently_used() {
+        let mut cache = EmbeddingCache::new(2);
+        let (a, b, c) = (
+            EmbeddingCache::key("moderate energy, neutral mood"),
+            EmbeddingCache::key("high energy"),
+            EmbeddingCache::key("low energy"),
+        );
+        cache.insert(a, vec![1.0]);
+        cache.insert(b, vec![2.0]);
+
+        // Touching `a` makes `b` the eviction candidate
+        assert_eq!(cache.get(&a), Some(vec![1.0]));
+        cache.insert(c, vec![3.0]);
+
+        assert_eq!(cache.get(&a), Some(vec![1.0]));
+        assert_eq!(cache.get(&b), None);
+        assert_eq!(cache.get(&c), Some(vec![3.0]));
+    }
+
     #[test]
     fn test_cosine_similarity() {
         let a = vec![1.0, 0.0, 0.0];
@@ -95,6 +95,17 @@ impl FeatureStore {
         .await
         .unwrap_or(0);
 
+        // Days until the next upcoming exam
+        let days_to_next_exam: Option<f64> = sqlx::query_scalar(
+            r#"
+            SELECT MIN(julianday(exam_date) - julianday('now')) FROM exams
+            WHERE exam_date IS NOT NULL AND julianday(exam_date) >= julianday('now')
+            "#
+        )
+        .fetch_one(pool)
+        .await
+        .unwrap_or(None);
+
         Ok(Context {
             hour_of_day,
             day_of_week,
@@ -106,6 +117,7 @@ impl FeatureStore {
             overdue_assignments: overdue_assignments as i32,
             current_streak_days: current_streak_days as i32,
             session_in_progress: session_in_progress > 0,
+            days_to_next_exam: days_to_next_exam.map(|d| d.floor() as i32),
         })
     }
 
@@ -117,8 +129,8 @@ impl FeatureStore {
                 hour_of_day, day_of_week, mood, energy,
                 recent_study_minutes, recent_workout_count,
                 active_assignments, overdue_assignments,
-                current_streak_days, session_in_progress
-            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                current_streak_days, session_in_progress, days_to_next_exam
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             "#
         )
         .bind(ctx.hour_of_day)
@@ -131,6 +143,7 @@ impl FeatureStore {
         .bind(ctx.overdue_assignments)
         .bind(ctx.current_streak_days)
         .bind(ctx.session_in_progress)
+        .bind(ctx.days_to_next_exam)
         .execute(pool)
         .await
         .map_err(|e| e.to_string())?;
@@ -143,13 +156,13 @@ impl FeatureStore {
         pool: &Pool<Sqlite>,
         days_back: i32
     ) -> Result<Vec<Context>, String> {
-        let rows: Vec<(i32, i32, Option<i32>, Option<i32>, i32, i32, i32, i32, i32, bool)> = 
+        let rows: Vec<(i32, i32, Option<i32>, Option<i32>, i32, i32, i32, i32, i32, bool, Option<i32>)> = 
             sqlx::query_as(
             r#"
             SELECT hour_of_day, day_of_week, mood, energy,
                    recent_study_minutes, recent_workout_count,
                    active_assignments, overdue_assignments,
-                   current_streak_days, session_in_progress
+                   current_streak_days, session_in_progress, days_to_next_exam
             FROM agent_feature_snapshots
             WHERE captured_at >= datetime('now', ? || ' days')
             ORDER BY captured_at DESC
@@ -171,6 +184,7 @@ impl FeatureStore {
             overdue_assignments: r.7,
             current_streak_days: r.8,
             session_in_progress: r.9,
+            days_to_next_exam: r.10,
         }).collect())
     }
 }
@@ -9,6 +9,7 @@
 //! - **Rich Features**: 50+ dimensional context vector for ML
 //! - **Hybrid Bandit**: Linear→Neural contextual bandit with UCB/Thompson Sampling
 //! - **Multi-Scale Rewards**: Balanced immediate/daily/weekly/monthly optimization
+//! - **Readiness**: Background initialization of the heavy components at startup
 //!
 //! ## Architecture
 //!
@@ -32,6 +33,7 @@ pub mod bandit_v2;
 pub mod models;
 pub mod pattern_miner;
 pub mod user_profile;
+pub mod readiness;
 pub mod bandit;  // Legacy bandit for backwards compatibility
 pub mod feature_store;  // Legacy feature store for backwards compatibility
 
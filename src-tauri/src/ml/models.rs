@@ -18,6 +18,17 @@ pub struct Context {
     pub overdue_assignments: i32,
     pub current_streak_days: i32,
     pub session_in_progress: bool,
+    pub days_to_next_exam: Option<i32>, // Whole days until the next scheduled exam
+}
+
+/// Number of days before an exam during which the pre-exam protocol applies
+pub const PRE_EXAM_WINDOW_DAYS: i32 = 5;
+
+impl Context {
+    /// Whether an exam falls within the pre-exam protocol window
+    pub fn in_pre_exam_window(&self) -> bool {
+        matches!(self.days_to_next_exam, Some(d) if (0..=PRE_EXAM_WINDOW_DAYS).contains(&d))
+    }
 }
 
 impl Default for Context {
@@ -33,6 +44,7 @@ impl Default for Context {
             overdue_assignments: 0,
             current_streak_days: 0,
             session_in_progress: false,
+            days_to_next_exam: None,
         }
     }
 }
@@ -165,6 +177,72 @@ impl Default for RewardWeights {
     }
 }
 
+/// Named reward weight presets: (name, label, description)
+pub const REWARD_WEIGHT_PRESETS: &[(&str, &str, &str)] = &[
+    ("balanced", "Balanced", "Default mix of short- and long-term outcomes"),
+    ("exam_crunch", "Exam crunch", "Favor immediate and same-day results while exams are close"),
+    ("health_first", "Health first", "Favor weekly and monthly wellbeing over quick wins"),
+];
+
+impl RewardWeights {
+    /// Look up a named preset
+    pub fn preset(name: &str) -> Option<Self> {
+        match name {
+            "balanced" => Some(Self::default()),
+            "exam_crunch" => Some(Self {
+                immediate: 0.4,
+                daily: 0.35,
+                weekly: 0.2,
+                monthly: 0.05,
+            }),
+            "health_first" => Some(Self {
+                immediate: 0.1,
+                daily: 0.2,
+                weekly: 0.35,
+                monthly: 0.35,
+            }),
+            _ => None,
+        }
+    }
+
+    /// Check that weights are finite, non-negative and sum to ~1.0
+    pub fn validate(&self) -> Result<(), String> {
+        let all = [self.immediate, self.daily, self.weekly, self.monthly];
+        if all.iter().any(|w| !w.is_finite() || *w < 0.0) {
+            return Err("Weights must be non-negative numbers".into());
+        }
+        if self.immediate <= 0.0 {
+            return Err("Immediate weight must be positive".into());
+        }
+        let total: f32 = all.iter().sum();
+        if (total - 1.0).abs() > 0.01 {
+            return Err("Weights must sum to 1.0".into());
+        }
+        Ok(())
+    }
+
+    /// Name of the preset these weights match, if any
+    pub fn matching_preset(&self) -> Option<&'static str> {
+        REWARD_WEIGHT_PRESETS.iter().map(|(name, _, _)| *name).find(|name| {
+            Self::preset(name).is_some_and(|p| {
+                (p.immediate - self.immediate).abs() < 1e-3
+                    && (p.daily - self.daily).abs() < 1e-3
+                    && (p.weekly - self.weekly).abs() < 1e-3
+                    && (p.monthly - self.monthly).abs() < 1e-3
+            })
+        })
+    }
+}
+
+/// A recorded change of reward weights
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RewardWeightChange {
+    pub id: i64,
+    pub weights: RewardWeights,
+    pub preset: Option<String>,
+    pub changed_at: String,
+}
+
 /// Multi-scale reward computation
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct MultiScaleReward {
@@ -232,6 +310,85 @@ impl RewardEngine {
         }
     }
 
+    /// Validate, store and record a change of reward weights
+    ///
+    /// Every change is appended to `agent_reward_weight_history` so shifts in
+    /// agent behavior can be correlated with tuning.
+    pub async fn set_weights(
+        pool: &Pool<Sqlite>,
+        weights: &RewardWeights,
+        preset: Option<&str>,
+    ) -> Result<(), String> {
+        weights.validate()?;
+        let json = serde_json::to_string(weights).map_err(|e| e.to_string())?;
+
+        let mut tx = pool.begin().await.map_err(|e| e.to_string())?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO agent_state (key, value_json, updated_at)
+            VALUES ('reward_weights', ?, datetime('now'))
+            ON CONFLICT(key) DO UPDATE SET value_json = excluded.value_json, updated_at = excluded.updated_at
+            "#,
+        )
+        .bind(&json)
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO agent_reward_weight_history (immediate, daily, weekly, monthly, preset)
+            VALUES (?, ?, ?, ?, ?)
+            "#,
+        )
+        .bind(weights.immediate)
+        .bind(weights.daily)
+        .bind(weights.weekly)
+        .bind(weights.monthly)
+        .bind(preset.or_else(|| weights.matching_preset()))
+        .execute(&mut *tx)
+        .await
+        .map_err(|e| e.to_string())?;
+
+        tx.commit().await.map_err(|e| e.to_string())?;
+        Ok(())
+    }
+
+    /// Get recent reward weight changes, newest first
+    pub async fn get_weight_history(
+        pool: &Pool<Sqlite>,
+        limit: i64,
+    ) -> Result<Vec<RewardWeightChange>, String> {
+        let rows: Vec<(i64, f64, f64, f64, f64, Option<String>, String)> = sqlx::query_as(
+            r#"
+            SELECT id, immediate, daily, weekly, monthly, preset, changed_at
+            FROM agent_reward_weight_history
+            ORDER BY changed_at DESC, id DESC
+            LIMIT ?
+            "#,
+        )
+        .bind(limit)
+        .fetch_all(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(id, immediate, daily, weekly, monthly, preset, changed_at)| RewardWeightChange {
+                id,
+                weights: RewardWeights {
+                    immediate: immediate as f32,
+                    daily: daily as f32,
+                    weekly: weekly as f32,
+                    monthly: monthly as f32,
+                },
+                preset,
+                changed_at,
+            })
+            .collect())
+    }
+
     /// Compute immediate reward from user feedback
     pub fn compute_immediate_reward(
         acted_on: bool,
@@ -320,6 +477,15 @@ impl RewardEngine {
         reward += study_reward;
         weight += 1.0;
 
+        // Life balance over the week ending that day, a secondary objective
+        let balance = crate::services::life_balance::life_balance(pool, *date)
+            .await
+            .map_err(|e| e.message)?;
+        if let Some(score) = balance.score {
+            reward += 0.5 * score as f32 / 100.0;
+            weight += 0.5;
+        }
+
         Ok(if weight > 0.0 { reward / weight } else { 0.5 })
     }
 
@@ -434,3 +600,35 @@ impl RewardEngine {
         Ok(result.rows_affected() as usize)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_presets_are_valid() {
+        for (name, _, _) in REWARD_WEIGHT_PRESETS {
+            let weights = RewardWeights::preset(name).unwrap();
+            assert!(weights.validate().is_ok(), "{} is invalid", name);
+            assert_eq!(weights.matching_preset(), Some(*name));
+        }
+        assert!(RewardWeights::preset("unknown").is_none());
+    }
+
+    #[test]
+    fn test_weight_validation() {
+        let mut weights = RewardWeights::default();
+        weights.monthly = 0.5;
+        assert!(weights.validate().is_err());
+
+        let negative = RewardWeights { immediate: 0.5, daily: 0.7, weekly: -0.2, monthly: 0.0 };
+        assert!(negative.validate().is_err());
+
+        let no_immediate = RewardWeights { immediate: 0.0, daily: 0.5, weekly: 0.5, monthly: 0.0 };
+        assert!(no_immediate.validate().is_err());
+
+        let custom = RewardWeights { immediate: 0.25, daily: 0.25, weekly: 0.25, monthly: 0.25 };
+        assert!(custom.validate().is_ok());
+        assert!(custom.matching_preset().is_none());
+    }
+}
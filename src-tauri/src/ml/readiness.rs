@@ -0,0 +1,180 @@
+//! ML Readiness
+//!
+//! Opening LanceDB and loading the ONNX embedding model can take seconds.
+//! `start` does both on a background task at app start, so the first
+//! recommendation does not pay for it. While loading, callers degrade
+//! instead of blocking: recommendations skip similar experiences and
+//! insights come from the rule-based pipeline.
+//!
+//! Before `start` is called (tests, tooling) nothing is degraded and the
+//! singletons initialize lazily as before.
+//!
+//! The `reduced_data_mode` setting turns the ML stack off for slow devices:
+//! nothing is loaded, the bandit stops learning and insights stay
+//! rule-based. Turning it back off loads the stack as at startup.
+
+use std::time::Instant;
+
+use once_cell::sync::Lazy;
+use parking_lot::RwLock;
+use serde::Serialize;
+use tokio::sync::broadcast;
+
+use super::embedding::{run_embedding_task, EmbeddingService};
+use super::semantic_memory::SemanticMemory;
+use crate::services::settings;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReadinessStatus {
+    NotStarted,
+    Loading,
+    /// Semantic memory is open; embeddings may still be missing
+    Ready,
+    /// Semantic memory failed to open; the agent runs without it
+    Unavailable,
+    /// Turned off by `reduced_data_mode`
+    Disabled,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MlReadiness {
+    pub status: ReadinessStatus,
+    pub semantic_memory: bool,
+    /// False when the model is not installed or failed to load
+    pub embeddings: bool,
+    pub error: Option<String>,
+    /// How long initialization took
+    pub load_ms: Option<u64>,
+}
+
+static READINESS: Lazy<RwLock<MlReadiness>> = Lazy::new(|| {
+    RwLock::new(MlReadiness {
+        status: ReadinessStatus::NotStarted,
+        semantic_memory: false,
+        embeddings: false,
+        error: None,
+        load_ms: None,
+    })
+});
+
+/// Current readiness snapshot
+pub fn status() -> MlReadiness {
+    READINESS.read().clone()
+}
+
+/// Whether initialization is still running in the background
+pub fn is_loading() -> bool {
+    READINESS.read().status == ReadinessStatus::Loading
+}
+
+/// Whether reduced-data mode has turned the ML stack off
+pub fn is_disabled() -> bool {
+    READINESS.read().status == ReadinessStatus::Disabled
+}
+
+/// Whether callers may use `SemanticMemory::global` without blocking on
+/// initialization or retrying one that already failed
+pub fn semantic_memory_usable() -> bool {
+    !matches!(
+        READINESS.read().status,
+        ReadinessStatus::Loading | ReadinessStatus::Unavailable | ReadinessStatus::Disabled
+    )
+}
+
+/// Apply the `reduced_data_mode` setting
+///
+/// Turning it on takes effect immediately; components already loaded stay
+/// in memory but are no longer used.
+pub fn set_reduced_data_mode(reduced: bool) {
+    if reduced {
+        READINESS.write().status = ReadinessStatus::Disabled;
+        log::info!("Reduced-data mode on: ML components disabled");
+    } else {
+        start();
+    }
+}
+
+/// Follow changes to the `reduced_data_mode` setting
+pub fn follow_setting() {
+    let mut changes = settings::subscribe();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            match changes.recv().await {
+                Ok(change) if change.key == "reduced_data_mode" => {
+                    set_reduced_data_mode(change.value.as_bool().unwrap_or(false));
+                }
+                Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => {}
+                Err(broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    });
+}
+
+/// Initialize semantic memory and embeddings in the background
+pub fn start() {
+    {
+        let mut readiness = READINESS.write();
+        if !matches!(readiness.status, ReadinessStatus::NotStarted | ReadinessStatus::Disabled) {
+            return;
+        }
+        readiness.status = ReadinessStatus::Loading;
+        readiness.error = None;
+    }
+
+    tauri::async_runtime::spawn(async {
+        let started = Instant::now();
+        let result = initialize().await;
+        let load_ms = started.elapsed().as_millis() as u64;
+
+        let mut readiness = READINESS.write();
+        readiness.load_ms = Some(load_ms);
+        // Reduced-data mode may have been turned on while loading
+        let disabled = readiness.status == ReadinessStatus::Disabled;
+        match result {
+            Ok(embeddings) => {
+                log::info!("ML ready in {} ms (embeddings: {})", load_ms, embeddings);
+                if !disabled {
+                    readiness.status = ReadinessStatus::Ready;
+                }
+                readiness.semantic_memory = true;
+                readiness.embeddings = embeddings;
+            }
+            Err(e) => {
+                log::warn!("ML initialization failed, running without semantic memory: {}", e);
+                if !disabled {
+                    readiness.status = ReadinessStatus::Unavailable;
+                }
+                readiness.error = Some(e);
+            }
+        }
+    });
+}
+
+/// Open semantic memory, then load the embedding model if it is installed
+async fn initialize() -> Result<bool, String> {
+    SemanticMemory::global().await?;
+
+    if !EmbeddingService::is_model_available() {
+        return Ok(false);
+    }
+    match run_embedding_task(EmbeddingService::global).await {
+        Ok(_) => Ok(true),
+        Err(e) => {
+            log::warn!("Embedding model failed to load: {}", e);
+            Ok(false)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn nothing_is_degraded_before_start() {
+        assert_eq!(status().status, ReadinessStatus::NotStarted);
+        assert!(!is_loading());
+        assert!(semantic_memory_usable());
+    }
+}
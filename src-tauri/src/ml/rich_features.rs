@@ -2,16 +2,63 @@
 //!
 //! Captures comprehensive user context for ML decision-making.
 //! This is the feature vector used by the contextual bandit.
+//!
+//! New features are only ever appended to the end of the vector so that
+//! previously stored vectors and bandit parameters remain a valid prefix.
+//! Each append bumps `FEATURE_VERSION` and adds an entry to
+//! `feature_dim_for_version`.
 
 #![allow(dead_code)] // Serialization methods for future use
 
-use chrono::{Datelike, Local, NaiveDate, Timelike};
+use chrono::{DateTime, Datelike, Local, NaiveDate, Timelike, Utc};
 use ndarray::Array1;
 use serde::{Deserialize, Serialize};
 use sqlx::{Pool, Sqlite};
+use std::time::{Duration, Instant};
+
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+
+use super::user_profile::UserProfile;
+use crate::services::{aggregates, clock, cycle, energy, social};
 
 /// Number of features in the rich context vector
-pub const FEATURE_DIM: usize = 50;
+pub const FEATURE_DIM: usize = 53;
+
+/// Current feature layout version; bump whenever features are appended
+pub const FEATURE_VERSION: u8 = 3;
+
+/// Feature vector dimension for each known layout version
+///
+/// - v1: original 50 features
+/// - v2: + days_to_next_exam, deadline_pressure
+/// - v3: + cycle_phase
+pub fn feature_dim_for_version(version: u8) -> Option<usize> {
+    match version {
+        1 => Some(50),
+        2 => Some(52),
+        3 => Some(53),
+        _ => None,
+    }
+}
+
+/// Infer the layout version of an unversioned vector from its dimension
+pub fn version_for_feature_dim(dim: usize) -> Option<u8> {
+    (1..=FEATURE_VERSION).find(|&v| feature_dim_for_version(v) == Some(dim))
+}
+
+/// Resize stored feature values to the current layout
+///
+/// Features are append-only, so vectors from an older layout are padded with
+/// the current defaults and vectors from a newer layout are truncated.
+pub fn upgrade_feature_values(mut values: Vec<f32>) -> Vec<f32> {
+    if values.len() < FEATURE_DIM {
+        let defaults = RichContext::default().to_feature_vector();
+        values.extend(defaults.iter().skip(values.len()));
+    }
+    values.truncate(FEATURE_DIM);
+    values
+}
 
 /// Rich context with 50+ dimensional feature vector
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -29,7 +76,7 @@ pub struct RichContext {
     pub energy_trajectory: f32,     // Change from previous (-1 to 1)
     pub mood_level: f32,            // 1-10, normalized to 0-1
     pub mood_trajectory: f32,       // Change from previous (-1 to 1)
-    pub fatigue_score: f32,         // Inferred from patterns (0-1)
+    pub fatigue_score: f32,         // Spent share of the energy budget (0-1)
     pub recovery_need: f32,         // Based on recent intensity (0-1)
 
     // Learning/Skill features (6)
@@ -81,6 +128,13 @@ pub struct RichContext {
     pub mood_traj_x_social: f32,       // Mood trajectory × social context
     pub circadian_x_task: f32,         // Circadian fit × task type
     pub history_x_current: f32,        // Historical success × current similarity
+
+    // Deadline features (2)
+    pub days_to_next_exam: f32,        // Days until next exam, normalized to 30 (1 = none soon)
+    pub deadline_pressure: f32,        // Effort-weighted inverse days-to-due, normalized
+
+    // Cycle features (1)
+    pub cycle_phase: f32,              // Expected energy of the cycle phase (0-1), 0.5 when not tracked
 }
 
 impl Default for RichContext {
@@ -151,6 +205,13 @@ impl Default for RichContext {
             mood_traj_x_social: 0.0,
             circadian_x_task: 0.25,
             history_x_current: 0.25,
+
+            // Deadlines
+            days_to_next_exam: 1.0,
+            deadline_pressure: 0.0,
+
+            // Cycle
+            cycle_phase: 0.5,
         }
     }
 }
@@ -217,31 +278,62 @@ impl RichContext {
             self.mood_traj_x_social,
             self.circadian_x_task,
             self.history_x_current,
+            // Deadlines (2)
+            self.days_to_next_exam,
+            self.deadline_pressure,
+            // Cycle (1)
+            self.cycle_phase,
         ])
     }
 
     /// Convert to bytes for storage
+    ///
+    /// Layout: one version byte followed by little-endian f32 features.
     pub fn to_bytes(&self) -> Vec<u8> {
         let vec = self.to_feature_vector();
-        vec.iter().flat_map(|f| f.to_le_bytes()).collect()
+        let mut bytes = Vec::with_capacity(1 + FEATURE_DIM * 4);
+        bytes.push(FEATURE_VERSION);
+        bytes.extend(vec.iter().flat_map(|f| f.to_le_bytes()));
+        bytes
     }
 
     /// Create from bytes
+    ///
+    /// Accepts versioned blobs as well as unversioned blobs written before the
+    /// version byte existed (the version is inferred from the length). Vectors
+    /// from other layouts are padded/truncated via `upgrade_feature_values`.
     pub fn from_bytes(bytes: &[u8]) -> Option<Self> {
-        if bytes.len() != FEATURE_DIM * 4 {
-            return None;
-        }
+        let payload = match bytes.len() % 4 {
+            0 => {
+                version_for_feature_dim(bytes.len() / 4)?;
+                bytes
+            }
+            1 => {
+                let version = bytes[0];
+                let payload = &bytes[1..];
+                if let Some(dim) = feature_dim_for_version(version) {
+                    if payload.len() != dim * 4 {
+                        return None;
+                    }
+                } else if version < FEATURE_VERSION {
+                    return None;
+                }
+                payload
+            }
+            _ => return None,
+        };
 
-        let floats: Vec<f32> = bytes
+        let floats: Vec<f32> = payload
             .chunks_exact(4)
             .map(|chunk| f32::from_le_bytes([chunk[0], chunk[1], chunk[2], chunk[3]]))
             .collect();
 
-        if floats.len() != FEATURE_DIM {
-            return None;
-        }
+        Some(Self::from_feature_values(&upgrade_feature_values(floats)))
+    }
 
-        Some(Self {
+    /// Build a context from a vector in the current layout
+    fn from_feature_values(floats: &[f32]) -> Self {
+        Self {
             hour_of_day: floats[0],
             day_of_week: floats[1],
             week_of_year: floats[2],
@@ -292,7 +384,10 @@ impl RichContext {
             mood_traj_x_social: floats[47],
             circadian_x_task: floats[48],
             history_x_current: floats[49],
-        })
+            days_to_next_exam: floats[50],
+            deadline_pressure: floats[51],
+            cycle_phase: floats[52],
+        }
     }
 
     /// Get feature names for explainability
@@ -348,6 +443,9 @@ impl RichContext {
             "mood_traj_x_social",
             "circadian_x_task",
             "history_x_current",
+            "days_to_next_exam",
+            "deadline_pressure",
+            "cycle_phase",
         ]
     }
 
@@ -400,16 +498,107 @@ impl RichContext {
     }
 }
 
+/// How long a captured context is reused; recommendations are requested
+/// several times a minute. Tests always capture fresh.
+const CONTEXT_TTL: Duration = if cfg!(test) { Duration::ZERO } else { Duration::from_secs(30) };
+
+/// (mood, energy, previous mood, previous energy, hours since check-in, streak)
+type CheckinStats = (Option<i32>, Option<i32>, Option<i32>, Option<i32>, Option<f64>, i64);
+
+/// (pomodoros today, study minutes today, skills practiced this week, skills,
+/// Big 3 today, Big 3 done, hours since a break, study minutes this week,
+/// target weekly hours, hours since workout)
+type ActivityStats = (i64, i64, i64, i64, i64, i64, Option<f64>, i64, f64, Option<f64>);
+
+/// (overdue, active, due today, due this week, days to next exam, deadline pressure)
+type DeadlineStats = (i64, i64, i64, i64, Option<f64>, f64);
+
+struct CachedContext {
+    ctx: RichContext,
+    /// `aggregates::generation` at capture; activity writes bump it
+    generation: u64,
+    captured_at: Instant,
+}
+
+static CONTEXT_CACHE: Lazy<Mutex<Option<CachedContext>>> = Lazy::new(|| Mutex::new(None));
+
+fn cached_context(generation: u64, ttl: Duration) -> Option<RichContext> {
+    CONTEXT_CACHE
+        .lock()
+        .as_ref()
+        .filter(|c| c.generation == generation && c.captured_at.elapsed() < ttl)
+        .map(|c| c.ctx.clone())
+}
+
 /// Rich feature store - captures comprehensive context
 pub struct RichFeatureStore;
 
 impl RichFeatureStore {
-    /// Capture current rich context from the database
+    /// Capture current rich context, reusing one captured in the last
+    /// `CONTEXT_TTL` unless activity has been logged since
     pub async fn capture_context(pool: &Pool<Sqlite>) -> Result<RichContext, String> {
-        let now = Local::now();
+        let generation = aggregates::generation();
+        if let Some(ctx) = cached_context(generation, CONTEXT_TTL) {
+            return Ok(ctx);
+        }
+
+        let ctx = Self::capture_context_uncached(pool).await?;
+        *CONTEXT_CACHE.lock() = Some(CachedContext {
+            ctx: ctx.clone(),
+            generation,
+            captured_at: Instant::now(),
+        });
+        Ok(ctx)
+    }
+
+    /// Capture current rich context from the database
+    pub async fn capture_context_uncached(pool: &Pool<Sqlite>) -> Result<RichContext, String> {
+        Self::capture_context_at(pool, clock::now()).await
+    }
+
+    /// Capture rich context as of `now`
+    ///
+    /// The counts come from a few multi-column queries run concurrently.
+    pub async fn capture_context_at(pool: &Pool<Sqlite>, now: DateTime<Local>) -> Result<RichContext, String> {
         let hour = now.hour() as f32;
         let day = now.weekday().num_days_from_sunday() as f32;
         let week = now.iso_week().week() as f32;
+        let today = now.format("%Y-%m-%d").to_string();
+        // SQLite's own timestamp format, in UTC like 'now'
+        let at = now.with_timezone(&Utc).format("%Y-%m-%d %H:%M:%S").to_string();
+
+        let (checkins, activity, deadlines, urgency_data, focus_trend, energy, chronotype, challenge_pace, cycle_day, social_time) = tokio::try_join!(
+            Self::checkin_stats(pool, &at),
+            Self::activity_stats(pool, &today, &at),
+            Self::deadline_stats(pool, &at),
+            Self::urgent_assignments(pool),
+            async {
+                // Optional feature; missing focus data never fails the capture
+                Ok::<_, String>(crate::services::focus::focus_trend(pool).await.ok().flatten())
+            },
+            async {
+                energy::energy_budget(pool, now.date_naive())
+                    .await
+                    .map_err(|e| e.message)
+            },
+            async {
+                // Not learned yet (or unreadable) falls back to the defaults below
+                Ok::<_, String>(UserProfile::chronotype(pool).await.ok().flatten())
+            },
+            async {
+                // Challenges are optional too; none active leaves the default
+                Ok::<_, String>(crate::services::challenges::goal_progress(pool, now.date_naive()).await.ok().flatten())
+            },
+            async {
+                // Opt-in; off or nothing logged leaves the neutral default
+                let today = now.date_naive();
+                Ok::<_, String>(cycle::days(pool, today, today, today).await.ok().and_then(|days| days.first().copied()))
+            },
+            async {
+                // Nothing logged yet leaves the mood trajectory unscaled
+                Ok::<_, String>(social::social_context(pool, now).await.ok().flatten())
+            },
+        )?;
 
         let mut ctx = RichContext::default();
 
@@ -428,74 +617,36 @@ impl RichFeatureStore {
         ctx.time_until_sleep = ((sleep_hour - hour).max(0.0) / 16.0).min(1.0);
 
         // === Physiological features ===
-        let checkin_data: Option<(Option<i32>, Option<i32>, Option<i32>, Option<i32>)> = sqlx::query_as(
-            r#"
-            SELECT c1.mood, c1.energy, c2.mood, c2.energy
-            FROM check_ins c1
-            LEFT JOIN check_ins c2 ON date(c2.checked_in_at) = date('now', '-1 day')
-            WHERE date(c1.checked_in_at) = date('now')
-            ORDER BY c1.checked_in_at DESC
-            LIMIT 1
-            "#
-        )
-        .fetch_optional(pool)
-        .await
-        .map_err(|e| e.to_string())?;
-
-        if let Some((mood, energy, prev_mood, prev_energy)) = checkin_data {
-            if let Some(e) = energy {
-                ctx.energy_level = (e as f32 - 1.0) / 9.0;
-                if let Some(pe) = prev_energy {
-                    ctx.energy_trajectory = ((e - pe) as f32 / 9.0).clamp(-1.0, 1.0);
-                }
+        let (mood, energy, prev_mood, prev_energy, hours_since_checkin, streak) = checkins;
+        if let Some(e) = energy {
+            ctx.energy_level = (e as f32 - 1.0) / 9.0;
+            if let Some(pe) = prev_energy {
+                ctx.energy_trajectory = ((e - pe) as f32 / 9.0).clamp(-1.0, 1.0);
             }
-            if let Some(m) = mood {
-                ctx.mood_level = (m as f32 - 1.0) / 9.0;
-                if let Some(pm) = prev_mood {
-                    ctx.mood_trajectory = ((m - pm) as f32 / 9.0).clamp(-1.0, 1.0);
-                }
+        }
+        if let Some(m) = mood {
+            ctx.mood_level = (m as f32 - 1.0) / 9.0;
+            if let Some(pm) = prev_mood {
+                ctx.mood_trajectory = ((m - pm) as f32 / 9.0).clamp(-1.0, 1.0);
             }
         }
-
-        // Hours since last check-in
-        let hours_since_checkin: Option<f64> = sqlx::query_scalar(
-            "SELECT (julianday('now') - julianday(checked_in_at)) * 24 FROM check_ins ORDER BY checked_in_at DESC LIMIT 1"
-        )
-        .fetch_optional(pool)
-        .await
-        .map_err(|e| e.to_string())?;
         ctx.hours_since_checkin = hours_since_checkin.map(|h| (h as f32 / 24.0).min(1.0)).unwrap_or(1.0);
 
         // === Learning/Skill features ===
-        // Pomodoros today
-        let pomodoros: i64 = sqlx::query_scalar(
-            "SELECT COUNT(*) FROM sessions WHERE session_type = 'study' AND date(started_at) = date('now')"
-        )
-        .fetch_one(pool)
-        .await
-        .unwrap_or(0);
+        let (
+            pomodoros,
+            study_mins,
+            skills_practiced,
+            total_skills,
+            big3_total,
+            big3_done,
+            hours_since_break,
+            week_study,
+            target,
+            hours_since_workout,
+        ) = activity;
         ctx.pomodoros_today = (pomodoros as f32 / 12.0).min(1.0); // Normalize to ~12 max
-
-        // Study minutes today
-        let study_mins: i64 = sqlx::query_scalar(
-            "SELECT COALESCE(SUM(duration_minutes), 0) FROM sessions WHERE session_type = 'study' AND date(started_at) = date('now')"
-        )
-        .fetch_one(pool)
-        .await
-        .unwrap_or(0);
         ctx.study_minutes_today = (study_mins as f32 / 480.0).min(1.0); // Normalize to 8 hours
-
-        // Practice diversity (unique skills practiced this week)
-        let skills_practiced: i64 = sqlx::query_scalar(
-            "SELECT COUNT(DISTINCT skill_id) FROM practice_logs WHERE logged_at >= date('now', '-7 days')"
-        )
-        .fetch_one(pool)
-        .await
-        .unwrap_or(0);
-        let total_skills: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM skills")
-            .fetch_one(pool)
-            .await
-            .unwrap_or(1);
         ctx.practice_diversity = if total_skills > 0 {
             (skills_practiced as f32 / total_skills as f32).min(1.0)
         } else {
@@ -503,35 +654,18 @@ impl RichFeatureStore {
         };
 
         // === Goal features ===
-        // Big 3 completion
-        let today = now.format("%Y-%m-%d").to_string();
-        let big3_stats: (i64, i64) = sqlx::query_as(
-            "SELECT COUNT(*), SUM(CASE WHEN is_completed = 1 THEN 1 ELSE 0 END) FROM agent_big_three WHERE date = ?"
-        )
-        .bind(&today)
-        .fetch_optional(pool)
-        .await
-        .map_err(|e| e.to_string())?
-        .unwrap_or((0, 0));
-        ctx.big_3_completion = if big3_stats.0 > 0 {
-            big3_stats.1 as f32 / big3_stats.0 as f32
+        ctx.big_3_completion = if big3_total > 0 {
+            big3_done as f32 / big3_total as f32
         } else {
             0.0
         };
 
-        // Assignment urgency (max urgency of incomplete assignments)
-        let urgency_data: Vec<(Option<String>, i32)> = sqlx::query_as(
-            r#"
-            SELECT due_date, priority FROM assignments 
-            WHERE is_completed = 0 AND due_date IS NOT NULL
-            ORDER BY due_date ASC
-            LIMIT 5
-            "#
-        )
-        .fetch_all(pool)
-        .await
-        .map_err(|e| e.to_string())?;
+        // Pace of active challenges against the time elapsed
+        if let Some(pace) = challenge_pace {
+            ctx.weekly_goal_progress = pace;
+        }
 
+        // Assignment urgency (max urgency of incomplete assignments)
         let mut max_urgency = 0.0f32;
         for (due_date, priority) in urgency_data {
             if let Some(due) = due_date {
@@ -552,67 +686,29 @@ impl RichFeatureStore {
         }
         ctx.assignment_urgency = max_urgency;
 
-        // Overdue count
-        let overdue: i64 = sqlx::query_scalar(
-            "SELECT COUNT(*) FROM assignments WHERE is_completed = 0 AND due_date < date('now')"
-        )
-        .fetch_one(pool)
-        .await
-        .unwrap_or(0);
+        let (overdue, active, due_today, due_week, days_to_exam, pressure) = deadlines;
         ctx.overdue_count = (overdue as f32 / 5.0).min(1.0);
-
-        // Active assignments
-        let active: i64 = sqlx::query_scalar(
-            "SELECT COUNT(*) FROM assignments WHERE is_completed = 0"
-        )
-        .fetch_one(pool)
-        .await
-        .unwrap_or(0);
         ctx.active_assignments = (active as f32 / 20.0).min(1.0);
-
-        // Due today/this week
-        let due_today: i64 = sqlx::query_scalar(
-            "SELECT COUNT(*) FROM assignments WHERE is_completed = 0 AND due_date = date('now')"
-        )
-        .fetch_one(pool)
-        .await
-        .unwrap_or(0);
         ctx.due_today = (due_today as f32 / 5.0).min(1.0);
-
-        let due_week: i64 = sqlx::query_scalar(
-            "SELECT COUNT(*) FROM assignments WHERE is_completed = 0 AND due_date BETWEEN date('now') AND date('now', '+7 days')"
-        )
-        .fetch_one(pool)
-        .await
-        .unwrap_or(0);
         ctx.due_this_week = (due_week as f32 / 10.0).min(1.0);
 
+        // Days until the next exam (no upcoming exam = far away)
+        ctx.days_to_next_exam = days_to_exam
+            .map(|d| (d as f32 / 30.0).clamp(0.0, 1.0))
+            .unwrap_or(1.0);
+
+        // Deadline pressure: sum of effort (hours) / days until due, overdue counts as due in a day
+        ctx.deadline_pressure = (pressure as f32 / 10.0).min(1.0);
+
+        // Expected energy of today's cycle phase
+        if let Some(day) = cycle_day {
+            ctx.cycle_phase = cycle::phase_energy(&day);
+        }
+
         // Streak days (check-in streak)
-        let streak: i64 = sqlx::query_scalar(
-            r#"
-            WITH RECURSIVE streak AS (
-                SELECT date('now') as d, 1 as count
-                UNION ALL
-                SELECT date(d, '-1 day'), count + 1 FROM streak
-                WHERE EXISTS (SELECT 1 FROM check_ins WHERE date(checked_in_at) = date(d, '-1 day'))
-                AND count < 100
-            )
-            SELECT MAX(count) FROM streak
-            WHERE EXISTS (SELECT 1 FROM check_ins WHERE date(checked_in_at) = d)
-            "#
-        )
-        .fetch_one(pool)
-        .await
-        .unwrap_or(0);
         ctx.streak_days = (streak as f32 / 30.0).min(1.0);
 
         // === Workout features ===
-        let hours_since_workout: Option<f64> = sqlx::query_scalar(
-            "SELECT (julianday('now') - julianday(logged_at)) * 24 FROM workouts ORDER BY logged_at DESC LIMIT 1"
-        )
-        .fetch_optional(pool)
-        .await
-        .map_err(|e| e.to_string())?;
         ctx.hours_since_workout = hours_since_workout.map(|h| (h as f32 / 48.0).min(1.0)).unwrap_or(1.0);
 
         // === Circadian features ===
@@ -629,57 +725,43 @@ impl RichFeatureStore {
         // Circadian phase (0 = wake, 1 = sleep)
         ctx.circadian_phase = ctx.time_since_wake;
 
-        // Creative vs analytical (morning = creative, afternoon = analytical)
-        ctx.optimal_creative = if hour >= 6.0 && hour <= 12.0 { 0.8 } else { 0.4 };
-        ctx.optimal_analytical = if hour >= 14.0 && hour <= 18.0 { 0.8 } else { 0.4 };
+        // Creative vs analytical from the learned chronotype; without one,
+        // morning = creative, afternoon = analytical
+        let (creative, analytical) = chronotype
+            .and_then(|c| c.suitability(now.hour()))
+            .unwrap_or_else(|| {
+                (
+                    if (6.0..=12.0).contains(&hour) { 0.8 } else { 0.4 },
+                    if (14.0..=18.0).contains(&hour) { 0.8 } else { 0.4 },
+                )
+            });
+        ctx.optimal_creative = creative;
+        ctx.optimal_analytical = analytical;
 
         // === Fatigue and recovery ===
-        // Estimate fatigue from recent session density
-        let recent_session_mins: i64 = sqlx::query_scalar(
-            "SELECT COALESCE(SUM(duration_minutes), 0) FROM sessions WHERE started_at >= datetime('now', '-4 hours')"
-        )
-        .fetch_one(pool)
-        .await
-        .unwrap_or(0);
-        ctx.fatigue_score = (recent_session_mins as f32 / 180.0).min(1.0); // 3 hours = max fatigue
+        // The spent share of today's energy budget
+        ctx.fatigue_score = energy.fatigue() as f32;
 
         // Recovery need based on fatigue and time since break
-        let hours_since_break: Option<f64> = sqlx::query_scalar(
-            r#"
-            SELECT MIN((julianday('now') - julianday(ended_at)) * 24) 
-            FROM sessions 
-            WHERE ended_at IS NOT NULL
-            "#
-        )
-        .fetch_optional(pool)
-        .await
-        .map_err(|e| e.to_string())?;
         ctx.hours_since_break = hours_since_break.map(|h| (h as f32 / 2.0).min(1.0)).unwrap_or(0.0);
         ctx.recovery_need = (ctx.fatigue_score * 0.6 + ctx.hours_since_break * 0.4).min(1.0);
 
         // === Weekly study hours ===
-        let week_study: i64 = sqlx::query_scalar(
-            "SELECT COALESCE(SUM(duration_minutes), 0) FROM sessions WHERE session_type = 'study' AND started_at >= date('now', 'weekday 0', '-7 days')"
-        )
-        .fetch_one(pool)
-        .await
-        .unwrap_or(0);
         ctx.study_hours_week = (week_study as f32 / 60.0) / 40.0; // Normalize to 40 hours
 
         // Target hours from courses
-        let target: i64 = sqlx::query_scalar(
-            "SELECT COALESCE(SUM(target_weekly_hours), 0) FROM courses"
-        )
-        .fetch_one(pool)
-        .await
-        .unwrap_or(0);
         ctx.target_hours_week = (target as f32 / 40.0).min(1.0);
-        ctx.workload_balance = if target > 0 {
+        ctx.workload_balance = if target > 0.0 {
             ((week_study as f32 / 60.0) / target as f32).clamp(0.0, 2.0)
         } else {
             1.0
         };
 
+        // === Focus trend (focus-mode samples) ===
+        if let Some(trend) = focus_trend {
+            ctx.focus_trend = trend;
+        }
+
         // === Compute interaction features ===
         ctx.energy_x_hour = ctx.energy_level * ctx.peak_focus_prob;
         ctx.mood_x_workload = ctx.mood_level * (1.0 - ctx.active_assignments);
@@ -688,13 +770,115 @@ impl RichFeatureStore {
         ctx.focus_x_complexity = ctx.focus_trend * ctx.assignment_urgency;
         ctx.recovery_x_intensity = ctx.recovery_need * ctx.fatigue_score;
         ctx.energy_traj_x_goals = ((ctx.energy_trajectory + 1.0) / 2.0) * ctx.assignment_urgency;
-        ctx.mood_traj_x_social = (ctx.mood_trajectory + 1.0) / 2.0; // Placeholder
+        ctx.mood_traj_x_social = ((ctx.mood_trajectory + 1.0) / 2.0) * social_time.unwrap_or(1.0);
         ctx.circadian_x_task = ctx.peak_focus_prob * ctx.optimal_analytical;
         ctx.history_x_current = ctx.similar_context_outcome * ctx.energy_level;
 
         Ok(ctx)
     }
 
+    /// Today's and yesterday's latest mood/energy, hours since the last
+    /// check-in and the check-in streak
+    async fn checkin_stats(pool: &Pool<Sqlite>, at: &str) -> Result<CheckinStats, String> {
+        sqlx::query_as(
+            r#"
+            WITH RECURSIVE streak AS (
+                SELECT date(?1) as d, 1 as count
+                UNION ALL
+                SELECT date(d, '-1 day'), count + 1 FROM streak
+                WHERE EXISTS (SELECT 1 FROM check_ins WHERE date(checked_in_at) = date(d, '-1 day'))
+                AND count < 100
+            )
+            SELECT
+                (SELECT mood FROM check_ins WHERE date(checked_in_at) = date(?1)
+                 ORDER BY checked_in_at DESC LIMIT 1),
+                (SELECT energy FROM check_ins WHERE date(checked_in_at) = date(?1)
+                 ORDER BY checked_in_at DESC LIMIT 1),
+                (SELECT mood FROM check_ins WHERE date(checked_in_at) = date(?1, '-1 day')
+                 ORDER BY checked_in_at DESC LIMIT 1),
+                (SELECT energy FROM check_ins WHERE date(checked_in_at) = date(?1, '-1 day')
+                 ORDER BY checked_in_at DESC LIMIT 1),
+                (SELECT (julianday(?1) - julianday(checked_in_at)) * 24 FROM check_ins
+                 ORDER BY checked_in_at DESC LIMIT 1),
+                (SELECT COALESCE(MAX(count), 0) FROM streak
+                 WHERE EXISTS (SELECT 1 FROM check_ins WHERE date(checked_in_at) = d))
+            "#,
+        )
+        .bind(at)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| e.to_string())
+    }
+
+    /// Session, practice, Big 3, workout and course-target figures
+    async fn activity_stats(pool: &Pool<Sqlite>, today: &str, at: &str) -> Result<ActivityStats, String> {
+        sqlx::query_as(
+            r#"
+            SELECT
+                (SELECT COUNT(*) FROM sessions
+                 WHERE session_type = 'study' AND date(started_at) = date(?2)),
+                (SELECT COALESCE(SUM(duration_minutes), 0) FROM sessions
+                 WHERE session_type = 'study' AND date(started_at) = date(?2)),
+                (SELECT COUNT(DISTINCT skill_id) FROM practice_logs WHERE logged_at >= date(?2, '-7 days')),
+                (SELECT COUNT(*) FROM skills),
+                (SELECT COUNT(*) FROM agent_big_three WHERE date = ?1),
+                (SELECT COALESCE(SUM(CASE WHEN is_completed = 1 THEN 1 ELSE 0 END), 0)
+                 FROM agent_big_three WHERE date = ?1),
+                (SELECT MIN((julianday(?2) - julianday(ended_at)) * 24) FROM sessions
+                 WHERE ended_at IS NOT NULL),
+                (SELECT COALESCE(SUM(duration_minutes), 0) FROM sessions
+                 WHERE session_type = 'study' AND started_at >= date(?2, 'weekday 0', '-7 days')),
+                (SELECT COALESCE(SUM(target_weekly_hours), 0.0) FROM courses),
+                (SELECT (julianday(?2) - julianday(logged_at)) * 24 FROM workouts
+                 ORDER BY logged_at DESC LIMIT 1)
+            "#,
+        )
+        .bind(today)
+        .bind(at)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| e.to_string())
+    }
+
+    /// Assignment counts, days to the next exam and deadline pressure
+    async fn deadline_stats(pool: &Pool<Sqlite>, at: &str) -> Result<DeadlineStats, String> {
+        sqlx::query_as(
+            r#"
+            SELECT
+                (SELECT COUNT(*) FROM assignments WHERE is_completed = 0 AND due_date < date(?1)),
+                (SELECT COUNT(*) FROM assignments WHERE is_completed = 0),
+                (SELECT COUNT(*) FROM assignments WHERE is_completed = 0 AND due_date = date(?1)),
+                (SELECT COUNT(*) FROM assignments
+                 WHERE is_completed = 0 AND due_date BETWEEN date(?1) AND date(?1, '+7 days')),
+                (SELECT MIN(julianday(exam_date) - julianday(?1)) FROM exams
+                 WHERE exam_date IS NOT NULL AND julianday(exam_date) >= julianday(?1)),
+                (SELECT COALESCE(SUM(
+                    (COALESCE(estimated_minutes, 60) / 60.0) / MAX(julianday(due_date) - julianday(?1), 1.0)
+                 ), 0.0)
+                 FROM assignments WHERE is_completed = 0 AND due_date IS NOT NULL)
+            "#,
+        )
+        .bind(at)
+        .fetch_one(pool)
+        .await
+        .map_err(|e| e.to_string())
+    }
+
+    /// Due date and priority of the five soonest incomplete assignments
+    async fn urgent_assignments(pool: &Pool<Sqlite>) -> Result<Vec<(Option<String>, i32)>, String> {
+        sqlx::query_as(
+            r#"
+            SELECT due_date, priority FROM assignments
+            WHERE is_completed = 0 AND due_date IS NOT NULL
+            ORDER BY due_date ASC
+            LIMIT 5
+            "#,
+        )
+        .fetch_all(pool)
+        .await
+        .map_err(|e| e.to_string())
+    }
+
     /// Save a rich context snapshot to the database
     pub async fn save_snapshot(pool: &Pool<Sqlite>, ctx: &RichContext) -> Result<i64, String> {
         let result = sqlx::query(
@@ -711,8 +895,10 @@ impl RichFeatureStore {
                 study_hours_this_week, target_study_hours_week, workload_balance,
                 energy_x_hour, mood_x_workload, streak_x_momentum, fatigue_x_time, focus_x_complexity,
                 recovery_x_intensity, energy_trajectory_x_goals, mood_trajectory_x_social, 
-                circadian_x_task_type, historical_x_current
-            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
+                circadian_x_task_type, historical_x_current,
+                days_to_next_exam, deadline_pressure, cycle_phase,
+                context_features
+            ) VALUES (?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?, ?)
             "#
         )
         .bind((ctx.hour_of_day * 23.0) as i32)
@@ -765,6 +951,10 @@ impl RichFeatureStore {
         .bind(ctx.mood_traj_x_social)
         .bind(ctx.circadian_x_task)
         .bind(ctx.history_x_current)
+        .bind(ctx.days_to_next_exam)
+        .bind(ctx.deadline_pressure)
+        .bind(ctx.cycle_phase)
+        .bind(ctx.to_bytes())
         .execute(pool)
         .await
         .map_err(|e| e.to_string())?;
@@ -772,3 +962,84 @@ impl RichFeatureStore {
         Ok(result.last_insert_rowid())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+    use std::str::FromStr;
+
+    async fn setup_pool_with_migrations() -> Pool<Sqlite> {
+        let options = SqliteConnectOptions::from_str("sqlite::memory:")
+            .unwrap()
+            .foreign_keys(false);
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect_with(options)
+            .await
+            .expect("Failed to connect to in-memory DB");
+
+        crate::db::migrations::run_migrations(&pool)
+            .await
+            .expect("Failed to run migrations");
+
+        pool
+    }
+
+    #[tokio::test]
+    async fn capture_context_reads_activity_from_the_combined_queries() {
+        let pool = setup_pool_with_migrations().await;
+        sqlx::query("INSERT INTO check_ins (mood, energy, checked_in_at) VALUES (10, 10, datetime('now'))")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query(
+            "INSERT INTO sessions (session_type, duration_minutes, started_at) VALUES ('study', 240, datetime('now'))",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let ctx = RichFeatureStore::capture_context(&pool).await.unwrap();
+        assert_eq!(ctx.energy_level, 1.0);
+        assert_eq!(ctx.mood_level, 1.0);
+        assert_eq!(ctx.study_minutes_today, 0.5);
+        assert!(ctx.streak_days > 0.0);
+        // Nothing scheduled: no deadline pressure, exam far away
+        assert_eq!(ctx.deadline_pressure, 0.0);
+        assert_eq!(ctx.days_to_next_exam, 1.0);
+    }
+
+    #[tokio::test]
+    async fn capture_context_replays_at_a_fixed_time() {
+        use chrono::TimeZone;
+
+        let pool = setup_pool_with_migrations().await;
+        let at = Local.with_ymd_and_hms(2026, 3, 2, 23, 5, 0).unwrap();
+        let at_sql = at.with_timezone(&Utc).format("%Y-%m-%d %H:%M:%S").to_string();
+        sqlx::query("INSERT INTO check_ins (mood, energy, checked_in_at) VALUES (10, 10, ?)")
+            .bind(&at_sql)
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO sessions (session_type, duration_minutes, started_at) VALUES ('study', 240, ?)")
+            .bind(&at_sql)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let _clock = clock::freeze(at);
+        let ctx = RichFeatureStore::capture_context_uncached(&pool).await.unwrap();
+        assert_eq!(ctx.hour_of_day, 1.0);
+        assert_eq!(ctx.energy_level, 1.0);
+        assert_eq!(ctx.study_minutes_today, 0.5);
+        assert_eq!(ctx.hours_since_checkin, 0.0);
+
+        let replayed = RichFeatureStore::capture_context_at(&pool, at).await.unwrap();
+        assert_eq!(replayed.to_feature_vector(), ctx.to_feature_vector());
+
+        // Two days on, the session no longer counts as today's
+        let later = RichFeatureStore::capture_context_at(&pool, at + chrono::Duration::days(2)).await.unwrap();
+        assert_eq!(later.study_minutes_today, 0.0);
+    }
+}
@@ -54,9 +54,7 @@ impl SemanticMemory {
             return Ok(mem.clone());
         }
 
-        let db_path = dirs::data_dir()
-            .map(|p| p.join("com.tauri.dev").join("lancedb"))
-            .unwrap_or_else(|| std::path::PathBuf::from("./lancedb"));
+        let db_path = crate::platform::data_dir().join("lancedb");
 
         let memory = Self::new(&db_path.to_string_lossy()).await?;
         let arc = Arc::new(memory);
@@ -27,6 +27,80 @@ pub enum ProfileValue {
     Threshold(f64),
     /// Preference score (0-1 scale)
     Preference(f64),
+    /// Learned daily rhythm
+    Chronotype(Chronotype),
+}
+
+/// Profile dimension holding the learned chronotype
+pub const CHRONOTYPE_DIMENSION: &str = "chronotype";
+
+/// Rated sessions and check-ins needed before a chronotype is stored
+const MIN_CHRONOTYPE_SAMPLES: i64 = 10;
+
+/// When in the day the user does their best work
+///
+/// Each hour's quality blends the average focus rating of sessions started in
+/// it with the average check-in energy logged in it, both scaled to 0-1 and
+/// weighted by their counts, then smoothed with the neighbouring hours.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Chronotype {
+    /// "morning", "intermediate" or "evening"
+    pub kind: String,
+    /// Local hour with the best quality
+    pub peak_hour: i32,
+    /// Quality (0-1) per local hour; None for hours without data nearby
+    pub hourly_quality: Vec<Option<f64>>,
+}
+
+impl Chronotype {
+    /// Build from per-hour (quality sum, sample count), hours 0-23
+    fn from_hourly(samples: &[(f64, i64); 24]) -> Option<Self> {
+        let hourly_quality: Vec<Option<f64>> = (0..24)
+            .map(|hour| {
+                // The hour counts double against each neighbour
+                let (sum, count) = [(23, 1.0), (0, 2.0), (1, 1.0)].iter().fold((0.0, 0.0), |acc, &(offset, weight)| {
+                    let (s, n) = samples[(hour + offset) % 24];
+                    (acc.0 + s * weight, acc.1 + n as f64 * weight)
+                });
+                (count > 0.0).then(|| (sum / count * 100.0).round() / 100.0)
+            })
+            .collect();
+
+        // Ties (smoothing spreads a lone hour over its neighbours) go to the
+        // hour with more samples
+        let (peak_hour, _) = hourly_quality
+            .iter()
+            .enumerate()
+            .filter_map(|(hour, q)| q.map(|q| (hour, q)))
+            .max_by(|a, b| a.1.total_cmp(&b.1).then(samples[a.0].1.cmp(&samples[b.0].1)))?;
+        let kind = match peak_hour {
+            5..=10 => "morning",
+            11..=15 => "intermediate",
+            _ => "evening",
+        };
+
+        Some(Self {
+            kind: kind.to_string(),
+            peak_hour: peak_hour as i32,
+            hourly_quality,
+        })
+    }
+
+    /// (creative, analytical) suitability of `hour`, or None without data
+    ///
+    /// Analytical work follows the quality curve; creative work does best a
+    /// little off-peak, when attention is looser.
+    pub fn suitability(&self, hour: u32) -> Option<(f32, f32)> {
+        let quality = (*self.hourly_quality.get(hour as usize)?)?;
+        let known: Vec<f64> = self.hourly_quality.iter().flatten().copied().collect();
+        let min = known.iter().copied().fold(f64::INFINITY, f64::min);
+        let max = known.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        let relative = if max > min { (quality - min) / (max - min) } else { 0.5 };
+        let analytical = 0.4 + 0.4 * relative;
+        // Peaks at a relative quality of 0.5
+        let creative = 0.8 - 0.8 * (relative - 0.5).abs();
+        Some((creative as f32, analytical as f32))
+    }
 }
 
 impl UserProfile {
@@ -209,11 +283,59 @@ impl UserProfile {
         Ok(())
     }
 
+    /// Learn the chronotype from rated sessions and check-in energy by hour
+    pub async fn learn_chronotype(pool: &Pool<Sqlite>) -> Result<Option<Chronotype>, String> {
+        let rows: Vec<(i64, f64, i64)> = sqlx::query_as(
+            r#"
+            SELECT hour, SUM(quality), COUNT(*) FROM (
+                SELECT CAST(strftime('%H', started_at, 'localtime') AS INTEGER) AS hour,
+                       (focus_rating - 1) / 4.0 AS quality
+                FROM sessions
+                WHERE focus_rating IS NOT NULL
+                UNION ALL
+                SELECT CAST(strftime('%H', checked_in_at, 'localtime') AS INTEGER),
+                       (energy - 1) / 9.0
+                FROM check_ins
+                WHERE energy IS NOT NULL
+            )
+            GROUP BY hour
+            "#
+        )
+        .fetch_all(pool)
+        .await
+        .map_err(|e| e.to_string())?;
+
+        if rows.iter().map(|r| r.2).sum::<i64>() < MIN_CHRONOTYPE_SAMPLES {
+            return Ok(None);
+        }
+        let mut samples = [(0.0, 0); 24];
+        for (hour, sum, count) in rows {
+            if let Some(slot) = samples.get_mut(hour as usize) {
+                *slot = (sum, count);
+            }
+        }
+
+        let chronotype = Chronotype::from_hourly(&samples);
+        if let Some(chronotype) = &chronotype {
+            Self::update_dimension(pool, CHRONOTYPE_DIMENSION, &ProfileValue::Chronotype(chronotype.clone())).await?;
+        }
+        Ok(chronotype)
+    }
+
+    /// The stored chronotype, if one has been learned
+    pub async fn chronotype(pool: &Pool<Sqlite>) -> Result<Option<Chronotype>, String> {
+        Ok(match Self::get_dimension(pool, CHRONOTYPE_DIMENSION).await? {
+            Some((ProfileValue::Chronotype(chronotype), _)) => Some(chronotype),
+            _ => None,
+        })
+    }
+
     /// Run all profile learning tasks
     pub async fn learn_all(pool: &Pool<Sqlite>) -> Result<(), String> {
         Self::learn_study_preferences(pool).await?;
         Self::learn_workout_preferences(pool).await?;
         Self::learn_wellbeing_baselines(pool).await?;
+        Self::learn_chronotype(pool).await?;
         Ok(())
     }
 
@@ -227,3 +349,61 @@ impl UserProfile {
         .map_err(|e| e.to_string())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Local, TimeZone, Utc};
+
+    /// A local hour on the given day, as stored (UTC)
+    fn utc(day: u32, hour: u32) -> String {
+        Local
+            .with_ymd_and_hms(2030, 4, day, hour, 0, 0)
+            .unwrap()
+            .with_timezone(&Utc)
+            .format("%Y-%m-%d %H:%M:%S")
+            .to_string()
+    }
+
+    async fn rated_session(pool: &Pool<Sqlite>, day: u32, hour: u32, focus: i64) {
+        sqlx::query("INSERT INTO sessions (session_type, started_at, duration_minutes, focus_rating) VALUES ('study', ?, 60, ?)")
+            .bind(utc(day, hour))
+            .bind(focus)
+            .execute(pool)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn learns_a_morning_chronotype_from_focus_and_energy() {
+        let pool = crate::test_support::pool().await;
+        for day in 1..=5 {
+            rated_session(&pool, day, 8, 5).await;
+        }
+        assert!(UserProfile::learn_chronotype(&pool).await.unwrap().is_none());
+
+        rated_session(&pool, 6, 8, 5).await;
+        for day in 1..=4 {
+            rated_session(&pool, day, 20, 2).await;
+        }
+        for day in 1..=2 {
+            sqlx::query("INSERT INTO check_ins (mood, energy, checked_in_at) VALUES (7, 10, ?)")
+                .bind(utc(day, 8))
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+
+        UserProfile::learn_chronotype(&pool).await.unwrap();
+        let chronotype = UserProfile::chronotype(&pool).await.unwrap().unwrap();
+        assert_eq!(chronotype.kind, "morning");
+        assert_eq!(chronotype.peak_hour, 8);
+        assert_eq!(chronotype.hourly_quality[8], Some(1.0));
+        assert_eq!(chronotype.hourly_quality[20], Some(0.25));
+        assert_eq!(chronotype.hourly_quality[3], None);
+
+        assert_eq!(chronotype.suitability(8), Some((0.4, 0.8)));
+        assert_eq!(chronotype.suitability(20), Some((0.4, 0.4)));
+        assert_eq!(chronotype.suitability(3), None);
+    }
+}
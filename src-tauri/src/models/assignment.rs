@@ -12,4 +12,20 @@ pub struct Assignment {
     pub is_completed: Option<i64>,
     pub completed_at: Option<String>,
     pub created_at: Option<String>,
+    pub score: Option<f64>,
+    pub estimated_minutes: Option<i64>,
+    pub actual_minutes: Option<i64>,
+}
+
+/// One step of an assignment's submission checklist
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct AssignmentChecklistItem {
+    pub id: i64,
+    pub assignment_id: i64,
+    pub label: String,
+    /// Required items must be done before the assignment is marked done
+    pub is_required: bool,
+    pub is_done: bool,
+    pub done_at: Option<String>,
+    pub position: i64,
 }
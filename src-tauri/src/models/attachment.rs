@@ -0,0 +1,14 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct Attachment {
+    pub id: i64,
+    pub owner_type: String,
+    pub owner_id: i64,
+    pub kind: String,
+    pub file_name: String,
+    pub stored_name: String,
+    pub size_bytes: i64,
+    pub created_at: Option<String>,
+}
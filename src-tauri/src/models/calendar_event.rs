@@ -16,5 +16,7 @@ pub struct CalendarEvent {
     pub linked_id: Option<i64>,
     pub locked: Option<i64>,
     pub notes: Option<String>,
+    /// Import source tag for events read from an .ics file
+    pub source: Option<String>,
     pub created_at: Option<String>,
 }
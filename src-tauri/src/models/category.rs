@@ -0,0 +1,12 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct Category {
+    pub id: i64,
+    pub name: String,
+    pub color: String,
+    pub is_busy: bool,
+    pub is_builtin: bool,
+    pub created_at: Option<String>,
+}
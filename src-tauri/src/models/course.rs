@@ -14,4 +14,8 @@ pub struct Course {
     pub created_at: Option<String>,
     pub current_grade: Option<f64>,
     pub target_grade: Option<f64>,
+    /// Shortest study block the planner suggests for the course
+    pub min_block_minutes: Option<i64>,
+    /// Weekdays to plan study on, JSON array with 0 = Sunday; null is any day
+    pub preferred_days: Option<String>,
 }
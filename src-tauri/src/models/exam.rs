@@ -12,5 +12,8 @@ pub struct Exam {
     pub notes: Option<String>,
     pub grade: Option<f64>,
     pub weight: Option<f64>,
+    pub score: Option<f64>,
+    pub max_score: Option<f64>,
+    pub post_mortem: Option<String>,
     pub created_at: Option<String>,
 }
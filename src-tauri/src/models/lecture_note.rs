@@ -0,0 +1,15 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct LectureNote {
+    pub id: i64,
+    pub course_id: i64,
+    pub course_meeting_id: Option<i64>,
+    pub meeting_date: String,
+    pub session_id: Option<i64>,
+    pub topic: Option<String>,
+    pub content: String,
+    pub created_at: Option<String>,
+    pub updated_at: Option<String>,
+}
@@ -1,5 +1,7 @@
 pub mod assignment;
+pub mod attachment;
 pub mod calendar_event;
+pub mod category;
 pub mod checkin;
 pub mod course;
 pub mod course_meeting;
@@ -9,8 +11,10 @@ pub mod google_account;
 pub mod google_calendar_pref;
 pub mod google_event_link;
 pub mod google_sync_state;
+pub mod lecture_note;
 pub mod session;
 pub mod skill;
+pub mod technique;
 pub mod user;
 pub mod week_plan_block;
 pub mod weekly_task;
@@ -1,11 +1,58 @@
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 
-#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
-#[serde(rename_all = "snake_case")]
+/// The built-in types plus any registered in `session_types`; custom names
+/// are lowercase slugs
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+#[serde(try_from = "String", into = "String")]
 pub enum SessionType {
     Study,
     Practice,
+    Custom(String),
+}
+
+impl SessionType {
+    pub fn as_str(&self) -> &str {
+        match self {
+            SessionType::Study => "study",
+            SessionType::Practice => "practice",
+            SessionType::Custom(name) => name,
+        }
+    }
+}
+
+impl TryFrom<String> for SessionType {
+    type Error = String;
+
+    fn try_from(raw: String) -> Result<Self, Self::Error> {
+        match raw.as_str() {
+            "study" => return Ok(SessionType::Study),
+            "practice" => return Ok(SessionType::Practice),
+            _ => {}
+        }
+        if is_slug(&raw) {
+            Ok(SessionType::Custom(raw))
+        } else {
+            Err(format!("invalid session_type: {}", raw))
+        }
+    }
+}
+
+impl From<SessionType> for String {
+    fn from(session_type: SessionType) -> Self {
+        match session_type {
+            SessionType::Custom(name) => name,
+            builtin => builtin.as_str().to_string(),
+        }
+    }
+}
+
+/// 1-40 characters of lowercase letters, digits and underscores, starting
+/// with a letter
+pub fn is_slug(name: &str) -> bool {
+    name.len() <= 40
+        && name.starts_with(|c: char| c.is_ascii_lowercase())
+        && name.chars().all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
 }
 
 impl sqlx::Type<sqlx::Sqlite> for SessionType {
@@ -23,11 +70,7 @@ impl<'q> sqlx::Encode<'q, sqlx::Sqlite> for SessionType {
         &self,
         buf: &mut Vec<sqlx::sqlite::SqliteArgumentValue<'q>>,
     ) -> Result<sqlx::encode::IsNull, sqlx::error::BoxDynError> {
-        let value = match self {
-            SessionType::Study => "study",
-            SessionType::Practice => "practice",
-        };
-        <&str as sqlx::Encode<sqlx::Sqlite>>::encode_by_ref(&value, buf)
+        <String as sqlx::Encode<sqlx::Sqlite>>::encode_by_ref(&self.as_str().to_string(), buf)
     }
 }
 
@@ -36,11 +79,7 @@ impl<'r> sqlx::Decode<'r, sqlx::Sqlite> for SessionType {
         value: sqlx::sqlite::SqliteValueRef<'r>,
     ) -> Result<Self, Box<dyn std::error::Error + Send + Sync>> {
         let raw = <String as sqlx::Decode<sqlx::Sqlite>>::decode(value)?;
-        match raw.as_str() {
-            "study" => Ok(SessionType::Study),
-            "practice" => Ok(SessionType::Practice),
-            other => Err(format!("invalid session_type: {}", other).into()),
-        }
+        Ok(SessionType::try_from(raw)?)
     }
 }
 
@@ -55,6 +94,15 @@ pub struct Session {
     pub ended_at: Option<String>,
     pub duration_minutes: Option<i64>,
     pub notes: Option<String>,
+    pub planned_minutes: Option<i64>,
+    pub focus_rating: Option<i64>,
+    pub focus_profile_id: Option<i64>,
+    pub title: Option<String>,
+    /// Set when the session was started from a course meeting
+    pub course_meeting_id: Option<i64>,
+    pub meeting_date: Option<String>,
+    /// Set when the session was started from a plan block
+    pub week_plan_block_id: Option<i64>,
 }
 
 #[cfg(test)]
@@ -70,7 +118,11 @@ mod tests {
         let parsed: SessionType = serde_json::from_str("\"practice\"").unwrap();
         assert_eq!(parsed, SessionType::Practice);
 
-        assert!(serde_json::from_str::<SessionType>("\"invalid\"").is_err());
+        let parsed: SessionType = serde_json::from_str("\"thesis_writing\"").unwrap();
+        assert_eq!(parsed, SessionType::Custom("thesis_writing".to_string()));
+        assert_eq!(serde_json::to_string(&parsed).unwrap(), "\"thesis_writing\"");
+
+        assert!(serde_json::from_str::<SessionType>("\"Not A Slug\"").is_err());
     }
 
     #[tokio::test]
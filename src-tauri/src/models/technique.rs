@@ -0,0 +1,11 @@
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+
+#[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
+pub struct Technique {
+    pub id: i64,
+    pub name: String,
+    pub description: Option<String>,
+    pub is_builtin: bool,
+    pub created_at: Option<String>,
+}
@@ -15,4 +15,22 @@ pub struct WeekPlanBlock {
     pub status: Option<String>,
     pub rationale_json: Option<String>,
     pub created_at: Option<String>,
+    /// Assignment the block was scheduled for
+    pub assignment_id: Option<i64>,
+    /// Exam the block prepares for
+    pub exam_id: Option<i64>,
+    /// Shared by the pomodoro sub-blocks split from one block
+    pub split_group: Option<String>,
+    /// `in_progress` while a session started from the block runs, then `done`
+    pub progress: Option<String>,
+    /// Length of the session started from the block, once it has ended
+    pub actual_minutes: Option<i64>,
+    /// Set by nightly maintenance when the block ended without a session
+    pub missed_at: Option<String>,
+    /// Replacement placed later in the week for a missed block
+    pub rescheduled_to_id: Option<i64>,
+    /// Skill a practice block is for
+    pub skill_id: Option<i64>,
+    /// Template a workout block suggests
+    pub workout_template_id: Option<i64>,
 }
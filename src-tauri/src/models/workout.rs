@@ -9,6 +9,8 @@ pub struct Workout {
     pub duration_minutes: Option<i64>,
     pub notes: Option<String>,
     pub logged_at: Option<String>,
+    /// Markdown notes; `notes` is the short summary
+    pub notes_md: Option<String>,
 }
 
 #[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
@@ -30,6 +32,12 @@ pub struct WorkoutTemplate {
     pub name: String,
     pub created_at: Option<String>,
     pub updated_at: Option<String>,
+    /// Program weekdays as a JSON array (0 = Sunday); null means any day
+    pub schedule_days: Option<String>,
+    /// What the workout trains, e.g. "legs"; used for recovery spacing
+    pub muscle_group: Option<String>,
+    pub is_heavy: bool,
+    pub planned_minutes: Option<i64>,
 }
 
 #[derive(Debug, Serialize, Deserialize, FromRow, Clone)]
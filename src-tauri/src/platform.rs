@@ -0,0 +1,159 @@
+//! Platform Services
+//!
+//! Where app data lives and where secrets are kept, on desktop and mobile.
+//!
+//! - Data: the app's data directory as resolved by Tauri (set once at
+//!   startup). On desktop this is the same `<data dir>/com.tauri.dev` the
+//!   app has always used; on Android/iOS it is the app's sandboxed storage,
+//!   where `dirs::data_dir()` resolves to nothing useful.
+//! - Secrets: the OS keyring on desktop. Mobile has no keyring crate
+//!   support, so secrets go to a file in app-private storage, which other
+//!   apps cannot read.
+
+use std::path::PathBuf;
+
+use once_cell::sync::OnceCell;
+
+use crate::error::ApiError;
+
+/// Service name secrets are stored under
+const SECRET_SERVICE: &str = "life-os";
+
+static APP_DATA_DIR: OnceCell<PathBuf> = OnceCell::new();
+
+/// Record the app data directory; call once during setup
+pub fn init(app_handle: &tauri::AppHandle) {
+    use tauri::Manager;
+
+    match app_handle.path().app_data_dir() {
+        Ok(dir) => {
+            let _ = APP_DATA_DIR.set(dir);
+        }
+        Err(e) => log::warn!("Failed to resolve app data dir: {}", e),
+    }
+}
+
+/// Directory for app-managed data (models, vector store, secrets on mobile)
+pub fn data_dir() -> PathBuf {
+    APP_DATA_DIR.get().cloned().unwrap_or_else(|| {
+        dirs::data_dir()
+            .map(|p| p.join("com.tauri.dev"))
+            .unwrap_or_else(|| PathBuf::from("."))
+    })
+}
+
+/// Read a secret; `None` if it was never stored
+pub fn get_secret(name: &str) -> Result<Option<String>, ApiError> {
+    store::get(name)
+}
+
+pub fn set_secret(name: &str, value: &str) -> Result<(), ApiError> {
+    store::set(name, value)
+}
+
+/// Remove a secret; removing a missing one is not an error
+pub fn delete_secret(name: &str) -> Result<(), ApiError> {
+    store::delete(name)
+}
+
+#[cfg(desktop)]
+mod store {
+    use keyring::Entry;
+
+    use super::SECRET_SERVICE;
+    use crate::error::ApiError;
+
+    fn entry(name: &str) -> Result<Entry, ApiError> {
+        Entry::new(SECRET_SERVICE, name).map_err(|e| ApiError::internal(e.to_string()))
+    }
+
+    pub fn get(name: &str) -> Result<Option<String>, ApiError> {
+        match entry(name)?.get_password() {
+            Ok(value) => Ok(Some(value)),
+            Err(keyring::Error::NoEntry) => Ok(None),
+            Err(e) => Err(ApiError::internal(e.to_string())),
+        }
+    }
+
+    pub fn set(name: &str, value: &str) -> Result<(), ApiError> {
+        entry(name)?
+            .set_password(value)
+            .map_err(|e| ApiError::internal(e.to_string()))
+    }
+
+    pub fn delete(name: &str) -> Result<(), ApiError> {
+        match entry(name)?.delete_credential() {
+            Ok(_) | Err(keyring::Error::NoEntry) => Ok(()),
+            Err(e) => Err(ApiError::internal(e.to_string())),
+        }
+    }
+}
+
+#[cfg(mobile)]
+mod store {
+    use std::collections::HashMap;
+    use std::path::PathBuf;
+
+    use parking_lot::{const_mutex, Mutex};
+
+    use super::{data_dir, SECRET_SERVICE};
+    use crate::error::ApiError;
+
+    /// Serializes read-modify-write of the secrets file
+    static LOCK: Mutex<()> = const_mutex(());
+
+    fn path() -> PathBuf {
+        data_dir().join(format!("{}.secrets.json", SECRET_SERVICE))
+    }
+
+    fn read() -> Result<HashMap<String, String>, ApiError> {
+        match std::fs::read(path()) {
+            Ok(bytes) => serde_json::from_slice(&bytes).map_err(|e| ApiError::internal(e.to_string())),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(HashMap::new()),
+            Err(e) => Err(ApiError::internal(e.to_string())),
+        }
+    }
+
+    fn write(secrets: &HashMap<String, String>) -> Result<(), ApiError> {
+        use std::io::Write;
+        use std::os::unix::fs::OpenOptionsExt;
+
+        let path = path();
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).map_err(|e| ApiError::internal(e.to_string()))?;
+        }
+        let bytes = serde_json::to_vec(secrets).map_err(|e| ApiError::internal(e.to_string()))?;
+        let tmp = path.with_extension("tmp");
+        let mut file = std::fs::OpenOptions::new()
+            .write(true)
+            .create(true)
+            .truncate(true)
+            .mode(0o600)
+            .open(&tmp)
+            .map_err(|e| ApiError::internal(e.to_string()))?;
+        file.write_all(&bytes).map_err(|e| ApiError::internal(e.to_string()))?;
+        file.sync_all().map_err(|e| ApiError::internal(e.to_string()))?;
+        std::fs::rename(&tmp, &path).map_err(|e| ApiError::internal(e.to_string()))
+    }
+
+    pub fn get(name: &str) -> Result<Option<String>, ApiError> {
+        let _guard = LOCK.lock();
+        Ok(read()?.remove(name))
+    }
+
+    pub fn set(name: &str, value: &str) -> Result<(), ApiError> {
+        let _guard = LOCK.lock();
+        let mut secrets = read()?;
+        secrets.insert(name.to_string(), value.to_string());
+        write(&secrets)
+    }
+
+    pub fn delete(name: &str) -> Result<(), ApiError> {
+        let _guard = LOCK.lock();
+        let mut secrets = read()?;
+        if secrets.remove(name).is_some() {
+            write(&secrets)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,527 @@
+//! Quick Actions
+//!
+//! Global keyboard shortcuts and tray menu entries for the few things worth
+//! doing without opening the window: toggling a pomodoro (study session),
+//! a quick check-in and quick add. Bindings live in the `global_shortcuts`
+//! setting and are validated here (syntax, duplicates, reserved system
+//! shortcuts) before they are stored; they are re-registered whenever the
+//! setting changes.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::{Pool, Sqlite};
+use tauri::Manager;
+#[cfg(desktop)]
+use tauri::Emitter;
+
+use crate::commands::sessions::{close_session, insert_session, SessionInput};
+use crate::deep_link::DeepLinkOutcome;
+use crate::error::ApiError;
+use crate::models::session::SessionType;
+use crate::services::settings;
+#[cfg(desktop)]
+use crate::DbState;
+
+/// Frontend event carrying a `QuickActionEvent`
+pub const QUICK_ACTION_EVENT: &str = "quick-action";
+
+#[cfg(desktop)]
+const TRAY_ID: &str = "main";
+const DEFAULT_POMODORO_MINUTES: i64 = 25;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QuickAction {
+    TogglePomodoro,
+    QuickCheckin,
+    QuickAdd,
+}
+
+impl QuickAction {
+    pub const ALL: &'static [QuickAction] = &[
+        QuickAction::TogglePomodoro,
+        QuickAction::QuickCheckin,
+        QuickAction::QuickAdd,
+    ];
+
+    pub fn id(self) -> &'static str {
+        match self {
+            QuickAction::TogglePomodoro => "toggle_pomodoro",
+            QuickAction::QuickCheckin => "quick_checkin",
+            QuickAction::QuickAdd => "quick_add",
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            QuickAction::TogglePomodoro => "Start/Stop Pomodoro",
+            QuickAction::QuickCheckin => "Quick Check-in",
+            QuickAction::QuickAdd => "Quick Add",
+        }
+    }
+
+    pub fn from_id(id: &str) -> Option<Self> {
+        Self::ALL.iter().copied().find(|a| a.id() == id)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct QuickActionEvent {
+    pub action: QuickAction,
+    pub outcome: Option<DeepLinkOutcome>,
+    pub error: Option<String>,
+}
+
+/// A quick action with its binding and whether the OS accepted it
+#[derive(Debug, Clone, Serialize)]
+pub struct QuickActionStatus {
+    pub action: QuickAction,
+    pub label: &'static str,
+    pub shortcut: Option<String>,
+    pub registered: bool,
+    pub error: Option<String>,
+}
+
+/// Registration errors from the last (re-)registration, by action
+#[derive(Default)]
+pub struct ShortcutState {
+    bindings: Mutex<HashMap<u32, QuickAction>>,
+    errors: Mutex<HashMap<QuickAction, String>>,
+}
+
+// ============================================================================
+// ACCELERATORS
+// ============================================================================
+
+/// Modifiers in canonical order, with accepted aliases
+const MODIFIERS: &[(&str, &[&str])] = &[
+    ("CmdOrCtrl", &["cmdorctrl", "commandorcontrol"]),
+    ("Ctrl", &["ctrl", "control"]),
+    ("Cmd", &["cmd", "command", "super", "meta"]),
+    ("Alt", &["alt", "option"]),
+    ("Shift", &["shift"]),
+];
+
+const NAMED_KEYS: &[&str] = &[
+    "Space", "Enter", "Tab", "Backspace", "Delete", "Escape", "Up", "Down", "Left", "Right", "Home", "End",
+    "PageUp", "PageDown",
+];
+
+/// Shortcuts the OS or every app already uses; CmdOrCtrl expands to both
+const RESERVED: &[&str] = &[
+    "CmdOrCtrl+C", "CmdOrCtrl+V", "CmdOrCtrl+X", "CmdOrCtrl+Z", "CmdOrCtrl+A", "CmdOrCtrl+S", "CmdOrCtrl+Q",
+    "CmdOrCtrl+W", "CmdOrCtrl+Tab", "CmdOrCtrl+Shift+Z", "Cmd+Space", "Alt+Tab", "Alt+F4",
+];
+
+/// Parse an accelerator into canonical form ("shift+cmdorctrl+p" -> "CmdOrCtrl+Shift+P")
+pub fn normalize_accelerator(accelerator: &str) -> Result<String, ApiError> {
+    let invalid = |reason: &str| ApiError::validation(format!("Invalid shortcut '{}': {}", accelerator, reason));
+
+    let parts: Vec<&str> = accelerator.split('+').map(str::trim).collect();
+    let (key, modifiers) = parts.split_last().ok_or_else(|| invalid("empty"))?;
+
+    let mut present = [false; MODIFIERS.len()];
+    for part in modifiers {
+        let lower = part.to_ascii_lowercase();
+        let index = MODIFIERS
+            .iter()
+            .position(|(_, aliases)| aliases.contains(&lower.as_str()))
+            .ok_or_else(|| invalid(&format!("unknown modifier '{}'", part)))?;
+        if present[index] {
+            return Err(invalid("repeated modifier"));
+        }
+        present[index] = true;
+    }
+    // Shift alone would swallow ordinary typing
+    if !present.iter().zip(MODIFIERS).any(|(p, (name, _))| *p && *name != "Shift") {
+        return Err(invalid("needs Ctrl, Cmd or Alt"));
+    }
+
+    let key = if key.len() == 1 && key.chars().all(|c| c.is_ascii_alphanumeric()) {
+        key.to_ascii_uppercase()
+    } else if let Some(n) = key
+        .strip_prefix(['F', 'f'])
+        .and_then(|n| n.parse::<u8>().ok())
+        .filter(|n| (1..=24).contains(n))
+    {
+        format!("F{}", n)
+    } else if let Some(named) = NAMED_KEYS.iter().find(|k| k.eq_ignore_ascii_case(key)) {
+        named.to_string()
+    } else {
+        return Err(invalid(&format!("unsupported key '{}'", key)));
+    };
+
+    let mut canonical: Vec<&str> = MODIFIERS
+        .iter()
+        .zip(present)
+        .filter(|(_, p)| *p)
+        .map(|((name, _), _)| *name)
+        .collect();
+    canonical.push(&key);
+    Ok(canonical.join("+"))
+}
+
+/// Whether two canonical accelerators fire on the same keys on some platform
+fn accelerators_clash(a: &str, b: &str) -> bool {
+    let expand = |s: &str| -> Vec<String> {
+        if s.contains("CmdOrCtrl") {
+            vec![s.replace("CmdOrCtrl", "Cmd"), s.replace("CmdOrCtrl", "Ctrl")]
+        } else {
+            vec![s.to_string()]
+        }
+    };
+    expand(a).iter().any(|x| expand(b).contains(x))
+}
+
+/// Validate the `global_shortcuts` setting: `{ action: accelerator | null }`
+pub fn validate_bindings(value: Value) -> Result<Value, ApiError> {
+    let Value::Object(map) = value else {
+        return Err(ApiError::validation("Shortcuts must be an object of action -> shortcut"));
+    };
+
+    let mut bindings: Vec<(QuickAction, String)> = Vec::new();
+    for (id, accelerator) in &map {
+        let action = QuickAction::from_id(id)
+            .ok_or_else(|| ApiError::validation(format!("Unknown quick action '{}'", id)))?;
+        match accelerator {
+            Value::Null => {}
+            Value::String(s) if s.trim().is_empty() => {}
+            Value::String(s) => bindings.push((action, normalize_accelerator(s)?)),
+            _ => return Err(ApiError::validation(format!("Shortcut for '{}' must be text or null", id))),
+        }
+    }
+
+    for (i, (action, accelerator)) in bindings.iter().enumerate() {
+        if let Some(reserved) = RESERVED.iter().find(|r| accelerators_clash(accelerator, r)) {
+            return Err(ApiError::conflict(format!(
+                "{} is reserved by the system ({})",
+                accelerator, reserved
+            )));
+        }
+        if let Some((other, _)) = bindings[..i].iter().find(|(_, a)| accelerators_clash(accelerator, a)) {
+            return Err(ApiError::conflict(format!(
+                "{} is used by both '{}' and '{}'",
+                accelerator,
+                other.id(),
+                action.id()
+            )));
+        }
+    }
+
+    Ok(Value::Object(
+        QuickAction::ALL
+            .iter()
+            .map(|a| {
+                let bound = bindings.iter().find(|(b, _)| b == a).map(|(_, s)| s.clone());
+                (a.id().to_string(), bound.map_or(Value::Null, Value::String))
+            })
+            .collect(),
+    ))
+}
+
+async fn get_bindings(pool: &Pool<Sqlite>) -> Result<Vec<(QuickAction, Option<String>)>, ApiError> {
+    let value = settings::get(pool, "global_shortcuts").await?;
+    Ok(QuickAction::ALL
+        .iter()
+        .map(|a| (*a, value.get(a.id()).and_then(Value::as_str).map(str::to_string)))
+        .collect())
+}
+
+// ============================================================================
+// ACTIONS
+// ============================================================================
+
+/// Run a quick action; check-in and quick add open their views in the frontend
+pub async fn run(pool: &Pool<Sqlite>, action: QuickAction) -> Result<DeepLinkOutcome, ApiError> {
+    match action {
+        QuickAction::TogglePomodoro => {
+            let open: Option<i64> = sqlx::query_scalar(
+                "SELECT id FROM sessions WHERE ended_at IS NULL ORDER BY started_at DESC, id DESC LIMIT 1",
+            )
+            .fetch_optional(pool)
+            .await
+            .map_err(ApiError::from)?;
+
+            match open {
+                Some(id) => Ok(DeepLinkOutcome::SessionEnded {
+                    session: close_session(pool, id, None).await?,
+                }),
+                None => {
+                    let session = insert_session(
+                        pool,
+                        &SessionInput {
+                            user_id: None,
+                            session_type: SessionType::Study,
+                            reference_id: None,
+                            reference_type: None,
+                            started_at: None,
+                            notes: None,
+                            planned_minutes: Some(DEFAULT_POMODORO_MINUTES),
+                            focus_profile_id: None,
+                            title: None,
+                            course_meeting_id: None,
+                            meeting_date: None,
+                            week_plan_block_id: None,
+                        },
+                    )
+                    .await?;
+                    Ok(DeepLinkOutcome::SessionStarted { session })
+                }
+            }
+        }
+        QuickAction::QuickCheckin => Ok(DeepLinkOutcome::Open { view: "checkin" }),
+        QuickAction::QuickAdd => Ok(DeepLinkOutcome::Open { view: "quick_add" }),
+    }
+}
+
+#[cfg(desktop)]
+fn spawn_run(app_handle: tauri::AppHandle, action: QuickAction) {
+    tauri::async_runtime::spawn(async move {
+        let pool = app_handle.state::<DbState>().0.clone();
+        let event = match run(&pool, action).await {
+            Ok(outcome) => {
+                // Views need the window in front
+                if matches!(outcome, DeepLinkOutcome::Open { .. }) {
+                    if let Some(window) = app_handle.get_webview_window("main") {
+                        let _ = window.show();
+                        let _ = window.set_focus();
+                    }
+                }
+                QuickActionEvent {
+                    action,
+                    outcome: Some(outcome),
+                    error: None,
+                }
+            }
+            Err(e) => {
+                log::warn!("Quick action {} failed: {}", action.id(), e.message);
+                QuickActionEvent {
+                    action,
+                    outcome: None,
+                    error: Some(e.message),
+                }
+            }
+        };
+
+        if let Err(e) = app_handle.emit(QUICK_ACTION_EVENT, event) {
+            log::warn!("Failed to emit quick action event: {}", e);
+        }
+    });
+}
+
+// ============================================================================
+// REGISTRATION
+// ============================================================================
+
+/// Install the global shortcut plugin and tray icon and keep both in sync
+/// with the settings
+#[cfg(desktop)]
+pub fn register(app: &tauri::App, pool: Pool<Sqlite>) -> Result<(), Box<dyn std::error::Error>> {
+    use tauri_plugin_global_shortcut::ShortcutState as KeyState;
+
+    app.manage(ShortcutState::default());
+    app.handle().plugin(
+        tauri_plugin_global_shortcut::Builder::new()
+            .with_handler(|app, shortcut, event| {
+                if event.state() != KeyState::Pressed {
+                    return;
+                }
+                let action = app
+                    .state::<ShortcutState>()
+                    .bindings
+                    .lock()
+                    .ok()
+                    .and_then(|b| b.get(&shortcut.id()).copied());
+                if let Some(action) = action {
+                    spawn_run(app.clone(), action);
+                }
+            })
+            .build(),
+    )?;
+
+    let handle = app.handle().clone();
+    tauri::async_runtime::spawn(async move {
+        let mut changes = settings::subscribe();
+        apply_settings(&handle, &pool).await;
+        loop {
+            match changes.recv().await {
+                Ok(change) if change.key == "global_shortcuts" || change.key == "tray_enabled" => {
+                    apply_settings(&handle, &pool).await;
+                }
+                Ok(_) => {}
+                Err(tokio::sync::broadcast::error::RecvError::Lagged(_)) => apply_settings(&handle, &pool).await,
+                Err(tokio::sync::broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    });
+
+    Ok(())
+}
+
+/// Mobile has no global shortcuts or tray; actions stay reachable through
+/// the `run_quick_action` command and deep links
+#[cfg(mobile)]
+pub fn register(app: &tauri::App, _pool: Pool<Sqlite>) -> Result<(), Box<dyn std::error::Error>> {
+    app.manage(ShortcutState::default());
+    Ok(())
+}
+
+#[cfg(desktop)]
+async fn apply_settings(app_handle: &tauri::AppHandle, pool: &Pool<Sqlite>) {
+    let bindings = match get_bindings(pool).await {
+        Ok(b) => b,
+        Err(e) => {
+            log::warn!("Failed to load shortcut settings: {}", e.message);
+            return;
+        }
+    };
+    register_shortcuts(app_handle, &bindings);
+
+    let tray_enabled = settings::get_bool(pool, "tray_enabled").await.unwrap_or(true);
+    if let Err(e) = update_tray(app_handle, &bindings, tray_enabled) {
+        log::warn!("Failed to update tray menu: {}", e);
+    }
+}
+
+#[cfg(desktop)]
+fn register_shortcuts(app_handle: &tauri::AppHandle, bindings: &[(QuickAction, Option<String>)]) {
+    use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut};
+
+    let state = app_handle.state::<ShortcutState>();
+    let (Ok(mut registered), Ok(mut errors)) = (state.bindings.lock(), state.errors.lock()) else {
+        return;
+    };
+    if let Err(e) = app_handle.global_shortcut().unregister_all() {
+        log::warn!("Failed to unregister shortcuts: {}", e);
+    }
+    registered.clear();
+    errors.clear();
+
+    for (action, accelerator) in bindings {
+        let Some(accelerator) = accelerator else { continue };
+        // Another app may already own the combination
+        let result = accelerator
+            .parse::<Shortcut>()
+            .map_err(|e| e.to_string())
+            .and_then(|s| app_handle.global_shortcut().register(s).map(|_| s).map_err(|e| e.to_string()));
+        match result {
+            Ok(shortcut) => {
+                registered.insert(shortcut.id(), *action);
+            }
+            Err(e) => {
+                log::warn!("Failed to register {} for {}: {}", accelerator, action.id(), e);
+                errors.insert(*action, e);
+            }
+        }
+    }
+}
+
+#[cfg(desktop)]
+fn update_tray(
+    app_handle: &tauri::AppHandle,
+    bindings: &[(QuickAction, Option<String>)],
+    enabled: bool,
+) -> tauri::Result<()> {
+    use tauri::menu::{IsMenuItem, Menu, MenuItem, PredefinedMenuItem};
+    use tauri::tray::TrayIconBuilder;
+
+    if !enabled {
+        if let Some(tray) = app_handle.tray_by_id(TRAY_ID) {
+            tray.set_visible(false)?;
+        }
+        return Ok(());
+    }
+
+    let mut items = Vec::new();
+    for (action, accelerator) in bindings {
+        items.push(MenuItem::with_id(app_handle, action.id(), action.label(), true, accelerator.as_deref())?);
+    }
+    let separator = PredefinedMenuItem::separator(app_handle)?;
+    let quit = MenuItem::with_id(app_handle, "quit", "Quit Life OS", true, None::<&str>)?;
+    let mut entries: Vec<&dyn IsMenuItem<_>> = items.iter().map(|i| i as &dyn IsMenuItem<_>).collect();
+    entries.push(&separator);
+    entries.push(&quit);
+    let menu = Menu::with_items(app_handle, &entries)?;
+
+    if let Some(tray) = app_handle.tray_by_id(TRAY_ID) {
+        tray.set_menu(Some(menu))?;
+        return tray.set_visible(true);
+    }
+
+    let mut tray = TrayIconBuilder::with_id(TRAY_ID).tooltip("Life OS").menu(&menu);
+    if let Some(icon) = app_handle.default_window_icon() {
+        tray = tray.icon(icon.clone());
+    }
+    tray.on_menu_event(|app, event| match event.id().as_ref() {
+        "quit" => app.exit(0),
+        id => {
+            if let Some(action) = QuickAction::from_id(id) {
+                spawn_run(app.clone(), action);
+            }
+        }
+    })
+    .build(app_handle)?;
+
+    Ok(())
+}
+
+/// Quick actions with their bindings and registration status
+pub async fn status(pool: &Pool<Sqlite>, state: &ShortcutState) -> Result<Vec<QuickActionStatus>, ApiError> {
+    let errors = state.errors.lock().map(|e| e.clone()).unwrap_or_default();
+
+    Ok(get_bindings(pool)
+        .await?
+        .into_iter()
+        .map(|(action, shortcut)| {
+            let error = errors.get(&action).cloned();
+            QuickActionStatus {
+                action,
+                label: action.label(),
+                registered: shortcut.is_some() && error.is_none(),
+                shortcut,
+                error,
+            }
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn test_normalize_accelerator() {
+        assert_eq!(normalize_accelerator("shift+cmdorctrl+p").unwrap(), "CmdOrCtrl+Shift+P");
+        assert_eq!(normalize_accelerator("Option + Command + f5").unwrap(), "Cmd+Alt+F5");
+        assert_eq!(normalize_accelerator("Ctrl+Alt+space").unwrap(), "Ctrl+Alt+Space");
+        assert!(normalize_accelerator("Shift+P").is_err());
+        assert!(normalize_accelerator("Ctrl+Ctrl+P").is_err());
+        assert!(normalize_accelerator("Hyper+P").is_err());
+        assert!(normalize_accelerator("Ctrl+F25").is_err());
+    }
+
+    #[test]
+    fn test_binding_conflicts() {
+        let normalized = validate_bindings(json!({ "toggle_pomodoro": "alt+cmdorctrl+p", "quick_add": "" })).unwrap();
+        assert_eq!(
+            normalized,
+            json!({ "toggle_pomodoro": "CmdOrCtrl+Alt+P", "quick_checkin": null, "quick_add": null })
+        );
+
+        // Same keys on Windows/Linux
+        assert!(validate_bindings(json!({ "toggle_pomodoro": "CmdOrCtrl+Alt+P", "quick_add": "Ctrl+Alt+P" })).is_err());
+        // Copy
+        assert!(validate_bindings(json!({ "quick_add": "Ctrl+C" })).is_err());
+        assert!(validate_bindings(json!({ "launch_rockets": "Ctrl+Alt+R" })).is_err());
+    }
+
+    #[test]
+    fn test_default_bindings_are_valid() {
+        let default = settings::definition("global_shortcuts").unwrap().default_value();
+        assert_eq!(validate_bindings(default.clone()).unwrap(), default);
+    }
+}
@@ -0,0 +1,479 @@
+//! Accountability Reports
+//!
+//! A weekly summary of the numbers an accountability partner cares about:
+//! study hours against course targets, practice, workouts, check-ins and
+//! assignments. It covers the seven days ending on `accountability_day` and
+//! is rendered as Markdown or HTML (`accountability_format`).
+//!
+//! Daily maintenance sends it once per week on that day through
+//! `accountability_delivery`:
+//! - file: written into the `accountability_path` folder, e.g. one shared
+//!   with the partner
+//! - webhook: POSTed as JSON to `accountability_webhook_url`
+//! - mailto: a draft to `accountability_email`. The backend can't open a
+//!   mail client, so the draft waits as pending until the frontend fetches
+//!   it with `due_draft`, opens it and acknowledges it, like the course
+//!   digests
+//!
+//! Every attempt is recorded in `accountability_deliveries`; a failed one can
+//! be retried with `send_accountability_report`.
+
+use std::path::PathBuf;
+use std::time::Duration as StdDuration;
+
+use chrono::{Datelike, Duration, NaiveDate};
+use serde::Serialize;
+use sqlx::{FromRow, Pool, Sqlite};
+
+use crate::{
+    error::ApiError,
+    services::{clock, settings, working_hours::WEEKDAYS},
+};
+
+const WEBHOOK_TIMEOUT: StdDuration = StdDuration::from_secs(15);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CourseHours {
+    pub course_name: String,
+    pub hours: f64,
+    pub target_hours: Option<f64>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct WeeklySummary {
+    pub week_start: String,
+    pub week_end: String,
+    pub study_hours: f64,
+    /// Sum of active courses' weekly targets; None when no course has one
+    pub study_target_hours: Option<f64>,
+    /// Active courses, most studied first
+    pub courses: Vec<CourseHours>,
+    pub practice_hours: f64,
+    pub workouts: i64,
+    pub workout_minutes: i64,
+    pub checkin_days: i64,
+    pub avg_mood: Option<f64>,
+    pub avg_energy: Option<f64>,
+    pub assignments_completed: i64,
+    /// Unfinished assignments past due at the end of the week
+    pub assignments_overdue: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AccountabilityReport {
+    pub summary: WeeklySummary,
+    /// 'markdown' or 'html'
+    pub format: String,
+    pub body: String,
+}
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct AccountabilityDelivery {
+    pub week_end: String,
+    /// 'file', 'webhook' or 'mailto'
+    pub method: String,
+    /// File written, webhook URL or mailto URL
+    pub target: Option<String>,
+    /// 'sent', 'pending' (a mailto draft not opened yet) or 'failed'
+    pub status: String,
+    pub error: Option<String>,
+    pub created_at: String,
+    pub delivered_at: Option<String>,
+}
+
+fn round1(value: f64) -> f64 {
+    (value * 10.0).round() / 10.0
+}
+
+/// Numbers for the seven days ending on `week_end`
+pub async fn summary(pool: &Pool<Sqlite>, week_end: NaiveDate) -> Result<WeeklySummary, ApiError> {
+    let week_start = week_end - Duration::days(6);
+    let (start, end) = (week_start.to_string(), week_end.to_string());
+
+    let courses: Vec<(String, Option<f64>, i64)> = sqlx::query_as(
+        r#"
+        SELECT c.name, c.target_weekly_hours,
+               (SELECT COALESCE(SUM(s.duration_minutes), 0) FROM sessions s
+                WHERE s.session_type = 'study' AND s.reference_type = 'course' AND s.reference_id = c.id
+                  AND s.ended_at IS NOT NULL
+                  AND date(s.started_at, 'localtime') BETWEEN ?1 AND ?2) AS minutes
+        FROM courses c
+        WHERE c.is_active = 1
+        ORDER BY minutes DESC, c.name
+        "#,
+    )
+    .bind(&start)
+    .bind(&end)
+    .fetch_all(pool)
+    .await
+    .map_err(ApiError::from)?;
+
+    let (study_minutes, practice_minutes, workouts, workout_minutes): (i64, i64, i64, i64) = sqlx::query_as(
+        r#"
+        SELECT
+            (SELECT COALESCE(SUM(duration_minutes), 0) FROM sessions
+             WHERE session_type IN (SELECT name FROM session_types WHERE counts_toward = 'study') AND ended_at IS NOT NULL
+               AND date(started_at, 'localtime') BETWEEN ?1 AND ?2),
+            (SELECT COALESCE(SUM(duration_minutes), 0) FROM practice_logs
+             WHERE date(logged_at, 'localtime') BETWEEN ?1 AND ?2)
+              + (SELECT COALESCE(SUM(duration_minutes), 0) FROM sessions
+                 WHERE session_type IN (SELECT name FROM session_types WHERE counts_toward = 'practice') AND ended_at IS NOT NULL
+                   AND date(started_at, 'localtime') BETWEEN ?1 AND ?2),
+            (SELECT COUNT(*) FROM workouts WHERE date(logged_at, 'localtime') BETWEEN ?1 AND ?2),
+            (SELECT COALESCE(SUM(duration_minutes), 0) FROM workouts
+             WHERE date(logged_at, 'localtime') BETWEEN ?1 AND ?2)
+        "#,
+    )
+    .bind(&start)
+    .bind(&end)
+    .fetch_one(pool)
+    .await
+    .map_err(ApiError::from)?;
+
+    let (checkin_days, avg_mood, avg_energy): (i64, Option<f64>, Option<f64>) = sqlx::query_as(
+        r#"
+        SELECT COUNT(DISTINCT date(checked_in_at, 'localtime')), AVG(mood), AVG(energy)
+        FROM check_ins
+        WHERE date(checked_in_at, 'localtime') BETWEEN ?1 AND ?2
+        "#,
+    )
+    .bind(&start)
+    .bind(&end)
+    .fetch_one(pool)
+    .await
+    .map_err(ApiError::from)?;
+
+    let (assignments_completed, assignments_overdue): (i64, i64) = sqlx::query_as(
+        r#"
+        SELECT
+            (SELECT COUNT(*) FROM assignments
+             WHERE is_completed = 1 AND date(completed_at, 'localtime') BETWEEN ?1 AND ?2),
+            (SELECT COUNT(*) FROM assignments
+             WHERE is_completed = 0 AND due_date IS NOT NULL AND date(due_date) <= ?2)
+        "#,
+    )
+    .bind(&start)
+    .bind(&end)
+    .fetch_one(pool)
+    .await
+    .map_err(ApiError::from)?;
+
+    let targets: Vec<f64> = courses.iter().filter_map(|(_, target, _)| *target).collect();
+    Ok(WeeklySummary {
+        week_start: start,
+        week_end: end,
+        study_hours: round1(study_minutes as f64 / 60.0),
+        study_target_hours: (!targets.is_empty()).then(|| round1(targets.iter().sum())),
+        courses: courses
+            .into_iter()
+            .map(|(course_name, target_hours, minutes)| CourseHours {
+                course_name,
+                hours: round1(minutes as f64 / 60.0),
+                target_hours,
+            })
+            .collect(),
+        practice_hours: round1(practice_minutes as f64 / 60.0),
+        workouts,
+        workout_minutes,
+        checkin_days,
+        avg_mood: avg_mood.map(round1),
+        avg_energy: avg_energy.map(round1),
+        assignments_completed,
+        assignments_overdue,
+    })
+}
+
+/// (label, value) lines shared by both formats
+fn lines(summary: &WeeklySummary) -> Vec<(String, String)> {
+    let study = match summary.study_target_hours {
+        Some(target) => format!("{} h of {} h target", summary.study_hours, target),
+        None => format!("{} h", summary.study_hours),
+    };
+    let checkins = match (summary.avg_mood, summary.avg_energy) {
+        (Some(mood), Some(energy)) => format!(
+            "{} of 7 days (mood {}/10, energy {}/10)",
+            summary.checkin_days, mood, energy
+        ),
+        _ => format!("{} of 7 days", summary.checkin_days),
+    };
+    vec![
+        ("Study".to_string(), study),
+        ("Practice".to_string(), format!("{} h", summary.practice_hours)),
+        (
+            "Workouts".to_string(),
+            format!("{} ({} min)", summary.workouts, summary.workout_minutes),
+        ),
+        ("Check-ins".to_string(), checkins),
+        (
+            "Assignments".to_string(),
+            format!(
+                "{} completed, {} overdue",
+                summary.assignments_completed, summary.assignments_overdue
+            ),
+        ),
+    ]
+}
+
+fn course_line(course: &CourseHours) -> String {
+    match course.target_hours {
+        Some(target) => format!("{}: {} h of {} h", course.course_name, course.hours, target),
+        None => format!("{}: {} h", course.course_name, course.hours),
+    }
+}
+
+fn title(summary: &WeeklySummary) -> String {
+    format!("Weekly report {} to {}", summary.week_start, summary.week_end)
+}
+
+pub fn render_markdown(summary: &WeeklySummary) -> String {
+    let mut out = format!("# {}\n\n", title(summary));
+    for (label, value) in lines(summary) {
+        out.push_str(&format!("- **{}:** {}\n", label, value));
+    }
+    if !summary.courses.is_empty() {
+        out.push_str("\n## Courses\n\n");
+        for course in &summary.courses {
+            out.push_str(&format!("- {}\n", course_line(course)));
+        }
+    }
+    out
+}
+
+fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
+
+pub fn render_html(summary: &WeeklySummary) -> String {
+    let mut out = format!(
+        "<!DOCTYPE html>\n<html>\n<head><meta charset=\"utf-8\"><title>{0}</title></head>\n<body>\n<h1>{0}</h1>\n<ul>\n",
+        escape_html(&title(summary))
+    );
+    for (label, value) in lines(summary) {
+        out.push_str(&format!(
+            "<li><strong>{}:</strong> {}</li>\n",
+            escape_html(&label),
+            escape_html(&value)
+        ));
+    }
+    out.push_str("</ul>\n");
+    if !summary.courses.is_empty() {
+        out.push_str("<h2>Courses</h2>\n<ul>\n");
+        for course in &summary.courses {
+            out.push_str(&format!("<li>{}</li>\n", escape_html(&course_line(course))));
+        }
+        out.push_str("</ul>\n");
+    }
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+/// The report for the seven days ending on `week_end`, in the configured format
+pub async fn report(pool: &Pool<Sqlite>, week_end: NaiveDate) -> Result<AccountabilityReport, ApiError> {
+    let summary = summary(pool, week_end).await?;
+    let format = settings::get_string(pool, "accountability_format")
+        .await?
+        .unwrap_or_else(|| "markdown".to_string());
+    let body = match format.as_str() {
+        "html" => render_html(&summary),
+        _ => render_markdown(&summary),
+    };
+    Ok(AccountabilityReport { summary, format, body })
+}
+
+/// Send the report for the week ending on `week_end`; recorded whether it
+/// succeeds or not
+pub async fn deliver(pool: &Pool<Sqlite>, week_end: NaiveDate) -> Result<AccountabilityDelivery, ApiError> {
+    let report = report(pool, week_end).await?;
+    let method = settings::get_string(pool, "accountability_delivery")
+        .await?
+        .unwrap_or_else(|| "file".to_string());
+
+    let outcome = match method.as_str() {
+        "webhook" => send_webhook(pool, &report).await.map(|url| (url, "sent")),
+        "mailto" => mailto_url(pool, &report).await.map(|url| (url, "pending")),
+        _ => write_file(pool, &report).await.map(|path| (path.display().to_string(), "sent")),
+    };
+    let (target, status, error) = match outcome {
+        Ok((target, status)) => (Some(target), status, None),
+        Err(e) => (None, "failed", Some(e.message)),
+    };
+
+    sqlx::query_as::<_, AccountabilityDelivery>(
+        r#"INSERT OR REPLACE INTO accountability_deliveries (week_end, method, target, status, error, delivered_at)
+           VALUES (?1, ?2, ?3, ?4, ?5, CASE WHEN ?4 = 'sent' THEN datetime('now') END)
+           RETURNING week_end, method, target, status, error, created_at, delivered_at"#,
+    )
+    .bind(&report.summary.week_end)
+    .bind(&method)
+    .bind(&target)
+    .bind(status)
+    .bind(&error)
+    .fetch_one(pool)
+    .await
+    .map_err(ApiError::from)
+}
+
+async fn write_file(pool: &Pool<Sqlite>, report: &AccountabilityReport) -> Result<PathBuf, ApiError> {
+    let folder = settings::get_string(pool, "accountability_path")
+        .await?
+        .ok_or_else(|| ApiError::validation("No folder is set for accountability reports"))?;
+    let folder = PathBuf::from(folder.trim());
+    if !folder.is_absolute() || !folder.is_dir() {
+        return Err(ApiError::validation("The accountability report folder must be an existing absolute path"));
+    }
+    let extension = if report.format == "html" { "html" } else { "md" };
+    let path = folder.join(format!("weekly-report-{}.{}", report.summary.week_end, extension));
+    tokio::fs::write(&path, &report.body)
+        .await
+        .map_err(|e| ApiError::internal(format!("Failed to write accountability report: {}", e)))?;
+    Ok(path)
+}
+
+async fn send_webhook(pool: &Pool<Sqlite>, report: &AccountabilityReport) -> Result<String, ApiError> {
+    let url = settings::get_string(pool, "accountability_webhook_url")
+        .await?
+        .ok_or_else(|| ApiError::validation("No webhook URL is set for accountability reports"))?;
+    let parsed = url::Url::parse(url.trim()).map_err(|_| ApiError::validation("The webhook URL is not valid"))?;
+    if !matches!(parsed.scheme(), "http" | "https") {
+        return Err(ApiError::validation("The webhook URL must be http or https"));
+    }
+
+    let response = reqwest::Client::new()
+        .post(parsed.clone())
+        .timeout(WEBHOOK_TIMEOUT)
+        .json(report)
+        .send()
+        .await?;
+    if !response.status().is_success() {
+        return Err(ApiError::internal(format!("Webhook returned {}", response.status())));
+    }
+    Ok(parsed.to_string())
+}
+
+async fn mailto_url(pool: &Pool<Sqlite>, report: &AccountabilityReport) -> Result<String, ApiError> {
+    let email = settings::get_string(pool, "accountability_email")
+        .await?
+        .map(|e| e.trim().to_string())
+        .filter(|e| e.contains('@'))
+        .ok_or_else(|| ApiError::validation("No email address is set for accountability reports"))?;
+    // Mail clients take a plain-text body, so drafts always use Markdown
+    Ok(format!(
+        "mailto:{}?subject={}&body={}",
+        urlencoding::encode(&email),
+        urlencoding::encode(&title(&report.summary)),
+        urlencoding::encode(&render_markdown(&report.summary))
+    ))
+}
+
+/// Maintenance step: send this week's report on the report day, once;
+/// None when it isn't due
+pub async fn run(pool: &Pool<Sqlite>) -> Result<Option<AccountabilityDelivery>, ApiError> {
+    let today = clock::now().date_naive();
+    let day = settings::get_string(pool, "accountability_day").await?;
+    if day.as_deref() != Some(WEEKDAYS[today.weekday().num_days_from_sunday() as usize]) {
+        return Ok(None);
+    }
+    let already: Option<String> = sqlx::query_scalar("SELECT status FROM accountability_deliveries WHERE week_end = ?")
+        .bind(today.to_string())
+        .fetch_optional(pool)
+        .await
+        .map_err(ApiError::from)?;
+    if already.is_some() {
+        return Ok(None);
+    }
+    deliver(pool, today).await.map(Some)
+}
+
+/// The oldest mailto draft the frontend hasn't opened yet
+pub async fn due_draft(pool: &Pool<Sqlite>) -> Result<Option<AccountabilityDelivery>, ApiError> {
+    sqlx::query_as(
+        r#"SELECT week_end, method, target, status, error, created_at, delivered_at
+           FROM accountability_deliveries
+           WHERE status = 'pending'
+           ORDER BY week_end
+           LIMIT 1"#,
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(ApiError::from)
+}
+
+/// Record that the draft for `week_end` was opened
+pub async fn ack_draft(pool: &Pool<Sqlite>, week_end: &str) -> Result<(), ApiError> {
+    sqlx::query(
+        "UPDATE accountability_deliveries SET status = 'sent', delivered_at = datetime('now') WHERE week_end = ? AND status = 'pending'",
+    )
+    .bind(week_end)
+    .execute(pool)
+    .await
+    .map_err(ApiError::from)?;
+    Ok(())
+}
+
+/// Recent deliveries, newest first
+pub async fn history(pool: &Pool<Sqlite>, limit: i64) -> Result<Vec<AccountabilityDelivery>, ApiError> {
+    sqlx::query_as(
+        r#"SELECT week_end, method, target, status, error, created_at, delivered_at
+           FROM accountability_deliveries
+           ORDER BY week_end DESC
+           LIMIT ?"#,
+    )
+    .bind(limit)
+    .fetch_all(pool)
+    .await
+    .map_err(ApiError::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Local, TimeZone};
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn sunday_report_becomes_a_mailto_draft_once() {
+        let pool = crate::test_support::pool().await;
+        // A Sunday
+        let _clock = clock::freeze(Local.with_ymd_and_hms(2030, 3, 10, 21, 0, 0).unwrap());
+        sqlx::query("INSERT INTO courses (name, target_weekly_hours, is_active) VALUES ('Algebra & Logic', 6, 1)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query(
+            "INSERT INTO sessions (session_type, reference_type, reference_id, started_at, ended_at, duration_minutes) VALUES ('study', 'course', 1, '2030-03-05 12:00:00', '2030-03-05 14:30:00', 150), ('study', 'course', 1, '2030-03-01 12:00:00', '2030-03-01 13:00:00', 60)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query("INSERT INTO workouts (name, duration_minutes, logged_at) VALUES ('Run', 30, '2030-03-09 12:00:00')")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let report = report(&pool, clock::now().date_naive()).await.unwrap();
+        assert_eq!(report.summary.week_start, "2030-03-04");
+        assert_eq!(report.summary.study_hours, 2.5);
+        assert!(report.body.contains("- **Study:** 2.5 h of 6 h target"));
+        assert!(report.body.contains("- **Workouts:** 1 (30 min)"));
+        assert!(render_html(&report.summary).contains("Algebra &amp; Logic: 2.5 h of 6 h"));
+
+        // Off by default
+        assert!(run(&pool).await.unwrap().is_none());
+        settings::set(&pool, "accountability_day", json!("sunday")).await.unwrap();
+        settings::set(&pool, "accountability_delivery", json!("mailto")).await.unwrap();
+        settings::set(&pool, "accountability_email", json!("partner@example.com")).await.unwrap();
+
+        let delivery = run(&pool).await.unwrap().unwrap();
+        assert_eq!(delivery.status, "pending");
+        assert!(delivery.target.unwrap().starts_with("mailto:partner%40example.com?subject=Weekly%20report"));
+        assert!(run(&pool).await.unwrap().is_none());
+
+        let draft = due_draft(&pool).await.unwrap().unwrap();
+        ack_draft(&pool, &draft.week_end).await.unwrap();
+        assert!(due_draft(&pool).await.unwrap().is_none());
+        assert_eq!(history(&pool, 5).await.unwrap()[0].status, "sent");
+    }
+}
@@ -0,0 +1,416 @@
+//! Achievements
+//!
+//! Milestones for total workouts, study hours, skill levels, check-in
+//! streaks and books read, plus the yearly reading goal, XP levels while gamification is on and completed
+//! challenges. Each milestone is awarded once.
+//!
+//! Evaluation is incremental: `start` listens for activity events and only
+//! re-checks the metric the event can have moved (a logged workout can only
+//! unlock workout milestones), then XP levels and challenges, which any
+//! activity can move.
+//! `evaluate_all` rescans every metric; it backs
+//! the `check_achievements` command and daily maintenance, and catches up
+//! when events were missed.
+
+use sqlx::{Pool, Sqlite};
+use tokio::sync::broadcast;
+
+use crate::commands::analytics::Achievement;
+use crate::error::ApiError;
+use crate::services::session_types::SessionTarget;
+use crate::services::events::{self, ActivityEvent};
+use crate::services::challenges;
+use crate::services::{clock, reading};
+use crate::services::gamification::{self, LevelUp};
+
+const WORKOUT_MILESTONES: &[i64] = &[10, 25, 50, 100, 250, 500];
+const STUDY_HOUR_MILESTONES: &[i64] = &[10, 25, 50, 100, 250, 500];
+const SKILL_LEVEL_MILESTONES: &[i64] = &[5, 10, 15, 20];
+const STREAK_MILESTONES: &[i64] = &[7, 14, 30, 60, 90];
+const BOOK_MILESTONES: &[i64] = &[5, 10, 25, 50, 100];
+
+/// What an achievement measures
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Metric {
+    Workouts,
+    StudyHours,
+    SkillLevels,
+    CheckinStreak,
+    /// Books finished and the yearly reading goal
+    Reading,
+    /// Overall and domain XP levels; nothing while gamification is off
+    Levels,
+    Challenges,
+}
+
+pub const ALL_METRICS: &[Metric] = &[
+    Metric::Workouts,
+    Metric::StudyHours,
+    Metric::SkillLevels,
+    Metric::CheckinStreak,
+    Metric::Reading,
+    Metric::Levels,
+    Metric::Challenges,
+];
+
+impl Metric {
+    /// The metric an event can change; None for sessions counting toward
+    /// no target
+    pub fn for_event(event: ActivityEvent) -> Option<Metric> {
+        match event {
+            ActivityEvent::WorkoutLogged { .. } => Some(Metric::Workouts),
+            ActivityEvent::SessionEnded { counts_toward: Some(SessionTarget::Study), .. } => Some(Metric::StudyHours),
+            ActivityEvent::SessionEnded { counts_toward: Some(SessionTarget::Practice), .. } => Some(Metric::SkillLevels),
+            ActivityEvent::SessionEnded { counts_toward: None, .. } => None,
+            ActivityEvent::CheckInRecorded { .. } => Some(Metric::CheckinStreak),
+            ActivityEvent::ReadingLogged { .. } => Some(Metric::Reading),
+        }
+    }
+}
+
+/// Evaluate achievements as activity events arrive
+pub fn start(pool: Pool<Sqlite>) {
+    let mut events = events::subscribe();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            let result = match events.recv().await {
+                Ok(event) => evaluate_event(&pool, event).await,
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    log::warn!("Missed {} activity events, rescanning achievements", skipped);
+                    evaluate_all(&pool).await
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            };
+            match result {
+                Ok(unlocked) => {
+                    for achievement in unlocked {
+                        log::info!("Achievement unlocked: {}", achievement.title);
+                    }
+                }
+                Err(e) => log::warn!("Achievement check failed: {}", e.message),
+            }
+        }
+    });
+}
+
+/// The metric the event moved, then XP levels and challenges, which any
+/// activity can move
+async fn evaluate_event(pool: &Pool<Sqlite>, event: ActivityEvent) -> Result<Vec<Achievement>, ApiError> {
+    let mut unlocked = match Metric::for_event(event) {
+        Some(metric) => evaluate(pool, metric).await?,
+        None => Vec::new(),
+    };
+    unlocked.extend(evaluate(pool, Metric::Levels).await?);
+    unlocked.extend(evaluate(pool, Metric::Challenges).await?);
+    Ok(unlocked)
+}
+
+/// Rescan every metric; returns newly awarded achievements
+pub async fn evaluate_all(pool: &Pool<Sqlite>) -> Result<Vec<Achievement>, ApiError> {
+    let mut unlocked = Vec::new();
+    for metric in ALL_METRICS {
+        unlocked.extend(evaluate(pool, *metric).await?);
+    }
+    Ok(unlocked)
+}
+
+/// Check one metric's milestones; returns newly awarded achievements
+pub async fn evaluate(pool: &Pool<Sqlite>, metric: Metric) -> Result<Vec<Achievement>, ApiError> {
+    let mut unlocked = Vec::new();
+
+    match metric {
+        Metric::Workouts => {
+            let total: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM workouts WHERE user_id = 1")
+                .fetch_one(pool)
+                .await
+                .map_err(ApiError::from)?;
+            for &milestone in WORKOUT_MILESTONES.iter().filter(|m| total >= **m) {
+                unlocked.extend(
+                    award(
+                        pool,
+                        "workout_milestone",
+                        "physical",
+                        format!("{} Workouts!", milestone),
+                        format!("Completed {} total workouts", milestone),
+                        format!(r#"{{"count":{}}}"#, milestone),
+                    )
+                    .await?,
+                );
+            }
+        }
+        Metric::StudyHours => {
+            let hours: f64 = sqlx::query_scalar(
+                "SELECT COALESCE(SUM(duration_minutes), 0) / 60.0 FROM sessions WHERE session_type IN (SELECT name FROM session_types WHERE counts_toward = 'study')",
+            )
+            .fetch_one(pool)
+            .await
+            .map_err(ApiError::from)?;
+            for &milestone in STUDY_HOUR_MILESTONES.iter().filter(|m| hours >= **m as f64) {
+                unlocked.extend(
+                    award(
+                        pool,
+                        "study_milestone",
+                        "academic",
+                        format!("{} Study Hours!", milestone),
+                        format!("Studied for {} total hours", milestone),
+                        format!(r#"{{"hours":{}}}"#, milestone),
+                    )
+                    .await?,
+                );
+            }
+        }
+        Metric::SkillLevels => {
+            let skills: Vec<(String, i64)> =
+                sqlx::query_as("SELECT name, COALESCE(current_level, 0) FROM skills WHERE user_id = 1")
+                    .fetch_all(pool)
+                    .await
+                    .map_err(ApiError::from)?;
+            for (skill_name, level) in skills {
+                for &milestone in SKILL_LEVEL_MILESTONES.iter().filter(|m| level >= **m) {
+                    unlocked.extend(
+                        award(
+                            pool,
+                            "skill_level",
+                            "skills",
+                            format!("{} Level {}!", skill_name, milestone),
+                            format!("Reached level {} in {}", milestone, skill_name),
+                            format!(r#"{{"skill":"{}","level":{}}}"#, skill_name, milestone),
+                        )
+                        .await?,
+                    );
+                }
+            }
+        }
+        Metric::CheckinStreak => {
+            let streak = checkin_streak(pool).await?;
+            for &milestone in STREAK_MILESTONES.iter().filter(|m| streak >= **m) {
+                unlocked.extend(
+                    award(
+                        pool,
+                        "checkin_streak",
+                        "wellness",
+                        format!("{}-Day Check-in Streak!", milestone),
+                        format!("Checked in for {} consecutive days", milestone),
+                        format!(r#"{{"days":{}}}"#, milestone),
+                    )
+                    .await?,
+                );
+            }
+        }
+        Metric::Reading => {
+            let books = reading::finished_count(pool).await?;
+            for &milestone in BOOK_MILESTONES.iter().filter(|m| books >= **m) {
+                unlocked.extend(
+                    award(
+                        pool,
+                        "books_milestone",
+                        "wellness",
+                        format!("{} Books Read!", milestone),
+                        format!("Finished {} books", milestone),
+                        format!(r#"{{"books":{}}}"#, milestone),
+                    )
+                    .await?,
+                );
+            }
+            let goal = reading::goal(pool, clock::now().date_naive()).await?;
+            if let Some(target) = goal.goal.filter(|target| goal.finished >= *target) {
+                unlocked.extend(
+                    award(
+                        pool,
+                        "reading_goal",
+                        "wellness",
+                        format!("{} Reading Goal Reached!", goal.year),
+                        format!("Finished {} books in {}", target, goal.year),
+                        format!(r#"{{"year":{}}}"#, goal.year),
+                    )
+                    .await?,
+                );
+            }
+        }
+        Metric::Levels => {
+            // Only the current level is awarded, so raising an XP rate
+            // doesn't backfill a level-up for every level skipped
+            for (domain, level) in gamification::current_levels(pool).await? {
+                let (title, category) = match domain.as_str() {
+                    "overall" => (format!("Level {}!", level), "overall"),
+                    _ => (format!("{} Level {}!", capitalize(&domain), level), level_category(&domain)),
+                };
+                let awarded = award(
+                    pool,
+                    "level_up",
+                    category,
+                    title,
+                    match domain.as_str() {
+                        "overall" => format!("Reached level {} overall", level),
+                        _ => format!("Reached level {} in {}", level, domain),
+                    },
+                    format!(r#"{{"domain":"{}","level":{}}}"#, domain, level),
+                )
+                .await?;
+                if let Some(achievement) = awarded {
+                    gamification::publish(LevelUp {
+                        domain,
+                        level,
+                        title: achievement.title.clone(),
+                    });
+                    unlocked.push(achievement);
+                }
+            }
+        }
+        Metric::Challenges => {
+            for challenge in challenges::check(pool).await? {
+                unlocked.extend(
+                    award(
+                        pool,
+                        "challenge_completed",
+                        challenge.metric.category(),
+                        format!("Challenge complete: {}", challenge.title),
+                        format!("Completed the challenge \"{}\"", challenge.title),
+                        format!(r#"{{"challenge_id":{}}}"#, challenge.id),
+                    )
+                    .await?,
+                );
+            }
+        }
+    }
+
+    Ok(unlocked)
+}
+
+/// Insert the achievement unless it was already awarded
+async fn award(
+    pool: &Pool<Sqlite>,
+    achievement_type: &str,
+    category: &str,
+    title: String,
+    description: String,
+    metadata: String,
+) -> Result<Option<Achievement>, ApiError> {
+    let row: Option<(i64, String)> = sqlx::query_as(
+        r#"
+        INSERT INTO achievements (user_id, achievement_type, title, description, category, metadata)
+        SELECT 1, ?1, ?2, ?3, ?4, ?5
+        WHERE NOT EXISTS (
+            SELECT 1 FROM achievements
+            WHERE user_id = 1 AND achievement_type = ?1 AND metadata = ?5
+        )
+        RETURNING id, achieved_at
+        "#,
+    )
+    .bind(achievement_type)
+    .bind(&title)
+    .bind(&description)
+    .bind(category)
+    .bind(&metadata)
+    .fetch_optional(pool)
+    .await
+    .map_err(ApiError::from)?;
+
+    Ok(row.map(|(id, achieved_at)| Achievement {
+        id,
+        achievement_type: achievement_type.to_string(),
+        title,
+        description: Some(description),
+        category: Some(category.to_string()),
+        achieved_at,
+        metadata: Some(metadata),
+    }))
+}
+
+/// Achievement category of an XP domain
+fn level_category(domain: &str) -> &'static str {
+    match domain {
+        "academics" => "academic",
+        "skills" => "skills",
+        "fitness" => "physical",
+        _ => "wellness",
+    }
+}
+
+fn capitalize(word: &str) -> String {
+    let mut chars = word.chars();
+    match chars.next() {
+        Some(first) => first.to_uppercase().chain(chars).collect(),
+        None => String::new(),
+    }
+}
+
+/// Consecutive days with a check-in, ending today or yesterday
+async fn checkin_streak(pool: &Pool<Sqlite>) -> Result<i64, ApiError> {
+    sqlx::query_scalar(
+        r#"
+        WITH dated AS (
+            SELECT DISTINCT date(checked_in_at) as activity_date
+            FROM check_ins
+            ORDER BY activity_date DESC
+        ),
+        numbered AS (
+            SELECT activity_date,
+                   ROW_NUMBER() OVER (ORDER BY activity_date DESC) as rn,
+                   julianday(activity_date) as jd
+            FROM dated
+        ),
+        streak AS (
+            SELECT activity_date, jd - rn as grp
+            FROM numbered
+            WHERE julianday('now') - jd <= 1
+        )
+        SELECT COUNT(*) FROM streak
+        WHERE grp = (SELECT grp FROM streak LIMIT 1)
+        "#,
+    )
+    .fetch_one(pool)
+    .await
+    .map_err(ApiError::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+    use std::str::FromStr;
+
+    async fn setup_pool_with_migrations() -> Pool<Sqlite> {
+        let options = SqliteConnectOptions::from_str("sqlite::memory:")
+            .unwrap()
+            .foreign_keys(false);
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect_with(options)
+            .await
+            .expect("Failed to connect to in-memory DB");
+
+        crate::db::migrations::run_migrations(&pool)
+            .await
+            .expect("Failed to run migrations");
+
+        pool
+    }
+
+    #[tokio::test]
+    async fn only_the_affected_metric_is_evaluated_and_awards_are_not_repeated() {
+        let pool = setup_pool_with_migrations().await;
+        for _ in 0..10 {
+            sqlx::query("INSERT INTO workouts (user_id, name) VALUES (1, 'Run')")
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+        sqlx::query("INSERT INTO sessions (session_type, duration_minutes) VALUES ('study', 600)")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let metric = Metric::for_event(ActivityEvent::WorkoutLogged { workout_id: 10 }).unwrap();
+        let unlocked = evaluate(&pool, metric).await.unwrap();
+        assert_eq!(unlocked.len(), 1);
+        assert_eq!(unlocked[0].title, "10 Workouts!");
+        assert!(evaluate(&pool, metric).await.unwrap().is_empty());
+
+        // The study milestone waits for its own event or a rescan
+        let unlocked = evaluate_all(&pool).await.unwrap();
+        assert_eq!(unlocked.len(), 1);
+        assert_eq!(unlocked[0].achievement_type, "study_milestone");
+        assert!(evaluate_all(&pool).await.unwrap().is_empty());
+    }
+}
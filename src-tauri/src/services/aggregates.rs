@@ -0,0 +1,89 @@
+//! Aggregates Cache
+//!
+//! Keeps the expensive read-side aggregates (streaks, today's and tomorrow's
+//! calendar) in memory so glance views answer without recomputing them.
+//! Entries expire after `TTL` or at midnight, and activity writes call
+//! `invalidate` so streaks never lag behind a logged session or workout.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use chrono::{Local, NaiveDate};
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use sqlx::{Pool, Sqlite};
+
+use crate::commands::analytics::{compute_streaks, Streaks};
+use crate::commands::calendar::{get_calendar_items_for_pool, CalendarItem, CalendarQuery};
+use crate::error::ApiError;
+
+const TTL: Duration = Duration::from_secs(60);
+
+#[derive(Debug)]
+pub struct Aggregates {
+    pub streaks: Streaks,
+    /// Calendar items for today and tomorrow, in start order
+    pub upcoming: Vec<CalendarItem>,
+    day: NaiveDate,
+    generation: u64,
+    computed_at: Instant,
+}
+
+static CACHE: Lazy<Mutex<Option<Arc<Aggregates>>>> = Lazy::new(|| Mutex::new(None));
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// Drop cached aggregates; call after writes that change them
+pub fn invalidate() {
+    GENERATION.fetch_add(1, Ordering::SeqCst);
+}
+
+/// Bumped by every `invalidate`, for other caches of activity-derived data
+pub fn generation() -> u64 {
+    GENERATION.load(Ordering::SeqCst)
+}
+
+/// Cached aggregates, recomputed when stale
+pub async fn get(pool: &Pool<Sqlite>) -> Result<Arc<Aggregates>, ApiError> {
+    let today = Local::now().date_naive();
+    let generation = GENERATION.load(Ordering::SeqCst);
+
+    if let Some(cached) = CACHE.lock().as_ref() {
+        if cached.generation == generation && cached.day == today && cached.computed_at.elapsed() < TTL {
+            return Ok(cached.clone());
+        }
+    }
+
+    let fresh = Arc::new(compute(pool, today, generation).await?);
+    // Don't overwrite a newer entry computed concurrently
+    let mut slot = CACHE.lock();
+    if slot.as_ref().map_or(true, |c| c.generation <= generation) {
+        *slot = Some(fresh.clone());
+    }
+    Ok(fresh)
+}
+
+async fn compute(pool: &Pool<Sqlite>, today: NaiveDate, generation: u64) -> Result<Aggregates, ApiError> {
+    let streaks = compute_streaks(pool).await?;
+
+    let tomorrow = today.succ_opt().unwrap_or(today);
+    let mut upcoming = get_calendar_items_for_pool(
+        pool,
+        CalendarQuery {
+            start_date: today.format("%Y-%m-%d").to_string(),
+            end_date: tomorrow.format("%Y-%m-%d").to_string(),
+            include_assignments: Some(true),
+            include_exams: Some(true),
+        },
+    )
+    .await?;
+    upcoming.sort_by(|a, b| a.start_at.cmp(&b.start_at));
+
+    Ok(Aggregates {
+        streaks,
+        upcoming,
+        day: today,
+        generation,
+        computed_at: Instant::now(),
+    })
+}
@@ -0,0 +1,339 @@
+//! Attachments Service
+//!
+//! Photos and recordings attached to practice logs and workouts. Files are
+//! copied into an attachments directory under app data and named by their
+//! SHA-256, so attaching the same file twice stores it once; the
+//! `attachments` table indexes them by owner.
+//!
+//! Owners are polymorphic (no foreign key), so deleting a practice log or
+//! workout leaves its rows behind until `gc` removes them, together with any
+//! file no row refers to.
+
+use std::collections::HashSet;
+use std::path::Path;
+
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use sqlx::{Pool, Sqlite};
+
+use crate::error::ApiError;
+use crate::models::attachment::Attachment;
+
+/// Directory under app data holding the files
+pub const ATTACHMENTS_DIR: &str = "attachments";
+
+const MAX_FILE_BYTES: u64 = 200 * 1024 * 1024;
+const MAX_FILE_NAME_LENGTH: usize = 255;
+
+/// owner_type -> owning table
+const OWNERS: &[(&str, &str)] = &[("practice_log", "practice_logs"), ("workout", "workouts")];
+
+const IMAGE_EXTENSIONS: &[&str] = &["jpg", "jpeg", "png", "gif", "webp", "heic", "heif"];
+const AUDIO_EXTENSIONS: &[&str] = &["mp3", "m4a", "wav", "ogg", "flac", "aac", "opus"];
+const VIDEO_EXTENSIONS: &[&str] = &["mp4", "mov", "webm", "mkv"];
+
+/// An attachment with the absolute path of its file
+#[derive(Debug, Clone, Serialize)]
+pub struct StoredAttachment {
+    #[serde(flatten)]
+    pub attachment: Attachment,
+    pub path: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct GcReport {
+    /// Rows whose owner was deleted or whose file is missing
+    pub rows_removed: u64,
+    /// Files no row refers to
+    pub files_removed: u64,
+    pub bytes_freed: u64,
+}
+
+fn owner_table(owner_type: &str) -> Result<&'static str, ApiError> {
+    OWNERS
+        .iter()
+        .find(|(t, _)| *t == owner_type)
+        .map(|(_, table)| *table)
+        .ok_or_else(|| ApiError::validation(format!("Unknown attachment owner type: {}", owner_type)))
+}
+
+/// Lowercased extension, if it is short and alphanumeric
+fn extension(path: &Path) -> Option<String> {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .map(str::to_ascii_lowercase)
+        .filter(|e| !e.is_empty() && e.len() <= 8 && e.chars().all(|c| c.is_ascii_alphanumeric()))
+}
+
+fn kind_for(extension: Option<&str>) -> &'static str {
+    match extension {
+        Some(e) if IMAGE_EXTENSIONS.contains(&e) => "image",
+        Some(e) if AUDIO_EXTENSIONS.contains(&e) => "audio",
+        Some(e) if VIDEO_EXTENSIONS.contains(&e) => "video",
+        _ => "other",
+    }
+}
+
+fn stored(root: &Path, attachment: Attachment) -> StoredAttachment {
+    let path = root.join(&attachment.stored_name).to_string_lossy().to_string();
+    StoredAttachment { attachment, path }
+}
+
+/// Copy `source` into the attachments directory and index it
+pub async fn attach(
+    pool: &Pool<Sqlite>,
+    root: &Path,
+    owner_type: &str,
+    owner_id: i64,
+    source: &Path,
+) -> Result<StoredAttachment, ApiError> {
+    let table = owner_table(owner_type)?;
+    let exists: bool = sqlx::query_scalar(&format!("SELECT EXISTS(SELECT 1 FROM {} WHERE id = ?)", table))
+        .bind(owner_id)
+        .fetch_one(pool)
+        .await
+        .map_err(ApiError::from)?;
+    if !exists {
+        return Err(ApiError::not_found(format!("{} {} not found", owner_type.replace('_', " "), owner_id)));
+    }
+
+    let metadata = tokio::fs::metadata(source)
+        .await
+        .map_err(|e| ApiError::validation(format!("Can't read {}: {}", source.display(), e)))?;
+    if !metadata.is_file() {
+        return Err(ApiError::validation(format!("{} is not a file", source.display())));
+    }
+    if metadata.len() > MAX_FILE_BYTES {
+        return Err(ApiError::validation(format!(
+            "Attachments are limited to {} MB",
+            MAX_FILE_BYTES / (1024 * 1024)
+        )));
+    }
+
+    let file_name: String = source
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_else(|| "attachment".to_string())
+        .chars()
+        .take(MAX_FILE_NAME_LENGTH)
+        .collect();
+    let extension = extension(source);
+    let bytes = tokio::fs::read(source).await?;
+    let hash: String = Sha256::digest(&bytes).iter().map(|b| format!("{:02x}", b)).collect();
+    let stored_name = match &extension {
+        Some(e) => format!("{}.{}", hash, e),
+        None => hash,
+    };
+
+    tokio::fs::create_dir_all(root).await?;
+    let target = root.join(&stored_name);
+    if !tokio::fs::try_exists(&target).await? {
+        // Write then rename so a crash never leaves a truncated file under the final name
+        let partial = root.join(format!("{}.partial", stored_name));
+        tokio::fs::write(&partial, &bytes).await?;
+        tokio::fs::rename(&partial, &target).await?;
+    }
+
+    let attachment = sqlx::query_as::<_, Attachment>(
+        r#"
+        INSERT INTO attachments (owner_type, owner_id, kind, file_name, stored_name, size_bytes)
+        VALUES (?, ?, ?, ?, ?, ?)
+        RETURNING *
+        "#,
+    )
+    .bind(owner_type)
+    .bind(owner_id)
+    .bind(kind_for(extension.as_deref()))
+    .bind(&file_name)
+    .bind(&stored_name)
+    .bind(bytes.len() as i64)
+    .fetch_one(pool)
+    .await
+    .map_err(ApiError::from)?;
+
+    Ok(stored(root, attachment))
+}
+
+/// Attachments of one practice log or workout, oldest first
+pub async fn list(
+    pool: &Pool<Sqlite>,
+    root: &Path,
+    owner_type: &str,
+    owner_id: i64,
+) -> Result<Vec<StoredAttachment>, ApiError> {
+    owner_table(owner_type)?;
+    let rows = sqlx::query_as::<_, Attachment>(
+        "SELECT * FROM attachments WHERE owner_type = ? AND owner_id = ? ORDER BY created_at, id",
+    )
+    .bind(owner_type)
+    .bind(owner_id)
+    .fetch_all(pool)
+    .await
+    .map_err(ApiError::from)?;
+    Ok(rows.into_iter().map(|a| stored(root, a)).collect())
+}
+
+/// Remove an attachment, and its file unless another attachment shares it
+pub async fn delete(pool: &Pool<Sqlite>, root: &Path, id: i64) -> Result<(), ApiError> {
+    let stored_name: Option<String> =
+        sqlx::query_scalar("DELETE FROM attachments WHERE id = ? RETURNING stored_name")
+            .bind(id)
+            .fetch_optional(pool)
+            .await
+            .map_err(ApiError::from)?;
+    let Some(stored_name) = stored_name else {
+        return Err(ApiError::not_found("Attachment not found"));
+    };
+
+    let shared: bool = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM attachments WHERE stored_name = ?)")
+        .bind(&stored_name)
+        .fetch_one(pool)
+        .await
+        .map_err(ApiError::from)?;
+    if !shared {
+        remove_file(&root.join(&stored_name)).await?;
+    }
+    Ok(())
+}
+
+async fn remove_file(path: &Path) -> Result<u64, ApiError> {
+    match tokio::fs::metadata(path).await {
+        Ok(metadata) => {
+            tokio::fs::remove_file(path).await?;
+            Ok(metadata.len())
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(0),
+        Err(e) => Err(e.into()),
+    }
+}
+
+/// Drop rows whose owner or file is gone, then files no row refers to
+pub async fn gc(pool: &Pool<Sqlite>, root: &Path) -> Result<GcReport, ApiError> {
+    let mut report = GcReport::default();
+
+    for (owner_type, table) in OWNERS {
+        let removed = sqlx::query(&format!(
+            "DELETE FROM attachments WHERE owner_type = ? AND owner_id NOT IN (SELECT id FROM {})",
+            table
+        ))
+        .bind(owner_type)
+        .execute(pool)
+        .await
+        .map_err(ApiError::from)?;
+        report.rows_removed += removed.rows_affected();
+    }
+
+    let rows: Vec<(i64, String)> = sqlx::query_as("SELECT id, stored_name FROM attachments")
+        .fetch_all(pool)
+        .await
+        .map_err(ApiError::from)?;
+    let mut referenced = HashSet::new();
+    for (id, stored_name) in rows {
+        if tokio::fs::try_exists(root.join(&stored_name)).await? {
+            referenced.insert(stored_name);
+        } else {
+            sqlx::query("DELETE FROM attachments WHERE id = ?")
+                .bind(id)
+                .execute(pool)
+                .await
+                .map_err(ApiError::from)?;
+            report.rows_removed += 1;
+        }
+    }
+
+    let mut entries = match tokio::fs::read_dir(root).await {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(report),
+        Err(e) => return Err(e.into()),
+    };
+    while let Some(entry) = entries.next_entry().await? {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if entry.file_type().await?.is_file() && !referenced.contains(&name) {
+            report.bytes_freed += remove_file(&entry.path()).await?;
+            report.files_removed += 1;
+        }
+    }
+
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+    use std::str::FromStr;
+    use std::time::{SystemTime, UNIX_EPOCH};
+
+    async fn setup_pool_with_migrations() -> Pool<Sqlite> {
+        let options = SqliteConnectOptions::from_str("sqlite::memory:")
+            .unwrap()
+            .foreign_keys(false);
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect_with(options)
+            .await
+            .expect("Failed to connect to in-memory DB");
+
+        crate::db::migrations::run_migrations(&pool)
+            .await
+            .expect("Failed to run migrations");
+
+        pool
+    }
+
+    #[tokio::test]
+    async fn attach_dedupes_and_gc_cleans_orphans() {
+        let pool = setup_pool_with_migrations().await;
+        let nanos = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_nanos();
+        let dir = std::env::temp_dir().join(format!("life-os-attachments-{}", nanos));
+        let root = dir.join(ATTACHMENTS_DIR);
+        std::fs::create_dir_all(&dir).unwrap();
+        let recording = dir.join("Nocturne take 3.M4A");
+        std::fs::write(&recording, b"not really audio").unwrap();
+
+        let skill: i64 = sqlx::query_scalar("INSERT INTO skills (name) VALUES ('Piano') RETURNING id")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        let log = |minutes: i64| {
+            sqlx::query_scalar::<_, i64>("INSERT INTO practice_logs (skill_id, duration_minutes) VALUES (?, ?) RETURNING id")
+                .bind(skill)
+                .bind(minutes)
+                .fetch_one(&pool)
+        };
+        let first = log(30).await.unwrap();
+        let second = log(45).await.unwrap();
+
+        let a = attach(&pool, &root, "practice_log", first, &recording).await.unwrap();
+        let b = attach(&pool, &root, "practice_log", second, &recording).await.unwrap();
+        assert_eq!(a.attachment.kind, "audio");
+        assert_eq!(a.attachment.file_name, "Nocturne take 3.M4A");
+        assert!(a.attachment.stored_name.ends_with(".m4a"));
+        // Same content, one file
+        assert_eq!(a.attachment.stored_name, b.attachment.stored_name);
+        assert!(Path::new(&a.path).is_file());
+
+        assert!(attach(&pool, &root, "practice_log", 999, &recording).await.is_err());
+        assert!(attach(&pool, &root, "skill", skill, &recording).await.is_err());
+        assert_eq!(list(&pool, &root, "practice_log", first).await.unwrap().len(), 1);
+
+        // Deleting one attachment keeps the shared file
+        delete(&pool, &root, a.attachment.id).await.unwrap();
+        assert!(Path::new(&b.path).is_file());
+
+        // Second log deleted and a stray file left behind: both are collected
+        sqlx::query("DELETE FROM practice_logs WHERE id = ?")
+            .bind(second)
+            .execute(&pool)
+            .await
+            .unwrap();
+        std::fs::write(root.join("stray.jpg"), b"xx").unwrap();
+        let report = gc(&pool, &root).await.unwrap();
+        assert_eq!(report.rows_removed, 1);
+        assert_eq!(report.files_removed, 2);
+        assert_eq!(report.bytes_freed, 18);
+        assert_eq!(std::fs::read_dir(&root).unwrap().count(), 0);
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
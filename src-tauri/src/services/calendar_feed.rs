@@ -0,0 +1,273 @@
+//! Calendar Feed
+//!
+//! A read-only iCalendar feed of the plan for people who should see when the
+//! user is busy without access to the Google account. It covers the next
+//! `calendar_feed_days` days of busy calendar items: classes, events, exams
+//! and accepted or locked plan blocks. Suggested blocks, deadlines and items
+//! in free categories are left out, and every event is titled "Busy" unless
+//! `calendar_feed_titles` is on.
+//!
+//! While `calendar_feed_enabled` is on the feed is published two ways:
+//! - served by the local HTTP API at `/feed/<token>.ics`; calendar apps can't
+//!   send a bearer header, so the feed has its own token in the URL, separate
+//!   from the API token
+//! - written to `calendar_feed_path` after each Google sync and by daily
+//!   maintenance, e.g. into a cloud-synced folder that can be shared
+//!
+//! The HTTP API only listens on localhost, so other devices need the file or
+//! a tunnel to reach the feed.
+
+use std::path::PathBuf;
+
+use chrono::{DateTime, Duration, NaiveDate, Utc};
+use rand::distributions::Alphanumeric;
+use rand::Rng;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use sqlx::{Pool, Sqlite};
+
+use crate::{
+    commands::{
+        calendar::{get_calendar_items_for_pool, CalendarItem, CalendarQuery},
+        export::validate_path,
+    },
+    error::ApiError,
+    http_api, platform,
+    services::{clock, settings},
+};
+
+const TOKEN_LENGTH: usize = 32;
+const TOKEN_SECRET: &str = "calendar_feed_token";
+/// Content lines longer than this many octets are folded (RFC 5545 3.1)
+const MAX_LINE_OCTETS: usize = 75;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CalendarFeedStatus {
+    pub enabled: bool,
+    pub has_token: bool,
+    /// Feed URL while the local HTTP API is running and a token exists
+    pub url: Option<String>,
+    /// File the feed is written to
+    pub path: Option<String>,
+}
+
+/// The feed as an iCalendar document
+pub async fn render(pool: &Pool<Sqlite>) -> Result<String, ApiError> {
+    let days = settings::get_i64(pool, "calendar_feed_days").await?;
+    let titles = settings::get_bool(pool, "calendar_feed_titles").await?;
+    let today = clock::now().date_naive();
+    let items = get_calendar_items_for_pool(
+        pool,
+        CalendarQuery {
+            start_date: today.to_string(),
+            end_date: (today + Duration::days(days)).to_string(),
+            include_assignments: Some(false),
+            include_exams: Some(true),
+        },
+    )
+    .await?;
+
+    let stamp = utc_stamp(&clock::now().with_timezone(&Utc));
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        "PRODID:-//Life OS//Calendar Feed//EN".to_string(),
+        "CALSCALE:GREGORIAN".to_string(),
+        "METHOD:PUBLISH".to_string(),
+        "X-WR-CALNAME:Life OS".to_string(),
+    ];
+    for item in items.iter().filter(|i| published(i)) {
+        lines.extend(event(item, titles, &stamp));
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    Ok(lines.iter().map(|l| fold(l.as_str())).collect::<Vec<_>>().join("\r\n") + "\r\n")
+}
+
+fn published(item: &CalendarItem) -> bool {
+    item.busy && (item.source != "plan_block" || matches!(item.status.as_deref(), Some("accepted" | "locked")))
+}
+
+fn event(item: &CalendarItem, titles: bool, stamp: &str) -> Vec<String> {
+    let summary = if titles { escape(&item.title) } else { "Busy".to_string() };
+    let (start, end) = if item.all_day {
+        // All-day items end on the (exclusive) next day
+        let start = item.start_at.get(..10).and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok());
+        let end = item.end_at.get(..10).and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok());
+        match (start, end) {
+            (Some(start), Some(end)) => (
+                format!("DTSTART;VALUE=DATE:{}", start.format("%Y%m%d")),
+                format!("DTEND;VALUE=DATE:{}", (end.max(start) + Duration::days(1)).format("%Y%m%d")),
+            ),
+            _ => return Vec::new(),
+        }
+    } else {
+        match (DateTime::parse_from_rfc3339(&item.start_at), DateTime::parse_from_rfc3339(&item.end_at)) {
+            (Ok(start), Ok(end)) => (
+                format!("DTSTART:{}", utc_stamp(&start.with_timezone(&Utc))),
+                format!("DTEND:{}", utc_stamp(&end.with_timezone(&Utc))),
+            ),
+            _ => return Vec::new(),
+        }
+    };
+
+    vec![
+        "BEGIN:VEVENT".to_string(),
+        format!("UID:{}@life-os", item.id),
+        format!("DTSTAMP:{}", stamp),
+        start,
+        end,
+        format!("SUMMARY:{}", summary),
+        "TRANSP:OPAQUE".to_string(),
+        "CLASS:PRIVATE".to_string(),
+        "END:VEVENT".to_string(),
+    ]
+}
+
+pub(crate) fn utc_stamp(time: &DateTime<Utc>) -> String {
+    time.format("%Y%m%dT%H%M%SZ").to_string()
+}
+
+/// Escape a TEXT value
+pub(crate) fn escape(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+/// Fold a content line into continuation lines, never splitting a character
+pub(crate) fn fold(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len());
+    let mut octets = 0;
+    for c in line.chars() {
+        if octets + c.len_utf8() > MAX_LINE_OCTETS {
+            folded.push_str("\r\n ");
+            // The leading space counts towards the next line
+            octets = 1;
+        }
+        folded.push(c);
+        octets += c.len_utf8();
+    }
+    folded
+}
+
+/// Write the feed to `calendar_feed_path`; returns the path written, or None
+/// when the feed is off or has no path
+pub async fn publish(pool: &Pool<Sqlite>) -> Result<Option<PathBuf>, ApiError> {
+    if !settings::get_bool(pool, "calendar_feed_enabled").await? {
+        return Ok(None);
+    }
+    let Some(path) = settings::get_string(pool, "calendar_feed_path").await? else {
+        return Ok(None);
+    };
+    let path = validate_path(&path)?;
+    let ics = render(pool).await?;
+
+    // Write beside the target and rename, so a syncing client never sees half a file
+    let partial = path.with_extension("ics.partial");
+    tokio::fs::write(&partial, ics)
+        .await
+        .map_err(|e| ApiError::internal(format!("Failed to write calendar feed: {}", e)))?;
+    tokio::fs::rename(&partial, &path)
+        .await
+        .map_err(|e| ApiError::internal(format!("Failed to write calendar feed: {}", e)))?;
+    Ok(Some(path))
+}
+
+/// Whether `token` opens the feed; compares digests so timing says nothing
+/// about the stored token
+pub fn token_matches(token: &str) -> Result<bool, ApiError> {
+    Ok(load_token()?.is_some_and(|stored| Sha256::digest(stored.as_bytes()) == Sha256::digest(token.as_bytes())))
+}
+
+/// Replace the feed token; the old feed URL stops working
+pub fn regenerate_token() -> Result<String, ApiError> {
+    let token: String = rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(TOKEN_LENGTH)
+        .map(char::from)
+        .collect();
+    platform::set_secret(TOKEN_SECRET, &token)?;
+    Ok(token)
+}
+
+fn load_token() -> Result<Option<String>, ApiError> {
+    platform::get_secret(TOKEN_SECRET)
+}
+
+pub async fn status(pool: &Pool<Sqlite>) -> Result<CalendarFeedStatus, ApiError> {
+    let token = load_token()?;
+    let api = http_api::status(pool).await?;
+    // The feed token is made to be shared, so unlike the API token it's shown
+    let url = match (&token, api.running) {
+        (Some(token), true) => Some(format!("http://127.0.0.1:{}/feed/{}.ics", api.port, token)),
+        _ => None,
+    };
+
+    Ok(CalendarFeedStatus {
+        enabled: settings::get_bool(pool, "calendar_feed_enabled").await?,
+        has_token: token.is_some(),
+        url,
+        path: settings::get_string(pool, "calendar_feed_path").await?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Local, TimeZone};
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn feed_lists_busy_commitments_without_titles_by_default() {
+        let pool = crate::test_support::pool().await;
+        let _clock = clock::freeze(Local.with_ymd_and_hms(2030, 3, 4, 8, 0, 0).unwrap());
+
+        for (title, start, end, category) in [
+            ("Dentist, then lunch", "2030-03-05T10:00:00", "2030-03-05T11:30:00", "health"),
+            ("Deadline reminder", "2030-03-05T12:00:00", "2030-03-05T12:30:00", "deadline"),
+        ] {
+            sqlx::query("INSERT INTO calendar_events (title, start_at, end_at, category) VALUES (?, ?, ?, ?)")
+                .bind(title)
+                .bind(start)
+                .bind(end)
+                .bind(category)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+        for status in ["accepted", "suggested"] {
+            sqlx::query(
+                "INSERT INTO week_plan_blocks (week_start_date, start_at, end_at, block_type, title, status) VALUES ('2030-03-04', '2030-03-06T14:00:00', '2030-03-06T15:00:00', 'study', 'Revise', ?)",
+            )
+            .bind(status)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+
+        let ics = render(&pool).await.unwrap();
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\n") && ics.ends_with("END:VCALENDAR\r\n"));
+        assert_eq!(ics.matches("BEGIN:VEVENT").count(), 2);
+        assert_eq!(ics.matches("SUMMARY:Busy").count(), 2);
+        assert!(!ics.contains("Dentist"));
+        let start = Local.with_ymd_and_hms(2030, 3, 5, 10, 0, 0).unwrap().with_timezone(&Utc);
+        assert!(ics.contains(&format!("DTSTART:{}", utc_stamp(&start))));
+
+        settings::set(&pool, "calendar_feed_titles", json!(true)).await.unwrap();
+        let ics = render(&pool).await.unwrap();
+        assert!(ics.contains("SUMMARY:Dentist\\, then lunch"));
+        assert!(ics.lines().all(|l| l.len() <= MAX_LINE_OCTETS + 1));
+    }
+
+    #[test]
+    fn long_lines_fold_between_characters() {
+        let line = format!("SUMMARY:{}", "é".repeat(60));
+        let folded = fold(&line);
+        assert!(folded.split("\r\n").all(|l| l.len() <= MAX_LINE_OCTETS));
+        assert_eq!(folded.replace("\r\n ", ""), line);
+    }
+}
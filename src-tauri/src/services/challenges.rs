@@ -0,0 +1,439 @@
+//! Challenges
+//!
+//! Self-set, time-boxed targets such as "30 workouts in 60 days" or "5
+//! flashcard reviews a day for 2 weeks". A challenge either sums a metric over
+//! its dates or, with `per_day`, needs the target met on every day; a missed
+//! day fails a daily challenge straight away.
+//!
+//! Progress is computed from the activity tables on each read, like the
+//! gamification XP, so backdated logs count. The achievements engine calls
+//! `check` after activity: crossing 25, 50, 75 and 100% publishes a
+//! `challenges://milestone` event once, and completion is awarded as a
+//! `challenge_completed` achievement. The pace of active challenges feeds the
+//! agent's goal-progress feature.
+
+use chrono::{Duration, NaiveDate};
+use once_cell::sync::Lazy;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, Pool, Sqlite};
+use tokio::sync::broadcast;
+
+use crate::{
+    error::ApiError,
+    services::{clock, gamification::FLASHCARD_TECHNIQUE},
+};
+
+/// Frontend event carrying a `ChallengeMilestone`
+pub const MILESTONE_EVENT: &str = "challenges://milestone";
+
+/// Percentages announced as milestones
+const MILESTONES: &[i64] = &[25, 50, 75, 100];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChallengeMetric {
+    Workouts,
+    StudyHours,
+    PracticeHours,
+    CheckinDays,
+    /// Study sessions using spaced repetition
+    FlashcardReviews,
+}
+
+impl ChallengeMetric {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ChallengeMetric::Workouts => "workouts",
+            ChallengeMetric::StudyHours => "study_hours",
+            ChallengeMetric::PracticeHours => "practice_hours",
+            ChallengeMetric::CheckinDays => "checkin_days",
+            ChallengeMetric::FlashcardReviews => "flashcard_reviews",
+        }
+    }
+
+    fn parse(value: &str) -> Option<ChallengeMetric> {
+        [
+            ChallengeMetric::Workouts,
+            ChallengeMetric::StudyHours,
+            ChallengeMetric::PracticeHours,
+            ChallengeMetric::CheckinDays,
+            ChallengeMetric::FlashcardReviews,
+        ]
+        .into_iter()
+        .find(|m| m.as_str() == value)
+    }
+
+    /// Achievement category of a completed challenge
+    pub fn category(self) -> &'static str {
+        match self {
+            ChallengeMetric::Workouts => "physical",
+            ChallengeMetric::StudyHours | ChallengeMetric::FlashcardReviews => "academic",
+            ChallengeMetric::PracticeHours => "skills",
+            ChallengeMetric::CheckinDays => "wellness",
+        }
+    }
+
+    /// `(day, value)` rows of the metric, by local day
+    fn daily_sql(self) -> String {
+        match self {
+            ChallengeMetric::Workouts => {
+                "SELECT date(logged_at, 'localtime') AS day, 1.0 AS value FROM workouts".to_string()
+            }
+            ChallengeMetric::StudyHours => r#"
+                SELECT date(started_at, 'localtime') AS day, duration_minutes / 60.0 AS value
+                FROM sessions WHERE session_type IN (SELECT name FROM session_types WHERE counts_toward = 'study') AND ended_at IS NOT NULL
+                UNION ALL
+                SELECT date, total_minutes / 60.0 FROM session_daily_aggregates WHERE session_type IN (SELECT name FROM session_types WHERE counts_toward = 'study')"#
+                .to_string(),
+            ChallengeMetric::PracticeHours => r#"
+                SELECT date(logged_at, 'localtime') AS day, duration_minutes / 60.0 AS value FROM practice_logs
+                UNION ALL
+                SELECT date(started_at, 'localtime'), duration_minutes / 60.0
+                FROM sessions WHERE session_type IN (SELECT name FROM session_types WHERE counts_toward = 'practice') AND ended_at IS NOT NULL
+                UNION ALL
+                SELECT date, total_minutes / 60.0 FROM session_daily_aggregates WHERE session_type IN (SELECT name FROM session_types WHERE counts_toward = 'practice')"#
+                .to_string(),
+            ChallengeMetric::CheckinDays => {
+                "SELECT DISTINCT date(checked_in_at, 'localtime') AS day, 1.0 AS value FROM check_ins".to_string()
+            }
+            ChallengeMetric::FlashcardReviews => format!(
+                r#"
+                SELECT date(s.started_at, 'localtime') AS day, 1.0 AS value
+                FROM sessions s
+                WHERE s.ended_at IS NOT NULL AND EXISTS (
+                    SELECT 1 FROM session_techniques st JOIN techniques t ON t.id = st.technique_id
+                    WHERE st.session_id = s.id AND t.name = '{}'
+                )"#,
+                FLASHCARD_TECHNIQUE
+            ),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ChallengeStatus {
+    Upcoming,
+    Active,
+    Completed,
+    /// Ended short of the target, or a daily challenge with a missed day
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ChallengeProgress {
+    /// Metric total, or days the target was met for daily challenges
+    pub value: f64,
+    /// Target, or the number of days for daily challenges
+    pub goal: f64,
+    pub percent: i64,
+    /// Share of the challenge's days elapsed, as a percentage
+    pub expected_percent: i64,
+    pub status: ChallengeStatus,
+    /// Days left including today; 0 once ended
+    pub days_left: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Challenge {
+    pub id: i64,
+    pub title: String,
+    pub metric: ChallengeMetric,
+    pub target: f64,
+    pub per_day: bool,
+    pub start_date: String,
+    pub end_date: String,
+    pub completed_at: Option<String>,
+    pub created_at: String,
+    pub progress: ChallengeProgress,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ChallengeMilestone {
+    pub challenge_id: i64,
+    pub title: String,
+    pub percent: i64,
+}
+
+#[derive(FromRow)]
+struct ChallengeRow {
+    id: i64,
+    title: String,
+    metric: String,
+    target: f64,
+    per_day: bool,
+    start_date: String,
+    end_date: String,
+    notified_percent: i64,
+    completed_at: Option<String>,
+    created_at: String,
+}
+
+const SELECT_CHALLENGE: &str = "SELECT id, title, metric, target, per_day, start_date, end_date, notified_percent, completed_at, created_at FROM challenges";
+
+static MILESTONES_REACHED: Lazy<broadcast::Sender<ChallengeMilestone>> = Lazy::new(|| broadcast::channel(16).0);
+
+/// Forward milestones to the frontend as `challenges://milestone` events
+pub fn start_event_forwarder(app_handle: tauri::AppHandle) {
+    use tauri::Emitter;
+
+    let mut milestones = MILESTONES_REACHED.subscribe();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            match milestones.recv().await {
+                Ok(milestone) => {
+                    if let Err(e) = app_handle.emit(MILESTONE_EVENT, milestone) {
+                        log::warn!("Failed to emit challenge milestone: {}", e);
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(_)) => {}
+                Err(broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    });
+}
+
+fn parse_date(date: &str) -> Result<NaiveDate, ApiError> {
+    NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .map_err(|_| ApiError::internal(format!("Invalid challenge date '{}'", date)))
+}
+
+async fn load(pool: &Pool<Sqlite>, row: ChallengeRow, today: NaiveDate) -> Result<(Challenge, i64), ApiError> {
+    let metric = ChallengeMetric::parse(&row.metric)
+        .ok_or_else(|| ApiError::internal(format!("Unknown challenge metric '{}'", row.metric)))?;
+    let start = parse_date(&row.start_date)?;
+    let end = parse_date(&row.end_date)?;
+
+    let daily: Vec<(String, f64)> = sqlx::query_as(&format!(
+        "SELECT day, SUM(value) FROM ({}) WHERE day BETWEEN ?1 AND ?2 GROUP BY day",
+        metric.daily_sql()
+    ))
+    .bind(&row.start_date)
+    .bind(&row.end_date)
+    .fetch_all(pool)
+    .await
+    .map_err(ApiError::from)?;
+
+    let days = (end - start).num_days() + 1;
+    let (value, goal, missed_day) = if row.per_day {
+        let met: Vec<&str> = daily
+            .iter()
+            .filter(|(_, v)| *v >= row.target)
+            .map(|(d, _)| d.as_str())
+            .collect();
+        // Today can still be made up
+        let mut missed = false;
+        let mut day = start;
+        while day < today.min(end + Duration::days(1)) {
+            if !met.contains(&day.format("%Y-%m-%d").to_string().as_str()) {
+                missed = true;
+                break;
+            }
+            day += Duration::days(1);
+        }
+        (met.len() as f64, days as f64, missed)
+    } else {
+        (daily.iter().map(|(_, v)| v).sum(), row.target, false)
+    };
+
+    let percent = ((value / goal * 100.0).floor() as i64).clamp(0, 100);
+    let elapsed = ((today - start).num_days() + 1).clamp(0, days);
+    let status = if row.completed_at.is_some() || percent >= 100 {
+        ChallengeStatus::Completed
+    } else if today < start {
+        ChallengeStatus::Upcoming
+    } else if today > end || missed_day {
+        ChallengeStatus::Failed
+    } else {
+        ChallengeStatus::Active
+    };
+
+    let challenge = Challenge {
+        id: row.id,
+        title: row.title,
+        metric,
+        target: row.target,
+        per_day: row.per_day,
+        start_date: row.start_date,
+        end_date: row.end_date,
+        completed_at: row.completed_at,
+        created_at: row.created_at,
+        progress: ChallengeProgress {
+            value: (value * 10.0).round() / 10.0,
+            goal,
+            percent,
+            expected_percent: elapsed * 100 / days,
+            status,
+            days_left: (days - elapsed).max(0) + i64::from(today >= start && today <= end),
+        },
+    };
+    Ok((challenge, row.notified_percent))
+}
+
+pub async fn get(pool: &Pool<Sqlite>, id: i64) -> Result<Challenge, ApiError> {
+    let row: ChallengeRow = sqlx::query_as(&format!("{} WHERE id = ?", SELECT_CHALLENGE))
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+        .map_err(ApiError::from)?
+        .ok_or_else(|| ApiError::not_found("Challenge not found"))?;
+    Ok(load(pool, row, clock::now().date_naive()).await?.0)
+}
+
+/// Challenges ending soonest first; finished ones only with `include_finished`
+pub async fn list(pool: &Pool<Sqlite>, include_finished: bool) -> Result<Vec<Challenge>, ApiError> {
+    list_as_of(pool, include_finished, clock::now().date_naive()).await
+}
+
+async fn list_as_of(pool: &Pool<Sqlite>, include_finished: bool, today: NaiveDate) -> Result<Vec<Challenge>, ApiError> {
+    let rows: Vec<ChallengeRow> = sqlx::query_as(&format!("{} ORDER BY end_date, id", SELECT_CHALLENGE))
+        .fetch_all(pool)
+        .await
+        .map_err(ApiError::from)?;
+    let mut challenges = Vec::new();
+    for row in rows {
+        let (challenge, _) = load(pool, row, today).await?;
+        if include_finished || matches!(challenge.progress.status, ChallengeStatus::Upcoming | ChallengeStatus::Active) {
+            challenges.push(challenge);
+        }
+    }
+    Ok(challenges)
+}
+
+/// Announce newly reached milestones and stamp completions; returns every
+/// completed challenge, including earlier ones, so awarding from the result
+/// is idempotent
+pub async fn check(pool: &Pool<Sqlite>) -> Result<Vec<Challenge>, ApiError> {
+    let rows: Vec<ChallengeRow> = sqlx::query_as(&format!("{} ORDER BY id", SELECT_CHALLENGE))
+        .fetch_all(pool)
+        .await
+        .map_err(ApiError::from)?;
+    let today = clock::now().date_naive();
+
+    let mut completed = Vec::new();
+    for row in rows {
+        let (mut challenge, notified) = load(pool, row, today).await?;
+        let reached = MILESTONES
+            .iter()
+            .copied()
+            .filter(|m| challenge.progress.percent >= *m)
+            .max()
+            .unwrap_or(0);
+        let counts = matches!(challenge.progress.status, ChallengeStatus::Active | ChallengeStatus::Completed);
+        if counts && reached > notified {
+            let completed_at: Option<String> = sqlx::query_scalar(
+                r#"UPDATE challenges
+                   SET notified_percent = ?1,
+                       completed_at = CASE WHEN ?1 >= 100 THEN COALESCE(completed_at, datetime('now')) END
+                   WHERE id = ?2
+                   RETURNING completed_at"#,
+            )
+            .bind(reached)
+            .bind(challenge.id)
+            .fetch_one(pool)
+            .await
+            .map_err(ApiError::from)?;
+            challenge.completed_at = completed_at;
+            let _ = MILESTONES_REACHED.send(ChallengeMilestone {
+                challenge_id: challenge.id,
+                title: challenge.title.clone(),
+                percent: reached,
+            });
+        }
+        if challenge.completed_at.is_some() {
+            completed.push(challenge);
+        }
+    }
+    Ok(completed)
+}
+
+/// How far active challenges are along on `today` relative to the time
+/// elapsed, 0-1; None without active challenges
+pub async fn goal_progress(pool: &Pool<Sqlite>, today: NaiveDate) -> Result<Option<f32>, ApiError> {
+    let active: Vec<Challenge> = list_as_of(pool, false, today)
+        .await?
+        .into_iter()
+        .filter(|c| c.progress.status == ChallengeStatus::Active)
+        .collect();
+    if active.is_empty() {
+        return Ok(None);
+    }
+    let pace: f32 = active
+        .iter()
+        .map(|c| (c.progress.percent as f32 / c.progress.expected_percent.max(1) as f32).min(1.0))
+        .sum();
+    Ok(Some(pace / active.len() as f32))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Local, TimeZone};
+
+    async fn create(pool: &Pool<Sqlite>, metric: &str, target: f64, per_day: bool, start: &str, end: &str) -> i64 {
+        sqlx::query_scalar(
+            "INSERT INTO challenges (title, metric, target, per_day, start_date, end_date) VALUES ('Challenge', ?, ?, ?, ?, ?) RETURNING id",
+        )
+        .bind(metric)
+        .bind(target)
+        .bind(per_day)
+        .bind(start)
+        .bind(end)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn totals_announce_each_milestone_once_and_complete() {
+        let pool = crate::test_support::pool().await;
+        let _clock = clock::freeze(Local.with_ymd_and_hms(2030, 5, 10, 20, 0, 0).unwrap());
+        let id = create(&pool, "workouts", 4.0, false, "2030-05-01", "2030-05-30").await;
+        let mut milestones = MILESTONES_REACHED.subscribe();
+
+        for _ in 0..2 {
+            sqlx::query("INSERT INTO workouts (name, logged_at) VALUES ('Run', '2030-05-05 12:00:00')")
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+        assert!(check(&pool).await.unwrap().is_empty());
+        assert!(check(&pool).await.unwrap().is_empty());
+        let progress = get(&pool, id).await.unwrap().progress;
+        assert_eq!((progress.percent, progress.status), (50, ChallengeStatus::Active));
+        assert_eq!(progress.expected_percent, 33);
+        assert_eq!(goal_progress(&pool, clock::now().date_naive()).await.unwrap(), Some(1.0));
+
+        sqlx::query("INSERT INTO workouts (name, logged_at) VALUES ('Run', '2030-05-09 12:00:00'), ('Swim', '2030-05-10 12:00:00')")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let completed = check(&pool).await.unwrap();
+        assert_eq!(completed.len(), 1);
+        assert!(completed[0].completed_at.is_some());
+
+        let mut announced = Vec::new();
+        while let Ok(m) = milestones.try_recv() {
+            if m.challenge_id == id {
+                announced.push(m.percent);
+            }
+        }
+        assert_eq!(announced, vec![50, 100]);
+        assert!(list(&pool, false).await.unwrap().is_empty());
+    }
+
+    #[tokio::test]
+    async fn a_missed_day_fails_a_daily_challenge() {
+        let pool = crate::test_support::pool().await;
+        let _clock = clock::freeze(Local.with_ymd_and_hms(2030, 5, 3, 20, 0, 0).unwrap());
+        let id = create(&pool, "checkin_days", 1.0, true, "2030-05-01", "2030-05-14").await;
+        sqlx::query("INSERT INTO check_ins (mood, energy, checked_in_at) VALUES (5, 5, '2030-05-01 12:00:00')")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let progress = get(&pool, id).await.unwrap().progress;
+        assert_eq!(progress.status, ChallengeStatus::Failed);
+        assert_eq!((progress.value, progress.goal), (1.0, 14.0));
+    }
+}
@@ -0,0 +1,267 @@
+//! Change Log
+//!
+//! Records every write that sync or an import makes to local data, with the
+//! row before and after as JSON. Writers take a `snapshot` before changing a
+//! row and `record` the change afterwards, on the same connection (normally
+//! inside their transaction); writes that leave the row as it was are not
+//! logged.
+//!
+//! `revert` puts a row back the way it was before a change: a created row is
+//! deleted, an updated one restored and a deleted one re-inserted with its old
+//! id. A revert is a local edit like any other, so the source may apply the
+//! change again on its next sync if it still differs there.
+
+use serde::Serialize;
+use sqlx::{FromRow, Pool, Sqlite, SqliteConnection};
+
+use crate::error::ApiError;
+
+/// Tables external sources write to; the only ones that can be reverted
+pub const TRACKED_TABLES: &[&str] = &["calendar_events", "week_plan_blocks", "assignments", "exams"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Source {
+    Google,
+    DeadlineImport,
+    IcsImport,
+}
+
+impl Source {
+    fn as_str(self) -> &'static str {
+        match self {
+            Source::Google => "google",
+            Source::DeadlineImport => "deadline_import",
+            Source::IcsImport => "ics_import",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct ChangeLogEntry {
+    pub id: i64,
+    pub source: String,
+    pub entity_type: String,
+    pub entity_id: i64,
+    /// 'create', 'update' or 'delete'
+    pub action: String,
+    pub summary: Option<String>,
+    pub before_json: Option<String>,
+    pub after_json: Option<String>,
+    pub changed_at: String,
+    pub reverted_at: Option<String>,
+}
+
+fn tracked(table: &str) -> Result<&'static str, ApiError> {
+    TRACKED_TABLES
+        .iter()
+        .find(|t| **t == table)
+        .copied()
+        .ok_or_else(|| ApiError::validation(format!("Changes to {} are not tracked", table)))
+}
+
+async fn columns(conn: &mut SqliteConnection, table: &str) -> Result<Vec<String>, ApiError> {
+    Ok(sqlx::query_scalar("SELECT name FROM pragma_table_info(?) ORDER BY cid")
+        .bind(table)
+        .fetch_all(conn)
+        .await?)
+}
+
+/// The row as a JSON object, or None when it doesn't exist
+pub async fn snapshot(conn: &mut SqliteConnection, table: &str, id: i64) -> Result<Option<String>, ApiError> {
+    let table = tracked(table)?;
+    let fields: Vec<String> = columns(&mut *conn, table)
+        .await?
+        .iter()
+        .map(|c| format!("'{c}', \"{c}\""))
+        .collect();
+    Ok(sqlx::query_scalar(&format!("SELECT json_object({}) FROM {} WHERE id = ?", fields.join(", "), table))
+        .bind(id)
+        .fetch_optional(conn)
+        .await?)
+}
+
+/// Log a change to a row whose prior state is `before`
+pub async fn record(
+    conn: &mut SqliteConnection,
+    source: Source,
+    table: &str,
+    id: i64,
+    before: Option<String>,
+    summary: &str,
+) -> Result<(), ApiError> {
+    let after = snapshot(&mut *conn, table, id).await?;
+    let action = match (&before, &after) {
+        (None, None) => return Ok(()),
+        (Some(b), Some(a)) if a == b => return Ok(()),
+        (None, Some(_)) => "create",
+        (Some(_), None) => "delete",
+        (Some(_), Some(_)) => "update",
+    };
+    sqlx::query(
+        "INSERT INTO change_log (source, entity_type, entity_id, action, summary, before_json, after_json) VALUES (?, ?, ?, ?, ?, ?, ?)",
+    )
+    .bind(source.as_str())
+    .bind(table)
+    .bind(id)
+    .bind(action)
+    .bind(summary)
+    .bind(&before)
+    .bind(&after)
+    .execute(conn)
+    .await?;
+    Ok(())
+}
+
+/// Logged changes, newest first
+pub async fn list(pool: &Pool<Sqlite>, source: Option<&str>, limit: i64) -> Result<Vec<ChangeLogEntry>, ApiError> {
+    Ok(sqlx::query_as::<_, ChangeLogEntry>(
+        r#"SELECT id, source, entity_type, entity_id, action, summary, before_json, after_json, changed_at, reverted_at
+           FROM change_log
+           WHERE ?1 IS NULL OR source = ?1
+           ORDER BY id DESC
+           LIMIT ?2"#,
+    )
+    .bind(source)
+    .bind(limit)
+    .fetch_all(pool)
+    .await?)
+}
+
+/// Put the row back the way it was before change `id`
+///
+/// Refused when the row changed again since, unless `force` is set.
+pub async fn revert(pool: &Pool<Sqlite>, id: i64, force: bool) -> Result<ChangeLogEntry, ApiError> {
+    let mut tx = pool.begin().await?;
+    let entry = sqlx::query_as::<_, ChangeLogEntry>(
+        r#"SELECT id, source, entity_type, entity_id, action, summary, before_json, after_json, changed_at, reverted_at
+           FROM change_log WHERE id = ?"#,
+    )
+    .bind(id)
+    .fetch_optional(&mut *tx)
+    .await?
+    .ok_or_else(|| ApiError::not_found("Change not found"))?;
+    if entry.reverted_at.is_some() {
+        return Err(ApiError::conflict("This change was already reverted"));
+    }
+    let table = tracked(&entry.entity_type)?;
+
+    let current = snapshot(&mut tx, table, entry.entity_id).await?;
+    if !force && current != entry.after_json {
+        return Err(ApiError::conflict("The record changed again since; revert anyway to overwrite it")
+            .with_details(serde_json::json!({ "current": current })));
+    }
+
+    let columns = columns(&mut tx, table).await?;
+    match (&entry.before_json, &current) {
+        (None, _) => {
+            sqlx::query(&format!("DELETE FROM {} WHERE id = ?", table))
+                .bind(entry.entity_id)
+                .execute(&mut *tx)
+                .await?;
+        }
+        (Some(before), Some(_)) => {
+            let assignments: Vec<String> = columns
+                .iter()
+                .filter(|c| *c != "id")
+                .map(|c| format!("\"{c}\" = json_extract(?1, '$.{c}')"))
+                .collect();
+            sqlx::query(&format!("UPDATE {} SET {} WHERE id = ?2", table, assignments.join(", ")))
+                .bind(before)
+                .bind(entry.entity_id)
+                .execute(&mut *tx)
+                .await?;
+        }
+        (Some(before), None) => {
+            let names: Vec<String> = columns.iter().map(|c| format!("\"{c}\"")).collect();
+            let values: Vec<String> = columns.iter().map(|c| format!("json_extract(?1, '$.{c}')")).collect();
+            sqlx::query(&format!("INSERT INTO {} ({}) VALUES ({})", table, names.join(", "), values.join(", ")))
+                .bind(before)
+                .execute(&mut *tx)
+                .await?;
+        }
+    }
+
+    let reverted = sqlx::query_as::<_, ChangeLogEntry>(
+        r#"UPDATE change_log SET reverted_at = datetime('now') WHERE id = ?
+           RETURNING id, source, entity_type, entity_id, action, summary, before_json, after_json, changed_at, reverted_at"#,
+    )
+    .bind(id)
+    .fetch_one(&mut *tx)
+    .await?;
+    tx.commit().await?;
+    Ok(reverted)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn google_edit(pool: &Pool<Sqlite>, id: i64, sql: &str) {
+        let mut conn = pool.acquire().await.unwrap();
+        let before = snapshot(&mut conn, "calendar_events", id).await.unwrap();
+        sqlx::query(sql).bind(id).execute(&mut *conn).await.unwrap();
+        record(&mut conn, Source::Google, "calendar_events", id, before, "Synced from Google").await.unwrap();
+    }
+
+    async fn title(pool: &Pool<Sqlite>, id: i64) -> Option<String> {
+        sqlx::query_scalar("SELECT title FROM calendar_events WHERE id = ?")
+            .bind(id)
+            .fetch_optional(pool)
+            .await
+            .unwrap()
+    }
+
+    #[tokio::test]
+    async fn external_updates_and_deletes_are_logged_and_reverted() {
+        let pool = crate::test_support::pool().await;
+        let id: i64 = sqlx::query_scalar(
+            "INSERT INTO calendar_events (title, start_at, end_at, category) VALUES ('Office hours', '2030-01-07T10:00:00', '2030-01-07T11:00:00', 'personal') RETURNING id",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+
+        google_edit(&pool, id, "UPDATE calendar_events SET title = 'Busy' WHERE id = ?").await;
+        // No-op writes aren't logged
+        google_edit(&pool, id, "UPDATE calendar_events SET title = 'Busy' WHERE id = ?").await;
+        let log = list(&pool, Some("google"), 10).await.unwrap();
+        assert_eq!(log.len(), 1);
+        assert_eq!(log[0].action, "update");
+        assert!(list(&pool, Some("deadline_import"), 10).await.unwrap().is_empty());
+
+        let reverted = revert(&pool, log[0].id, false).await.unwrap();
+        assert!(reverted.reverted_at.is_some());
+        assert_eq!(title(&pool, id).await.as_deref(), Some("Office hours"));
+        assert!(revert(&pool, log[0].id, false).await.is_err());
+
+        google_edit(&pool, id, "DELETE FROM calendar_events WHERE id = ?").await;
+        assert_eq!(title(&pool, id).await, None);
+        let deleted = list(&pool, None, 1).await.unwrap().remove(0);
+        assert_eq!(deleted.action, "delete");
+        revert(&pool, deleted.id, false).await.unwrap();
+        assert_eq!(title(&pool, id).await.as_deref(), Some("Office hours"));
+    }
+
+    #[tokio::test]
+    async fn reverting_over_a_later_local_edit_needs_force() {
+        let pool = crate::test_support::pool().await;
+        let id: i64 = sqlx::query_scalar(
+            "INSERT INTO calendar_events (title, start_at, end_at, category) VALUES ('Lab', '2030-01-08T14:00:00', '2030-01-08T16:00:00', 'personal') RETURNING id",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        google_edit(&pool, id, "UPDATE calendar_events SET start_at = '2030-01-08T15:00:00' WHERE id = ?").await;
+        sqlx::query("UPDATE calendar_events SET title = 'Lab (moved)' WHERE id = ?")
+            .bind(id)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let change = list(&pool, None, 1).await.unwrap().remove(0);
+        let err = revert(&pool, change.id, false).await.unwrap_err();
+        assert_eq!(err.code, crate::error::ErrorCode::Conflict);
+        revert(&pool, change.id, true).await.unwrap();
+        assert_eq!(title(&pool, id).await.as_deref(), Some("Lab"));
+    }
+}
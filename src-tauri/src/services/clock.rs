@@ -0,0 +1,113 @@
+//! Clock and Randomness
+//!
+//! The agent's time-dependent and random decisions (context capture,
+//! Thompson sampling, the maintenance schedule) read the time and draw
+//! randomness through here instead of calling `Local::now()` or
+//! `thread_rng()` directly.
+//!
+//! Normally that is the system clock and OS entropy. A test or a bug replay
+//! ("at 23:05 local the planner did X") pins both for the current thread:
+//! `freeze` fixes "now" and `seed` makes every RNG handed out reproducible.
+//! Both return guards that restore the previous state when dropped.
+//! Overrides are per thread, so parallel tests do not see each other's;
+//! `#[tokio::test]` runs the test body on a single thread.
+
+use std::cell::{Cell, RefCell};
+
+use chrono::{DateTime, Local};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+thread_local! {
+    static FROZEN_AT: Cell<Option<DateTime<Local>>> = const { Cell::new(None) };
+    static SEEDED: RefCell<Option<StdRng>> = const { RefCell::new(None) };
+}
+
+/// Current local time, or the frozen time on this thread
+pub fn now() -> DateTime<Local> {
+    FROZEN_AT.with(|f| f.get()).unwrap_or_else(Local::now)
+}
+
+/// RNG for one decision
+///
+/// Seeded on this thread, successive calls return a fixed sequence of
+/// generators; otherwise each is seeded from OS entropy.
+pub fn rng() -> StdRng {
+    SEEDED.with(|s| match s.borrow_mut().as_mut() {
+        Some(parent) => StdRng::seed_from_u64(parent.gen()),
+        None => StdRng::from_entropy(),
+    })
+}
+
+/// Restores the previous frozen time on drop
+#[must_use = "the clock unfreezes when the guard is dropped"]
+pub struct FrozenClock {
+    previous: Option<DateTime<Local>>,
+}
+
+impl Drop for FrozenClock {
+    fn drop(&mut self) {
+        FROZEN_AT.with(|f| f.set(self.previous));
+    }
+}
+
+/// Make `now()` return `at` on this thread until the guard is dropped
+pub fn freeze(at: DateTime<Local>) -> FrozenClock {
+    FrozenClock {
+        previous: FROZEN_AT.with(|f| f.replace(Some(at))),
+    }
+}
+
+/// Restores the previous RNG state on drop
+#[must_use = "seeding ends when the guard is dropped"]
+pub struct SeededRng {
+    previous: Option<StdRng>,
+}
+
+impl Drop for SeededRng {
+    fn drop(&mut self) {
+        let previous = self.previous.take();
+        SEEDED.with(|s| *s.borrow_mut() = previous);
+    }
+}
+
+/// Derive every `rng()` on this thread from `seed` until the guard is dropped
+pub fn seed(seed: u64) -> SeededRng {
+    SeededRng {
+        previous: SEEDED.with(|s| s.replace(Some(StdRng::seed_from_u64(seed)))),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn frozen_time_is_restored_on_drop() {
+        let at = Local.with_ymd_and_hms(2026, 3, 2, 23, 5, 0).unwrap();
+        {
+            let _clock = freeze(at);
+            assert_eq!(now(), at);
+            {
+                let later = at + chrono::Duration::hours(1);
+                let _inner = freeze(later);
+                assert_eq!(now(), later);
+            }
+            assert_eq!(now(), at);
+        }
+        assert_ne!(now(), at);
+    }
+
+    #[test]
+    fn same_seed_gives_the_same_draws() {
+        let draw = || {
+            let _seed = seed(42);
+            (rng().gen::<u64>(), rng().gen::<u64>())
+        };
+        let first = draw();
+        assert_eq!(first, draw());
+        // Each rng() in a sequence is distinct
+        assert_ne!(first.0, first.1);
+    }
+}
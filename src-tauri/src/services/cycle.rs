@@ -0,0 +1,385 @@
+//! Cycle Tracking
+//!
+//! Opt-in (`cycle_tracking_enabled`) menstrual cycle log. Each period is a
+//! start date with an optional last day; nothing else is stored and nothing
+//! leaves the device. The CSV export only includes the log when
+//! `cycle_include_in_exports` is on.
+//!
+//! Predictions use the mean of up to `RECENT_CYCLES` recent cycle lengths
+//! (gaps between starts, ignoring ones outside `PLAUSIBLE_CYCLE_DAYS` as
+//! missed logs) and period lengths, falling back to 28 and 5 days. Ovulation
+//! is placed `LUTEAL_DAYS` before the next start. Days of the current cycle
+//! up to today are never wrapped, so a late period reads as late luteal;
+//! later days are projected from the next predicted start.
+//!
+//! The phase feeds the rich context as expected energy (`phase_energy`) and
+//! the workout planner, which keeps heavy templates off low-energy days.
+
+use chrono::{Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, Pool, Sqlite};
+
+use crate::{error::ApiError, services::settings};
+
+const DEFAULT_CYCLE_DAYS: i64 = 28;
+const DEFAULT_PERIOD_DAYS: i64 = 5;
+/// Cycles averaged for the prediction
+const RECENT_CYCLES: usize = 6;
+const PLAUSIBLE_CYCLE_DAYS: std::ops::RangeInclusive<i64> = 21..=45;
+const MAX_PERIOD_DAYS: i64 = 14;
+/// Days from ovulation to the next period
+const LUTEAL_DAYS: i64 = 14;
+/// Last days of the luteal phase counted as premenstrual
+const PREMENSTRUAL_DAYS: i64 = 5;
+const MAX_NOTE_LENGTH: usize = 500;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CyclePhase {
+    Menstrual,
+    Follicular,
+    Ovulatory,
+    Luteal,
+}
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct CyclePeriod {
+    pub id: i64,
+    /// YYYY-MM-DD
+    pub start_date: String,
+    pub end_date: Option<String>,
+    pub note: Option<String>,
+    pub created_at: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CyclePeriodInput {
+    /// YYYY-MM-DD
+    pub start_date: String,
+    #[serde(default)]
+    pub end_date: Option<String>,
+    #[serde(default)]
+    pub note: Option<String>,
+}
+
+/// Where a day falls in its cycle
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct CycleDay {
+    pub date: NaiveDate,
+    /// 1 on the first day of a period
+    pub day: i64,
+    pub cycle_length: i64,
+    pub phase: CyclePhase,
+    /// Menstrual or premenstrual
+    pub low_energy: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CyclePrediction {
+    pub cycle_length: i64,
+    pub period_length: i64,
+    /// Cycles the lengths were averaged over; 0 means defaults
+    pub cycles_used: i64,
+    /// Standard deviation of the cycle lengths used
+    pub variability_days: Option<f64>,
+    pub last_start: String,
+    pub today: CycleDay,
+    pub next_period_start: String,
+    pub ovulation_date: String,
+    /// Days past the predicted start with no period logged
+    pub days_late: i64,
+}
+
+struct CycleModel {
+    /// Ascending
+    starts: Vec<NaiveDate>,
+    cycle_length: i64,
+    period_length: i64,
+    cycles_used: i64,
+    variability: Option<f64>,
+    today: NaiveDate,
+}
+
+impl CycleModel {
+    fn next_start(&self) -> Option<NaiveDate> {
+        let last = *self.starts.last()?;
+        Some((last + Duration::days(self.cycle_length)).max(self.today + Duration::days(1)))
+    }
+
+    fn day(&self, date: NaiveDate) -> Option<CycleDay> {
+        let index = self.starts.iter().rposition(|s| *s <= date)?;
+        let start = self.starts[index];
+        let (day, length) = match self.starts.get(index + 1) {
+            // A past cycle with its real length
+            Some(next) => ((date - start).num_days() + 1, (*next - start).num_days()),
+            None if date <= self.today => ((date - start).num_days() + 1, self.cycle_length),
+            None => {
+                let next = self.next_start()?;
+                if date < next {
+                    ((date - start).num_days() + 1, self.cycle_length)
+                } else {
+                    ((date - next).num_days() % self.cycle_length + 1, self.cycle_length)
+                }
+            }
+        };
+        let phase = phase(day, length, self.period_length);
+        Some(CycleDay {
+            date,
+            day,
+            cycle_length: length,
+            phase,
+            low_energy: phase == CyclePhase::Menstrual || day > length - PREMENSTRUAL_DAYS,
+        })
+    }
+}
+
+fn phase(day: i64, cycle_length: i64, period_length: i64) -> CyclePhase {
+    let ovulation = (cycle_length - LUTEAL_DAYS).max(period_length + 2);
+    if day <= period_length {
+        CyclePhase::Menstrual
+    } else if (day - ovulation).abs() <= 1 {
+        CyclePhase::Ovulatory
+    } else if day < ovulation {
+        CyclePhase::Follicular
+    } else {
+        CyclePhase::Luteal
+    }
+}
+
+/// Expected energy of a phase, 0-1; premenstrual days sit below the rest of
+/// the luteal phase
+pub fn phase_energy(day: &CycleDay) -> f32 {
+    match day.phase {
+        CyclePhase::Menstrual => 0.25,
+        CyclePhase::Follicular => 0.75,
+        CyclePhase::Ovulatory => 0.9,
+        CyclePhase::Luteal if day.low_energy => 0.35,
+        CyclePhase::Luteal => 0.55,
+    }
+}
+
+pub async fn enabled(pool: &Pool<Sqlite>) -> Result<bool, ApiError> {
+    settings::get_bool(pool, "cycle_tracking_enabled").await
+}
+
+async fn ensure_enabled(pool: &Pool<Sqlite>) -> Result<(), ApiError> {
+    if enabled(pool).await? {
+        Ok(())
+    } else {
+        Err(ApiError::validation("Cycle tracking is off; turn on cycle_tracking_enabled to use it"))
+    }
+}
+
+fn parse_date(value: &str, name: &str) -> Result<NaiveDate, ApiError> {
+    NaiveDate::parse_from_str(value.trim(), "%Y-%m-%d")
+        .map_err(|_| ApiError::validation(format!("{} must be a YYYY-MM-DD date", name)))
+}
+
+/// Log a period, or update the one starting that day
+pub async fn log_period(pool: &Pool<Sqlite>, input: &CyclePeriodInput, today: NaiveDate) -> Result<CyclePeriod, ApiError> {
+    ensure_enabled(pool).await?;
+    let start = parse_date(&input.start_date, "start_date")?;
+    let end = input.end_date.as_deref().map(|d| parse_date(d, "end_date")).transpose()?;
+    if start > today {
+        return Err(ApiError::validation("A period can't start in the future"));
+    }
+    if let Some(end) = end {
+        if end < start || (end - start).num_days() >= MAX_PERIOD_DAYS {
+            return Err(ApiError::validation(format!(
+                "end_date must be on or after start_date and within {} days",
+                MAX_PERIOD_DAYS
+            )));
+        }
+    }
+    let note = input.note.as_deref().map(str::trim).filter(|n| !n.is_empty());
+    if note.is_some_and(|n| n.chars().count() > MAX_NOTE_LENGTH) {
+        return Err(ApiError::validation(format!("Note must be at most {} characters", MAX_NOTE_LENGTH)));
+    }
+
+    let nearby: Option<String> = sqlx::query_scalar(
+        "SELECT start_date FROM cycle_periods WHERE start_date != ?1 AND abs(julianday(start_date) - julianday(?1)) < ?2",
+    )
+    .bind(start.to_string())
+    .bind(MAX_PERIOD_DAYS)
+    .fetch_optional(pool)
+    .await
+    .map_err(ApiError::from)?;
+    if let Some(other) = nearby {
+        return Err(ApiError::conflict(format!("A period starting {} is already logged", other)));
+    }
+
+    sqlx::query_as(
+        r#"INSERT INTO cycle_periods (start_date, end_date, note) VALUES (?, ?, ?)
+           ON CONFLICT (start_date) DO UPDATE SET end_date = excluded.end_date, note = excluded.note
+           RETURNING id, start_date, end_date, note, created_at"#,
+    )
+    .bind(start.to_string())
+    .bind(end.map(|d| d.to_string()))
+    .bind(note)
+    .fetch_one(pool)
+    .await
+    .map_err(ApiError::from)
+}
+
+pub async fn delete_period(pool: &Pool<Sqlite>, id: i64) -> Result<bool, ApiError> {
+    let result = sqlx::query("DELETE FROM cycle_periods WHERE id = ?")
+        .bind(id)
+        .execute(pool)
+        .await
+        .map_err(ApiError::from)?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Newest first
+pub async fn periods(pool: &Pool<Sqlite>) -> Result<Vec<CyclePeriod>, ApiError> {
+    ensure_enabled(pool).await?;
+    sqlx::query_as("SELECT id, start_date, end_date, note, created_at FROM cycle_periods ORDER BY start_date DESC")
+        .fetch_all(pool)
+        .await
+        .map_err(ApiError::from)
+}
+
+fn mean(values: &[i64]) -> Option<f64> {
+    (!values.is_empty()).then(|| values.iter().sum::<i64>() as f64 / values.len() as f64)
+}
+
+/// None while tracking is off or nothing is logged
+async fn model(pool: &Pool<Sqlite>, today: NaiveDate) -> Result<Option<CycleModel>, ApiError> {
+    if !enabled(pool).await? {
+        return Ok(None);
+    }
+    let rows: Vec<(String, Option<String>)> =
+        sqlx::query_as("SELECT start_date, end_date FROM cycle_periods WHERE start_date <= ? ORDER BY start_date")
+            .bind(today.to_string())
+            .fetch_all(pool)
+            .await
+            .map_err(ApiError::from)?;
+    let periods: Vec<(NaiveDate, Option<NaiveDate>)> = rows
+        .iter()
+        .filter_map(|(start, end)| {
+            let start = NaiveDate::parse_from_str(start, "%Y-%m-%d").ok()?;
+            Some((start, end.as_deref().and_then(|e| NaiveDate::parse_from_str(e, "%Y-%m-%d").ok())))
+        })
+        .collect();
+    if periods.is_empty() {
+        return Ok(None);
+    }
+
+    let starts: Vec<NaiveDate> = periods.iter().map(|(s, _)| *s).collect();
+    let cycles: Vec<i64> = starts
+        .windows(2)
+        .map(|w| (w[1] - w[0]).num_days())
+        .filter(|d| PLAUSIBLE_CYCLE_DAYS.contains(d))
+        .collect();
+    let cycles = &cycles[cycles.len().saturating_sub(RECENT_CYCLES)..];
+    let lengths: Vec<i64> = periods
+        .iter()
+        .filter_map(|(start, end)| end.map(|e| (e - *start).num_days() + 1))
+        .collect();
+    let lengths = &lengths[lengths.len().saturating_sub(RECENT_CYCLES)..];
+
+    let cycle_mean = mean(cycles);
+    let variability = cycle_mean.filter(|_| cycles.len() >= 2).map(|m| {
+        let variance = cycles.iter().map(|c| (*c as f64 - m).powi(2)).sum::<f64>() / cycles.len() as f64;
+        (variance.sqrt() * 10.0).round() / 10.0
+    });
+    Ok(Some(CycleModel {
+        starts,
+        cycle_length: cycle_mean.map_or(DEFAULT_CYCLE_DAYS, |m| m.round() as i64),
+        period_length: mean(lengths).map_or(DEFAULT_PERIOD_DAYS, |m| m.round() as i64),
+        cycles_used: cycles.len() as i64,
+        variability,
+        today,
+    }))
+}
+
+/// Today's phase and the next period; None while tracking is off or nothing
+/// is logged
+pub async fn prediction(pool: &Pool<Sqlite>, today: NaiveDate) -> Result<Option<CyclePrediction>, ApiError> {
+    let Some(model) = model(pool, today).await? else {
+        return Ok(None);
+    };
+    let (Some(last_start), Some(next), Some(day)) = (model.starts.last().copied(), model.next_start(), model.day(today))
+    else {
+        return Ok(None);
+    };
+    let predicted = last_start + Duration::days(model.cycle_length);
+    Ok(Some(CyclePrediction {
+        cycle_length: model.cycle_length,
+        period_length: model.period_length,
+        cycles_used: model.cycles_used,
+        variability_days: model.variability,
+        last_start: last_start.to_string(),
+        today: day,
+        next_period_start: next.to_string(),
+        ovulation_date: (predicted - Duration::days(LUTEAL_DAYS)).to_string(),
+        days_late: (today - predicted).num_days().max(0),
+    }))
+}
+
+/// Predicted days from `from` to `to` inclusive; empty while tracking is off
+pub async fn days(pool: &Pool<Sqlite>, from: NaiveDate, to: NaiveDate, today: NaiveDate) -> Result<Vec<CycleDay>, ApiError> {
+    let Some(model) = model(pool, today).await? else {
+        return Ok(Vec::new());
+    };
+    Ok(from
+        .iter_days()
+        .take_while(|d| *d <= to)
+        .filter_map(|d| model.day(d))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn period(start: &str, end: Option<&str>) -> CyclePeriodInput {
+        CyclePeriodInput {
+            start_date: start.to_string(),
+            end_date: end.map(str::to_string),
+            note: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn phases_are_predicted_from_logged_cycles() {
+        let pool = crate::test_support::pool().await;
+        let today = NaiveDate::from_ymd_opt(2030, 4, 20).unwrap();
+        assert!(log_period(&pool, &period("2030-04-01", None), today).await.is_err());
+        assert!(prediction(&pool, today).await.unwrap().is_none());
+
+        settings::set(&pool, "cycle_tracking_enabled", json!(true)).await.unwrap();
+        // 30- and 32-day cycles, 4- and 6-day periods; the 70-day gap is a missed log
+        for (start, end) in [
+            ("2029-11-16", Some("2029-11-19")),
+            ("2030-01-25", None),
+            ("2030-02-24", Some("2030-03-01")),
+            ("2030-03-28", None),
+        ] {
+            log_period(&pool, &period(start, end), today).await.unwrap();
+        }
+        assert!(log_period(&pool, &period("2030-03-30", None), today).await.is_err());
+        assert!(log_period(&pool, &period("2030-05-01", None), today).await.is_err());
+
+        let p = prediction(&pool, today).await.unwrap().unwrap();
+        assert_eq!((p.cycle_length, p.period_length, p.cycles_used), (31, 5, 2));
+        assert_eq!(p.variability_days, Some(1.0));
+        assert_eq!(p.next_period_start, "2030-04-28");
+        assert_eq!(p.ovulation_date, "2030-04-14");
+        assert_eq!((p.today.day, p.today.phase, p.days_late), (24, CyclePhase::Luteal, 0));
+
+        // Projected past the next start into the following cycle
+        let week = days(&pool, today, today + Duration::days(13), today).await.unwrap();
+        let late = week.iter().find(|d| d.date == NaiveDate::from_ymd_opt(2030, 4, 27).unwrap()).unwrap();
+        assert!(late.low_energy && late.phase == CyclePhase::Luteal);
+        let next = week.iter().find(|d| d.date == NaiveDate::from_ymd_opt(2030, 4, 28).unwrap()).unwrap();
+        assert_eq!((next.day, next.phase), (1, CyclePhase::Menstrual));
+        assert_eq!(week.last().unwrap().phase, CyclePhase::Follicular);
+
+        // Late: today keeps counting instead of wrapping
+        let later = NaiveDate::from_ymd_opt(2030, 4, 30).unwrap();
+        let p = prediction(&pool, later).await.unwrap().unwrap();
+        assert_eq!((p.today.day, p.days_late, p.next_period_start.as_str()), (34, 2, "2030-05-01"));
+    }
+}
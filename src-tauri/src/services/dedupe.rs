@@ -0,0 +1,205 @@
+//! Duplicate Detection
+//!
+//! Guards the manual create commands against double logging:
+//! - workouts: same name and duration logged within `WORKOUT_WINDOW_MINUTES`
+//! - assignments: same course and due date, and the same title ignoring case
+//!   and surrounding whitespace
+//! - sessions: same type and reference as a session running at the new start;
+//!   open sessions count as running for `MAX_OPEN_SESSION_HOURS`
+//!
+//! A match fails with a conflict whose details name the existing row
+//! (`{"duplicate_of": {"kind", "id"}}`); the commands take `force` to log it
+//! anyway.
+
+use serde_json::json;
+use sqlx::{Pool, Sqlite};
+
+use crate::{
+    commands::{assignments::AssignmentInput, sessions::SessionInput, workouts::WorkoutInput},
+    error::ApiError,
+};
+
+/// Workouts this close together are treated as the same one
+const WORKOUT_WINDOW_MINUTES: i64 = 10;
+
+/// An open session older than this is assumed forgotten, not running
+const MAX_OPEN_SESSION_HOURS: i64 = 12;
+
+fn duplicate(kind: &str, id: i64, message: String) -> ApiError {
+    ApiError::conflict(message).with_details(json!({ "duplicate_of": { "kind": kind, "id": id } }))
+}
+
+pub async fn check_workout(pool: &Pool<Sqlite>, data: &WorkoutInput) -> Result<(), ApiError> {
+    let existing: Option<(i64, String)> = sqlx::query_as(
+        r#"
+        SELECT id, logged_at FROM workouts
+        WHERE lower(trim(COALESCE(name, ''))) = lower(trim(COALESCE(?1, '')))
+          AND duration_minutes IS ?2
+          AND abs(julianday(logged_at) - julianday(COALESCE(?3, CURRENT_TIMESTAMP))) * 1440 <= ?4
+        ORDER BY id DESC
+        LIMIT 1
+        "#,
+    )
+    .bind(&data.name)
+    .bind(data.duration_minutes)
+    .bind(&data.logged_at)
+    .bind(WORKOUT_WINDOW_MINUTES)
+    .fetch_optional(pool)
+    .await
+    .map_err(ApiError::from)?;
+
+    match existing {
+        Some((id, logged_at)) => Err(duplicate(
+            "workout",
+            id,
+            format!("A matching workout was already logged at {}", logged_at),
+        )),
+        None => Ok(()),
+    }
+}
+
+pub async fn check_assignment(pool: &Pool<Sqlite>, data: &AssignmentInput) -> Result<(), ApiError> {
+    let existing: Option<i64> = sqlx::query_scalar(
+        "SELECT id FROM assignments WHERE course_id = ? AND lower(trim(title)) = lower(trim(?)) AND due_date IS ? LIMIT 1",
+    )
+    .bind(data.course_id)
+    .bind(&data.title)
+    .bind(&data.due_date)
+    .fetch_optional(pool)
+    .await
+    .map_err(ApiError::from)?;
+
+    match existing {
+        Some(id) => Err(duplicate(
+            "assignment",
+            id,
+            format!("\"{}\" already exists with the same due date", data.title.trim()),
+        )),
+        None => Ok(()),
+    }
+}
+
+pub async fn check_session(pool: &Pool<Sqlite>, data: &SessionInput) -> Result<(), ApiError> {
+    let existing: Option<(i64, bool)> = sqlx::query_as(
+        r#"
+        SELECT id, ended_at IS NULL FROM sessions
+        WHERE session_type = ?1 AND reference_type IS ?2 AND reference_id IS ?3
+          AND julianday(started_at) <= julianday(COALESCE(?4, CURRENT_TIMESTAMP))
+          AND CASE WHEN ended_at IS NULL
+                   THEN julianday(started_at) > julianday(COALESCE(?4, CURRENT_TIMESTAMP), '-' || ?5 || ' hours')
+                   ELSE julianday(ended_at) > julianday(COALESCE(?4, CURRENT_TIMESTAMP)) END
+        ORDER BY started_at DESC
+        LIMIT 1
+        "#,
+    )
+    .bind(&data.session_type)
+    .bind(&data.reference_type)
+    .bind(data.reference_id)
+    .bind(&data.started_at)
+    .bind(MAX_OPEN_SESSION_HOURS)
+    .fetch_optional(pool)
+    .await
+    .map_err(ApiError::from)?;
+
+    match existing {
+        Some((id, true)) => Err(duplicate("session", id, "A matching session is already running".to_string())),
+        Some((id, false)) => Err(duplicate("session", id, "A matching session already covers that time".to_string())),
+        None => Ok(()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        commands::{assignments::insert_assignment, sessions::insert_session, workouts::insert_workout},
+        models::session::SessionType,
+    };
+
+    fn workout(name: &str, logged_at: &str) -> WorkoutInput {
+        WorkoutInput {
+            user_id: None,
+            name: Some(name.to_string()),
+            duration_minutes: Some(45),
+            notes: None,
+            notes_md: None,
+            logged_at: Some(logged_at.to_string()),
+        }
+    }
+
+    fn session(started_at: &str) -> SessionInput {
+        SessionInput {
+            user_id: None,
+            session_type: SessionType::Study,
+            reference_id: Some(1),
+            reference_type: Some("course".to_string()),
+            started_at: Some(started_at.to_string()),
+            notes: None,
+            planned_minutes: None,
+            focus_profile_id: None,
+            title: None,
+            course_meeting_id: None,
+            meeting_date: None,
+            week_plan_block_id: None,
+        }
+    }
+
+    fn duplicate_id(err: ApiError) -> i64 {
+        assert_eq!(err.code, crate::error::ErrorCode::Conflict);
+        err.details.unwrap()["duplicate_of"]["id"].as_i64().unwrap()
+    }
+
+    #[tokio::test]
+    async fn workouts_logged_minutes_apart_are_duplicates() {
+        let pool = crate::test_support::pool().await;
+        let first = insert_workout(&pool, &workout("Leg day", "2030-02-01 18:00:00")).await.unwrap();
+
+        let err = check_workout(&pool, &workout(" leg day", "2030-02-01 18:07:00")).await.unwrap_err();
+        assert_eq!(duplicate_id(err), first.id);
+        assert!(check_workout(&pool, &workout("Leg day", "2030-02-01 18:30:00")).await.is_ok());
+        assert!(check_workout(&pool, &workout("Push day", "2030-02-01 18:05:00")).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn assignments_match_on_course_title_and_due_date() {
+        let pool = crate::test_support::pool().await;
+        let course = crate::test_support::course("History").create(&pool).await;
+        let input = |title: &str, due: &str| AssignmentInput {
+            course_id: course.id,
+            title: title.to_string(),
+            description: None,
+            due_date: Some(due.to_string()),
+            priority: None,
+            score: None,
+            estimated_minutes: None,
+            actual_minutes: None,
+        };
+        let essay = insert_assignment(&pool, &input("Essay 1", "2030-02-10")).await.unwrap();
+
+        let err = check_assignment(&pool, &input("essay 1 ", "2030-02-10")).await.unwrap_err();
+        assert_eq!(duplicate_id(err), essay.id);
+        assert!(check_assignment(&pool, &input("Essay 1", "2030-02-17")).await.is_ok());
+    }
+
+    #[tokio::test]
+    async fn sessions_overlapping_a_matching_one_are_duplicates() {
+        let pool = crate::test_support::pool().await;
+        let running = insert_session(&pool, &session("2030-02-01 09:00:00")).await.unwrap();
+
+        let err = check_session(&pool, &session("2030-02-01 09:30:00")).await.unwrap_err();
+        assert_eq!(duplicate_id(err), running.id);
+        // A different reference, or an open session long forgotten, doesn't count
+        let mut other = session("2030-02-01 09:30:00");
+        other.reference_id = Some(2);
+        assert!(check_session(&pool, &other).await.is_ok());
+        assert!(check_session(&pool, &session("2030-02-02 09:00:00")).await.is_ok());
+
+        sqlx::query("UPDATE sessions SET ended_at = '2030-02-01 10:00:00' WHERE id = ?")
+            .bind(running.id)
+            .execute(&pool)
+            .await
+            .unwrap();
+        assert!(check_session(&pool, &session("2030-02-01 09:59:00")).await.is_err());
+        assert!(check_session(&pool, &session("2030-02-01 10:00:00")).await.is_ok());
+    }
+}
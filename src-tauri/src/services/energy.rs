@@ -0,0 +1,231 @@
+//! Energy Budget
+//!
+//! Each day starts with `DAILY_BUDGET` points of energy, scaled by:
+//! - sleep: activity past bedtime (worked out from `sleep_hours` and a
+//!   `WAKE_HOUR` wake-up) the night before shortens the night
+//! - the day's latest check-in energy, when there is one
+//! - recovery: a share of the previous day's workouts carries over
+//!
+//! Study and practice sessions spend points per minute, more at a higher
+//! focus rating, and so do workouts. The agent holds back demanding actions
+//! once the remainder drops below `LOW_ENERGY_SHARE`, the rich context's
+//! fatigue is the spent share, and the week planner caps each day's study by
+//! `demanding_minutes_left`.
+
+use chrono::{Duration, NaiveDate, NaiveTime};
+use serde::Serialize;
+use sqlx::{Pool, Sqlite};
+
+use crate::{error::ApiError, services::settings};
+
+pub const DAILY_BUDGET: f64 = 100.0;
+
+/// Assumed wake-up hour; bedtime is `sleep_hours` before it
+const WAKE_HOUR: u32 = 7;
+
+/// Points per minute of activity, at a neutral focus rating
+const STUDY_COST: f64 = 0.25;
+const PRACTICE_COST: f64 = 0.15;
+const WORKOUT_COST: f64 = 0.3;
+
+/// Share of the previous day's workout cost still owed the next day
+const WORKOUT_CARRY_OVER: f64 = 0.3;
+
+/// Below this share of the budget left, demanding actions are held back
+pub const LOW_ENERGY_SHARE: f64 = 0.2;
+
+/// Agent actions that need a good share of the day's energy
+pub const DEMANDING_ACTIONS: &[&str] = &[
+    "deep_work_block",
+    "tackle_assignment",
+    "start_study_session",
+    "start_pomodoro",
+    "do_workout",
+    "learn_new",
+];
+
+#[derive(Debug, Clone, Serialize)]
+pub struct EnergyBudget {
+    pub date: String,
+    /// Points available for the day after sleep, check-in and recovery
+    pub budget: f64,
+    pub spent: f64,
+    pub remaining: f64,
+    /// Estimated sleep the night before, in hours
+    pub sleep_hours: f64,
+    /// Minutes of study the remainder still covers
+    pub demanding_minutes_left: i64,
+}
+
+impl EnergyBudget {
+    /// Spent share of the budget (0-1)
+    pub fn fatigue(&self) -> f64 {
+        if self.budget > 0.0 {
+            (self.spent / self.budget).clamp(0.0, 1.0)
+        } else {
+            1.0
+        }
+    }
+
+    pub fn is_low(&self) -> bool {
+        self.remaining < self.budget * LOW_ENERGY_SHARE
+    }
+}
+
+/// The energy budget of `date` (local), with what was spent so far
+pub async fn energy_budget(pool: &Pool<Sqlite>, date: NaiveDate) -> Result<EnergyBudget, ApiError> {
+    let day = date.to_string();
+    let yesterday = (date - Duration::days(1)).to_string();
+
+    // Activity that ran past bedtime, up to the wake-up
+    let sleep_need = settings::get_i64(pool, "sleep_hours").await? as f64;
+    let wake = date.and_time(NaiveTime::from_hms_opt(WAKE_HOUR, 0, 0).unwrap_or_default());
+    let bedtime = wake - Duration::minutes((sleep_need * 60.0) as i64);
+    let fmt = "%Y-%m-%d %H:%M:%S";
+    let lost_hours: Option<f64> = sqlx::query_scalar(
+        r#"
+        SELECT MAX((julianday(last_end) - julianday(?1)) * 24) FROM (
+            SELECT datetime(ended_at, 'localtime') AS last_end FROM sessions WHERE ended_at IS NOT NULL
+            UNION ALL
+            SELECT datetime(logged_at, 'localtime') FROM workouts
+        )
+        WHERE last_end > ?1 AND last_end < ?2
+        "#,
+    )
+    .bind(bedtime.format(fmt).to_string())
+    .bind(wake.format(fmt).to_string())
+    .fetch_one(pool)
+    .await
+    .map_err(ApiError::from)?;
+    let sleep_hours = (sleep_need - lost_hours.unwrap_or(0.0)).max(0.0);
+    let sleep_factor = if sleep_need > 0.0 { (sleep_hours / sleep_need).clamp(0.5, 1.0) } else { 1.0 };
+
+    let checkin_energy: Option<i64> = sqlx::query_scalar(
+        "SELECT energy FROM check_ins WHERE energy IS NOT NULL AND date(checked_in_at, 'localtime') = ? ORDER BY checked_in_at DESC LIMIT 1",
+    )
+    .bind(&day)
+    .fetch_optional(pool)
+    .await
+    .map_err(ApiError::from)?;
+    // 1 -> 0.65, 8 -> 1.0, 10 -> 1.1
+    let checkin_factor = checkin_energy.map_or(1.0, |e| 0.6 + 0.05 * e as f64);
+
+    let (session_cost, workout_minutes, previous_workout_minutes): (f64, i64, i64) = sqlx::query_as(
+        r#"
+        SELECT
+            COALESCE((SELECT SUM(duration_minutes
+                        * CASE session_type WHEN 'study' THEN ?3 ELSE ?4 END
+                        * CASE WHEN focus_rating IS NULL THEN 1.0 ELSE 0.8 + 0.1 * (focus_rating - 1) END)
+                      FROM sessions WHERE date(started_at, 'localtime') = ?1), 0.0),
+            COALESCE((SELECT SUM(duration_minutes) FROM workouts WHERE date(logged_at, 'localtime') = ?1), 0),
+            COALESCE((SELECT SUM(duration_minutes) FROM workouts WHERE date(logged_at, 'localtime') = ?2), 0)
+        "#,
+    )
+    .bind(&day)
+    .bind(&yesterday)
+    .bind(STUDY_COST)
+    .bind(PRACTICE_COST)
+    .fetch_one(pool)
+    .await
+    .map_err(ApiError::from)?;
+
+    let carried = previous_workout_minutes as f64 * WORKOUT_COST * WORKOUT_CARRY_OVER;
+    let budget = (DAILY_BUDGET * sleep_factor * checkin_factor - carried).max(0.0);
+    let spent = session_cost + workout_minutes as f64 * WORKOUT_COST;
+    let remaining = (budget - spent).max(0.0);
+
+    Ok(EnergyBudget {
+        date: day,
+        budget: budget.round(),
+        spent: spent.round(),
+        remaining: remaining.round(),
+        sleep_hours: (sleep_hours * 10.0).round() / 10.0,
+        demanding_minutes_left: (remaining / STUDY_COST).round() as i64,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Local, TimeZone, Utc};
+
+    /// A local time plus `minutes`, as stored (UTC)
+    fn utc(day: u32, hour: u32, minutes: i64) -> String {
+        (Local.with_ymd_and_hms(2030, 4, day, hour, 0, 0).unwrap() + Duration::minutes(minutes))
+            .with_timezone(&Utc)
+            .format("%Y-%m-%d %H:%M:%S")
+            .to_string()
+    }
+
+    async fn session(pool: &Pool<Sqlite>, session_type: &str, day: u32, start: u32, minutes: i64, focus: Option<i64>) {
+        sqlx::query(
+            "INSERT INTO sessions (session_type, started_at, ended_at, duration_minutes, focus_rating) VALUES (?, ?, ?, ?, ?)",
+        )
+        .bind(session_type)
+        .bind(utc(day, start, 0))
+        .bind(utc(day, start, minutes))
+        .bind(minutes)
+        .bind(focus)
+        .execute(pool)
+        .await
+        .unwrap();
+    }
+
+    #[tokio::test]
+    async fn sessions_and_workouts_spend_the_budget() {
+        let pool = crate::test_support::pool().await;
+        let date = NaiveDate::from_ymd_opt(2030, 4, 10).unwrap();
+
+        let fresh = energy_budget(&pool, date).await.unwrap();
+        assert_eq!(fresh.budget, DAILY_BUDGET);
+        assert_eq!(fresh.sleep_hours, 8.0);
+        assert_eq!(fresh.demanding_minutes_left, 400);
+
+        // 2h of study at the top focus rating: 120 * 0.25 * 1.2
+        session(&pool, "study", 10, 9, 120, Some(5)).await;
+        // 1h of practice at no rating: 60 * 0.15
+        session(&pool, "practice", 10, 14, 60, None).await;
+        sqlx::query("INSERT INTO workouts (duration_minutes, logged_at) VALUES (50, ?)")
+            .bind(utc(10, 18, 0))
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let budget = energy_budget(&pool, date).await.unwrap();
+        assert_eq!(budget.spent, 60.0);
+        assert_eq!(budget.remaining, 40.0);
+        assert_eq!(budget.demanding_minutes_left, 160);
+        assert!((budget.fatigue() - 0.6).abs() < 1e-9);
+        assert!(!budget.is_low());
+    }
+
+    #[tokio::test]
+    async fn a_late_night_low_check_in_and_yesterdays_workout_shrink_the_budget() {
+        let pool = crate::test_support::pool().await;
+        let date = NaiveDate::from_ymd_opt(2030, 4, 10).unwrap();
+
+        // Bedtime is 23:00; studying until 01:00 costs two hours of sleep
+        session(&pool, "study", 9, 22, 180, None).await;
+        let budget = energy_budget(&pool, date).await.unwrap();
+        assert_eq!(budget.sleep_hours, 6.0);
+        assert_eq!(budget.budget, 75.0);
+
+        // Yesterday's hour of training: 60 * 0.3 * 0.3 carried over
+        sqlx::query("INSERT INTO workouts (duration_minutes, logged_at) VALUES (60, ?)")
+            .bind(utc(9, 12, 0))
+            .execute(&pool)
+            .await
+            .unwrap();
+        // Check-in energy 4 of 10: * 0.8
+        sqlx::query("INSERT INTO check_ins (mood, energy, checked_in_at) VALUES (5, 4, ?)")
+            .bind(utc(10, 8, 0))
+            .execute(&pool)
+            .await
+            .unwrap();
+        let budget = energy_budget(&pool, date).await.unwrap();
+        assert_eq!(budget.budget, 55.0);
+
+        session(&pool, "study", 10, 9, 180, None).await;
+        assert!(energy_budget(&pool, date).await.unwrap().is_low());
+    }
+}
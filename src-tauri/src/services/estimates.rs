@@ -0,0 +1,155 @@
+//! Assignment Estimate Calibration
+//!
+//! Learns, per course, how long assignments really take compared with their
+//! `estimated_minutes`. Actual time is the recorded `actual_minutes`, or else
+//! the study sessions logged against the assignment. The ratio is smoothed
+//! towards 1 so a single outlier doesn't swing the forecast, and applied to
+//! open assignments wherever their effort is forecast.
+
+use std::collections::HashMap;
+
+use serde::Serialize;
+use sqlx::{Pool, Sqlite};
+
+use crate::error::ApiError;
+
+/// Most recent completed assignments used per course
+const MAX_SAMPLES: i64 = 20;
+/// Minutes of "estimate was right" added to both sides of the ratio
+const PRIOR_MINUTES: f64 = 120.0;
+const MIN_RATIO: f64 = 0.25;
+const MAX_RATIO: f64 = 4.0;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct CourseCalibration {
+    pub course_id: i64,
+    pub course_name: String,
+    /// Completed assignments with both an estimate and an actual time
+    pub samples: i64,
+    pub estimated_minutes: i64,
+    pub actual_minutes: i64,
+    /// Multiply estimates by this to get the expected time
+    pub ratio: f64,
+}
+
+/// Calibration for every course with at least one usable sample
+pub async fn calibrations(pool: &Pool<Sqlite>) -> Result<Vec<CourseCalibration>, ApiError> {
+    let rows: Vec<(i64, String, i64, i64, i64)> = sqlx::query_as(
+        r#"
+        WITH done AS (
+            SELECT a.course_id, a.estimated_minutes AS estimated,
+                   COALESCE(a.actual_minutes,
+                            (SELECT SUM(s.duration_minutes) FROM sessions s
+                             WHERE s.reference_type = 'assignment' AND s.reference_id = a.id)) AS actual,
+                   ROW_NUMBER() OVER (PARTITION BY a.course_id ORDER BY a.completed_at DESC, a.id DESC) AS n
+            FROM assignments a
+            WHERE a.is_completed = 1 AND a.estimated_minutes > 0
+        )
+        SELECT c.id, c.name, COUNT(*), SUM(d.estimated), SUM(d.actual)
+        FROM done d
+        JOIN courses c ON c.id = d.course_id
+        WHERE d.actual > 0 AND d.n <= ?
+        GROUP BY c.id
+        ORDER BY c.name
+        "#,
+    )
+    .bind(MAX_SAMPLES)
+    .fetch_all(pool)
+    .await
+    .map_err(ApiError::from)?;
+
+    Ok(rows
+        .into_iter()
+        .map(|(course_id, course_name, samples, estimated, actual)| CourseCalibration {
+            course_id,
+            course_name,
+            samples,
+            estimated_minutes: estimated,
+            actual_minutes: actual,
+            ratio: ((actual as f64 + PRIOR_MINUTES) / (estimated as f64 + PRIOR_MINUTES)).clamp(MIN_RATIO, MAX_RATIO),
+        })
+        .collect())
+}
+
+/// course_id -> ratio; courses without history are absent (ratio 1)
+pub async fn ratios(pool: &Pool<Sqlite>) -> Result<HashMap<i64, f64>, ApiError> {
+    Ok(calibrations(pool)
+        .await?
+        .into_iter()
+        .map(|c| (c.course_id, c.ratio))
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+    use std::str::FromStr;
+
+    async fn setup_pool_with_migrations() -> Pool<Sqlite> {
+        let options = SqliteConnectOptions::from_str("sqlite::memory:")
+            .unwrap()
+            .foreign_keys(false);
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect_with(options)
+            .await
+            .expect("Failed to connect to in-memory DB");
+
+        crate::db::migrations::run_migrations(&pool)
+            .await
+            .expect("Failed to run migrations");
+
+        pool
+    }
+
+    #[tokio::test]
+    async fn learns_chronic_underestimation_per_course() {
+        let pool = setup_pool_with_migrations().await;
+        let course = |name: &'static str| {
+            sqlx::query_scalar::<_, i64>("INSERT INTO courses (name) VALUES (?) RETURNING id")
+                .bind(name)
+                .fetch_one(&pool)
+        };
+        let physics = course("Physics").await.unwrap();
+        let english = course("English").await.unwrap();
+
+        // Physics problem sets take twice the estimate
+        for _ in 0..4 {
+            sqlx::query(
+                "INSERT INTO assignments (course_id, title, is_completed, completed_at, estimated_minutes, actual_minutes) VALUES (?, 'Problem set', 1, datetime('now'), 120, 240)",
+            )
+            .bind(physics)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+        // English: actual time comes from sessions logged against the essay
+        let essay: i64 = sqlx::query_scalar(
+            "INSERT INTO assignments (course_id, title, is_completed, completed_at, estimated_minutes) VALUES (?, 'Essay', 1, datetime('now'), 180) RETURNING id",
+        )
+        .bind(english)
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO sessions (session_type, reference_id, reference_type, started_at, duration_minutes) VALUES ('study', ?, 'assignment', datetime('now'), 90), ('study', ?, 'assignment', datetime('now'), 90)",
+        )
+        .bind(essay)
+        .bind(essay)
+        .execute(&pool)
+        .await
+        .unwrap();
+        // Open and unestimated assignments are ignored
+        sqlx::query("INSERT INTO assignments (course_id, title, estimated_minutes, actual_minutes) VALUES (?, 'Open', 60, 600)")
+            .bind(physics)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let ratios = ratios(&pool).await.unwrap();
+        // (960 + 120) / (480 + 120)
+        assert!((ratios[&physics] - 1.8).abs() < 1e-9);
+        assert!((ratios[&english] - 1.0).abs() < 1e-9);
+    }
+}
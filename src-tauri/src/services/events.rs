@@ -0,0 +1,34 @@
+//! Activity Events
+//!
+//! In-process broadcast of activity writes: workouts logged, sessions ended,
+//! check-ins recorded and reading logged. Background work subscribes to react to exactly
+//! what changed instead of rescanning all history.
+//!
+//! Delivery is best-effort, like the settings change channel: a subscriber
+//! that lags should fall back to a full recompute.
+
+use once_cell::sync::Lazy;
+use tokio::sync::broadcast;
+
+use crate::services::session_types::SessionTarget;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ActivityEvent {
+    WorkoutLogged { workout_id: i64 },
+    /// `counts_toward` is the ended session's target, from its type
+    SessionEnded { session_id: i64, counts_toward: Option<SessionTarget> },
+    CheckInRecorded { checkin_id: i64 },
+    ReadingLogged { log_id: i64 },
+}
+
+static EVENTS: Lazy<broadcast::Sender<ActivityEvent>> = Lazy::new(|| broadcast::channel(256).0);
+
+/// Notify subscribers; no subscribers is fine
+pub fn publish(event: ActivityEvent) {
+    let _ = EVENTS.send(event);
+}
+
+/// Receive every event published after this call
+pub fn subscribe() -> broadcast::Receiver<ActivityEvent> {
+    EVENTS.subscribe()
+}
@@ -0,0 +1,489 @@
+//! Focus Mode
+//!
+//! While a study session is running (and `focus_mode_enabled` is on), the
+//! foreground application is sampled every `SAMPLE_INTERVAL` and stored in
+//! `focus_samples`, flagged as a distraction when it matches the configured
+//! apps or sites. Only app names are stored; window titles are read to match
+//! distraction sites and then dropped.
+//!
+//! The samples feed the per-session distraction report and the `focus_trend`
+//! context feature.
+
+use std::time::Duration;
+
+use chrono::{Days, Local, NaiveDate, Weekday};
+use serde::Serialize;
+use sqlx::{Pool, Sqlite};
+
+use crate::error::ApiError;
+use crate::services::settings;
+
+pub const SAMPLE_INTERVAL: Duration = Duration::from_secs(15);
+
+/// Sessions compared against the baseline for `focus_trend`
+const TREND_RECENT_SESSIONS: i64 = 3;
+const TREND_BASELINE_DAYS: i64 = 28;
+/// Sessions with less sampled time than this are ignored by the trend
+const TREND_MIN_SESSION_SECONDS: i64 = 10 * 60;
+/// Uninterrupted focused time that counts as deep work
+const DEEP_WORK_MIN_SECONDS: i64 = 25 * 60;
+
+/// The window in front, as reported by the OS
+#[derive(Debug, Clone)]
+pub struct ForegroundWindow {
+    pub app_name: String,
+    pub title: String,
+}
+
+/// Source of foreground-window samples (the OS in the app, a fake in tests)
+pub trait ForegroundSource: Send + Sync {
+    fn current(&self) -> Option<ForegroundWindow>;
+}
+
+/// Reads the active window from the operating system
+pub struct SystemForeground;
+
+impl ForegroundSource for SystemForeground {
+    #[cfg(desktop)]
+    fn current(&self) -> Option<ForegroundWindow> {
+        active_win_pos_rs::get_active_window()
+            .ok()
+            .map(|w| ForegroundWindow {
+                app_name: w.app_name,
+                title: w.title,
+            })
+    }
+
+    /// Mobile apps cannot see other apps' windows; no samples are taken
+    #[cfg(mobile)]
+    fn current(&self) -> Option<ForegroundWindow> {
+        None
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AppUsage {
+    pub app_name: String,
+    pub site: Option<String>,
+    pub is_distraction: bool,
+    pub minutes: f64,
+}
+
+/// Where the time in one session went
+#[derive(Debug, Clone, Serialize)]
+pub struct DistractionReport {
+    pub session_id: i64,
+    pub sampled_minutes: f64,
+    pub distraction_minutes: f64,
+    /// Share of sampled time spent distracted (0-1); None without samples
+    pub distraction_share: Option<f64>,
+    pub by_app: Vec<AppUsage>,
+}
+
+/// Hours of attention on one day
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct AttentionDay {
+    pub date: String,
+    /// Focused stretches of at least 25 minutes without a distraction
+    pub deep_hours: f64,
+    /// Focused time in shorter stretches
+    pub shallow_hours: f64,
+    pub distraction_hours: f64,
+}
+
+/// Screen-time style summary of one week of focus samples
+#[derive(Debug, Clone, Serialize)]
+pub struct AttentionReport {
+    pub week_start: String,
+    pub days: Vec<AttentionDay>,
+    pub deep_hours: f64,
+    pub shallow_hours: f64,
+    pub distraction_hours: f64,
+}
+
+/// Start the background sampler
+pub fn start(pool: Pool<Sqlite>) {
+    tauri::async_runtime::spawn(async move {
+        let source = SystemForeground;
+        loop {
+            tokio::time::sleep(SAMPLE_INTERVAL).await;
+            if let Err(e) = record_sample(&pool, &source, SAMPLE_INTERVAL.as_secs() as i64).await {
+                log::warn!("Focus sample failed: {}", e.message);
+            }
+        }
+    });
+}
+
+/// Match a window against the distraction lists; returns (site, is_distraction)
+pub fn classify(window: &ForegroundWindow, apps: &[String], sites: &[String]) -> (Option<String>, bool) {
+    let app = window.app_name.to_lowercase();
+    if apps.iter().any(|a| app.contains(&a.to_lowercase())) {
+        return (None, true);
+    }
+
+    let title = window.title.to_lowercase();
+    match sites.iter().find(|s| title.contains(&s.to_lowercase())) {
+        Some(site) => (Some(site.clone()), true),
+        None => (None, false),
+    }
+}
+
+/// Take one sample if focus mode is on and a study session is running;
+/// returns the sample id
+pub async fn record_sample(
+    pool: &Pool<Sqlite>,
+    source: &dyn ForegroundSource,
+    seconds: i64,
+) -> Result<Option<i64>, ApiError> {
+    if !settings::get_bool(pool, "focus_mode_enabled").await? {
+        return Ok(None);
+    }
+
+    let session_id: Option<i64> = sqlx::query_scalar(
+        "SELECT id FROM sessions WHERE ended_at IS NULL AND session_type = 'study' ORDER BY started_at DESC, id DESC LIMIT 1",
+    )
+    .fetch_optional(pool)
+    .await
+    .map_err(ApiError::from)?;
+    let Some(session_id) = session_id else {
+        return Ok(None);
+    };
+
+    let Some(window) = source.current() else {
+        return Ok(None);
+    };
+    let apps = settings::get_string_list(pool, "focus_distraction_apps").await?;
+    let sites = settings::get_string_list(pool, "focus_distraction_sites").await?;
+    let (site, is_distraction) = classify(&window, &apps, &sites);
+
+    let id = sqlx::query_scalar(
+        "INSERT INTO focus_samples (session_id, app_name, site, is_distraction, seconds) VALUES (?, ?, ?, ?, ?) RETURNING id",
+    )
+    .bind(session_id)
+    .bind(&window.app_name)
+    .bind(site)
+    .bind(is_distraction)
+    .bind(seconds)
+    .fetch_one(pool)
+    .await
+    .map_err(ApiError::from)?;
+
+    Ok(Some(id))
+}
+
+/// Distraction breakdown for one session
+pub async fn session_report(pool: &Pool<Sqlite>, session_id: i64) -> Result<DistractionReport, ApiError> {
+    let exists: bool = sqlx::query_scalar("SELECT EXISTS(SELECT 1 FROM sessions WHERE id = ?)")
+        .bind(session_id)
+        .fetch_one(pool)
+        .await
+        .map_err(ApiError::from)?;
+    if !exists {
+        return Err(ApiError::not_found("Session not found"));
+    }
+
+    let rows: Vec<(String, Option<String>, bool, i64)> = sqlx::query_as(
+        r#"
+        SELECT app_name, site, is_distraction, SUM(seconds)
+        FROM focus_samples
+        WHERE session_id = ?
+        GROUP BY app_name, site, is_distraction
+        ORDER BY SUM(seconds) DESC, app_name
+        "#,
+    )
+    .bind(session_id)
+    .fetch_all(pool)
+    .await
+    .map_err(ApiError::from)?;
+
+    let sampled: i64 = rows.iter().map(|r| r.3).sum();
+    let distracted: i64 = rows.iter().filter(|r| r.2).map(|r| r.3).sum();
+
+    Ok(DistractionReport {
+        session_id,
+        sampled_minutes: sampled as f64 / 60.0,
+        distraction_minutes: distracted as f64 / 60.0,
+        distraction_share: (sampled > 0).then(|| distracted as f64 / sampled as f64),
+        by_app: rows
+            .into_iter()
+            .map(|(app_name, site, is_distraction, seconds)| AppUsage {
+                app_name,
+                site,
+                is_distraction,
+                minutes: seconds as f64 / 60.0,
+            })
+            .collect(),
+    })
+}
+
+/// Focus trend (-1 to 1): focused share of the last few sampled sessions
+/// compared with the sessions before them. None without enough samples.
+pub async fn focus_trend(pool: &Pool<Sqlite>) -> Result<Option<f32>, ApiError> {
+    // Focused share per sampled session, newest first
+    let shares: Vec<f64> = sqlx::query_scalar(
+        r#"
+        SELECT 1.0 - CAST(SUM(CASE WHEN f.is_distraction THEN f.seconds ELSE 0 END) AS REAL) / SUM(f.seconds)
+        FROM focus_samples f
+        JOIN sessions s ON s.id = f.session_id
+        WHERE s.started_at >= datetime('now', '-' || ? || ' days')
+        GROUP BY f.session_id
+        HAVING SUM(f.seconds) >= ?
+        ORDER BY MAX(s.started_at) DESC
+        "#,
+    )
+    .bind(TREND_BASELINE_DAYS)
+    .bind(TREND_MIN_SESSION_SECONDS)
+    .fetch_all(pool)
+    .await
+    .map_err(ApiError::from)?;
+
+    let split = TREND_RECENT_SESSIONS as usize;
+    if shares.len() <= split {
+        return Ok(None);
+    }
+    let mean = |s: &[f64]| s.iter().sum::<f64>() / s.len() as f64;
+    let (recent, baseline) = shares.split_at(split);
+
+    // A 50-point swing in focused share saturates the signal
+    Ok(Some(((mean(recent) - mean(baseline)) * 2.0).clamp(-1.0, 1.0) as f32))
+}
+
+/// First day of the week containing `date`, honouring `week_start_day`
+pub async fn week_start_for(pool: &Pool<Sqlite>, date: NaiveDate) -> Result<NaiveDate, ApiError> {
+    let start_day = match settings::get_string(pool, "week_start_day").await?.as_deref() {
+        Some("sunday") => Weekday::Sun,
+        _ => Weekday::Mon,
+    };
+    Ok(date.week(start_day).first_day())
+}
+
+/// Deep vs shallow vs distracted hours for each day of the week starting
+/// `week_start` (defaults to the current week)
+pub async fn weekly_attention(
+    pool: &Pool<Sqlite>,
+    week_start: Option<NaiveDate>,
+) -> Result<AttentionReport, ApiError> {
+    let week_start = match week_start {
+        Some(d) => d,
+        None => week_start_for(pool, Local::now().date_naive()).await?,
+    };
+    let week_end = week_start + Days::new(7);
+
+    // Samples in order, so focused stretches can be measured per session
+    let rows: Vec<(i64, String, bool, i64)> = sqlx::query_as(
+        r#"
+        SELECT session_id, date(sampled_at, 'localtime'), is_distraction, seconds
+        FROM focus_samples
+        WHERE date(sampled_at, 'localtime') >= ? AND date(sampled_at, 'localtime') < ?
+        ORDER BY session_id, sampled_at, id
+        "#,
+    )
+    .bind(week_start.to_string())
+    .bind(week_end.to_string())
+    .fetch_all(pool)
+    .await
+    .map_err(ApiError::from)?;
+
+    let mut days: Vec<AttentionDay> = week_start
+        .iter_days()
+        .take(7)
+        .map(|d| AttentionDay {
+            date: d.to_string(),
+            ..Default::default()
+        })
+        .collect();
+    let day_index = |date: &str| {
+        NaiveDate::parse_from_str(date, "%Y-%m-%d")
+            .ok()
+            .map(|d| (d - week_start).num_days() as usize)
+            .filter(|&i| i < 7)
+    };
+
+    // Focused seconds per day of the current stretch; a stretch ends at a
+    // distraction or when the session changes
+    let mut stretch: Vec<(usize, i64)> = Vec::new();
+    let mut stretch_session = None;
+    for (session_id, date, is_distraction, seconds) in rows {
+        let Some(i) = day_index(&date) else { continue };
+        if is_distraction || stretch_session != Some(session_id) {
+            add_stretch(&mut days, &mut stretch);
+        }
+        if is_distraction {
+            days[i].distraction_hours += seconds as f64 / 3600.0;
+            stretch_session = None;
+        } else {
+            stretch.push((i, seconds));
+            stretch_session = Some(session_id);
+        }
+    }
+    add_stretch(&mut days, &mut stretch);
+
+    Ok(AttentionReport {
+        week_start: week_start.to_string(),
+        deep_hours: days.iter().map(|d| d.deep_hours).sum(),
+        shallow_hours: days.iter().map(|d| d.shallow_hours).sum(),
+        distraction_hours: days.iter().map(|d| d.distraction_hours).sum(),
+        days,
+    })
+}
+
+fn add_stretch(days: &mut [AttentionDay], stretch: &mut Vec<(usize, i64)>) {
+    let deep = stretch.iter().map(|p| p.1).sum::<i64>() >= DEEP_WORK_MIN_SECONDS;
+    for (i, seconds) in stretch.drain(..) {
+        let hours = seconds as f64 / 3600.0;
+        if deep {
+            days[i].deep_hours += hours;
+        } else {
+            days[i].shallow_hours += hours;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+    use std::str::FromStr;
+
+    async fn setup_pool_with_migrations() -> Pool<Sqlite> {
+        let options = SqliteConnectOptions::from_str("sqlite::memory:")
+            .unwrap()
+            .foreign_keys(false);
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect_with(options)
+            .await
+            .expect("Failed to connect to in-memory DB");
+
+        crate::db::migrations::run_migrations(&pool)
+            .await
+            .expect("Failed to run migrations");
+
+        pool
+    }
+
+    struct FakeForeground(&'static str, &'static str);
+
+    impl ForegroundSource for FakeForeground {
+        fn current(&self) -> Option<ForegroundWindow> {
+            Some(ForegroundWindow {
+                app_name: self.0.to_string(),
+                title: self.1.to_string(),
+            })
+        }
+    }
+
+    #[test]
+    fn test_classify() {
+        let apps = vec!["Discord".to_string()];
+        let sites = vec!["youtube".to_string()];
+        let window = |app: &str, title: &str| ForegroundWindow {
+            app_name: app.into(),
+            title: title.into(),
+        };
+
+        assert_eq!(classify(&window("Discord", "general"), &apps, &sites), (None, true));
+        assert_eq!(
+            classify(&window("Firefox", "Lofi beats - YouTube"), &apps, &sites),
+            (Some("youtube".into()), true)
+        );
+        assert_eq!(classify(&window("Code", "main.rs"), &apps, &sites), (None, false));
+    }
+
+    #[tokio::test]
+    async fn samples_only_during_study_sessions_when_enabled() {
+        let pool = setup_pool_with_migrations().await;
+        let youtube = FakeForeground("Firefox", "Lecture 4 - YouTube");
+
+        let session_id: i64 = sqlx::query_scalar(
+            "INSERT INTO sessions (session_type, started_at) VALUES ('study', datetime('now')) RETURNING id",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(record_sample(&pool, &youtube, 15).await.unwrap(), None);
+
+        settings::set(&pool, "focus_mode_enabled", json!(true)).await.unwrap();
+        record_sample(&pool, &youtube, 15).await.unwrap().unwrap();
+        record_sample(&pool, &FakeForeground("Code", "notes.md"), 45).await.unwrap().unwrap();
+
+        let report = session_report(&pool, session_id).await.unwrap();
+        assert_eq!(report.sampled_minutes, 1.0);
+        assert_eq!(report.distraction_minutes, 0.25);
+        assert_eq!(report.by_app[0].app_name, "Code");
+        assert_eq!(report.by_app[1].site.as_deref(), Some("youtube"));
+
+        sqlx::query("UPDATE sessions SET ended_at = CURRENT_TIMESTAMP")
+            .execute(&pool)
+            .await
+            .unwrap();
+        assert_eq!(record_sample(&pool, &youtube, 15).await.unwrap(), None);
+    }
+
+    #[tokio::test]
+    async fn focus_trend_compares_recent_sessions_with_baseline() {
+        let pool = setup_pool_with_migrations().await;
+        assert_eq!(focus_trend(&pool).await.unwrap(), None);
+
+        // Oldest two sessions half distracted, newest three fully focused
+        for (days_ago, distracted) in [(10, true), (9, true), (3, false), (2, false), (1, false)] {
+            let id: i64 = sqlx::query_scalar(
+                "INSERT INTO sessions (session_type, started_at) VALUES ('study', datetime('now', ?)) RETURNING id",
+            )
+            .bind(format!("-{} days", days_ago))
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+            sqlx::query(
+                "INSERT INTO focus_samples (session_id, app_name, is_distraction, seconds) VALUES (?, 'Code', 0, 600), (?, 'Discord', ?, 600)",
+            )
+            .bind(id)
+            .bind(id)
+            .bind(distracted)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+
+        assert_eq!(focus_trend(&pool).await.unwrap(), Some(1.0));
+    }
+
+    #[tokio::test]
+    async fn weekly_attention_splits_deep_shallow_and_distracted_time() {
+        let pool = setup_pool_with_migrations().await;
+        let session_id: i64 = sqlx::query_scalar(
+            "INSERT INTO sessions (session_type, started_at) VALUES ('study', datetime('now')) RETURNING id",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+
+        // Midday (UTC) on a Wednesday: 30 focused minutes, a 5 minute
+        // distraction, then 10 focused minutes
+        for (at, distraction, seconds) in [("12:00", false, 1800), ("12:30", true, 300), ("12:35", false, 600)] {
+            sqlx::query(
+                "INSERT INTO focus_samples (session_id, sampled_at, app_name, is_distraction, seconds) VALUES (?, '2026-01-07 ' || ?, 'App', ?, ?)",
+            )
+            .bind(session_id)
+            .bind(at)
+            .bind(distraction)
+            .bind(seconds)
+            .execute(&pool)
+            .await
+            .unwrap();
+        }
+
+        let monday = NaiveDate::from_ymd_opt(2026, 1, 5).unwrap();
+        let report = weekly_attention(&pool, Some(monday)).await.unwrap();
+        assert_eq!(report.days.len(), 7);
+        assert_eq!(report.days[2].date, "2026-01-07");
+        assert_eq!(report.days[2].deep_hours, 0.5);
+        assert_eq!(report.deep_hours, 0.5);
+        assert!((report.shallow_hours - 1.0 / 6.0).abs() < 1e-9);
+        assert!((report.distraction_hours - 1.0 / 12.0).abs() < 1e-9);
+
+        let report = weekly_attention(&pool, Some(monday - Days::new(7))).await.unwrap();
+        assert_eq!(report.deep_hours + report.shallow_hours + report.distraction_hours, 0.0);
+    }
+}
@@ -0,0 +1,292 @@
+//! Gamification
+//!
+//! An optional XP engine, off unless `gamification_enabled` is set. XP is
+//! worked out from the activity history each time, so changing a rate
+//! re-prices everything already done:
+//! - academics: `gamification_xp_study_hour` per study hour and
+//!   `gamification_xp_flashcard_review` per flashcard review, meaning a study
+//!   session tagged with the "Spaced repetition" technique
+//! - skills: `gamification_xp_practice_hour` per hour of practice logs and
+//!   practice sessions
+//! - fitness: `gamification_xp_workout` per workout
+//! - wellness: `gamification_xp_streak_day` per check-in day that continues
+//!   a streak from the day before
+//!
+//! Sessions compressed into daily aggregates by retention still count.
+//!
+//! Each domain has a level, and so does the XP total. Going from level n to
+//! n + 1 takes `LEVEL_STEP_XP * n`. Level-ups are awarded as `level_up`
+//! achievements by the achievements engine and published as
+//! `gamification://level-up` events.
+
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use sqlx::{Pool, Sqlite};
+use tokio::sync::broadcast;
+
+use crate::{
+    commands::analytics::Achievement,
+    error::ApiError,
+    services::settings,
+};
+
+/// Frontend event carrying a `LevelUp`
+pub const LEVEL_UP_EVENT: &str = "gamification://level-up";
+
+/// XP from level 1 to 2; each later level costs this much more
+const LEVEL_STEP_XP: i64 = 100;
+/// Technique whose sessions count as flashcard reviews
+pub const FLASHCARD_TECHNIQUE: &str = "Spaced repetition";
+/// Level-ups listed in the status
+const RECENT_LEVEL_UPS: i64 = 5;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LevelProgress {
+    pub xp: i64,
+    pub level: i64,
+    /// XP earned since reaching `level`
+    pub xp_into_level: i64,
+    /// XP from `level` to the next
+    pub xp_for_next_level: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DomainProgress {
+    /// 'academics', 'skills', 'fitness' or 'wellness'
+    pub domain: String,
+    #[serde(flatten)]
+    pub progress: LevelProgress,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct XpSource {
+    /// 'study_hours', 'flashcard_reviews', 'practice_hours', 'workouts' or 'streak_days'
+    pub source: String,
+    pub domain: String,
+    pub amount: f64,
+    pub xp_each: i64,
+    pub xp: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GamificationStatus {
+    pub enabled: bool,
+    pub overall: LevelProgress,
+    pub domains: Vec<DomainProgress>,
+    pub sources: Vec<XpSource>,
+    /// Newest first
+    pub recent_level_ups: Vec<Achievement>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LevelUp {
+    /// 'overall' or a domain
+    pub domain: String,
+    pub level: i64,
+    pub title: String,
+}
+
+static LEVEL_UPS: Lazy<broadcast::Sender<LevelUp>> = Lazy::new(|| broadcast::channel(16).0);
+
+/// Notify subscribers; no subscribers is fine
+pub fn publish(level_up: LevelUp) {
+    let _ = LEVEL_UPS.send(level_up);
+}
+
+/// Forward level-ups to the frontend as `gamification://level-up` events
+pub fn start_event_forwarder(app_handle: tauri::AppHandle) {
+    use tauri::Emitter;
+
+    let mut level_ups = LEVEL_UPS.subscribe();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            match level_ups.recv().await {
+                Ok(level_up) => {
+                    if let Err(e) = app_handle.emit(LEVEL_UP_EVENT, level_up) {
+                        log::warn!("Failed to emit level-up: {}", e);
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(_)) => {}
+                Err(broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    });
+}
+
+/// Level reached with `xp`
+pub fn level_for(xp: i64) -> LevelProgress {
+    let mut level = 1;
+    let mut floor = 0;
+    while xp >= floor + LEVEL_STEP_XP * level {
+        floor += LEVEL_STEP_XP * level;
+        level += 1;
+    }
+    LevelProgress {
+        xp,
+        level,
+        xp_into_level: xp - floor,
+        xp_for_next_level: LEVEL_STEP_XP * level,
+    }
+}
+
+/// XP earned from each source
+async fn sources(pool: &Pool<Sqlite>) -> Result<Vec<XpSource>, ApiError> {
+    let (study_minutes, practice_minutes, workouts, streak_days, reviews): (i64, i64, i64, i64, i64) =
+        sqlx::query_as(
+            r#"
+            WITH checkin_days AS (SELECT DISTINCT date(checked_in_at, 'localtime') AS day FROM check_ins)
+            SELECT
+                COALESCE((SELECT SUM(duration_minutes) FROM sessions
+                          WHERE session_type IN (SELECT name FROM session_types WHERE counts_toward = 'study') AND ended_at IS NOT NULL), 0)
+                  + COALESCE((SELECT SUM(total_minutes) FROM session_daily_aggregates WHERE session_type IN (SELECT name FROM session_types WHERE counts_toward = 'study')), 0),
+                COALESCE((SELECT SUM(duration_minutes) FROM practice_logs), 0)
+                  + COALESCE((SELECT SUM(duration_minutes) FROM sessions
+                              WHERE session_type IN (SELECT name FROM session_types WHERE counts_toward = 'practice') AND ended_at IS NOT NULL), 0)
+                  + COALESCE((SELECT SUM(total_minutes) FROM session_daily_aggregates WHERE session_type IN (SELECT name FROM session_types WHERE counts_toward = 'practice')), 0),
+                (SELECT COUNT(*) FROM workouts),
+                (SELECT COUNT(*) FROM checkin_days d
+                 WHERE EXISTS (SELECT 1 FROM checkin_days p WHERE p.day = date(d.day, '-1 day'))),
+                (SELECT COUNT(DISTINCT s.id) FROM sessions s
+                 JOIN session_techniques st ON st.session_id = s.id
+                 JOIN techniques t ON t.id = st.technique_id
+                 WHERE s.ended_at IS NOT NULL AND t.name = ?)
+            "#,
+        )
+        .bind(FLASHCARD_TECHNIQUE)
+        .fetch_one(pool)
+        .await
+        .map_err(ApiError::from)?;
+
+    let mut sources = Vec::new();
+    for (source, domain, amount, setting) in [
+        ("study_hours", "academics", study_minutes as f64 / 60.0, "gamification_xp_study_hour"),
+        ("flashcard_reviews", "academics", reviews as f64, "gamification_xp_flashcard_review"),
+        ("practice_hours", "skills", practice_minutes as f64 / 60.0, "gamification_xp_practice_hour"),
+        ("workouts", "fitness", workouts as f64, "gamification_xp_workout"),
+        ("streak_days", "wellness", streak_days as f64, "gamification_xp_streak_day"),
+    ] {
+        let xp_each = settings::get_i64(pool, setting).await?;
+        sources.push(XpSource {
+            source: source.to_string(),
+            domain: domain.to_string(),
+            amount: (amount * 10.0).round() / 10.0,
+            xp_each,
+            xp: (amount * xp_each as f64).floor() as i64,
+        });
+    }
+    Ok(sources)
+}
+
+/// XP and levels overall and per domain
+pub async fn status(pool: &Pool<Sqlite>) -> Result<GamificationStatus, ApiError> {
+    let sources = sources(pool).await?;
+    let domains = ["academics", "skills", "fitness", "wellness"]
+        .into_iter()
+        .map(|domain| DomainProgress {
+            domain: domain.to_string(),
+            progress: level_for(sources.iter().filter(|s| s.domain == domain).map(|s| s.xp).sum()),
+        })
+        .collect();
+
+    let recent_level_ups = sqlx::query_as::<_, (i64, String, String, Option<String>, Option<String>, String, Option<String>)>(
+        r#"SELECT id, achievement_type, title, description, category, achieved_at, metadata
+           FROM achievements
+           WHERE user_id = 1 AND achievement_type = 'level_up'
+           ORDER BY achieved_at DESC, id DESC
+           LIMIT ?"#,
+    )
+    .bind(RECENT_LEVEL_UPS)
+    .fetch_all(pool)
+    .await
+    .map_err(ApiError::from)?
+    .into_iter()
+    .map(|(id, achievement_type, title, description, category, achieved_at, metadata)| Achievement {
+        id,
+        achievement_type,
+        title,
+        description,
+        category,
+        achieved_at,
+        metadata,
+    })
+    .collect();
+
+    Ok(GamificationStatus {
+        enabled: settings::get_bool(pool, "gamification_enabled").await?,
+        overall: level_for(sources.iter().map(|s| s.xp).sum()),
+        domains,
+        sources,
+        recent_level_ups,
+    })
+}
+
+/// Current level overall and per domain, past level 1; empty while disabled
+pub async fn current_levels(pool: &Pool<Sqlite>) -> Result<Vec<(String, i64)>, ApiError> {
+    if !settings::get_bool(pool, "gamification_enabled").await? {
+        return Ok(Vec::new());
+    }
+    let status = status(pool).await?;
+    Ok(std::iter::once(("overall".to_string(), status.overall.level))
+        .chain(status.domains.into_iter().map(|d| (d.domain, d.progress.level)))
+        .filter(|(_, level)| *level > 1)
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::services::achievements::{self, Metric};
+    use serde_json::json;
+
+    #[test]
+    fn each_level_costs_more_than_the_last() {
+        assert_eq!(level_for(0).level, 1);
+        assert_eq!(level_for(99).level, 1);
+        let two = level_for(100);
+        assert_eq!((two.level, two.xp_into_level, two.xp_for_next_level), (2, 0, 200));
+        assert_eq!(level_for(299).level, 2);
+        assert_eq!(level_for(300).level, 3);
+    }
+
+    #[tokio::test]
+    async fn xp_comes_from_each_domain_and_level_ups_are_awarded_once() {
+        let pool = crate::test_support::pool().await;
+        sqlx::query(
+            "INSERT INTO sessions (session_type, started_at, ended_at, duration_minutes) VALUES ('study', '2030-01-01 09:00:00', '2030-01-01 12:00:00', 180)",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO session_techniques (session_id, technique_id) SELECT 1, id FROM techniques WHERE name = 'Spaced repetition'",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query("INSERT INTO workouts (name) VALUES ('Run'), ('Swim')").execute(&pool).await.unwrap();
+        for day in ["2030-01-01 12:00:00", "2030-01-02 12:00:00", "2030-01-03 12:00:00", "2030-01-05 12:00:00"] {
+            sqlx::query("INSERT INTO check_ins (mood, energy, checked_in_at) VALUES (5, 5, ?)")
+                .bind(day)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+
+        let status = status(&pool).await.unwrap();
+        let xp: Vec<(&str, i64)> = status.sources.iter().map(|s| (s.source.as_str(), s.xp)).collect();
+        assert_eq!(
+            xp,
+            vec![("study_hours", 180), ("flashcard_reviews", 20), ("practice_hours", 0), ("workouts", 80), ("streak_days", 20)]
+        );
+        assert_eq!((status.overall.xp, status.overall.level), (300, 3));
+        assert_eq!(status.domains[0].progress.level, 2);
+
+        // Nothing is awarded until the engine is switched on
+        assert!(achievements::evaluate(&pool, Metric::Levels).await.unwrap().is_empty());
+        settings::set(&pool, "gamification_enabled", json!(true)).await.unwrap();
+        let unlocked = achievements::evaluate(&pool, Metric::Levels).await.unwrap();
+        let titles: Vec<&str> = unlocked.iter().map(|a| a.title.as_str()).collect();
+        assert_eq!(titles, vec!["Level 3!", "Academics Level 2!"]);
+        assert!(achievements::evaluate(&pool, Metric::Levels).await.unwrap().is_empty());
+    }
+}
@@ -0,0 +1,213 @@
+//! Google Sync Journal
+//!
+//! Creating a Google event and linking it locally are two writes that cannot
+//! share a transaction. A crash between them used to leave an event on
+//! Google with no `google_event_links` row, and the next sync would create
+//! it again.
+//!
+//! Sync now journals these operations: `begin` before the remote call,
+//! `record_remote` once Google returns the event id, and `complete` in the
+//! same transaction as the local link write. Anything still incomplete at
+//! the next sync was interrupted and is reconciled before syncing.
+//! Patches are not journaled; the next sync compares the event again and
+//! re-applies the change.
+
+use serde::Serialize;
+use sqlx::{FromRow, Pool, Sqlite, SqliteExecutor};
+
+use crate::error::ApiError;
+
+/// Completed entries are kept this long for debugging
+pub const KEEP_COMPLETED_DAYS: i64 = 30;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Operation {
+    /// Insert an event and add a link
+    CreateEvent,
+    /// Insert an event to replace one missing on Google and repoint the link
+    RecreateEvent,
+}
+
+impl Operation {
+    fn as_str(self) -> &'static str {
+        match self {
+            Operation::CreateEvent => "create_event",
+            Operation::RecreateEvent => "recreate_event",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Resolution {
+    /// Finished normally
+    Applied,
+    /// Interrupted; the event exists on Google and was linked on recovery
+    Relinked,
+    /// Interrupted before Google created the event; nothing to link
+    NotCreated,
+}
+
+impl Resolution {
+    fn as_str(self) -> &'static str {
+        match self {
+            Resolution::Applied => "applied",
+            Resolution::Relinked => "relinked",
+            Resolution::NotCreated => "not_created",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct JournalEntry {
+    pub id: i64,
+    pub operation: String,
+    pub local_type: String,
+    pub local_id: i64,
+    pub google_calendar_id: String,
+    /// Set once the remote write has returned
+    pub google_event_id: Option<String>,
+    pub started_at: String,
+}
+
+/// Record the intent to write `local_type`/`local_id` to a Google calendar
+pub async fn begin(
+    pool: &Pool<Sqlite>,
+    operation: Operation,
+    local_type: &str,
+    local_id: i64,
+    google_calendar_id: &str,
+) -> Result<i64, ApiError> {
+    let id = sqlx::query_scalar(
+        r#"INSERT INTO google_sync_journal (operation, local_type, local_id, google_calendar_id)
+           VALUES (?, ?, ?, ?)
+           RETURNING id"#,
+    )
+    .bind(operation.as_str())
+    .bind(local_type)
+    .bind(local_id)
+    .bind(google_calendar_id)
+    .fetch_one(pool)
+    .await?;
+    Ok(id)
+}
+
+/// Record the event id Google returned for an entry
+pub async fn record_remote(pool: &Pool<Sqlite>, entry_id: i64, google_event_id: &str) -> Result<(), ApiError> {
+    sqlx::query("UPDATE google_sync_journal SET google_event_id = ? WHERE id = ?")
+        .bind(google_event_id)
+        .bind(entry_id)
+        .execute(pool)
+        .await?;
+    Ok(())
+}
+
+/// Mark an entry done; run in the transaction that writes the local side
+pub async fn complete<'c>(
+    executor: impl SqliteExecutor<'c>,
+    entry_id: i64,
+    resolution: Resolution,
+) -> Result<(), ApiError> {
+    sqlx::query("UPDATE google_sync_journal SET completed_at = datetime('now'), resolution = ? WHERE id = ?")
+        .bind(resolution.as_str())
+        .bind(entry_id)
+        .execute(executor)
+        .await?;
+    Ok(())
+}
+
+/// Entries left incomplete, oldest first
+pub async fn pending(pool: &Pool<Sqlite>) -> Result<Vec<JournalEntry>, ApiError> {
+    let entries = sqlx::query_as::<_, JournalEntry>(
+        r#"SELECT id, operation, local_type, local_id, google_calendar_id, google_event_id, started_at
+           FROM google_sync_journal
+           WHERE completed_at IS NULL
+           ORDER BY id"#,
+    )
+    .fetch_all(pool)
+    .await?;
+    Ok(entries)
+}
+
+/// Delete completed entries older than `KEEP_COMPLETED_DAYS`
+pub async fn prune(pool: &Pool<Sqlite>) -> Result<u64, ApiError> {
+    let result = sqlx::query(
+        "DELETE FROM google_sync_journal WHERE completed_at IS NOT NULL AND completed_at < datetime('now', ?)",
+    )
+    .bind(format!("-{} days", KEEP_COMPLETED_DAYS))
+    .execute(pool)
+    .await?;
+    Ok(result.rows_affected())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+    use std::str::FromStr;
+
+    async fn setup_pool_with_migrations() -> Pool<Sqlite> {
+        let options = SqliteConnectOptions::from_str("sqlite::memory:")
+            .unwrap()
+            .foreign_keys(false);
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect_with(options)
+            .await
+            .expect("Failed to connect to in-memory DB");
+
+        crate::db::migrations::run_migrations(&pool)
+            .await
+            .expect("Failed to run migrations");
+
+        pool
+    }
+
+    #[tokio::test]
+    async fn entries_stay_pending_until_completed_with_the_local_write() {
+        let pool = setup_pool_with_migrations().await;
+
+        let created = begin(&pool, Operation::CreateEvent, "week_plan_block", 7, "cal").await.unwrap();
+        let interrupted = begin(&pool, Operation::RecreateEvent, "week_plan_block", 8, "cal").await.unwrap();
+        record_remote(&pool, created, "evt-7").await.unwrap();
+
+        let open = pending(&pool).await.unwrap();
+        assert_eq!(open.len(), 2);
+        assert_eq!(open[0].google_event_id.as_deref(), Some("evt-7"));
+        assert_eq!(open[1].google_event_id, None);
+
+        // A rolled back transaction leaves the entry pending
+        let mut tx = pool.begin().await.unwrap();
+        complete(&mut *tx, created, Resolution::Applied).await.unwrap();
+        tx.rollback().await.unwrap();
+        assert_eq!(pending(&pool).await.unwrap().len(), 2);
+
+        let mut tx = pool.begin().await.unwrap();
+        complete(&mut *tx, created, Resolution::Applied).await.unwrap();
+        tx.commit().await.unwrap();
+        let open = pending(&pool).await.unwrap();
+        assert_eq!(open.len(), 1);
+        assert_eq!(open[0].id, interrupted);
+    }
+
+    #[tokio::test]
+    async fn prune_keeps_recent_and_pending_entries() {
+        let pool = setup_pool_with_migrations().await;
+        let old = begin(&pool, Operation::CreateEvent, "week_plan_block", 1, "cal").await.unwrap();
+        let recent = begin(&pool, Operation::CreateEvent, "week_plan_block", 2, "cal").await.unwrap();
+        begin(&pool, Operation::CreateEvent, "week_plan_block", 3, "cal").await.unwrap();
+        complete(&pool, old, Resolution::Applied).await.unwrap();
+        complete(&pool, recent, Resolution::Applied).await.unwrap();
+        sqlx::query("UPDATE google_sync_journal SET completed_at = datetime('now', '-31 days') WHERE id = ?")
+            .bind(old)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        assert_eq!(prune(&pool).await.unwrap(), 1);
+        let remaining: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM google_sync_journal")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(remaining, 2);
+    }
+}
@@ -0,0 +1,519 @@
+//! iCalendar Import
+//!
+//! Reads the VEVENTs of an .ics file into occurrences ready to store as
+//! one-off calendar events. Calendar events only know open-ended weekly
+//! rules, so recurring VEVENTs are expanded: DAILY, WEEKLY (with BYDAY),
+//! MONTHLY and YEARLY rules with INTERVAL, COUNT and UNTIL, minus EXDATEs,
+//! with RECURRENCE-ID overrides replacing or cancelling single occurrences.
+//! Occurrences are kept between `from` and `to`, so unbounded rules stay
+//! finite. Other rules import their first occurrence with a warning.
+//!
+//! Each occurrence is keyed by the event's UID and its original start, which
+//! is what lets a repeated import update rows instead of duplicating them.
+//!
+//! Times with a TZID are read as local wall-clock time (university calendars
+//! are almost always in the user's own zone); UTC times are converted.
+
+use std::collections::{BTreeMap, BTreeSet};
+
+use chrono::{Datelike, Duration, Local, NaiveDate, NaiveDateTime, NaiveTime, Weekday};
+use sha2::{Digest, Sha256};
+
+use crate::error::ApiError;
+
+/// One event, or one occurrence of a recurring event
+#[derive(Debug, Clone, PartialEq)]
+pub struct IcsOccurrence {
+    pub uid: String,
+    /// Original start of a recurring occurrence ("YYYYMMDD" or
+    /// "YYYYMMDDTHHMMSS"); empty for a single event
+    pub occurrence: String,
+    pub title: String,
+    pub start: NaiveDateTime,
+    pub end: NaiveDateTime,
+    pub all_day: bool,
+    /// Location and description
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Default)]
+pub struct ParsedCalendar {
+    pub occurrences: Vec<IcsOccurrence>,
+    /// Every UID in the file, cancelled events included
+    pub uids: BTreeSet<String>,
+    pub warnings: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct IcsTime {
+    at: NaiveDateTime,
+    all_day: bool,
+}
+
+impl IcsTime {
+    fn key(&self) -> String {
+        if self.all_day {
+            self.at.format("%Y%m%d").to_string()
+        } else {
+            self.at.format("%Y%m%dT%H%M%S").to_string()
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct RawEvent {
+    uid: Option<String>,
+    summary: Option<String>,
+    description: Option<String>,
+    location: Option<String>,
+    start: Option<IcsTime>,
+    end: Option<IcsTime>,
+    duration: Option<Duration>,
+    rrule: Option<String>,
+    exdates: Vec<IcsTime>,
+    recurrence_id: Option<IcsTime>,
+    cancelled: bool,
+}
+
+impl RawEvent {
+    fn set(&mut self, name: &str, params: &[(String, String)], value: &str) {
+        match name {
+            "UID" => self.uid = Some(value.trim().to_string()).filter(|u| !u.is_empty()),
+            "SUMMARY" => self.summary = Some(unescape(value)),
+            "DESCRIPTION" => self.description = Some(unescape(value)),
+            "LOCATION" => self.location = Some(unescape(value)),
+            "DTSTART" => self.start = parse_time(value, params),
+            "DTEND" => self.end = parse_time(value, params),
+            "DURATION" => self.duration = parse_duration(value),
+            "RRULE" => self.rrule = Some(value.trim().to_string()),
+            "EXDATE" => self.exdates.extend(value.split(',').filter_map(|v| parse_time(v, params))),
+            "RECURRENCE-ID" => self.recurrence_id = parse_time(value, params),
+            "STATUS" => self.cancelled = value.trim().eq_ignore_ascii_case("CANCELLED"),
+            _ => {}
+        }
+    }
+
+    fn title(&self) -> String {
+        self.summary
+            .as_deref()
+            .map(str::trim)
+            .filter(|s| !s.is_empty())
+            .unwrap_or("(No title)")
+            .to_string()
+    }
+
+    fn notes(&self) -> Option<String> {
+        let location = self.location.as_deref().map(str::trim).filter(|l| !l.is_empty());
+        let description = self.description.as_deref().map(str::trim).filter(|d| !d.is_empty());
+        match (location, description) {
+            (Some(l), Some(d)) => Some(format!("Location: {}\n\n{}", l, d)),
+            (Some(l), None) => Some(format!("Location: {}", l)),
+            (None, d) => d.map(str::to_string),
+        }
+    }
+
+    /// End from DTEND or DURATION; an all-day event lasts a day and a timed
+    /// one without either ends when it starts
+    fn end_for(&self, start: IcsTime) -> NaiveDateTime {
+        let end = match (self.end, self.duration) {
+            (Some(end), _) => end.at,
+            (None, Some(duration)) => start.at + duration,
+            (None, None) if start.all_day => start.at + Duration::days(1),
+            (None, None) => start.at,
+        };
+        match end {
+            end if start.all_day && end <= start.at => start.at + Duration::days(1),
+            end if end < start.at => start.at,
+            end => end,
+        }
+    }
+
+    fn occurrence(&self, uid: &str, key: String, start: IcsTime, length: Duration) -> IcsOccurrence {
+        IcsOccurrence {
+            uid: uid.to_string(),
+            occurrence: key,
+            title: self.title(),
+            start: start.at,
+            end: start.at + length,
+            all_day: start.all_day,
+            notes: self.notes(),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Frequency {
+    Daily,
+    Weekly,
+    Monthly,
+    Yearly,
+}
+
+#[derive(Debug)]
+struct Rule {
+    frequency: Frequency,
+    interval: u32,
+    count: Option<usize>,
+    until: Option<NaiveDateTime>,
+    by_day: Vec<Weekday>,
+}
+
+impl Rule {
+    fn parse(value: &str) -> Result<Rule, String> {
+        let mut frequency = None;
+        let mut rule = Rule { frequency: Frequency::Daily, interval: 1, count: None, until: None, by_day: Vec::new() };
+        for part in value.trim_start_matches("RRULE:").split(';').filter(|p| !p.is_empty()) {
+            let (key, val) = part.split_once('=').ok_or_else(|| format!("malformed rule part '{}'", part))?;
+            match key.to_ascii_uppercase().as_str() {
+                "FREQ" => {
+                    frequency = Some(match val.to_ascii_uppercase().as_str() {
+                        "DAILY" => Frequency::Daily,
+                        "WEEKLY" => Frequency::Weekly,
+                        "MONTHLY" => Frequency::Monthly,
+                        "YEARLY" => Frequency::Yearly,
+                        other => return Err(format!("FREQ={} isn't supported", other)),
+                    })
+                }
+                "INTERVAL" => {
+                    rule.interval = val.parse().ok().filter(|i| *i > 0).ok_or("invalid INTERVAL")?;
+                }
+                "COUNT" => rule.count = Some(val.parse().map_err(|_| "invalid COUNT")?),
+                "UNTIL" => {
+                    let until = parse_time(val, &[]).ok_or("invalid UNTIL")?;
+                    // A date UNTIL includes that whole day
+                    rule.until = Some(if until.all_day { until.at + Duration::days(1) - Duration::seconds(1) } else { until.at });
+                }
+                "BYDAY" => {
+                    for code in val.split(',') {
+                        rule.by_day.push(weekday(code).ok_or_else(|| format!("BYDAY={} isn't supported", code))?);
+                    }
+                }
+                "WKST" => {}
+                other => return Err(format!("{} isn't supported", other)),
+            }
+        }
+        rule.frequency = frequency.ok_or("the rule has no FREQ")?;
+        if !rule.by_day.is_empty() && rule.frequency != Frequency::Weekly {
+            return Err("BYDAY is only supported in weekly rules".to_string());
+        }
+        Ok(rule)
+    }
+
+    /// Occurrence starts from `start` on, up to `to`; COUNT counts from the
+    /// first occurrence even when it falls before the import window
+    fn expand(&self, start: NaiveDateTime, to: NaiveDate) -> Vec<NaiveDateTime> {
+        let date = start.date();
+        let week = date - Duration::days(date.weekday().num_days_from_monday() as i64);
+        let month = NaiveDate::from_ymd_opt(date.year(), date.month(), 1).expect("first of month");
+        let mut days = if self.by_day.is_empty() { vec![date.weekday()] } else { self.by_day.clone() };
+        days.sort_by_key(|d| d.num_days_from_monday());
+
+        let mut starts = Vec::new();
+        for period in 0.. {
+            let n = period * self.interval;
+            let (period_start, candidates): (NaiveDate, Vec<NaiveDate>) = match self.frequency {
+                Frequency::Daily => {
+                    let day = date + Duration::days(n as i64);
+                    (day, vec![day])
+                }
+                Frequency::Weekly => {
+                    let monday = week + Duration::weeks(n as i64);
+                    (monday, days.iter().map(|d| monday + Duration::days(d.num_days_from_monday() as i64)).collect())
+                }
+                Frequency::Monthly | Frequency::Yearly => {
+                    let months = if self.frequency == Frequency::Yearly { n * 12 } else { n };
+                    let Some(first) = month.checked_add_months(chrono::Months::new(months)) else { break };
+                    // Months without the day are skipped, as RFC 5545 says
+                    (first, NaiveDate::from_ymd_opt(first.year(), first.month(), date.day()).into_iter().collect())
+                }
+            };
+            if period_start > to {
+                break;
+            }
+            for day in candidates {
+                let at = day.and_time(start.time());
+                if at < start {
+                    continue;
+                }
+                if day > to || self.until.is_some_and(|u| at > u) || self.count.is_some_and(|c| starts.len() >= c) {
+                    return starts;
+                }
+                starts.push(at);
+            }
+        }
+        starts
+    }
+}
+
+fn weekday(code: &str) -> Option<Weekday> {
+    Some(match code.trim().to_ascii_uppercase().as_str() {
+        "MO" => Weekday::Mon,
+        "TU" => Weekday::Tue,
+        "WE" => Weekday::Wed,
+        "TH" => Weekday::Thu,
+        "FR" => Weekday::Fri,
+        "SA" => Weekday::Sat,
+        "SU" => Weekday::Sun,
+        _ => return None,
+    })
+}
+
+/// Content lines with folded continuations joined
+fn unfold(text: &str) -> Vec<String> {
+    let mut lines: Vec<String> = Vec::new();
+    for line in text.lines() {
+        if let Some(rest) = line.strip_prefix([' ', '\t']) {
+            if let Some(last) = lines.last_mut() {
+                last.push_str(rest);
+                continue;
+            }
+        }
+        if !line.trim().is_empty() {
+            lines.push(line.to_string());
+        }
+    }
+    lines
+}
+
+/// Name (uppercased), parameters and value of a content line
+fn property(line: &str) -> Option<(String, Vec<(String, String)>, &str)> {
+    let mut quoted = false;
+    let colon = line.char_indices().find_map(|(i, c)| {
+        if c == '"' {
+            quoted = !quoted;
+        }
+        (c == ':' && !quoted).then_some(i)
+    })?;
+    let (head, value) = (&line[..colon], &line[colon + 1..]);
+    let mut parts = head.split(';');
+    let name = parts.next()?.trim().to_ascii_uppercase();
+    let params = parts
+        .filter_map(|p| p.split_once('='))
+        .map(|(k, v)| (k.trim().to_ascii_uppercase(), v.trim_matches('"').to_string()))
+        .collect();
+    Some((name, params, value))
+}
+
+fn unescape(value: &str) -> String {
+    let mut out = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(c) = chars.next() {
+        if c != '\\' {
+            out.push(c);
+            continue;
+        }
+        match chars.next() {
+            Some('n' | 'N') => out.push('\n'),
+            Some(other) => out.push(other),
+            None => {}
+        }
+    }
+    out
+}
+
+/// A DATE or DATE-TIME value as local time
+fn parse_time(value: &str, params: &[(String, String)]) -> Option<IcsTime> {
+    let value = value.trim();
+    let is_date = params.iter().any(|(k, v)| k == "VALUE" && v.eq_ignore_ascii_case("DATE")) || value.len() == 8;
+    if is_date {
+        let date = NaiveDate::parse_from_str(value, "%Y%m%d").ok()?;
+        return Some(IcsTime { at: date.and_time(NaiveTime::MIN), all_day: true });
+    }
+    let at = match value.strip_suffix('Z') {
+        Some(utc) => NaiveDateTime::parse_from_str(utc, "%Y%m%dT%H%M%S")
+            .ok()?
+            .and_utc()
+            .with_timezone(&Local)
+            .naive_local(),
+        None => NaiveDateTime::parse_from_str(value, "%Y%m%dT%H%M%S").ok()?,
+    };
+    Some(IcsTime { at, all_day: false })
+}
+
+/// An RFC 5545 duration such as "PT1H30M" or "P1D"; negative ones are ignored
+fn parse_duration(value: &str) -> Option<Duration> {
+    let rest = value.trim().trim_start_matches('+').strip_prefix('P')?;
+    let (mut total, mut number, mut in_time) = (Duration::zero(), String::new(), false);
+    for c in rest.chars() {
+        match c {
+            'T' => in_time = true,
+            '0'..='9' => number.push(c),
+            unit => {
+                let n: i64 = number.parse().ok()?;
+                number.clear();
+                total += match (unit, in_time) {
+                    ('W', false) => Duration::weeks(n),
+                    ('D', false) => Duration::days(n),
+                    ('H', true) => Duration::hours(n),
+                    ('M', true) => Duration::minutes(n),
+                    ('S', true) => Duration::seconds(n),
+                    _ => return None,
+                };
+            }
+        }
+    }
+    number.is_empty().then_some(total)
+}
+
+/// Stand-in UID for events without one, stable across imports of the same file
+fn generated_uid(event: &RawEvent) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(event.title().as_bytes());
+    hasher.update(event.start.map(|s| s.key()).unwrap_or_default().as_bytes());
+    let digest = hasher.finalize();
+    format!("generated-{}", digest[..8].iter().map(|b| format!("{:02x}", b)).collect::<String>())
+}
+
+fn read_events(text: &str) -> Result<Vec<RawEvent>, ApiError> {
+    let lines = unfold(text.trim_start_matches('\u{feff}'));
+    if !lines.first().is_some_and(|l| l.trim().eq_ignore_ascii_case("BEGIN:VCALENDAR")) {
+        return Err(ApiError::validation("Not an iCalendar (.ics) file"));
+    }
+
+    let (mut events, mut current, mut nested) = (Vec::new(), None::<RawEvent>, 0usize);
+    for line in &lines {
+        let Some((name, params, value)) = property(line) else { continue };
+        let in_event = current.is_some();
+        match name.as_str() {
+            "BEGIN" if !in_event && value.trim().eq_ignore_ascii_case("VEVENT") => current = Some(RawEvent::default()),
+            // Alarms and other components inside an event
+            "BEGIN" if in_event => nested += 1,
+            "END" if in_event && nested > 0 => nested -= 1,
+            "END" if in_event => events.extend(current.take()),
+            _ if nested == 0 => {
+                if let Some(event) = current.as_mut() {
+                    event.set(&name, &params, value);
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(events)
+}
+
+/// The events of an .ics file, recurring ones expanded between `from` and `to`
+pub fn parse(text: &str, from: NaiveDate, to: NaiveDate) -> Result<ParsedCalendar, ApiError> {
+    let mut calendar = ParsedCalendar::default();
+    let mut occurrences: BTreeMap<(String, String), IcsOccurrence> = BTreeMap::new();
+    let (mut masters, mut overrides) = (Vec::new(), Vec::new());
+    for mut event in read_events(text)? {
+        let uid = event.uid.take().unwrap_or_else(|| generated_uid(&event));
+        calendar.uids.insert(uid.clone());
+        if event.recurrence_id.is_some() {
+            overrides.push((uid, event));
+        } else {
+            masters.push((uid, event));
+        }
+    }
+
+    for (uid, event) in &masters {
+        let Some(start) = event.start else {
+            calendar.warnings.push(format!("Skipped \"{}\": it has no start", event.title()));
+            continue;
+        };
+        if event.cancelled {
+            continue;
+        }
+        let length = event.end_for(start) - start.at;
+        let Some(rrule) = &event.rrule else {
+            occurrences.insert((uid.clone(), String::new()), event.occurrence(uid, String::new(), start, length));
+            continue;
+        };
+
+        let starts = match Rule::parse(rrule) {
+            Ok(rule) => rule.expand(start.at, to),
+            Err(reason) => {
+                calendar
+                    .warnings
+                    .push(format!("Imported only the first occurrence of \"{}\": {}", event.title(), reason));
+                vec![start.at]
+            }
+        };
+        for at in starts {
+            let excluded = event
+                .exdates
+                .iter()
+                .any(|x| if x.all_day || start.all_day { x.at.date() == at.date() } else { x.at == at });
+            if excluded || at.date() < from {
+                continue;
+            }
+            let occurrence = IcsTime { at, all_day: start.all_day };
+            occurrences.insert((uid.clone(), occurrence.key()), event.occurrence(uid, occurrence.key(), occurrence, length));
+        }
+    }
+
+    for (uid, event) in overrides {
+        let Some(recurrence_id) = event.recurrence_id else { continue };
+        let key = recurrence_id.key();
+        occurrences.remove(&(uid.clone(), key.clone()));
+        if event.cancelled {
+            continue;
+        }
+        let start = event.start.unwrap_or(recurrence_id);
+        let length = event.end_for(start) - start.at;
+        occurrences.insert((uid.clone(), key.clone()), event.occurrence(&uid, key, start, length));
+    }
+
+    calendar.occurrences = occurrences.into_values().collect();
+    Ok(calendar)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(value: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    #[test]
+    fn expands_recurrences_with_exceptions_and_overrides() {
+        let ics = "BEGIN:VCALENDAR\r\nVERSION:2.0\r\n\
+            BEGIN:VEVENT\r\nUID:lecture-1@uni.example\r\nSUMMARY:Algorithms\\, lecture\r\n\
+            DTSTART;TZID=Europe/Berlin:20300902T100000\r\nDURATION:PT1H30M\r\nLOCATION:Room 1\r\n\
+            RRULE:FREQ=WEEKLY;BYDAY=MO,WE;UNTIL=20300918T235959\r\nEXDATE;TZID=Europe/Berlin:20300911T100000\r\n\
+            BEGIN:VALARM\r\nACTION:DISPLAY\r\nDESCRIPTION:Reminder\r\nEND:VALARM\r\nEND:VEVENT\r\n\
+            BEGIN:VEVENT\r\nUID:lecture-1@uni.example\r\nRECURRENCE-ID;TZID=Europe/Berlin:20300916T100000\r\n\
+            SUMMARY:Algorithms (moved)\r\nDTSTART;TZID=Europe/Berlin:20300916T140000\r\nDTEND;TZID=Europe/Berlin:20300916T153000\r\nEND:VEVENT\r\n\
+            BEGIN:VEVENT\r\nUID:deadline@uni.example\r\nSUMMARY:Enrolment\r\n  closes\r\nDTSTART;VALUE=DATE:20301001\r\nEND:VEVENT\r\n\
+            BEGIN:VEVENT\r\nUID:rent\r\nSUMMARY:Rent\r\nDTSTART:20300131T090000\r\nRRULE:FREQ=MONTHLY;COUNT=3\r\nEND:VEVENT\r\n\
+            BEGIN:VEVENT\r\nUID:odd\r\nSUMMARY:Odd\r\nDTSTART:20300105T090000\r\nRRULE:FREQ=MONTHLY;BYSETPOS=-1\r\nEND:VEVENT\r\n\
+            END:VCALENDAR\r\n";
+        let from = NaiveDate::from_ymd_opt(2030, 1, 1).unwrap();
+        let to = NaiveDate::from_ymd_opt(2030, 12, 31).unwrap();
+        let calendar = parse(ics, from, to).unwrap();
+
+        let lectures: Vec<(&str, NaiveDateTime, NaiveDateTime)> = calendar
+            .occurrences
+            .iter()
+            .filter(|o| o.uid == "lecture-1@uni.example")
+            .map(|o| (o.occurrence.as_str(), o.start, o.end))
+            .collect();
+        // Mon 2, Wed 4, Mon 9, (Wed 11 excluded), Mon 16 moved, Wed 18
+        assert_eq!(
+            lectures,
+            vec![
+                ("20300902T100000", at("2030-09-02 10:00"), at("2030-09-02 11:30")),
+                ("20300904T100000", at("2030-09-04 10:00"), at("2030-09-04 11:30")),
+                ("20300909T100000", at("2030-09-09 10:00"), at("2030-09-09 11:30")),
+                ("20300916T100000", at("2030-09-16 14:00"), at("2030-09-16 15:30")),
+                ("20300918T100000", at("2030-09-18 10:00"), at("2030-09-18 11:30")),
+            ]
+        );
+        let first = calendar.occurrences.iter().find(|o| o.uid == "lecture-1@uni.example").unwrap();
+        assert_eq!(first.title, "Algorithms, lecture");
+        assert_eq!(first.notes.as_deref(), Some("Location: Room 1"));
+
+        let enrolment = calendar.occurrences.iter().find(|o| o.uid == "deadline@uni.example").unwrap();
+        assert_eq!((enrolment.title.as_str(), enrolment.all_day, enrolment.occurrence.as_str()), ("Enrolment closes", true, ""));
+        assert_eq!(enrolment.end, at("2030-10-02 00:00"));
+
+        // February and April have no 31st; COUNT still stops at three
+        let rent: Vec<NaiveDateTime> = calendar.occurrences.iter().filter(|o| o.uid == "rent").map(|o| o.start).collect();
+        assert_eq!(rent, vec![at("2030-01-31 09:00"), at("2030-03-31 09:00"), at("2030-05-31 09:00")]);
+
+        assert_eq!(calendar.occurrences.iter().filter(|o| o.uid == "odd").count(), 1);
+        assert_eq!(calendar.warnings.len(), 1);
+        assert!(parse("not a calendar", from, to).is_err());
+    }
+}
@@ -0,0 +1,580 @@
+//! Language Learning
+//!
+//! A skill template for learning a language. Creating a language skill makes
+//! an ordinary skill (category "language") with its language and CEFR levels,
+//! the flashcard decks studied for it, and the `listening` and `speaking`
+//! session types, which count toward practice. Listening and speaking
+//! sessions reference the skill like practice sessions do.
+//!
+//! Reviewing a deck records the cards reviewed, answered correctly and new,
+//! with an optional snapshot of the deck's size and mature cards from the
+//! SRS app. The minutes spent are also a practice log, so reviews show up in
+//! practice hours, XP and the portfolio like any other practice.
+//!
+//! Proficiency combines three scores out of 100 over a window:
+//! - vocabulary: the mean of deck maturity (mature / cards) and review
+//!   accuracy (correct / reviewed)
+//! - listening and speaking: hours against `COMPONENT_SHARE` of the skill's
+//!   weekly target each
+//!
+//! The overall score is the mean of the scores available, and the weakest is
+//! the one to practice next.
+
+use std::collections::HashMap;
+
+use chrono::NaiveDate;
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, Pool, Sqlite};
+
+use crate::{
+    error::ApiError,
+    services::{aggregates, clock},
+};
+
+/// Category of skills made from the template
+pub const CATEGORY: &str = "language";
+/// Session types the template registers: (name, label)
+const SESSION_TYPES: &[(&str, &str)] = &[("listening", "Listening"), ("speaking", "Speaking")];
+const LEVELS: &[&str] = &["A1", "A2", "B1", "B2", "C1", "C2"];
+/// Share of the weekly target listening and speaking each stand for
+const COMPONENT_SHARE: f64 = 0.25;
+const DEFAULT_WEEKLY_HOURS: f64 = 3.0;
+const MAX_REVIEWED: i64 = 5_000;
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct FlashcardDeck {
+    pub id: i64,
+    pub skill_id: i64,
+    pub name: String,
+    pub source: Option<String>,
+    pub card_count: i64,
+    pub mature_count: i64,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LanguageSkill {
+    pub skill_id: i64,
+    pub name: String,
+    pub language: String,
+    pub current_level: String,
+    pub target_level: Option<String>,
+    pub target_weekly_hours: Option<f64>,
+    pub decks: Vec<FlashcardDeck>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct LanguageSkillInput {
+    pub language: String,
+    /// Defaults to the language
+    #[serde(default)]
+    pub name: Option<String>,
+    #[serde(default)]
+    pub description: Option<String>,
+    /// CEFR level; defaults to A1
+    #[serde(default)]
+    pub current_level: Option<String>,
+    #[serde(default)]
+    pub target_level: Option<String>,
+    #[serde(default)]
+    pub target_weekly_hours: Option<f64>,
+    /// Decks to create with the skill
+    #[serde(default)]
+    pub decks: Vec<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct FlashcardDeckInput {
+    pub skill_id: i64,
+    pub name: String,
+    #[serde(default)]
+    pub source: Option<String>,
+    #[serde(default)]
+    pub card_count: Option<i64>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct FlashcardReviewInput {
+    pub deck_id: i64,
+    pub reviewed: i64,
+    pub correct: i64,
+    #[serde(default)]
+    pub new_cards: Option<i64>,
+    #[serde(default)]
+    pub minutes: Option<i64>,
+    /// Deck size reported by the SRS app, replacing the stored one
+    #[serde(default)]
+    pub card_count: Option<i64>,
+    #[serde(default)]
+    pub mature_count: Option<i64>,
+}
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct FlashcardReview {
+    pub id: i64,
+    pub deck_id: i64,
+    pub practice_log_id: Option<i64>,
+    pub reviewed: i64,
+    pub correct: i64,
+    pub new_cards: i64,
+    pub minutes: Option<i64>,
+    pub reviewed_at: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LanguageProficiency {
+    pub skill_id: i64,
+    pub name: String,
+    pub language: String,
+    pub current_level: String,
+    pub target_level: Option<String>,
+    pub days: i64,
+    pub flashcard_hours: f64,
+    pub listening_hours: f64,
+    pub speaking_hours: f64,
+    /// Practice logs and practice sessions other than reviews
+    pub other_practice_hours: f64,
+    pub total_hours: f64,
+    pub cards: i64,
+    pub mature_cards: i64,
+    pub reviews: i64,
+    pub new_cards: i64,
+    /// Correct / reviewed over the window
+    pub accuracy: Option<f64>,
+    pub vocabulary_score: Option<i64>,
+    pub listening_score: Option<i64>,
+    pub speaking_score: Option<i64>,
+    pub proficiency: Option<i64>,
+    /// 'vocabulary', 'listening' or 'speaking'
+    pub weakest: Option<String>,
+}
+
+fn level(value: Option<&str>, default: &str) -> Result<String, ApiError> {
+    let value = value.map(str::trim).filter(|v| !v.is_empty()).unwrap_or(default).to_uppercase();
+    if LEVELS.contains(&value.as_str()) {
+        Ok(value)
+    } else {
+        Err(ApiError::validation(format!("Level must be one of {}", LEVELS.join(", "))))
+    }
+}
+
+fn non_empty<'a>(value: &'a str, field: &str) -> Result<&'a str, ApiError> {
+    let value = value.trim();
+    if value.is_empty() {
+        return Err(ApiError::validation(format!("{} is required", field)));
+    }
+    Ok(value)
+}
+
+pub async fn create(pool: &Pool<Sqlite>, input: &LanguageSkillInput) -> Result<LanguageSkill, ApiError> {
+    let language = non_empty(&input.language, "Language")?;
+    let name = input.name.as_deref().map(str::trim).filter(|n| !n.is_empty()).unwrap_or(language);
+    let current_level = level(input.current_level.as_deref(), "A1")?;
+    let target_level = match input.target_level.as_deref() {
+        Some(target) => Some(level(Some(target), "A1")?),
+        None => None,
+    };
+    let weekly_hours = input.target_weekly_hours.unwrap_or(DEFAULT_WEEKLY_HOURS);
+    if !(0.0..=80.0).contains(&weekly_hours) {
+        return Err(ApiError::validation("Weekly hours must be between 0 and 80"));
+    }
+
+    let mut tx = pool.begin().await.map_err(ApiError::from)?;
+    let skill_id: i64 = sqlx::query_scalar(
+        "INSERT INTO skills (name, category, description, target_weekly_hours) VALUES (?, ?, ?, ?) RETURNING id",
+    )
+    .bind(name)
+    .bind(CATEGORY)
+    .bind(&input.description)
+    .bind(weekly_hours)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(ApiError::from)?;
+
+    sqlx::query("INSERT INTO language_skills (skill_id, language, current_level, target_level) VALUES (?, ?, ?, ?)")
+        .bind(skill_id)
+        .bind(language)
+        .bind(&current_level)
+        .bind(&target_level)
+        .execute(&mut *tx)
+        .await
+        .map_err(ApiError::from)?;
+
+    for (type_name, label) in SESSION_TYPES {
+        sqlx::query(
+            "INSERT OR IGNORE INTO session_types (name, label, domain, counts_toward) VALUES (?, ?, 'skills', 'practice')",
+        )
+        .bind(type_name)
+        .bind(label)
+        .execute(&mut *tx)
+        .await
+        .map_err(ApiError::from)?;
+    }
+
+    for deck in &input.decks {
+        sqlx::query("INSERT INTO flashcard_decks (skill_id, name) VALUES (?, ?)")
+            .bind(skill_id)
+            .bind(non_empty(deck, "Deck name")?)
+            .execute(&mut *tx)
+            .await
+            .map_err(|e| ApiError::from_sqlx(e, "Failed to create deck"))?;
+    }
+    tx.commit().await.map_err(ApiError::from)?;
+
+    get(pool, skill_id).await
+}
+
+pub async fn get(pool: &Pool<Sqlite>, skill_id: i64) -> Result<LanguageSkill, ApiError> {
+    list(pool)
+        .await?
+        .into_iter()
+        .find(|s| s.skill_id == skill_id)
+        .ok_or_else(|| ApiError::not_found("Language skill not found"))
+}
+
+/// Active language skills, oldest first
+pub async fn list(pool: &Pool<Sqlite>) -> Result<Vec<LanguageSkill>, ApiError> {
+    let rows: Vec<(i64, String, String, String, Option<String>, Option<f64>)> = sqlx::query_as(
+        r#"SELECT s.id, s.name, l.language, l.current_level, l.target_level, s.target_weekly_hours
+           FROM language_skills l
+           JOIN skills s ON s.id = l.skill_id
+           WHERE s.archived_at IS NULL
+           ORDER BY l.created_at, s.id"#,
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(ApiError::from)?;
+
+    let decks: Vec<FlashcardDeck> = sqlx::query_as(
+        "SELECT id, skill_id, name, source, card_count, mature_count, created_at FROM flashcard_decks ORDER BY name",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(ApiError::from)?;
+
+    Ok(rows
+        .into_iter()
+        .map(|(skill_id, name, language, current_level, target_level, target_weekly_hours)| LanguageSkill {
+            skill_id,
+            name,
+            language,
+            current_level,
+            target_level,
+            target_weekly_hours,
+            decks: decks.iter().filter(|d| d.skill_id == skill_id).cloned().collect(),
+        })
+        .collect())
+}
+
+pub async fn add_deck(pool: &Pool<Sqlite>, input: &FlashcardDeckInput) -> Result<FlashcardDeck, ApiError> {
+    get(pool, input.skill_id).await?;
+    let card_count = input.card_count.unwrap_or(0);
+    if card_count < 0 {
+        return Err(ApiError::validation("Card count can't be negative"));
+    }
+    sqlx::query_as(
+        r#"INSERT INTO flashcard_decks (skill_id, name, source, card_count) VALUES (?, ?, ?, ?)
+           RETURNING id, skill_id, name, source, card_count, mature_count, created_at"#,
+    )
+    .bind(input.skill_id)
+    .bind(non_empty(&input.name, "Deck name")?)
+    .bind(input.source.as_deref().map(str::trim).filter(|s| !s.is_empty()))
+    .bind(card_count)
+    .fetch_one(pool)
+    .await
+    .map_err(|e| ApiError::from_sqlx(e, "Failed to create deck"))
+}
+
+/// Record a review sitting; its minutes are logged as practice on the skill
+pub async fn log_review(pool: &Pool<Sqlite>, input: &FlashcardReviewInput) -> Result<FlashcardReview, ApiError> {
+    if !(1..=MAX_REVIEWED).contains(&input.reviewed) {
+        return Err(ApiError::validation(format!("Reviewed must be between 1 and {}", MAX_REVIEWED)));
+    }
+    if !(0..=input.reviewed).contains(&input.correct) {
+        return Err(ApiError::validation("Correct must be between 0 and the cards reviewed"));
+    }
+    let new_cards = input.new_cards.unwrap_or(0);
+    if !(0..=input.reviewed).contains(&new_cards) {
+        return Err(ApiError::validation("New cards must be between 0 and the cards reviewed"));
+    }
+    if matches!(input.minutes, Some(m) if !(0..=24 * 60).contains(&m)) {
+        return Err(ApiError::validation("Minutes must be between 0 and 1440"));
+    }
+    if matches!((input.card_count, input.mature_count), (Some(cards), Some(mature)) if mature > cards)
+        || input.card_count.is_some_and(|c| c < 0)
+        || input.mature_count.is_some_and(|m| m < 0)
+    {
+        return Err(ApiError::validation("Mature cards must be between 0 and the deck's cards"));
+    }
+
+    let deck: Option<(i64, String)> = sqlx::query_as("SELECT skill_id, name FROM flashcard_decks WHERE id = ?")
+        .bind(input.deck_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(ApiError::from)?;
+    let (skill_id, deck_name) = deck.ok_or_else(|| ApiError::not_found("Deck not found"))?;
+    let reviewed_at = clock::now().with_timezone(&chrono::Utc).format("%Y-%m-%d %H:%M:%S").to_string();
+
+    let mut tx = pool.begin().await.map_err(ApiError::from)?;
+    let practice_log_id = match input.minutes.filter(|m| *m > 0) {
+        Some(minutes) => {
+            let id: i64 = sqlx::query_scalar(
+                "INSERT INTO practice_logs (skill_id, duration_minutes, notes, logged_at) VALUES (?, ?, ?, ?) RETURNING id",
+            )
+            .bind(skill_id)
+            .bind(minutes)
+            .bind(format!("Flashcards: {} ({} cards)", deck_name, input.reviewed))
+            .bind(&reviewed_at)
+            .fetch_one(&mut *tx)
+            .await
+            .map_err(ApiError::from)?;
+            sqlx::query("UPDATE skills SET total_hours = COALESCE(total_hours, 0) + (? / 60.0) WHERE id = ?")
+                .bind(minutes)
+                .bind(skill_id)
+                .execute(&mut *tx)
+                .await
+                .map_err(ApiError::from)?;
+            Some(id)
+        }
+        None => None,
+    };
+
+    sqlx::query(
+        r#"UPDATE flashcard_decks
+           SET card_count = COALESCE(?1, card_count + ?2), mature_count = COALESCE(?3, mature_count)
+           WHERE id = ?4"#,
+    )
+    .bind(input.card_count)
+    .bind(new_cards)
+    .bind(input.mature_count)
+    .bind(input.deck_id)
+    .execute(&mut *tx)
+    .await
+    .map_err(ApiError::from)?;
+
+    let review = sqlx::query_as(
+        r#"INSERT INTO flashcard_reviews (deck_id, practice_log_id, reviewed, correct, new_cards, minutes, reviewed_at)
+           VALUES (?, ?, ?, ?, ?, ?, ?)
+           RETURNING id, deck_id, practice_log_id, reviewed, correct, new_cards, minutes, reviewed_at"#,
+    )
+    .bind(input.deck_id)
+    .bind(practice_log_id)
+    .bind(input.reviewed)
+    .bind(input.correct)
+    .bind(new_cards)
+    .bind(input.minutes)
+    .bind(&reviewed_at)
+    .fetch_one(&mut *tx)
+    .await
+    .map_err(ApiError::from)?;
+    tx.commit().await.map_err(ApiError::from)?;
+
+    aggregates::invalidate();
+    Ok(review)
+}
+
+fn hours(minutes: i64) -> f64 {
+    (minutes as f64 / 60.0 * 10.0).round() / 10.0
+}
+
+fn hour_score(hours: f64, weekly_hours: Option<f64>, days: i64) -> Option<i64> {
+    let target = weekly_hours.filter(|h| *h > 0.0)? * COMPONENT_SHARE * days as f64 / 7.0;
+    Some(((hours / target).min(1.0) * 100.0).round() as i64)
+}
+
+/// Proficiency of each language skill, or of one, over the last `days` days
+/// ending with `today`
+pub async fn proficiency(
+    pool: &Pool<Sqlite>,
+    skill_id: Option<i64>,
+    days: i64,
+    today: NaiveDate,
+) -> Result<Vec<LanguageProficiency>, ApiError> {
+    let today = today.format("%Y-%m-%d").to_string();
+    let since = format!("-{} days", days);
+    let skills: Vec<LanguageSkill> = list(pool)
+        .await?
+        .into_iter()
+        .filter(|s| skill_id.map_or(true, |id| id == s.skill_id))
+        .collect();
+    if skill_id.is_some() && skills.is_empty() {
+        return Err(ApiError::not_found("Language skill not found"));
+    }
+
+    let mut result = Vec::with_capacity(skills.len());
+    for skill in skills {
+        let session_minutes: HashMap<String, i64> = sqlx::query_as::<_, (String, i64)>(
+            r#"WITH logged AS (
+                   SELECT session_type, duration_minutes AS minutes FROM sessions
+                   WHERE reference_type = 'skill' AND reference_id = ?1 AND ended_at IS NOT NULL
+                     AND date(started_at, 'localtime') > date(?2, ?3)
+                   UNION ALL
+                   SELECT session_type, total_minutes FROM session_daily_aggregates
+                   WHERE reference_type = 'skill' AND reference_id = ?1 AND date > date(?2, ?3)
+               )
+               SELECT session_type, COALESCE(SUM(minutes), 0) FROM logged GROUP BY session_type"#,
+        )
+        .bind(skill.skill_id)
+        .bind(&today)
+        .bind(&since)
+        .fetch_all(pool)
+        .await
+        .map_err(ApiError::from)?
+        .into_iter()
+        .collect();
+
+        let practice_minutes: i64 = sqlx::query_scalar(
+            r#"SELECT COALESCE(SUM(duration_minutes), 0) FROM practice_logs p
+               WHERE skill_id = ?1 AND date(logged_at, 'localtime') > date(?2, ?3)
+                 AND NOT EXISTS (SELECT 1 FROM flashcard_reviews r WHERE r.practice_log_id = p.id)"#,
+        )
+        .bind(skill.skill_id)
+        .bind(&today)
+        .bind(&since)
+        .fetch_one(pool)
+        .await
+        .map_err(ApiError::from)?;
+
+        let (reviewed, correct, new_cards, review_minutes): (i64, i64, i64, i64) = sqlx::query_as(
+            r#"SELECT COALESCE(SUM(r.reviewed), 0), COALESCE(SUM(r.correct), 0),
+                      COALESCE(SUM(r.new_cards), 0), COALESCE(SUM(r.minutes), 0)
+               FROM flashcard_reviews r
+               JOIN flashcard_decks d ON d.id = r.deck_id
+               WHERE d.skill_id = ?1 AND date(r.reviewed_at, 'localtime') > date(?2, ?3)"#,
+        )
+        .bind(skill.skill_id)
+        .bind(&today)
+        .bind(&since)
+        .fetch_one(pool)
+        .await
+        .map_err(ApiError::from)?;
+
+        let session = |name: &str| session_minutes.get(name).copied().unwrap_or(0);
+        let other_practice = practice_minutes + session("practice");
+        let cards: i64 = skill.decks.iter().map(|d| d.card_count).sum();
+        let mature_cards: i64 = skill.decks.iter().map(|d| d.mature_count).sum();
+        let accuracy = (reviewed > 0).then(|| (correct as f64 / reviewed as f64 * 100.0).round() / 100.0);
+        let maturity = (cards > 0).then(|| mature_cards as f64 / cards as f64);
+
+        let vocabulary_score = match (maturity, accuracy) {
+            (Some(m), Some(a)) => Some(((m + a) / 2.0 * 100.0).round() as i64),
+            (Some(x), None) | (None, Some(x)) => Some((x * 100.0).round() as i64),
+            (None, None) => None,
+        };
+        let listening_hours = hours(session("listening"));
+        let speaking_hours = hours(session("speaking"));
+        let listening_score = hour_score(listening_hours, skill.target_weekly_hours, days);
+        let speaking_score = hour_score(speaking_hours, skill.target_weekly_hours, days);
+
+        let scores: Vec<(&str, i64)> = [
+            ("vocabulary", vocabulary_score),
+            ("listening", listening_score),
+            ("speaking", speaking_score),
+        ]
+        .into_iter()
+        .filter_map(|(name, score)| score.map(|s| (name, s)))
+        .collect();
+        let proficiency = (!scores.is_empty())
+            .then(|| (scores.iter().map(|(_, s)| *s as f64).sum::<f64>() / scores.len() as f64).round() as i64);
+        let weakest = scores.iter().min_by_key(|(_, s)| *s).map(|(name, _)| name.to_string());
+
+        result.push(LanguageProficiency {
+            skill_id: skill.skill_id,
+            name: skill.name,
+            language: skill.language,
+            current_level: skill.current_level,
+            target_level: skill.target_level,
+            days,
+            flashcard_hours: hours(review_minutes),
+            listening_hours,
+            speaking_hours,
+            other_practice_hours: hours(other_practice),
+            total_hours: hours(review_minutes + session("listening") + session("speaking") + other_practice),
+            cards,
+            mature_cards,
+            reviews: reviewed,
+            new_cards,
+            accuracy,
+            vocabulary_score,
+            listening_score,
+            speaking_score,
+            proficiency,
+            weakest,
+        });
+    }
+    Ok(result)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Local, TimeZone};
+
+    #[tokio::test]
+    async fn reviews_listening_and_speaking_combine_into_proficiency() {
+        let pool = crate::test_support::pool().await;
+        let _clock = clock::freeze(Local.with_ymd_and_hms(2030, 4, 10, 19, 0, 0).unwrap());
+        let skill = create(
+            &pool,
+            &LanguageSkillInput {
+                language: "Spanish".to_string(),
+                name: None,
+                description: None,
+                current_level: Some("a2".to_string()),
+                target_level: Some("B2".to_string()),
+                target_weekly_hours: Some(4.0),
+                decks: vec!["Core 2000".to_string()],
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!((skill.name.as_str(), skill.current_level.as_str()), ("Spanish", "A2"));
+        let deck = &skill.decks[0];
+
+        let review = log_review(
+            &pool,
+            &FlashcardReviewInput {
+                deck_id: deck.id,
+                reviewed: 100,
+                correct: 80,
+                new_cards: Some(20),
+                minutes: Some(30),
+                card_count: Some(400),
+                mature_count: Some(200),
+            },
+        )
+        .await
+        .unwrap();
+        assert!(review.practice_log_id.is_some());
+
+        // Listening and speaking are practice types once the template ran
+        sqlx::query(
+            r#"INSERT INTO sessions (session_type, reference_type, reference_id, started_at, ended_at, duration_minutes) VALUES
+               ('listening', 'skill', ?1, '2030-04-08 10:00:00', '2030-04-08 11:00:00', 60),
+               ('speaking', 'skill', ?1, '2030-04-09 10:00:00', '2030-04-09 10:30:00', 30)"#,
+        )
+        .bind(skill.skill_id)
+        .execute(&pool)
+        .await
+        .unwrap();
+        let practice_types: i64 =
+            sqlx::query_scalar("SELECT COUNT(*) FROM session_types WHERE counts_toward = 'practice'")
+                .fetch_one(&pool)
+                .await
+                .unwrap();
+        assert_eq!(practice_types, 3);
+
+        let today = clock::now().date_naive();
+        let stats = proficiency(&pool, Some(skill.skill_id), 7, today).await.unwrap();
+        let p = &stats[0];
+        assert_eq!((p.flashcard_hours, p.listening_hours, p.speaking_hours, p.total_hours), (0.5, 1.0, 0.5, 2.0));
+        assert_eq!(p.other_practice_hours, 0.0);
+        assert_eq!(p.accuracy, Some(0.8));
+        // Maturity 0.5 and accuracy 0.8; listening and speaking each aim at 1h a week
+        assert_eq!((p.vocabulary_score, p.listening_score, p.speaking_score), (Some(65), Some(100), Some(50)));
+        assert_eq!(p.proficiency, Some(72));
+        assert_eq!(p.weakest.as_deref(), Some("speaking"));
+
+        assert!(proficiency(&pool, Some(9_999), 7, today).await.is_err());
+    }
+}
@@ -0,0 +1,249 @@
+//! Life Balance
+//!
+//! Compares the last seven days of time across the four domains against the
+//! user's ideal proportions (`balance_ideal_*`, relative weights):
+//! - academics: study sessions
+//! - skills: practice logs
+//! - fitness: workouts
+//! - wellness: calendar events in the wellness domain
+//!
+//! Sessions of custom types and tracker minutes count toward the domain
+//! their type or tracker names.
+//!
+//! The score is 100 minus the share of time that would have to move between
+//! domains to match the ideal. The week planner scales its study and
+//! practice budgets by `planner_scale`, nudging time toward under-served
+//! domains, and the daily agent reward counts the score as a secondary
+//! objective.
+
+use chrono::{Duration, NaiveDate, NaiveDateTime};
+use serde::Serialize;
+use sqlx::{Pool, Sqlite};
+
+use crate::{
+    commands::calendar::{expand_weekly_rule, occurrence_bounds},
+    error::ApiError,
+    services::{settings, trackers},
+};
+
+/// Domains in display order, with the setting holding each ideal weight
+pub const DOMAINS: &[(&str, &str)] = &[
+    ("academics", "balance_ideal_academics"),
+    ("skills", "balance_ideal_skills"),
+    ("fitness", "balance_ideal_fitness"),
+    ("wellness", "balance_ideal_wellness"),
+];
+
+/// Days of history the score covers, ending on the given day
+const WINDOW_DAYS: i64 = 7;
+
+/// Bounds of `planner_scale`: the planner is nudged, never overturned
+const MIN_PLANNER_SCALE: f64 = 0.75;
+const MAX_PLANNER_SCALE: f64 = 1.25;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DomainBalance {
+    pub domain: &'static str,
+    pub hours: f64,
+    /// Share of the logged time (0-1)
+    pub share: f64,
+    /// Share the ideal proportions ask for (0-1)
+    pub ideal_share: f64,
+    /// Multiplies the planner's weekly budget for the domain
+    pub planner_scale: f64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct LifeBalance {
+    /// 0-100; None when nothing was logged
+    pub score: Option<f64>,
+    pub domains: Vec<DomainBalance>,
+}
+
+/// Balance over the `WINDOW_DAYS` days ending on `end` (local dates)
+pub async fn life_balance(pool: &Pool<Sqlite>, end: NaiveDate) -> Result<LifeBalance, ApiError> {
+    let start = end - Duration::days(WINDOW_DAYS - 1);
+    let mut minutes = [
+        logged_minutes(pool, "SELECT COALESCE(SUM(duration_minutes), 0) FROM sessions WHERE session_type IN (SELECT name FROM session_types WHERE domain = 'academics') AND date(started_at, 'localtime') BETWEEN ? AND ?", start, end).await?,
+        logged_minutes(pool, "SELECT COALESCE(SUM(duration_minutes), 0) FROM practice_logs WHERE date(logged_at, 'localtime') BETWEEN ? AND ?", start, end).await?
+            + custom_session_minutes(pool, "skills", start, end).await?,
+        logged_minutes(pool, "SELECT COALESCE(SUM(duration_minutes), 0) FROM workouts WHERE date(logged_at, 'localtime') BETWEEN ? AND ?", start, end).await?
+            + custom_session_minutes(pool, "fitness", start, end).await?,
+        wellness_minutes(pool, start, end).await? + custom_session_minutes(pool, "wellness", start, end).await?,
+    ];
+    for (minutes, (domain, _)) in minutes.iter_mut().zip(DOMAINS) {
+        *minutes += trackers::domain_minutes(pool, domain, start, end).await?;
+    }
+
+    let mut weights = Vec::with_capacity(DOMAINS.len());
+    for (_, key) in DOMAINS {
+        weights.push(settings::get_i64(pool, key).await?.max(0) as f64);
+    }
+    let weight_total: f64 = weights.iter().sum();
+    // All-zero weights mean an even split
+    let ideal: Vec<f64> = weights
+        .iter()
+        .map(|w| if weight_total > 0.0 { w / weight_total } else { 1.0 / DOMAINS.len() as f64 })
+        .collect();
+    let total: f64 = minutes.iter().sum();
+    let share = |minutes: f64| if total > 0.0 { minutes / total } else { 0.0 };
+
+    // Total variation distance between the actual and ideal split
+    let score = (total > 0.0).then(|| {
+        let distance: f64 = minutes.iter().zip(&ideal).map(|(&m, &i)| (share(m) - i).abs()).sum::<f64>() / 2.0;
+        ((1.0 - distance) * 100.0).round()
+    });
+
+    let domains = DOMAINS
+        .iter()
+        .zip(minutes.iter().zip(&ideal))
+        .map(|((domain, _), (&minutes, &ideal_share))| {
+            let planner_scale = if total > 0.0 {
+                (1.0 + ideal_share - share(minutes)).clamp(MIN_PLANNER_SCALE, MAX_PLANNER_SCALE)
+            } else {
+                1.0
+            };
+            DomainBalance {
+                domain,
+                hours: (minutes / 6.0).round() / 10.0,
+                share: round2(share(minutes)),
+                ideal_share: round2(ideal_share),
+                planner_scale: round2(planner_scale),
+            }
+        })
+        .collect();
+
+    Ok(LifeBalance { score, domains })
+}
+
+async fn logged_minutes(pool: &Pool<Sqlite>, sql: &str, start: NaiveDate, end: NaiveDate) -> Result<f64, ApiError> {
+    let minutes: i64 = sqlx::query_scalar(sql)
+        .bind(start.to_string())
+        .bind(end.to_string())
+        .fetch_one(pool)
+        .await
+        .map_err(ApiError::from)?;
+    Ok(minutes as f64)
+}
+
+/// Minutes of sessions of custom types in `domain`; the built-in practice
+/// type is left out as practice logs already cover skills
+async fn custom_session_minutes(pool: &Pool<Sqlite>, domain: &str, start: NaiveDate, end: NaiveDate) -> Result<f64, ApiError> {
+    let minutes: i64 = sqlx::query_scalar(
+        r#"SELECT COALESCE(SUM(duration_minutes), 0) FROM sessions
+           WHERE session_type IN (SELECT name FROM session_types WHERE domain = ? AND builtin = 0)
+             AND date(started_at, 'localtime') BETWEEN ? AND ?"#,
+    )
+    .bind(domain)
+    .bind(start.to_string())
+    .bind(end.to_string())
+    .fetch_one(pool)
+    .await
+    .map_err(ApiError::from)?;
+    Ok(minutes as f64)
+}
+
+/// Minutes of wellness-domain calendar events, recurring ones expanded
+async fn wellness_minutes(pool: &Pool<Sqlite>, start: NaiveDate, end: NaiveDate) -> Result<f64, ApiError> {
+    let events: Vec<(Option<String>, Option<String>, Option<String>, Option<String>, Option<String>)> = sqlx::query_as(
+        "SELECT start_at, end_at, rrule, start_time, end_time FROM calendar_events WHERE domain = 'wellness'",
+    )
+    .fetch_all(pool)
+    .await
+    .map_err(ApiError::from)?;
+
+    let mut minutes = 0.0;
+    for (start_at, end_at, rrule, start_time, end_time) in events {
+        match (rrule, start_at, end_at) {
+            (Some(rule), _, _) => {
+                let st = start_time.as_deref().unwrap_or("09:00");
+                let et = end_time.as_deref().unwrap_or("10:00");
+                for day in expand_weekly_rule(&rule, start, end) {
+                    let (from, to) = occurrence_bounds(day, st, et);
+                    minutes += span_minutes(&from, &to).unwrap_or(0.0);
+                }
+            }
+            (None, Some(from), Some(to)) => {
+                let in_window = from
+                    .get(..10)
+                    .and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok())
+                    .is_some_and(|d| d >= start && d <= end);
+                if in_window {
+                    minutes += span_minutes(&from, &to).unwrap_or(0.0);
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(minutes)
+}
+
+/// Minutes between two local datetimes; None for all-day or unparsable ones
+fn span_minutes(from: &str, to: &str) -> Option<f64> {
+    let parse = |s: &str| NaiveDateTime::parse_from_str(s.get(..16)?, "%Y-%m-%dT%H:%M").ok();
+    let minutes = (parse(to)? - parse(from)?).num_minutes();
+    (minutes > 0).then_some(minutes as f64)
+}
+
+fn round2(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    async fn log(pool: &Pool<Sqlite>, sql: &str, minutes: i64) {
+        sqlx::query(sql).bind(minutes).execute(pool).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn scores_the_split_against_the_ideal_proportions() {
+        let pool = crate::test_support::pool().await;
+        let end = NaiveDate::from_ymd_opt(2030, 3, 10).unwrap();
+        for (_, key) in DOMAINS {
+            settings::set(&pool, key, json!(25)).await.unwrap();
+        }
+
+        let empty = life_balance(&pool, end).await.unwrap();
+        assert_eq!(empty.score, None);
+        assert!(empty.domains.iter().all(|d| d.planner_scale == 1.0));
+
+        log(&pool, "INSERT INTO sessions (session_type, duration_minutes, started_at) VALUES ('study', ?, '2030-03-08 12:00:00')", 360).await;
+        sqlx::query("INSERT INTO skills (id, name) VALUES (1, 'Piano')").execute(&pool).await.unwrap();
+        log(&pool, "INSERT INTO practice_logs (skill_id, duration_minutes, logged_at) VALUES (1, ?, '2030-03-09 12:00:00')", 120).await;
+        log(&pool, "INSERT INTO workouts (duration_minutes, logged_at) VALUES (?, '2030-03-09 12:00:00')", 120).await;
+        // Outside the window
+        log(&pool, "INSERT INTO workouts (duration_minutes, logged_at) VALUES (?, '2030-03-01 12:00:00')", 600).await;
+        // Weekly yoga on Mondays (1) and Thursdays (4): 2030-03-04 and 03-07
+        sqlx::query(
+            "INSERT INTO calendar_events (title, rrule, start_time, end_time, category, domain) VALUES ('Yoga', 'WEEKLY:1,4', '07:00', '07:30', 'personal', 'wellness')",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+        sqlx::query(
+            "INSERT INTO calendar_events (title, start_at, end_at, category, domain) VALUES ('Walk', '2030-03-10T18:00:00', '2030-03-10T19:00:00', 'personal', 'wellness')",
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        // 6h / 2h / 2h / 2h of 12h against a 3h each ideal
+        let balance = life_balance(&pool, end).await.unwrap();
+        let hours: Vec<f64> = balance.domains.iter().map(|d| d.hours).collect();
+        assert_eq!(hours, vec![6.0, 2.0, 2.0, 2.0]);
+        // Half of the time is academics against a quarter: a quarter has to move
+        assert_eq!(balance.score, Some(75.0));
+        assert_eq!(balance.domains[0].planner_scale, 0.75);
+        assert_eq!(balance.domains[1].planner_scale, 1.08);
+
+        // Ideals that match the split score full marks
+        settings::set(&pool, "balance_ideal_academics", json!(60)).await.unwrap();
+        for key in ["balance_ideal_skills", "balance_ideal_fitness", "balance_ideal_wellness"] {
+            settings::set(&pool, key, json!(20)).await.unwrap();
+        }
+        assert_eq!(life_balance(&pool, end).await.unwrap().score, Some(100.0));
+    }
+}
@@ -0,0 +1,370 @@
+//! Meditation
+//!
+//! Meditation is the built-in `meditation` tracker, so it gets the tracker's
+//! daily streak and counts toward wellness in the life balance. A sit is a
+//! tracker log in minutes with the style as its `kind` and the calm felt
+//! afterwards (1-5) as its `rating`. Logged sits are reported to the agent as
+//! the outcome of its `meditation` action.
+//!
+//! The guided timer runs in the backend, one at a time. It publishes
+//! `meditation://timer` events: a `bell` every `interval_minutes` and
+//! `finished` once the planned time is up. Nothing is logged until the user
+//! ends the timer with a calm rating; the time actually sat is logged, so
+//! stopping early or sitting on counts as it was.
+
+use std::sync::atomic::{AtomicU64, Ordering};
+
+use chrono::{DateTime, Local};
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use sqlx::{Pool, Sqlite};
+use tokio::sync::broadcast;
+
+use crate::{
+    agent::outcomes::link_meditation_outcome,
+    error::ApiError,
+    services::{
+        clock,
+        trackers::{self, TrackerLog, TrackerLogInput},
+    },
+};
+
+/// Slug of the built-in tracker
+pub const TRACKER_SLUG: &str = "meditation";
+/// Frontend event carrying a `TimerEvent`
+pub const TIMER_EVENT: &str = "meditation://timer";
+
+const MAX_MINUTES: i64 = 180;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MeditationStyle {
+    Breath,
+    BodyScan,
+    LovingKindness,
+    OpenAwareness,
+    Walking,
+    Guided,
+    Other,
+}
+
+impl MeditationStyle {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            MeditationStyle::Breath => "breath",
+            MeditationStyle::BodyScan => "body_scan",
+            MeditationStyle::LovingKindness => "loving_kindness",
+            MeditationStyle::OpenAwareness => "open_awareness",
+            MeditationStyle::Walking => "walking",
+            MeditationStyle::Guided => "guided",
+            MeditationStyle::Other => "other",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MeditationTimer {
+    pub id: u64,
+    pub style: MeditationStyle,
+    pub planned_minutes: i64,
+    pub interval_minutes: Option<i64>,
+    pub started_at: String,
+    pub elapsed_seconds: i64,
+    /// The planned time is up; waiting for the rating
+    pub finished: bool,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TimerEventKind {
+    Bell,
+    Finished,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TimerEvent {
+    pub timer_id: u64,
+    pub event: TimerEventKind,
+    pub elapsed_minutes: i64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MeditationInput {
+    pub minutes: i64,
+    pub style: MeditationStyle,
+    #[serde(default)]
+    pub calm_rating: Option<i64>,
+    #[serde(default)]
+    pub note: Option<String>,
+    /// UTC SQLite timestamp; defaults to now
+    #[serde(default)]
+    pub logged_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct StyleCount {
+    pub style: String,
+    pub sessions: i64,
+    pub minutes: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MeditationStats {
+    pub days: i64,
+    pub sessions: i64,
+    pub minutes: i64,
+    /// Mean calm rating of rated sits
+    pub avg_calm: Option<f64>,
+    /// Most practised first
+    pub by_style: Vec<StyleCount>,
+    pub current_streak: i64,
+    pub best_streak: i64,
+    pub week_minutes: i64,
+}
+
+#[derive(Debug, Clone, Copy)]
+struct ActiveTimer {
+    id: u64,
+    style: MeditationStyle,
+    planned_minutes: i64,
+    interval_minutes: Option<i64>,
+    started_at: DateTime<Local>,
+}
+
+impl ActiveTimer {
+    fn status(&self) -> MeditationTimer {
+        let elapsed_seconds = (clock::now() - self.started_at).num_seconds().max(0);
+        MeditationTimer {
+            id: self.id,
+            style: self.style,
+            planned_minutes: self.planned_minutes,
+            interval_minutes: self.interval_minutes,
+            started_at: self.started_at.to_rfc3339(),
+            elapsed_seconds,
+            finished: elapsed_seconds >= self.planned_minutes * 60,
+        }
+    }
+}
+
+static TIMER: Lazy<Mutex<Option<ActiveTimer>>> = Lazy::new(|| Mutex::new(None));
+static NEXT_TIMER_ID: AtomicU64 = AtomicU64::new(1);
+static TIMER_EVENTS: Lazy<broadcast::Sender<TimerEvent>> = Lazy::new(|| broadcast::channel(16).0);
+
+/// Forward timer bells to the frontend as `meditation://timer` events
+pub fn start_event_forwarder(app_handle: tauri::AppHandle) {
+    use tauri::Emitter;
+
+    let mut events = TIMER_EVENTS.subscribe();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            match events.recv().await {
+                Ok(event) => {
+                    if let Err(e) = app_handle.emit(TIMER_EVENT, event) {
+                        log::warn!("Failed to emit meditation timer event: {}", e);
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(_)) => {}
+                Err(broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    });
+}
+
+fn validate_minutes(minutes: i64) -> Result<(), ApiError> {
+    if !(1..=MAX_MINUTES).contains(&minutes) {
+        return Err(ApiError::validation(format!("Minutes must be between 1 and {}", MAX_MINUTES)));
+    }
+    Ok(())
+}
+
+/// Start the guided timer; fails while another one runs
+pub fn start_timer(
+    style: MeditationStyle,
+    planned_minutes: i64,
+    interval_minutes: Option<i64>,
+) -> Result<MeditationTimer, ApiError> {
+    validate_minutes(planned_minutes)?;
+    if interval_minutes.is_some_and(|i| !(1..planned_minutes).contains(&i)) {
+        return Err(ApiError::validation("The bell interval must be shorter than the sit"));
+    }
+
+    let timer = {
+        let mut active = TIMER.lock();
+        if active.is_some() {
+            return Err(ApiError::conflict("A meditation timer is already running"));
+        }
+        let timer = ActiveTimer {
+            id: NEXT_TIMER_ID.fetch_add(1, Ordering::Relaxed),
+            style,
+            planned_minutes,
+            interval_minutes,
+            started_at: clock::now(),
+        };
+        *active = Some(timer);
+        timer
+    };
+
+    tauri::async_runtime::spawn(async move {
+        let mut elapsed = 0;
+        while elapsed < timer.planned_minutes {
+            let next = timer
+                .interval_minutes
+                .map_or(timer.planned_minutes, |i| (elapsed / i + 1) * i)
+                .min(timer.planned_minutes);
+            tokio::time::sleep(std::time::Duration::from_secs((next - elapsed) as u64 * 60)).await;
+            elapsed = next;
+            // Stop ringing once this timer was ended or replaced
+            if TIMER.lock().as_ref().map(|t| t.id) != Some(timer.id) {
+                break;
+            }
+            let event = if elapsed >= timer.planned_minutes { TimerEventKind::Finished } else { TimerEventKind::Bell };
+            let _ = TIMER_EVENTS.send(TimerEvent { timer_id: timer.id, event, elapsed_minutes: elapsed });
+        }
+    });
+
+    Ok(timer.status())
+}
+
+/// The running timer, if any
+pub fn timer() -> Option<MeditationTimer> {
+    TIMER.lock().as_ref().map(ActiveTimer::status)
+}
+
+/// Drop the running timer without logging; false when none ran
+pub fn cancel_timer() -> bool {
+    TIMER.lock().take().is_some()
+}
+
+/// End the running timer and log the time sat
+pub async fn finish_timer(pool: &Pool<Sqlite>, calm_rating: Option<i64>, note: Option<String>) -> Result<TrackerLog, ApiError> {
+    let timer = (*TIMER.lock()).ok_or_else(|| ApiError::not_found("No meditation timer is running"))?;
+    let now = clock::now();
+    let minutes = ((now - timer.started_at).num_seconds() as f64 / 60.0).round() as i64;
+    if minutes < 1 {
+        return Err(ApiError::validation("Sit for at least a minute to log it, or cancel the timer"));
+    }
+
+    let input = MeditationInput {
+        minutes: minutes.min(MAX_MINUTES),
+        style: timer.style,
+        calm_rating,
+        note,
+        logged_at: Some(now.with_timezone(&chrono::Utc).format("%Y-%m-%d %H:%M:%S").to_string()),
+    };
+    let log = record(pool, &input, Some(timer.planned_minutes)).await?;
+    // Only clear once logged, so a failed log can be retried
+    let mut active = TIMER.lock();
+    if active.as_ref().is_some_and(|t| t.id == timer.id) {
+        *active = None;
+    }
+    Ok(log)
+}
+
+/// Log a sit without the timer
+pub async fn log_sit(pool: &Pool<Sqlite>, input: &MeditationInput) -> Result<TrackerLog, ApiError> {
+    record(pool, input, None).await
+}
+
+async fn record(pool: &Pool<Sqlite>, input: &MeditationInput, planned_minutes: Option<i64>) -> Result<TrackerLog, ApiError> {
+    validate_minutes(input.minutes)?;
+    let tracker = trackers::by_slug(pool, TRACKER_SLUG).await?;
+    let log = trackers::record(
+        pool,
+        &TrackerLogInput {
+            tracker_id: tracker.id,
+            amount: input.minutes as f64,
+            minutes: Some(input.minutes),
+            kind: Some(input.style.as_str().to_string()),
+            rating: input.calm_rating,
+            note: input.note.clone(),
+            logged_at: input.logged_at.clone(),
+            ..Default::default()
+        },
+    )
+    .await?;
+    link_meditation_outcome(pool, &log, planned_minutes);
+    Ok(log)
+}
+
+/// Sits over the last `days` days ending with `today`, with the streak
+pub async fn stats(pool: &Pool<Sqlite>, days: i64, today: chrono::NaiveDate) -> Result<MeditationStats, ApiError> {
+    let tracker = trackers::by_slug(pool, TRACKER_SLUG).await?;
+    let since = format!("-{} days", days);
+    let rows: Vec<(Option<String>, i64, i64, i64, i64)> = sqlx::query_as(
+        r#"SELECT kind, COUNT(*), CAST(SUM(amount) AS INTEGER), COUNT(rating), COALESCE(SUM(rating), 0)
+           FROM tracker_logs
+           WHERE tracker_id = ?1 AND date(logged_at, 'localtime') > date(?2, ?3)
+           GROUP BY kind
+           ORDER BY SUM(amount) DESC, kind"#,
+    )
+    .bind(tracker.id)
+    .bind(today.to_string())
+    .bind(&since)
+    .fetch_all(pool)
+    .await
+    .map_err(ApiError::from)?;
+
+    let (rated, calm_sum) = rows.iter().fold((0, 0), |(n, sum), r| (n + r.3, sum + r.4));
+    let summary = trackers::summary(pool, tracker, today).await?;
+    Ok(MeditationStats {
+        days,
+        sessions: rows.iter().map(|r| r.1).sum(),
+        minutes: rows.iter().map(|r| r.2).sum(),
+        avg_calm: (rated > 0).then(|| ((calm_sum as f64 / rated as f64) * 100.0).round() / 100.0),
+        by_style: rows
+            .into_iter()
+            .map(|(style, sessions, minutes, _, _)| StyleCount {
+                style: style.unwrap_or_else(|| MeditationStyle::Other.as_str().to_string()),
+                sessions,
+                minutes,
+            })
+            .collect(),
+        current_streak: summary.current_streak,
+        best_streak: summary.best_streak,
+        week_minutes: summary.week_minutes,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{NaiveDate, TimeZone};
+
+    #[tokio::test]
+    async fn the_timer_logs_the_time_sat_and_sits_build_a_streak() {
+        let pool = crate::test_support::pool().await;
+        for (day, minutes, rating) in [("2030-07-01", 10, 3), ("2030-07-02", 15, 4)] {
+            log_sit(
+                &pool,
+                &MeditationInput {
+                    minutes,
+                    style: MeditationStyle::Breath,
+                    calm_rating: Some(rating),
+                    note: None,
+                    logged_at: Some(format!("{} 12:00:00", day)),
+                },
+            )
+            .await
+            .unwrap();
+        }
+
+        let started = Local.with_ymd_and_hms(2030, 7, 3, 7, 0, 0).unwrap();
+        let _clock = clock::freeze(started);
+        let timer = start_timer(MeditationStyle::BodyScan, 20, Some(5)).unwrap();
+        assert!(!timer.finished);
+        assert!(start_timer(MeditationStyle::Breath, 10, None).is_err());
+        {
+            // Stopped a little early
+            let _later = clock::freeze(started + chrono::Duration::minutes(18));
+            let log = finish_timer(&pool, Some(5), None).await.unwrap();
+            assert_eq!((log.amount, log.kind.as_deref(), log.rating), (18.0, Some("body_scan"), Some(5)));
+        }
+        assert!(timer().is_none());
+
+        let stats = stats(&pool, 7, NaiveDate::from_ymd_opt(2030, 7, 3).unwrap()).await.unwrap();
+        assert_eq!((stats.sessions, stats.minutes, stats.avg_calm), (3, 43, Some(4.0)));
+        assert_eq!(stats.by_style[0].style, "breath");
+        assert_eq!((stats.current_streak, stats.best_streak), (3, 3));
+    }
+}
@@ -0,0 +1,362 @@
+//! Missed Plan Blocks
+//!
+//! Nightly maintenance looks for accepted or locked blocks that ended without
+//! a session: none started from the block, and none for its course,
+//! assignment or skill that started around it. Workout blocks count as kept
+//! when a workout was logged that day. Those are marked missed. With
+//! `planner_reschedule_missed` on, each missed block also gets an accepted
+//! replacement of the same length in the first free slot later that week.
+//!
+//! Runs that find missed blocks are published as `planner://missed-blocks`
+//! events so the frontend can tell the user what moved.
+
+use chrono::{Duration, Local, NaiveDate, NaiveDateTime};
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use sqlx::{Pool, Sqlite};
+use tokio::sync::broadcast;
+
+use crate::{
+    commands::{
+        free_slots::{free_slots, local_naive, round_up_to_step, FreeSlotQuery},
+        week_plan_blocks::{create_week_plan_block_inner, WeekPlanBlockInput},
+    },
+    error::ApiError,
+    models::week_plan_block::WeekPlanBlock,
+    services::{clock, settings},
+    utils::parse_datetime_utc,
+};
+
+/// Frontend event carrying a `MissedBlocksReport`
+pub const MISSED_BLOCKS_EVENT: &str = "planner://missed-blocks";
+
+pub const RESCHEDULE_SETTING: &str = "planner_reschedule_missed";
+
+/// Older blocks are left alone, so the first run doesn't flag all history
+const LOOKBACK_DAYS: i64 = 7;
+/// How early a session may start and still count for a block
+const EARLY_START_MINUTES: i64 = 30;
+
+const BLOCK_TIME_FORMAT: &str = "%Y-%m-%dT%H:%M:%S";
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RescheduledBlock {
+    pub missed_block_id: i64,
+    pub new_block_id: i64,
+    pub start_at: String,
+    pub end_at: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct MissedBlocksReport {
+    /// Blocks marked missed in this run
+    pub missed: Vec<i64>,
+    pub rescheduled: Vec<RescheduledBlock>,
+    /// Missed blocks with no free slot left in their week
+    pub unplaced: Vec<i64>,
+}
+
+static REPORTS: Lazy<broadcast::Sender<MissedBlocksReport>> = Lazy::new(|| broadcast::channel(16).0);
+
+/// Receive every report with missed blocks published after this call
+pub fn subscribe() -> broadcast::Receiver<MissedBlocksReport> {
+    REPORTS.subscribe()
+}
+
+/// Forward reports to the frontend as `planner://missed-blocks` events
+pub fn start_event_forwarder(app_handle: tauri::AppHandle) {
+    use tauri::Emitter;
+
+    let mut reports = subscribe();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            match reports.recv().await {
+                Ok(report) => {
+                    if let Err(e) = app_handle.emit(MISSED_BLOCKS_EVENT, report) {
+                        log::warn!("Failed to emit missed blocks: {}", e);
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(_)) => {}
+                Err(broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    });
+}
+
+fn parse_block_time(value: &str) -> Option<NaiveDateTime> {
+    NaiveDateTime::parse_from_str(value, BLOCK_TIME_FORMAT).ok()
+}
+
+/// A session, in local time, with what it was for
+struct StudySession {
+    start: NaiveDateTime,
+    reference_type: Option<String>,
+    reference_id: Option<i64>,
+}
+
+impl StudySession {
+    fn covers(&self, block: &WeekPlanBlock, start: NaiveDateTime, end: NaiveDateTime) -> bool {
+        if self.start < start - Duration::minutes(EARLY_START_MINUTES) || self.start >= end {
+            return false;
+        }
+        if let Some(skill_id) = block.skill_id {
+            return self.reference_type.as_deref() == Some("skill") && self.reference_id == Some(skill_id);
+        }
+        match (self.reference_type.as_deref(), block.assignment_id, block.course_id) {
+            (Some("assignment"), Some(assignment_id), _) => self.reference_id == Some(assignment_id),
+            (Some("course"), _, Some(course_id)) => self.reference_id == Some(course_id),
+            // A block without a course counts any study session
+            (Some("course") | Some("assignment") | None, None, None) => true,
+            _ => false,
+        }
+    }
+}
+
+/// Mark blocks that ended by now without a session as missed
+pub async fn mark_missed(pool: &Pool<Sqlite>) -> Result<Vec<WeekPlanBlock>, ApiError> {
+    let now = clock::now().naive_local();
+    let since = now - Duration::days(LOOKBACK_DAYS);
+
+    let candidates = sqlx::query_as::<_, WeekPlanBlock>(
+        r#"
+        SELECT * FROM week_plan_blocks b
+        WHERE b.status IN ('accepted', 'locked')
+          AND b.block_type <> 'break'
+          AND b.missed_at IS NULL
+          AND b.progress IS NULL
+          AND b.start_at >= ? AND b.end_at <= ?
+          AND NOT EXISTS (SELECT 1 FROM sessions s WHERE s.week_plan_block_id = b.id)
+          AND NOT (b.block_type = 'workout' AND EXISTS (
+              SELECT 1 FROM workouts w WHERE date(w.logged_at, 'localtime') = date(b.start_at)))
+        ORDER BY b.start_at
+        "#,
+    )
+    .bind(since.format(BLOCK_TIME_FORMAT).to_string())
+    .bind(now.format(BLOCK_TIME_FORMAT).to_string())
+    .fetch_all(pool)
+    .await?;
+    if candidates.is_empty() {
+        return Ok(Vec::new());
+    }
+
+    // Session times are stored in UTC; a day of slack covers any offset
+    let session_rows: Vec<(String, Option<String>, Option<i64>)> = sqlx::query_as(
+        "SELECT started_at, reference_type, reference_id FROM sessions WHERE started_at >= ?",
+    )
+    .bind((since - Duration::days(1)).format("%Y-%m-%d").to_string())
+    .fetch_all(pool)
+    .await?;
+    let sessions: Vec<StudySession> = session_rows
+        .into_iter()
+        .filter_map(|(started_at, reference_type, reference_id)| {
+            Some(StudySession {
+                start: parse_datetime_utc(&started_at)?.with_timezone(&Local).naive_local(),
+                reference_type,
+                reference_id,
+            })
+        })
+        .collect();
+
+    let missed_at = clock::now().with_timezone(&chrono::Utc).format("%Y-%m-%d %H:%M:%S").to_string();
+    let mut missed = Vec::new();
+    for block in candidates {
+        let (Some(start), Some(end)) = (parse_block_time(&block.start_at), parse_block_time(&block.end_at)) else {
+            continue;
+        };
+        // Workout blocks are kept by a workout logged that day, checked above
+        if block.block_type != "workout" && sessions.iter().any(|s| s.covers(&block, start, end)) {
+            continue;
+        }
+        let block = sqlx::query_as::<_, WeekPlanBlock>(
+            "UPDATE week_plan_blocks SET missed_at = ? WHERE id = ? RETURNING *",
+        )
+        .bind(&missed_at)
+        .bind(block.id)
+        .fetch_one(pool)
+        .await?;
+        missed.push(block);
+    }
+    Ok(missed)
+}
+
+/// Place a replacement for a missed block in the first free slot left in its week
+async fn reschedule(pool: &Pool<Sqlite>, block: &WeekPlanBlock) -> Result<Option<RescheduledBlock>, ApiError> {
+    let (Some(start), Some(end)) = (parse_block_time(&block.start_at), parse_block_time(&block.end_at)) else {
+        return Ok(None);
+    };
+    let minutes = (end - start).num_minutes();
+    let now = clock::now().naive_local();
+    let Ok(week_start) = NaiveDate::parse_from_str(&block.week_start_date, "%Y-%m-%d") else {
+        return Ok(None);
+    };
+    let week_end = week_start + Duration::days(6);
+    if now.date() > week_end {
+        return Ok(None);
+    }
+
+    let slots = free_slots(
+        pool,
+        FreeSlotQuery {
+            start_date: now.date().to_string(),
+            end_date: week_end.to_string(),
+            min_minutes: Some(minutes),
+            include_suggested: Some(false),
+        },
+    )
+    .await?;
+    // Today's slots may already have started
+    let earliest = round_up_to_step(now);
+    let placement = slots.iter().find_map(|slot| {
+        let slot_start = local_naive(&slot.start_at)?.max(earliest);
+        let slot_end = local_naive(&slot.end_at)?;
+        (slot_end - slot_start >= Duration::minutes(minutes)).then_some(slot_start)
+    });
+    let Some(new_start) = placement else {
+        return Ok(None);
+    };
+    let new_end = new_start + Duration::minutes(minutes);
+
+    let replacement = create_week_plan_block_inner(
+        pool,
+        WeekPlanBlockInput {
+            user_id: Some(block.user_id),
+            week_start_date: block.week_start_date.clone(),
+            start_at: new_start.format(BLOCK_TIME_FORMAT).to_string(),
+            end_at: new_end.format(BLOCK_TIME_FORMAT).to_string(),
+            block_type: block.block_type.clone(),
+            course_id: block.course_id,
+            weekly_task_id: block.weekly_task_id,
+            title: block.title.clone(),
+            status: Some("accepted".to_string()),
+            rationale_json: Some(
+                serde_json::json!({ "reason": "Rescheduled missed block", "missed_block_id": block.id }).to_string(),
+            ),
+            assignment_id: block.assignment_id,
+            exam_id: block.exam_id,
+            split_group: None,
+            skill_id: block.skill_id,
+            workout_template_id: block.workout_template_id,
+        },
+    )
+    .await?;
+
+    sqlx::query("UPDATE week_plan_blocks SET rescheduled_to_id = ? WHERE id = ?")
+        .bind(replacement.id)
+        .bind(block.id)
+        .execute(pool)
+        .await?;
+
+    Ok(Some(RescheduledBlock {
+        missed_block_id: block.id,
+        new_block_id: replacement.id,
+        start_at: replacement.start_at,
+        end_at: replacement.end_at,
+    }))
+}
+
+/// Mark missed blocks, reschedule them when enabled, and notify the frontend
+pub async fn run(pool: &Pool<Sqlite>) -> Result<MissedBlocksReport, ApiError> {
+    let missed = mark_missed(pool).await?;
+    let mut report = MissedBlocksReport {
+        missed: missed.iter().map(|b| b.id).collect(),
+        ..Default::default()
+    };
+    if missed.is_empty() {
+        return Ok(report);
+    }
+
+    if settings::get_bool(pool, RESCHEDULE_SETTING).await? {
+        for block in &missed {
+            match reschedule(pool, block).await? {
+                Some(rescheduled) => report.rescheduled.push(rescheduled),
+                None => report.unplaced.push(block.id),
+            }
+        }
+    }
+
+    log::info!(
+        "Marked {} plan blocks missed, rescheduled {}",
+        report.missed.len(),
+        report.rescheduled.len()
+    );
+    let _ = REPORTS.send(report.clone());
+    Ok(report)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::commands::week_plan_blocks::bulk_create_plan_blocks_inner;
+    use crate::test_support::{self, course, plan_block};
+    use chrono::TimeZone;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn missed_blocks_are_marked_and_moved_later_in_the_week() {
+        let pool = test_support::pool().await;
+        // Wednesday evening, after working hours
+        let _clock = clock::freeze(Local.with_ymd_and_hms(2030, 9, 4, 21, 0, 0).unwrap());
+        settings::set(&pool, RESCHEDULE_SETTING, json!(true)).await.unwrap();
+        let algorithms = course("Algorithms").create(&pool).await;
+
+        let accepted = |start: &str, end: &str, status: &str| {
+            let mut block = plan_block("2030-09-02", start, end, Some(algorithms.id));
+            block.status = Some(status.to_string());
+            block
+        };
+        let blocks = bulk_create_plan_blocks_inner(
+            &pool,
+            vec![
+                // Missed
+                accepted("2030-09-03T09:00:00", "2030-09-03T10:30:00", "accepted"),
+                // Worked through a session started from the block
+                accepted("2030-09-03T14:00:00", "2030-09-03T15:00:00", "accepted"),
+                // Only suggested
+                accepted("2030-09-03T16:00:00", "2030-09-03T17:00:00", "suggested"),
+                // Still ahead, and busy for the replacement
+                accepted("2030-09-05T08:30:00", "2030-09-05T09:00:00", "locked"),
+            ],
+        )
+        .await
+        .unwrap();
+        sqlx::query("INSERT INTO sessions (session_type, started_at, week_plan_block_id) VALUES ('study', '2030-09-03 12:00:00', ?)")
+            .bind(blocks[1].id)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let report = run(&pool).await.unwrap();
+        assert_eq!(report.missed, vec![blocks[0].id]);
+        assert!(report.unplaced.is_empty());
+        let moved = &report.rescheduled[0];
+        assert_eq!(moved.missed_block_id, blocks[0].id);
+        assert_eq!(
+            (moved.start_at.as_str(), moved.end_at.as_str()),
+            ("2030-09-05T09:00:00", "2030-09-05T10:30:00")
+        );
+
+        // Already marked blocks are not reported again
+        assert!(run(&pool).await.unwrap().missed.is_empty());
+    }
+
+    #[tokio::test]
+    async fn a_session_for_the_course_around_the_block_counts() {
+        let pool = test_support::pool().await;
+        let _clock = clock::freeze(Local.with_ymd_and_hms(2030, 9, 4, 21, 0, 0).unwrap());
+        let algorithms = course("Algorithms").create(&pool).await;
+        let mut block = plan_block("2030-09-02", "2030-09-03T09:00:00", "2030-09-03T10:00:00", Some(algorithms.id));
+        block.status = Some("accepted".to_string());
+        bulk_create_plan_blocks_inner(&pool, vec![block]).await.unwrap();
+
+        let started = Local.with_ymd_and_hms(2030, 9, 3, 8, 45, 0).unwrap().with_timezone(&chrono::Utc);
+        sqlx::query("INSERT INTO sessions (session_type, reference_type, reference_id, started_at) VALUES ('study', 'course', ?, ?)")
+            .bind(algorithms.id)
+            .bind(started.format("%Y-%m-%d %H:%M:%S").to_string())
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let report = run(&pool).await.unwrap();
+        assert!(report.missed.is_empty());
+    }
+}
@@ -1 +1,33 @@
+pub mod accountability;
+pub mod achievements;
+pub mod aggregates;
+pub mod attachments;
+pub mod calendar_feed;
+pub mod challenges;
+pub mod change_log;
+pub mod clock;
+pub mod cycle;
+pub mod dedupe;
+pub mod estimates;
+pub mod energy;
+pub mod events;
+pub mod focus;
+pub mod gamification;
+pub mod google_sync_journal;
+pub mod ics_import;
+pub mod language_learning;
+pub mod life_balance;
+pub mod meditation;
+pub mod missed_blocks;
+pub mod pomodoro;
+pub mod progress;
+pub mod reading;
+pub mod retention;
+pub mod session_types;
+pub mod settings;
+pub mod social;
+pub mod spending;
+pub mod telemetry;
+pub mod trackers;
 pub mod wger;
+pub mod working_hours;
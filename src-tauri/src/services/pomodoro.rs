@@ -0,0 +1,299 @@
+//! Pomodoro Statistics
+//!
+//! A pomodoro is a finished session started by the focus timer: one with a
+//! planned length of at most `MAX_POMODORO_MINUTES` that didn't come from a
+//! plan block or a course meeting. It is completed when it ran its planned
+//! length (within a minute) and abandoned when it was stopped early.
+//!
+//! Interruptions are logged against the running session with a quick reason.
+//! A streak is a run of completed pomodoros on one day with no interruption
+//! and no abandoned pomodoro in between.
+//!
+//! `quality` turns completion and interruptions into a 0-1 signal the agent
+//! uses when rewarding productivity actions.
+
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, Pool, Sqlite};
+
+use crate::error::ApiError;
+
+/// Longest planned session that counts as a pomodoro (the timer's maximum)
+const MAX_POMODORO_MINUTES: i64 = 120;
+/// Minutes short of the plan that still count as completed
+const COMPLETION_SLACK_MINUTES: i64 = 1;
+/// Quality lost per interruption
+const INTERRUPTION_PENALTY: f64 = 0.15;
+/// Quality never drops below this share for interruptions alone
+const MIN_INTERRUPTED_QUALITY: f64 = 0.4;
+
+/// Sessions counted as pomodoros, with whether each was completed and its
+/// interruptions; binds nothing
+fn pomodoro_sessions() -> String {
+    format!(
+        r#"
+        SELECT s.started_at,
+               s.duration_minutes >= s.planned_minutes - {} AS completed,
+               (SELECT COUNT(*) FROM session_interruptions i WHERE i.session_id = s.id) AS interruptions
+        FROM sessions s
+        WHERE s.ended_at IS NOT NULL AND s.planned_minutes IS NOT NULL AND s.planned_minutes <= {}
+          AND s.week_plan_block_id IS NULL AND s.course_meeting_id IS NULL
+        "#,
+        COMPLETION_SLACK_MINUTES, MAX_POMODORO_MINUTES
+    )
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InterruptionReason {
+    Phone,
+    Messages,
+    Person,
+    Noise,
+    /// Own mind wandering off
+    Wandering,
+    Other,
+}
+
+impl InterruptionReason {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            InterruptionReason::Phone => "phone",
+            InterruptionReason::Messages => "messages",
+            InterruptionReason::Person => "person",
+            InterruptionReason::Noise => "noise",
+            InterruptionReason::Wandering => "wandering",
+            InterruptionReason::Other => "other",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct Interruption {
+    pub id: i64,
+    pub session_id: i64,
+    pub reason: String,
+    pub note: Option<String>,
+    pub occurred_at: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReasonCount {
+    pub reason: String,
+    pub count: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PomodoroStats {
+    pub days: i64,
+    pub completed: i64,
+    pub abandoned: i64,
+    /// Completed share of all pomodoros; None without any
+    pub completion_rate: Option<f64>,
+    pub interruptions: i64,
+    pub interruptions_per_pomodoro: Option<f64>,
+    /// Interruptions of any session by reason, most frequent first
+    pub by_reason: Vec<ReasonCount>,
+    /// Longest run of uninterrupted completed pomodoros on one day
+    pub best_streak: i64,
+    /// Local day of the best streak
+    pub best_streak_date: Option<String>,
+    /// Today's run so far
+    pub current_streak: i64,
+}
+
+#[derive(FromRow)]
+struct PomodoroRow {
+    local_date: String,
+    completed: bool,
+    interruptions: i64,
+}
+
+/// 0-1 quality of one pomodoro: 0 when abandoned, otherwise reduced per
+/// interruption
+pub fn quality(completed: bool, interruptions: i64) -> f64 {
+    if !completed {
+        return 0.0;
+    }
+    interruption_factor(interruptions)
+}
+
+/// Share of a session's value left after `interruptions`
+pub fn interruption_factor(interruptions: i64) -> f64 {
+    (1.0 - INTERRUPTION_PENALTY * interruptions.max(0) as f64).max(MIN_INTERRUPTED_QUALITY)
+}
+
+/// Log an interruption of a running session
+pub async fn log_interruption(
+    pool: &Pool<Sqlite>,
+    session_id: i64,
+    reason: InterruptionReason,
+    note: Option<&str>,
+) -> Result<Interruption, ApiError> {
+    let open: Option<bool> = sqlx::query_scalar("SELECT ended_at IS NULL FROM sessions WHERE id = ?")
+        .bind(session_id)
+        .fetch_optional(pool)
+        .await
+        .map_err(ApiError::from)?;
+    match open {
+        None => return Err(ApiError::not_found("Session not found")),
+        Some(false) => return Err(ApiError::validation("Interruptions can only be logged while the session runs")),
+        Some(true) => {}
+    }
+
+    sqlx::query_as(
+        r#"INSERT INTO session_interruptions (session_id, reason, note) VALUES (?, ?, ?)
+           RETURNING id, session_id, reason, note, occurred_at"#,
+    )
+    .bind(session_id)
+    .bind(reason.as_str())
+    .bind(note.map(str::trim).filter(|n| !n.is_empty()))
+    .fetch_one(pool)
+    .await
+    .map_err(ApiError::from)
+}
+
+pub async fn interruption_count(pool: &Pool<Sqlite>, session_id: i64) -> Result<i64, ApiError> {
+    sqlx::query_scalar("SELECT COUNT(*) FROM session_interruptions WHERE session_id = ?")
+        .bind(session_id)
+        .fetch_one(pool)
+        .await
+        .map_err(ApiError::from)
+}
+
+/// Mean quality of the pomodoros started in the `hours` after `from` (a UTC
+/// SQLite timestamp); None when none were
+pub async fn window_quality(pool: &Pool<Sqlite>, from: &str, hours: f64) -> Result<Option<f64>, ApiError> {
+    let rows: Vec<(bool, i64)> = sqlx::query_as(&format!(
+        "SELECT completed, interruptions FROM ({}) WHERE julianday(started_at) BETWEEN julianday(?1) AND julianday(?1) + ?2 / 24.0",
+        pomodoro_sessions()
+    ))
+    .bind(from)
+    .bind(hours)
+    .fetch_all(pool)
+    .await
+    .map_err(ApiError::from)?;
+    if rows.is_empty() {
+        return Ok(None);
+    }
+    let total: f64 = rows.iter().map(|(completed, n)| quality(*completed, *n)).sum();
+    Ok(Some(total / rows.len() as f64))
+}
+
+/// Pomodoro figures over the last `days` days, ending with `today`
+pub async fn stats(pool: &Pool<Sqlite>, days: i64, today: &str) -> Result<PomodoroStats, ApiError> {
+    let rows: Vec<PomodoroRow> = sqlx::query_as(&format!(
+        r#"SELECT date(started_at, 'localtime') AS local_date, completed, interruptions
+           FROM ({})
+           WHERE date(started_at, 'localtime') > date(?1, ?2)
+           ORDER BY started_at"#,
+        pomodoro_sessions()
+    ))
+    .bind(today)
+    .bind(format!("-{} days", days))
+    .fetch_all(pool)
+    .await
+    .map_err(ApiError::from)?;
+
+    let by_reason: Vec<(String, i64)> = sqlx::query_as(
+        r#"SELECT i.reason, COUNT(*) AS n
+           FROM session_interruptions i JOIN sessions s ON s.id = i.session_id
+           WHERE date(s.started_at, 'localtime') > date(?1, ?2)
+           GROUP BY i.reason
+           ORDER BY n DESC, i.reason"#,
+    )
+    .bind(today)
+    .bind(format!("-{} days", days))
+    .fetch_all(pool)
+    .await
+    .map_err(ApiError::from)?;
+
+    let completed = rows.iter().filter(|r| r.completed).count() as i64;
+    let total = rows.len() as i64;
+    let interruptions: i64 = rows.iter().map(|r| r.interruptions).sum();
+
+    let (mut best_streak, mut best_streak_date, mut run, mut run_date) = (0, None, 0, None::<&str>);
+    for row in &rows {
+        if run_date != Some(row.local_date.as_str()) {
+            run = 0;
+            run_date = Some(&row.local_date);
+        }
+        if row.completed && row.interruptions == 0 {
+            run += 1;
+            if run > best_streak {
+                best_streak = run;
+                best_streak_date = Some(row.local_date.clone());
+            }
+        } else {
+            run = 0;
+        }
+    }
+    let current_streak = if run_date == Some(today) { run } else { 0 };
+
+    Ok(PomodoroStats {
+        days,
+        completed,
+        abandoned: total - completed,
+        completion_rate: (total > 0).then(|| completed as f64 / total as f64),
+        interruptions,
+        interruptions_per_pomodoro: (total > 0).then(|| interruptions as f64 / total as f64),
+        by_reason: by_reason
+            .into_iter()
+            .map(|(reason, count)| ReasonCount { reason, count })
+            .collect(),
+        best_streak,
+        best_streak_date,
+        current_streak,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn pomodoro(pool: &Pool<Sqlite>, started_at: &str) -> i64 {
+        sqlx::query_scalar(
+            "INSERT INTO sessions (session_type, started_at, planned_minutes) VALUES ('study', ?, 25) RETURNING id",
+        )
+        .bind(started_at)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+    }
+
+    async fn finish(pool: &Pool<Sqlite>, id: i64, minutes: i64) {
+        sqlx::query("UPDATE sessions SET ended_at = datetime(started_at, ?), duration_minutes = ? WHERE id = ?")
+            .bind(format!("+{} minutes", minutes))
+            .bind(minutes)
+            .bind(id)
+            .execute(pool)
+            .await
+            .unwrap();
+    }
+
+    #[tokio::test]
+    async fn streaks_break_on_interruptions_and_abandoned_pomodoros() {
+        let pool = crate::test_support::pool().await;
+        let mut ids = Vec::new();
+        for (hour, minutes) in [(9, 25), (10, 25), (11, 12), (13, 25), (14, 25), (15, 25)] {
+            let id = pomodoro(&pool, &format!("2030-04-02 {:02}:00:00", hour)).await;
+            ids.push((id, minutes));
+        }
+        // Interrupted while running, before it finishes
+        log_interruption(&pool, ids[3].0, InterruptionReason::Phone, Some("call")).await.unwrap();
+        for (id, minutes) in &ids {
+            finish(&pool, *id, *minutes).await;
+        }
+        assert!(log_interruption(&pool, ids[0].0, InterruptionReason::Noise, None).await.is_err());
+
+        let stats = stats(&pool, 7, "2030-04-02").await.unwrap();
+        assert_eq!((stats.completed, stats.abandoned, stats.interruptions), (5, 1, 1));
+        assert_eq!(stats.by_reason[0].reason, "phone");
+        // 9, 10 | abandoned 11 | interrupted 13 | 14, 15
+        assert_eq!(stats.best_streak, 2);
+        assert_eq!(stats.current_streak, 2);
+
+        let quality = window_quality(&pool, "2030-04-02 12:30:00", 2.0).await.unwrap().unwrap();
+        assert!((quality - (interruption_factor(1) + 1.0) / 2.0).abs() < 1e-9);
+        assert_eq!(self::quality(false, 0), 0.0);
+    }
+}
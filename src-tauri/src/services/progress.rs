@@ -0,0 +1,153 @@
+//! Task Progress
+//!
+//! Long-running commands (exports, imports, backfills) report progress as
+//! `task://progress` events. Every event carries the task id the caller
+//! passed in, so the frontend can match events to the command it invoked;
+//! the last event for a task has `finished` set.
+//!
+//! Reporters publish to a broadcast channel; `start_event_forwarder` relays
+//! it to the frontend. Updates are throttled, so reporting per row is cheap.
+
+use std::time::{Duration, Instant};
+
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use tokio::sync::broadcast;
+
+/// Frontend event carrying a `TaskProgress`
+pub const TASK_PROGRESS_EVENT: &str = "task://progress";
+
+/// Minimum time between intermediate updates for one task
+const MIN_INTERVAL: Duration = Duration::from_millis(200);
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskProgress {
+    pub task_id: String,
+    /// Kind of task, e.g. `export_analytics_csv`
+    pub task: &'static str,
+    pub done: u64,
+    /// Unknown until the task has counted its work
+    pub total: Option<u64>,
+    pub finished: bool,
+    pub error: Option<String>,
+}
+
+static PROGRESS: Lazy<broadcast::Sender<TaskProgress>> = Lazy::new(|| broadcast::channel(64).0);
+
+/// Receive progress for every task started after this call
+pub fn subscribe() -> broadcast::Receiver<TaskProgress> {
+    PROGRESS.subscribe()
+}
+
+/// Forward task progress to the frontend as `task://progress` events
+pub fn start_event_forwarder(app_handle: tauri::AppHandle) {
+    use tauri::Emitter;
+
+    let mut updates = subscribe();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            match updates.recv().await {
+                Ok(progress) => {
+                    if let Err(e) = app_handle.emit(TASK_PROGRESS_EVENT, progress) {
+                        log::warn!("Failed to emit task progress: {}", e);
+                    }
+                }
+                // Progress is superseded by the next update; nothing to catch up on
+                Err(broadcast::error::RecvError::Lagged(_)) => {}
+                Err(broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    });
+}
+
+/// Progress of one running task
+pub struct ProgressReporter {
+    progress: TaskProgress,
+    last_sent: Option<Instant>,
+}
+
+impl ProgressReporter {
+    /// Start reporting; without a task id from the caller, one is generated
+    pub fn new(task: &'static str, task_id: Option<String>) -> Self {
+        let task_id = task_id
+            .filter(|id| !id.trim().is_empty())
+            .unwrap_or_else(|| format!("{}-{}", task, chrono::Utc::now().timestamp_millis()));
+        Self {
+            progress: TaskProgress {
+                task_id,
+                task,
+                done: 0,
+                total: None,
+                finished: false,
+                error: None,
+            },
+            last_sent: None,
+        }
+    }
+
+    pub fn task_id(&self) -> &str {
+        &self.progress.task_id
+    }
+
+    pub fn set_total(&mut self, total: u64) {
+        self.progress.total = Some(total);
+        self.send(true);
+    }
+
+    /// Record `n` more units of work
+    pub fn advance(&mut self, n: u64) {
+        self.progress.done += n;
+        self.send(false);
+    }
+
+    /// Report completion
+    pub fn finish(mut self) {
+        self.progress.finished = true;
+        self.send(true);
+    }
+
+    /// Report failure; the error is passed through for `?`
+    pub fn fail<E: std::fmt::Display>(mut self, error: E) -> E {
+        self.progress.finished = true;
+        self.progress.error = Some(error.to_string());
+        self.send(true);
+        error
+    }
+
+    fn send(&mut self, force: bool) {
+        if !force && self.last_sent.is_some_and(|t| t.elapsed() < MIN_INTERVAL) {
+            return;
+        }
+        self.last_sent = Some(Instant::now());
+        // No receivers just means nobody is listening
+        let _ = PROGRESS.send(self.progress.clone());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn updates_are_throttled_but_the_end_is_always_reported() {
+        let mut updates = subscribe();
+        let mut reporter = ProgressReporter::new("test_task", Some("throttle".into()));
+        reporter.set_total(1000);
+        for _ in 0..1000 {
+            reporter.advance(1);
+        }
+        reporter.finish();
+
+        let mut received = Vec::new();
+        while let Ok(p) = updates.try_recv() {
+            if p.task_id == "throttle" {
+                received.push(p);
+            }
+        }
+        // The total, then nothing until the final event
+        assert_eq!(received.len(), 2);
+        let last = received.last().unwrap();
+        assert!(last.finished);
+        assert_eq!((last.done, last.total), (1000, Some(1000)));
+    }
+}
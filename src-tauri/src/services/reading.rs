@@ -0,0 +1,383 @@
+//! Reading
+//!
+//! Reading is the built-in `reading` tracker, counted in pages. Books are its
+//! entities with the page count as target, so a book is finished by the
+//! session that reaches its last page. A session logs the pages read (or the
+//! page reached) and the minutes taken; the minutes count toward wellness in
+//! the life balance and any day with a page read extends the streak.
+//!
+//! A book's projected finish comes from its pace over the last
+//! `PACE_DAYS` days, or from the overall reading pace when it wasn't read
+//! then. The yearly goal is the `reading_goal_books` setting.
+
+use chrono::{Datelike, Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
+use sqlx::{Pool, Sqlite};
+
+use crate::{
+    error::ApiError,
+    services::{
+        events::{self, ActivityEvent},
+        settings,
+        trackers::{self, Tracker, TrackerEntityInput, TrackerLog, TrackerLogInput},
+    },
+};
+
+/// Slug of the built-in tracker
+pub const TRACKER_SLUG: &str = "reading";
+/// Days the reading pace is measured over
+const PACE_DAYS: i64 = 14;
+const MAX_PAGES: i64 = 20_000;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct Book {
+    pub id: i64,
+    pub title: String,
+    pub pages: i64,
+    pub pages_read: i64,
+    pub minutes: i64,
+    /// First session, UTC
+    pub started_at: Option<String>,
+    pub finished_at: Option<String>,
+    /// Recent pages per day
+    pub pages_per_day: Option<f64>,
+    /// YYYY-MM-DD at the recent pace; None once finished or without a pace
+    pub projected_finish: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct BookInput {
+    pub title: String,
+    pub pages: i64,
+}
+
+/// Pages read in a session, given directly or as the page reached
+#[derive(Debug, Default, Deserialize)]
+pub struct ReadingSessionInput {
+    pub book_id: i64,
+    #[serde(default)]
+    pub pages: Option<i64>,
+    #[serde(default)]
+    pub to_page: Option<i64>,
+    #[serde(default)]
+    pub minutes: Option<i64>,
+    #[serde(default)]
+    pub note: Option<String>,
+    /// UTC SQLite timestamp; defaults to now
+    #[serde(default)]
+    pub logged_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReadingGoal {
+    pub year: i32,
+    /// Books to finish this year; None when no goal is set
+    pub goal: Option<i64>,
+    pub finished: i64,
+    /// Books the goal calls for by today
+    pub expected_by_now: Option<f64>,
+    pub on_track: Option<bool>,
+    /// Books by the end of the year at this year's rate
+    pub projected: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ReadingStats {
+    pub days: i64,
+    pub sessions: i64,
+    pub pages: i64,
+    pub minutes: i64,
+    /// Over sessions with minutes logged
+    pub pages_per_hour: Option<f64>,
+    pub current_streak: i64,
+    pub best_streak: i64,
+    pub goal: ReadingGoal,
+    /// Books in progress
+    pub reading: Vec<Book>,
+}
+
+async fn tracker(pool: &Pool<Sqlite>) -> Result<Tracker, ApiError> {
+    trackers::by_slug(pool, TRACKER_SLUG).await
+}
+
+pub async fn add_book(pool: &Pool<Sqlite>, input: &BookInput, today: NaiveDate) -> Result<Book, ApiError> {
+    if !(1..=MAX_PAGES).contains(&input.pages) {
+        return Err(ApiError::validation(format!("Pages must be between 1 and {}", MAX_PAGES)));
+    }
+    let tracker = tracker(pool).await?;
+    let entity = trackers::add_entity(
+        pool,
+        &TrackerEntityInput {
+            tracker_id: tracker.id,
+            name: input.title.clone(),
+            target_amount: Some(input.pages as f64),
+        },
+    )
+    .await?;
+    book(pool, entity.id, today).await
+}
+
+pub async fn book(pool: &Pool<Sqlite>, id: i64, today: NaiveDate) -> Result<Book, ApiError> {
+    books(pool, true, today)
+        .await?
+        .into_iter()
+        .find(|b| b.id == id)
+        .ok_or_else(|| ApiError::not_found("Book not found"))
+}
+
+/// Books being read first, most recently added first
+pub async fn books(pool: &Pool<Sqlite>, include_finished: bool, today: NaiveDate) -> Result<Vec<Book>, ApiError> {
+    let tracker = tracker(pool).await?;
+    let since = format!("-{} days", PACE_DAYS);
+    let sessions: Vec<(i64, i64, String, f64, i64)> = sqlx::query_as(
+        r#"SELECT entity_id, COALESCE(SUM(minutes), 0), MIN(logged_at),
+                  COALESCE(SUM(CASE WHEN date(logged_at, 'localtime') > date(?2, ?3) THEN amount END), 0.0),
+                  CAST(julianday(?2) - julianday(date(MIN(logged_at), 'localtime')) AS INTEGER) + 1
+           FROM tracker_logs
+           WHERE tracker_id = ?1 AND entity_id IS NOT NULL
+           GROUP BY entity_id"#,
+    )
+    .bind(tracker.id)
+    .bind(today.to_string())
+    .bind(&since)
+    .fetch_all(pool)
+    .await
+    .map_err(ApiError::from)?;
+    let recent_pages: f64 = sqlx::query_scalar(
+        "SELECT COALESCE(SUM(amount), 0.0) FROM tracker_logs WHERE tracker_id = ? AND date(logged_at, 'localtime') > date(?, ?)",
+    )
+    .bind(tracker.id)
+    .bind(today.to_string())
+    .bind(&since)
+    .fetch_one(pool)
+    .await
+    .map_err(ApiError::from)?;
+    let overall_pace = (recent_pages > 0.0).then(|| recent_pages / PACE_DAYS as f64);
+
+    let entities = trackers::entities(pool, tracker.id, include_finished).await?;
+    Ok(entities
+        .into_iter()
+        .map(|entity| {
+            let pages = entity.target_amount.unwrap_or(0.0).round() as i64;
+            let pages_read = entity.logged_amount.round() as i64;
+            let logged = sessions.iter().find(|s| s.0 == entity.id);
+            // A book started within the window is paced over the days since
+            let pace = logged
+                .filter(|(_, _, _, recent, _)| *recent > 0.0)
+                .map(|(_, _, _, recent, days)| recent / (*days).clamp(1, PACE_DAYS) as f64)
+                .or(overall_pace);
+            let projected_finish = match (entity.completed_at.is_some(), pace) {
+                (false, Some(pace)) => {
+                    let days = ((pages - pages_read).max(0) as f64 / pace).ceil() as i64;
+                    Some((today + Duration::days(days)).to_string())
+                }
+                _ => None,
+            };
+            Book {
+                id: entity.id,
+                title: entity.name,
+                pages,
+                pages_read: pages_read.min(pages),
+                minutes: logged.map_or(0, |s| s.1),
+                started_at: logged.map(|s| s.2.clone()),
+                finished_at: entity.completed_at,
+                pages_per_day: pace.map(|p| (p * 10.0).round() / 10.0),
+                projected_finish,
+            }
+        })
+        .collect())
+}
+
+/// Log a session; finishing the last page finishes the book
+pub async fn log_session(pool: &Pool<Sqlite>, input: &ReadingSessionInput) -> Result<TrackerLog, ApiError> {
+    let tracker = tracker(pool).await?;
+    let book = trackers::entity(pool, input.book_id).await?;
+    if book.tracker_id != tracker.id {
+        return Err(ApiError::validation("Not a book"));
+    }
+    if book.completed_at.is_some() {
+        return Err(ApiError::validation("This book is already finished"));
+    }
+    let pages = match (input.pages, input.to_page) {
+        (Some(pages), None) => pages,
+        (None, Some(to_page)) => to_page - book.logged_amount.round() as i64,
+        _ => return Err(ApiError::validation("Give either the pages read or the page reached")),
+    };
+    if !(1..=MAX_PAGES).contains(&pages) {
+        return Err(ApiError::validation("A session must move the book forward by at least a page"));
+    }
+
+    let log = trackers::record(
+        pool,
+        &TrackerLogInput {
+            tracker_id: tracker.id,
+            entity_id: Some(book.id),
+            amount: pages as f64,
+            minutes: input.minutes,
+            note: input.note.clone(),
+            logged_at: input.logged_at.clone(),
+            ..Default::default()
+        },
+    )
+    .await?;
+    events::publish(ActivityEvent::ReadingLogged { log_id: log.id });
+    Ok(log)
+}
+
+/// Books finished in total
+pub async fn finished_count(pool: &Pool<Sqlite>) -> Result<i64, ApiError> {
+    sqlx::query_scalar(
+        r#"SELECT COUNT(*) FROM tracker_entities
+           WHERE completed_at IS NOT NULL AND tracker_id = (SELECT id FROM trackers WHERE slug = ?)"#,
+    )
+    .bind(TRACKER_SLUG)
+    .fetch_one(pool)
+    .await
+    .map_err(ApiError::from)
+}
+
+/// Progress toward this year's goal as of `today`
+pub async fn goal(pool: &Pool<Sqlite>, today: NaiveDate) -> Result<ReadingGoal, ApiError> {
+    let goal = Some(settings::get_i64(pool, "reading_goal_books").await?).filter(|g| *g > 0);
+    let finished: i64 = sqlx::query_scalar(
+        r#"SELECT COUNT(*) FROM tracker_entities
+           WHERE tracker_id = (SELECT id FROM trackers WHERE slug = ?1)
+             AND strftime('%Y', completed_at, 'localtime') = ?2"#,
+    )
+    .bind(TRACKER_SLUG)
+    .bind(today.year().to_string())
+    .fetch_one(pool)
+    .await
+    .map_err(ApiError::from)?;
+
+    let year_days = if NaiveDate::from_ymd_opt(today.year(), 2, 29).is_some() { 366.0 } else { 365.0 };
+    let elapsed = today.ordinal() as f64 / year_days;
+    let expected_by_now = goal.map(|g| (g as f64 * elapsed * 10.0).round() / 10.0);
+    Ok(ReadingGoal {
+        year: today.year(),
+        goal,
+        finished,
+        expected_by_now,
+        on_track: expected_by_now.map(|expected| finished as f64 >= expected.floor()),
+        projected: (finished as f64 / elapsed).floor() as i64,
+    })
+}
+
+/// Sessions over the last `days` days ending with `today`, with streaks, the
+/// goal and the books in progress
+pub async fn stats(pool: &Pool<Sqlite>, days: i64, today: NaiveDate) -> Result<ReadingStats, ApiError> {
+    let tracker = tracker(pool).await?;
+    let (sessions, pages, minutes, timed_pages): (i64, i64, i64, i64) = sqlx::query_as(
+        r#"SELECT COUNT(*), CAST(COALESCE(SUM(amount), 0) AS INTEGER), COALESCE(SUM(minutes), 0),
+                  CAST(COALESCE(SUM(CASE WHEN minutes > 0 THEN amount END), 0) AS INTEGER)
+           FROM tracker_logs
+           WHERE tracker_id = ?1 AND date(logged_at, 'localtime') > date(?2, ?3)"#,
+    )
+    .bind(tracker.id)
+    .bind(today.to_string())
+    .bind(format!("-{} days", days))
+    .fetch_one(pool)
+    .await
+    .map_err(ApiError::from)?;
+
+    let summary = trackers::summary(pool, tracker, today).await?;
+    Ok(ReadingStats {
+        days,
+        sessions,
+        pages,
+        minutes,
+        pages_per_hour: (minutes > 0).then(|| (timed_pages as f64 * 60.0 / minutes as f64 * 10.0).round() / 10.0),
+        current_streak: summary.current_streak,
+        best_streak: summary.best_streak,
+        goal: goal(pool, today).await?,
+        reading: books(pool, false, today).await?,
+    })
+}
+
+/// A nudge for the end of the day when nothing was read yet and a book is in
+/// progress: keep the streak, catch up on the goal, or finish on time
+pub async fn evening_suggestion(pool: &Pool<Sqlite>, today: NaiveDate) -> Result<Option<String>, ApiError> {
+    let stats = stats(pool, 1, today).await?;
+    if stats.sessions > 0 {
+        return Ok(None);
+    }
+    let Some(book) = stats.reading.first() else {
+        return Ok(None);
+    };
+    let pages = book.pages_per_day.map_or(10, |p| p.ceil().max(1.0) as i64);
+    let suggestion = if stats.current_streak > 0 {
+        format!(
+            "Read {} pages of \"{}\" to keep your {}-day reading streak",
+            pages, book.title, stats.current_streak
+        )
+    } else if let (Some(false), Some(goal)) = (stats.goal.on_track, stats.goal.goal) {
+        format!(
+            "Read {} pages of \"{}\"; {} of {} books so far puts you behind this year's goal",
+            pages, book.title, stats.goal.finished, goal
+        )
+    } else {
+        format!("Read {} pages of \"{}\" before bed", pages, book.title)
+    };
+    Ok(Some(suggestion))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[tokio::test]
+    async fn sessions_project_the_finish_and_count_toward_the_goal() {
+        let pool = crate::test_support::pool().await;
+        settings::set(&pool, "reading_goal_books", json!(24)).await.unwrap();
+        let today = NaiveDate::from_ymd_opt(2030, 3, 10).unwrap();
+        let novel = add_book(&pool, &BookInput { title: "Middlemarch".to_string(), pages: 300 }, today).await.unwrap();
+
+        for (day, pages) in [("2030-03-06", 20), ("2030-03-07", 30), ("2030-03-08", 25), ("2030-03-09", 25)] {
+            log_session(
+                &pool,
+                &ReadingSessionInput {
+                    book_id: novel.id,
+                    pages: Some(pages),
+                    minutes: Some(pages * 2),
+                    logged_at: Some(format!("{} 20:00:00", day)),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        }
+
+        // 100 pages over the 5 days since starting: 20 a day, 200 to go
+        let novel = book(&pool, novel.id, today).await.unwrap();
+        assert_eq!((novel.pages_read, novel.minutes, novel.pages_per_day), (100, 200, Some(20.0)));
+        assert_eq!(novel.projected_finish.as_deref(), Some("2030-03-20"));
+
+        let suggestion = evening_suggestion(&pool, today).await.unwrap().unwrap();
+        assert!(suggestion.contains("4-day reading streak"), "{}", suggestion);
+
+        assert!(log_session(&pool, &ReadingSessionInput { book_id: novel.id, to_page: Some(100), ..Default::default() })
+            .await
+            .is_err());
+        log_session(
+            &pool,
+            &ReadingSessionInput {
+                book_id: novel.id,
+                to_page: Some(300),
+                logged_at: Some("2030-03-10 21:00:00".to_string()),
+                ..Default::default()
+            },
+        )
+        .await
+        .unwrap();
+
+        let stats = stats(&pool, 7, today).await.unwrap();
+        assert_eq!((stats.sessions, stats.pages, stats.current_streak), (5, 300, 5));
+        assert_eq!(stats.pages_per_hour, Some(30.0));
+        assert!(stats.reading.is_empty());
+        // 69 days into the year a 24-book goal expects 4.5 books
+        assert_eq!((stats.goal.finished, stats.goal.expected_by_now, stats.goal.on_track), (1, Some(4.5), Some(false)));
+        assert_eq!(finished_count(&pool).await.unwrap(), 1);
+        assert_eq!(evening_suggestion(&pool, today).await.unwrap(), None);
+    }
+}
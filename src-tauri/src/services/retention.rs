@@ -0,0 +1,214 @@
+//! Data Retention
+//!
+//! Run by daily maintenance; each rule has a month setting where 0 keeps
+//! everything:
+//! - `retention_sessions_months`: finished sessions from before the cutoff
+//!   day are folded into `session_daily_aggregates` (one row per local day,
+//!   type and reference) and deleted, along with their focus samples and
+//!   technique tags
+//! - `retention_agent_context_months`: rich context and feature snapshots
+//!   captured before the cutoff are deleted; recommendations keep their other
+//!   fields but lose the context link
+//!
+//! Reclaimed space is the drop in pages in use. SQLite reuses freed pages for
+//! new rows rather than shrinking the file.
+
+use chrono::{Months, Utc};
+use serde::Serialize;
+use sqlx::{Pool, Sqlite};
+
+use crate::{
+    error::ApiError,
+    services::{clock, settings},
+};
+
+#[derive(Debug, Default, Serialize)]
+pub struct RetentionReport {
+    pub sessions_compressed: i64,
+    /// Daily aggregate rows written or added to
+    pub daily_rows: i64,
+    pub contexts_purged: i64,
+    pub bytes_reclaimed: i64,
+}
+
+/// Apply every retention rule
+pub async fn run(pool: &Pool<Sqlite>) -> Result<RetentionReport, ApiError> {
+    let before = used_bytes(pool).await?;
+    let mut report = RetentionReport::default();
+
+    let session_months = settings::get_i64(pool, "retention_sessions_months").await?;
+    if session_months > 0 {
+        let cutoff = clock::now().date_naive() - Months::new(session_months as u32);
+        (report.sessions_compressed, report.daily_rows) = compress_sessions(pool, &cutoff.to_string()).await?;
+    }
+
+    let context_months = settings::get_i64(pool, "retention_agent_context_months").await?;
+    if context_months > 0 {
+        let cutoff = (clock::now() - Months::new(context_months as u32))
+            .with_timezone(&Utc)
+            .format("%Y-%m-%d %H:%M:%S")
+            .to_string();
+        report.contexts_purged = purge_contexts(pool, &cutoff).await?;
+    }
+
+    report.bytes_reclaimed = (before - used_bytes(pool).await?).max(0);
+    Ok(report)
+}
+
+/// Fold finished sessions started before local day `cutoff` into daily
+/// aggregates; returns (sessions removed, aggregate rows touched)
+async fn compress_sessions(pool: &Pool<Sqlite>, cutoff: &str) -> Result<(i64, i64), ApiError> {
+    let mut tx = pool.begin().await.map_err(ApiError::from)?;
+
+    let daily_rows = sqlx::query(
+        r#"
+        INSERT INTO session_daily_aggregates
+            (date, session_type, reference_type, reference_id, session_count, total_minutes, rated_sessions, focus_rating_sum)
+        SELECT date(started_at, 'localtime'), session_type, COALESCE(reference_type, ''), COALESCE(reference_id, 0),
+               COUNT(*), COALESCE(SUM(duration_minutes), 0), COUNT(focus_rating), COALESCE(SUM(focus_rating), 0)
+        FROM sessions
+        WHERE ended_at IS NOT NULL AND date(started_at, 'localtime') < ?
+        GROUP BY 1, 2, 3, 4
+        ON CONFLICT (date, session_type, reference_type, reference_id) DO UPDATE SET
+            session_count = session_count + excluded.session_count,
+            total_minutes = total_minutes + excluded.total_minutes,
+            rated_sessions = rated_sessions + excluded.rated_sessions,
+            focus_rating_sum = focus_rating_sum + excluded.focus_rating_sum
+        "#,
+    )
+    .bind(cutoff)
+    .execute(&mut *tx)
+    .await
+    .map_err(ApiError::from)?
+    .rows_affected();
+
+    // Children are cleared explicitly rather than relying on foreign key enforcement
+    let old = "SELECT id FROM sessions WHERE ended_at IS NOT NULL AND date(started_at, 'localtime') < ?";
+    for child in [
+        format!("DELETE FROM focus_samples WHERE session_id IN ({})", old),
+        format!("DELETE FROM session_techniques WHERE session_id IN ({})", old),
+        format!("UPDATE lecture_notes SET session_id = NULL WHERE session_id IN ({})", old),
+    ] {
+        sqlx::query(&child).bind(cutoff).execute(&mut *tx).await.map_err(ApiError::from)?;
+    }
+    let sessions = sqlx::query("DELETE FROM sessions WHERE ended_at IS NOT NULL AND date(started_at, 'localtime') < ?")
+        .bind(cutoff)
+        .execute(&mut *tx)
+        .await
+        .map_err(ApiError::from)?
+        .rows_affected();
+
+    tx.commit().await.map_err(ApiError::from)?;
+    Ok((sessions as i64, daily_rows as i64))
+}
+
+/// Delete context snapshots captured before `cutoff` (UTC)
+async fn purge_contexts(pool: &Pool<Sqlite>, cutoff: &str) -> Result<i64, ApiError> {
+    let mut tx = pool.begin().await.map_err(ApiError::from)?;
+
+    sqlx::query(
+        "UPDATE agent_recommendations SET context_id = NULL WHERE context_id IN (SELECT id FROM agent_rich_context WHERE captured_at < ?)",
+    )
+    .bind(cutoff)
+    .execute(&mut *tx)
+    .await
+    .map_err(ApiError::from)?;
+
+    let mut purged = 0;
+    for table in ["agent_rich_context", "agent_feature_snapshots"] {
+        purged += sqlx::query(&format!("DELETE FROM {} WHERE captured_at < ?", table))
+            .bind(cutoff)
+            .execute(&mut *tx)
+            .await
+            .map_err(ApiError::from)?
+            .rows_affected();
+    }
+
+    tx.commit().await.map_err(ApiError::from)?;
+    Ok(purged as i64)
+}
+
+/// Bytes of database pages holding data
+async fn used_bytes(pool: &Pool<Sqlite>) -> Result<i64, ApiError> {
+    let pragma = |name: &'static str| async move {
+        sqlx::query_scalar::<_, i64>(name).fetch_one(pool).await.map_err(ApiError::from)
+    };
+    let page_size = pragma("PRAGMA page_size").await?;
+    let pages = pragma("PRAGMA page_count").await?;
+    let free = pragma("PRAGMA freelist_count").await?;
+    Ok((pages - free) * page_size)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Local, TimeZone};
+    use serde_json::json;
+
+    async fn session(pool: &Pool<Sqlite>, started_at: &str, minutes: i64, focus: Option<i64>) -> i64 {
+        sqlx::query_scalar(
+            "INSERT INTO sessions (session_type, reference_type, reference_id, started_at, ended_at, duration_minutes, focus_rating) VALUES ('study', 'course', 1, ?1, ?1, ?2, ?3) RETURNING id",
+        )
+        .bind(started_at)
+        .bind(minutes)
+        .bind(focus)
+        .fetch_one(pool)
+        .await
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn old_sessions_become_daily_totals_and_old_contexts_are_purged() {
+        let pool = crate::test_support::pool().await;
+        let _clock = clock::freeze(Local.with_ymd_and_hms(2030, 6, 15, 3, 0, 0).unwrap());
+
+        let old = session(&pool, "2027-03-10 11:00:00", 50, Some(4)).await;
+        session(&pool, "2027-03-10 12:00:00", 30, None).await;
+        session(&pool, "2027-03-11 12:00:00", 20, Some(2)).await;
+        let recent = session(&pool, "2029-01-10 12:00:00", 45, None).await;
+        sqlx::query("INSERT INTO focus_samples (session_id, app_name, seconds) VALUES (?, 'Editor', 60)")
+            .bind(old)
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        for captured_at in ["2029-11-01 12:00:00", "2030-05-01 12:00:00"] {
+            sqlx::query("INSERT INTO agent_rich_context (captured_at) VALUES (?)")
+                .bind(captured_at)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+        sqlx::query("INSERT INTO agent_recommendations (action_recommended, context_id) VALUES ('deep_work_block', 1)")
+            .execute(&pool)
+            .await
+            .unwrap();
+
+        let report = run(&pool).await.unwrap();
+        assert_eq!(report.sessions_compressed, 3);
+        assert_eq!(report.daily_rows, 2);
+        assert_eq!(report.contexts_purged, 1);
+
+        let left: Vec<i64> = sqlx::query_scalar("SELECT id FROM sessions").fetch_all(&pool).await.unwrap();
+        assert_eq!(left, vec![recent]);
+        let samples: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM focus_samples").fetch_one(&pool).await.unwrap();
+        assert_eq!(samples, 0);
+        let totals: Vec<(i64, i64, i64, i64)> = sqlx::query_as(
+            "SELECT session_count, total_minutes, rated_sessions, focus_rating_sum FROM session_daily_aggregates ORDER BY date",
+        )
+        .fetch_all(&pool)
+        .await
+        .unwrap();
+        assert_eq!(totals, vec![(2, 80, 1, 4), (1, 20, 1, 2)]);
+        let context: Option<i64> = sqlx::query_scalar("SELECT context_id FROM agent_recommendations")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        assert_eq!(context, None);
+
+        // A second run finds nothing; 0 months switches a rule off
+        settings::set(&pool, "retention_agent_context_months", json!(0)).await.unwrap();
+        let again = run(&pool).await.unwrap();
+        assert_eq!((again.sessions_compressed, again.contexts_purged), (0, 0));
+    }
+}
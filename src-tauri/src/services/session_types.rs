@@ -0,0 +1,259 @@
+//! Session Types
+//!
+//! The registry behind `sessions.session_type`. 'study' and 'practice' are
+//! built in; custom types such as "research" or "job_applications" are
+//! tracked and reported the same way without touching the schema.
+//!
+//! Each type belongs to a life-balance domain and may count toward the study
+//! or practice targets. Target queries (study hours, practice hours, XP,
+//! challenges, the weekly report) select sessions by
+//! `session_type IN (SELECT name FROM session_types WHERE counts_toward = ...)`
+//! rather than by the built-in name.
+
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, Pool, Sqlite};
+
+use crate::{
+    error::ApiError,
+    models::session::{is_slug, SessionType},
+    services::life_balance,
+};
+
+const MAX_LABEL_LENGTH: usize = 60;
+
+/// Targets a session type can count toward
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionTarget {
+    Study,
+    Practice,
+}
+
+impl SessionTarget {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            SessionTarget::Study => "study",
+            SessionTarget::Practice => "practice",
+        }
+    }
+
+    fn parse(value: &str) -> Option<SessionTarget> {
+        [SessionTarget::Study, SessionTarget::Practice]
+            .into_iter()
+            .find(|t| t.as_str() == value)
+    }
+}
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct SessionTypeDef {
+    pub name: String,
+    pub label: String,
+    pub domain: String,
+    pub counts_toward: Option<String>,
+    pub builtin: bool,
+    pub created_at: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SessionTypeInput {
+    /// Lowercase slug, e.g. "thesis_writing"
+    pub name: String,
+    pub label: String,
+    /// A life-balance domain
+    pub domain: String,
+    #[serde(default)]
+    pub counts_toward: Option<SessionTarget>,
+}
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct SessionTypeStats {
+    pub name: String,
+    pub label: String,
+    pub domain: String,
+    pub counts_toward: Option<String>,
+    pub sessions: i64,
+    pub hours: f64,
+    /// Mean focus rating of rated sessions
+    pub avg_focus: Option<f64>,
+}
+
+const SELECT_TYPE: &str = "SELECT name, label, domain, counts_toward, builtin, created_at FROM session_types";
+
+pub async fn list(pool: &Pool<Sqlite>) -> Result<Vec<SessionTypeDef>, ApiError> {
+    sqlx::query_as(&format!("{} ORDER BY builtin DESC, label", SELECT_TYPE))
+        .fetch_all(pool)
+        .await
+        .map_err(ApiError::from)
+}
+
+pub async fn create(pool: &Pool<Sqlite>, input: &SessionTypeInput) -> Result<SessionTypeDef, ApiError> {
+    let name = input.name.trim();
+    if !is_slug(name) {
+        return Err(ApiError::validation(
+            "Name must be up to 40 lowercase letters, digits or underscores, starting with a letter",
+        ));
+    }
+    let label = input.label.trim();
+    if label.is_empty() || label.len() > MAX_LABEL_LENGTH {
+        return Err(ApiError::validation(format!(
+            "Label must be between 1 and {} characters",
+            MAX_LABEL_LENGTH
+        )));
+    }
+    if !life_balance::DOMAINS.iter().any(|(domain, _)| *domain == input.domain) {
+        return Err(ApiError::validation(format!("Unknown domain '{}'", input.domain)));
+    }
+
+    sqlx::query_as(
+        r#"INSERT INTO session_types (name, label, domain, counts_toward) VALUES (?, ?, ?, ?)
+           RETURNING name, label, domain, counts_toward, builtin, created_at"#,
+    )
+    .bind(name)
+    .bind(label)
+    .bind(&input.domain)
+    .bind(input.counts_toward.map(SessionTarget::as_str))
+    .fetch_one(pool)
+    .await
+    .map_err(|e| ApiError::from_sqlx(e, "Failed to create session type"))
+}
+
+/// Delete a custom type no session uses
+pub async fn delete(pool: &Pool<Sqlite>, name: &str) -> Result<bool, ApiError> {
+    let builtin: Option<bool> = sqlx::query_scalar("SELECT builtin FROM session_types WHERE name = ?")
+        .bind(name)
+        .fetch_optional(pool)
+        .await
+        .map_err(ApiError::from)?;
+    match builtin {
+        None => return Ok(false),
+        Some(true) => return Err(ApiError::validation("Built-in session types can't be deleted")),
+        Some(false) => {}
+    }
+
+    let used: bool = sqlx::query_scalar(
+        r#"SELECT EXISTS (SELECT 1 FROM sessions WHERE session_type = ?1)
+               OR EXISTS (SELECT 1 FROM session_daily_aggregates WHERE session_type = ?1)"#,
+    )
+    .bind(name)
+    .fetch_one(pool)
+    .await
+    .map_err(ApiError::from)?;
+    if used {
+        return Err(ApiError::conflict("Sessions of this type exist; delete them first"));
+    }
+
+    let result = sqlx::query("DELETE FROM session_types WHERE name = ?")
+        .bind(name)
+        .execute(pool)
+        .await
+        .map_err(ApiError::from)?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Fail unless the type is registered
+pub async fn ensure_registered(pool: &Pool<Sqlite>, session_type: &SessionType) -> Result<(), ApiError> {
+    let exists: bool = sqlx::query_scalar("SELECT EXISTS (SELECT 1 FROM session_types WHERE name = ?)")
+        .bind(session_type)
+        .fetch_one(pool)
+        .await
+        .map_err(ApiError::from)?;
+    if exists {
+        Ok(())
+    } else {
+        Err(ApiError::validation(format!("Unknown session type '{}'", session_type.as_str())))
+    }
+}
+
+/// The target a type's sessions count toward, if any
+pub async fn counts_toward(pool: &Pool<Sqlite>, session_type: &SessionType) -> Result<Option<SessionTarget>, ApiError> {
+    let target: Option<Option<String>> = sqlx::query_scalar("SELECT counts_toward FROM session_types WHERE name = ?")
+        .bind(session_type)
+        .fetch_optional(pool)
+        .await
+        .map_err(ApiError::from)?;
+    Ok(target.flatten().as_deref().and_then(SessionTarget::parse))
+}
+
+/// Sessions, hours and focus per type over the last `days` days ending with
+/// `today`, including compressed history; every type is listed
+pub async fn stats(pool: &Pool<Sqlite>, days: i64, today: &str) -> Result<Vec<SessionTypeStats>, ApiError> {
+    sqlx::query_as(
+        r#"
+        WITH logged AS (
+            SELECT session_type, 1 AS sessions, duration_minutes AS minutes,
+                   (focus_rating IS NOT NULL) AS rated, COALESCE(focus_rating, 0) AS focus
+            FROM sessions
+            WHERE ended_at IS NOT NULL AND date(started_at, 'localtime') > date(?1, ?2)
+            UNION ALL
+            SELECT session_type, session_count, total_minutes, rated_sessions, focus_rating_sum
+            FROM session_daily_aggregates
+            WHERE date > date(?1, ?2)
+        )
+        SELECT t.name, t.label, t.domain, t.counts_toward,
+               COALESCE(SUM(l.sessions), 0) AS sessions,
+               ROUND(COALESCE(SUM(l.minutes), 0) / 60.0, 1) AS hours,
+               ROUND(SUM(l.focus) * 1.0 / NULLIF(SUM(l.rated), 0), 2) AS avg_focus
+        FROM session_types t
+        LEFT JOIN logged l ON l.session_type = t.name
+        GROUP BY t.name
+        ORDER BY hours DESC, t.label
+        "#,
+    )
+    .bind(today)
+    .bind(format!("-{} days", days))
+    .fetch_all(pool)
+    .await
+    .map_err(ApiError::from)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn custom_types_count_toward_their_target_and_report_alongside_builtins() {
+        let pool = crate::test_support::pool().await;
+        let research = create(
+            &pool,
+            &SessionTypeInput {
+                name: "research".to_string(),
+                label: "Research".to_string(),
+                domain: "academics".to_string(),
+                counts_toward: Some(SessionTarget::Study),
+            },
+        )
+        .await
+        .unwrap();
+        assert!(!research.builtin);
+
+        let custom = SessionType::Custom("research".to_string());
+        ensure_registered(&pool, &custom).await.unwrap();
+        assert!(ensure_registered(&pool, &SessionType::Custom("unknown".to_string())).await.is_err());
+        assert_eq!(counts_toward(&pool, &custom).await.unwrap(), Some(SessionTarget::Study));
+
+        sqlx::query(
+            r#"INSERT INTO sessions (session_type, started_at, ended_at, duration_minutes, focus_rating) VALUES
+               ('research', '2030-05-02 09:00:00', '2030-05-02 10:30:00', 90, 4),
+               ('study', '2030-05-02 11:00:00', '2030-05-02 11:30:00', 30, NULL)"#,
+        )
+        .execute(&pool)
+        .await
+        .unwrap();
+
+        let study_hours: f64 = sqlx::query_scalar(
+            "SELECT SUM(duration_minutes) / 60.0 FROM sessions WHERE session_type IN (SELECT name FROM session_types WHERE counts_toward = 'study')",
+        )
+        .fetch_one(&pool)
+        .await
+        .unwrap();
+        assert_eq!(study_hours, 2.0);
+
+        let stats = stats(&pool, 7, "2030-05-03").await.unwrap();
+        let rows: Vec<(&str, i64, f64)> = stats.iter().map(|s| (s.name.as_str(), s.sessions, s.hours)).collect();
+        assert_eq!(rows, vec![("research", 1, 1.5), ("study", 1, 0.5), ("practice", 0, 0.0)]);
+        assert_eq!(stats[0].avg_focus, Some(4.0));
+
+        assert!(delete(&pool, "study").await.is_err());
+        assert!(delete(&pool, "research").await.is_err());
+    }
+}
@@ -0,0 +1,730 @@
+//! Settings Service
+//!
+//! Typed key-value settings stored as JSON in the `settings` table. Every key
+//! is declared in `SETTINGS` with its type, bounds and default, so adding a
+//! setting doesn't need a schema change. Unset keys read as their default.
+//!
+//! Changes are published on a broadcast channel (`subscribe`); the app
+//! forwards them to the frontend as `settings-changed` events.
+
+use once_cell::sync::Lazy;
+use serde::Serialize;
+use serde_json::Value;
+use sqlx::{Pool, Sqlite};
+use tokio::sync::broadcast;
+
+use crate::agent::preferences::MUTABLE_CATEGORIES;
+use crate::error::ApiError;
+use crate::mcp::MCP_SCOPES;
+use crate::services::working_hours;
+
+/// Frontend event carrying a `SettingChange`
+pub const SETTINGS_CHANGED_EVENT: &str = "settings-changed";
+
+const MAX_STRING_LENGTH: usize = 512;
+const MAX_TIMEZONE_LENGTH: usize = 64;
+
+/// Value type and constraints of a setting
+#[derive(Debug, Clone, Copy, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum SettingKind {
+    Bool,
+    Int { min: i64, max: i64 },
+    /// One of a fixed set of strings
+    Enum { values: &'static [&'static str] },
+    /// Free text; null allowed
+    OptionalString,
+    /// IANA timezone name; null means the system timezone
+    Timezone,
+    /// List of strings from a fixed set
+    StringList { values: &'static [&'static str] },
+    /// Quick action -> accelerator map, see `quick_actions`
+    Shortcuts,
+    /// List of free-text entries
+    TextList { max_items: usize },
+    /// Timer profiles, see `commands::focus_profiles`
+    FocusProfiles,
+    /// Weekday -> time range or null, see `working_hours`
+    WorkingHours,
+    /// Do-not-schedule windows, see `working_hours`
+    BlackoutWindows,
+    /// Credit hours -> weekly target and meetings, see `commands::course_workload`
+    WorkloadTemplates,
+}
+
+/// Declaration of one setting
+#[derive(Debug, Clone, Copy, Serialize)]
+pub struct SettingDef {
+    pub key: &'static str,
+    #[serde(flatten)]
+    pub kind: SettingKind,
+    /// Default as JSON text
+    #[serde(serialize_with = "serialize_json_text")]
+    pub default: &'static str,
+    pub description: &'static str,
+}
+
+fn serialize_json_text<S: serde::Serializer>(json: &&'static str, serializer: S) -> Result<S::Ok, S::Error> {
+    serde_json::from_str::<Value>(json)
+        .unwrap_or(Value::Null)
+        .serialize(serializer)
+}
+
+/// All known settings
+pub const SETTINGS: &[SettingDef] = &[
+    SettingDef {
+        key: "weekly_workout_target",
+        kind: SettingKind::Int { min: 0, max: 14 },
+        default: "3",
+        description: "Workouts per week",
+    },
+    SettingDef {
+        key: "weekly_active_skills_target",
+        kind: SettingKind::Int { min: 0, max: 50 },
+        default: "5",
+        description: "Skills practiced per week",
+    },
+    SettingDef {
+        key: "reading_goal_books",
+        kind: SettingKind::Int { min: 0, max: 365 },
+        default: "0",
+        description: "Books to finish per year; 0 turns the reading goal off",
+    },
+    SettingDef {
+        key: "spending_daily_budget",
+        kind: SettingKind::Int { min: 0, max: 100_000 },
+        default: "0",
+        description: "Daily discretionary budget; spending check-ins at or below it count as within budget unless answered otherwise",
+    },
+    SettingDef {
+        key: "cycle_tracking_enabled",
+        kind: SettingKind::Bool,
+        default: "false",
+        description: "Log menstrual cycles and use the predicted phase in recommendations and workout planning",
+    },
+    SettingDef {
+        key: "cycle_include_in_exports",
+        kind: SettingKind::Bool,
+        default: "false",
+        description: "Allow the cycle log in CSV exports",
+    },
+    SettingDef {
+        key: "week_start_day",
+        kind: SettingKind::Enum { values: &["monday", "sunday"] },
+        default: "\"monday\"",
+        description: "First day of the week",
+    },
+    SettingDef {
+        key: "sleep_hours",
+        kind: SettingKind::Int { min: 4, max: 12 },
+        default: "8",
+        description: "Hours of sleep per night, used to work out waking hours",
+    },
+    SettingDef {
+        key: "capacity_limit_percent",
+        kind: SettingKind::Int { min: 50, max: 100 },
+        default: "75",
+        description: "Share of waking hours that can be committed; the rest is meals, commuting and rest",
+    },
+    SettingDef {
+        key: "checkin_reminder_hour",
+        kind: SettingKind::Int { min: 0, max: 23 },
+        default: "8",
+        description: "Hour of the morning check-in reminder scheduled by the daily shutdown",
+    },
+    SettingDef {
+        key: "course_workload_templates",
+        kind: SettingKind::WorkloadTemplates,
+        default: r#"[{"credit_hours":1,"weekly_hours":2.0,"meetings":[{"day_of_week":3,"start_time":"10:00","end_time":"10:50","meeting_type":"lecture"}]},{"credit_hours":2,"weekly_hours":4.0,"meetings":[{"day_of_week":2,"start_time":"10:00","end_time":"10:50","meeting_type":"lecture"},{"day_of_week":4,"start_time":"10:00","end_time":"10:50","meeting_type":"lecture"}]},{"credit_hours":3,"weekly_hours":6.0,"meetings":[{"day_of_week":1,"start_time":"10:00","end_time":"10:50","meeting_type":"lecture"},{"day_of_week":3,"start_time":"10:00","end_time":"10:50","meeting_type":"lecture"},{"day_of_week":5,"start_time":"10:00","end_time":"10:50","meeting_type":"lecture"}]},{"credit_hours":4,"weekly_hours":8.0,"meetings":[{"day_of_week":1,"start_time":"10:00","end_time":"10:50","meeting_type":"lecture"},{"day_of_week":2,"start_time":"10:00","end_time":"10:50","meeting_type":"lecture"},{"day_of_week":3,"start_time":"10:00","end_time":"10:50","meeting_type":"lecture"},{"day_of_week":4,"start_time":"10:00","end_time":"10:50","meeting_type":"lecture"}]},{"credit_hours":5,"weekly_hours":10.0,"meetings":[{"day_of_week":1,"start_time":"10:00","end_time":"10:50","meeting_type":"lecture"},{"day_of_week":2,"start_time":"10:00","end_time":"10:50","meeting_type":"lecture"},{"day_of_week":3,"start_time":"10:00","end_time":"10:50","meeting_type":"lecture"},{"day_of_week":4,"start_time":"10:00","end_time":"10:50","meeting_type":"lecture"},{"day_of_week":5,"start_time":"10:00","end_time":"10:50","meeting_type":"lecture"}]}]"#,
+        description: "Weekly study hours and default meeting pattern per credit hour count, applied to new courses",
+    },
+    SettingDef {
+        key: "course_digest_day",
+        kind: SettingKind::Enum {
+            values: &["off", "sunday", "monday", "tuesday", "wednesday", "thursday", "friday", "saturday"],
+        },
+        default: "\"sunday\"",
+        description: "Day the weekly per-course digest notifications are shown",
+    },
+    SettingDef {
+        key: "working_hours",
+        kind: SettingKind::WorkingHours,
+        default: r#"{"sunday":{"start":"08:00","end":"20:00"},"monday":{"start":"08:00","end":"20:00"},"tuesday":{"start":"08:00","end":"20:00"},"wednesday":{"start":"08:00","end":"20:00"},"thursday":{"start":"08:00","end":"20:00"},"friday":{"start":"08:00","end":"20:00"},"saturday":{"start":"08:00","end":"20:00"}}"#,
+        description: "Hours the planner may schedule into on each weekday; null keeps a day free",
+    },
+    SettingDef {
+        key: "blackout_windows",
+        kind: SettingKind::BlackoutWindows,
+        default: "[]",
+        description: "Recurring do-not-schedule windows, also used as quiet hours for reminders",
+    },
+    SettingDef {
+        key: "timezone",
+        kind: SettingKind::Timezone,
+        default: "null",
+        description: "Timezone (IANA name); empty uses the system timezone",
+    },
+    SettingDef {
+        key: "muted_categories",
+        kind: SettingKind::StringList { values: MUTABLE_CATEGORIES },
+        default: "[]",
+        description: "Insight and recommendation categories the agent must not suggest",
+    },
+    SettingDef {
+        key: "agent_shadow_mode",
+        kind: SettingKind::Bool,
+        default: "false",
+        description: "Log recommendations without showing them",
+    },
+    SettingDef {
+        key: "reduced_data_mode",
+        kind: SettingKind::Bool,
+        default: "false",
+        description: "Turn off embeddings, semantic memory and bandit learning on slow devices",
+    },
+    SettingDef {
+        key: "telemetry_enabled",
+        kind: SettingKind::Bool,
+        default: "false",
+        description: "Count command use and errors on this device; nothing is sent anywhere",
+    },
+    SettingDef {
+        key: "global_shortcuts",
+        kind: SettingKind::Shortcuts,
+        default: r#"{"toggle_pomodoro":"CmdOrCtrl+Alt+P","quick_checkin":"CmdOrCtrl+Alt+C","quick_add":"CmdOrCtrl+Alt+N"}"#,
+        description: "Global keyboard shortcuts for quick actions; null disables one",
+    },
+    SettingDef {
+        key: "tray_enabled",
+        kind: SettingKind::Bool,
+        default: "true",
+        description: "Show the tray icon with quick actions",
+    },
+    SettingDef {
+        key: "focus_mode_enabled",
+        kind: SettingKind::Bool,
+        default: "false",
+        description: "Sample the foreground app during study sessions to measure distractions",
+    },
+    SettingDef {
+        key: "focus_distraction_apps",
+        kind: SettingKind::TextList { max_items: 100 },
+        default: r#"["Discord","Messages","Slack","Steam","Telegram","WhatsApp"]"#,
+        description: "Apps counted as distractions during study sessions",
+    },
+    SettingDef {
+        key: "focus_distraction_sites",
+        kind: SettingKind::TextList { max_items: 100 },
+        default: r#"["facebook","instagram","netflix","reddit","tiktok","twitch","twitter","youtube"]"#,
+        description: "Sites (matched in browser window titles) counted as distractions",
+    },
+    SettingDef {
+        key: "assignment_checklist_template",
+        kind: SettingKind::TextList { max_items: 20 },
+        default: r#"["Draft","Review","Submit"]"#,
+        description: "Required checklist items added to new assignments",
+    },
+    SettingDef {
+        key: "focus_profiles",
+        kind: SettingKind::FocusProfiles,
+        default: r#"[{"id":1,"name":"Pomodoro","work_minutes":25,"break_minutes":5,"long_break_minutes":15,"long_break_every":4,"auto_start_breaks":false,"auto_start_work":false,"tag":null}]"#,
+        description: "Named focus timer profiles (work and break lengths, auto-start, tag)",
+    },
+    SettingDef {
+        key: "planner_split_pomodoros",
+        kind: SettingKind::Bool,
+        default: "false",
+        description: "Split generated study blocks into pomodoros with breaks, using the first focus profile",
+    },
+    SettingDef {
+        key: "planner_reschedule_missed",
+        kind: SettingKind::Bool,
+        default: "false",
+        description: "Move missed plan blocks to free time later in the same week",
+    },
+    SettingDef {
+        key: "planner_study_hours_cap",
+        kind: SettingKind::Int { min: 0, max: 80 },
+        default: "12",
+        description: "Most hours of course study the planner suggests per week",
+    },
+    SettingDef {
+        key: "planner_practice_hours_cap",
+        kind: SettingKind::Int { min: 0, max: 40 },
+        default: "5",
+        description: "Most hours of skill practice the planner suggests per week",
+    },
+    SettingDef {
+        key: "planner_switch_penalty",
+        kind: SettingKind::Int { min: 0, max: 100 },
+        default: "30",
+        description: "How strongly the planner avoids switching courses within a day (0 = not at all)",
+    },
+    SettingDef {
+        key: "planner_mode",
+        kind: SettingKind::Enum { values: crate::commands::planner_mode::MODES },
+        default: "\"normal\"",
+        description: "Scheduling mode: normal, exam period or break",
+    },
+    SettingDef {
+        key: "balance_ideal_academics",
+        kind: SettingKind::Int { min: 0, max: 100 },
+        default: "50",
+        description: "Ideal share of weekly time for academics (study sessions), relative to the other balance domains",
+    },
+    SettingDef {
+        key: "balance_ideal_skills",
+        kind: SettingKind::Int { min: 0, max: 100 },
+        default: "20",
+        description: "Ideal share of weekly time for skills (practice), relative to the other balance domains",
+    },
+    SettingDef {
+        key: "balance_ideal_fitness",
+        kind: SettingKind::Int { min: 0, max: 100 },
+        default: "20",
+        description: "Ideal share of weekly time for fitness (workouts), relative to the other balance domains",
+    },
+    SettingDef {
+        key: "balance_ideal_wellness",
+        kind: SettingKind::Int { min: 0, max: 100 },
+        default: "10",
+        description: "Ideal share of weekly time for wellness (wellness calendar events), relative to the other balance domains",
+    },
+    SettingDef {
+        key: "http_api_enabled",
+        kind: SettingKind::Bool,
+        default: "false",
+        description: "Serve read-only stats on localhost for dashboards (requires a token)",
+    },
+    SettingDef {
+        key: "http_api_port",
+        kind: SettingKind::Int { min: 1024, max: 65535 },
+        default: "47800",
+        description: "Port of the local HTTP API",
+    },
+    SettingDef {
+        key: "mcp_enabled",
+        kind: SettingKind::Bool,
+        default: "false",
+        description: "Let AI assistants use Life OS over MCP at /mcp on the local HTTP API",
+    },
+    SettingDef {
+        key: "mcp_scopes",
+        kind: SettingKind::StringList { values: MCP_SCOPES },
+        default: r#"["agenda:read"]"#,
+        description: "What MCP clients may do",
+    },
+    SettingDef {
+        key: "calendar_feed_enabled",
+        kind: SettingKind::Bool,
+        default: "false",
+        description: "Publish a read-only ICS feed of busy times, served by the local HTTP API and written to the feed path",
+    },
+    SettingDef {
+        key: "calendar_feed_titles",
+        kind: SettingKind::Bool,
+        default: "false",
+        description: "Show event titles in the calendar feed instead of \"Busy\"",
+    },
+    SettingDef {
+        key: "calendar_feed_days",
+        kind: SettingKind::Int { min: 7, max: 180 },
+        default: "28",
+        description: "How many days ahead the calendar feed covers",
+    },
+    SettingDef {
+        key: "calendar_feed_path",
+        kind: SettingKind::OptionalString,
+        default: "null",
+        description: "File the calendar feed is written to after each Google sync and daily, e.g. in a cloud-synced folder",
+    },
+    SettingDef {
+        key: "gamification_enabled",
+        kind: SettingKind::Bool,
+        default: "false",
+        description: "Earn XP and levels for studying, practice, workouts and check-in streaks",
+    },
+    SettingDef {
+        key: "gamification_xp_study_hour",
+        kind: SettingKind::Int { min: 0, max: 1000 },
+        default: "60",
+        description: "XP per hour of study",
+    },
+    SettingDef {
+        key: "gamification_xp_practice_hour",
+        kind: SettingKind::Int { min: 0, max: 1000 },
+        default: "60",
+        description: "XP per hour of skill practice",
+    },
+    SettingDef {
+        key: "gamification_xp_workout",
+        kind: SettingKind::Int { min: 0, max: 1000 },
+        default: "40",
+        description: "XP per workout",
+    },
+    SettingDef {
+        key: "gamification_xp_streak_day",
+        kind: SettingKind::Int { min: 0, max: 1000 },
+        default: "10",
+        description: "XP per day that continues a check-in streak",
+    },
+    SettingDef {
+        key: "gamification_xp_flashcard_review",
+        kind: SettingKind::Int { min: 0, max: 1000 },
+        default: "20",
+        description: "XP per flashcard review (a study session using spaced repetition)",
+    },
+    SettingDef {
+        key: "accountability_day",
+        kind: SettingKind::Enum {
+            values: &["off", "sunday", "monday", "tuesday", "wednesday", "thursday", "friday", "saturday"],
+        },
+        default: "\"off\"",
+        description: "Day the weekly accountability report is sent to your partner",
+    },
+    SettingDef {
+        key: "accountability_delivery",
+        kind: SettingKind::Enum { values: &["file", "webhook", "mailto"] },
+        default: "\"file\"",
+        description: "How the accountability report is delivered: a file in a folder, a webhook or an email draft",
+    },
+    SettingDef {
+        key: "accountability_format",
+        kind: SettingKind::Enum { values: &["markdown", "html"] },
+        default: "\"markdown\"",
+        description: "Format of accountability report files and webhook payloads",
+    },
+    SettingDef {
+        key: "accountability_path",
+        kind: SettingKind::OptionalString,
+        default: "null",
+        description: "Folder accountability reports are written to, e.g. one shared with your partner",
+    },
+    SettingDef {
+        key: "accountability_webhook_url",
+        kind: SettingKind::OptionalString,
+        default: "null",
+        description: "URL the accountability report is POSTed to as JSON",
+    },
+    SettingDef {
+        key: "accountability_email",
+        kind: SettingKind::OptionalString,
+        default: "null",
+        description: "Email address of your accountability partner, for email drafts",
+    },
+    SettingDef {
+        key: "db_max_connections",
+        kind: SettingKind::Int { min: 1, max: 32 },
+        default: "8",
+        description: "Database connection pool size (applies after restart)",
+    },
+    SettingDef {
+        key: "db_busy_timeout_ms",
+        kind: SettingKind::Int { min: 0, max: 60000 },
+        default: "5000",
+        description: "How long a query waits for a locked database before failing (applies after restart)",
+    },
+    SettingDef {
+        key: "db_wal_mode",
+        kind: SettingKind::Bool,
+        default: "true",
+        description: "Write-ahead logging, so reads don't block on writes (applies after restart)",
+    },
+    SettingDef {
+        key: "db_synchronous",
+        kind: SettingKind::Enum { values: &["off", "normal", "full", "extra"] },
+        default: "\"normal\"",
+        description: "SQLite synchronous pragma (applies after restart)",
+    },
+    SettingDef {
+        key: "retention_sessions_months",
+        kind: SettingKind::Int { min: 0, max: 240 },
+        default: "24",
+        description: "Compress sessions older than this many months into daily totals (0 keeps them forever)",
+    },
+    SettingDef {
+        key: "retention_agent_context_months",
+        kind: SettingKind::Int { min: 0, max: 240 },
+        default: "6",
+        description: "Delete agent context snapshots older than this many months (0 keeps them forever)",
+    },
+    SettingDef {
+        key: "google_client_id",
+        kind: SettingKind::OptionalString,
+        default: "null",
+        description: "Google OAuth client ID for calendar sync",
+    },
+    SettingDef {
+        key: "google_export_busy_only",
+        kind: SettingKind::Bool,
+        default: "false",
+        description: "Export plan blocks to Google as untitled \"Busy\" events",
+    },
+    SettingDef {
+        key: "onboarding_completed_at",
+        kind: SettingKind::OptionalString,
+        default: "null",
+        description: "When the onboarding wizard was finished",
+    },
+];
+
+/// A changed setting, as published to subscribers
+#[derive(Debug, Clone, Serialize)]
+pub struct SettingChange {
+    pub key: String,
+    pub value: Value,
+}
+
+static CHANGES: Lazy<broadcast::Sender<SettingChange>> = Lazy::new(|| broadcast::channel(64).0);
+
+/// Receive every setting change made after this call
+pub fn subscribe() -> broadcast::Receiver<SettingChange> {
+    CHANGES.subscribe()
+}
+
+/// Forward setting changes to the frontend as `settings-changed` events
+pub fn start_event_forwarder(app_handle: tauri::AppHandle) {
+    use tauri::Emitter;
+
+    let mut changes = subscribe();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            match changes.recv().await {
+                Ok(change) => {
+                    if let Err(e) = app_handle.emit(SETTINGS_CHANGED_EVENT, change) {
+                        log::warn!("Failed to emit settings change: {}", e);
+                    }
+                }
+                Err(broadcast::error::RecvError::Lagged(skipped)) => {
+                    log::warn!("Dropped {} settings change events", skipped);
+                }
+                Err(broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    });
+}
+
+/// Look up a setting declaration
+pub fn definition(key: &str) -> Result<&'static SettingDef, ApiError> {
+    SETTINGS
+        .iter()
+        .find(|d| d.key == key)
+        .ok_or_else(|| ApiError::validation(format!("Unknown setting: {}", key)))
+}
+
+impl SettingDef {
+    pub fn default_value(&self) -> Value {
+        serde_json::from_str(self.default).unwrap_or(Value::Null)
+    }
+
+    /// Check a value against the declaration, returning it normalized
+    pub fn validate(&self, value: Value) -> Result<Value, ApiError> {
+        let invalid = |expected: &str| {
+            ApiError::validation(format!("Setting {} must be {}", self.key, expected))
+        };
+
+        match self.kind {
+            SettingKind::Bool => value.as_bool().map(Value::Bool).ok_or_else(|| invalid("true or false")),
+            SettingKind::Int { min, max } => match value.as_i64() {
+                Some(n) if (min..=max).contains(&n) => Ok(Value::from(n)),
+                _ => Err(invalid(&format!("an integer between {} and {}", min, max))),
+            },
+            SettingKind::Enum { values } => match value.as_str() {
+                Some(s) if values.contains(&s) => Ok(value),
+                _ => Err(invalid(&format!("one of {:?}", values))),
+            },
+            SettingKind::OptionalString => match value {
+                Value::Null => Ok(Value::Null),
+                Value::String(s) if s.trim().is_empty() => Ok(Value::Null),
+                Value::String(s) if s.len() <= MAX_STRING_LENGTH => Ok(Value::String(s.trim().to_string())),
+                _ => Err(invalid(&format!("text of at most {} characters", MAX_STRING_LENGTH))),
+            },
+            SettingKind::Timezone => match value {
+                Value::Null => Ok(Value::Null),
+                Value::String(ref s) if s.is_empty() => Ok(Value::Null),
+                Value::String(ref s)
+                    if s.len() <= MAX_TIMEZONE_LENGTH
+                        && s.chars().all(|c| c.is_ascii_alphanumeric() || "/_-+".contains(c)) =>
+                {
+                    Ok(value)
+                }
+                _ => Err(invalid("an IANA timezone name such as \"Europe/Berlin\"")),
+            },
+            SettingKind::Shortcuts => crate::quick_actions::validate_bindings(value),
+            SettingKind::FocusProfiles => crate::commands::focus_profiles::validate_profiles(value),
+            SettingKind::WorkingHours => working_hours::validate_working_hours(value),
+            SettingKind::BlackoutWindows => working_hours::validate_blackout_windows(value),
+            SettingKind::WorkloadTemplates => crate::commands::course_workload::validate_templates(value),
+            SettingKind::TextList { max_items } => {
+                let items: Vec<String> = serde_json::from_value(value)
+                    .map_err(|_| invalid("a list of strings"))?;
+                let mut items: Vec<String> = items
+                    .iter()
+                    .map(|i| i.trim().to_string())
+                    .filter(|i| !i.is_empty())
+                    .collect();
+                items.sort();
+                items.dedup();
+                if items.len() > max_items || items.iter().any(|i| i.len() > MAX_STRING_LENGTH) {
+                    return Err(invalid(&format!("at most {} entries", max_items)));
+                }
+                Ok(Value::from(items))
+            }
+            SettingKind::StringList { values } => {
+                let items: Vec<String> = serde_json::from_value(value)
+                    .map_err(|_| invalid("a list of strings"))?;
+                if let Some(unknown) = items.iter().find(|i| !values.contains(&i.as_str())) {
+                    return Err(invalid(&format!("a list of {:?} (got {:?})", values, unknown)));
+                }
+                let mut items = items;
+                items.sort();
+                items.dedup();
+                Ok(Value::from(items))
+            }
+        }
+    }
+}
+
+/// Get a setting (its default when unset)
+pub async fn get(pool: &Pool<Sqlite>, key: &str) -> Result<Value, ApiError> {
+    let def = definition(key)?;
+    let stored: Option<String> = sqlx::query_scalar("SELECT value_json FROM settings WHERE key = ?")
+        .bind(key)
+        .fetch_optional(pool)
+        .await
+        .map_err(ApiError::from)?;
+
+    // A value that no longer validates (e.g. bounds tightened) reads as the default
+    Ok(stored
+        .and_then(|json| serde_json::from_str(&json).ok())
+        .and_then(|value| def.validate(value).ok())
+        .unwrap_or_else(|| def.default_value()))
+}
+
+/// Validate and store a setting, then notify subscribers; returns the stored value
+pub async fn set(pool: &Pool<Sqlite>, key: &str, value: Value) -> Result<Value, ApiError> {
+    let value = definition(key)?.validate(value)?;
+
+    sqlx::query(
+        r#"
+        INSERT INTO settings (key, value_json, updated_at)
+        VALUES (?, ?, datetime('now'))
+        ON CONFLICT(key) DO UPDATE SET value_json = excluded.value_json, updated_at = excluded.updated_at
+        "#,
+    )
+    .bind(key)
+    .bind(value.to_string())
+    .execute(pool)
+    .await
+    .map_err(ApiError::from)?;
+
+    // No subscribers is fine
+    let _ = CHANGES.send(SettingChange {
+        key: key.to_string(),
+        value: value.clone(),
+    });
+
+    Ok(value)
+}
+
+/// Every setting with its current value
+pub async fn get_all(pool: &Pool<Sqlite>) -> Result<serde_json::Map<String, Value>, ApiError> {
+    let mut all = serde_json::Map::new();
+    for def in SETTINGS {
+        all.insert(def.key.to_string(), get(pool, def.key).await?);
+    }
+    Ok(all)
+}
+
+pub async fn get_bool(pool: &Pool<Sqlite>, key: &str) -> Result<bool, ApiError> {
+    Ok(get(pool, key).await?.as_bool().unwrap_or(false))
+}
+
+pub async fn get_i64(pool: &Pool<Sqlite>, key: &str) -> Result<i64, ApiError> {
+    let def = definition(key)?;
+    let value = get(pool, key).await?;
+    Ok(value.as_i64().or_else(|| def.default_value().as_i64()).unwrap_or(0))
+}
+
+pub async fn get_string(pool: &Pool<Sqlite>, key: &str) -> Result<Option<String>, ApiError> {
+    Ok(get(pool, key).await?.as_str().map(str::to_string))
+}
+
+pub async fn get_string_list(pool: &Pool<Sqlite>, key: &str) -> Result<Vec<String>, ApiError> {
+    Ok(serde_json::from_value(get(pool, key).await?).unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+    use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+    use std::str::FromStr;
+
+    async fn setup_pool_with_migrations() -> Pool<Sqlite> {
+        let options = SqliteConnectOptions::from_str("sqlite::memory:")
+            .unwrap()
+            .foreign_keys(false);
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect_with(options)
+            .await
+            .expect("Failed to connect to in-memory DB");
+
+        crate::db::migrations::run_migrations(&pool)
+            .await
+            .expect("Failed to run migrations");
+
+        pool
+    }
+
+    #[test]
+    fn test_defaults_are_valid() {
+        for def in SETTINGS {
+            let default = def.default_value();
+            assert_eq!(def.validate(default.clone()).ok(), Some(default), "{}", def.key);
+        }
+    }
+
+    #[test]
+    fn test_validation() {
+        let target = definition("weekly_workout_target").unwrap();
+        assert!(target.validate(json!(4)).is_ok());
+        assert!(target.validate(json!(15)).is_err());
+        assert!(target.validate(json!("4")).is_err());
+
+        let muted = definition("muted_categories").unwrap();
+        assert_eq!(
+            muted.validate(json!(["wellness", "academic", "wellness"])).unwrap(),
+            json!(["academic", "wellness"])
+        );
+        assert!(muted.validate(json!(["nonsense"])).is_err());
+
+        let tz = definition("timezone").unwrap();
+        assert_eq!(tz.validate(json!("")).unwrap(), Value::Null);
+        assert!(tz.validate(json!("Robert'); DROP TABLE")).is_err());
+
+        assert!(definition("no_such_key").is_err());
+    }
+
+    #[tokio::test]
+    async fn set_persists_and_notifies() {
+        let pool = setup_pool_with_migrations().await;
+        let mut changes = subscribe();
+
+        assert_eq!(get_i64(&pool, "weekly_workout_target").await.unwrap(), 3);
+        set(&pool, "weekly_workout_target", json!(5)).await.unwrap();
+        assert_eq!(get_i64(&pool, "weekly_workout_target").await.unwrap(), 5);
+
+        // Other tests may publish concurrently; look for ours
+        let mut seen = false;
+        while let Ok(change) = changes.try_recv() {
+            seen |= change.key == "weekly_workout_target" && change.value == json!(5);
+        }
+        assert!(seen);
+
+        let all = get_all(&pool).await.unwrap();
+        assert_eq!(all.len(), SETTINGS.len());
+        assert_eq!(all["week_start_day"], json!("monday"));
+    }
+}
@@ -0,0 +1,342 @@
+//! Social Time
+//!
+//! A simple log of time spent with other people, kept as the built-in
+//! `social` tracker so it counts toward wellness in the life balance. An
+//! event is a tracker log in minutes with its name as the `note` and how it
+//! left the user, from draining (1) to energizing (5), as its `rating`.
+//!
+//! The stats look for isolation streaks, runs of `MIN_ISOLATION_DAYS` or more
+//! days without an event, and line them up with crunch days: days with an
+//! exam, or `CRUNCH_LOAD` assignments, due within `CRUNCH_WINDOW_DAYS`. Days
+//! before the first event ever logged never count as isolated.
+//!
+//! `social_context` feeds the agent's `mood_traj_x_social` feature: the last
+//! `CONTEXT_DAYS` days of social minutes, with draining time counting less.
+
+use std::collections::HashSet;
+
+use chrono::{DateTime, Duration, Local, NaiveDate};
+use serde::{Deserialize, Serialize};
+use sqlx::{Pool, Sqlite};
+
+use crate::{
+    error::ApiError,
+    services::trackers::{self, TrackerLog, TrackerLogInput},
+};
+
+/// Slug of the built-in tracker
+pub const TRACKER_SLUG: &str = "social";
+
+const MAX_MINUTES: i64 = 24 * 60;
+const MAX_NAME_LENGTH: usize = 200;
+/// Event-free days that make an isolation streak
+const MIN_ISOLATION_DAYS: i64 = 3;
+/// Days ahead a deadline makes a day a crunch day
+const CRUNCH_WINDOW_DAYS: i64 = 3;
+/// Deadline weight within the window that makes a crunch; an exam weighs 2
+const CRUNCH_LOAD: i64 = 2;
+/// Days of each kind needed before comparing crunch and other days
+const MIN_COMPARED_DAYS: i64 = 3;
+/// Share of days social, crunch against other days, worth an insight
+const NOTABLE_SHARE_GAP: f64 = 0.2;
+/// Days of social time behind the context feature
+const CONTEXT_DAYS: i64 = 3;
+/// Weighted minutes over `CONTEXT_DAYS` that saturate the context feature
+const CONTEXT_MINUTES: f64 = 240.0;
+
+#[derive(Debug, Deserialize)]
+pub struct SocialEventInput {
+    pub name: String,
+    pub minutes: i64,
+    /// 1 (draining) to 5 (energizing)
+    #[serde(default)]
+    pub rating: Option<i64>,
+    /// UTC SQLite timestamp; defaults to now
+    #[serde(default)]
+    pub logged_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct IsolationStreak {
+    /// YYYY-MM-DD
+    pub start: String,
+    /// YYYY-MM-DD; today while the streak is still running
+    pub end: String,
+    pub days: i64,
+    pub crunch_days: i64,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SocialStats {
+    pub days: i64,
+    pub events: i64,
+    pub minutes: i64,
+    /// Mean rating of rated events
+    pub avg_rating: Option<f64>,
+    /// Minutes of events rated 4-5
+    pub energizing_minutes: i64,
+    /// Minutes of events rated 1-2
+    pub draining_minutes: i64,
+    /// Event-free days up to and including today
+    pub current_isolation_days: i64,
+    /// Oldest first
+    pub isolation_streaks: Vec<IsolationStreak>,
+    pub crunch_days: i64,
+    /// Share of crunch days with an event
+    pub crunch_social_share: Option<f64>,
+    /// Share of the other days with an event
+    pub other_social_share: Option<f64>,
+    pub insights: Vec<String>,
+}
+
+/// Log a social event
+pub async fn log_event(pool: &Pool<Sqlite>, input: &SocialEventInput) -> Result<TrackerLog, ApiError> {
+    let name = input.name.trim();
+    if name.is_empty() {
+        return Err(ApiError::validation("Name the event"));
+    }
+    if name.chars().count() > MAX_NAME_LENGTH {
+        return Err(ApiError::validation(format!("The name can be at most {} characters", MAX_NAME_LENGTH)));
+    }
+    if !(1..=MAX_MINUTES).contains(&input.minutes) {
+        return Err(ApiError::validation(format!("Minutes must be between 1 and {}", MAX_MINUTES)));
+    }
+    let tracker = trackers::by_slug(pool, TRACKER_SLUG).await?;
+    trackers::record(
+        pool,
+        &TrackerLogInput {
+            tracker_id: tracker.id,
+            amount: input.minutes as f64,
+            minutes: Some(input.minutes),
+            rating: input.rating,
+            note: Some(name.to_string()),
+            logged_at: input.logged_at.clone(),
+            ..Default::default()
+        },
+    )
+    .await
+}
+
+/// Logged events, newest first
+pub async fn events(pool: &Pool<Sqlite>, limit: i64) -> Result<Vec<TrackerLog>, ApiError> {
+    let tracker = trackers::by_slug(pool, TRACKER_SLUG).await?;
+    trackers::logs(pool, tracker.id, limit).await
+}
+
+/// Total deadline weight due from `day` to `CRUNCH_WINDOW_DAYS` after it
+fn is_crunch(day: NaiveDate, deadlines: &[(NaiveDate, i64)]) -> bool {
+    let until = day + Duration::days(CRUNCH_WINDOW_DAYS);
+    deadlines.iter().filter(|(due, _)| (day..=until).contains(due)).map(|(_, weight)| weight).sum::<i64>() >= CRUNCH_LOAD
+}
+
+fn share(social: i64, total: i64) -> Option<f64> {
+    (total >= MIN_COMPARED_DAYS).then(|| ((social as f64 / total as f64) * 100.0).round() / 100.0)
+}
+
+/// Events over the last `days` days ending with `today`, with isolation
+/// streaks against crunch periods
+pub async fn stats(pool: &Pool<Sqlite>, days: i64, today: NaiveDate) -> Result<SocialStats, ApiError> {
+    let tracker = trackers::by_slug(pool, TRACKER_SLUG).await?;
+    let from = today - Duration::days(days - 1);
+    let logs: Vec<(String, i64, Option<i64>)> = sqlx::query_as(
+        r#"SELECT date(logged_at, 'localtime'), CAST(amount AS INTEGER), rating
+           FROM tracker_logs
+           WHERE tracker_id = ?1 AND date(logged_at, 'localtime') BETWEEN ?2 AND ?3"#,
+    )
+    .bind(tracker.id)
+    .bind(from.to_string())
+    .bind(today.to_string())
+    .fetch_all(pool)
+    .await
+    .map_err(ApiError::from)?;
+    let first: Option<String> = sqlx::query_scalar("SELECT MIN(date(logged_at, 'localtime')) FROM tracker_logs WHERE tracker_id = ?")
+        .bind(tracker.id)
+        .fetch_one(pool)
+        .await
+        .map_err(ApiError::from)?;
+    let deadlines: Vec<(String, i64)> = sqlx::query_as(
+        r#"SELECT date(due_date), 1 FROM assignments
+           WHERE due_date IS NOT NULL AND date(due_date) BETWEEN ?1 AND ?2
+           UNION ALL
+           SELECT date(exam_date), 2 FROM exams
+           WHERE exam_date IS NOT NULL AND date(exam_date) BETWEEN ?1 AND ?2"#,
+    )
+    .bind(from.to_string())
+    .bind((today + Duration::days(CRUNCH_WINDOW_DAYS)).to_string())
+    .fetch_all(pool)
+    .await
+    .map_err(ApiError::from)?;
+    let deadlines: Vec<(NaiveDate, i64)> = deadlines
+        .into_iter()
+        .filter_map(|(day, weight)| NaiveDate::parse_from_str(&day, "%Y-%m-%d").ok().map(|d| (d, weight)))
+        .collect();
+
+    let social_days: HashSet<&str> = logs.iter().map(|(day, _, _)| day.as_str()).collect();
+    let rated: Vec<i64> = logs.iter().filter_map(|(_, _, rating)| *rating).collect();
+    let minutes_rated = |keep: fn(i64) -> bool| logs.iter().filter(|(_, _, r)| r.is_some_and(keep)).map(|(_, m, _)| m).sum::<i64>();
+
+    // Walk the tracked days, closing a streak at each event
+    let start = first
+        .and_then(|d| NaiveDate::parse_from_str(&d, "%Y-%m-%d").ok())
+        .map_or(today + Duration::days(1), |first| first.max(from));
+    let mut streaks = Vec::new();
+    let mut run: Option<IsolationStreak> = None;
+    let (mut crunch_days, mut crunch_social, mut other_days, mut other_social) = (0, 0, 0, 0);
+    for day in start.iter_days().take_while(|d| *d <= today) {
+        let social = social_days.contains(day.to_string().as_str());
+        let crunch = is_crunch(day, &deadlines);
+        if crunch {
+            crunch_days += 1;
+            crunch_social += social as i64;
+        } else {
+            other_days += 1;
+            other_social += social as i64;
+        }
+        if social {
+            streaks.extend(run.take().filter(|s| s.days >= MIN_ISOLATION_DAYS));
+            continue;
+        }
+        let streak = run.get_or_insert_with(|| IsolationStreak {
+            start: day.to_string(),
+            end: day.to_string(),
+            days: 0,
+            crunch_days: 0,
+        });
+        streak.end = day.to_string();
+        streak.days += 1;
+        streak.crunch_days += crunch as i64;
+    }
+    let current_isolation_days = run.as_ref().map_or(0, |s| s.days);
+    streaks.extend(run.filter(|s| s.days >= MIN_ISOLATION_DAYS));
+
+    let crunch_social_share = share(crunch_social, crunch_days);
+    let other_social_share = share(other_social, other_days);
+    let energizing_minutes = minutes_rated(|r| r >= 4);
+    let draining_minutes = minutes_rated(|r| r <= 2);
+
+    let mut insights = Vec::new();
+    if current_isolation_days >= MIN_ISOLATION_DAYS {
+        insights.push(if is_crunch(today, &deadlines) {
+            format!(
+                "No social time in {} days, with deadlines close. Even a short call or meal with someone helps through a crunch.",
+                current_isolation_days
+            )
+        } else {
+            format!("No social time in {} days.", current_isolation_days)
+        });
+    }
+    let in_crunch: Vec<&IsolationStreak> = streaks.iter().filter(|s| s.crunch_days * 2 >= s.days).collect();
+    if let Some(longest) = in_crunch.iter().max_by_key(|s| s.days) {
+        insights.push(format!(
+            "{} of your {} isolation streaks fell in crunch periods; the longest ran {} days up to {}.",
+            in_crunch.len(),
+            streaks.len(),
+            longest.days,
+            longest.end
+        ));
+    }
+    if let (Some(crunch), Some(other)) = (crunch_social_share, other_social_share) {
+        if other - crunch >= NOTABLE_SHARE_GAP {
+            insights.push(format!(
+                "You saw people on {:.0}% of crunch days against {:.0}% of other days.",
+                crunch * 100.0,
+                other * 100.0
+            ));
+        }
+    }
+    if draining_minutes > energizing_minutes {
+        insights.push("More of your social time was draining than energizing; make room for the people who recharge you.".to_string());
+    }
+
+    Ok(SocialStats {
+        days,
+        events: logs.len() as i64,
+        minutes: logs.iter().map(|(_, m, _)| m).sum(),
+        avg_rating: (!rated.is_empty())
+            .then(|| ((rated.iter().sum::<i64>() as f64 / rated.len() as f64) * 100.0).round() / 100.0),
+        energizing_minutes,
+        draining_minutes,
+        current_isolation_days,
+        isolation_streaks: streaks,
+        crunch_days,
+        crunch_social_share,
+        other_social_share,
+        insights,
+    })
+}
+
+/// Recent social time as a 0-1 feature; None until an event has been logged.
+/// Unrated events count as a 3.
+pub async fn social_context(pool: &Pool<Sqlite>, now: DateTime<Local>) -> Result<Option<f32>, ApiError> {
+    let tracker = trackers::by_slug(pool, TRACKER_SLUG).await?;
+    let at = now.with_timezone(&chrono::Utc).format("%Y-%m-%d %H:%M:%S").to_string();
+    let (logged, weighted): (i64, Option<f64>) = sqlx::query_as(
+        r#"SELECT
+               (SELECT COUNT(*) FROM tracker_logs WHERE tracker_id = ?1 AND logged_at <= ?2),
+               (SELECT SUM(amount * COALESCE(rating, 3) / 5.0) FROM tracker_logs
+                WHERE tracker_id = ?1 AND logged_at <= ?2 AND logged_at > datetime(?2, ?3))"#,
+    )
+    .bind(tracker.id)
+    .bind(&at)
+    .bind(format!("-{} days", CONTEXT_DAYS))
+    .fetch_one(pool)
+    .await
+    .map_err(ApiError::from)?;
+    Ok((logged > 0).then(|| (weighted.unwrap_or(0.0) / CONTEXT_MINUTES).min(1.0) as f32))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[tokio::test]
+    async fn isolation_streaks_line_up_with_crunch_periods() {
+        let pool = crate::test_support::pool().await;
+        assert_eq!(social_context(&pool, Local::now()).await.unwrap(), None);
+
+        for (day, minutes, rating) in [("2030-09-01", 60, Some(5)), ("2030-09-02", 30, Some(2)), ("2030-09-10", 120, Some(4)), ("2030-09-19", 90, None)] {
+            log_event(
+                &pool,
+                &SocialEventInput {
+                    name: "Dinner".to_string(),
+                    minutes,
+                    rating,
+                    logged_at: Some(format!("{} 12:00:00", day)),
+                },
+            )
+            .await
+            .unwrap();
+        }
+        assert!(log_event(&pool, &SocialEventInput { name: " ".to_string(), minutes: 30, rating: None, logged_at: None }).await.is_err());
+
+        // Two assignments due on the 8th make the 5th-8th a crunch
+        let course: i64 = sqlx::query_scalar("INSERT INTO courses (name) VALUES ('Physics') RETURNING id")
+            .fetch_one(&pool)
+            .await
+            .unwrap();
+        for title in ["Lab report", "Problem set"] {
+            sqlx::query("INSERT INTO assignments (course_id, title, due_date) VALUES (?, ?, '2030-09-08')")
+                .bind(course)
+                .bind(title)
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+
+        let stats = stats(&pool, 20, NaiveDate::from_ymd_opt(2030, 9, 20).unwrap()).await.unwrap();
+        assert_eq!((stats.events, stats.minutes, stats.avg_rating), (4, 300, Some(3.67)));
+        assert_eq!((stats.energizing_minutes, stats.draining_minutes), (180, 30));
+        assert_eq!(stats.current_isolation_days, 1);
+        let streaks: Vec<_> = stats.isolation_streaks.iter().map(|s| (s.start.as_str(), s.days, s.crunch_days)).collect();
+        assert_eq!(streaks, [("2030-09-03", 7, 4), ("2030-09-11", 8, 0)]);
+        assert_eq!((stats.crunch_days, stats.crunch_social_share, stats.other_social_share), (4, Some(0.0), Some(0.25)));
+        assert_eq!(stats.insights.len(), 2);
+        assert!(stats.insights[0].starts_with("1 of your 2 isolation streaks"));
+
+        // Only the 90 unrated minutes fall in the last three days
+        let now = Local.with_ymd_and_hms(2030, 9, 20, 12, 0, 0).unwrap();
+        let context = social_context(&pool, now).await.unwrap().unwrap();
+        assert!((context - 0.225).abs() < 1e-6);
+    }
+}
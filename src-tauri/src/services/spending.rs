@@ -0,0 +1,397 @@
+//! Spending Check-ins
+//!
+//! A lightweight money habit rather than a budget: once a day the user logs
+//! the day's discretionary spend and whether it stayed within budget. When
+//! `spending_daily_budget` is set the answer defaults to spend at or below
+//! it; otherwise it must be given. Logging the same day again replaces it.
+//!
+//! The summary groups check-ins into weeks (honouring `week_start_day`),
+//! tracks the check-in and within-budget streaks, and lines each day up
+//! against that day's mean check-in mood and energy. Check-ins carry no
+//! stress rating, so low energy stands in for it. Correlations need
+//! `MIN_PAIRED_DAYS` days with both a spending check-in and a mood check-in.
+
+use chrono::{Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, Pool, Sqlite};
+
+use crate::{
+    error::ApiError,
+    services::{focus, settings, trackers},
+};
+
+const MAX_AMOUNT: f64 = 1_000_000.0;
+const MAX_NOTE_LENGTH: usize = 500;
+/// Days with both check-ins needed before correlating
+const MIN_PAIRED_DAYS: usize = 7;
+/// |r| from which a correlation is worth an insight
+const NOTABLE_CORRELATION: f64 = 0.3;
+/// Mood points between within- and over-budget days worth an insight
+const NOTABLE_MOOD_GAP: f64 = 1.0;
+/// Days without a check-in after which the evening prompt stops
+const PROMPT_LAPSE_DAYS: i64 = 14;
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct SpendingCheckIn {
+    /// YYYY-MM-DD
+    pub date: String,
+    pub amount: f64,
+    pub within_budget: bool,
+    pub note: Option<String>,
+    pub updated_at: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct SpendingCheckInInput {
+    /// YYYY-MM-DD; defaults to today
+    #[serde(default)]
+    pub date: Option<String>,
+    pub amount: f64,
+    /// Defaults to the amount against `spending_daily_budget` when one is set
+    #[serde(default)]
+    pub within_budget: Option<bool>,
+    #[serde(default)]
+    pub note: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SpendingWeek {
+    /// YYYY-MM-DD
+    pub week_start: String,
+    pub days_logged: i64,
+    pub total: f64,
+    /// Over the days logged
+    pub average_per_day: Option<f64>,
+    pub days_within_budget: i64,
+    /// Date of the biggest spend
+    pub biggest_day: Option<String>,
+}
+
+/// Spending against mood and energy over the summary's weeks
+#[derive(Debug, Clone, Serialize)]
+pub struct SpendingWellness {
+    /// Days with both a spending and a mood/energy check-in
+    pub paired_days: i64,
+    /// Pearson r of the day's spend with its mean mood
+    pub mood_correlation: Option<f64>,
+    pub energy_correlation: Option<f64>,
+    pub mood_within_budget: Option<f64>,
+    pub mood_over_budget: Option<f64>,
+    pub energy_within_budget: Option<f64>,
+    pub energy_over_budget: Option<f64>,
+    pub insights: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct SpendingSummary {
+    /// None when within-budget is answered each time
+    pub daily_budget: Option<i64>,
+    pub today: Option<SpendingCheckIn>,
+    /// Days in a row with a check-in
+    pub current_streak: i64,
+    pub best_streak: i64,
+    /// Days in a row within budget
+    pub within_budget_streak: i64,
+    /// Newest first, ending with the current week
+    pub weeks: Vec<SpendingWeek>,
+    pub wellness: SpendingWellness,
+}
+
+pub fn parse_date(value: &str) -> Result<NaiveDate, ApiError> {
+    NaiveDate::parse_from_str(value, "%Y-%m-%d").map_err(|_| ApiError::validation("date must be a YYYY-MM-DD date"))
+}
+
+async fn daily_budget(pool: &Pool<Sqlite>) -> Result<Option<i64>, ApiError> {
+    Ok(Some(settings::get_i64(pool, "spending_daily_budget").await?).filter(|b| *b > 0))
+}
+
+/// Log (or replace) a day's check-in
+pub async fn check_in(
+    pool: &Pool<Sqlite>,
+    input: &SpendingCheckInInput,
+    today: NaiveDate,
+) -> Result<SpendingCheckIn, ApiError> {
+    let date = match input.date.as_deref() {
+        Some(d) => parse_date(d)?,
+        None => today,
+    };
+    if date > today {
+        return Err(ApiError::validation("Can't check in for a future day"));
+    }
+    if !(input.amount.is_finite() && (0.0..=MAX_AMOUNT).contains(&input.amount)) {
+        return Err(ApiError::validation(format!("Amount must be between 0 and {}", MAX_AMOUNT)));
+    }
+    let note = input.note.as_deref().map(str::trim).filter(|n| !n.is_empty());
+    if note.is_some_and(|n| n.chars().count() > MAX_NOTE_LENGTH) {
+        return Err(ApiError::validation(format!("Note must be at most {} characters", MAX_NOTE_LENGTH)));
+    }
+    let within_budget = match (input.within_budget, daily_budget(pool).await?) {
+        (Some(within), _) => within,
+        (None, Some(budget)) => input.amount <= budget as f64,
+        (None, None) => return Err(ApiError::validation("Say whether the day was within budget")),
+    };
+
+    sqlx::query_as(
+        r#"INSERT INTO spending_checkins (date, amount, within_budget, note) VALUES (?, ?, ?, ?)
+           ON CONFLICT (date) DO UPDATE SET
+               amount = excluded.amount, within_budget = excluded.within_budget,
+               note = excluded.note, updated_at = datetime('now')
+           RETURNING date, amount, within_budget, note, updated_at"#,
+    )
+    .bind(date.to_string())
+    .bind(input.amount)
+    .bind(within_budget)
+    .bind(note)
+    .fetch_one(pool)
+    .await
+    .map_err(ApiError::from)
+}
+
+pub async fn delete(pool: &Pool<Sqlite>, date: NaiveDate) -> Result<bool, ApiError> {
+    let result = sqlx::query("DELETE FROM spending_checkins WHERE date = ?")
+        .bind(date.to_string())
+        .execute(pool)
+        .await
+        .map_err(ApiError::from)?;
+    Ok(result.rows_affected() > 0)
+}
+
+async fn all(pool: &Pool<Sqlite>) -> Result<Vec<SpendingCheckIn>, ApiError> {
+    sqlx::query_as("SELECT date, amount, within_budget, note, updated_at FROM spending_checkins ORDER BY date")
+        .fetch_all(pool)
+        .await
+        .map_err(ApiError::from)
+}
+
+fn round2(value: f64) -> f64 {
+    (value * 100.0).round() / 100.0
+}
+
+fn mean(values: impl Iterator<Item = f64>) -> Option<f64> {
+    let (sum, n) = values.fold((0.0, 0usize), |(sum, n), v| (sum + v, n + 1));
+    (n > 0).then(|| sum / n as f64)
+}
+
+fn pearson(pairs: &[(f64, f64)]) -> Option<f64> {
+    if pairs.len() < MIN_PAIRED_DAYS {
+        return None;
+    }
+    let mean_x = mean(pairs.iter().map(|p| p.0))?;
+    let mean_y = mean(pairs.iter().map(|p| p.1))?;
+    let sxx: f64 = pairs.iter().map(|p| (p.0 - mean_x).powi(2)).sum();
+    let syy: f64 = pairs.iter().map(|p| (p.1 - mean_y).powi(2)).sum();
+    if sxx < 1e-9 || syy < 1e-9 {
+        return None;
+    }
+    let sxy: f64 = pairs.iter().map(|p| (p.0 - mean_x) * (p.1 - mean_y)).sum();
+    Some(sxy / (sxx * syy).sqrt())
+}
+
+/// Spend against the day's mean mood and energy since `start`
+async fn wellness(pool: &Pool<Sqlite>, checkins: &[&SpendingCheckIn], start: NaiveDate) -> Result<SpendingWellness, ApiError> {
+    let moods: Vec<(String, Option<f64>, Option<f64>)> = sqlx::query_as(
+        r#"SELECT date(checked_in_at, 'localtime') AS day, AVG(mood), AVG(energy)
+           FROM check_ins
+           WHERE date(checked_in_at, 'localtime') >= ?
+           GROUP BY day"#,
+    )
+    .bind(start.to_string())
+    .fetch_all(pool)
+    .await
+    .map_err(ApiError::from)?;
+
+    // (amount, within budget, mood, energy)
+    let days: Vec<(f64, bool, Option<f64>, Option<f64>)> = checkins
+        .iter()
+        .filter_map(|c| {
+            let (_, mood, energy) = moods.iter().find(|(day, _, _)| *day == c.date)?;
+            (mood.is_some() || energy.is_some()).then_some((c.amount, c.within_budget, *mood, *energy))
+        })
+        .collect();
+
+    let mood_pairs: Vec<(f64, f64)> = days.iter().filter_map(|d| d.2.map(|m| (d.0, m))).collect();
+    let energy_pairs: Vec<(f64, f64)> = days.iter().filter_map(|d| d.3.map(|e| (d.0, e))).collect();
+    let mood_correlation = pearson(&mood_pairs).map(round2);
+    let energy_correlation = pearson(&energy_pairs).map(round2);
+    let split = |within: bool, pick: fn(&(f64, bool, Option<f64>, Option<f64>)) -> Option<f64>| {
+        mean(days.iter().filter(|d| d.1 == within).filter_map(pick)).map(|v| (v * 10.0).round() / 10.0)
+    };
+    let mood_within_budget = split(true, |d| d.2);
+    let mood_over_budget = split(false, |d| d.2);
+
+    let mut insights = Vec::new();
+    match mood_correlation {
+        Some(r) if r <= -NOTABLE_CORRELATION => {
+            insights.push(format!("You tend to spend more on lower-mood days (r = {:.2})", r))
+        }
+        Some(r) if r >= NOTABLE_CORRELATION => {
+            insights.push(format!("Bigger spending days come with better moods (r = {:.2})", r))
+        }
+        _ => {}
+    }
+    if let Some(r) = energy_correlation.filter(|r| *r <= -NOTABLE_CORRELATION) {
+        insights.push(format!(
+            "Spending rises when your energy is low (r = {:.2}); tired days may call for a plan",
+            r
+        ));
+    }
+    if let (Some(within), Some(over)) = (mood_within_budget, mood_over_budget) {
+        if within - over >= NOTABLE_MOOD_GAP {
+            insights.push(format!(
+                "Mood averaged {:.1} on within-budget days and {:.1} on days over it",
+                within, over
+            ));
+        }
+    }
+
+    Ok(SpendingWellness {
+        paired_days: days.len() as i64,
+        mood_correlation,
+        energy_correlation,
+        mood_within_budget,
+        mood_over_budget,
+        energy_within_budget: split(true, |d| d.3),
+        energy_over_budget: split(false, |d| d.3),
+        insights,
+    })
+}
+
+/// The last `weeks` weeks of check-ins as of `today`
+pub async fn summary(pool: &Pool<Sqlite>, weeks: i64, today: NaiveDate) -> Result<SpendingSummary, ApiError> {
+    let checkins = all(pool).await?;
+    let current_week = focus::week_start_for(pool, today).await?;
+    let start = current_week - Duration::weeks(weeks - 1);
+
+    let dates: Vec<NaiveDate> = checkins.iter().filter_map(|c| c.date.parse().ok()).collect();
+    let (current_streak, best_streak) = trackers::runs(&dates, Duration::days(1), today);
+    let within: Vec<NaiveDate> = checkins
+        .iter()
+        .filter(|c| c.within_budget)
+        .filter_map(|c| c.date.parse().ok())
+        .collect();
+    // A break in check-ins breaks the within-budget run too
+    let (within_budget_streak, _) = match checkins.last() {
+        Some(last) if last.within_budget => trackers::runs(&within, Duration::days(1), today),
+        _ => (0, 0),
+    };
+
+    let in_window: Vec<&SpendingCheckIn> = checkins.iter().filter(|c| c.date >= start.to_string()).collect();
+    let weeks = (0..weeks)
+        .map(|i| {
+            let week_start = current_week - Duration::weeks(i);
+            let (from, to) = (week_start.to_string(), (week_start + Duration::days(7)).to_string());
+            let days: Vec<&&SpendingCheckIn> = in_window.iter().filter(|c| c.date >= from && c.date < to).collect();
+            let total: f64 = days.iter().map(|c| c.amount).sum();
+            SpendingWeek {
+                week_start: from,
+                days_logged: days.len() as i64,
+                total: round2(total),
+                average_per_day: (!days.is_empty()).then(|| round2(total / days.len() as f64)),
+                days_within_budget: days.iter().filter(|c| c.within_budget).count() as i64,
+                biggest_day: days
+                    .iter()
+                    .filter(|c| c.amount > 0.0)
+                    .max_by(|a, b| a.amount.total_cmp(&b.amount))
+                    .map(|c| c.date.clone()),
+            }
+        })
+        .collect();
+
+    Ok(SpendingSummary {
+        daily_budget: daily_budget(pool).await?,
+        today: checkins.iter().find(|c| c.date == today.to_string()).cloned(),
+        current_streak,
+        best_streak,
+        within_budget_streak,
+        weeks,
+        wellness: wellness(pool, &in_window, start).await?,
+    })
+}
+
+/// Evening nudge while check-ins are a habit: set when today's is missing
+/// and one was logged within `PROMPT_LAPSE_DAYS`
+pub async fn evening_prompt(pool: &Pool<Sqlite>, today: NaiveDate) -> Result<Option<String>, ApiError> {
+    let last: Option<String> = sqlx::query_scalar("SELECT MAX(date) FROM spending_checkins")
+        .fetch_one(pool)
+        .await
+        .map_err(ApiError::from)?;
+    let Some(last) = last.and_then(|d| d.parse::<NaiveDate>().ok()) else {
+        return Ok(None);
+    };
+    if last >= today || (today - last).num_days() > PROMPT_LAPSE_DAYS {
+        return Ok(None);
+    }
+    let dates: Vec<NaiveDate> = sqlx::query_scalar::<_, String>("SELECT date FROM spending_checkins ORDER BY date")
+        .fetch_all(pool)
+        .await
+        .map_err(ApiError::from)?
+        .iter()
+        .filter_map(|d| d.parse().ok())
+        .collect();
+    let (streak, _) = trackers::runs(&dates, Duration::days(1), today);
+    Ok(Some(if streak > 0 {
+        format!("Log today's spending to keep your {}-day check-in streak", streak)
+    } else {
+        "Log today's spending before bed".to_string()
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn weekly_summary_streaks_and_mood_correlation() {
+        let pool = crate::test_support::pool().await;
+        let today = NaiveDate::from_ymd_opt(2030, 3, 14).unwrap(); // a Thursday
+        let input = |date: NaiveDate, amount: f64| SpendingCheckInInput {
+            date: Some(date.to_string()),
+            amount,
+            within_budget: None,
+            note: None,
+        };
+        assert!(check_in(&pool, &input(today, 10.0), today).await.is_err());
+        settings::set(&pool, "spending_daily_budget", serde_json::json!(30)).await.unwrap();
+        assert!(check_in(&pool, &input(today + Duration::days(1), 10.0), today).await.is_err());
+
+        // Ten days, mood falling as spending rises
+        for i in 0..10 {
+            let date = today - Duration::days(9 - i);
+            let amount = [5.0, 12.0, 50.0, 8.0, 20.0, 65.0, 15.0, 25.0, 40.0, 10.0][i as usize];
+            check_in(&pool, &input(date, amount), today).await.unwrap();
+            let mood = 9 - (amount / 10.0) as i64;
+            sqlx::query("INSERT INTO check_ins (mood, energy, checked_in_at) VALUES (?, 6, ?)")
+                .bind(mood)
+                .bind(format!("{} 12:00:00", date))
+                .execute(&pool)
+                .await
+                .unwrap();
+        }
+        // Replacing a day keeps one check-in for it
+        let replaced = check_in(&pool, &SpendingCheckInInput { within_budget: Some(false), ..input(today, 12.0) }, today)
+            .await
+            .unwrap();
+        assert!(!replaced.within_budget);
+
+        let summary = summary(&pool, 2, today).await.unwrap();
+        assert_eq!(summary.daily_budget, Some(30));
+        assert_eq!((summary.current_streak, summary.best_streak, summary.within_budget_streak), (10, 10, 0));
+        let this_week = &summary.weeks[0];
+        assert_eq!(this_week.week_start, "2030-03-11");
+        assert_eq!((this_week.days_logged, this_week.total, this_week.days_within_budget), (4, 92.0, 2));
+        assert_eq!(this_week.biggest_day.as_deref(), Some("2030-03-13"));
+        assert_eq!(summary.weeks[1].days_logged, 6);
+
+        let wellness = &summary.wellness;
+        assert_eq!(wellness.paired_days, 10);
+        assert!(wellness.mood_correlation.unwrap() < -0.9);
+        assert_eq!(wellness.energy_correlation, None);
+        assert!(wellness.insights[0].starts_with("You tend to spend more on lower-mood days"));
+
+        assert_eq!(evening_prompt(&pool, today).await.unwrap(), None);
+        assert_eq!(
+            evening_prompt(&pool, today + Duration::days(1)).await.unwrap().as_deref(),
+            Some("Log today's spending to keep your 10-day check-in streak")
+        );
+    }
+}
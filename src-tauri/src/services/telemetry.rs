@@ -0,0 +1,276 @@
+//! Local Telemetry
+//!
+//! Opt-in (`telemetry_enabled`), anonymous usage counts that never leave the
+//! device: how often each command is invoked and where errors are raised.
+//! Only names and counts are kept, no arguments or error messages.
+//!
+//! Errors are counted where an `ApiError` is built, keyed by the source
+//! module and error code (`courses:validation`), so errors that are handled
+//! internally count too. Counts collect in memory and `start` flushes them
+//! into `telemetry_counts`, one row per local day.
+
+use std::collections::HashMap;
+use std::panic::Location;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use once_cell::sync::Lazy;
+use parking_lot::Mutex;
+use serde::Serialize;
+use sqlx::{Pool, Sqlite};
+use tokio::sync::broadcast;
+
+use crate::{
+    error::{ApiError, ErrorCode},
+    services::{clock, settings},
+};
+
+const FLUSH_INTERVAL: Duration = Duration::from_secs(60);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+enum Kind {
+    Command,
+    Error,
+}
+
+impl Kind {
+    fn as_str(self) -> &'static str {
+        match self {
+            Kind::Command => "command",
+            Kind::Error => "error",
+        }
+    }
+}
+
+static ENABLED: AtomicBool = AtomicBool::new(false);
+
+/// Counts not yet written to the database
+static PENDING: Lazy<Mutex<HashMap<(Kind, String), i64>>> = Lazy::new(|| Mutex::new(HashMap::new()));
+
+pub fn is_enabled() -> bool {
+    ENABLED.load(Ordering::Relaxed)
+}
+
+/// Turning telemetry off drops counts not yet flushed
+pub fn set_enabled(enabled: bool) {
+    ENABLED.store(enabled, Ordering::Relaxed);
+    if !enabled {
+        PENDING.lock().clear();
+    }
+}
+
+/// Apply `telemetry_enabled` changes as they are saved
+pub fn follow_setting() {
+    let mut changes = settings::subscribe();
+    tauri::async_runtime::spawn(async move {
+        loop {
+            match changes.recv().await {
+                Ok(change) if change.key == "telemetry_enabled" => {
+                    set_enabled(change.value.as_bool().unwrap_or(false));
+                }
+                Ok(_) | Err(broadcast::error::RecvError::Lagged(_)) => {}
+                Err(broadcast::error::RecvError::Closed) => break,
+            }
+        }
+    });
+}
+
+fn bump(kind: Kind, name: String) {
+    *PENDING.lock().entry((kind, name)).or_insert(0) += 1;
+}
+
+/// Count one invocation of a command
+pub fn record_command(command: &str) {
+    if is_enabled() {
+        bump(Kind::Command, command.to_string());
+    }
+}
+
+/// Wrap the app's invoke handler so every command invocation is counted
+pub fn count_invocations<R: tauri::Runtime>(
+    handler: impl Fn(tauri::ipc::Invoke<R>) -> bool + Send + Sync + 'static,
+) -> impl Fn(tauri::ipc::Invoke<R>) -> bool + Send + Sync + 'static {
+    move |invoke| {
+        record_command(invoke.message.command());
+        handler(invoke)
+    }
+}
+
+/// Count an error raised at `location`
+pub fn record_error(code: &ErrorCode, location: &Location<'_>) {
+    if is_enabled() {
+        bump(Kind::Error, format!("{}:{}", module_of(location.file()), code.as_str()));
+    }
+}
+
+/// "src/commands/courses.rs" -> "courses"; "src/ml/bandit_v2.rs" -> "bandit_v2"
+fn module_of(file: &str) -> &str {
+    let name = file.rsplit(&['/', '\\'][..]).next().unwrap_or(file);
+    let stem = name.strip_suffix(".rs").unwrap_or(name);
+    if stem == "mod" {
+        // A module's mod.rs is named after its directory
+        file.rsplit(&['/', '\\'][..]).nth(1).unwrap_or(stem)
+    } else {
+        stem
+    }
+}
+
+/// Write pending counts to the database
+pub async fn flush(pool: &Pool<Sqlite>) -> Result<(), ApiError> {
+    let pending = std::mem::take(&mut *PENDING.lock());
+    if pending.is_empty() {
+        return Ok(());
+    }
+    let day = clock::now().format("%Y-%m-%d").to_string();
+
+    let mut tx = pool.begin().await?;
+    for ((kind, name), count) in &pending {
+        sqlx::query(
+            r#"
+            INSERT INTO telemetry_counts (day, kind, name, count) VALUES (?, ?, ?, ?)
+            ON CONFLICT(day, kind, name) DO UPDATE SET count = count + excluded.count
+            "#,
+        )
+        .bind(&day)
+        .bind(kind.as_str())
+        .bind(name)
+        .bind(*count)
+        .execute(&mut *tx)
+        .await?;
+    }
+    tx.commit().await?;
+    Ok(())
+}
+
+/// Read the setting and flush counts in the background
+pub fn start(pool: Pool<Sqlite>) {
+    tauri::async_runtime::spawn(async move {
+        match settings::get_bool(&pool, "telemetry_enabled").await {
+            Ok(enabled) => set_enabled(enabled),
+            Err(e) => log::warn!("Failed to read telemetry setting: {}", e.message),
+        }
+        loop {
+            tokio::time::sleep(FLUSH_INTERVAL).await;
+            if let Err(e) = flush(&pool).await {
+                log::warn!("Failed to flush telemetry: {}", e.message);
+            }
+        }
+    });
+}
+
+#[derive(Debug, Serialize)]
+pub struct UsageCount {
+    pub name: String,
+    pub count: i64,
+}
+
+#[derive(Debug, Serialize)]
+pub struct TelemetryReport {
+    pub enabled: bool,
+    /// First day included (local YYYY-MM-DD)
+    pub since: String,
+    /// Most used first
+    pub commands: Vec<UsageCount>,
+    /// "module:code", most frequent first
+    pub errors: Vec<UsageCount>,
+}
+
+/// Totals over the last `days` days, including counts not yet flushed
+pub async fn report(pool: &Pool<Sqlite>, days: i64) -> Result<TelemetryReport, ApiError> {
+    flush(pool).await?;
+    let since = (clock::now().date_naive() - chrono::Duration::days(days - 1))
+        .format("%Y-%m-%d")
+        .to_string();
+
+    let totals = |kind: Kind| {
+        sqlx::query_as::<_, (String, i64)>(
+            r#"
+            SELECT name, SUM(count) AS total FROM telemetry_counts
+            WHERE kind = ? AND day >= ?
+            GROUP BY name
+            ORDER BY total DESC, name
+            "#,
+        )
+        .bind(kind.as_str())
+        .bind(since.clone())
+        .fetch_all(pool)
+    };
+    let to_counts = |rows: Vec<(String, i64)>| -> Vec<UsageCount> {
+        rows.into_iter()
+            .map(|(name, count)| UsageCount { name, count })
+            .collect()
+    };
+
+    Ok(TelemetryReport {
+        enabled: is_enabled(),
+        commands: to_counts(totals(Kind::Command).await?),
+        errors: to_counts(totals(Kind::Error).await?),
+        since,
+    })
+}
+
+/// Delete every stored and pending count
+pub async fn clear(pool: &Pool<Sqlite>) -> Result<u64, ApiError> {
+    PENDING.lock().clear();
+    let result = sqlx::query("DELETE FROM telemetry_counts").execute(pool).await?;
+    Ok(result.rows_affected())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+    use std::str::FromStr;
+
+    async fn setup_pool_with_migrations() -> Pool<Sqlite> {
+        let options = SqliteConnectOptions::from_str("sqlite::memory:")
+            .unwrap()
+            .foreign_keys(false);
+        let pool = SqlitePoolOptions::new()
+            .max_connections(1)
+            .connect_with(options)
+            .await
+            .expect("Failed to connect to in-memory DB");
+
+        crate::db::migrations::run_migrations(&pool)
+            .await
+            .expect("Failed to run migrations");
+
+        pool
+    }
+
+    #[test]
+    fn errors_are_keyed_by_module() {
+        assert_eq!(module_of("src/commands/courses.rs"), "courses");
+        assert_eq!(module_of("src\\ml\\bandit_v2.rs"), "bandit_v2");
+        assert_eq!(module_of("src/commands/mod.rs"), "commands");
+    }
+
+    #[tokio::test]
+    async fn flushed_counts_accumulate_per_day() {
+        let pool = setup_pool_with_migrations().await;
+        // Other tests may raise errors while this one has telemetry on, so
+        // only this test's names are checked
+        set_enabled(true);
+        record_command("telemetry_test_command");
+        record_command("telemetry_test_command");
+        flush(&pool).await.unwrap();
+        record_command("telemetry_test_command");
+        bump(Kind::Error, "telemetry_test:validation".to_string());
+
+        let report = report(&pool, 7).await.unwrap();
+        set_enabled(false);
+
+        let count = |counts: &[UsageCount], name: &str| counts.iter().find(|c| c.name == name).map(|c| c.count);
+        assert_eq!(count(&report.commands, "telemetry_test_command"), Some(3));
+        assert_eq!(count(&report.errors, "telemetry_test:validation"), Some(1));
+
+        clear(&pool).await.unwrap();
+        assert!(report_is_empty(&pool).await);
+    }
+
+    async fn report_is_empty(pool: &Pool<Sqlite>) -> bool {
+        let report = report(pool, 7).await.unwrap();
+        report.commands.is_empty() && report.errors.is_empty()
+    }
+}
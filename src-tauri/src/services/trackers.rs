@@ -0,0 +1,555 @@
+//! Trackers
+//!
+//! The pattern behind courses, skills and workouts, reduced to data: a
+//! tracker is a life area logged as amounts in its own unit (minutes, pages,
+//! cards), optionally against entities such as a book, with a weekly target
+//! and a streak rule:
+//! - `daily`: consecutive days logging at least `streak_min` (any log when 0)
+//! - `weekly`: consecutive weeks meeting `weekly_target`
+//! - `none`: no streak
+//!
+//! A new life area is a `trackers` row plus whatever thin commands it needs
+//! on top of this module. Logged minutes count toward the tracker's domain in
+//! the life balance. Entities complete once their logs reach
+//! `target_amount`.
+
+use std::collections::BTreeMap;
+
+use chrono::{Duration, NaiveDate};
+use serde::{Deserialize, Serialize};
+use sqlx::{FromRow, Pool, Sqlite};
+
+use crate::{
+    error::ApiError,
+    models::session::is_slug,
+    services::{focus, life_balance},
+};
+
+const MAX_NAME_LENGTH: usize = 60;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StreakRule {
+    Daily,
+    Weekly,
+    None,
+}
+
+impl StreakRule {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            StreakRule::Daily => "daily",
+            StreakRule::Weekly => "weekly",
+            StreakRule::None => "none",
+        }
+    }
+
+    fn parse(value: &str) -> StreakRule {
+        match value {
+            "daily" => StreakRule::Daily,
+            "weekly" => StreakRule::Weekly,
+            _ => StreakRule::None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct Tracker {
+    pub id: i64,
+    pub slug: String,
+    pub name: String,
+    pub domain: String,
+    pub unit: String,
+    pub weekly_target: Option<f64>,
+    pub streak_rule: String,
+    pub streak_min: f64,
+    pub builtin: bool,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct TrackerEntity {
+    pub id: i64,
+    pub tracker_id: i64,
+    pub name: String,
+    pub target_amount: Option<f64>,
+    /// Amount logged against the entity so far
+    pub logged_amount: f64,
+    pub completed_at: Option<String>,
+    pub created_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, FromRow)]
+pub struct TrackerLog {
+    pub id: i64,
+    pub tracker_id: i64,
+    pub entity_id: Option<i64>,
+    pub amount: f64,
+    pub minutes: Option<i64>,
+    pub kind: Option<String>,
+    pub rating: Option<i64>,
+    pub note: Option<String>,
+    pub logged_at: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct TrackerSummary {
+    #[serde(flatten)]
+    pub tracker: Tracker,
+    /// Amount logged in the current week
+    pub week_amount: f64,
+    pub week_minutes: i64,
+    /// None without a weekly target
+    pub week_target_met: Option<bool>,
+    /// Days or weeks, per the streak rule
+    pub current_streak: i64,
+    pub best_streak: i64,
+    pub total_amount: f64,
+    pub last_logged_at: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TrackerInput {
+    pub name: String,
+    /// A life-balance domain
+    pub domain: String,
+    #[serde(default)]
+    pub unit: Option<String>,
+    #[serde(default)]
+    pub weekly_target: Option<f64>,
+    #[serde(default)]
+    pub streak_rule: Option<StreakRule>,
+    #[serde(default)]
+    pub streak_min: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TrackerEntityInput {
+    pub tracker_id: i64,
+    pub name: String,
+    #[serde(default)]
+    pub target_amount: Option<f64>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct TrackerLogInput {
+    pub tracker_id: i64,
+    #[serde(default)]
+    pub entity_id: Option<i64>,
+    pub amount: f64,
+    #[serde(default)]
+    pub minutes: Option<i64>,
+    #[serde(default)]
+    pub kind: Option<String>,
+    #[serde(default)]
+    pub rating: Option<i64>,
+    #[serde(default)]
+    pub note: Option<String>,
+    /// UTC SQLite timestamp; defaults to now
+    #[serde(default)]
+    pub logged_at: Option<String>,
+}
+
+const SELECT_TRACKER: &str =
+    "SELECT id, slug, name, domain, unit, weekly_target, streak_rule, streak_min, builtin, created_at FROM trackers";
+const SELECT_ENTITY: &str = r#"
+    SELECT e.id, e.tracker_id, e.name, e.target_amount,
+           COALESCE((SELECT SUM(l.amount) FROM tracker_logs l WHERE l.entity_id = e.id), 0.0) AS logged_amount,
+           e.completed_at, e.created_at
+    FROM tracker_entities e"#;
+const SELECT_LOG: &str =
+    "SELECT id, tracker_id, entity_id, amount, minutes, kind, rating, note, logged_at FROM tracker_logs";
+
+fn validate_name(name: &str, what: &str) -> Result<(), ApiError> {
+    if name.is_empty() || name.len() > MAX_NAME_LENGTH {
+        return Err(ApiError::validation(format!(
+            "{} name must be between 1 and {} characters",
+            what, MAX_NAME_LENGTH
+        )));
+    }
+    Ok(())
+}
+
+fn positive(value: Option<f64>, what: &str) -> Result<(), ApiError> {
+    match value {
+        Some(v) if !(v.is_finite() && v > 0.0) => Err(ApiError::validation(format!("{} must be greater than 0", what))),
+        _ => Ok(()),
+    }
+}
+
+/// Lowercase slug of a tracker name, e.g. "Language learning" -> "language_learning"
+fn slugify(name: &str) -> String {
+    let mut slug = String::new();
+    for c in name.trim().chars() {
+        if c.is_ascii_alphanumeric() {
+            slug.push(c.to_ascii_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('_') {
+            slug.push('_');
+        }
+    }
+    slug.trim_end_matches('_').chars().take(40).collect()
+}
+
+pub async fn get(pool: &Pool<Sqlite>, id: i64) -> Result<Tracker, ApiError> {
+    sqlx::query_as(&format!("{} WHERE id = ?", SELECT_TRACKER))
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+        .map_err(ApiError::from)?
+        .ok_or_else(|| ApiError::not_found("Tracker not found"))
+}
+
+/// Tracker by slug, for areas built on top of this module
+pub async fn by_slug(pool: &Pool<Sqlite>, slug: &str) -> Result<Tracker, ApiError> {
+    sqlx::query_as(&format!("{} WHERE slug = ?", SELECT_TRACKER))
+        .bind(slug)
+        .fetch_optional(pool)
+        .await
+        .map_err(ApiError::from)?
+        .ok_or_else(|| ApiError::not_found(format!("Tracker '{}' not found", slug)))
+}
+
+pub async fn create(pool: &Pool<Sqlite>, input: &TrackerInput) -> Result<Tracker, ApiError> {
+    let name = input.name.trim();
+    validate_name(name, "Tracker")?;
+    let slug = slugify(name);
+    if !is_slug(&slug) {
+        return Err(ApiError::validation("Tracker name must start with a letter"));
+    }
+    if !life_balance::DOMAINS.iter().any(|(domain, _)| *domain == input.domain) {
+        return Err(ApiError::validation(format!("Unknown domain '{}'", input.domain)));
+    }
+    positive(input.weekly_target, "Weekly target")?;
+    if input.streak_min.is_some_and(|m| !(m.is_finite() && m >= 0.0)) {
+        return Err(ApiError::validation("Streak minimum can't be negative"));
+    }
+    let streak_rule = input.streak_rule.unwrap_or(StreakRule::Daily);
+    if streak_rule == StreakRule::Weekly && input.weekly_target.is_none() {
+        return Err(ApiError::validation("A weekly streak needs a weekly target"));
+    }
+    let unit = input.unit.as_deref().map(str::trim).filter(|u| !u.is_empty()).unwrap_or("minutes");
+
+    let id: i64 = sqlx::query_scalar(
+        r#"INSERT INTO trackers (slug, name, domain, unit, weekly_target, streak_rule, streak_min)
+           VALUES (?, ?, ?, ?, ?, ?, ?) RETURNING id"#,
+    )
+    .bind(&slug)
+    .bind(name)
+    .bind(&input.domain)
+    .bind(unit)
+    .bind(input.weekly_target)
+    .bind(streak_rule.as_str())
+    .bind(input.streak_min.unwrap_or(0.0))
+    .fetch_one(pool)
+    .await
+    .map_err(|e| ApiError::from_sqlx(e, format!("A tracker named '{}' already exists", name)))?;
+    get(pool, id).await
+}
+
+/// Delete a user tracker with its entities and logs
+pub async fn delete(pool: &Pool<Sqlite>, id: i64) -> Result<bool, ApiError> {
+    if get(pool, id).await?.builtin {
+        return Err(ApiError::validation("Built-in trackers can't be deleted"));
+    }
+    let result = sqlx::query("DELETE FROM trackers WHERE id = ?")
+        .bind(id)
+        .execute(pool)
+        .await
+        .map_err(ApiError::from)?;
+    Ok(result.rows_affected() > 0)
+}
+
+pub async fn add_entity(pool: &Pool<Sqlite>, input: &TrackerEntityInput) -> Result<TrackerEntity, ApiError> {
+    let name = input.name.trim();
+    validate_name(name, "Entry")?;
+    positive(input.target_amount, "Target amount")?;
+    get(pool, input.tracker_id).await?;
+
+    let id: i64 = sqlx::query_scalar("INSERT INTO tracker_entities (tracker_id, name, target_amount) VALUES (?, ?, ?) RETURNING id")
+        .bind(input.tracker_id)
+        .bind(name)
+        .bind(input.target_amount)
+        .fetch_one(pool)
+        .await
+        .map_err(ApiError::from)?;
+    entity(pool, id).await
+}
+
+pub async fn entity(pool: &Pool<Sqlite>, id: i64) -> Result<TrackerEntity, ApiError> {
+    sqlx::query_as(&format!("{} WHERE e.id = ?", SELECT_ENTITY))
+        .bind(id)
+        .fetch_optional(pool)
+        .await
+        .map_err(ApiError::from)?
+        .ok_or_else(|| ApiError::not_found("Tracker entry not found"))
+}
+
+/// A tracker's entities, open ones first
+pub async fn entities(pool: &Pool<Sqlite>, tracker_id: i64, include_completed: bool) -> Result<Vec<TrackerEntity>, ApiError> {
+    sqlx::query_as(&format!(
+        "{} WHERE e.tracker_id = ? AND (? OR e.completed_at IS NULL) ORDER BY e.completed_at IS NOT NULL, e.created_at DESC, e.id DESC",
+        SELECT_ENTITY
+    ))
+    .bind(tracker_id)
+    .bind(include_completed)
+    .fetch_all(pool)
+    .await
+    .map_err(ApiError::from)
+}
+
+pub async fn delete_entity(pool: &Pool<Sqlite>, id: i64) -> Result<bool, ApiError> {
+    let result = sqlx::query("DELETE FROM tracker_entities WHERE id = ?")
+        .bind(id)
+        .execute(pool)
+        .await
+        .map_err(ApiError::from)?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Log an amount; completes the entity once its logs reach its target
+pub async fn record(pool: &Pool<Sqlite>, input: &TrackerLogInput) -> Result<TrackerLog, ApiError> {
+    if !(input.amount.is_finite() && input.amount >= 0.0) {
+        return Err(ApiError::validation("Amount can't be negative"));
+    }
+    if input.minutes.is_some_and(|m| m < 0) {
+        return Err(ApiError::validation("Minutes can't be negative"));
+    }
+    if input.rating.is_some_and(|r| !(1..=5).contains(&r)) {
+        return Err(ApiError::validation("Rating must be between 1 and 5"));
+    }
+    get(pool, input.tracker_id).await?;
+    if let Some(entity_id) = input.entity_id {
+        if entity(pool, entity_id).await?.tracker_id != input.tracker_id {
+            return Err(ApiError::validation("The entry belongs to another tracker"));
+        }
+    }
+
+    let log: TrackerLog = sqlx::query_as(
+        r#"INSERT INTO tracker_logs (tracker_id, entity_id, amount, minutes, kind, rating, note, logged_at)
+           VALUES (?, ?, ?, ?, ?, ?, ?, COALESCE(?, datetime('now')))
+           RETURNING id, tracker_id, entity_id, amount, minutes, kind, rating, note, logged_at"#,
+    )
+    .bind(input.tracker_id)
+    .bind(input.entity_id)
+    .bind(input.amount)
+    .bind(input.minutes)
+    .bind(input.kind.as_deref().map(str::trim).filter(|k| !k.is_empty()))
+    .bind(input.rating)
+    .bind(input.note.as_deref().map(str::trim).filter(|n| !n.is_empty()))
+    .bind(&input.logged_at)
+    .fetch_one(pool)
+    .await
+    .map_err(ApiError::from)?;
+
+    if let Some(entity_id) = log.entity_id {
+        sqlx::query(
+            r#"UPDATE tracker_entities SET completed_at = ?
+               WHERE id = ? AND completed_at IS NULL AND target_amount IS NOT NULL
+                 AND (SELECT SUM(amount) FROM tracker_logs WHERE entity_id = tracker_entities.id) >= target_amount"#,
+        )
+        .bind(&log.logged_at)
+        .bind(entity_id)
+        .execute(pool)
+        .await
+        .map_err(ApiError::from)?;
+    }
+    Ok(log)
+}
+
+/// A tracker's logs, newest first
+pub async fn logs(pool: &Pool<Sqlite>, tracker_id: i64, limit: i64) -> Result<Vec<TrackerLog>, ApiError> {
+    sqlx::query_as(&format!("{} WHERE tracker_id = ? ORDER BY logged_at DESC, id DESC LIMIT ?", SELECT_LOG))
+        .bind(tracker_id)
+        .bind(limit)
+        .fetch_all(pool)
+        .await
+        .map_err(ApiError::from)
+}
+
+pub async fn delete_log(pool: &Pool<Sqlite>, id: i64) -> Result<bool, ApiError> {
+    let result = sqlx::query("DELETE FROM tracker_logs WHERE id = ?")
+        .bind(id)
+        .execute(pool)
+        .await
+        .map_err(ApiError::from)?;
+    Ok(result.rows_affected() > 0)
+}
+
+/// Current and best run of consecutive `periods` (sorted ascending) that
+/// `step` apart; the current run must reach `latest` or the period before it
+pub(crate) fn runs(periods: &[NaiveDate], step: Duration, latest: NaiveDate) -> (i64, i64) {
+    let (mut best, mut run) = (0, 0);
+    let mut previous: Option<NaiveDate> = None;
+    for &period in periods {
+        run = if previous == Some(period - step) { run + 1 } else { 1 };
+        best = best.max(run);
+        previous = Some(period);
+    }
+    let current = match previous {
+        Some(last) if last == latest || last == latest - step => run,
+        _ => 0,
+    };
+    (current, best)
+}
+
+/// Week figures and streaks of one tracker as of `today`
+pub async fn summary(pool: &Pool<Sqlite>, tracker: Tracker, today: NaiveDate) -> Result<TrackerSummary, ApiError> {
+    let daily: Vec<(String, f64, i64)> = sqlx::query_as(
+        r#"SELECT date(logged_at, 'localtime') AS day, SUM(amount), COALESCE(SUM(minutes), 0)
+           FROM tracker_logs WHERE tracker_id = ?
+           GROUP BY day ORDER BY day"#,
+    )
+    .bind(tracker.id)
+    .fetch_all(pool)
+    .await
+    .map_err(ApiError::from)?;
+    let last_logged_at: Option<String> = sqlx::query_scalar("SELECT MAX(logged_at) FROM tracker_logs WHERE tracker_id = ?")
+        .bind(tracker.id)
+        .fetch_one(pool)
+        .await
+        .map_err(ApiError::from)?;
+
+    let days: Vec<(NaiveDate, f64, i64)> = daily
+        .into_iter()
+        .filter_map(|(day, amount, minutes)| Some((NaiveDate::parse_from_str(&day, "%Y-%m-%d").ok()?, amount, minutes)))
+        .collect();
+
+    let week_start = focus::week_start_for(pool, today).await?;
+    // Minutes are the amount itself for minute-based trackers
+    let minutes_of = |amount: f64, minutes: i64| if tracker.unit == "minutes" { amount.round() as i64 } else { minutes };
+    let (mut week_amount, mut week_minutes) = (0.0, 0);
+    let mut weeks: BTreeMap<NaiveDate, f64> = BTreeMap::new();
+    for &(day, amount, minutes) in &days {
+        if day >= week_start && day <= today {
+            week_amount += amount;
+            week_minutes += minutes_of(amount, minutes);
+        }
+        let day_week_start = day - Duration::days((day - week_start).num_days().rem_euclid(7));
+        *weeks.entry(day_week_start).or_default() += amount;
+    }
+
+    let (current_streak, best_streak) = match StreakRule::parse(&tracker.streak_rule) {
+        StreakRule::Daily => {
+            let met: Vec<NaiveDate> = days
+                .iter()
+                .filter(|(_, amount, _)| *amount >= tracker.streak_min && (tracker.streak_min > 0.0 || *amount > 0.0))
+                .map(|(day, _, _)| *day)
+                .collect();
+            runs(&met, Duration::days(1), today)
+        }
+        StreakRule::Weekly => {
+            let target = tracker.weekly_target.unwrap_or(f64::INFINITY);
+            let met: Vec<NaiveDate> = weeks.iter().filter(|(_, amount)| **amount >= target).map(|(week, _)| *week).collect();
+            runs(&met, Duration::weeks(1), week_start)
+        }
+        StreakRule::None => (0, 0),
+    };
+
+    Ok(TrackerSummary {
+        week_amount: (week_amount * 10.0).round() / 10.0,
+        week_minutes,
+        week_target_met: tracker.weekly_target.map(|target| week_amount >= target),
+        current_streak,
+        best_streak,
+        total_amount: (days.iter().map(|(_, amount, _)| amount).sum::<f64>() * 10.0).round() / 10.0,
+        last_logged_at,
+        tracker,
+    })
+}
+
+/// Every tracker with its summary, built-in ones first
+pub async fn summaries(pool: &Pool<Sqlite>, today: NaiveDate) -> Result<Vec<TrackerSummary>, ApiError> {
+    let trackers: Vec<Tracker> = sqlx::query_as(&format!("{} ORDER BY builtin DESC, name", SELECT_TRACKER))
+        .fetch_all(pool)
+        .await
+        .map_err(ApiError::from)?;
+    let mut summaries = Vec::with_capacity(trackers.len());
+    for tracker in trackers {
+        summaries.push(summary(pool, tracker, today).await?);
+    }
+    Ok(summaries)
+}
+
+/// Minutes logged by trackers in `domain` between two local dates
+pub async fn domain_minutes(pool: &Pool<Sqlite>, domain: &str, start: NaiveDate, end: NaiveDate) -> Result<f64, ApiError> {
+    let minutes: f64 = sqlx::query_scalar(
+        r#"SELECT COALESCE(SUM(CASE WHEN t.unit = 'minutes' THEN l.amount ELSE COALESCE(l.minutes, 0) END), 0.0)
+           FROM tracker_logs l JOIN trackers t ON t.id = l.tracker_id
+           WHERE t.domain = ? AND date(l.logged_at, 'localtime') BETWEEN ? AND ?"#,
+    )
+    .bind(domain)
+    .bind(start.to_string())
+    .bind(end.to_string())
+    .fetch_one(pool)
+    .await
+    .map_err(ApiError::from)?;
+    Ok(minutes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn runs_count_consecutive_periods_ending_now_or_just_before() {
+        let day = |d: u32| NaiveDate::from_ymd_opt(2030, 6, d).unwrap();
+        let days = [day(1), day(2), day(3), day(6), day(7)];
+        assert_eq!(runs(&days, Duration::days(1), day(8)), (2, 3));
+        assert_eq!(runs(&days, Duration::days(1), day(9)), (0, 3));
+        assert_eq!(slugify("  Language learning!"), "language_learning");
+    }
+
+    #[tokio::test]
+    async fn a_tracker_logs_against_entities_and_targets() {
+        let pool = crate::test_support::pool().await;
+        let tracker = create(
+            &pool,
+            &TrackerInput {
+                name: "Reading".to_string(),
+                domain: "wellness".to_string(),
+                unit: Some("pages".to_string()),
+                weekly_target: Some(50.0),
+                streak_rule: Some(StreakRule::Daily),
+                streak_min: Some(10.0),
+            },
+        )
+        .await
+        .unwrap();
+        assert_eq!(tracker.slug, "reading");
+        assert!(create(&pool, &TrackerInput { name: "reading".to_string(), domain: "wellness".to_string(), unit: None, weekly_target: None, streak_rule: None, streak_min: None }).await.is_err());
+
+        let book = add_entity(&pool, &TrackerEntityInput { tracker_id: tracker.id, name: "Dune".to_string(), target_amount: Some(60.0) })
+            .await
+            .unwrap();
+        // Tue-Thu of a Monday week; the 5-page day misses the streak minimum
+        for (day, pages) in [("2030-06-04", 20.0), ("2030-06-05", 5.0), ("2030-06-06", 15.0), ("2030-06-07", 25.0)] {
+            record(
+                &pool,
+                &TrackerLogInput {
+                    tracker_id: tracker.id,
+                    entity_id: Some(book.id),
+                    amount: pages,
+                    minutes: Some(30),
+                    logged_at: Some(format!("{} 12:00:00", day)),
+                    ..Default::default()
+                },
+            )
+            .await
+            .unwrap();
+        }
+
+        let book = entity(&pool, book.id).await.unwrap();
+        assert_eq!(book.logged_amount, 65.0);
+        assert!(book.completed_at.is_some());
+
+        let summary = summary(&pool, tracker, NaiveDate::from_ymd_opt(2030, 6, 7).unwrap()).await.unwrap();
+        assert_eq!((summary.week_amount, summary.week_minutes, summary.week_target_met), (65.0, 120, Some(true)));
+        assert_eq!((summary.current_streak, summary.best_streak), (2, 2));
+        assert_eq!(
+            domain_minutes(&pool, "wellness", NaiveDate::from_ymd_opt(2030, 6, 1).unwrap(), NaiveDate::from_ymd_opt(2030, 6, 7).unwrap())
+                .await
+                .unwrap(),
+            120.0
+        );
+    }
+}
@@ -0,0 +1,323 @@
+//! Working Hours and Blackout Windows
+//!
+//! `working_hours` bounds when anything may be scheduled on each weekday
+//! (null means the day is off). `blackout_windows` are do-not-schedule
+//! ranges on top of that, such as dinner or a commute; a window whose end is
+//! before its start runs past midnight. Blackouts also act as quiet hours:
+//! reminders the app schedules are moved out of them.
+//!
+//! Both are settings; `Schedule` is their parsed form, working in naive local
+//! time.
+
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use sqlx::{Pool, Sqlite};
+
+use crate::{error::ApiError, services::settings, utils::is_valid_time};
+
+/// Indexed by `num_days_from_sunday`
+pub const WEEKDAYS: [&str; 7] = ["sunday", "monday", "tuesday", "wednesday", "thursday", "friday", "saturday"];
+
+const MAX_BLACKOUT_WINDOWS: usize = 50;
+const MAX_LABEL_LENGTH: usize = 100;
+
+/// A local time range, "HH:MM" (24-hour)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct TimeRange {
+    pub start: String,
+    pub end: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BlackoutWindow {
+    #[serde(default)]
+    pub label: Option<String>,
+    /// Weekday names; empty means every day
+    #[serde(default)]
+    pub days: Vec<String>,
+    pub start: String,
+    pub end: String,
+}
+
+type Interval = (NaiveDateTime, NaiveDateTime);
+
+#[derive(Debug, Clone)]
+struct Blackout {
+    days: [bool; 7],
+    start: NaiveTime,
+    end: NaiveTime,
+}
+
+/// Parsed working hours and blackout windows
+#[derive(Debug, Clone)]
+pub struct Schedule {
+    hours: [Option<(NaiveTime, NaiveTime)>; 7],
+    blackouts: Vec<Blackout>,
+}
+
+fn parse_time(value: &str) -> Option<NaiveTime> {
+    if !is_valid_time(value) {
+        return None;
+    }
+    NaiveTime::parse_from_str(value, "%H:%M").ok()
+}
+
+fn weekday_index(name: &str) -> Option<usize> {
+    WEEKDAYS.iter().position(|d| d.eq_ignore_ascii_case(name.trim()))
+}
+
+/// Check a `working_hours` value: an object with every weekday, each
+/// null or a range with start before end
+pub fn validate_working_hours(value: Value) -> Result<Value, ApiError> {
+    let days: serde_json::Map<String, Value> = serde_json::from_value(value)
+        .map_err(|_| ApiError::validation("Working hours must be an object keyed by weekday"))?;
+    if let Some(unknown) = days.keys().find(|k| weekday_index(k).is_none()) {
+        return Err(ApiError::validation(format!("Unknown weekday in working hours: {}", unknown)));
+    }
+
+    let mut normalized = serde_json::Map::new();
+    for day in WEEKDAYS {
+        let entry = days
+            .iter()
+            .find(|(k, _)| weekday_index(k) == weekday_index(day))
+            .map(|(_, v)| v.clone())
+            .unwrap_or(Value::Null);
+        if entry.is_null() {
+            normalized.insert(day.to_string(), Value::Null);
+            continue;
+        }
+        let range: TimeRange = serde_json::from_value(entry)
+            .map_err(|_| ApiError::validation(format!("Working hours for {} must have start and end", day)))?;
+        match (parse_time(&range.start), parse_time(&range.end)) {
+            (Some(start), Some(end)) if start < end => {}
+            _ => {
+                return Err(ApiError::validation(format!(
+                    "Working hours for {} must be HH:MM times with start before end",
+                    day
+                )))
+            }
+        }
+        normalized.insert(day.to_string(), serde_json::json!(range));
+    }
+    Ok(Value::Object(normalized))
+}
+
+/// Check a `blackout_windows` value
+pub fn validate_blackout_windows(value: Value) -> Result<Value, ApiError> {
+    let windows: Vec<BlackoutWindow> = serde_json::from_value(value)
+        .map_err(|e| ApiError::validation(format!("Invalid blackout windows: {}", e)))?;
+    if windows.len() > MAX_BLACKOUT_WINDOWS {
+        return Err(ApiError::validation(format!("At most {} blackout windows", MAX_BLACKOUT_WINDOWS)));
+    }
+
+    let mut normalized = Vec::with_capacity(windows.len());
+    for window in windows {
+        let (start, end) = (parse_time(&window.start), parse_time(&window.end));
+        if start.is_none() || end.is_none() || start == end {
+            return Err(ApiError::validation(
+                "Blackout windows must be HH:MM times with different start and end",
+            ));
+        }
+        let mut days = Vec::new();
+        for day in &window.days {
+            let index = weekday_index(day)
+                .ok_or_else(|| ApiError::validation(format!("Unknown weekday in blackout window: {}", day)))?;
+            days.push(index);
+        }
+        days.sort_unstable();
+        days.dedup();
+        let label = window
+            .label
+            .map(|l| l.trim().to_string())
+            .filter(|l| !l.is_empty());
+        if label.as_ref().is_some_and(|l| l.chars().count() > MAX_LABEL_LENGTH) {
+            return Err(ApiError::validation(format!(
+                "Blackout labels must be at most {} characters",
+                MAX_LABEL_LENGTH
+            )));
+        }
+        normalized.push(BlackoutWindow {
+            label,
+            days: days.into_iter().map(|i| WEEKDAYS[i].to_string()).collect(),
+            ..window
+        });
+    }
+    serde_json::to_value(normalized).map_err(|e| ApiError::internal(e.to_string()))
+}
+
+impl Schedule {
+    /// Build from already validated setting values
+    pub fn from_settings(working_hours: &Value, blackout_windows: &Value) -> Self {
+        let mut hours = [None; 7];
+        for (i, day) in WEEKDAYS.iter().enumerate() {
+            hours[i] = working_hours
+                .get(day)
+                .and_then(|v| serde_json::from_value::<TimeRange>(v.clone()).ok())
+                .and_then(|r| Some((parse_time(&r.start)?, parse_time(&r.end)?)));
+        }
+
+        let windows: Vec<BlackoutWindow> = serde_json::from_value(blackout_windows.clone()).unwrap_or_default();
+        let blackouts = windows
+            .iter()
+            .filter_map(|w| {
+                let mut days = [w.days.is_empty(); 7];
+                for index in w.days.iter().filter_map(|d| weekday_index(d)) {
+                    days[index] = true;
+                }
+                Some(Blackout {
+                    days,
+                    start: parse_time(&w.start)?,
+                    end: parse_time(&w.end)?,
+                })
+            })
+            .collect();
+
+        Self { hours, blackouts }
+    }
+
+    pub async fn load(pool: &Pool<Sqlite>) -> Result<Self, ApiError> {
+        Ok(Self::from_settings(
+            &settings::get(pool, "working_hours").await?,
+            &settings::get(pool, "blackout_windows").await?,
+        ))
+    }
+
+    /// Blackout intervals touching `date`, including overnight windows
+    /// that started the day before
+    fn blackouts_on(&self, date: NaiveDate) -> Vec<Interval> {
+        let weekday = date.weekday().num_days_from_sunday() as usize;
+        let previous = (weekday + 6) % 7;
+        let midnight = date.and_time(NaiveTime::MIN);
+        let next_midnight = midnight + Duration::days(1);
+
+        let mut intervals = Vec::new();
+        for b in &self.blackouts {
+            if b.start < b.end {
+                if b.days[weekday] {
+                    intervals.push((date.and_time(b.start), date.and_time(b.end)));
+                }
+            } else {
+                if b.days[weekday] {
+                    intervals.push((date.and_time(b.start), next_midnight));
+                }
+                if b.days[previous] {
+                    intervals.push((midnight, date.and_time(b.end)));
+                }
+            }
+        }
+        intervals
+    }
+
+    /// Time on `date` that may be scheduled: working hours minus blackouts
+    pub fn schedulable(&self, date: NaiveDate) -> Vec<Interval> {
+        let weekday = date.weekday().num_days_from_sunday() as usize;
+        match self.hours[weekday] {
+            Some((start, end)) => subtract(&[(date.and_time(start), date.and_time(end))], &self.blackouts_on(date)),
+            None => Vec::new(),
+        }
+    }
+
+    /// `at`, or the end of the blackout(s) it falls in
+    pub fn quiet_until(&self, at: NaiveDateTime) -> NaiveDateTime {
+        let mut at = at;
+        // Windows can chain into each other; a week of them is everything there is
+        for _ in 0..(self.blackouts.len() * 8) {
+            match self.blackouts_on(at.date()).into_iter().find(|(s, e)| *s <= at && at < *e) {
+                Some((_, end)) => at = end,
+                None => break,
+            }
+        }
+        at
+    }
+}
+
+/// `intervals` with every `remove` interval cut out
+pub fn subtract(intervals: &[Interval], remove: &[Interval]) -> Vec<Interval> {
+    let mut remaining: Vec<Interval> = intervals.to_vec();
+    for (cut_start, cut_end) in remove {
+        remaining = remaining
+            .into_iter()
+            .flat_map(|(start, end)| {
+                if *cut_end <= start || end <= *cut_start {
+                    return vec![(start, end)];
+                }
+                let mut parts = Vec::new();
+                if start < *cut_start {
+                    parts.push((start, *cut_start));
+                }
+                if *cut_end < end {
+                    parts.push((*cut_end, end));
+                }
+                parts
+            })
+            .collect();
+    }
+    remaining
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn at(date: &str, time: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(&format!("{} {}", date, time), "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    fn schedule() -> Schedule {
+        let hours = validate_working_hours(json!({
+            "monday": { "start": "09:00", "end": "18:00" },
+            "Tuesday": { "start": "06:00", "end": "23:00" },
+        }))
+        .unwrap();
+        let blackouts = validate_blackout_windows(json!([
+            { "label": "Lunch", "days": ["monday", "Monday"], "start": "12:00", "end": "13:00" },
+            { "label": "Night", "start": "22:00", "end": "07:00" },
+        ]))
+        .unwrap();
+        Schedule::from_settings(&hours, &blackouts)
+    }
+
+    #[test]
+    fn validation_normalizes_and_rejects_bad_ranges() {
+        let hours = validate_working_hours(json!({ "friday": { "start": "08:00", "end": "12:00" } })).unwrap();
+        assert_eq!(hours["sunday"], Value::Null);
+        assert_eq!(hours["friday"]["start"], "08:00");
+        assert!(validate_working_hours(json!({ "friday": { "start": "12:00", "end": "08:00" } })).is_err());
+        assert!(validate_working_hours(json!({ "funday": null })).is_err());
+
+        let windows = validate_blackout_windows(json!([{ "days": ["Monday", "monday"], "start": "12:00", "end": "13:00", "label": " " }])).unwrap();
+        assert_eq!(windows[0]["days"], json!(["monday"]));
+        assert_eq!(windows[0]["label"], Value::Null);
+        assert!(validate_blackout_windows(json!([{ "start": "12:00", "end": "12:00" }])).is_err());
+        assert!(validate_blackout_windows(json!([{ "start": "3am", "end": "04:00" }])).is_err());
+    }
+
+    #[test]
+    fn schedulable_time_excludes_blackouts_and_days_off() {
+        let schedule = schedule();
+        // 2026-03-02 is a Monday
+        let monday = NaiveDate::from_ymd_opt(2026, 3, 2).unwrap();
+        assert_eq!(
+            schedule.schedulable(monday),
+            vec![(at("2026-03-02", "09:00"), at("2026-03-02", "12:00")), (at("2026-03-02", "13:00"), at("2026-03-02", "18:00"))]
+        );
+        // Tuesday's early start and late end are cut by the overnight window
+        assert_eq!(
+            schedule.schedulable(monday + Duration::days(1)),
+            vec![(at("2026-03-03", "07:00"), at("2026-03-03", "22:00"))]
+        );
+        assert!(schedule.schedulable(monday - Duration::days(1)).is_empty());
+    }
+
+    #[test]
+    fn quiet_hours_follow_blackouts() {
+        let schedule = schedule();
+        assert_eq!(schedule.quiet_until(at("2026-03-03", "03:00")), at("2026-03-03", "07:00"));
+        assert_eq!(schedule.quiet_until(at("2026-03-03", "08:00")), at("2026-03-03", "08:00"));
+        assert_eq!(schedule.quiet_until(at("2026-03-02", "23:30")), at("2026-03-03", "07:00"));
+        assert_eq!(schedule.quiet_until(at("2026-03-02", "12:15")), at("2026-03-02", "13:00"));
+        assert_eq!(schedule.quiet_until(at("2026-03-02", "10:00")), at("2026-03-02", "10:00"));
+    }
+}
@@ -0,0 +1,192 @@
+//! Test Harness
+//!
+//! Shared setup for tests that drive several commands in sequence. Each
+//! command keeps its body in a function that takes the pool (`*_inner`, or
+//! `insert_*` / `compute_*` where the HTTP API shares it), so tests run the
+//! same code the frontend reaches without a Tauri runtime.
+//!
+//! `pool()` is a fresh in-memory database with every migration applied and
+//! the default user present. The fixture builders fill in sensible defaults
+//! and go through the command functions, so fixtures are validated exactly
+//! like user input.
+
+use chrono::{Duration, Utc};
+use sqlx::sqlite::{SqliteConnectOptions, SqlitePoolOptions};
+use sqlx::{Pool, Sqlite};
+use std::str::FromStr;
+
+use crate::{
+    commands::{
+        course_meetings::{create_course_meeting_inner, CourseMeetingInput},
+        courses::{insert_course, CourseInput},
+        sessions::{close_session, insert_session, SessionInput},
+        week_plan_blocks::WeekPlanBlockInput,
+    },
+    models::{course::Course, course_meeting::CourseMeeting, session::{Session, SessionType}},
+};
+
+/// In-memory database with migrations and the default user
+///
+/// One connection only: every connection to `sqlite::memory:` would be a
+/// separate, empty database.
+pub async fn pool() -> Pool<Sqlite> {
+    let options = SqliteConnectOptions::from_str("sqlite::memory:").expect("Invalid sqlite URL");
+    let pool = SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect_with(options)
+        .await
+        .expect("Failed to connect to in-memory DB");
+
+    crate::db::migrations::run_migrations(&pool)
+        .await
+        .expect("Failed to run migrations");
+    crate::db::connection::ensure_default_user(&pool)
+        .await
+        .expect("Failed to create default user");
+
+    pool
+}
+
+/// Builder for a course, created through `insert_course`
+pub struct CourseFixture {
+    input: CourseInput,
+}
+
+pub fn course(name: &str) -> CourseFixture {
+    CourseFixture {
+        input: CourseInput {
+            user_id: None,
+            name: Some(name.to_string()),
+            code: None,
+            color: None,
+            credit_hours: None,
+            target_weekly_hours: None,
+            is_active: None,
+            current_grade: None,
+            target_grade: None,
+            apply_meeting_pattern: None,
+        },
+    }
+}
+
+impl CourseFixture {
+    pub fn code(mut self, code: &str) -> Self {
+        self.input.code = Some(code.to_string());
+        self
+    }
+
+    pub fn target_weekly_hours(mut self, hours: f64) -> Self {
+        self.input.target_weekly_hours = Some(hours);
+        self
+    }
+
+    pub async fn create(self, pool: &Pool<Sqlite>) -> Course {
+        insert_course(pool, self.input).await.expect("Failed to create course fixture")
+    }
+}
+
+/// Builder for a weekly course meeting, created through `create_course_meeting_inner`
+pub struct MeetingFixture {
+    input: CourseMeetingInput,
+}
+
+/// A lecture on `day_of_week` (0 = Sunday) from `start` to `end` ("HH:MM")
+pub fn meeting(course_id: i64, day_of_week: i64, start: &str, end: &str) -> MeetingFixture {
+    MeetingFixture {
+        input: CourseMeetingInput {
+            course_id,
+            day_of_week,
+            start_time: start.to_string(),
+            end_time: end.to_string(),
+            location: None,
+            meeting_type: None,
+        },
+    }
+}
+
+impl MeetingFixture {
+    pub async fn create(self, pool: &Pool<Sqlite>) -> CourseMeeting {
+        create_course_meeting_inner(pool, self.input)
+            .await
+            .expect("Failed to create course meeting fixture")
+    }
+}
+
+/// Input for a suggested plan block; pass a batch to `bulk_create_plan_blocks_inner`
+pub fn plan_block(week_start_date: &str, start_at: &str, end_at: &str, course_id: Option<i64>) -> WeekPlanBlockInput {
+    WeekPlanBlockInput {
+        user_id: None,
+        week_start_date: week_start_date.to_string(),
+        start_at: start_at.to_string(),
+        end_at: end_at.to_string(),
+        block_type: "study".to_string(),
+        course_id,
+        weekly_task_id: None,
+        title: None,
+        status: None,
+        rationale_json: None,
+        assignment_id: None,
+        exam_id: None,
+        split_group: None,
+        skill_id: None,
+        workout_template_id: None,
+    }
+}
+
+/// Builder for a session, started through `insert_session`
+pub struct SessionFixture {
+    input: SessionInput,
+    minutes_ago: Option<i64>,
+}
+
+/// A study session on a course
+pub fn study_session(course_id: i64) -> SessionFixture {
+    SessionFixture {
+        input: SessionInput {
+            user_id: None,
+            session_type: SessionType::Study,
+            reference_id: Some(course_id),
+            reference_type: Some("course".to_string()),
+            started_at: None,
+            notes: None,
+            planned_minutes: None,
+            focus_profile_id: None,
+            title: None,
+            course_meeting_id: None,
+            meeting_date: None,
+            week_plan_block_id: None,
+        },
+        minutes_ago: None,
+    }
+}
+
+impl SessionFixture {
+    /// Start the session this many minutes before now
+    pub fn started_minutes_ago(mut self, minutes: i64) -> Self {
+        self.minutes_ago = Some(minutes);
+        self
+    }
+
+    pub fn planned_minutes(mut self, minutes: i64) -> Self {
+        self.input.planned_minutes = Some(minutes);
+        self
+    }
+
+    /// Start the session and leave it running
+    pub async fn start(mut self, pool: &Pool<Sqlite>) -> Session {
+        if let Some(minutes) = self.minutes_ago {
+            // Same format as CURRENT_TIMESTAMP, which the duration math expects
+            let started = Utc::now() - Duration::minutes(minutes);
+            self.input.started_at = Some(started.format("%Y-%m-%d %H:%M:%S").to_string());
+        }
+        insert_session(pool, &self.input).await.expect("Failed to start session fixture")
+    }
+
+    /// Start the session and end it now, as `end_session` does
+    pub async fn complete(self, pool: &Pool<Sqlite>, focus_rating: Option<i64>) -> Session {
+        let session = self.start(pool).await;
+        close_session(pool, session.id, focus_rating)
+            .await
+            .expect("Failed to end session fixture")
+    }
+}
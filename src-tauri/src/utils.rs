@@ -1,4 +1,4 @@
-use chrono::TimeZone;
+use chrono::{LocalResult, Offset, TimeZone};
 
 pub fn is_valid_time(time: &str) -> bool {
     if time.len() != 5 {
@@ -25,36 +25,141 @@ pub fn is_valid_time(time: &str) -> bool {
 }
 
 pub fn parse_datetime_to_rfc3339(value: &str) -> Option<String> {
+    parse_datetime_in(value, &chrono::Local)
+}
+
+/// `parse_datetime_to_rfc3339` with naive values read in `tz`
+pub(crate) fn parse_datetime_in<Tz: TimeZone>(value: &str, tz: &Tz) -> Option<String>
+where
+    Tz::Offset: std::fmt::Display,
+{
     if let Ok(dt) = chrono::DateTime::parse_from_rfc3339(value) {
         return Some(dt.to_rfc3339());
     }
 
     if let Ok(naive) = chrono::NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M:%S") {
-        if let Some(local) = chrono::Local.from_local_datetime(&naive).single() {
-            return Some(local.to_rfc3339());
-        }
+        return resolve_local(tz, naive).map(|dt| dt.to_rfc3339());
     }
 
     if let Ok(naive) = chrono::NaiveDateTime::parse_from_str(value, "%Y-%m-%dT%H:%M") {
-        if let Some(local) = chrono::Local.from_local_datetime(&naive).single() {
-            return Some(local.to_rfc3339());
-        }
+        return resolve_local(tz, naive).map(|dt| dt.to_rfc3339());
     }
 
     if let Ok(date) = chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d") {
         let naive = date.and_hms_opt(0, 0, 0)?;
-        if let Some(local) = chrono::Local.from_local_datetime(&naive).single() {
-            return Some(local.to_rfc3339());
-        }
+        return resolve_local(tz, naive).map(|dt| dt.to_rfc3339());
     }
 
     None
 }
 
+/// Place a wall-clock time in `tz`. When clocks go back the earlier of the
+/// two instants is used; a time skipped when clocks go forward is moved
+/// forward by the size of the gap (02:30 becomes 03:30).
+fn resolve_local<Tz: TimeZone>(tz: &Tz, naive: chrono::NaiveDateTime) -> Option<chrono::DateTime<Tz>> {
+    match tz.from_local_datetime(&naive) {
+        LocalResult::Single(dt) => Some(dt),
+        LocalResult::Ambiguous(earliest, _) => Some(earliest),
+        LocalResult::None => {
+            // Read the time with the offset in effect before the gap
+            let before = tz.from_local_datetime(&(naive - chrono::Duration::hours(3))).earliest()?;
+            let offset = before.offset().fix().local_minus_utc() as i64;
+            Some(tz.from_utc_datetime(&(naive - chrono::Duration::seconds(offset))))
+        }
+    }
+}
+
+/// Parse a SQLite UTC timestamp ("YYYY-MM-DD HH:MM:SS") or anything
+/// `parse_datetime_to_rfc3339` accepts (naive values are local time)
+pub fn parse_datetime_utc(value: &str) -> Option<chrono::DateTime<chrono::Utc>> {
+    if let Ok(naive) = chrono::NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S") {
+        return Some(naive.and_utc());
+    }
+    let normalized = parse_datetime_to_rfc3339(value)?;
+    chrono::DateTime::parse_from_rfc3339(&normalized)
+        .ok()
+        .map(|dt| dt.with_timezone(&chrono::Utc))
+}
+
 #[cfg(test)]
 mod tests {
-    use super::{is_valid_time, parse_datetime_to_rfc3339};
-    use chrono::TimeZone;
+    use super::{is_valid_time, parse_datetime_in, parse_datetime_to_rfc3339};
+    use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, TimeZone};
+    use chrono_tz::Tz;
+    use proptest::prelude::*;
+
+    /// UTC plus zones with hour-long DST, a southern-hemisphere DST and a
+    /// half-hour DST shift
+    const ZONES: [Tz; 4] = [
+        chrono_tz::UTC,
+        chrono_tz::America::New_York,
+        chrono_tz::Europe::London,
+        chrono_tz::Australia::Lord_Howe,
+    ];
+
+    fn zone() -> impl Strategy<Value = Tz> {
+        prop::sample::select(ZONES.to_vec())
+    }
+
+    /// Any minute from 1970 to about 2100
+    fn wall_time() -> impl Strategy<Value = NaiveDateTime> {
+        (0i64..130 * 366 * 24 * 60).prop_map(|minutes| {
+            NaiveDate::from_ymd_opt(1970, 1, 1).unwrap().and_hms_opt(0, 0, 0).unwrap() + Duration::minutes(minutes)
+        })
+    }
+
+    fn wall_time_in(output: &str, tz: &Tz) -> NaiveDateTime {
+        DateTime::parse_from_rfc3339(output)
+            .expect("output is RFC3339")
+            .with_timezone(tz)
+            .naive_local()
+    }
+
+    proptest! {
+        #[test]
+        fn naive_times_keep_their_wall_time(naive in wall_time(), tz in zone()) {
+            let input = naive.format("%Y-%m-%dT%H:%M:%S").to_string();
+            let output = parse_datetime_in(&input, &tz).expect("every wall time resolves");
+            let back = wall_time_in(&output, &tz);
+
+            if tz.from_local_datetime(&naive).earliest().is_some() {
+                prop_assert_eq!(back, naive);
+            } else {
+                // Skipped by a DST jump: moved forward by the gap
+                prop_assert!(back > naive && back - naive <= Duration::hours(1), "{} -> {}", input, output);
+            }
+        }
+
+        #[test]
+        fn normalizing_is_idempotent(naive in wall_time(), tz in zone()) {
+            let input = naive.format("%Y-%m-%dT%H:%M:%S").to_string();
+            let once = parse_datetime_in(&input, &tz).unwrap();
+            prop_assert_eq!(parse_datetime_in(&once, &tz), Some(once));
+        }
+
+        #[test]
+        fn minute_precision_matches_seconds(naive in wall_time(), tz in zone()) {
+            let minutes = parse_datetime_in(&naive.format("%Y-%m-%dT%H:%M").to_string(), &tz);
+            let seconds = parse_datetime_in(&naive.format("%Y-%m-%dT%H:%M:%S").to_string(), &tz);
+            prop_assert_eq!(minutes, seconds);
+        }
+
+        #[test]
+        fn dates_start_at_local_midnight(naive in wall_time(), tz in zone()) {
+            let date = naive.date();
+            let output = parse_datetime_in(&date.format("%Y-%m-%d").to_string(), &tz).unwrap();
+            let back = wall_time_in(&output, &tz);
+            prop_assert_eq!(back.date(), date);
+            prop_assert!(back.time() < chrono::NaiveTime::from_hms_opt(1, 0, 0).unwrap());
+        }
+
+        #[test]
+        fn leap_days_exist_only_in_leap_years(year in 1970i32..2100, tz in zone()) {
+            let leap = (year % 4 == 0 && year % 100 != 0) || year % 400 == 0;
+            let parsed = parse_datetime_in(&format!("{}-02-29T12:00:00", year), &tz);
+            prop_assert_eq!(parsed.is_some(), leap);
+        }
+    }
 
     #[test]
     fn rejects_invalid_times() {
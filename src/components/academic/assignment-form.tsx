@@ -2,6 +2,7 @@ import { useEffect, useState } from 'react'
 import { Pencil, PlusIcon } from 'lucide-react'
 import type { Assignment } from '@/types'
 import { useAssignments } from '@/hooks/useAssignments'
+import { duplicateOf, getApiErrorMessage } from '@/lib/tauri'
 import { formatDate } from '@/lib/time'
 
 import { Button } from '@/components/ui/button'
@@ -44,8 +45,11 @@ export function AssignmentForm({
   const [description, setDescription] = useState('')
   const [dueDate, setDueDate] = useState('')
   const [priority, setPriority] = useState<string>('medium')
+  // Set when creating was refused as a likely duplicate
+  const [duplicate, setDuplicate] = useState<string | null>(null)
 
   useEffect(() => {
+    setDuplicate(null)
     if (assignment) {
       setTitle(assignment.title)
       setDescription(assignment.description ?? '')
@@ -63,8 +67,12 @@ export function AssignmentForm({
     }
   }, [assignment, open])
 
-  const handleSubmit = async (e: React.FormEvent) => {
+  const handleSubmit = (e: React.FormEvent) => {
     e.preventDefault()
+    void save()
+  }
+
+  const save = async (force?: boolean) => {
     const data = {
       course_id: courseId,
       title,
@@ -76,7 +84,13 @@ export function AssignmentForm({
     if (isEditing) {
       await updateAssignment.mutateAsync({ id: assignment.id, data })
     } else {
-      await createAssignment.mutateAsync(data)
+      try {
+        await createAssignment.mutateAsync({ ...data, force })
+      } catch (error) {
+        if (!duplicateOf(error)) throw error
+        setDuplicate(getApiErrorMessage(error))
+        return
+      }
     }
     setOpen(false)
   }
@@ -156,6 +170,14 @@ export function AssignmentForm({
           </div>
 
           <DialogFooter>
+            {duplicate && (
+              <div className="mr-auto flex flex-wrap items-center gap-2 text-xs text-amber-600">
+                <span>{duplicate}</span>
+                <Button type="button" variant="outline" size="xs" onClick={() => void save(true)}>
+                  Create anyway
+                </Button>
+              </div>
+            )}
             <Button type="submit" disabled={!title || isPending}>
               {isPending ? 'Saving...' : isEditing ? 'Update' : 'Create'}
             </Button>
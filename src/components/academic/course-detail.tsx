@@ -1,5 +1,6 @@
 import { useMemo, useState } from 'react'
 import { Link, useNavigate, useParams } from '@tanstack/react-router'
+import { useMutation, useQuery, useQueryClient } from '@tanstack/react-query'
 import {
   AlertCircle,
   ArrowLeft,
@@ -26,11 +27,13 @@ import {
   fromNow,
   getDeadlineInfo,
 } from '@/lib/time'
+import { decodeApiError, tauri } from '@/lib/tauri'
 import { cn } from '@/lib/utils'
 import { Badge } from '@/components/ui/badge'
 import { Button } from '@/components/ui/button'
 import { Calendar } from '@/components/ui/calendar'
 import { Card, CardContent, CardHeader, CardTitle } from '@/components/ui/card'
+import { Checkbox } from '@/components/ui/checkbox'
 import { Progress } from '@/components/ui/progress'
 import { Tabs, TabsContent, TabsList, TabsTrigger } from '@/components/ui/tabs'
 
@@ -367,7 +370,7 @@ export function CourseDetail() {
                       key={assignment.id}
                       assignment={assignment}
                       courseId={id}
-                      onToggle={() => toggleAssignment.mutate(assignment.id)}
+                      onToggle={(force) => toggleAssignment.mutateAsync({ id: assignment.id, force })}
                       onDelete={() => deleteAssignment.mutate(assignment.id)}
                     />
                   ))}
@@ -385,7 +388,7 @@ export function CourseDetail() {
                       key={assignment.id}
                       assignment={assignment}
                       courseId={id}
-                      onToggle={() => toggleAssignment.mutate(assignment.id)}
+                      onToggle={(force) => toggleAssignment.mutateAsync({ id: assignment.id, force })}
                       onDelete={() => deleteAssignment.mutate(assignment.id)}
                     />
                   ))}
@@ -635,10 +638,23 @@ function AssignmentCard({
     is_completed?: number
   }
   courseId: number
-  onToggle: () => void
+  onToggle: (force?: boolean) => Promise<unknown>
   onDelete: () => void
 }) {
   const deadline = getDeadlineInfo(assignment.due_date)
+  // Set when marking done was refused because of the checklist
+  const [blocked, setBlocked] = useState<string | null>(null)
+
+  const handleToggle = async (force?: boolean) => {
+    try {
+      await onToggle(force)
+      setBlocked(null)
+    } catch (error) {
+      const apiError = decodeApiError(error)
+      if (apiError.code !== 'conflict') throw error
+      setBlocked(apiError.message)
+    }
+  }
 
   return (
     <Card>
@@ -647,7 +663,7 @@ function AssignmentCard({
           <button
             type="button"
             className="text-muted-foreground hover:text-primary mt-0.5 transition-colors"
-            onClick={onToggle}
+            onClick={() => handleToggle()}
           >
             <CheckCircle2Icon
               className={cn(
@@ -678,6 +694,15 @@ function AssignmentCard({
                 </span>
               </div>
             )}
+            {!assignment.is_completed && <AssignmentChecklist assignmentId={assignment.id} />}
+            {blocked && (
+              <div className="flex flex-wrap items-center gap-2 text-xs text-amber-600">
+                <span>{blocked}</span>
+                <Button variant="outline" size="xs" onClick={() => handleToggle(true)}>
+                  Mark done anyway
+                </Button>
+              </div>
+            )}
           </div>
         </div>
         <div className="flex items-center gap-1">
@@ -702,6 +727,42 @@ function AssignmentCard({
   )
 }
 
+// Submission checklist shown under a pending assignment
+function AssignmentChecklist({ assignmentId }: { assignmentId: number }) {
+  const queryClient = useQueryClient()
+  const checklistKey = ['assignment-checklist', assignmentId]
+  const checklistQuery = useQuery({
+    queryKey: checklistKey,
+    queryFn: () => tauri.getAssignmentChecklist(assignmentId),
+  })
+  const toggleItem = useMutation({
+    mutationFn: (itemId: number) => tauri.toggleChecklistItem(itemId),
+    onSuccess: () => queryClient.invalidateQueries({ queryKey: checklistKey }),
+  })
+
+  const items = checklistQuery.data ?? []
+  if (items.length === 0) return null
+
+  return (
+    <ul className="flex flex-wrap gap-x-3 gap-y-1 pt-1">
+      {items.map((item) => (
+        <li key={item.id} className="flex items-center gap-1.5 text-xs">
+          <Checkbox
+            checked={item.is_done}
+            onCheckedChange={() => toggleItem.mutate(item.id)}
+            disabled={toggleItem.isPending}
+            aria-label={item.label}
+          />
+          <span className={cn(item.is_done && 'text-muted-foreground line-through')}>
+            {item.label}
+            {!item.is_required && <span className="text-muted-foreground"> (optional)</span>}
+          </span>
+        </li>
+      ))}
+    </ul>
+  )
+}
+
 // Exam Card Component
 function ExamCard({
   exam,
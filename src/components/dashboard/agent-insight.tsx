@@ -13,6 +13,7 @@ interface Insight {
   confidence?: number
   insight_id?: number
   arm_name?: string
+  insight_key?: string
 }
 
 export function AgentInsight() {
@@ -25,11 +26,12 @@ export function AgentInsight() {
   })
 
   const feedbackMutation = useMutation({
-    mutationFn: ({ insightId, actedOn, feedbackScore }: {
+    mutationFn: ({ insightId, actedOn, feedbackScore, insightKey }: {
       insightId: number
       actedOn: boolean
       feedbackScore?: number
-    }) => tauri.recordInsightFeedback(insightId, actedOn, feedbackScore),
+      insightKey?: string
+    }) => tauri.recordInsightFeedback(insightId, actedOn, feedbackScore, insightKey),
     onSuccess: () => {
       // Refetch insights after feedback to potentially get better recommendations
       queryClient.invalidateQueries({ queryKey: ['insights'] })
@@ -41,7 +43,8 @@ export function AgentInsight() {
       feedbackMutation.mutate({
         insightId: insight.insight_id,
         actedOn: false,
-        feedbackScore: positive ? 1 : -1
+        feedbackScore: positive ? 1 : -1,
+        insightKey: insight.insight_key
       })
     }
   }
@@ -51,7 +54,8 @@ export function AgentInsight() {
       feedbackMutation.mutate({
         insightId: insight.insight_id,
         actedOn: true,
-        feedbackScore: 1
+        feedbackScore: 1,
+        insightKey: insight.insight_key
       })
     }
   }
@@ -11,6 +11,7 @@ import {
 } from '@/components/ui/popover'
 import { useDetailedStats } from '@/hooks/useStats'
 import { cn } from '@/lib/utils'
+import type { LifeBalance } from '@/types'
 
 export function QuickStats() {
   const { data, isLoading } = useDetailedStats()
@@ -84,168 +85,197 @@ export function QuickStats() {
   ]
 
   return (
-    <div className="grid gap-4 md:grid-cols-4">
-      {stats.map((stat) => {
-        const Icon = stat.icon
-        const hasBreakdown =
-          stat.type === 'study' ||
-          stat.type === 'practice' ||
-          stat.type === 'skills'
-        const displayValue =
-          stat.type === 'workout' || stat.type === 'skills'
-            ? stat.value
-            : stat.value.toFixed(1)
-        const displayTarget =
-          stat.type === 'workout' || stat.type === 'skills'
-            ? stat.target
-            : stat.target.toFixed(1)
+    <div className="space-y-4">
+      <div className="grid gap-4 md:grid-cols-4">
+        {stats.map((stat) => {
+          const Icon = stat.icon
+          const hasBreakdown =
+            stat.type === 'study' ||
+            stat.type === 'practice' ||
+            stat.type === 'skills'
+          const displayValue =
+            stat.type === 'workout' || stat.type === 'skills'
+              ? stat.value
+              : stat.value.toFixed(1)
+          const displayTarget =
+            stat.type === 'workout' || stat.type === 'skills'
+              ? stat.target
+              : stat.target.toFixed(1)
 
-        const CardComponent = (
-          <Card
-            className={cn(
-              'transition-all duration-200',
-              hasBreakdown &&
-                'cursor-pointer hover:border-primary/50 hover:shadow-md',
-            )}
-          >
-            <CardHeader className="pb-2">
-              <div className="flex items-center justify-between">
-                <CardTitle className="text-sm font-medium">
-                  {stat.label}
-                </CardTitle>
-                <div className={cn('rounded-md p-1.5', stat.bgColor)}>
-                  <Icon className={cn('h-4 w-4', stat.color)} />
+          const CardComponent = (
+            <Card
+              className={cn(
+                'transition-all duration-200',
+                hasBreakdown &&
+                  'cursor-pointer hover:border-primary/50 hover:shadow-md',
+              )}
+            >
+              <CardHeader className="pb-2">
+                <div className="flex items-center justify-between">
+                  <CardTitle className="text-sm font-medium">
+                    {stat.label}
+                  </CardTitle>
+                  <div className={cn('rounded-md p-1.5', stat.bgColor)}>
+                    <Icon className={cn('h-4 w-4', stat.color)} />
+                  </div>
                 </div>
-              </div>
-            </CardHeader>
-            <CardContent className="space-y-3">
-              <div className="flex items-baseline gap-1">
-                <span className="text-2xl font-bold">{displayValue}</span>
-                <span className="text-muted-foreground text-sm">
-                  / {displayTarget}
-                </span>
-              </div>
-              <div className="space-y-1">
-                <Progress value={stat.percent} className="h-2" />
-                <p className="text-muted-foreground text-xs">
-                  {Math.round(stat.percent)}% of weekly target
-                </p>
-              </div>
-            </CardContent>
-          </Card>
-        )
+              </CardHeader>
+              <CardContent className="space-y-3">
+                <div className="flex items-baseline gap-1">
+                  <span className="text-2xl font-bold">{displayValue}</span>
+                  <span className="text-muted-foreground text-sm">
+                    / {displayTarget}
+                  </span>
+                </div>
+                <div className="space-y-1">
+                  <Progress value={stat.percent} className="h-2" />
+                  <p className="text-muted-foreground text-xs">
+                    {Math.round(stat.percent)}% of weekly target
+                  </p>
+                </div>
+              </CardContent>
+            </Card>
+          )
 
-        if (!hasBreakdown) {
-          return <div key={stat.label}>{CardComponent}</div>
-        }
+          if (!hasBreakdown) {
+            return <div key={stat.label}>{CardComponent}</div>
+          }
 
-        return (
-          <Popover key={stat.label}>
-            <PopoverTrigger asChild>{CardComponent}</PopoverTrigger>
-            <PopoverContent className="w-80">
-              <PopoverHeader>
-                <PopoverTitle className="flex items-center gap-2">
-                  <Icon className={cn('h-4 w-4', stat.color)} />
-                  {stat.label} Breakdown
-                </PopoverTitle>
-                <PopoverDescription>
-                  Weekly progress by{' '}
-                  {stat.type === 'study' ? 'course' : 'skill'}
-                </PopoverDescription>
-              </PopoverHeader>
+          return (
+            <Popover key={stat.label}>
+              <PopoverTrigger asChild>{CardComponent}</PopoverTrigger>
+              <PopoverContent className="w-80">
+                <PopoverHeader>
+                  <PopoverTitle className="flex items-center gap-2">
+                    <Icon className={cn('h-4 w-4', stat.color)} />
+                    {stat.label} Breakdown
+                  </PopoverTitle>
+                  <PopoverDescription>
+                    Weekly progress by{' '}
+                    {stat.type === 'study' ? 'course' : 'skill'}
+                  </PopoverDescription>
+                </PopoverHeader>
 
-              <div className="mt-2 max-h-64 space-y-3 overflow-y-auto">
-                {stat.type === 'study' &&
-                  data?.study_breakdown?.map((course) => (
-                    <div key={course.course_id} className="space-y-1.5">
-                      <div className="flex items-center justify-between">
-                        <div className="flex items-center gap-2">
-                          <div
-                            className="h-3 w-3 rounded-full"
-                            style={{ backgroundColor: course.color }}
-                          />
-                          <span className="text-sm font-medium">
-                            {course.course_name}
+                <div className="mt-2 max-h-64 space-y-3 overflow-y-auto">
+                  {stat.type === 'study' &&
+                    data?.study_breakdown?.map((course) => (
+                      <div key={course.course_id} className="space-y-1.5">
+                        <div className="flex items-center justify-between">
+                          <div className="flex items-center gap-2">
+                            <div
+                              className="h-3 w-3 rounded-full"
+                              style={{ backgroundColor: course.color }}
+                            />
+                            <span className="text-sm font-medium">
+                              {course.course_name}
+                            </span>
+                          </div>
+                          <span className="text-muted-foreground text-xs">
+                            {course.hours_this_week.toFixed(1)}h /{' '}
+                            {course.target_hours.toFixed(1)}h
                           </span>
                         </div>
-                        <span className="text-muted-foreground text-xs">
-                          {course.hours_this_week.toFixed(1)}h /{' '}
-                          {course.target_hours.toFixed(1)}h
-                        </span>
+                        <Progress value={course.percent} className="h-1.5" />
+                        {course.current_grade !== undefined &&
+                          course.current_grade !== null && (
+                            <p className="text-muted-foreground text-xs">
+                              Grade: {course.current_grade.toFixed(1)}%
+                              {course.target_grade && (
+                                <> (target: {course.target_grade}%)</>
+                              )}
+                            </p>
+                          )}
                       </div>
-                      <Progress value={course.percent} className="h-1.5" />
-                      {course.current_grade !== undefined &&
-                        course.current_grade !== null && (
-                          <p className="text-muted-foreground text-xs">
-                            Grade: {course.current_grade.toFixed(1)}%
-                            {course.target_grade && (
-                              <> (target: {course.target_grade}%)</>
-                            )}
-                          </p>
-                        )}
-                    </div>
-                  ))}
+                    ))}
 
-                {(stat.type === 'practice' || stat.type === 'skills') &&
-                  data?.practice_breakdown?.map((skill) => (
-                    <div key={skill.skill_id} className="space-y-1.5">
-                      <div className="flex items-center justify-between">
-                        <div className="flex items-center gap-2">
-                          <span className="bg-primary/20 text-primary rounded px-1.5 py-0.5 text-xs font-medium">
-                            Lv.{skill.current_level}
-                          </span>
-                          <span className="text-sm font-medium">
-                            {skill.skill_name}
-                          </span>
-                        </div>
-                        <span className="text-muted-foreground text-xs">
-                          {skill.hours_this_week.toFixed(1)}h /{' '}
-                          {skill.target_weekly_hours.toFixed(1)}h
-                        </span>
-                      </div>
-                      <div className="space-y-0.5">
-                        <div className="flex items-center gap-2">
-                          <span className="text-muted-foreground w-12 text-xs">
-                            Week
-                          </span>
-                          <Progress
-                            value={skill.weekly_percent}
-                            className="h-1.5 flex-1"
-                          />
-                        </div>
-                        <div className="flex items-center gap-2">
-                          <span className="text-muted-foreground w-12 text-xs">
-                            Total
-                          </span>
-                          <Progress
-                            value={skill.mastery_percent}
-                            className="h-1.5 flex-1"
-                          />
+                  {(stat.type === 'practice' || stat.type === 'skills') &&
+                    data?.practice_breakdown?.map((skill) => (
+                      <div key={skill.skill_id} className="space-y-1.5">
+                        <div className="flex items-center justify-between">
+                          <div className="flex items-center gap-2">
+                            <span className="bg-primary/20 text-primary rounded px-1.5 py-0.5 text-xs font-medium">
+                              Lv.{skill.current_level}
+                            </span>
+                            <span className="text-sm font-medium">
+                              {skill.skill_name}
+                            </span>
+                          </div>
                           <span className="text-muted-foreground text-xs">
-                            {skill.total_hours.toFixed(0)}h
+                            {skill.hours_this_week.toFixed(1)}h /{' '}
+                            {skill.target_weekly_hours.toFixed(1)}h
                           </span>
                         </div>
+                        <div className="space-y-0.5">
+                          <div className="flex items-center gap-2">
+                            <span className="text-muted-foreground w-12 text-xs">
+                              Week
+                            </span>
+                            <Progress
+                              value={skill.weekly_percent}
+                              className="h-1.5 flex-1"
+                            />
+                          </div>
+                          <div className="flex items-center gap-2">
+                            <span className="text-muted-foreground w-12 text-xs">
+                              Total
+                            </span>
+                            <Progress
+                              value={skill.mastery_percent}
+                              className="h-1.5 flex-1"
+                            />
+                            <span className="text-muted-foreground text-xs">
+                              {skill.total_hours.toFixed(0)}h
+                            </span>
+                          </div>
+                        </div>
                       </div>
-                    </div>
-                  ))}
+                    ))}
 
-                {((stat.type === 'study' &&
-                  (!data?.study_breakdown ||
-                    data.study_breakdown.length === 0)) ||
-                  ((stat.type === 'practice' || stat.type === 'skills') &&
-                    (!data?.practice_breakdown ||
-                      data.practice_breakdown.length === 0))) && (
-                  <p className="text-muted-foreground py-4 text-center text-sm">
-                    No {stat.type === 'study' ? 'courses' : 'skills'} yet.
-                    <br />
-                    Add some to track your progress!
-                  </p>
-                )}
-              </div>
-            </PopoverContent>
-          </Popover>
-        )
-      })}
+                  {((stat.type === 'study' &&
+                    (!data?.study_breakdown ||
+                      data.study_breakdown.length === 0)) ||
+                    ((stat.type === 'practice' || stat.type === 'skills') &&
+                      (!data?.practice_breakdown ||
+                        data.practice_breakdown.length === 0))) && (
+                    <p className="text-muted-foreground py-4 text-center text-sm">
+                      No {stat.type === 'study' ? 'courses' : 'skills'} yet.
+                      <br />
+                      Add some to track your progress!
+                    </p>
+                  )}
+                </div>
+              </PopoverContent>
+            </Popover>
+          )
+        })}
+      </div>
+      {data?.balance && <BalanceSummary balance={data.balance} />}
     </div>
   )
 }
+
+/** Life balance score with each domain's share against its ideal */
+function BalanceSummary({ balance }: { balance: LifeBalance }) {
+  if (balance.score === null) return null
+  return (
+    <Card>
+      <CardContent className="flex flex-wrap items-center gap-x-6 gap-y-2 py-3">
+        <span className="text-sm font-medium">
+          Life balance {balance.score}/100
+        </span>
+        {balance.domains.map((domain) => (
+          <span
+            key={domain.domain}
+            className="text-muted-foreground text-xs capitalize"
+          >
+            {domain.domain} {Math.round(domain.share * 100)}%
+            <span className="normal-case">
+              {' '}
+              (ideal {Math.round(domain.ideal_share * 100)}%)
+            </span>
+          </span>
+        ))}
+      </CardContent>
+    </Card>
+  )
+}
@@ -6,6 +6,7 @@ import { TemplateSelector } from './template-selector'
 import type { Exercise, Workout, WorkoutExercise } from '@/types'
 import { useWorkoutTemplates, useWorkouts } from '@/hooks/useWorkouts'
 import { useCheckAndUpdatePrs } from '@/hooks/useStats'
+import { duplicateOf, getApiErrorMessage } from '@/lib/tauri'
 import { formatPRValue } from '@/lib/workout-utils'
 import { Button } from '@/components/ui/button'
 import {
@@ -120,9 +121,12 @@ export function WorkoutForm({
     onClose?.()
   }
 
-  const handleSubmit = async (e: React.FormEvent) => {
+  const handleSubmit = (e: React.FormEvent) => {
     e.preventDefault()
+    void save()
+  }
 
+  const save = async (force?: boolean) => {
     try {
       let workoutId: number
 
@@ -150,6 +154,7 @@ export function WorkoutForm({
           name: name || undefined,
           duration_minutes: duration ? parseInt(duration) : undefined,
           notes: notes || undefined,
+          force,
         })
         workoutId = workout.id
       }
@@ -197,6 +202,12 @@ export function WorkoutForm({
 
       handleClose()
     } catch (error) {
+      if (duplicateOf(error)) {
+        toast.warning(getApiErrorMessage(error), {
+          action: { label: 'Log anyway', onClick: () => void save(true) },
+        })
+        return
+      }
       console.error('Failed to save workout:', error)
       toast.error('Failed to save workout')
     }
@@ -14,6 +14,8 @@ import {
   loadPomodoroState,
   savePomodoroState,
 } from '@/lib/pomodoroPersistence'
+import { duplicateOf, tauri } from '@/lib/tauri'
+import type { InterruptionReason } from '@/types'
 import { Button } from '@/components/ui/button'
 import { Card, CardContent, CardHeader, CardTitle } from '@/components/ui/card'
 import { Input } from '@/components/ui/input'
@@ -31,6 +33,15 @@ import {
   PopoverTrigger,
 } from '@/components/ui/popover'
 
+const INTERRUPTION_REASONS: Array<{ value: InterruptionReason; label: string }> = [
+  { value: 'phone', label: 'Phone' },
+  { value: 'messages', label: 'Messages' },
+  { value: 'person', label: 'Someone' },
+  { value: 'noise', label: 'Noise' },
+  { value: 'wandering', label: 'Mind wandered' },
+  { value: 'other', label: 'Other' },
+]
+
 const TIMER_PRESETS = [
   { name: 'Classic', work: 25, break: 5 },
   { name: 'Short', work: 15, break: 3 },
@@ -100,19 +111,29 @@ export function PomodoroTimer({ initialCourseId }: PomodoroTimerProps) {
           ? Number(selectedCourseId)
           : undefined
 
-      const session = await startSession.mutateAsync({
-        session_type: 'study',
-        reference_id: courseId,
-        reference_type: courseId ? 'course' : undefined,
-      })
-
-      setActiveSessionId(session.id)
+      let sessionId: number
+      try {
+        const session = await startSession.mutateAsync({
+          session_type: 'study',
+          reference_id: courseId,
+          reference_type: courseId ? 'course' : undefined,
+          planned_minutes: timer.workMinutes,
+        })
+        sessionId = session.id
+      } catch (error) {
+        // The same session is already running (e.g. from another window): pick it up
+        const duplicate = duplicateOf(error)
+        if (duplicate?.kind !== 'session') throw error
+        sessionId = duplicate.id
+      }
+
+      setActiveSessionId(sessionId)
       const persisted = loadPomodoroState()
       savePomodoroState({
         ...persisted,
         version: 1,
         selectedCourseId,
-        activeSessionId: session.id,
+        activeSessionId: sessionId,
         lastUpdatedAt: Date.now(),
       })
     }
@@ -140,6 +161,15 @@ export function PomodoroTimer({ initialCourseId }: PomodoroTimerProps) {
     })
   }
 
+  const handleInterruption = (reason: string) => {
+    if (!activeSessionId) return
+    tauri
+      .logSessionInterruption(activeSessionId, reason as InterruptionReason)
+      .catch((err) => {
+        console.warn('Failed to log interruption:', err)
+      })
+  }
+
   const applyPreset = (preset: (typeof TIMER_PRESETS)[number]) => {
     timer.setWorkMinutes(preset.work)
     timer.setBreakMinutes(preset.break)
@@ -377,6 +407,22 @@ export function PomodoroTimer({ initialCourseId }: PomodoroTimerProps) {
             </>
           )}
         </div>
+
+        {/* Interruption log */}
+        {timer.mode === 'work' && timer.state !== 'idle' && activeSessionId && (
+          <Select value="" onValueChange={handleInterruption}>
+            <SelectTrigger className="w-full">
+              <SelectValue placeholder="Log an interruption" />
+            </SelectTrigger>
+            <SelectContent>
+              {INTERRUPTION_REASONS.map((reason) => (
+                <SelectItem key={reason.value} value={reason.value}>
+                  {reason.label}
+                </SelectItem>
+              ))}
+            </SelectContent>
+          </Select>
+        )}
       </CardContent>
     </Card>
   )
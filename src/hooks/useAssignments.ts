@@ -13,7 +13,8 @@ export function useAssignments(courseId?: number) {
   })
 
   const createAssignment = useMutation({
-    mutationFn: (data: Partial<Assignment>) => tauri.createAssignment(data),
+    mutationFn: ({ force, ...data }: Partial<Assignment> & { force?: boolean }) =>
+      tauri.createAssignment(data, force),
     onSuccess: () => queryClient.invalidateQueries({ queryKey: [ASSIGNMENTS_KEY, courseId] }),
   })
 
@@ -24,7 +25,8 @@ export function useAssignments(courseId?: number) {
   })
 
   const toggleAssignment = useMutation({
-    mutationFn: (id: number) => tauri.toggleAssignment(id),
+    mutationFn: ({ id, force }: { id: number; force?: boolean }) =>
+      tauri.toggleAssignment(id, force),
     onSuccess: () => queryClient.invalidateQueries({ queryKey: [ASSIGNMENTS_KEY, courseId] }),
   })
 
@@ -16,7 +16,8 @@ export function useSessions(
   })
 
   const startSession = useMutation({
-    mutationFn: (data: Partial<Session>) => tauri.startSession(data),
+    mutationFn: ({ force, ...data }: Partial<Session> & { force?: boolean }) =>
+      tauri.startSession(data, force),
     onSuccess: () =>
       queryClient.invalidateQueries({
         queryKey: [SESSIONS_KEY, referenceId, referenceType],
@@ -14,7 +14,8 @@ export function useWorkouts() {
   })
 
   const createWorkout = useMutation({
-    mutationFn: (data: Partial<Workout>) => tauri.createWorkout(data),
+    mutationFn: ({ force, ...data }: Partial<Workout> & { force?: boolean }) =>
+      tauri.createWorkout(data, force),
     onSuccess: () => queryClient.invalidateQueries({ queryKey: WORKOUTS_KEY }),
   })
 
@@ -1,33 +1,161 @@
 import { invoke as rawInvoke } from '@tauri-apps/api/core'
 import type {
+  AccountabilityDelivery,
+  AccountabilityReport,
   Achievement,
   AgentRecommendation,
+  ApiCapabilities,
+  ArchiveTermInput,
+  ArchiveTermResult,
   AgentStatus,
   Assignment,
+  AssignmentChecklistItem,
+  Attachment,
+  AttachmentGcReport,
+  AttachmentOwner,
+  AttentionReport,
   BigThreeGoal,
   BigThreeInput,
+  Book,
+  BookInput,
+  CalendarFeedStatus,
   CalendarItem,
+  CapacityReport,
+  Category,
+  CategoryInput,
+  CategoryUpdate,
+  Challenge,
+  ChallengeInput,
+  ChangeLogEntry,
+  ChangeSource,
   CheckIn,
   Course,
   CourseAnalytics,
+  CourseCalibration,
+  CourseDigest,
+  CourseSchedulePrefsInput,
+  CourseTargetChange,
+  CourseEfficiency,
   CourseWithProgress,
+  CustomMetric,
+  CustomMetricInput,
+  CycleDay,
+  CyclePeriod,
+  CyclePeriodInput,
+  CyclePrediction,
+  DbPoolStatus,
+  DeadlineExportResult,
+  DeadlineImportResult,
+  DeliverablePlanHours,
   DetailedStats,
+  DistractionReport,
+  DuplicateRef,
+  EnergyBudget,
   Exam,
+  ExamReadiness,
+  ExamResultInput,
   Exercise,
+  ExportMetric,
+  ExportRange,
+  ExportResult,
+  FocusProfile,
+  FocusProfileInput,
+  FocusProfileStats,
+  FlashcardDeck,
+  FlashcardDeckInput,
+  FlashcardReview,
+  FlashcardReviewInput,
+  FreeSlot,
+  GamificationStatus,
+  GlanceData,
   GoogleAccount,
   GoogleAuthBeginResponse,
+  GoogleSyncPreview,
   GoogleSyncStatus,
+  GradeSimulation,
+  HttpApiStatus,
+  IcsExportResult,
+  IcsImportResult,
+  Interruption,
+  InterruptionReason,
+  LanguageProficiency,
+  LanguageSkill,
+  LanguageSkillInput,
+  LectureNote,
+  LectureNoteInput,
+  LectureNoteQuery,
+  McpAuditEntry,
+  MeditationInput,
+  MeditationStats,
+  MeditationStyle,
+  MeditationTimer,
+  MeetingFocusReport,
+  MeetingSessionInput,
+  MetricEvaluation,
+  MlReadiness,
+  MorningBriefing,
+  NextTask,
+  OnboardingCoursesInput,
+  OnboardingCoursesResult,
+  OnboardingSettingsInput,
+  OnboardingStatus,
+  PlanRealismReport,
+  PlannerMode,
+  PlannerModeStatus,
+  PomodoroStats,
+  OnboardingStep,
   PersonalRecord,
+  PortfolioFormat,
   PracticeLog,
+  ProcrastinationReport,
+  QuickAction,
+  QuickActionOutcome,
+  QuickActionStatus,
+  ReadingSessionInput,
+  ReadingStats,
   RichContext,
+  SampleDataCounts,
+  SemesterReview,
   Session,
+  SessionTypeDef,
+  SessionTypeInput,
+  SessionTypeStats,
+  SettingDef,
+  SettingKey,
+  Settings,
+  ShadowReport,
+  ShutdownInput,
+  ShutdownResult,
   SimilarExperience,
   Skill,
+  SkillPortfolioExportResult,
+  SkillPracticeNeed,
+  SocialEventInput,
+  SocialStats,
+  SpendingCheckIn,
+  SpendingCheckInInput,
+  SpendingSummary,
+  TelemetryExportResult,
+  TelemetryReport,
+  TemplateScheduleInput,
+  Technique,
+  TechniqueInput,
+  TechniqueStats,
+  Term,
+  TermSnapshot,
+  Tracker,
+  TrackerEntity,
+  TrackerEntityInput,
+  TrackerInput,
+  TrackerLog,
+  TrackerLogInput,
+  TrackerSummary,
   UserSettings,
   WeekPlanBlock,
   WeekPlanBlockInput,
   WeeklyReview,
   Workout,
+  WorkoutDetail,
   WorkoutExercise,
   WorkoutHeatmapDay,
   WorkoutTemplate,
@@ -74,6 +202,14 @@ export function decodeApiError(error: unknown): ApiError {
   }
 }
 
+/** The existing row a create command refused to duplicate, if that was the error */
+export function duplicateOf(error: unknown): DuplicateRef | null {
+  const { code, details } = decodeApiError(error)
+  if (code !== 'conflict' || !details || typeof details !== 'object') return null
+  const duplicate = (details as { duplicate_of?: DuplicateRef }).duplicate_of
+  return duplicate ?? null
+}
+
 export function getApiErrorMessage(error: unknown): string {
   const decoded = decodeApiError(error)
   if (decoded.code === 'validation') {
@@ -109,17 +245,22 @@ async function invoke<T>(command: string, args?: Record<string, unknown>): Promi
 
 export const tauri = {
   // Courses
-  createCourse: (data: Partial<Course>) =>
+  /** apply_meeting_pattern adds the credit hours' template meetings */
+  createCourse: (data: Partial<Course> & { apply_meeting_pattern?: boolean }) =>
     invoke<Course>('create_course', { data }),
   getCourses: () => invoke<Array<Course>>('get_courses'),
   getCourse: (id: number) => invoke<Course>('get_course', { id }),
   updateCourse: (id: number, data: Partial<Course>) =>
     invoke<Course>('update_course', { id, data }),
+  setCourseSchedulePrefs: (id: number, prefs: CourseSchedulePrefsInput) =>
+    invoke<Course>('set_course_schedule_prefs', { id, prefs }),
   deleteCourse: (id: number) => invoke<boolean>('delete_course', { id }),
   getCoursesWithProgress: () =>
     invoke<Array<CourseWithProgress>>('get_courses_with_progress'),
   getCourseAnalytics: (courseId: number) =>
     invoke<CourseAnalytics>('get_course_analytics', { courseId }),
+  recomputeCourseTargets: (dryRun?: boolean) =>
+    invoke<Array<CourseTargetChange>>('recompute_course_targets', { dryRun }),
 
   // Exams
   createExam: (data: Partial<Exam>) => invoke<Exam>('create_exam', { data }),
@@ -131,25 +272,106 @@ export const tauri = {
   deleteExam: (id: number) => invoke<boolean>('delete_exam', { id }),
   getUpcomingExams: (days: number) =>
     invoke<Array<Exam>>('get_upcoming_exams', { days }),
+  recordExamResult: (id: number, data: ExamResultInput) =>
+    invoke<Exam>('record_exam_result', { id, data }),
+  getExamReadiness: (days?: number) =>
+    invoke<Array<ExamReadiness>>('get_exam_readiness', { days }),
+  /** Scores are keyed by exam id */
+  simulateGrade: (courseId: number, hypotheticalScores: Record<number, number>) =>
+    invoke<GradeSimulation>('simulate_grade', { courseId, hypotheticalScores }),
+  getEffortEfficiency: (weeks?: number) =>
+    invoke<Array<CourseEfficiency>>('get_effort_efficiency', { weeks }),
+  getMeetingFocusStats: (weeks?: number) =>
+    invoke<MeetingFocusReport>('get_meeting_focus_stats', { weeks }),
 
   // Assignments
-  createAssignment: (data: Partial<Assignment>) =>
-    invoke<Assignment>('create_assignment', { data }),
+  /** Fails with a conflict naming a same-titled assignment due the same day, unless forced */
+  createAssignment: (data: Partial<Assignment>, force?: boolean) =>
+    invoke<Assignment>('create_assignment', { data, force }),
   getAssignments: (courseId?: number) =>
     invoke<Array<Assignment>>('get_assignments', { courseId }),
   updateAssignment: (id: number, data: Partial<Assignment>) =>
     invoke<Assignment>('update_assignment', { id, data }),
   deleteAssignment: (id: number) =>
     invoke<boolean>('delete_assignment', { id }),
-  toggleAssignment: (id: number) =>
-    invoke<Assignment>('toggle_assignment', { id }),
+  /** Fails with a conflict while required checklist items remain, unless forced */
+  toggleAssignment: (id: number, force?: boolean) =>
+    invoke<Assignment>('toggle_assignment', { id, force }),
+  getAssignmentChecklist: (assignmentId: number) =>
+    invoke<Array<AssignmentChecklistItem>>('get_assignment_checklist', { assignmentId }),
+  addChecklistItem: (assignmentId: number, label: string, isRequired?: boolean) =>
+    invoke<AssignmentChecklistItem>('add_checklist_item', { assignmentId, label, isRequired }),
+  toggleChecklistItem: (id: number) =>
+    invoke<AssignmentChecklistItem>('toggle_checklist_item', { id }),
+  deleteChecklistItem: (id: number) =>
+    invoke<boolean>('delete_checklist_item', { id }),
+  getEstimateCalibration: () =>
+    invoke<Array<CourseCalibration>>('get_estimate_calibration'),
+  getProcrastinationReport: () =>
+    invoke<ProcrastinationReport>('get_procrastination_report'),
 
   // Sessions
-  startSession: (data: Partial<Session>) =>
-    invoke<Session>('start_session', { data }),
-  endSession: (id: number) => invoke<Session>('end_session', { id }),
+  /** Fails with a conflict naming a matching session already running, unless forced */
+  startSession: (data: Partial<Session>, force?: boolean) =>
+    invoke<Session>('start_session', { data, force }),
+  endSession: (id: number, focusRating?: number) =>
+    invoke<Session>('end_session', { id, focusRating }),
   getSessions: (referenceId?: number, referenceType?: string) =>
     invoke<Array<Session>>('get_sessions', { referenceId, referenceType }),
+  /** Only while the session is running */
+  logSessionInterruption: (sessionId: number, reason: InterruptionReason, note?: string) =>
+    invoke<Interruption>('log_session_interruption', { sessionId, reason, note }),
+
+  // Session types
+  getSessionTypes: () => invoke<Array<SessionTypeDef>>('get_session_types'),
+  createSessionType: (data: SessionTypeInput) =>
+    invoke<SessionTypeDef>('create_session_type', { data }),
+  /** Fails for built-in types and types with sessions */
+  deleteSessionType: (name: string) =>
+    invoke<boolean>('delete_session_type', { name }),
+
+  // Lecture notes
+  startMeetingSession: (data: MeetingSessionInput) =>
+    invoke<Session>('start_meeting_session', { data }),
+  addLectureNote: (data: LectureNoteInput) =>
+    invoke<LectureNote>('add_lecture_note', { data }),
+  getLectureNotes: (query: LectureNoteQuery = {}) =>
+    invoke<Array<LectureNote>>('get_lecture_notes', { query }),
+  updateLectureNote: (id: number, data: { topic?: string; content?: string }) =>
+    invoke<LectureNote>('update_lecture_note', { id, data }),
+  deleteLectureNote: (id: number) =>
+    invoke<void>('delete_lecture_note', { id }),
+
+  // Focus profiles
+  getFocusProfiles: () => invoke<Array<FocusProfile>>('get_focus_profiles'),
+  createFocusProfile: (data: FocusProfileInput) =>
+    invoke<FocusProfile>('create_focus_profile', { data }),
+  updateFocusProfile: (id: number, data: FocusProfileInput) =>
+    invoke<FocusProfile>('update_focus_profile', { id, data }),
+  deleteFocusProfile: (id: number) =>
+    invoke<void>('delete_focus_profile', { id }),
+  getFocusProfileStats: (days?: number) =>
+    invoke<Array<FocusProfileStats>>('get_focus_profile_stats', { days }),
+
+  // Study techniques
+  getTechniques: () => invoke<Array<Technique>>('get_techniques'),
+  createTechnique: (data: TechniqueInput) =>
+    invoke<Technique>('create_technique', { data }),
+  deleteTechnique: (id: number) => invoke<void>('delete_technique', { id }),
+  getSessionTechniques: (sessionId: number) =>
+    invoke<Array<Technique>>('get_session_techniques', { sessionId }),
+  setSessionTechniques: (sessionId: number, techniqueIds: Array<number>) =>
+    invoke<Array<Technique>>('set_session_techniques', { sessionId, techniqueIds }),
+  getTechniqueStats: (days?: number, courseId?: number) =>
+    invoke<Array<TechniqueStats>>('get_technique_stats', { days, courseId }),
+
+  // Attachments (practice logs and workouts)
+  attachFile: (ownerType: AttachmentOwner, ownerId: number, sourcePath: string) =>
+    invoke<Attachment>('attach_file', { ownerType, ownerId, sourcePath }),
+  getAttachments: (ownerType: AttachmentOwner, ownerId: number) =>
+    invoke<Array<Attachment>>('get_attachments', { ownerType, ownerId }),
+  deleteAttachment: (id: number) => invoke<void>('delete_attachment', { id }),
+  gcAttachments: () => invoke<AttachmentGcReport>('gc_attachments'),
 
   // Skills
   createSkill: (data: Partial<Skill>) =>
@@ -163,12 +385,18 @@ export const tauri = {
     invoke<PracticeLog>('log_practice', { data }),
   getPracticeLogs: (skillId?: number) =>
     invoke<Array<PracticeLog>>('get_practice_logs', { skillId }),
+  getSkillPracticeNeeds: (weekStartDate: string) =>
+    invoke<Array<SkillPracticeNeed>>('get_skill_practice_needs', { weekStartDate }),
+  /** Markdown for .md paths and JSON otherwise, unless `format` says */
+  exportSkillPortfolio: (path: string, format?: PortfolioFormat, includeArchived?: boolean) =>
+    invoke<SkillPortfolioExportResult>('export_skill_portfolio', { path, format, includeArchived }),
 
   // Workouts
-  createWorkout: (data: Partial<Workout>) =>
-    invoke<Workout>('create_workout', { data }),
+  /** Fails with a conflict naming a matching workout logged minutes earlier, unless forced */
+  createWorkout: (data: Partial<Workout>, force?: boolean) =>
+    invoke<Workout>('create_workout', { data, force }),
   getWorkouts: () => invoke<Array<Workout>>('get_workouts'),
-  getWorkout: (id: number) => invoke<Workout>('get_workout', { id }),
+  getWorkout: (id: number) => invoke<WorkoutDetail>('get_workout', { id }),
   deleteWorkout: (id: number) => invoke<boolean>('delete_workout', { id }),
 
   addExerciseToWorkout: (data: Partial<WorkoutExercise>) =>
@@ -201,6 +429,11 @@ export const tauri = {
     invoke<WorkoutTemplate>('update_workout_template', { id, name, exercises }),
   deleteWorkoutTemplate: (id: number) =>
     invoke<boolean>('delete_workout_template', { id }),
+  setWorkoutTemplateSchedule: (id: number, schedule: TemplateScheduleInput) =>
+    invoke<WorkoutTemplate>('set_workout_template_schedule', { id, schedule }),
+  /** Workout blocks for the week's remaining target, from the program schedule */
+  suggestWorkoutBlocks: (weekStartDate: string) =>
+    invoke<Array<WeekPlanBlockInput>>('suggest_workout_blocks', { weekStartDate }),
 
   // Exercises search
   fetchAndCacheExercises: () => invoke<number>('fetch_and_cache_exercises'),
@@ -226,6 +459,7 @@ export const tauri = {
       count: number
       sample: Array<{ id: number; name: string; source: string }>
     }>('get_exercise_cache_stats'),
+  getDbPoolStatus: () => invoke<DbPoolStatus>('get_db_pool_status'),
 
   // Analytics
   getStats: () =>
@@ -251,6 +485,7 @@ export const tauri = {
         confidence?: number
         insight_id?: number
         arm_name?: string
+        insight_key?: string
       }>
     >('get_insights'),
 
@@ -259,12 +494,20 @@ export const tauri = {
     insightId: number,
     actedOn: boolean,
     feedbackScore?: number,
+    insightKey?: string,
   ) =>
     invoke<void>('record_insight_feedback', {
       insightId,
       actedOn,
       feedbackScore,
+      insightKey,
     }),
+  dismissInsight: (insightKey: string, insightId?: number) =>
+    invoke<void>('dismiss_insight', { insightKey, insightId }),
+  getMutedInsightCategories: () =>
+    invoke<Array<string>>('get_muted_insight_categories'),
+  setInsightCategoryMuted: (category: string, muted: boolean) =>
+    invoke<Array<string>>('set_insight_category_muted', { category, muted }),
   runPatternAnalysis: () => invoke<number>('run_pattern_analysis'),
   getUserProfile: () =>
     invoke<
@@ -310,6 +553,8 @@ export const tauri = {
       metadata,
     }),
   getAgentStatus: () => invoke<AgentStatus>('get_agent_status'),
+  /** Recommendations and insights are degraded while `status` is 'loading' */
+  getMlReadiness: () => invoke<MlReadiness>('get_ml_readiness'),
   getRichContext: () => invoke<RichContext>('get_rich_context'),
 
   // Big 3 Goals
@@ -319,8 +564,33 @@ export const tauri = {
   completeBigThree: (goalId: number, satisfaction?: number) =>
     invoke<void>('complete_big_three', { goalId, satisfaction }),
 
+  // What now
+  getNextBestTask: (availableMinutes: number) =>
+    invoke<Array<NextTask>>('get_next_best_task', { availableMinutes }),
+  getMorningBriefing: () => invoke<MorningBriefing>('get_morning_briefing'),
+  getCourseDigests: () => invoke<Array<CourseDigest>>('get_course_digests'),
+  /** Empty unless today is the digest day and they have not been shown yet */
+  getDueCourseDigests: () => invoke<Array<CourseDigest>>('get_due_course_digests'),
+  ackCourseDigests: (courses: number) => invoke<void>('ack_course_digests', { courses }),
+  previewAccountabilityReport: (weekEnd?: string) =>
+    invoke<AccountabilityReport>('preview_accountability_report', { weekEnd }),
+  sendAccountabilityReport: (weekEnd?: string) =>
+    invoke<AccountabilityDelivery>('send_accountability_report', { weekEnd }),
+  /** A mailto draft to open with the partner's address; ack it once opened */
+  getDueAccountabilityDraft: () => invoke<AccountabilityDelivery | null>('get_due_accountability_draft'),
+  ackAccountabilityDraft: (weekEnd: string) => invoke<void>('ack_accountability_draft', { weekEnd }),
+  getAccountabilityDeliveries: () => invoke<Array<AccountabilityDelivery>>('get_accountability_deliveries'),
+  /** Call without decisions first; answer `pending_big_three` and call again */
+  runDailyShutdown: (data?: ShutdownInput) =>
+    invoke<ShutdownResult>('run_daily_shutdown', { data }),
+
   // Agent Maintenance
   runAgentMaintenance: () => invoke<void>('run_agent_maintenance'),
+  getAgentShadowMode: () => invoke<boolean>('get_agent_shadow_mode'),
+  setAgentShadowMode: (enabled: boolean) =>
+    invoke<void>('set_agent_shadow_mode', { enabled }),
+  getShadowReport: (days?: number) =>
+    invoke<ShadowReport>('get_shadow_report', { days }),
   getFeatureNames: () => invoke<Array<string>>('get_feature_names'),
   searchSimilarExperiences: (query: string, limit?: number) =>
     invoke<Array<SimilarExperience>>('search_similar_experiences', { query, limit }),
@@ -349,11 +619,108 @@ export const tauri = {
   // Physical Analytics
   getWorkoutHeatmap: (months: number) =>
     invoke<Array<WorkoutHeatmapDay>>('get_workout_heatmap', { months }),
+  getWeeklyAttentionReport: (weekStart?: string) =>
+    invoke<AttentionReport>('get_weekly_attention_report', { weekStart }),
+  getPomodoroStats: (days?: number) =>
+    invoke<PomodoroStats>('get_pomodoro_stats', { days }),
+  getSessionTypeStats: (days?: number) =>
+    invoke<Array<SessionTypeStats>>('get_session_type_stats', { days }),
+  exportAnalyticsCsv: (metric: ExportMetric, path: string, range?: ExportRange, taskId?: string) =>
+    invoke<ExportResult>('export_analytics_csv', { metric, range, path, taskId }),
+  exportCourseDeadlines: (courseId: number, path: string) =>
+    invoke<DeadlineExportResult>('export_course_deadlines', { courseId, path }),
+  importCourseDeadlines: (courseId: number, path: string, taskId?: string) =>
+    invoke<DeadlineImportResult>('import_course_deadlines', { courseId, path, taskId }),
+
+  // Local telemetry
+  getTelemetryReport: (days?: number) => invoke<TelemetryReport>('get_telemetry_report', { days }),
+  exportTelemetry: (path: string, days?: number) =>
+    invoke<TelemetryExportResult>('export_telemetry', { path, days }),
+  clearTelemetry: () => invoke<number>('clear_telemetry'),
+
+  // Local HTTP API
+  getHttpApiStatus: () => invoke<HttpApiStatus>('get_http_api_status'),
+  regenerateHttpApiToken: () => invoke<string>('regenerate_http_api_token'),
+
+  // Calendar feed
+  getCalendarFeedStatus: () => invoke<CalendarFeedStatus>('get_calendar_feed_status'),
+  /** Revokes the old feed URL */
+  regenerateCalendarFeedToken: () => invoke<CalendarFeedStatus>('regenerate_calendar_feed_token'),
+  /** Path written, or null when no feed path is set */
+  publishCalendarFeed: () => invoke<string | null>('publish_calendar_feed'),
+  getMcpAuditLog: (limit?: number) => invoke<Array<McpAuditEntry>>('get_mcp_audit_log', { limit }),
   getPersonalRecords: () => invoke<Array<PersonalRecord>>('get_personal_records'),
   checkAndUpdatePrs: (workoutId: number) =>
     invoke<Array<PersonalRecord>>('check_and_update_prs', { workoutId }),
   getAchievements: () => invoke<Array<Achievement>>('get_achievements'),
   checkAchievements: () => invoke<Array<Achievement>>('check_achievements'),
+  getGamificationStatus: () => invoke<GamificationStatus>('get_gamification_status'),
+
+  // Challenges
+  createChallenge: (data: ChallengeInput) => invoke<Challenge>('create_challenge', { data }),
+  getChallenges: (includeFinished?: boolean) => invoke<Array<Challenge>>('get_challenges', { includeFinished }),
+  deleteChallenge: (id: number) => invoke<boolean>('delete_challenge', { id }),
+
+  // Trackers
+  getTrackers: () => invoke<Array<TrackerSummary>>('get_trackers'),
+  createTracker: (data: TrackerInput) => invoke<Tracker>('create_tracker', { data }),
+  /** Deletes the tracker's entries and logs too; built-in trackers stay */
+  deleteTracker: (id: number) => invoke<boolean>('delete_tracker', { id }),
+  addTrackerEntity: (data: TrackerEntityInput) =>
+    invoke<TrackerEntity>('add_tracker_entity', { data }),
+  getTrackerEntities: (trackerId: number, includeCompleted?: boolean) =>
+    invoke<Array<TrackerEntity>>('get_tracker_entities', { trackerId, includeCompleted }),
+  deleteTrackerEntity: (id: number) => invoke<boolean>('delete_tracker_entity', { id }),
+  logTrackerEntry: (data: TrackerLogInput) => invoke<TrackerLog>('log_tracker_entry', { data }),
+  getTrackerLogs: (trackerId: number, limit?: number) =>
+    invoke<Array<TrackerLog>>('get_tracker_logs', { trackerId, limit }),
+  deleteTrackerLog: (id: number) => invoke<boolean>('delete_tracker_log', { id }),
+
+  // Meditation
+  /** Bells and the end of the sit arrive as `meditation://timer` events */
+  startMeditationTimer: (style: MeditationStyle, plannedMinutes: number, intervalMinutes?: number) =>
+    invoke<MeditationTimer>('start_meditation_timer', { style, plannedMinutes, intervalMinutes }),
+  getMeditationTimer: () => invoke<MeditationTimer | null>('get_meditation_timer'),
+  cancelMeditationTimer: () => invoke<boolean>('cancel_meditation_timer'),
+  finishMeditationTimer: (calmRating?: number, note?: string) =>
+    invoke<TrackerLog>('finish_meditation_timer', { calmRating, note }),
+  logMeditation: (data: MeditationInput) => invoke<TrackerLog>('log_meditation', { data }),
+  getMeditationStats: (days?: number) => invoke<MeditationStats>('get_meditation_stats', { days }),
+
+  // Reading
+  getBooks: (includeFinished?: boolean) => invoke<Array<Book>>('get_books', { includeFinished }),
+  addBook: (data: BookInput) => invoke<Book>('add_book', { data }),
+  /** Reaching the last page finishes the book */
+  logReadingSession: (data: ReadingSessionInput) => invoke<TrackerLog>('log_reading_session', { data }),
+  getReadingStats: (days?: number) => invoke<ReadingStats>('get_reading_stats', { days }),
+
+  // Language learning
+  /** Creates the skill with its decks and the listening and speaking session types */
+  createLanguageSkill: (data: LanguageSkillInput) => invoke<LanguageSkill>('create_language_skill', { data }),
+  getLanguageSkills: () => invoke<Array<LanguageSkill>>('get_language_skills'),
+  addFlashcardDeck: (data: FlashcardDeckInput) => invoke<FlashcardDeck>('add_flashcard_deck', { data }),
+  /** The review's minutes are also logged as practice */
+  logFlashcardReview: (data: FlashcardReviewInput) => invoke<FlashcardReview>('log_flashcard_review', { data }),
+  getLanguageProficiency: (skillId?: number, days?: number) =>
+    invoke<Array<LanguageProficiency>>('get_language_proficiency', { skillId, days }),
+
+  // Spending check-ins
+  /** Replaces an earlier check-in the same day */
+  logSpendingCheckin: (data: SpendingCheckInInput) => invoke<SpendingCheckIn>('log_spending_checkin', { data }),
+  deleteSpendingCheckin: (date: string) => invoke<boolean>('delete_spending_checkin', { date }),
+  getSpendingSummary: (weeks?: number) => invoke<SpendingSummary>('get_spending_summary', { weeks }),
+
+  // Cycle tracking (opt-in via cycle_tracking_enabled)
+  logCyclePeriod: (data: CyclePeriodInput) => invoke<CyclePeriod>('log_cycle_period', { data }),
+  getCyclePeriods: () => invoke<Array<CyclePeriod>>('get_cycle_periods'),
+  deleteCyclePeriod: (id: number) => invoke<boolean>('delete_cycle_period', { id }),
+  getCyclePrediction: () => invoke<CyclePrediction | null>('get_cycle_prediction'),
+  getCycleForecast: (days?: number) => invoke<Array<CycleDay>>('get_cycle_forecast', { days }),
+
+  // Social time
+  logSocialEvent: (data: SocialEventInput) => invoke<TrackerLog>('log_social_event', { data }),
+  getSocialEvents: (limit?: number) => invoke<Array<TrackerLog>>('get_social_events', { limit }),
+  getSocialStats: (days?: number) => invoke<SocialStats>('get_social_stats', { days }),
 
   // Calendar aggregation
   getCalendarItems: (
@@ -370,6 +737,53 @@ export const tauri = {
         includeExams,
       },
     }),
+  /** Weekly meetings and recurring events are written as RRULE series */
+  exportCalendarIcs: (
+    path: string,
+    startDate: string,
+    endDate: string,
+    includeAssignments?: boolean,
+    includeExams?: boolean,
+  ) =>
+    invoke<IcsExportResult>('export_calendar_ics', {
+      query: {
+        start_date: startDate,
+        end_date: endDate,
+        include_assignments: includeAssignments,
+        include_exams: includeExams,
+      },
+      path,
+    }),
+  /**
+   * Recurring events are stored per occurrence; importing again under the
+   * same source (the file name by default) updates them in place
+   */
+  importIcsFile: (path: string, source?: string, category?: string) =>
+    invoke<IcsImportResult>('import_ics_file', { path, source, category }),
+
+  // Categories (calendar colors and busy/free)
+  getCategories: () => invoke<Array<Category>>('get_categories'),
+  createCategory: (data: CategoryInput) =>
+    invoke<Category>('create_category', { data }),
+  updateCategory: (id: number, data: CategoryUpdate) =>
+    invoke<Category>('update_category', { id, data }),
+  deleteCategory: (id: number) => invoke<void>('delete_category', { id }),
+
+  // Free slots within working hours, outside blackout windows
+  findFreeSlots: (
+    startDate: string,
+    endDate: string,
+    minMinutes?: number,
+    includeSuggested?: boolean,
+  ) =>
+    invoke<Array<FreeSlot>>('find_free_slots', {
+      query: {
+        start_date: startDate,
+        end_date: endDate,
+        min_minutes: minMinutes,
+        include_suggested: includeSuggested,
+      },
+    }),
 
   // Week plan blocks
   createWeekPlanBlock: (data: WeekPlanBlockInput) =>
@@ -386,6 +800,22 @@ export const tauri = {
     invoke<number>('clear_suggested_blocks', { weekStartDate }),
   bulkCreatePlanBlocks: (blocks: Array<WeekPlanBlockInput>) =>
     invoke<Array<WeekPlanBlock>>('bulk_create_plan_blocks', { blocks }),
+  /** Pomodoro sub-blocks to pass to `bulkCreatePlanBlocks`; first focus profile by default */
+  splitPlanBlocks: (blocks: Array<WeekPlanBlockInput>, focusProfileId?: number) =>
+    invoke<Array<WeekPlanBlockInput>>('split_plan_blocks', { blocks, focusProfileId }),
+  /** Start (or resume) the session for a block; ending it records the block's actual minutes */
+  startSessionFromBlock: (blockId: number, focusProfileId?: number) =>
+    invoke<Session>('start_session_from_block', { blockId, focusProfileId }),
+  getPlannedHoursByDeliverable: (courseId?: number) =>
+    invoke<Array<DeliverablePlanHours>>('get_planned_hours_by_deliverable', { courseId }),
+  /** `week` is any date in the week; defaults to the current week */
+  getCapacityReport: (week?: string) =>
+    invoke<CapacityReport>('get_capacity_report', { week }),
+  getPlanRealism: (weeks?: number) =>
+    invoke<PlanRealismReport>('get_plan_realism', { weeks }),
+  getPlannerMode: () => invoke<PlannerModeStatus>('get_planner_mode'),
+  setPlannerMode: (mode: PlannerMode) =>
+    invoke<PlannerModeStatus>('set_planner_mode', { mode }),
 
   // Google Calendar sync
   setGoogleClientId: (clientId: string) =>
@@ -397,7 +827,75 @@ export const tauri = {
       callbackUrl,
     }),
   googleSyncNow: () => invoke<boolean>('google_sync_now'),
+  googleSyncPreview: () => invoke<GoogleSyncPreview>('google_sync_preview'),
   getGoogleSyncStatus: () =>
     invoke<GoogleSyncStatus>('get_google_sync_status'),
   disconnectGoogle: () => invoke<boolean>('disconnect_google'),
+
+  // External change log
+  getChangeLog: (source?: ChangeSource, limit?: number) =>
+    invoke<Array<ChangeLogEntry>>('get_change_log', { source, limit }),
+  /** Fails with a conflict when the record changed again since, unless forced */
+  revertExternalChange: (id: number, force?: boolean) =>
+    invoke<ChangeLogEntry>('revert_external_change', { id, force }),
+
+  // Onboarding
+  getOnboardingStatus: () => invoke<OnboardingStatus>('get_onboarding_status'),
+  completeOnboardingStep: (step: OnboardingStep) =>
+    invoke<OnboardingStatus>('complete_onboarding_step', { step }),
+  applyOnboardingSettings: (data: OnboardingSettingsInput) =>
+    invoke<OnboardingStatus>('apply_onboarding_settings', { data }),
+  createOnboardingCourses: (data: OnboardingCoursesInput) =>
+    invoke<OnboardingCoursesResult>('create_onboarding_courses', { data }),
+  resetOnboarding: () => invoke<OnboardingStatus>('reset_onboarding'),
+
+  // Custom metrics
+  createCustomMetric: (data: CustomMetricInput) =>
+    invoke<CustomMetric>('create_custom_metric', { data }),
+  getCustomMetrics: () => invoke<Array<CustomMetric>>('get_custom_metrics'),
+  updateCustomMetric: (id: number, data: CustomMetricInput) =>
+    invoke<CustomMetric>('update_custom_metric', { id, data }),
+  deleteCustomMetric: (id: number) =>
+    invoke<boolean>('delete_custom_metric', { id }),
+  evaluateMetric: (id: number) =>
+    invoke<MetricEvaluation>('evaluate_metric', { id }),
+
+  // Terms
+  getTerms: () => invoke<Array<Term>>('get_terms'),
+  archiveTerm: (data: ArchiveTermInput) =>
+    invoke<ArchiveTermResult>('archive_term', { data }),
+  getTermSnapshots: (termId: number) =>
+    invoke<Array<TermSnapshot>>('get_term_snapshots', { termId }),
+  getSemesterReview: (termId?: number) =>
+    invoke<SemesterReview>('get_semester_review', { termId }),
+
+  // Capabilities
+  getApiCapabilities: () => invoke<ApiCapabilities>('get_api_capabilities'),
+
+  // Glance (tray popover / widgets)
+  getGlanceData: () => invoke<GlanceData>('get_glance_data'),
+
+  // Focus mode
+  getSessionDistractionReport: (sessionId: number) =>
+    invoke<DistractionReport>('get_session_distraction_report', { sessionId }),
+  getEnergyBudgets: (startDate: string, endDate: string) =>
+    invoke<Array<EnergyBudget>>('get_energy_budgets', { startDate, endDate }),
+
+  // Quick actions
+  getQuickActions: () => invoke<Array<QuickActionStatus>>('get_quick_actions'),
+  runQuickAction: (action: QuickAction) =>
+    invoke<QuickActionOutcome>('run_quick_action', { action }),
+
+  // Settings
+  getSetting: <K extends SettingKey>(key: K) =>
+    invoke<Settings[K]>('get_setting', { key }),
+  setSetting: <K extends SettingKey>(key: K, value: Settings[K]) =>
+    invoke<Settings[K]>('set_setting', { key, value }),
+  getSettings: () => invoke<Settings>('get_settings'),
+  getSettingsSchema: () => invoke<Array<SettingDef>>('get_settings_schema'),
+
+  // Sample data
+  loadSampleData: () => invoke<SampleDataCounts>('load_sample_data'),
+  removeSampleData: () => invoke<SampleDataCounts>('remove_sample_data'),
+  getSampleDataStatus: () => invoke<SampleDataCounts>('get_sample_data_status'),
 }
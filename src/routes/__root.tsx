@@ -87,6 +87,39 @@ function RootDocument({ children }: { children: React.ReactNode }) {
       if (intervalId) window.clearInterval(intervalId)
     }
   }, [])
+
+  React.useEffect(() => {
+    // Weekly course digests (best-effort); only returned on the digest day
+    if (!('Notification' in window) || Notification.permission !== 'granted') return
+    tauri
+      .getDueCourseDigests()
+      .then(async (digests) => {
+        if (digests.length === 0) return
+        for (const digest of digests) {
+          new Notification(`${digest.course_code ?? digest.course_name} this week`, {
+            body: digest.text,
+          })
+        }
+        await tauri.ackCourseDigests(digests.length)
+      })
+      .catch((err) => {
+        console.warn('Failed to show course digests:', err)
+      })
+  }, [])
+
+  React.useEffect(() => {
+    // Weekly accountability report sent as an email draft (best-effort)
+    tauri
+      .getDueAccountabilityDraft()
+      .then(async (draft) => {
+        if (!draft?.target) return
+        window.open(draft.target)
+        await tauri.ackAccountabilityDraft(draft.week_end)
+      })
+      .catch((err) => {
+        console.warn('Failed to open accountability report draft:', err)
+      })
+  }, [])
   return (
     <html lang="en" className="h-full" suppressHydrationWarning>
       <head>
@@ -15,7 +15,18 @@ import { Button } from '@/components/ui/button'
 import { cn } from '@/lib/utils'
 import { formatTime, getWeekDays, weekStart } from '@/lib/time'
 import { tauri } from '@/lib/tauri'
-import type { CalendarItem, WeekPlanBlockInput } from '@/types'
+import type {
+  Assignment,
+  BalanceDomain,
+  CalendarItem,
+  Course,
+  Exam,
+  FreeSlot,
+  MeetingFocus,
+  PlannerMode,
+  SkillPracticeNeed,
+  WeekPlanBlockInput,
+} from '@/types'
 
 export const Route = createFileRoute('/calendar')({
   component: CalendarPage,
@@ -24,6 +35,16 @@ export const Route = createFileRoute('/calendar')({
 const HOUR_HEIGHT = 56
 const STEP_MINUTES = 15
 const DEFAULT_BLOCK_MINUTES = 90
+/** How far ahead exams get prep blocks */
+const DELIVERABLE_HORIZON_DAYS = 21
+/** Longest practice block the planner suggests */
+const PRACTICE_BLOCK_MINUTES = 60
+
+const PLANNER_MODE_LABELS: Record<PlannerMode, string> = {
+  normal: 'normal',
+  exam_period: 'exam period',
+  break: 'break',
+}
 
 function CalendarPage() {
   const queryClient = useQueryClient()
@@ -43,6 +64,26 @@ function CalendarPage() {
     queryFn: () => tauri.getCalendarItems(startDate, endDate),
   })
 
+  const capacityQuery = useQuery({
+    queryKey: ['capacity-report', startDate],
+    queryFn: () => tauri.getCapacityReport(startDate),
+  })
+
+  const realismQuery = useQuery({
+    queryKey: ['plan-realism'],
+    queryFn: () => tauri.getPlanRealism(),
+  })
+
+  const plannerModeQuery = useQuery({
+    queryKey: ['planner-mode'],
+    queryFn: tauri.getPlannerMode,
+  })
+
+  const setPlannerMode = useMutation({
+    mutationFn: (mode: PlannerMode) => tauri.setPlannerMode(mode),
+    onSuccess: (status) => queryClient.setQueryData(['planner-mode'], status),
+  })
+
   const syncStatusQuery = useQuery({
     queryKey: ['google-sync-status'],
     queryFn: tauri.getGoogleSyncStatus,
@@ -52,6 +93,7 @@ function CalendarPage() {
     mutationFn: tauri.googleSyncNow,
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['calendar-items'] })
+      queryClient.invalidateQueries({ queryKey: ['capacity-report'] })
       queryClient.invalidateQueries({ queryKey: ['google-sync-status'] })
     },
   })
@@ -61,6 +103,7 @@ function CalendarPage() {
       tauri.updateWeekPlanBlock(id, data),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['calendar-items'] })
+      queryClient.invalidateQueries({ queryKey: ['capacity-report'] })
     },
   })
 
@@ -68,6 +111,7 @@ function CalendarPage() {
     mutationFn: (id: number) => tauri.acceptWeekPlanBlock(id),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['calendar-items'] })
+      queryClient.invalidateQueries({ queryKey: ['capacity-report'] })
     },
   })
 
@@ -75,6 +119,15 @@ function CalendarPage() {
     mutationFn: (id: number) => tauri.lockWeekPlanBlock(id),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['calendar-items'] })
+      queryClient.invalidateQueries({ queryKey: ['capacity-report'] })
+    },
+  })
+
+  const startFromBlock = useMutation({
+    mutationFn: (id: number) => tauri.startSessionFromBlock(id),
+    onSuccess: () => {
+      queryClient.invalidateQueries({ queryKey: ['calendar-items'] })
+      queryClient.invalidateQueries({ queryKey: [['sessions']] })
     },
   })
 
@@ -82,22 +135,108 @@ function CalendarPage() {
     mutationFn: (id: number) => tauri.deleteWeekPlanBlock(id),
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['calendar-items'] })
+      queryClient.invalidateQueries({ queryKey: ['capacity-report'] })
       setSelectedItem(null)
     },
   })
 
   const generatePlan = useMutation({
     mutationFn: async () => {
-      const items = calendarQuery.data ?? []
       const weekStartDate = format(weekStart(days[0]), 'yyyy-MM-dd')
       await tauri.clearSuggestedBlocks(weekStartDate)
-      const suggestions = buildSuggestedBlocks(days, items, weekStartDate)
+      // Workouts go in first so study blocks plan around them
+      const workouts = await tauri.suggestWorkoutBlocks(weekStartDate)
+      if (workouts.length > 0) {
+        await tauri.bulkCreatePlanBlocks(workouts)
+      }
+      // Plan only as much as past plans turned into deep work
+      const blockMinutes = Math.max(
+        STEP_MINUTES * 2,
+        Math.round((DEFAULT_BLOCK_MINUTES * (realismQuery.data?.plan_scale ?? 1)) / STEP_MINUTES) *
+          STEP_MINUTES,
+      )
+      // Slots already honor working hours and blackout windows
+      const slots = await tauri.findFreeSlots(
+        format(days[0], 'yyyy-MM-dd'),
+        format(days[days.length - 1], 'yyyy-MM-dd'),
+        blockMinutes,
+        true,
+      )
+      const [
+        assignments,
+        exams,
+        courses,
+        skillNeeds,
+        studyCap,
+        practiceCap,
+        switchPenalty,
+        splitPomodoros,
+        plannerMode,
+        readiness,
+        stats,
+        energy,
+        meetingFocus,
+      ] = await Promise.all([
+        tauri.getAssignments(),
+        tauri.getUpcomingExams(DELIVERABLE_HORIZON_DAYS),
+        tauri.getCourses(),
+        tauri.getSkillPracticeNeeds(weekStartDate),
+        tauri.getSetting('planner_study_hours_cap'),
+        tauri.getSetting('planner_practice_hours_cap'),
+        tauri.getSetting('planner_switch_penalty'),
+        tauri.getSetting('planner_split_pomodoros'),
+        tauri.getPlannerMode(),
+        tauri.getExamReadiness(DELIVERABLE_HORIZON_DAYS),
+        tauri.getDetailedStats(),
+        tauri.getEnergyBudgets(
+          format(days[0], 'yyyy-MM-dd'),
+          format(days[days.length - 1], 'yyyy-MM-dd'),
+        ),
+        tauri.getMeetingFocusStats(),
+      ])
+      const { weights } = plannerMode
+      // Nudge time toward domains the last week under-served
+      const balanceScale = (domain: BalanceDomain) =>
+        stats.balance.domains.find((d) => d.domain === domain)?.planner_scale ?? 1
+      const deliverables = openDeliverables(assignments, exams)
+      // Self-study in place of lectures it has historically beaten
+      const substitutes = substituteBlocks(days, weekStartDate, meetingFocus.meetings)
+      const substituteMinutes = substitutes.reduce(
+        (sum, block) => sum + differenceInMinutes(parseDate(block.end_at), parseDate(block.start_at)),
+        0,
+      )
+      const planned = buildSuggestedBlocks(
+        slots,
+        weekStartDate,
+        blockMinutes,
+        deliverables,
+        skillNeeds,
+        {
+          studyMinutes: Math.max(
+            0,
+            studyCap * 60 * weights.study_scale * balanceScale('academics') - substituteMinutes,
+          ),
+          practiceMinutes: practiceCap * 60 * weights.practice_scale * balanceScale('skills'),
+          energyMinutes: new Map(energy.map((day) => [day.date, day.demanding_minutes_left])),
+        },
+        {
+          courses: new Map(courses.map((course) => [course.id, course])),
+          switchPenalty,
+          revisionWeight: weights.revision_weight,
+          examWeights: new Map(readiness.map((exam) => [exam.exam_id, exam.planner_weight])),
+        },
+      )
+      let suggestions = [...substitutes, ...planned]
+      if (splitPomodoros && suggestions.length > 0) {
+        suggestions = await tauri.splitPlanBlocks(suggestions)
+      }
       if (suggestions.length > 0) {
         await tauri.bulkCreatePlanBlocks(suggestions)
       }
     },
     onSuccess: () => {
       queryClient.invalidateQueries({ queryKey: ['calendar-items'] })
+      queryClient.invalidateQueries({ queryKey: ['capacity-report'] })
     },
   })
 
@@ -231,6 +370,12 @@ function CalendarPage() {
     }
   }
 
+  const handleStartSession = async (item: CalendarItem) => {
+    const id = parsePlanBlockId(item.id)
+    if (!id) return
+    await startFromBlock.mutateAsync(id)
+  }
+
   const handleLock = async (item: CalendarItem) => {
     const id = parsePlanBlockId(item.id)
     if (!id) return
@@ -291,6 +436,39 @@ function CalendarPage() {
           </div>
         </div>
 
+        {plannerModeQuery.data?.suggested_mode && (
+          <div className="flex flex-wrap items-center justify-between gap-2 rounded-xl border border-sky-500/40 bg-sky-500/10 p-3 text-sm">
+            <p>
+              <span className="font-semibold">
+                Switch to {PLANNER_MODE_LABELS[plannerModeQuery.data.suggested_mode]} mode?
+              </span>{' '}
+              <span className="text-muted-foreground">{plannerModeQuery.data.reason}</span>
+            </p>
+            <Button
+              size="sm"
+              variant="outline"
+              onClick={() => setPlannerMode.mutate(plannerModeQuery.data!.suggested_mode!)}
+              disabled={setPlannerMode.isPending}
+            >
+              Switch
+            </Button>
+          </div>
+        )}
+
+        {(capacityQuery.data?.warnings.length ?? 0) > 0 && (
+          <div className="rounded-xl border border-amber-500/40 bg-amber-500/10 p-3 text-sm">
+            <p className="font-semibold">
+              {Math.round((capacityQuery.data?.utilization_with_suggested ?? 0) * 100)}% of
+              available hours committed with suggested blocks
+            </p>
+            <ul className="mt-1 list-disc pl-5 text-xs text-muted-foreground">
+              {capacityQuery.data?.warnings.map((warning) => (
+                <li key={warning}>{warning}</li>
+              ))}
+            </ul>
+          </div>
+        )}
+
         <div className="grid gap-6 lg:grid-cols-[1fr_320px]">
           <div className="space-y-4">
             <div className="rounded-xl border bg-card">
@@ -505,6 +683,15 @@ function CalendarPage() {
                   )}
                   {selectedItem.source === 'plan_block' && (
                     <div className="flex flex-wrap gap-2">
+                      {selectedItem.category !== 'break' && (
+                        <Button
+                          size="sm"
+                          onClick={() => handleStartSession(selectedItem)}
+                          disabled={startFromBlock.isPending}
+                        >
+                          Start session
+                        </Button>
+                      )}
                       {selectedItem.status === 'suggested' && (
                         <>
                           <Button
@@ -598,7 +785,7 @@ function parsePlanBlockId(id: string) {
 
 function normalizeBlockType(type?: string | null) {
   if (!type) return 'study'
-  const allowed = new Set(['study', 'assignment', 'exam_prep', 'break', 'weekly_task'])
+  const allowed = new Set(['study', 'assignment', 'exam_prep', 'break', 'weekly_task', 'practice', 'workout'])
   return allowed.has(type) ? type : 'study'
 }
 
@@ -675,83 +862,237 @@ function groupTimedItems(days: Date[], items: Array<CalendarItem>) {
   return map
 }
 
-function buildSuggestedBlocks(
+type Deliverable =
+  | { kind: 'assignment'; item: Assignment; due: Date }
+  | { kind: 'exam'; item: Exam; due: Date }
+
+/** Unfinished assignments and ungraded exams with a date, soonest first */
+function openDeliverables(assignments: Array<Assignment>, exams: Array<Exam>): Array<Deliverable> {
+  const open: Array<Deliverable> = []
+  for (const item of assignments) {
+    if (item.is_completed || !item.due_date) continue
+    open.push({ kind: 'assignment', item, due: parseDate(item.due_date) })
+  }
+  for (const item of exams) {
+    if (item.score != null || !item.exam_date) continue
+    open.push({ kind: 'exam', item, due: parseDate(item.exam_date) })
+  }
+  return open.sort((a, b) => a.due.getTime() - b.due.getTime())
+}
+
+/** A self-study block at the time of each substitutable lecture this week */
+function substituteBlocks(
   days: Date[],
-  items: Array<CalendarItem>,
   weekStartDate: string,
+  meetings: Array<MeetingFocus>,
 ): Array<WeekPlanBlockInput> {
-  const busyMap = new Map<string, Array<{ start: Date; end: Date }>>()
-  for (const day of days) {
-    busyMap.set(format(day, 'yyyy-MM-dd'), [])
+  const blocks: Array<WeekPlanBlockInput> = []
+  const today = startOfDay(new Date())
+  for (const meeting of meetings.filter((m) => m.substitute)) {
+    for (const day of days.filter((d) => d.getDay() === meeting.day_of_week && d >= today)) {
+      const date = format(day, 'yyyy-MM-dd')
+      blocks.push({
+        week_start_date: weekStartDate,
+        start_at: `${date}T${meeting.start_time}:00`,
+        end_at: `${date}T${meeting.end_time}:00`,
+        block_type: 'study',
+        title: `Self-study: ${meeting.course_name} (instead of ${meeting.meeting_type})`,
+        course_id: meeting.course_id,
+        status: 'suggested',
+        rationale_json: JSON.stringify({
+          reason: 'Self-study has focused better than this lecture',
+          meeting_id: meeting.meeting_id,
+          meeting_focus: meeting.avg_focus,
+          self_study_focus: meeting.self_study_focus,
+        }),
+      })
+    }
   }
+  return blocks
+}
 
-  for (const item of items) {
-    if (item.source === 'plan_block' && item.status === 'suggested') {
-      continue
-    }
-    const start = parseDate(item.start_at)
-    const end = parseDate(item.end_at)
-    const key = format(start, 'yyyy-MM-dd')
-    if (!busyMap.has(key)) {
-      busyMap.set(key, [])
-    }
-    if (item.all_day) {
-      const dayStart = startOfDay(start)
-      const blockStart = addMinutes(dayStart, 8 * 60)
-      const blockEnd = addMinutes(dayStart, 20 * 60)
-      busyMap.get(key)!.push({ start: blockStart, end: blockEnd })
-    } else {
-      busyMap.get(key)!.push({ start, end })
-    }
+/** Weekly minutes the planner may suggest per domain */
+interface DomainCaps {
+  studyMinutes: number
+  practiceMinutes: number
+  /** Study minutes each day's energy budget still covers, by date */
+  energyMinutes?: Map<string, number>
+}
+
+/** How study blocks are shaped per course */
+interface StudyPreferences {
+  /** Minimum block length and preferred days, by course id */
+  courses: Map<number, Course>
+  /** Score a block loses for a different course than the day's previous one */
+  switchPenalty: number
+  /** Multiplies the score of exam revision, from the planner mode */
+  revisionWeight: number
+  /** Further multiplies an exam's revision score by exam id, from its readiness */
+  examWeights: Map<number, number>
+}
+
+/** Weekdays from a course's `preferred_days`, or null for any day */
+function preferredDays(course?: Course): Set<number> | null {
+  if (!course?.preferred_days) return null
+  try {
+    const days = JSON.parse(course.preferred_days)
+    return Array.isArray(days) && days.length > 0 ? new Set(days) : null
+  } catch {
+    return null
   }
+}
 
+function buildSuggestedBlocks(
+  slots: Array<FreeSlot>,
+  weekStartDate: string,
+  blockMinutes: number = DEFAULT_BLOCK_MINUTES,
+  deliverables: Array<Deliverable> = [],
+  skillNeeds: Array<SkillPracticeNeed> = [],
+  caps: DomainCaps = { studyMinutes: Infinity, practiceMinutes: 0 },
+  preferences: StudyPreferences = {
+    courses: new Map(),
+    switchPenalty: 0,
+    revisionWeight: 1,
+    examWeights: new Map(),
+  },
+): Array<WeekPlanBlockInput> {
   const suggestions: Array<WeekPlanBlockInput> = []
+  // Free time left in each slot, used up from the start
+  const open = slots.map((slot) => ({
+    date: slot.date,
+    start: parseDate(slot.start_at),
+    end: parseDate(slot.end_at),
+  }))
+  const fits = (date: string, minutes: number) =>
+    open.find((o) => o.date === date && differenceInMinutes(o.end, o.start) >= minutes)
+  const take = (date: string, minutes: number) => {
+    const slot = fits(date, minutes)
+    if (!slot) return null
+    const start = slot.start
+    slot.start = addMinutes(start, minutes)
+    return { start, end: slot.start }
+  }
+  const dates = [...new Set(slots.map((slot) => slot.date))]
+  let studyLeft = caps.studyMinutes
+  let practiceLeft = caps.practiceMinutes
+  // Spread the study cap evenly so early days don't take all of it
+  const dailyStudyMinutes = Math.max(
+    blockMinutes,
+    Math.ceil(caps.studyMinutes / Math.max(dates.length, 1) / STEP_MINUTES) * STEP_MINUTES,
+  )
+  const practice = skillNeeds.map((need) => ({
+    need,
+    total: Math.round(need.remaining_hours * 60),
+    left: Math.round(need.remaining_hours * 60),
+  }))
+
+  // Each day gets study blocks in its first slots that fit, then a practice
+  // block, each domain up to its weekly cap
+  for (const date of dates) {
+    const weekday = parseDate(date).getDay()
+    let dayLeft = Math.min(
+      dailyStudyMinutes,
+      studyLeft,
+      caps.energyMinutes?.get(date) ?? Infinity,
+    )
+    // Course of the day's previous study block; undefined before the first
+    let lastCourse: number | null | undefined
+    let last: { block: WeekPlanBlockInput; deliverable?: Deliverable } | null = null
+
+    while (dayLeft >= blockMinutes) {
+      // Score what is due next: sooner is better, off days and switching
+      // course cost, and a course's blocks are never shorter than its minimum
+      let best: { deliverable: Deliverable; minutes: number; score: number } | null = null
+      const dayStart = parseDate(date)
+      for (const deliverable of deliverables) {
+        const course = preferences.courses.get(deliverable.item.course_id)
+        const minutes = Math.max(blockMinutes, course?.min_block_minutes ?? 0)
+        const slot = minutes <= studyLeft ? fits(date, minutes) : undefined
+        if (!slot) continue
+        if (deliverable.due.getTime() <= addMinutes(slot.start, minutes).getTime()) continue
+        const daysLeft = Math.max(0, differenceInMinutes(deliverable.due, dayStart) / (24 * 60))
+        let score = 100 / (1 + daysLeft)
+        if (deliverable.kind === 'exam') {
+          score *= preferences.revisionWeight * (preferences.examWeights.get(deliverable.item.id) ?? 1)
+        }
+        const days = preferredDays(course)
+        if (days && !days.has(weekday)) score /= 2
+        if (lastCourse !== undefined && lastCourse !== deliverable.item.course_id) {
+          score -= preferences.switchPenalty
+        }
+        if (!best || score > best.score) best = { deliverable, minutes, score }
+      }
 
-  for (const day of days) {
-    const key = format(day, 'yyyy-MM-dd')
-    const intervals = busyMap.get(key) ?? []
-    const slot = findFirstSlot(day, intervals, DEFAULT_BLOCK_MINUTES)
-    if (!slot) continue
+      const minutes = best?.minutes ?? blockMinutes
+      const study = take(date, minutes)
+      if (!study) break
+      studyLeft -= minutes
+      dayLeft -= minutes
+      lastCourse = best?.deliverable.item.course_id ?? null
+
+      // Back-to-back blocks on the same work become one longer block
+      if (
+        last &&
+        last.deliverable === best?.deliverable &&
+        last.block.end_at === formatLocalDateTime(study.start)
+      ) {
+        last.block.end_at = formatLocalDateTime(study.end)
+        continue
+      }
+
+      const block: WeekPlanBlockInput = {
+        week_start_date: weekStartDate,
+        start_at: formatLocalDateTime(study.start),
+        end_at: formatLocalDateTime(study.end),
+        block_type: 'study',
+        title: 'Focus block',
+        status: 'suggested',
+      }
+      const next = best?.deliverable
+      if (next?.kind === 'assignment') {
+        Object.assign(block, {
+          block_type: 'assignment',
+          title: next.item.title,
+          course_id: next.item.course_id,
+          assignment_id: next.item.id,
+        })
+      } else if (next?.kind === 'exam') {
+        Object.assign(block, {
+          block_type: 'exam_prep',
+          title: `Prep: ${next.item.title}`,
+          course_id: next.item.course_id,
+          exam_id: next.item.id,
+        })
+      }
+      suggestions.push(block)
+      last = { block, deliverable: next }
+    }
 
+    // The skill with the most urgent share of its need still open
+    const skill = practice
+      .filter((p) => p.left > 0)
+      .sort((a, b) => b.need.priority * (b.left / b.total) - a.need.priority * (a.left / a.total))[0]
+    if (!skill) continue
+    const minutes = Math.min(
+      PRACTICE_BLOCK_MINUTES,
+      practiceLeft,
+      Math.max(STEP_MINUTES * 2, Math.ceil(skill.left / STEP_MINUTES) * STEP_MINUTES),
+    )
+    if (minutes < STEP_MINUTES * 2) continue
+    const slot = take(date, minutes)
+    if (!slot) continue
+    practiceLeft -= minutes
+    skill.left -= minutes
     suggestions.push({
       week_start_date: weekStartDate,
       start_at: formatLocalDateTime(slot.start),
       end_at: formatLocalDateTime(slot.end),
-      block_type: 'study',
-      title: 'Focus block',
+      block_type: 'practice',
+      title: `Practice: ${skill.need.name}`,
+      skill_id: skill.need.skill_id,
       status: 'suggested',
     })
   }
 
   return suggestions
 }
-
-function findFirstSlot(
-  day: Date,
-  busy: Array<{ start: Date; end: Date }>,
-  durationMinutes: number,
-) {
-  const startMinutes = 8 * 60
-  const endMinutes = 20 * 60
-  const dayStart = startOfDay(day)
-
-  for (
-    let minutes = startMinutes;
-    minutes + durationMinutes <= endMinutes;
-    minutes += STEP_MINUTES
-  ) {
-    const slotStart = addMinutes(dayStart, minutes)
-    const slotEnd = addMinutes(slotStart, durationMinutes)
-
-    const overlaps = busy.some(
-      (interval) =>
-        slotStart < interval.end && interval.start < slotEnd,
-    )
-
-    if (!overlaps) {
-      return { start: slotStart, end: slotEnd }
-    }
-  }
-
-  return null
-}
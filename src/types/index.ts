@@ -17,6 +17,16 @@ export interface Course {
   created_at?: string
   current_grade?: number
   target_grade?: number
+  /** Shortest study block the planner suggests for the course */
+  min_block_minutes?: number | null
+  /** Weekdays to plan study on as a JSON array, 0 = Sunday; null means any day */
+  preferred_days?: string | null
+}
+
+export interface CourseSchedulePrefsInput {
+  min_block_minutes?: number | null
+  /** Weekdays, 0 = Sunday; empty means any day */
+  preferred_days: Array<number>
 }
 
 export interface CourseWithProgress extends Course {
@@ -53,18 +63,246 @@ export interface Assignment {
   is_completed?: number
   completed_at?: string
   created_at?: string
+  /** Percent score once graded */
+  score?: number
+  estimated_minutes?: number
+  /** Time actually spent, once done */
+  actual_minutes?: number
+}
+
+/** One step of an assignment's submission checklist */
+export interface AssignmentChecklistItem {
+  id: number
+  assignment_id: number
+  label: string
+  /** Required items must be done before the assignment can be marked done */
+  is_required: boolean
+  is_done: boolean
+  done_at: string | null
+  position: number
+}
+
+export interface CourseCalibration {
+  course_id: number
+  course_name: string
+  samples: number
+  estimated_minutes: number
+  actual_minutes: number
+  /** Multiply estimates by this to get the expected time */
+  ratio: number
+}
+
+export interface ProcrastinationStats {
+  assignments: number
+  /** Days from creating an assignment to its first session */
+  avg_start_delay_days: number | null
+  /** Days before the deadline the first session started */
+  avg_start_lead_days: number | null
+  /** Hours between completion and the deadline; negative when late */
+  avg_completion_margin_hours: number | null
+  late: number
+}
+
+export interface CourseProcrastination {
+  course_id: number
+  course_name: string
+  stats: ProcrastinationStats
+}
+
+export interface ProcrastinationWeek {
+  week_start: string
+  stats: ProcrastinationStats
+}
+
+export interface ProcrastinationReport {
+  since: string
+  overall: ProcrastinationStats
+  /** Least lead first */
+  by_course: Array<CourseProcrastination>
+  trend: Array<ProcrastinationWeek>
 }
 
 export interface Session {
   id: number
   user_id: number
-  session_type: 'study' | 'practice'
+  /** 'study', 'practice' or the name of a custom session type */
+  session_type: string
   reference_id?: number
   reference_type?: 'course' | 'skill'
   started_at: string
   ended_at?: string
   duration_minutes?: number
   notes?: string
+  planned_minutes?: number
+  focus_rating?: number
+  focus_profile_id?: number
+  title?: string
+  /** Set when started from a course meeting */
+  course_meeting_id?: number
+  meeting_date?: string
+  /** Set when started from a plan block */
+  week_plan_block_id?: number
+}
+
+export type SessionTarget = 'study' | 'practice'
+
+export interface SessionTypeDef {
+  name: string
+  label: string
+  domain: 'academics' | 'skills' | 'fitness' | 'wellness'
+  /** Targets the type's sessions count toward */
+  counts_toward?: SessionTarget
+  builtin: boolean
+  created_at: string
+}
+
+export interface SessionTypeInput {
+  /** Lowercase slug, e.g. "thesis_writing" */
+  name: string
+  label: string
+  domain: SessionTypeDef['domain']
+  counts_toward?: SessionTarget
+}
+
+export interface SessionTypeStats {
+  name: string
+  label: string
+  domain: SessionTypeDef['domain']
+  counts_toward?: SessionTarget
+  sessions: number
+  hours: number
+  avg_focus?: number
+}
+
+export type InterruptionReason = 'phone' | 'messages' | 'person' | 'noise' | 'wandering' | 'other'
+
+export interface Interruption {
+  id: number
+  session_id: number
+  reason: InterruptionReason
+  note?: string
+  occurred_at: string
+}
+
+/** Details of a conflict from a create command that would double-log a row */
+export interface DuplicateRef {
+  kind: 'workout' | 'assignment' | 'session'
+  id: number
+}
+
+export interface MeetingSessionInput {
+  meeting_id: number
+  /** YYYY-MM-DD; defaults to today */
+  date?: string
+  focus_profile_id?: number
+}
+
+export interface LectureNote {
+  id: number
+  course_id: number
+  course_meeting_id: number | null
+  meeting_date: string
+  session_id: number | null
+  topic: string | null
+  content: string
+  created_at: string | null
+  updated_at: string | null
+}
+
+export interface LectureNoteInput {
+  session_id?: number
+  course_meeting_id?: number
+  meeting_date?: string
+  topic?: string
+  content: string
+}
+
+export interface LectureNoteQuery {
+  course_id?: number
+  course_meeting_id?: number
+  start_date?: string
+  end_date?: string
+  search?: string
+}
+
+export type AttachmentOwner = 'practice_log' | 'workout'
+
+export interface Attachment {
+  id: number
+  owner_type: AttachmentOwner
+  owner_id: number
+  kind: 'image' | 'audio' | 'video' | 'other'
+  /** Original file name */
+  file_name: string
+  stored_name: string
+  size_bytes: number
+  created_at: string | null
+  /** Absolute path of the stored file */
+  path: string
+}
+
+export interface AttachmentGcReport {
+  rows_removed: number
+  files_removed: number
+  bytes_freed: number
+}
+
+export interface FocusProfileInput {
+  name: string
+  work_minutes: number
+  break_minutes: number
+  long_break_minutes?: number
+  /** Work blocks before a long break */
+  long_break_every?: number
+  auto_start_breaks?: boolean
+  auto_start_work?: boolean
+  tag?: string | null
+}
+
+export interface FocusProfile extends Required<FocusProfileInput> {
+  id: number
+}
+
+export interface FocusProfileStats {
+  profile_id: number
+  /** null once the profile has been deleted */
+  name: string | null
+  tag: string | null
+  sessions: number
+  total_minutes: number
+  avg_minutes: number
+  avg_focus_rating: number | null
+  completion_rate: number | null
+  avg_outcome: number
+}
+
+export interface Technique {
+  id: number
+  name: string
+  description: string | null
+  is_builtin: boolean
+  created_at: string | null
+}
+
+export interface TechniqueInput {
+  name: string
+  description?: string
+}
+
+export interface TechniqueStats {
+  technique_id: number
+  technique_name: string
+  /** null for sessions not tied to a course */
+  course_id: number | null
+  course_name: string | null
+  sessions: number
+  total_minutes: number
+  avg_focus_rating: number | null
+  /** Share of tracked time not spent in distractions */
+  focus_share: number | null
+  avg_outcome: number
+  /** Next exam/assignment grade for the course within two weeks of the session */
+  avg_next_grade: number | null
 }
 
 export interface Skill {
@@ -87,6 +325,33 @@ export interface PracticeLog {
   logged_at?: string
 }
 
+export type PortfolioFormat = 'json' | 'markdown'
+
+export interface SkillPortfolioExportResult {
+  path: string
+  skills: number
+  total_hours: number
+}
+
+/** Practice a skill still needs in a week, from `get_skill_practice_needs` */
+export interface SkillPracticeNeed {
+  skill_id: number
+  name: string
+  category: string | null
+  target_weekly_hours: number
+  /** Logged this week */
+  practiced_hours: number
+  /** Accepted or locked practice blocks still ahead this week */
+  planned_hours: number
+  remaining_hours: number
+  /** null when never practiced */
+  days_since_practice: number | null
+  /** 0 right after practice, approaching 1 as the skill goes unpracticed */
+  decay: number
+  /** Share of the target still open, weighted up by decay */
+  priority: number
+}
+
 export interface Workout {
   id: number
   user_id: number
@@ -94,6 +359,8 @@ export interface Workout {
   duration_minutes?: number
   notes?: string
   logged_at?: string
+  /** Markdown notes; `notes` is the short summary */
+  notes_md?: string
 }
 
 export interface WorkoutExercise {
@@ -112,6 +379,10 @@ export interface WorkoutWithExercises extends Workout {
   exercises: Array<WorkoutExercise>
 }
 
+export interface WorkoutDetail extends WorkoutWithExercises {
+  attachments: Array<Attachment>
+}
+
 // Template types
 export interface WorkoutTemplate {
   id: number
@@ -119,6 +390,19 @@ export interface WorkoutTemplate {
   name: string
   created_at?: string
   updated_at?: string
+  /** Program weekdays as a JSON array, 0 = Sunday; null means any day */
+  schedule_days?: string | null
+  muscle_group?: string | null
+  is_heavy?: boolean
+  planned_minutes?: number | null
+}
+
+export interface TemplateScheduleInput {
+  /** Program weekdays, 0 = Sunday; empty means any day */
+  days: Array<number>
+  muscle_group?: string | null
+  is_heavy?: boolean
+  planned_minutes?: number | null
 }
 
 export interface WorkoutTemplateExercise {
@@ -210,6 +494,23 @@ export interface AgentRecommendation {
   alternatives: Array<AlternativeAction>
   confidence_level: 'low' | 'medium' | 'high'
   recommendation_id?: number
+  is_shadow: boolean
+}
+
+export interface ShadowActionStats {
+  action_name: string
+  recommended: number
+  evaluated: number
+  followed: number
+}
+
+export interface ShadowReport {
+  days: number
+  recommended: number
+  evaluated: number
+  followed: number
+  follow_rate: number | null
+  by_action: Array<ShadowActionStats>
 }
 
 export interface AgentStatus {
@@ -222,6 +523,15 @@ export interface AgentStatus {
   last_training?: string
 }
 
+export interface MlReadiness {
+  status: 'not_started' | 'loading' | 'ready' | 'unavailable' | 'disabled'
+  semantic_memory: boolean
+  /** False when the model is not installed or failed to load */
+  embeddings: boolean
+  error: string | null
+  load_ms: number | null
+}
+
 export interface BigThreeGoal {
   id: number
   priority: number
@@ -269,12 +579,35 @@ export interface CalendarItem {
   course_id?: number | null
   course_name?: string | null
   category?: string | null
+  /** From the category; unknown categories count as busy */
+  busy: boolean
   status?: string | null
   locked: boolean
   editable: boolean
   metadata_json?: string | null
 }
 
+/** Calendar category; names match event categories and plan block types */
+export interface Category {
+  id: number
+  name: string
+  color: string
+  is_busy: boolean
+  is_builtin: boolean
+  created_at?: string | null
+}
+
+export interface CategoryInput {
+  name: string
+  color: string
+  is_busy?: boolean
+}
+
+export interface CategoryUpdate {
+  color?: string
+  is_busy?: boolean
+}
+
 export interface WeekPlanBlock {
   id: number
   user_id: number
@@ -288,6 +621,24 @@ export interface WeekPlanBlock {
   status?: 'suggested' | 'accepted' | 'locked' | string | null
   rationale_json?: string | null
   created_at?: string | null
+  /** Assignment the block was scheduled for */
+  assignment_id?: number | null
+  /** Exam the block prepares for */
+  exam_id?: number | null
+  /** Shared by the pomodoro sub-blocks split from one block */
+  split_group?: string | null
+  /** Skill a practice block is for */
+  skill_id?: number | null
+  /** Template a workout block suggests */
+  workout_template_id?: number | null
+  /** Work on the block through `start_session_from_block` */
+  progress?: 'in_progress' | 'done' | null
+  /** Length of the block's session once it ended */
+  actual_minutes?: number | null
+  /** Set when nightly maintenance found the block ended without a session */
+  missed_at?: string | null
+  /** Replacement placed later in the week for a missed block */
+  rescheduled_to_id?: number | null
 }
 
 export interface WeekPlanBlockInput {
@@ -301,6 +652,21 @@ export interface WeekPlanBlockInput {
   title?: string | null
   status?: 'suggested' | 'accepted' | 'locked' | string | null
   rationale_json?: string | null
+  assignment_id?: number | null
+  exam_id?: number | null
+  split_group?: string | null
+  skill_id?: number | null
+  workout_template_id?: number | null
+}
+
+/** Accepted and locked plan hours for one assignment or exam */
+export interface DeliverablePlanHours {
+  kind: 'assignment' | 'exam'
+  id: number
+  title: string
+  course_id: number
+  planned_past_hours: number
+  planned_upcoming_hours: number
 }
 
 export interface GoogleAuthBeginResponse {
@@ -308,14 +674,59 @@ export interface GoogleAuthBeginResponse {
   redirect_uri: string
 }
 
+export interface SyncApiCalls {
+  reads: number
+  writes: number
+  /** Calls repeated after a rate limit or server error */
+  retries: number
+}
+
+export interface SyncChange {
+  action: 'create' | 'update' | 'delete'
+  local_type: 'calendar_event' | 'week_plan_block'
+  local_id?: number | null
+  google_calendar_id: string
+  google_event_id?: string | null
+  title?: string | null
+  start_at?: string | null
+  end_at?: string | null
+}
+
+/** What a sync would change, computed without applying it */
+export interface GoogleSyncPreview {
+  local: Array<SyncChange>
+  remote: Array<SyncChange>
+  creates_plan_calendar: boolean
+  interrupted_operations: number
+  calls: SyncApiCalls
+}
+
 export interface GoogleSyncStatus {
   connected: boolean
   email?: string | null
   last_sync?: string | null
+  /** Calendar API calls made by the last sync */
+  last_sync_calls?: SyncApiCalls | null
   client_id_set: boolean
   client_id?: string | null
 }
 
+export type ChangeSource = 'google' | 'deadline_import' | 'ics_import'
+
+/** A write made by sync or an import, with the row before and after as JSON */
+export interface ChangeLogEntry {
+  id: number
+  source: ChangeSource
+  entity_type: 'calendar_events' | 'week_plan_blocks' | 'assignments' | 'exams'
+  entity_id: number
+  action: 'create' | 'update' | 'delete'
+  summary: string | null
+  before_json: string | null
+  after_json: string | null
+  changed_at: string
+  reverted_at: string | null
+}
+
 export interface GoogleAccount {
   id: number
   user_id: number
@@ -364,6 +775,685 @@ export interface DetailedStats {
   workout_percent: number
   active_skills_count: number
   skills_target: number
+  /** Time across domains against the ideal proportions, last 7 days */
+  balance: LifeBalance
+}
+
+export type BalanceDomain = 'academics' | 'skills' | 'fitness' | 'wellness'
+
+export interface DomainBalance {
+  domain: BalanceDomain
+  hours: number
+  /** Share of the logged time (0-1) */
+  share: number
+  /** Share the ideal proportions ask for (0-1) */
+  ideal_share: number
+  /** Multiplies the planner's weekly budget for the domain */
+  planner_scale: number
+}
+
+export interface LifeBalance {
+  /** 0-100; null when nothing was logged */
+  score: number | null
+  domains: Array<DomainBalance>
+}
+
+export type OnboardingStep =
+  | 'welcome'
+  | 'preferences'
+  | 'courses'
+  | 'integrations'
+  | 'finish'
+
+export interface OnboardingStatus {
+  completed_steps: Array<OnboardingStep>
+  next_step: OnboardingStep | null
+  is_complete: boolean
+  completed_at: string | null
+}
+
+export interface OnboardingSettingsInput {
+  week_start_day?: 'monday' | 'sunday'
+  weekly_workout_target?: number
+  weekly_active_skills_target?: number
+  timezone?: string
+}
+
+export interface TermInput {
+  name: string
+  start_date?: string
+  end_date?: string
+}
+
+export interface OnboardingCoursesInput {
+  term?: TermInput
+  courses: Array<Partial<Course>>
+}
+
+export interface OnboardingCoursesResult {
+  term_id: number | null
+  course_ids: Array<number>
+}
+
+export interface Term {
+  id: number
+  name: string
+  start_date: string | null
+  end_date: string | null
+  is_current: boolean
+  archived_at: string | null
+}
+
+export interface ArchiveTermInput {
+  /** Defaults to the current term */
+  term_id?: number
+  next_term?: TermInput
+  carry_forward_skills?: boolean
+  carry_forward_workout_templates?: boolean
+}
+
+export interface ArchiveTermResult {
+  term_id: number
+  archived_course_ids: Array<number>
+  archived_meetings: number
+  archived_skills: number
+  archived_workout_templates: number
+  next_term_id: number | null
+}
+
+export interface TermSnapshot {
+  id: number
+  term_id: number
+  course_id: number | null
+  course_name: string
+  course_code: string | null
+  credit_hours: number | null
+  study_minutes: number
+  study_sessions: number
+  assignments_total: number
+  assignments_completed: number
+  average_assignment_score: number | null
+  exams_graded: number
+  average_exam_grade: number | null
+  current_grade: number | null
+  target_grade: number | null
+  created_at: string
+}
+
+export interface ExamOutcome {
+  exam_id: number
+  course_id: number
+  course_name: string
+  title: string
+  exam_date: string
+  grade: number
+  score: number | null
+  max_score: number | null
+  prep_hours: number
+  planned_prep_hours: number
+  adherence: number | null
+  post_mortem: string | null
+}
+
+export interface ExamReadiness {
+  exam_id: number
+  course_id: number
+  course_name: string
+  title: string
+  exam_date: string
+  days_until: number
+  planned_revision_hours: number
+  completed_revision_hours: number
+  /** Completed share of planned revision (0-1); null with nothing planned */
+  revision_progress: number | null
+  /** Percent standing in the course; null before anything is graded */
+  course_performance: number | null
+  /** 0-100; null when neither signal has data */
+  readiness: number | null
+  /** Multiplies the exam's revision score in the planner during its final week */
+  planner_weight: number
+}
+
+export interface SemesterReview {
+  term_id: number | null
+  term_name: string | null
+  exams: Array<ExamOutcome>
+  average_grade: number | null
+  average_prep_hours: number | null
+  prep_grade_correlation: number | null
+  on_plan_average_grade: number | null
+  off_plan_average_grade: number | null
+  insights: Array<string>
+}
+
+export type MetricEntity = 'sessions' | 'practice' | 'workouts' | 'checkins'
+export type MetricAggregation = 'count' | 'sum' | 'avg' | 'min' | 'max'
+export type MetricBucket = 'none' | 'day' | 'week'
+
+export interface MetricFilter {
+  /** Entity field, or `hour` (0-23) / `weekday` (0 = Sunday) */
+  field: string
+  op: 'eq' | 'ne' | 'gt' | 'gte' | 'lt' | 'lte' | 'contains'
+  value: string | number
+}
+
+export interface CustomMetricInput {
+  name: string
+  entity: MetricEntity
+  filters?: Array<MetricFilter>
+  aggregation: MetricAggregation
+  field?: string | null
+  window_days: number
+  bucket?: MetricBucket
+}
+
+export interface CustomMetric extends Required<CustomMetricInput> {
+  id: number
+  created_at: string
+  updated_at: string
+}
+
+export interface MetricEvaluation {
+  metric_id: number
+  name: string
+  value: number | null
+  points: Array<{ bucket: string; value: number }>
+}
+
+export interface RealizationStats {
+  planned_blocks: number
+  realized_blocks: number
+  planned_hours: number
+  realized_hours: number
+  ratio: number | null
+}
+
+export type PlannerMode = 'normal' | 'exam_period' | 'break'
+
+/** How the current planner mode weighs each domain */
+export interface PlannerWeights {
+  study_scale: number
+  practice_scale: number
+  workout_scale: number
+  revision_weight: number
+  heavy_workouts: boolean
+}
+
+export interface PlannerModeStatus {
+  mode: PlannerMode
+  weights: PlannerWeights
+  /** A better fitting mode, when exams are near or no term is in session */
+  suggested_mode: PlannerMode | null
+  reason: string | null
+}
+
+export interface PlanRealismReport extends RealizationStats {
+  weeks: number
+  by_course: Array<RealizationStats & { course_id: number | null; course_name: string | null }>
+  by_week: Array<RealizationStats & { week_start: string }>
+  /** Blocks worked through a session started from the block: actual vs planned */
+  adherence: { blocks: number; planned_hours: number; actual_hours: number; ratio: number | null }
+  /** Smoothed share of planned blocks that became deep work (0-1) */
+  realism_score: number
+  /** Share of the usual plan the auto-scheduler should propose */
+  plan_scale: number
+}
+
+export interface NextTask {
+  kind: 'plan_block' | 'big_three' | 'assignment' | 'recommendation'
+  reference_id: number | null
+  title: string
+  course_id: number | null
+  /** 0-100, higher first */
+  score: number
+  /** Time to spend now, within the available minutes */
+  suggested_minutes: number | null
+  /** Whether the whole task fits in the available time */
+  fits: boolean
+  reasons: Array<string>
+}
+
+export interface DueItem {
+  kind: 'assignment' | 'exam'
+  id: number
+  title: string
+  course_name: string
+  due_at: string
+  /** Negative when overdue */
+  days_left: number
+}
+
+export interface RecoveryStatus {
+  level: 'fresh' | 'normal' | 'strained'
+  workout_minutes: number
+  typical_workout_minutes: number
+  study_minutes_yesterday: number
+  energy: number | null
+  note: string
+}
+
+export interface MorningBriefing {
+  date: string
+  agenda: Array<CalendarItem>
+  due: Array<DueItem>
+  big_three: Array<BigThreeGoal>
+  /** Candidates for the Big Three; empty once today's are set */
+  suggested_big_three: Array<{ title: string; category: string; reason: string }>
+  recovery: RecoveryStatus
+  insight: {
+    icon: string
+    message: string
+    category: string
+    confidence?: number
+    insight_id?: number
+    arm_name?: string
+    insight_key?: string
+  } | null
+  /** Plain-text digest for a notification */
+  text: string
+}
+
+export interface CourseDigest {
+  course_id: number
+  course_name: string
+  course_code: string | null
+  /** Study hours logged over the past 7 days */
+  hours_logged: number
+  target_weekly_hours: number | null
+  /** Unfinished assignments and exams in the coming week */
+  upcoming: Array<DueItem>
+  current_grade: number | null
+  /** Graded results, oldest first */
+  recent_grades: Array<number>
+  trajectory: 'rising' | 'steady' | 'falling' | null
+  /** Plain-text digest for a notification */
+  text: string
+}
+
+export interface CourseHours {
+  course_name: string
+  hours: number
+  target_hours: number | null
+}
+
+export interface WeeklySummary {
+  week_start: string
+  week_end: string
+  study_hours: number
+  /** Sum of active courses' weekly targets */
+  study_target_hours: number | null
+  courses: CourseHours[]
+  practice_hours: number
+  workouts: number
+  workout_minutes: number
+  checkin_days: number
+  avg_mood: number | null
+  avg_energy: number | null
+  assignments_completed: number
+  assignments_overdue: number
+}
+
+export interface AccountabilityReport {
+  summary: WeeklySummary
+  format: 'markdown' | 'html'
+  body: string
+}
+
+export interface AccountabilityDelivery {
+  week_end: string
+  method: 'file' | 'webhook' | 'mailto'
+  /** File written, webhook URL or mailto URL */
+  target: string | null
+  /** 'pending' is a mailto draft not opened yet */
+  status: 'sent' | 'pending' | 'failed'
+  error: string | null
+  created_at: string
+  delivered_at: string | null
+}
+
+export interface CalendarEvent {
+  id: number
+  user_id: number
+  title: string
+  start_at: string | null
+  end_at: string | null
+  rrule: string | null
+  start_time: string | null
+  end_time: string | null
+  category: string
+  domain: string | null
+  linked_id: number | null
+  locked: number | null
+  notes: string | null
+  /** Import source tag of events read from an .ics file */
+  source: string | null
+  created_at: string | null
+}
+
+export type BigThreeDisposition = 'complete' | 'carry_forward' | 'drop'
+
+export interface ShutdownInput {
+  big_three?: Array<{ goal_id: number; disposition: BigThreeDisposition }>
+}
+
+export interface DaySummary {
+  date: string
+  study_minutes: number
+  sessions: number
+  practice_minutes: number
+  workouts: number
+  assignments_completed: number
+  big_three_completed: number
+  big_three_total: number
+  mood: number | null
+  energy: number | null
+}
+
+export interface ShutdownResult {
+  closed_sessions: Array<Session>
+  /** Unfinished goals not decided in this call; ask the user and call again */
+  pending_big_three: Array<BigThreeGoal>
+  carried_forward: Array<BigThreeGoal>
+  checkin_reminder: CalendarEvent
+  /** Set when a book is in progress and nothing was read today */
+  reading_suggestion: string | null
+  /** Set while spending check-ins are kept up and today's is missing */
+  spending_prompt: string | null
+  summary: DaySummary
+  memory_event_id: number
+}
+
+export interface SampleDataCounts {
+  courses: number
+  assignments: number
+  workouts: number
+  plan_blocks: number
+}
+
+/** Typed settings; keys and defaults mirror services/settings.rs */
+export interface MeetingPattern {
+  /** 0 = Sunday */
+  day_of_week: number
+  start_time: string
+  end_time: string
+  meeting_type?: string | null
+}
+
+/** Suggested load for a credit hour count */
+export interface WorkloadTemplate {
+  credit_hours: number
+  weekly_hours: number
+  meetings: Array<MeetingPattern>
+}
+
+export interface CourseTargetChange {
+  course_id: number
+  name: string
+  credit_hours: number
+  previous_hours: number | null
+  target_weekly_hours: number
+}
+
+export type Weekday =
+  | 'sunday'
+  | 'monday'
+  | 'tuesday'
+  | 'wednesday'
+  | 'thursday'
+  | 'friday'
+  | 'saturday'
+
+/** Local "HH:MM" (24-hour) */
+export interface TimeRange {
+  start: string
+  end: string
+}
+
+/** null keeps the day free of scheduling */
+export type WorkingHours = Record<Weekday, TimeRange | null>
+
+/** Do-not-schedule window; end before start runs past midnight */
+export interface BlackoutWindow extends TimeRange {
+  label?: string | null
+  /** Empty means every day */
+  days: Array<Weekday>
+}
+
+export interface FreeSlot {
+  date: string
+  start_at: string
+  end_at: string
+  minutes: number
+}
+
+export interface Settings {
+  weekly_workout_target: number
+  weekly_active_skills_target: number
+  /** Books per year; 0 = no goal */
+  reading_goal_books: number
+  /** Daily discretionary budget; 0 = ask each check-in */
+  spending_daily_budget: number
+  cycle_tracking_enabled: boolean
+  /** The cycle log is left out of exports unless set */
+  cycle_include_in_exports: boolean
+  week_start_day: 'monday' | 'sunday'
+  sleep_hours: number
+  capacity_limit_percent: number
+  checkin_reminder_hour: number
+  course_workload_templates: Array<WorkloadTemplate>
+  course_digest_day: 'off' | 'sunday' | 'monday' | 'tuesday' | 'wednesday' | 'thursday' | 'friday' | 'saturday'
+  working_hours: WorkingHours
+  blackout_windows: Array<BlackoutWindow>
+  timezone: string | null
+  muted_categories: Array<string>
+  agent_shadow_mode: boolean
+  reduced_data_mode: boolean
+  telemetry_enabled: boolean
+  global_shortcuts: Record<QuickAction, string | null>
+  tray_enabled: boolean
+  focus_mode_enabled: boolean
+  focus_distraction_apps: Array<string>
+  focus_distraction_sites: Array<string>
+  focus_profiles: Array<FocusProfile>
+  planner_split_pomodoros: boolean
+  planner_reschedule_missed: boolean
+  planner_study_hours_cap: number
+  planner_practice_hours_cap: number
+  planner_switch_penalty: number
+  planner_mode: PlannerMode
+  balance_ideal_academics: number
+  balance_ideal_skills: number
+  balance_ideal_fitness: number
+  balance_ideal_wellness: number
+  http_api_enabled: boolean
+  http_api_port: number
+  mcp_enabled: boolean
+  mcp_scopes: Array<McpScope>
+  calendar_feed_enabled: boolean
+  calendar_feed_titles: boolean
+  calendar_feed_days: number
+  calendar_feed_path: string | null
+  gamification_enabled: boolean
+  gamification_xp_study_hour: number
+  gamification_xp_practice_hour: number
+  gamification_xp_workout: number
+  gamification_xp_streak_day: number
+  gamification_xp_flashcard_review: number
+  accountability_day: 'off' | 'sunday' | 'monday' | 'tuesday' | 'wednesday' | 'thursday' | 'friday' | 'saturday'
+  accountability_delivery: 'file' | 'webhook' | 'mailto'
+  accountability_format: 'markdown' | 'html'
+  accountability_path: string | null
+  accountability_webhook_url: string | null
+  accountability_email: string | null
+  db_max_connections: number
+  db_busy_timeout_ms: number
+  db_wal_mode: boolean
+  db_synchronous: 'off' | 'normal' | 'full' | 'extra'
+  retention_sessions_months: number
+  retention_agent_context_months: number
+  google_client_id: string | null
+  google_export_busy_only: boolean
+  onboarding_completed_at: string | null
+}
+
+export type SettingKey = keyof Settings
+
+export interface DbPoolStatus {
+  max_connections: number
+  /** Connections currently open */
+  open_connections: number
+  idle_connections: number
+  journal_mode: string
+  busy_timeout_ms: number
+  /** 0 = off, 1 = normal, 2 = full, 3 = extra */
+  synchronous: number
+  /** From settings; differs from the values above until the next start */
+  configured: {
+    max_connections: number
+    busy_timeout_ms: number
+    wal: boolean
+    synchronous: 'off' | 'normal' | 'full' | 'extra'
+  }
+}
+
+export type SettingKind =
+  | { type: 'bool' }
+  | { type: 'int'; min: number; max: number }
+  | { type: 'enum'; values: Array<string> }
+  | { type: 'optional_string' }
+  | { type: 'timezone' }
+  | { type: 'string_list'; values: Array<string> }
+  | { type: 'shortcuts' }
+  | { type: 'text_list'; max_items: number }
+  | { type: 'focus_profiles' }
+  | { type: 'working_hours' }
+  | { type: 'blackout_windows' }
+  | { type: 'workload_templates' }
+
+export type SettingDef = SettingKind & {
+  key: SettingKey
+  default: unknown
+  description: string
+}
+
+/** Payload of the `settings-changed` event */
+export interface SettingChange<K extends SettingKey = SettingKey> {
+  key: K
+  value: Settings[K]
+}
+
+export interface ApiCapabilities {
+  api_version: number
+  backend_version: string
+  schema_version: number | null
+  commands: Array<{ name: string; version: number }>
+  integrations: Array<{ name: string; available: boolean; configured: boolean }>
+  features: Record<string, boolean>
+}
+
+export type QuickActionOutcome =
+  | { kind: 'session_started'; session: Session }
+  | { kind: 'session_ended'; session: Session }
+  | { kind: 'checkin_created'; checkin: CheckIn }
+  | { kind: 'open'; view: 'checkin' | 'quick_add' }
+
+/** Payload of the `task://progress` event emitted by long-running commands */
+export interface TaskProgress {
+  task_id: string
+  task: string
+  done: number
+  total: number | null
+  finished: boolean
+  error: string | null
+}
+
+/** Payload of the `planner://missed-blocks` event emitted by nightly maintenance */
+export interface MissedBlocksReport {
+  /** Blocks marked missed in this run */
+  missed: Array<number>
+  rescheduled: Array<{ missed_block_id: number; new_block_id: number; start_at: string; end_at: string }>
+  /** Missed blocks with no free slot left in their week */
+  unplaced: Array<number>
+}
+
+/** Payload of the `deep-link` event emitted for `lifeos://` URLs */
+export interface DeepLinkEvent {
+  url: string
+  outcome: QuickActionOutcome | null
+  error: string | null
+}
+
+export type QuickAction = 'toggle_pomodoro' | 'quick_checkin' | 'quick_add'
+
+export interface QuickActionStatus {
+  action: QuickAction
+  label: string
+  shortcut: string | null
+  registered: boolean
+  error: string | null
+}
+
+/** Payload of the `quick-action` event emitted for shortcuts and tray items */
+export interface QuickActionEvent {
+  action: QuickAction
+  outcome: QuickActionOutcome | null
+  error: string | null
+}
+
+export interface Streaks {
+  study_streak: number
+  workout_streak: number
+  practice_streak: number
+  checkin_streak: number
+}
+
+export interface GlanceData {
+  active_session: {
+    id: number
+    session_type: string
+    course_name: string | null
+    started_at: string
+    elapsed_seconds: number
+    planned_minutes: number | null
+  } | null
+  next_item: {
+    title: string
+    source: string
+    start_at: string
+    end_at: string
+    color: string | null
+  } | null
+  big_three_completed: number
+  big_three_total: number
+  streaks: Streaks
+}
+
+export interface AppUsage {
+  app_name: string
+  /** Matched distraction site, when the time was spent in a browser */
+  site: string | null
+  is_distraction: boolean
+  minutes: number
+}
+
+export interface EnergyBudget {
+  date: string
+  /** Points available for the day after sleep, check-in and recovery */
+  budget: number
+  spent: number
+  remaining: number
+  /** Estimated sleep the night before, in hours */
+  sleep_hours: number
+  /** Minutes of study the remainder still covers */
+  demanding_minutes_left: number
+}
+
+export interface DistractionReport {
+  session_id: number
+  sampled_minutes: number
+  distraction_minutes: number
+  distraction_share: number | null
+  by_app: Array<AppUsage>
 }
 
 export interface UserSettings {
@@ -377,6 +1467,143 @@ export interface WorkoutHeatmapDay {
   total_minutes: number
 }
 
+export interface HttpApiStatus {
+  enabled: boolean
+  running: boolean
+  port: number
+  /** Base URL while running, e.g. http://127.0.0.1:47800/v1 */
+  url: string | null
+  has_token: boolean
+  /** Why the server isn't running despite being enabled */
+  error: string | null
+}
+
+export interface CalendarFeedStatus {
+  enabled: boolean
+  has_token: boolean
+  /** Subscribable URL while the local HTTP API runs, e.g. http://127.0.0.1:47800/feed/<token>.ics */
+  url: string | null
+  /** File the feed is written to after each sync */
+  path: string | null
+}
+
+export type McpScope = 'agenda:read' | 'assignments:write' | 'workouts:write'
+
+export interface McpAuditEntry {
+  id: number
+  tool: string
+  scope: McpScope | null
+  arguments_json: string
+  /** False when the call was refused for a missing scope */
+  allowed: boolean
+  is_error: boolean
+  result_summary: string | null
+  created_at: string
+}
+
+export interface AttentionDay {
+  date: string
+  /** Focused stretches of 25+ minutes without a distraction */
+  deep_hours: number
+  shallow_hours: number
+  distraction_hours: number
+}
+
+export interface ReasonCount {
+  reason: InterruptionReason
+  count: number
+}
+
+export interface PomodoroStats {
+  days: number
+  completed: number
+  abandoned: number
+  /** Completed share of all pomodoros; unset without any */
+  completion_rate?: number
+  interruptions: number
+  interruptions_per_pomodoro?: number
+  /** Most frequent first */
+  by_reason: Array<ReasonCount>
+  /** Longest run of uninterrupted completed pomodoros on one day */
+  best_streak: number
+  best_streak_date?: string
+  current_streak: number
+}
+
+export type ExportMetric = 'sessions' | 'practice' | 'workouts' | 'checkins' | 'daily' | 'cycle'
+
+/** Inclusive YYYY-MM-DD bounds; omitted ends are open */
+export interface ExportRange {
+  from?: string
+  to?: string
+}
+
+export interface ExportResult {
+  path: string
+  metric: ExportMetric
+  rows: number
+}
+
+export interface UsageCount {
+  name: string
+  count: number
+}
+
+/** Local-only usage counts; errors are named "module:code" */
+export interface TelemetryReport {
+  enabled: boolean
+  /** First day included (YYYY-MM-DD) */
+  since: string
+  commands: Array<UsageCount>
+  errors: Array<UsageCount>
+}
+
+export interface TelemetryExportResult {
+  path: string
+  commands: number
+  errors: number
+}
+
+export interface IcsExportResult {
+  path: string
+  /** VEVENTs written; a weekly series counts once */
+  events: number
+}
+
+export interface IcsImportResult {
+  source: string
+  created: number
+  updated: number
+  unchanged: number
+  /** Rows of this source whose event or occurrence left the file */
+  removed: number
+  /** Events skipped or imported only in part */
+  warnings: Array<string>
+}
+
+export interface DeadlineExportResult {
+  path: string
+  assignments: number
+  exams: number
+}
+
+export interface DeadlineImportResult {
+  /** Course name in the imported file */
+  source_course: string
+  assignments_added: number
+  exams_added: number
+  /** Items already in the course (same title and date) */
+  duplicates_skipped: number
+}
+
+export interface AttentionReport {
+  week_start: string
+  days: Array<AttentionDay>
+  deep_hours: number
+  shallow_hours: number
+  distraction_hours: number
+}
+
 export interface Achievement {
   id: number
   achievement_type: string
@@ -387,6 +1614,485 @@ export interface Achievement {
   metadata?: string
 }
 
+export interface LevelProgress {
+  xp: number
+  level: number
+  /** XP earned since reaching `level` */
+  xp_into_level: number
+  /** XP from `level` to the next */
+  xp_for_next_level: number
+}
+
+export type XpDomain = 'academics' | 'skills' | 'fitness' | 'wellness'
+
+export interface DomainProgress extends LevelProgress {
+  domain: XpDomain
+}
+
+export interface XpSource {
+  source: 'study_hours' | 'flashcard_reviews' | 'practice_hours' | 'workouts' | 'streak_days'
+  domain: XpDomain
+  /** Hours, reviews, workouts or days */
+  amount: number
+  xp_each: number
+  xp: number
+}
+
+export interface GamificationStatus {
+  enabled: boolean
+  overall: LevelProgress
+  domains: DomainProgress[]
+  sources: XpSource[]
+  /** Newest first */
+  recent_level_ups: Achievement[]
+}
+
+/** Payload of the `gamification://level-up` event */
+export interface LevelUp {
+  domain: 'overall' | XpDomain
+  level: number
+  title: string
+}
+
+export type ChallengeMetric = 'workouts' | 'study_hours' | 'practice_hours' | 'checkin_days' | 'flashcard_reviews'
+
+export type ChallengeStatus = 'upcoming' | 'active' | 'completed' | 'failed'
+
+export interface ChallengeInput {
+  title: string
+  metric: ChallengeMetric
+  /** Total over the challenge, or per day with `per_day` */
+  target: number
+  per_day?: boolean
+  /** YYYY-MM-DD; defaults to today */
+  start_date?: string
+  days: number
+}
+
+export interface ChallengeProgress {
+  /** Metric total, or days the target was met for daily challenges */
+  value: number
+  /** Target, or the number of days for daily challenges */
+  goal: number
+  percent: number
+  /** Share of the challenge's days elapsed, as a percentage */
+  expected_percent: number
+  status: ChallengeStatus
+  /** Days left including today */
+  days_left: number
+}
+
+export interface Challenge {
+  id: number
+  title: string
+  metric: ChallengeMetric
+  target: number
+  per_day: boolean
+  start_date: string
+  end_date: string
+  completed_at: string | null
+  created_at: string
+  progress: ChallengeProgress
+}
+
+/** Payload of the `challenges://milestone` event */
+export interface ChallengeMilestone {
+  challenge_id: number
+  title: string
+  percent: number
+}
+
+export type StreakRule = 'daily' | 'weekly' | 'none'
+
+export interface Tracker {
+  id: number
+  slug: string
+  name: string
+  domain: 'academics' | 'skills' | 'fitness' | 'wellness'
+  /** What a log's `amount` counts, e.g. 'minutes' or 'pages' */
+  unit: string
+  weekly_target?: number
+  streak_rule: StreakRule
+  /** Daily amount a 'daily' streak day needs; any log when 0 */
+  streak_min: number
+  builtin: boolean
+  created_at: string
+}
+
+export interface TrackerSummary extends Tracker {
+  week_amount: number
+  week_minutes: number
+  /** Unset without a weekly target */
+  week_target_met?: boolean
+  /** Days or weeks, per the streak rule */
+  current_streak: number
+  best_streak: number
+  total_amount: number
+  last_logged_at?: string
+}
+
+export interface TrackerInput {
+  name: string
+  domain: Tracker['domain']
+  unit?: string
+  weekly_target?: number
+  streak_rule?: StreakRule
+  streak_min?: number
+}
+
+export interface TrackerEntity {
+  id: number
+  tracker_id: number
+  name: string
+  target_amount?: number
+  logged_amount: number
+  completed_at?: string
+  created_at: string
+}
+
+export interface TrackerEntityInput {
+  tracker_id: number
+  name: string
+  target_amount?: number
+}
+
+export interface TrackerLog {
+  id: number
+  tracker_id: number
+  entity_id?: number
+  amount: number
+  minutes?: number
+  kind?: string
+  rating?: number
+  note?: string
+  logged_at: string
+}
+
+export interface TrackerLogInput {
+  tracker_id: number
+  entity_id?: number
+  amount: number
+  /** Time spent, when the unit isn't minutes */
+  minutes?: number
+  kind?: string
+  /** 1-5 */
+  rating?: number
+  note?: string
+  logged_at?: string
+}
+
+export type MeditationStyle =
+  | 'breath'
+  | 'body_scan'
+  | 'loving_kindness'
+  | 'open_awareness'
+  | 'walking'
+  | 'guided'
+  | 'other'
+
+export interface MeditationTimer {
+  id: number
+  style: MeditationStyle
+  planned_minutes: number
+  interval_minutes?: number
+  started_at: string
+  elapsed_seconds: number
+  /** The planned time is up; waiting for the rating */
+  finished: boolean
+}
+
+/** Payload of the `meditation://timer` event */
+export interface MeditationTimerEvent {
+  timer_id: number
+  event: 'bell' | 'finished'
+  elapsed_minutes: number
+}
+
+export interface MeditationInput {
+  minutes: number
+  style: MeditationStyle
+  /** 1-5, how calm the sit left you */
+  calm_rating?: number
+  note?: string
+  logged_at?: string
+}
+
+export interface MeditationStats {
+  days: number
+  sessions: number
+  minutes: number
+  avg_calm?: number
+  by_style: Array<{ style: string; sessions: number; minutes: number }>
+  current_streak: number
+  best_streak: number
+  week_minutes: number
+}
+
+export interface Book {
+  id: number
+  title: string
+  pages: number
+  pages_read: number
+  minutes: number
+  started_at?: string
+  finished_at?: string
+  /** Recent pages per day */
+  pages_per_day?: number
+  /** YYYY-MM-DD at the recent pace */
+  projected_finish?: string
+}
+
+export interface BookInput {
+  title: string
+  pages: number
+}
+
+/** Give either `pages` read or the page reached as `to_page` */
+export interface ReadingSessionInput {
+  book_id: number
+  pages?: number
+  to_page?: number
+  minutes?: number
+  note?: string
+  logged_at?: string
+}
+
+export interface ReadingGoal {
+  year: number
+  /** Unset when no goal is set */
+  goal?: number
+  finished: number
+  expected_by_now?: number
+  on_track?: boolean
+  /** Books by the end of the year at this year's rate */
+  projected: number
+}
+
+export interface ReadingStats {
+  days: number
+  sessions: number
+  pages: number
+  minutes: number
+  pages_per_hour?: number
+  current_streak: number
+  best_streak: number
+  goal: ReadingGoal
+  /** Books in progress */
+  reading: Array<Book>
+}
+
+export interface FlashcardDeck {
+  id: number
+  skill_id: number
+  name: string
+  source?: string
+  card_count: number
+  mature_count: number
+  created_at: string
+}
+
+export interface FlashcardDeckInput {
+  skill_id: number
+  name: string
+  source?: string
+  card_count?: number
+}
+
+export interface FlashcardReviewInput {
+  deck_id: number
+  reviewed: number
+  correct: number
+  new_cards?: number
+  minutes?: number
+  /** Deck size reported by the SRS app */
+  card_count?: number
+  mature_count?: number
+}
+
+export interface FlashcardReview {
+  id: number
+  deck_id: number
+  practice_log_id?: number
+  reviewed: number
+  correct: number
+  new_cards: number
+  minutes?: number
+  reviewed_at: string
+}
+
+export interface SpendingCheckIn {
+  /** YYYY-MM-DD */
+  date: string
+  amount: number
+  within_budget: boolean
+  note?: string
+  updated_at: string
+}
+
+export interface SpendingCheckInInput {
+  /** YYYY-MM-DD; defaults to today */
+  date?: string
+  amount: number
+  /** Defaults to the amount against spending_daily_budget when one is set */
+  within_budget?: boolean
+  note?: string
+}
+
+export interface SpendingWeek {
+  week_start: string
+  days_logged: number
+  total: number
+  average_per_day?: number
+  days_within_budget: number
+  biggest_day?: string
+}
+
+export interface SpendingWellness {
+  paired_days: number
+  /** Pearson r of daily spend with mean check-in mood */
+  mood_correlation?: number
+  energy_correlation?: number
+  mood_within_budget?: number
+  mood_over_budget?: number
+  energy_within_budget?: number
+  energy_over_budget?: number
+  insights: Array<string>
+}
+
+export interface SpendingSummary {
+  daily_budget?: number
+  today?: SpendingCheckIn
+  current_streak: number
+  best_streak: number
+  within_budget_streak: number
+  /** Newest first */
+  weeks: Array<SpendingWeek>
+  wellness: SpendingWellness
+}
+
+export type CyclePhase = 'menstrual' | 'follicular' | 'ovulatory' | 'luteal'
+
+export interface CyclePeriod {
+  id: number
+  /** YYYY-MM-DD */
+  start_date: string
+  end_date?: string
+  note?: string
+  created_at: string
+}
+
+export interface CyclePeriodInput {
+  start_date: string
+  /** Last day of the period */
+  end_date?: string
+  note?: string
+}
+
+export interface CycleDay {
+  date: string
+  /** 1 on the first day of a period */
+  day: number
+  cycle_length: number
+  phase: CyclePhase
+  /** Menstrual or premenstrual */
+  low_energy: boolean
+}
+
+export interface CyclePrediction {
+  cycle_length: number
+  period_length: number
+  /** 0 means the default lengths */
+  cycles_used: number
+  variability_days?: number
+  last_start: string
+  today: CycleDay
+  next_period_start: string
+  ovulation_date: string
+  days_late: number
+}
+
+export interface SocialEventInput {
+  name: string
+  minutes: number
+  /** 1 (draining) to 5 (energizing) */
+  rating?: number
+  /** UTC SQLite timestamp; defaults to now */
+  logged_at?: string
+}
+
+export interface IsolationStreak {
+  start: string
+  /** Today while the streak is still running */
+  end: string
+  days: number
+  crunch_days: number
+}
+
+export interface SocialStats {
+  days: number
+  events: number
+  minutes: number
+  avg_rating?: number
+  energizing_minutes: number
+  draining_minutes: number
+  current_isolation_days: number
+  /** Oldest first */
+  isolation_streaks: Array<IsolationStreak>
+  crunch_days: number
+  /** Share of crunch days with an event */
+  crunch_social_share?: number
+  other_social_share?: number
+  insights: Array<string>
+}
+
+export type CefrLevel = 'A1' | 'A2' | 'B1' | 'B2' | 'C1' | 'C2'
+
+export interface LanguageSkill {
+  skill_id: number
+  name: string
+  language: string
+  current_level: CefrLevel
+  target_level?: CefrLevel
+  target_weekly_hours?: number
+  decks: Array<FlashcardDeck>
+}
+
+export interface LanguageSkillInput {
+  language: string
+  /** Defaults to the language */
+  name?: string
+  description?: string
+  current_level?: CefrLevel
+  target_level?: CefrLevel
+  target_weekly_hours?: number
+  /** Deck names to create with the skill */
+  decks?: Array<string>
+}
+
+export interface LanguageProficiency {
+  skill_id: number
+  name: string
+  language: string
+  current_level: CefrLevel
+  target_level?: CefrLevel
+  days: number
+  flashcard_hours: number
+  listening_hours: number
+  speaking_hours: number
+  other_practice_hours: number
+  total_hours: number
+  cards: number
+  mature_cards: number
+  reviews: number
+  new_cards: number
+  accuracy?: number
+  vocabulary_score?: number
+  listening_score?: number
+  speaking_score?: number
+  proficiency?: number
+  weakest?: 'vocabulary' | 'listening' | 'speaking'
+}
+
 export interface PersonalRecord {
   id: number
   exercise_name: string
@@ -406,5 +2112,138 @@ export interface Exam {
   notes?: string
   grade?: number
   weight?: number
+  score?: number
+  max_score?: number
+  post_mortem?: string
   created_at?: string
 }
+
+export interface ExamResultInput {
+  score: number
+  max_score?: number
+  post_mortem?: string
+}
+
+export type EfficiencyStatus = 'diminishing_returns' | 'under_invested' | 'on_track' | 'insufficient_data'
+
+export interface CourseEfficiency {
+  course_id: number
+  course_name: string
+  study_hours: number
+  hours_per_week: number
+  target_weekly_hours: number | null
+  current_grade: number | null
+  target_grade: number | null
+  average_score: number | null
+  /** Estimated grade points per extra preparation hour */
+  points_per_hour: number | null
+  status: EfficiencyStatus
+  suggested_weekly_hours: number
+  results: Array<{ kind: 'exam' | 'assignment'; title: string; date: string; score: number; prep_hours: number }>
+}
+
+export type TimeSlot = 'morning' | 'afternoon' | 'evening'
+
+export interface MeetingFocus {
+  meeting_id: number
+  course_id: number
+  course_name: string
+  meeting_type: string
+  /** 0 = Sunday */
+  day_of_week: number
+  start_time: string
+  end_time: string
+  time_slot: TimeSlot
+  held: number
+  attended: number
+  attendance_rate: number | null
+  /** Average focus rating (1-5) of the meeting's sessions */
+  avg_focus: number | null
+  rated_sessions: number
+  /** Focus of the course's self-study in the same time slot */
+  self_study_focus: number | null
+  self_study_sessions: number
+  /** Self-study has historically beaten this lecture */
+  substitute: boolean
+}
+
+export interface MeetingTypeFocus {
+  meeting_type: string
+  time_slot: TimeSlot
+  meetings: number
+  held: number
+  attended: number
+  attendance_rate: number | null
+  avg_focus: number | null
+  rated_sessions: number
+  self_study_focus: number | null
+  self_study_sessions: number
+}
+
+export interface MeetingFocusReport {
+  weeks: number
+  by_type: Array<MeetingTypeFocus>
+  meetings: Array<MeetingFocus>
+}
+
+export type CapacityStatus = 'ok' | 'tight' | 'overcommitted'
+
+export interface CapacityDay {
+  date: string
+  fixed_hours: number
+  planned_hours: number
+  suggested_hours: number
+  available_hours: number
+  overcommitted: boolean
+  overcommitted_with_suggested: boolean
+}
+
+export interface CapacityReport {
+  week_start: string
+  week_end: string
+  waking_hours: number
+  available_hours: number
+  class_hours: number
+  event_hours: number
+  study_target_hours: number
+  skill_target_hours: number
+  workout_target_hours: number
+  assignment_hours: number
+  unestimated_assignments: number
+  planned_hours: number
+  suggested_hours: number
+  committed_hours: number
+  utilization: number
+  status: CapacityStatus
+  /** As if every suggested plan block were accepted */
+  committed_hours_with_suggested: number
+  utilization_with_suggested: number
+  status_with_suggested: CapacityStatus
+  days: Array<CapacityDay>
+  warnings: Array<string>
+}
+
+export interface SimulatedAssessment {
+  exam_id: number
+  title: string
+  weight: number
+  /** Fixed score; null when projected */
+  score: number | null
+  source: 'graded' | 'hypothetical' | 'projected'
+}
+
+export interface GradeSimulation {
+  course_id: number
+  current_grade: number
+  target_grade: number | null
+  completed_weight: number
+  assessments: Array<SimulatedAssessment>
+  mean: number
+  p10: number
+  p50: number
+  p90: number
+  letter_probabilities: Array<{ letter: string; min_grade: number; probability: number }>
+  target_probability: number | null
+  /** Average needed on the projected exams to hit the target (may exceed 100) */
+  required_average: number | null
+}